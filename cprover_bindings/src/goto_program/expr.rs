@@ -99,6 +99,10 @@ pub enum ExprValue {
     EmptyUnion,
     /// `1.0f`
     FloatConstant(f32),
+    /// `1.0f16`, represented by its bit pattern since Rust's `f16` isn't stable yet.
+    Float16Constant(u16),
+    /// `1.0f128`, represented by its bit pattern since Rust's `f128` isn't stable yet.
+    Float128Constant(u128),
     /// `function(arguments)`
     FunctionCall {
         function: Expr,
@@ -591,6 +595,16 @@ pub fn float_constant_from_bitpattern(bp: u32) -> Self {
         Self::float_constant(c)
     }
 
+    /// `union {_Float16 f; uint16_t bp} u = {.bp = 0x1234}; >>> u.f <<<`
+    pub fn float16_constant_from_bitpattern(bp: u16) -> Self {
+        expr!(Float16Constant(bp), Type::float16())
+    }
+
+    /// `union {_Float128 f; __uint128_t bp} u = {.bp = 0x1234}; >>> u.f <<<`
+    pub fn float128_constant_from_bitpattern(bp: u128) -> Self {
+        expr!(Float128Constant(bp), Type::float128())
+    }
+
     /// `typ x[__CPROVER_infinity()] = >>> {elem} <<<`
     /// i.e. initilize an infinite sized sparse array.
     /// This is useful for maps:
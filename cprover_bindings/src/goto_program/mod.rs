@@ -23,5 +23,5 @@
 pub use location::Location;
 pub use stmt::{Stmt, StmtBody, SwitchCase};
 pub use symbol::{FunctionContract, Lambda, Symbol, SymbolValues};
-pub use symbol_table::SymbolTable;
+pub use symbol_table::{SliceIndex, SymbolTable};
 pub use typ::{CIntType, DatatypeComponent, Parameter, Type};
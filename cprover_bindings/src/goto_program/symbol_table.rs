@@ -1,9 +1,9 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
-use super::super::{env, MachineModel};
-use super::{BuiltinFn, FunctionContract, Stmt, Symbol};
+use super::super::{env, irep, MachineModel};
+use super::{BuiltinFn, FunctionContract, Stmt, Symbol, Type};
 use crate::InternedString;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 /// This is a typesafe implementation of the CBMC symbol table, based on the CBMC code at:
 /// <https://github.com/diffblue/cbmc/blob/develop/src/util/symbol_table.h>
 /// Since the field is kept private, with only immutable references handed out, elements can only
@@ -110,4 +110,137 @@ pub fn lookup<T: Into<InternedString>>(&self, name: T) -> Option<&Symbol> {
     pub fn machine_model(&self) -> &MachineModel {
         &self.machine_model
     }
+
+    /// The index used to slice this table for several roots, see [SliceIndex].
+    pub fn slice_index(&self) -> SliceIndex {
+        let mut symbols: HashMap<InternedString, (irep::Symbol, Vec<InternedString>)> = self
+            .iter()
+            .map(|(name, symbol)| {
+                let irep = symbol.to_irep(&self.machine_model);
+                let mut references = vec![];
+                irep.typ.for_each_identifier(|identifier| references.push(identifier));
+                irep.value.for_each_identifier(|identifier| references.push(identifier));
+                (*name, (irep, references))
+            })
+            .collect();
+        // A static variable refers to the constructors that initialize it.
+        let mut constructors: Vec<(InternedString, InternedString)> = vec![];
+        for (name, symbol) in self.iter() {
+            if symbol.typ.return_type() == Some(&Type::Constructor) {
+                symbols[name].0.value.for_each_identifier(|identifier| {
+                    let is_static_variable = self
+                        .lookup(identifier)
+                        .is_some_and(|var| var.is_static_lifetime && !var.typ.is_code());
+                    if is_static_variable {
+                        constructors.push((identifier, *name));
+                    }
+                });
+            }
+        }
+        for (variable, constructor) in constructors {
+            symbols.get_mut(&variable).unwrap().1.push(constructor);
+        }
+        let environment = SymbolTable::new(self.machine_model.clone());
+        SliceIndex { symbols, environment: environment.iter().map(|(name, _)| *name).collect() }
+    }
+}
+
+/// The symbols of a table converted to ireps, with the symbols that each of them refers to.
+///
+/// This is used to emit the model of each harness from a table shared by several harnesses. The
+/// ireps and the references are computed once for the whole table, so each slice only visits the
+/// symbols it keeps.
+pub struct SliceIndex {
+    symbols: HashMap<InternedString, (irep::Symbol, Vec<InternedString>)>,
+    environment: Vec<InternedString>,
+}
+
+impl SliceIndex {
+    /// The table with the symbols reachable from `roots`: the roots, the environment symbols,
+    /// and the symbols their types and values refer to, transitively. The constructors that
+    /// initialize a reachable static variable are reachable too, since they're called before
+    /// the entry point.
+    pub fn slice<T: Into<InternedString>>(
+        &self,
+        roots: impl IntoIterator<Item = T>,
+    ) -> irep::SymbolTable {
+        let mut sliced = irep::SymbolTable::new();
+        let mut worklist: Vec<InternedString> = roots.into_iter().map(Into::into).collect();
+        worklist.extend(self.environment.iter().copied());
+        while let Some(name) = worklist.pop() {
+            if sliced.symbol_table.contains_key(&name) {
+                continue;
+            }
+            let Some((symbol, references)) = self.symbols.get(&name) else { continue };
+            worklist.extend(references.iter().copied());
+            sliced.insert(symbol.clone());
+        }
+        sliced
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goto_program::{Expr, Location, Type};
+    use crate::machine_model::test_util::machine_model_test_stub;
+
+    #[test]
+    fn check_slice() {
+        let mut symtab = SymbolTable::new(machine_model_test_stub());
+        let function = |name: &str, callee: Option<&str>| {
+            let body = callee.map(|callee| {
+                let typ = Type::code_with_unnamed_parameters(vec![], Type::empty());
+                let call = Expr::symbol_expression(callee, typ).call(vec![]);
+                Stmt::block(vec![call.as_stmt(Location::none())], Location::none())
+            });
+            let typ = Type::code_with_unnamed_parameters(vec![], Type::struct_tag("Counter"));
+            Symbol::function(name, typ, body, name, Location::none())
+        };
+        symtab.insert(function("harness", Some("callee")));
+        symtab.insert(function("callee", None));
+        symtab.insert(function("other_harness", Some("callee")));
+        symtab.insert(Symbol::struct_type("Counter", "Counter".into(), vec![]));
+        // A static variable of `callee`, which is initialized by a constructor.
+        let var = Symbol::static_variable("callee::COUNT", "COUNT", Type::bool(), Location::none());
+        let init = var.to_expr().assign(Expr::bool_true(), Location::none());
+        let constructor = Symbol::function(
+            "callee::COUNT_init",
+            Type::code(vec![], Type::constructor()),
+            Some(Stmt::block(vec![init], Location::none())),
+            "callee::COUNT::init",
+            Location::none(),
+        );
+        symtab.insert(var);
+        symtab.insert(constructor);
+        symtab.update_fn_declaration_with_definition(
+            "callee",
+            Stmt::block(
+                vec![
+                    Expr::symbol_expression("callee::COUNT", Type::bool())
+                        .as_stmt(Location::none()),
+                ],
+                Location::none(),
+            ),
+        );
+
+        let index = symtab.slice_index();
+        let sliced = index.slice(["harness"]).symbol_table;
+        assert!(sliced.contains_key(&"harness".into()));
+        assert!(sliced.contains_key(&"callee".into()));
+        assert!(sliced.contains_key(&Type::struct_tag("Counter").type_name().unwrap()));
+        assert!(sliced.contains_key(&"callee::COUNT".into()));
+        assert!(sliced.contains_key(&"callee::COUNT_init".into()));
+        assert!(!sliced.contains_key(&"other_harness".into()));
+        // The environment symbols are kept.
+        assert_eq!(sliced.len(), SymbolTable::new(machine_model_test_stub()).iter().count() + 5);
+        // The same index can be sliced again.
+        let other = index.slice(["other_harness"]).symbol_table;
+        assert!(other.contains_key(&"callee::COUNT_init".into()));
+        assert!(!other.contains_key(&"harness".into()));
+        assert_eq!(
+            sliced[&"callee".into()],
+            symtab.lookup("callee").unwrap().to_irep(symtab.machine_model())
+        );
+    }
 }
@@ -41,6 +41,10 @@ pub enum Type {
     FlexibleArray { typ: Box<Type> },
     /// `float`
     Float,
+    /// `_Float16`
+    Float16,
+    /// `_Float128`
+    Float128,
     /// `struct x {}`
     IncompleteStruct { tag: InternedString },
     /// `union x {}`
@@ -166,6 +170,8 @@ fn typecheck_datatype_field(typ: &Type) -> bool {
             | Double
             | FlexibleArray { .. }
             | Float
+            | Float16
+            | Float128
             | Integer
             | Pointer { .. }
             | Signedbv { .. }
@@ -364,6 +370,8 @@ pub fn sizeof_in_bits(&self, st: &SymbolTable) -> u64 {
             Empty => 0,
             FlexibleArray { .. } => 0,
             Float => st.machine_model().float_width,
+            Float16 => 16,
+            Float128 => 128,
             IncompleteStruct { .. } => unreachable!("IncompleteStruct doesn't have a sizeof"),
             IncompleteUnion { .. } => unreachable!("IncompleteUnion doesn't have a sizeof"),
             InfiniteArray { .. } => unreachable!("InfiniteArray doesn't have a sizeof"),
@@ -540,10 +548,26 @@ pub fn is_float(&self) -> bool {
         }
     }
 
+    pub fn is_float16(&self) -> bool {
+        let concrete = self.unwrap_typedef();
+        match concrete {
+            Float16 => true,
+            _ => false,
+        }
+    }
+
+    pub fn is_float128(&self) -> bool {
+        let concrete = self.unwrap_typedef();
+        match concrete {
+            Float128 => true,
+            _ => false,
+        }
+    }
+
     pub fn is_floating_point(&self) -> bool {
         let concrete = self.unwrap_typedef();
         match concrete {
-            Double | Float => true,
+            Double | Float | Float16 | Float128 => true,
             _ => false,
         }
     }
@@ -577,6 +601,8 @@ pub fn can_be_lvalue(&self) -> bool {
             | CInteger(_)
             | Double
             | Float
+            | Float16
+            | Float128
             | Integer
             | Pointer { .. }
             | Signedbv { .. }
@@ -632,6 +658,8 @@ pub fn is_scalar(&self) -> bool {
             | Double
             | Empty
             | Float
+            | Float16
+            | Float128
             | Integer
             | Pointer { .. }
             | Signedbv { .. }
@@ -918,6 +946,8 @@ fn typecheck_array_elem(&self) -> bool {
             | CInteger(_)
             | Double
             | Float
+            | Float16
+            | Float128
             | Integer
             | Pointer { .. }
             | Signedbv { .. }
@@ -1046,6 +1076,14 @@ pub fn float() -> Self {
         Float
     }
 
+    pub fn float16() -> Self {
+        Float16
+    }
+
+    pub fn float128() -> Self {
+        Float128
+    }
+
     /// A forward declared struct.
     /// struct foo;
     pub fn incomplete_struct<T: Into<InternedString>>(tag: T) -> Self {
@@ -1277,6 +1315,10 @@ pub fn one(&self) -> Expr {
             Expr::float_constant(1.0)
         } else if self.is_double() {
             Expr::double_constant(1.0)
+        } else if self.is_float16() {
+            Expr::float16_constant_from_bitpattern(0x3C00)
+        } else if self.is_float128() {
+            Expr::float128_constant_from_bitpattern(0x3FFF << 112)
         } else {
             unreachable!("Can't convert {:?} to a one value", self)
         }
@@ -1293,6 +1335,10 @@ pub fn zero(&self) -> Expr {
             Expr::float_constant(0.0)
         } else if self.is_double() {
             Expr::double_constant(0.0)
+        } else if self.is_float16() {
+            Expr::float16_constant_from_bitpattern(0)
+        } else if self.is_float128() {
+            Expr::float128_constant_from_bitpattern(0)
         } else if self.is_pointer() {
             Expr::pointer_constant(0, self.clone())
         } else {
@@ -1309,6 +1355,8 @@ pub fn zero_initializer(&self, st: &SymbolTable) -> Expr {
             | CInteger(_)
             | Double
             | Float
+            | Float16
+            | Float128
             | Integer
             | Pointer { .. }
             | Signedbv { .. }
@@ -1413,6 +1461,8 @@ pub fn to_identifier(&self) -> String {
             Type::Empty => "empty".to_string(),
             Type::FlexibleArray { typ } => format!("flexarray_of_{}", typ.to_identifier()),
             Type::Float => "float".to_string(),
+            Type::Float16 => "float16".to_string(),
+            Type::Float128 => "float128".to_string(),
             Type::IncompleteStruct { tag } => tag.to_string(),
             Type::IncompleteUnion { tag } => tag.to_string(),
             Type::InfiniteArray { typ } => {
@@ -1450,7 +1500,7 @@ pub fn to_identifier(&self) -> String {
 mod type_tests {
     use super::*;
     use crate::goto_program::typ::CIntType::Char;
-    use crate::goto_program::{Location, Symbol};
+    use crate::goto_program::{ExprValue, Location, Symbol};
     use crate::machine_model::test_util::machine_model_test_stub;
 
     // Just a dummy name used for the tests.
@@ -1542,6 +1592,29 @@ fn check_typedef_float_properties() {
         check_equivalent(Float, SymbolTable::new(machine_model_test_stub()));
     }
 
+    #[test]
+    fn check_typedef_half_and_quad_float_properties() {
+        for typ in [Float16, Float128] {
+            check_properties(typ.clone());
+            check_equivalent(typ, SymbolTable::new(machine_model_test_stub()));
+        }
+    }
+
+    #[test]
+    fn check_half_and_quad_float_constants() {
+        let st = SymbolTable::new(machine_model_test_stub());
+        assert_eq!(Type::float16().sizeof_in_bits(&st), 16);
+        assert_eq!(Type::float128().sizeof_in_bits(&st), 128);
+        assert!(Type::float16().is_floating_point());
+        assert!(Type::float128().is_numeric());
+        assert!(matches!(Type::float16().one().value(), ExprValue::Float16Constant(0x3C00)));
+        assert!(matches!(
+            Type::float128().one().value(),
+            ExprValue::Float128Constant(0x3FFF_0000_0000_0000_0000_0000_0000_0000)
+        ));
+        assert!(matches!(Type::float16().zero().value(), ExprValue::Float16Constant(0)));
+    }
+
     #[test]
     fn check_typedef_struct_properties() {
         // Create a struct with a random field.
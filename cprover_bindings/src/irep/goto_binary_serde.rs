@@ -19,11 +19,16 @@
 /// - src/util/irep_hash_container.h
 /// - src/util/irep_hash.h
 pub fn write_goto_binary_file(filename: &Path, source: &crate::goto_program::SymbolTable) {
+    write_irep_goto_binary_file(filename, &source.to_irep());
+}
+
+/// Writes a symbol table whose symbols are already converted to ireps to a file in goto binary
+/// format in version 5, see [write_goto_binary_file].
+pub fn write_irep_goto_binary_file(filename: &Path, source: &SymbolTable) {
     let out_file = File::create(filename).unwrap();
     let mut writer = BufWriter::new(out_file);
     let mut serializer = GotoBinarySerializer::new(&mut writer);
-    let irep_symbol_table = &source.to_irep();
-    serializer.write_file(irep_symbol_table);
+    serializer.write_file(source);
 }
 
 /// Reads a symbol table from a file expected to be in goto binary format in version 5.
@@ -33,6 +33,22 @@ pub fn lookup_as_string(&self, id: IrepId) -> Option<String> {
             if s.is_empty() { None } else { Some(s) }
         })
     }
+
+    /// Call `f` with the identifier of every symbol referred to by this irep or its sub-ireps,
+    /// e.g., symbol expressions, parameters, struct tags and typedefs.
+    pub fn for_each_identifier(&self, mut f: impl FnMut(InternedString)) {
+        // Deeply nested expressions are common, so this doesn't recurse.
+        let mut worklist = vec![self];
+        while let Some(irep) = worklist.pop() {
+            for key in [IrepId::Identifier, IrepId::CIdentifier, IrepId::CTypedef] {
+                if let Some(IrepId::FreeformString(identifier)) = irep.lookup(key).map(|x| &x.id) {
+                    f(*identifier);
+                }
+            }
+            worklist.extend(irep.sub.iter());
+            worklist.extend(irep.named_sub.values());
+        }
+    }
 }
 
 /// Fluent Builders
@@ -254,6 +254,19 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                     )],
                 }
             }
+            ExprValue::Float16Constant(bits) => Irep {
+                id: IrepId::Constant,
+                sub: vec![],
+                named_sub: linear_map![(IrepId::Value, Irep::just_bitpattern_id(*bits, 16, false))],
+            },
+            ExprValue::Float128Constant(bits) => Irep {
+                id: IrepId::Constant,
+                sub: vec![],
+                named_sub: linear_map![(
+                    IrepId::Value,
+                    Irep::just_bitpattern_id(*bits, 128, false)
+                )],
+            },
             ExprValue::FunctionCall { function, arguments } => side_effect_irep(
                 IrepId::FunctionCall,
                 vec![function.to_irep(mm), arguments_irep(arguments.iter(), mm)],
@@ -695,6 +708,22 @@ fn to_irep(&self, mm: &MachineModel) -> Irep {
                     (IrepId::CCType, Irep::just_id(IrepId::Float)),
                 ],
             },
+            Type::Float16 => Irep {
+                id: IrepId::Floatbv,
+                sub: vec![],
+                named_sub: linear_map![
+                    (IrepId::F, Irep::just_int_id(10)),
+                    (IrepId::Width, Irep::just_int_id(16)),
+                ],
+            },
+            Type::Float128 => Irep {
+                id: IrepId::Floatbv,
+                sub: vec![],
+                named_sub: linear_map![
+                    (IrepId::F, Irep::just_int_id(112)),
+                    (IrepId::Width, Irep::just_int_id(128)),
+                ],
+            },
             Type::IncompleteStruct { tag } => Irep {
                 id: IrepId::Struct,
                 sub: vec![],
@@ -1,6 +1,8 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
+use kani_metadata::{HarnessFilter, HarnessMetadata, Stub};
+use std::path::PathBuf;
 use strum_macros::{AsRefStr, EnumString, VariantNames};
 use tracing_subscriber::filter::Directive;
 
@@ -18,6 +20,17 @@ pub enum ReachabilityType {
     Tests,
 }
 
+/// Where coverage checks for code generated by macro expansions should be reported.
+#[derive(Debug, Default, Clone, Copy, AsRefStr, EnumString, VariantNames, PartialEq, Eq)]
+#[strum(serialize_all = "snake_case")]
+pub enum CoverageMacroLocation {
+    /// Report the coverage at the outermost macro invocation in the user code.
+    #[default]
+    CallSite,
+    /// Report the coverage at the macro definition, if the expanded code has a source location.
+    Definition,
+}
+
 /// Command line arguments that this instance of the compiler run was called
 /// with. Usually stored in and accessible via [`crate::kani_queries::QueryDb`].
 #[derive(Debug, Default, Clone, clap::Parser)]
@@ -28,6 +41,9 @@ pub struct Arguments {
     /// Option name used to enable coverage checks.
     #[clap(long = "coverage-checks")]
     pub check_coverage: bool,
+    /// Option name used to select where coverage checks for macro expansions are reported.
+    #[clap(long = "coverage-macro-location", default_value = "call_site")]
+    pub coverage_macro_location: CoverageMacroLocation,
     /// Option name used to dump function pointer restrictions.
     #[clap(long = "restrict-vtable-fn-ptrs")]
     pub emit_vtable_restrictions: bool,
@@ -74,9 +90,72 @@ pub struct Arguments {
     /// Enable specific checks.
     #[clap(long)]
     pub ub_check: Vec<ExtraChecks>,
+    /// Option name used to model that heap allocations may fail.
+    #[clap(long)]
+    pub allocator_may_fail: bool,
+    /// Emit memory fences that implement the ordering of atomic operations.
+    #[clap(long)]
+    pub weak_memory: bool,
     /// Ignore storage markers.
     #[clap(long)]
     pub ignore_storage_markers: bool,
+    /// Pass the bytes of every symbolic value to `__KANI_nondet_created`, which is used to
+    /// minimize the counterexamples.
+    #[clap(long)]
+    pub minimize_counterexamples: bool,
+    /// Don't fold the constants and prune the dead branches of the function bodies before
+    /// generating their code.
+    #[clap(long)]
+    pub no_prepass: bool,
+    /// Dynamic libraries that implement instrumentation passes.
+    #[clap(long = "instrumentation-pass")]
+    pub instrumentation_passes: Vec<PathBuf>,
+    /// Stubs applied to every harness, in the form `ORIGINAL=REPLACEMENT`.
+    #[clap(long = "stub", value_parser = parse_stub)]
+    pub stubs: Vec<Stub>,
+    /// Only generate the code of the harnesses that match one of these filters, which use the
+    /// syntax of the `--harness` option of Kani. The code of every harness is generated if there's
+    /// no filter.
+    #[clap(long = "harness")]
+    pub harnesses: Vec<String>,
+    /// Don't generate the code of the harnesses that match one of these filters.
+    #[clap(long = "exclude-harness")]
+    pub exclude_harnesses: Vec<String>,
+    /// Only match the harness filters against the fully qualified names of the harnesses.
+    #[clap(long)]
+    pub exact: bool,
+    /// Only generate the metadata of the harnesses, without their code, e.g., to list them.
+    #[clap(long)]
+    pub metadata_only: bool,
+}
+
+impl Arguments {
+    /// Whether the code of `harness` should be generated according to the harness filters and
+    /// `--metadata-only`.
+    /// Invalid filters are reported by Kani, and they never match here.
+    pub fn selects_harness(&self, harness: &HarnessMetadata) -> bool {
+        if self.metadata_only {
+            return false;
+        }
+        let matches = |filters: &[String]| {
+            filters
+                .iter()
+                .filter_map(|filter| HarnessFilter::parse(filter).ok())
+                .any(|filter| filter.matches(harness, self.exact))
+        };
+        (self.harnesses.is_empty() || matches(&self.harnesses)) && !matches(&self.exclude_harnesses)
+    }
+}
+
+/// Parse a stub given as `ORIGINAL=REPLACEMENT`.
+fn parse_stub(stub: &str) -> Result<Stub, String> {
+    match stub.split_once('=') {
+        Some((original, replacement)) => Ok(Stub {
+            original: original.trim().to_string(),
+            replacement: replacement.trim().to_string(),
+        }),
+        None => Err(format!("expected `ORIGINAL=REPLACEMENT`, found `{stub}`")),
+    }
 }
 
 #[derive(Debug, Clone, Copy, AsRefStr, EnumString, VariantNames, PartialEq, Eq)]
@@ -85,4 +164,12 @@ pub enum ExtraChecks {
     /// Check that produced values are valid except for uninitialized values.
     /// See https://github.com/model-checking/kani/issues/920.
     Validity,
+    /// Check that references aren't used after a conflicting access, according to Tree Borrows.
+    Aliasing,
+    /// Check that pointer arithmetic and integer-to-pointer casts preserve the provenance.
+    Provenance,
+    /// Check that memory is initialized before it's read.
+    Uninit,
+    /// Check that raw pointers are aligned when they are dereferenced.
+    Alignment,
 }
@@ -0,0 +1,208 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the alignment checks enabled with `-Z alignment-checks`.
+//!
+//! Every access through a raw pointer, i.e., a read, a write or the creation of a reference, must
+//! use a pointer that is aligned for the pointee type. Raw pointers can be misaligned, e.g., a
+//! pointer to a field of a `#[repr(packed)]` struct obtained with `ptr::addr_of!`, so we check
+//! every pointer dereferenced by a place before each access, including the pointers that are
+//! themselves read through a pointer, e.g., `*(*ptr).field`. Taking the address of a place, e.g.,
+//! `ptr::addr_of!((*ptr).field)`, doesn't access the memory and isn't checked, but the pointers
+//! read to compute the address are.
+
+use crate::args::ExtraChecks;
+use crate::codegen_cprover_gotoc::codegen::ty_stable::pointee_type_stable;
+use crate::codegen_cprover_gotoc::codegen::PropertyClass;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rustc_smir::rustc_internal;
+use stable_mir::mir::{
+    NonDivergingIntrinsic, Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind,
+    Terminator, TerminatorKind,
+};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z alignment-checks` is enabled.
+    pub fn is_alignment_check_enabled(&self) -> bool {
+        self.queries.args().ub_check.contains(&ExtraChecks::Alignment)
+    }
+
+    /// Generate the alignment checks of the places accessed by a statement.
+    pub fn codegen_alignment_statement(&mut self, stmt: &Statement) -> Option<Stmt> {
+        if !self.is_alignment_check_enabled() {
+            return None;
+        }
+        let mut places = vec![];
+        match &stmt.kind {
+            StatementKind::Assign(place, rvalue) => {
+                places.push(Access::new(place));
+                rvalue_places(rvalue, &mut places);
+            }
+            StatementKind::SetDiscriminant { place, .. } => places.push(Access::new(place)),
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(op)) => {
+                operand_place(op, &mut places)
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+                // The pointers are checked by the codegen of the intrinsic, but the operands may
+                // be read through a pointer.
+                operand_place(&copy.src, &mut places);
+                operand_place(&copy.dst, &mut places);
+                operand_place(&copy.count, &mut places);
+            }
+            StatementKind::FakeRead(..)
+            | StatementKind::Deinit(_)
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::Retag(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+        let loc = self.codegen_span_stable(stmt.span);
+        self.codegen_alignment_checks(&places, loc)
+    }
+
+    /// Generate the alignment checks of the places accessed by a terminator.
+    pub fn codegen_alignment_terminator(&mut self, term: &Terminator) -> Option<Stmt> {
+        if !self.is_alignment_check_enabled() {
+            return None;
+        }
+        let mut places = vec![];
+        match &term.kind {
+            TerminatorKind::Call { func, args, destination, .. } => {
+                operand_place(func, &mut places);
+                args.iter().for_each(|arg| operand_place(arg, &mut places));
+                places.push(Access::new(destination));
+            }
+            TerminatorKind::SwitchInt { discr, .. } => operand_place(discr, &mut places),
+            TerminatorKind::Assert { cond, .. } => operand_place(cond, &mut places),
+            TerminatorKind::Drop { place, .. } => places.push(Access::new(place)),
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::InlineAsm { .. } => {}
+        }
+        let loc = self.codegen_span_stable(term.span);
+        self.codegen_alignment_checks(&places, loc)
+    }
+
+    fn codegen_alignment_checks(&mut self, places: &[Access], loc: Location) -> Option<Stmt> {
+        let checks = places
+            .iter()
+            .flat_map(|access| {
+                let derefs = deref_positions(access.place);
+                // Taking the address of `*ptr` doesn't access the memory that `ptr` points to.
+                let skip_last = access.is_address_of && !derefs.is_empty();
+                let checked = &derefs[..derefs.len() - usize::from(skip_last)];
+                checked
+                    .iter()
+                    .filter_map(|idx| self.codegen_alignment_check(access.place, *idx, loc))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        (!checks.is_empty()).then(|| Stmt::block(checks, loc))
+    }
+
+    /// Check that the raw pointer dereferenced by the projection `idx` of `place` is aligned for
+    /// its pointee, i.e., for the type of the dereferenced place.
+    fn codegen_alignment_check(
+        &mut self,
+        place: &Place,
+        idx: usize,
+        loc: Location,
+    ) -> Option<Stmt> {
+        let ptr_place = Place { local: place.local, projection: place.projection[..idx].to_vec() };
+        let ptr_ty = self.place_ty_stable(&ptr_place);
+        if !ptr_ty.kind().is_raw_ptr() {
+            return None;
+        }
+        let pointee_ty = pointee_type_stable(ptr_ty).unwrap();
+        let pointee_ty_internal = rustc_internal::internal(self.tcx, pointee_ty);
+        if self.use_vtable_fat_pointer(pointee_ty_internal) {
+            // The alignment is only known at runtime.
+            return None;
+        }
+        let align = self.layout_of_stable(pointee_ty).align.abi.bytes();
+        if align == 1 {
+            return None;
+        }
+        let ptr = self.codegen_place_stable(&ptr_place).ok()?.goto_expr;
+        let data = if self.is_fat_pointer_stable(ptr_ty) {
+            ptr.member("data", &self.symbol_table)
+        } else {
+            ptr
+        };
+        let is_aligned = data
+            .cast_to(Type::size_t())
+            .rem(Expr::int_constant(align, Type::size_t()))
+            .eq(Type::size_t().zero());
+        Some(self.codegen_assert_assume(
+            is_aligned,
+            PropertyClass::MisalignedAccess,
+            "misaligned pointer dereference: address must be a multiple of its type's alignment",
+            loc,
+        ))
+    }
+}
+
+/// A place used by a statement or terminator.
+struct Access<'a> {
+    place: &'a Place,
+    /// Whether only the address of the place is taken, e.g., by `ptr::addr_of!`.
+    is_address_of: bool,
+}
+
+impl<'a> Access<'a> {
+    fn new(place: &'a Place) -> Self {
+        Access { place, is_address_of: false }
+    }
+}
+
+/// The positions of the dereferences in the projections of `place`, e.g., `(*(*ptr).field)` has
+/// two dereferences, and the pointer of the second one is read through the first one.
+fn deref_positions(place: &Place) -> Vec<usize> {
+    place
+        .projection
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, elem)| (*elem == ProjectionElem::Deref).then_some(idx))
+        .collect()
+}
+
+/// Collect the place read by an operand.
+fn operand_place<'a>(operand: &'a Operand, places: &mut Vec<Access<'a>>) {
+    match operand {
+        Operand::Copy(place) | Operand::Move(place) => places.push(Access::new(place)),
+        Operand::Constant(_) => {}
+    }
+}
+
+/// Collect the places accessed by an rvalue. Creating a reference is considered an access, but
+/// taking the address of a place or its length isn't. The pointers dereferenced to compute the
+/// address of the place are still read, though.
+fn rvalue_places<'a>(rvalue: &'a Rvalue, places: &mut Vec<Access<'a>>) {
+    match rvalue {
+        Rvalue::Ref(_, _, place) | Rvalue::Discriminant(place) | Rvalue::CopyForDeref(place) => {
+            places.push(Access::new(place))
+        }
+        Rvalue::AddressOf(_, place) | Rvalue::Len(place) => {
+            places.push(Access { place, is_address_of: true })
+        }
+        Rvalue::Use(op)
+        | Rvalue::Repeat(op, _)
+        | Rvalue::Cast(_, op, _)
+        | Rvalue::UnaryOp(_, op)
+        | Rvalue::ShallowInitBox(op, _) => operand_place(op, places),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            operand_place(lhs, places);
+            operand_place(rhs, places);
+        }
+        Rvalue::Aggregate(_, ops) => ops.iter().for_each(|op| operand_place(op, places)),
+        Rvalue::NullaryOp(..) | Rvalue::ThreadLocalRef(_) => {}
+    }
+}
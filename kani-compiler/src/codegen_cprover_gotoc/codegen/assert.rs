@@ -71,6 +71,22 @@ pub enum PropertyClass {
     ///
     /// SPECIAL BEHAVIOR: None TODO: Why should this exist?
     FiniteCheck,
+    /// Checks added by `-Z valid-value-checks` for the reads of a union field whose bytes aren't
+    /// a valid value of the type of the field, e.g., because they were written through another
+    /// field.
+    ///
+    /// SPECIAL BEHAVIOR: None. This is a kind of safety check that is reported separately.
+    InvalidUnionRead,
+    /// Checks added by `-Z alignment-checks` that a raw pointer is aligned for its pointee type
+    /// when it's used to access memory.
+    ///
+    /// SPECIAL BEHAVIOR: None. This is a kind of safety check that is reported separately.
+    MisalignedAccess,
+    /// Checks that the source and destination regions of a non-overlapping copy, e.g.,
+    /// `ptr::copy_nonoverlapping`, don't overlap.
+    ///
+    /// SPECIAL BEHAVIOR: None. This is a kind of safety check that is reported separately.
+    OverlappingCopy,
     /// Checks added by Kani compiler to determine whether a property (e.g.
     /// `PropertyClass::Assertion` or `PropertyClass:Cover`) is reachable
     ReachabilityCheck,
@@ -148,17 +164,18 @@ pub fn codegen_cover(&self, cond: Expr, msg: &str, span: SpanStable) -> Stmt {
 
     /// Generate a cover statement for code coverage reports.
     pub fn codegen_coverage(&self, span: SpanStable) -> Stmt {
-        let loc = self.codegen_caller_span_stable(span);
+        let span = self.coverage_span(span);
+        let loc = self.codegen_span(&span);
+        let msg = if self.is_gap_line(span) {
+            "code coverage for gap region"
+        } else {
+            "code coverage for location"
+        };
         // Should use Stmt::cover, but currently this doesn't work with CBMC
         // unless it is run with '--cover cover' (see
         // https://github.com/diffblue/cbmc/issues/6613). So for now use
         // `assert(false)`.
-        self.codegen_assert(
-            Expr::bool_false(),
-            PropertyClass::CodeCoverage,
-            "code coverage for location",
-            loc,
-        )
+        self.codegen_assert(Expr::bool_false(), PropertyClass::CodeCoverage, msg, loc)
     }
 
     // The above represent the basic operations we can perform w.r.t. assert/assume/cover
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::codegen_cprover_gotoc::GotocCtx;
+use cbmc::goto_program::Stmt;
 use stable_mir::mir::{BasicBlock, BasicBlockIdx, Body};
 use std::collections::HashSet;
 use tracing::debug;
@@ -19,57 +20,46 @@ impl<'tcx> GotocCtx<'tcx> {
     /// `self.current_fn_mut().push_onto_block(...)`
     pub fn codegen_block(&mut self, bb: BasicBlockIdx, bbd: &BasicBlock) {
         debug!(?bb, "codegen_block");
-        let label = bb_label(bb);
-        let check_coverage = self.queries.args().check_coverage;
-        // the first statement should be labelled. if there is no statements, then the
-        // terminator should be labelled.
-        match bbd.statements.len() {
-            0 => {
-                let term = &bbd.terminator;
-                let tcode = self.codegen_terminator(term);
-                // When checking coverage, the `coverage` check should be
-                // labelled instead.
-                if check_coverage {
-                    let span = term.span;
-                    let cover = self.codegen_coverage(span);
-                    self.current_fn_mut().push_onto_block(cover.with_label(label));
-                    self.current_fn_mut().push_onto_block(tcode);
-                } else {
-                    self.current_fn_mut().push_onto_block(tcode.with_label(label));
-                }
+        let check_coverage = self.current_fn().check_coverage();
+        // The first statement should be labelled. When checking coverage, alignment or
+        // uninitialized memory, the instrumentation before the first instruction gets the label
+        // instead.
+        let mut label = Some(bb_label(bb));
+        for (idx, stmt) in bbd.statements.iter().enumerate() {
+            let mut instrumentation = vec![];
+            if check_coverage {
+                instrumentation.push(self.codegen_coverage(stmt.span));
             }
-            _ => {
-                let stmt = &bbd.statements[0];
-                let scode = self.codegen_statement(stmt);
-                // When checking coverage, the `coverage` check should be
-                // labelled instead.
-                if check_coverage {
-                    let span = stmt.span;
-                    let cover = self.codegen_coverage(span);
-                    self.current_fn_mut().push_onto_block(cover.with_label(label));
-                    self.current_fn_mut().push_onto_block(scode);
-                } else {
-                    self.current_fn_mut().push_onto_block(scode.with_label(label));
-                }
+            instrumentation.extend(self.codegen_alignment_statement(stmt));
+            instrumentation.extend(self.codegen_uninit_statement(bb, idx, stmt));
+            let scode = self.codegen_statement(stmt);
+            self.push_instruction(&mut label, instrumentation, scode);
+        }
+        let term = &bbd.terminator;
+        let mut instrumentation = vec![];
+        if check_coverage {
+            instrumentation.push(self.codegen_coverage(term.span));
+        }
+        instrumentation.extend(self.codegen_alignment_terminator(term));
+        instrumentation.extend(self.codegen_uninit_terminator(bb, bbd.statements.len(), term));
+        let tcode = self.codegen_terminator(term);
+        self.push_instruction(&mut label, instrumentation, tcode);
+    }
 
-                for s in &bbd.statements[1..] {
-                    if check_coverage {
-                        let span = s.span;
-                        let cover = self.codegen_coverage(span);
-                        self.current_fn_mut().push_onto_block(cover);
-                    }
-                    let stmt = self.codegen_statement(s);
-                    self.current_fn_mut().push_onto_block(stmt);
-                }
-                let term = &bbd.terminator;
-                if check_coverage {
-                    let span = term.span;
-                    let cover = self.codegen_coverage(span);
-                    self.current_fn_mut().push_onto_block(cover);
-                }
-                let tcode = self.codegen_terminator(term);
-                self.current_fn_mut().push_onto_block(tcode);
-            }
+    /// Push the code of an instruction preceded by its instrumentation onto the current block.
+    /// The first statement pushed takes the label, if any.
+    fn push_instruction(
+        &mut self,
+        label: &mut Option<String>,
+        instrumentation: Vec<Stmt>,
+        code: Stmt,
+    ) {
+        for stmt in instrumentation.into_iter().chain(std::iter::once(code)) {
+            let stmt = match label.take() {
+                Some(label) => stmt.with_label(label),
+                None => stmt,
+            };
+            self.current_fn_mut().push_onto_block(stmt);
         }
     }
 }
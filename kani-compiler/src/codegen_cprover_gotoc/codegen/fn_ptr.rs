@@ -0,0 +1,88 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the restriction of the targets of calls through function pointers that
+//! is enabled with `-Z restrict-fn-ptrs`.
+//!
+//! Instead of leaving the call through the pointer to CBMC, which considers every function with a
+//! compatible C signature, we emit a direct call to each of the possible targets computed by
+//! [crate::kani_middle::fn_ptr], guarded by a comparison with the pointer.
+
+use crate::codegen_cprover_gotoc::codegen::PropertyClass;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rustc_middle::ty::PolyFnSig;
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::{Operand, Place};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z restrict-fn-ptrs` is enabled.
+    pub fn is_fn_ptr_restriction_enabled(&self) -> bool {
+        self.queries.args().unstable_features.iter().any(|feature| feature == "restrict-fn-ptrs")
+    }
+
+    /// The possible targets of a call through the function pointer `func` with signature `sig`,
+    /// or `None` if the restriction of the targets isn't enabled.
+    ///
+    /// We use the values that the current function may store in `func` if they are known, and
+    /// every function converted to a pointer with the same signature otherwise.
+    pub fn fn_ptr_call_targets(
+        &self,
+        func: &Operand,
+        sig: PolyFnSig<'tcx>,
+    ) -> Option<Vec<Instance>> {
+        let targets = self.fn_ptr_targets.as_ref()?;
+        let local_targets =
+            self.current_fn().local_fn_ptrs().and_then(|local_fn_ptrs| local_fn_ptrs.targets(func));
+        Some(local_targets.unwrap_or_else(|| targets.targets(self.tcx, sig)))
+    }
+
+    /// Generate a call through the function pointer `fn_ptr`, which may only point to one of the
+    /// `targets`, and assign the result to `destination`.
+    ///
+    /// The call is replaced by a direct call to the target that is equal to the pointer. If the
+    /// pointer isn't equal to any of them, we fail a check instead of calling an arbitrary
+    /// function.
+    pub fn codegen_restricted_fn_ptr_call(
+        &mut self,
+        fn_ptr: Expr,
+        targets: &[Instance],
+        fargs: Vec<Expr>,
+        destination: &Place,
+        loc: Location,
+    ) -> Stmt {
+        let (fn_ptr, decl) = self.decl_temp_variable(fn_ptr.typ().clone(), Some(fn_ptr), loc);
+        let fn_typ = fn_ptr.typ().base_type().unwrap().clone();
+        let mut dispatch = self.codegen_assert_assume_false(
+            PropertyClass::SafetyCheck,
+            "function pointer must point to a function of a compatible type",
+            loc,
+        );
+        for target in targets.iter().rev() {
+            let func = self.codegen_func_expr(*target, None);
+            let address = func.clone().address_of().cast_to(fn_ptr.typ().clone());
+            // The goto signature of the target may differ from the pointer one, e.g., for the
+            // `FnOnce::call_once` shim of a closure.
+            let callee = if same_signature(func.typ(), &fn_typ) {
+                func
+            } else {
+                address.clone().dereference()
+            };
+            let call =
+                self.codegen_expr_to_place_stable(destination, callee.call(fargs.clone()), loc);
+            dispatch = Stmt::if_then_else(fn_ptr.clone().eq(address), call, Some(dispatch), loc);
+        }
+        Stmt::block(vec![decl, dispatch], loc)
+    }
+}
+
+/// Whether two function types have the same parameter and return types.
+fn same_signature(fn_typ: &Type, other: &Type) -> bool {
+    let param_types = |typ: &Type| {
+        typ.parameters()
+            .map(|params| params.iter().map(|param| param.typ().clone()).collect::<Vec<_>>())
+    };
+    fn_typ.is_code() == other.is_code()
+        && fn_typ.return_type() == other.return_type()
+        && param_types(fn_typ) == param_types(other)
+}
@@ -3,7 +3,11 @@
 //! This module implements foreign function handling.
 //!
 //! Kani currently only support CBMC built-in functions that are declared in the `cprover_bindings`
-//! crate, and allocation functions defined in `kani_lib.c`.
+//! crate, allocation functions defined in `kani_lib.c`, and some of the LLVM intrinsics used by
+//! `core::arch` (see `llvm_intrinsic.rs`).
+//!
+//! C variadic functions, such as `printf`, are declared as they are, so calls to them can be
+//! linked to the models in the CBMC C library, or to the C definitions provided by the user.
 //!
 //! All other functions will be replaced by an unimplemented check, due to current issues with
 //! linking and usability unless unstable C-FFI support is enabled.
@@ -35,6 +39,7 @@
             "__rust_alloc_zeroed".into(),
             "__rust_dealloc".into(),
             "__rust_realloc".into(),
+            "__rust_alloc_error_handler".into(),
         ])
     };
 }
@@ -54,10 +59,13 @@ pub fn codegen_foreign_fn(&mut self, instance: Instance) -> &Symbol {
             // Symbol has been added (either a built-in CBMC function or a Rust allocation function).
             self.symbol_table.lookup(fn_name).unwrap()
         } else if RUST_ALLOC_FNS.contains(&fn_name)
-            || (self.is_cffi_enabled() && instance.fn_abi().unwrap().conv == CallConvention::C)
+            || ((self.is_cffi_enabled() || instance.fn_abi().unwrap().c_variadic)
+                && instance.fn_abi().unwrap().conv == CallConvention::C
+                && !fn_name.starts_with("llvm."))
         {
             // Add a Rust alloc lib function as is declared by core.
-            // When C-FFI feature is enabled, we just trust the rust declaration.
+            // When C-FFI feature is enabled, or for C variadic functions, we just trust the rust
+            // declaration.
             // TODO: Add proper casting and clashing definitions check.
             // https://github.com/model-checking/kani/issues/1350
             // https://github.com/model-checking/kani/issues/2426
@@ -93,13 +101,15 @@ pub fn codegen_foreign_call(
         ret_place: &Place,
         loc: Location,
     ) -> Stmt {
-        let expected_args = fn_expr
-            .typ()
-            .parameters()
-            .unwrap()
-            .iter()
-            .zip(args)
-            .map(|(param, arg)| arg.cast_to(param.typ().clone()))
+        let params = fn_expr.typ().parameters().unwrap();
+        // The extra arguments of a variadic function are passed as they are.
+        let expected_args = args
+            .into_iter()
+            .enumerate()
+            .map(|(idx, arg)| match params.get(idx) {
+                Some(param) => arg.cast_to(param.typ().clone()),
+                None => arg,
+            })
             .collect::<Vec<_>>();
         let call_expr = fn_expr.call(expected_args);
 
@@ -128,6 +138,9 @@ fn codegen_ffi_shim(&mut self, shim_name: InternedString, instance: Instance) ->
         debug!(?shim_name, ?instance, sym=?self.symbol_table.lookup(shim_name), "generate_foreign_shim");
 
         let loc = self.codegen_span_stable(instance.def.span());
+        if let Some(body) = self.codegen_llvm_intrinsic(instance, loc) {
+            return body;
+        }
         let unsupported_check = self.codegen_ffi_unsupported(instance, loc);
         Stmt::block(vec![unsupported_check], loc)
     }
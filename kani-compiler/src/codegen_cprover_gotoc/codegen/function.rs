@@ -84,6 +84,12 @@ fn codegen_function_prelude(&mut self, body: &Body) {
         if let Some(spread_arg) = body.spread_arg() {
             self.codegen_spread_arg(body, spread_arg);
         }
+        if let Some(stmt) = self.codegen_catch_unwind_prelude() {
+            self.current_fn_mut().push_onto_block(stmt);
+        }
+        if let Some(stmt) = self.codegen_uninit_prelude(body) {
+            self.current_fn_mut().push_onto_block(stmt);
+        }
     }
 
     /// MIR functions have a `spread_arg` field that specifies whether the
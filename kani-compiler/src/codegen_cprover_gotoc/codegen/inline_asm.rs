@@ -0,0 +1,118 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the model of inline assembly that is enabled with `-Z inline-asm`.
+//!
+//! Kani can't interpret assembly code, so `asm!` blocks are reported as unsupported constructs by
+//! default. With `-Z inline-asm`, an `asm!` block is modeled as an operation that may produce
+//! any value:
+//!  - Every output operand is assigned a nondeterministic value.
+//!  - Unless the block is `nomem`, `readonly` or `pure`, it may write to memory. We model that by
+//!    assigning a nondeterministic value to the pointee of every pointer input that is valid.
+//!  - A `noreturn` block never returns.
+//!
+//! Register clobbers aren't modeled, since registers aren't visible to Rust code. Note that the
+//! memory writes are an under-approximation, since the assembly may write to memory that isn't
+//! reachable from its inputs, e.g., a static.
+
+use crate::codegen_cprover_gotoc::codegen::bb_label;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::unwrap_or_return_codegen_unimplemented_stmt;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use rustc_ast::InlineAsmOptions;
+use stable_mir::mir::{BasicBlockIdx, InlineAsmOperand, Mutability};
+use stable_mir::ty::{RigidTy, Ty, TyKind};
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z inline-asm` is enabled. Otherwise, inline assembly is unsupported.
+    pub fn is_inline_asm_enabled(&self) -> bool {
+        self.queries.args().unstable_features.iter().any(|feature| feature == "inline-asm")
+    }
+
+    /// Generate the model of an `asm!` block.
+    ///
+    /// Stable MIR only provides the debug representation of the options of the block, e.g.,
+    /// `InlineAsmOptions(PURE | NOMEM | NOSTACK)`, so they are parsed back first.
+    pub fn codegen_inline_asm(
+        &mut self,
+        operands: &[InlineAsmOperand],
+        options: &str,
+        destination: Option<BasicBlockIdx>,
+        loc: Location,
+    ) -> Stmt {
+        let options = parse_asm_options(options);
+        let writes_memory = !(options.contains(InlineAsmOptions::NOMEM)
+            || options.contains(InlineAsmOptions::READONLY)
+            || options.contains(InlineAsmOptions::PURE));
+
+        let mut stmts = vec![];
+        if writes_memory {
+            for operand in operands {
+                if let Some(value) = &operand.in_value
+                    && let Some(pointee_ty) = self.writable_pointee(self.operand_ty_stable(value))
+                {
+                    let ptr = self.codegen_operand_stable(value);
+                    stmts.push(self.codegen_havoc_pointee(ptr, pointee_ty, loc));
+                }
+            }
+        }
+        for operand in operands {
+            if let Some(place) = &operand.out_place {
+                let place_ty = self.place_ty_stable(place);
+                if self.is_zst_stable(place_ty) {
+                    continue;
+                }
+                let typ = self.codegen_ty_stable(place_ty);
+                let lhs = unwrap_or_return_codegen_unimplemented_stmt!(
+                    self,
+                    self.codegen_place_stable(place)
+                )
+                .goto_expr;
+                stmts.push(lhs.assign(Expr::nondet(typ), loc));
+            }
+        }
+        match destination {
+            Some(target) => stmts.push(Stmt::goto(bb_label(target), loc)),
+            None => stmts.push(Stmt::assume(Expr::bool_false(), loc)),
+        }
+        Stmt::block(stmts, loc)
+    }
+
+    /// The type of the memory that the assembly may write through a pointer input of type
+    /// `ptr_ty`, if any. Shared references can't be written to, and unsized or zero-sized
+    /// pointees are ignored.
+    fn writable_pointee(&self, ptr_ty: Ty) -> Option<Ty> {
+        let pointee_ty = match ptr_ty.kind() {
+            TyKind::RigidTy(RigidTy::RawPtr(pointee_ty, _))
+            | TyKind::RigidTy(RigidTy::Ref(_, pointee_ty, Mutability::Mut)) => pointee_ty,
+            _ => return None,
+        };
+        let layout = pointee_ty.layout().ok()?.shape();
+        (layout.is_sized() && layout.size.bytes() > 0).then_some(pointee_ty)
+    }
+
+    /// Assign a nondeterministic value to the pointee of `ptr` if it's valid for reads, i.e., it
+    /// points to an allocation that is large enough.
+    fn codegen_havoc_pointee(&mut self, ptr: Expr, pointee_ty: Ty, loc: Location) -> Stmt {
+        let typ = self.codegen_ty_stable(pointee_ty);
+        let size =
+            Expr::int_constant(pointee_ty.layout().unwrap().shape().size.bytes(), Type::size_t());
+        let is_valid = Expr::read_ok(ptr.clone().cast_to(Type::void_pointer()), size);
+        let havoc =
+            ptr.cast_to(typ.clone().to_pointer()).dereference().assign(Expr::nondet(typ), loc);
+        Stmt::if_then_else(is_valid, havoc, None, loc)
+    }
+}
+
+/// Parse the debug representation of the options of an `asm!` block, e.g.,
+/// `InlineAsmOptions(PURE | NOMEM | NOSTACK)`.
+fn parse_asm_options(options: &str) -> InlineAsmOptions {
+    let flags = options
+        .strip_prefix("InlineAsmOptions(")
+        .and_then(|flags| flags.strip_suffix(')'))
+        .unwrap_or(options);
+    flags
+        .split('|')
+        .filter_map(|flag| InlineAsmOptions::from_name(flag.trim()))
+        .fold(InlineAsmOptions::empty(), |options, flag| options | flag)
+}
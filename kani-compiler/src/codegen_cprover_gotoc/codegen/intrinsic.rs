@@ -7,7 +7,7 @@
 use crate::codegen_cprover_gotoc::{utils, GotocCtx};
 use crate::unwrap_or_return_codegen_unimplemented_stmt;
 use cbmc::goto_program::{
-    ArithmeticOverflowResult, BinaryOperator, BuiltinFn, Expr, Location, Stmt, Type,
+    ArithmeticOverflowResult, BinaryOperator, BuiltinFn, Expr, Location, Stmt, Symbol, Type,
 };
 use rustc_middle::ty::layout::ValidityRequirement;
 use rustc_middle::ty::ParamEnv;
@@ -17,9 +17,9 @@
 use stable_mir::ty::{GenericArgs, RigidTy, Span, Ty, TyKind, UintTy};
 use tracing::debug;
 
-struct SizeAlign {
-    size: Expr,
-    align: Expr,
+pub struct SizeAlign {
+    pub size: Expr,
+    pub align: Expr,
 }
 
 enum VTableInfo {
@@ -54,13 +54,16 @@ pub fn codegen_funcall_of_intrinsic(
         if let Some(target) = target {
             let loc = self.codegen_span_stable(span);
             let fargs = args.iter().map(|arg| self.codegen_operand_stable(arg)).collect::<Vec<_>>();
-            Stmt::block(
-                vec![
-                    self.codegen_intrinsic(instance, fargs, destination, span),
-                    Stmt::goto(bb_label(target), loc),
-                ],
+            let name = instance.intrinsic_name();
+            let mut stmts = Vec::from_iter(self.codegen_uninit_builtin_call(
+                name.as_deref(),
+                args,
+                destination,
                 loc,
-            )
+            ));
+            stmts.push(self.codegen_intrinsic(instance, fargs, destination, span));
+            stmts.push(Stmt::goto(bb_label(target), loc));
+            Stmt::block(stmts, loc)
         } else {
             self.codegen_never_return_intrinsic(instance, span)
         }
@@ -272,7 +275,8 @@ macro_rules! codegen_atomic_binop {
                 };
                 let assign_stmt = (var1.clone()).assign(op_expr, loc);
                 let res_stmt = self.codegen_expr_to_place_stable(place, tmp.clone(), loc);
-                Stmt::atomic_block(vec![decl_stmt, assign_stmt, res_stmt], loc)
+                let block = Stmt::atomic_block(vec![decl_stmt, assign_stmt, res_stmt], loc);
+                self.codegen_atomic_ordering(intrinsic, block, loc)
             }};
         }
 
@@ -527,6 +531,7 @@ macro_rules! unstable_codegen {
             "simd_extract" => {
                 self.codegen_intrinsic_simd_extract(fargs, place, farg_types, ret_ty, span)
             }
+            "simd_gather" => self.codegen_simd_gather(fargs, place, loc),
             "simd_ge" => {
                 self.codegen_simd_cmp(Expr::vector_ge, fargs, place, span, farg_types, ret_ty)
             }
@@ -542,6 +547,8 @@ macro_rules! unstable_codegen {
             "simd_lt" => {
                 self.codegen_simd_cmp(Expr::vector_lt, fargs, place, span, farg_types, ret_ty)
             }
+            "simd_masked_load" => self.codegen_simd_masked_load(fargs, place, loc),
+            "simd_masked_store" => self.codegen_simd_masked_store(fargs, loc),
             "simd_mul" => self.codegen_simd_op_with_overflow(
                 Expr::mul,
                 Expr::mul_overflow_p,
@@ -553,7 +560,30 @@ macro_rules! unstable_codegen {
             "simd_ne" => {
                 self.codegen_simd_cmp(Expr::vector_neq, fargs, place, span, farg_types, ret_ty)
             }
+            "simd_neg" => {
+                self.codegen_simd_lanewise(fargs, place, loc, |mut lanes| lanes.remove(0).neg())
+            }
             "simd_or" => codegen_intrinsic_binop!(bitor),
+            name if name.starts_with("simd_reduce_") => {
+                self.codegen_simd_reduce(intrinsic, fargs, place, cbmc_ret_ty, loc)
+            }
+            "simd_saturating_add" | "simd_saturating_sub" => {
+                let mm = self.symbol_table.machine_model().clone();
+                let saturating_op = if intrinsic == "simd_saturating_add" {
+                    Expr::saturating_add
+                } else {
+                    Expr::saturating_sub
+                };
+                self.codegen_simd_lanewise(fargs, place, loc, |mut lanes| {
+                    let lhs = lanes.remove(0);
+                    saturating_op(lhs, lanes.remove(0), &mm)
+                })
+            }
+            "simd_scatter" => self.codegen_simd_scatter(fargs, loc),
+            "simd_select" => self.codegen_simd_select(fargs, place, farg_types, span),
+            "simd_select_bitmask" => {
+                self.codegen_simd_select_bitmask(fargs, place, farg_types, loc)
+            }
             "simd_shl" | "simd_shr" => {
                 self.codegen_simd_shift_with_distance_check(fargs, intrinsic, place, loc)
             }
@@ -600,9 +630,13 @@ macro_rules! unstable_codegen {
             "unreachable" => unreachable!(
                 "Expected `std::intrinsics::unreachable` to be handled by `TerminatorKind::Unreachable`"
             ),
-            "volatile_copy_memory" => unstable_codegen!(codegen_intrinsic_copy!(Memmove)),
+            "volatile_copy_memory" => {
+                assert!(self.place_ty_stable(place).kind().is_unit());
+                self.codegen_volatile_copy(intrinsic, false, fargs, farg_types, loc)
+            }
             "volatile_copy_nonoverlapping_memory" => {
-                unstable_codegen!(codegen_intrinsic_copy!(Memcpy))
+                assert!(self.place_ty_stable(place).kind().is_unit());
+                self.codegen_volatile_copy(intrinsic, true, fargs, farg_types, loc)
             }
             "volatile_load" => self.codegen_volatile_load(fargs, farg_types, place, loc),
             "volatile_store" => {
@@ -864,7 +898,8 @@ fn codegen_atomic_load(
         let var1_ref = fargs.remove(0);
         let var1 = var1_ref.dereference().with_location(loc);
         let res_stmt = self.codegen_expr_to_place_stable(p, var1, loc);
-        Stmt::atomic_block(vec![res_stmt], loc)
+        let block = Stmt::atomic_block(vec![res_stmt], loc);
+        self.codegen_atomic_ordering(intrinsic, block, loc)
     }
 
     /// An atomic compare-and-exchange updates the value referenced in
@@ -904,7 +939,8 @@ fn codegen_atomic_cxchg(
             Expr::struct_expr_from_values(res_type, vec![tmp, Expr::c_true()], &self.symbol_table)
                 .with_location(loc);
         let res_stmt = self.codegen_expr_to_place_stable(p, tuple_expr, loc);
-        Stmt::atomic_block(vec![decl_stmt, cond_update_stmt, res_stmt], loc)
+        let block = Stmt::atomic_block(vec![decl_stmt, cond_update_stmt, res_stmt], loc);
+        self.codegen_atomic_ordering(intrinsic, block, loc)
     }
 
     /// An atomic store updates the value referenced in
@@ -932,14 +968,74 @@ fn codegen_atomic_store(
         let var2 = fargs.remove(0).with_location(loc);
         let assign_stmt = var1.assign(var2, loc);
         let res_stmt = self.codegen_expr_to_place_stable(place, tmp, loc);
-        Stmt::atomic_block(vec![decl_stmt, assign_stmt, res_stmt], loc)
+        let block = Stmt::atomic_block(vec![decl_stmt, assign_stmt, res_stmt], loc);
+        self.codegen_atomic_ordering(intrinsic, block, loc)
     }
 
     /// Atomic no-ops (e.g., atomic_fence) are transformed into SKIP statements
     fn codegen_atomic_noop(&mut self, intrinsic: &str, loc: Location) -> Stmt {
         self.store_concurrent_construct(intrinsic, loc);
         let skip_stmt = Stmt::skip(loc);
-        Stmt::atomic_block(vec![skip_stmt], loc)
+        let block = Stmt::atomic_block(vec![skip_stmt], loc);
+        self.codegen_atomic_ordering(intrinsic, block, loc)
+    }
+
+    /// With `-Z weak-memory`, surround the atomic operation `body` with the memory fences that
+    /// implement the ordering in the name of `intrinsic`. For example, an acquire load is followed
+    /// by a lightweight fence, and a release store is preceded by one. CBMC's weak memory
+    /// instrumentation then only reorders the accesses that the ordering allows. Without
+    /// `-Z weak-memory`, every atomic operation is sequentially consistent and `body` is returned
+    /// unchanged.
+    ///
+    /// Fence intrinsics are replaced by the corresponding fence, while compiler fences
+    /// (`atomic_singlethreadfence_*`) have no effect on the memory model.
+    fn codegen_atomic_ordering(&mut self, intrinsic: &str, body: Stmt, loc: Location) -> Stmt {
+        if !self.queries.args().weak_memory || intrinsic.starts_with("atomic_singlethreadfence") {
+            return body;
+        }
+        // The first ordering is the one of the operation. The second ordering of a
+        // compare-exchange, which applies to the failure case, can't be stronger.
+        let ordering = intrinsic
+            .split('_')
+            .find(|part| {
+                ["relaxed", "unordered", "acquire", "release", "acqrel", "seqcst"].contains(part)
+            })
+            .unwrap_or("seqcst");
+        if intrinsic.starts_with("atomic_fence") {
+            let fence = if ordering == "seqcst" { "fence" } else { "lwfence" };
+            return self.codegen_fence(fence, loc);
+        }
+        let (before, after) = match ordering {
+            "relaxed" | "unordered" => (None, None),
+            "acquire" => (None, Some("lwfence")),
+            "release" => (Some("lwfence"), None),
+            "acqrel" => (Some("lwfence"), Some("lwfence")),
+            _ => (Some("fence"), Some("fence")),
+        };
+        let mut stmts = vec![];
+        stmts.extend(before.map(|fence| self.codegen_fence(fence, loc)));
+        stmts.push(body);
+        stmts.extend(after.map(|fence| self.codegen_fence(fence, loc)));
+        Stmt::block(stmts, loc)
+    }
+
+    /// Call an empty function whose base name is `fence` (full fence) or `lwfence`
+    /// (lightweight fence), which the weak memory instrumentation of goto-instrument recognizes.
+    fn codegen_fence(&mut self, fence: &str, loc: Location) -> Stmt {
+        let name = format!("__KANI_{fence}");
+        let typ = Type::code(vec![], Type::empty());
+        let sym = self.ensure(&name, |_, name| {
+            let mut sym = Symbol::function(
+                name,
+                typ.clone(),
+                Some(Stmt::block(vec![], loc)),
+                name,
+                Location::none(),
+            );
+            sym.base_name = Some(fence.into());
+            sym
+        });
+        sym.to_expr().call(vec![]).as_stmt(loc)
     }
 
     /// Copies `count * size_of::<T>()` bytes from `src` to `dst`.
@@ -957,9 +1053,12 @@ fn codegen_atomic_noop(&mut self, intrinsic: &str, loc: Location) -> Stmt {
     ///  * (Exclusive to nonoverlapping copy) The region of memory beginning
     ///      at `src` with a size of `count * size_of::<T>()` bytes must *not*
     ///      overlap with the region of memory beginning at `dst` with the same
-    ///      size.
+    ///      size (done by an `overlapping_copy` check)
     /// In addition, we check that computing `count` in bytes (i.e., the third
     /// argument of the copy built-in call) would not overflow.
+    ///
+    /// Overlapping regions are copied by `memmove` as if through a temporary
+    /// buffer, which is what the non-volatile and volatile `copy` specify.
     pub fn codegen_copy(
         &mut self,
         intrinsic: &str,
@@ -996,8 +1095,21 @@ pub fn codegen_copy(
         let (count_bytes, overflow_check) =
             self.count_in_bytes(count, pointee_type, Type::size_t(), intrinsic, loc);
 
+        let overlap_check = if is_non_overlapping {
+            let no_overlap = self.is_non_overlapping(src.clone(), dst.clone(), count_bytes.clone());
+            Some(self.codegen_assert_assume(
+                no_overlap,
+                PropertyClass::OverlappingCopy,
+                &format!("{intrinsic}: `src` and `dst` regions must not overlap"),
+                loc,
+            ))
+        } else {
+            None
+        };
+
         // Build the call to the copy built-in (`memmove` or `memcpy`)
         let copy_builtin = if is_non_overlapping { BuiltinFn::Memcpy } else { BuiltinFn::Memmove };
+        let uninit_copy = self.codegen_uninit_copy(&dst, &src, count_bytes.clone(), loc);
         let copy_call = copy_builtin.call(vec![dst.clone(), src, count_bytes.clone()], loc);
 
         // The C implementations of `memmove` and `memcpy` do not allow an
@@ -1013,7 +1125,45 @@ pub fn codegen_copy(
         } else {
             copy_if_nontrivial.as_stmt(loc)
         };
-        Stmt::block(vec![src_align_check, dst_align_check, overflow_check, copy_expr], loc)
+        let mut stmts = vec![src_align_check, dst_align_check, overflow_check];
+        stmts.extend(overlap_check);
+        stmts.extend(uninit_copy);
+        stmts.push(copy_expr);
+        Stmt::block(stmts, loc)
+    }
+
+    /// Copies `count * size_of::<T>()` bytes from `src` to `dst` for the
+    /// `volatile_copy_memory` and `volatile_copy_nonoverlapping_memory`
+    /// intrinsics, which take the destination as their first argument.
+    /// <https://doc.rust-lang.org/core/intrinsics/fn.volatile_copy_memory.html>
+    ///
+    /// CBMC doesn't distinguish volatile accesses, so these are modeled like
+    /// their non-volatile counterparts.
+    fn codegen_volatile_copy(
+        &mut self,
+        intrinsic: &str,
+        is_non_overlapping: bool,
+        mut fargs: Vec<Expr>,
+        farg_types: &[Ty],
+        loc: Location,
+    ) -> Stmt {
+        fargs.swap(0, 1);
+        let farg_types = [farg_types[1], farg_types[0]];
+        self.codegen_copy(intrinsic, is_non_overlapping, fargs, &farg_types, None, loc)
+    }
+
+    /// Generates an expression that checks that the regions of `size` bytes
+    /// starting at `src` and `dst` don't overlap, i.e., that the pointers
+    /// point to different objects or that their offsets are at least `size`
+    /// bytes apart.
+    fn is_non_overlapping(&self, src: Expr, dst: Expr, size: Expr) -> Expr {
+        let src_offset = src.clone().pointer_offset().cast_to(Type::size_t());
+        let dst_offset = dst.clone().pointer_offset().cast_to(Type::size_t());
+        let distance = src_offset
+            .clone()
+            .ge(dst_offset.clone())
+            .ternary(src_offset.clone().sub(dst_offset.clone()), dst_offset.sub(src_offset));
+        src.same_object(dst).not().or(distance.ge(size))
     }
 
     /// This is an intrinsic that was added in
@@ -1354,7 +1504,7 @@ fn simd_shuffle_length(&mut self, stripped: &str, farg_types: &[Ty], span: Span)
     /// This function computes the size and alignment of a dynamically-sized type.
     /// The implementations follows closely the SSA implementation found in
     /// `rustc_codegen_ssa::glue::size_and_align_of_dst`.
-    fn size_and_align_of_dst(&mut self, ty: Ty, arg: Expr) -> SizeAlign {
+    pub fn size_and_align_of_dst(&mut self, ty: Ty, arg: Expr) -> SizeAlign {
         let layout = self.layout_of_stable(ty);
         let usizet = Type::size_t();
         if !layout.is_unsized() {
@@ -1743,6 +1893,219 @@ fn codegen_simd_shift_with_distance_check(
         }
     }
 
+    /// `simd_select(mask, if_true, if_false)` selects each element from `if_true` if the
+    /// corresponding mask element is `!0`, and from `if_false` if it's `0`.
+    ///
+    /// We check that the mask has the same length as the input vectors.
+    fn codegen_simd_select(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place,
+        rust_arg_types: &[Ty],
+        span: Span,
+    ) -> Stmt {
+        let mask = fargs.remove(0);
+        let if_true = fargs.remove(0);
+        let if_false = fargs.remove(0);
+        let len = if_true.typ().len().unwrap();
+        if mask.typ().len().unwrap() != len {
+            let err_msg = format!(
+                "expected mask with length {len} (same as input type `{}`), found `{}` with \
+                length {}",
+                self.pretty_ty(rust_arg_types[1]),
+                self.pretty_ty(rust_arg_types[0]),
+                mask.typ().len().unwrap()
+            );
+            utils::span_err(self.tcx, span, err_msg);
+        }
+        self.tcx.dcx().abort_if_errors();
+
+        let elems = (0..len)
+            .map(|i| {
+                let cond = simd_lane(&mask, i).is_zero().not();
+                cond.ternary(simd_lane(&if_true, i), simd_lane(&if_false, i))
+            })
+            .collect();
+        let loc = self.codegen_span_stable(span);
+        self.codegen_expr_to_place_stable(p, Expr::vector_expr(if_true.typ().clone(), elems), loc)
+    }
+
+    /// `simd_select_bitmask(mask, if_true, if_false)` is like `simd_select`, but the mask is
+    /// either an unsigned integer or an array of `u8`, where bit `i` selects element `i`.
+    /// The bit order matches `simd_bitmask`, i.e., element `0` is the least significant bit of
+    /// the first byte on little endian targets.
+    fn codegen_simd_select_bitmask(
+        &mut self,
+        mut fargs: Vec<Expr>,
+        p: &Place,
+        rust_arg_types: &[Ty],
+        loc: Location,
+    ) -> Stmt {
+        let mask = fargs.remove(0);
+        let if_true = fargs.remove(0);
+        let if_false = fargs.remove(0);
+        let len = if_true.typ().len().unwrap();
+        let is_big_endian = self.symbol_table.machine_model().is_big_endian;
+        let mask_is_array = matches!(rust_arg_types[0].kind(), TyKind::RigidTy(RigidTy::Array(..)));
+        let mask_width =
+            if mask_is_array { 8 } else { mask.typ().sizeof_in_bits(&self.symbol_table) };
+        let elems = (0..len)
+            .map(|i| {
+                let (word, bit_width) = if mask_is_array {
+                    let byte = Expr::int_constant(i / 8, Type::ssize_t());
+                    (self.codegen_idx_array(mask.clone(), byte), 8)
+                } else {
+                    (mask.clone(), mask_width)
+                };
+                let bit =
+                    if is_big_endian { bit_width - 1 - (i % bit_width) } else { i % bit_width };
+                let bit_mask = Expr::int_constant(1, word.typ().clone())
+                    .shl(Expr::int_constant(bit, word.typ().clone()));
+                let cond = word.bitand(bit_mask).is_zero().not();
+                cond.ternary(simd_lane(&if_true, i), simd_lane(&if_false, i))
+            })
+            .collect();
+        self.codegen_expr_to_place_stable(p, Expr::vector_expr(if_true.typ().clone(), elems), loc)
+    }
+
+    /// `simd_gather(values, pointers, mask)` reads each element from the corresponding pointer
+    /// if the mask element is `!0`, and takes it from `values` otherwise. Masked out pointers
+    /// aren't dereferenced, so they don't need to be valid.
+    fn codegen_simd_gather(&mut self, mut fargs: Vec<Expr>, p: &Place, loc: Location) -> Stmt {
+        let values = fargs.remove(0);
+        let pointers = fargs.remove(0);
+        let mask = fargs.remove(0);
+        let elems = (0..values.typ().len().unwrap())
+            .map(|i| {
+                let cond = simd_lane(&mask, i).is_zero().not();
+                cond.ternary(simd_lane(&pointers, i).dereference(), simd_lane(&values, i))
+            })
+            .collect();
+        self.codegen_expr_to_place_stable(p, Expr::vector_expr(values.typ().clone(), elems), loc)
+    }
+
+    /// `simd_scatter(values, pointers, mask)` writes each element of `values` to the
+    /// corresponding pointer if the mask element is `!0`. The writes happen in order, so if two
+    /// pointers alias, the last element wins.
+    fn codegen_simd_scatter(&mut self, mut fargs: Vec<Expr>, loc: Location) -> Stmt {
+        let values = fargs.remove(0);
+        let pointers = fargs.remove(0);
+        let mask = fargs.remove(0);
+        let stmts = (0..values.typ().len().unwrap())
+            .map(|i| {
+                let cond = simd_lane(&mask, i).is_zero().not();
+                let store =
+                    simd_lane(&pointers, i).dereference().assign(simd_lane(&values, i), loc);
+                Stmt::if_then_else(cond, store, None, loc)
+            })
+            .collect();
+        Stmt::block(stmts, loc)
+    }
+
+    /// `simd_masked_load(mask, ptr, values)` reads element `i` from `ptr.wrapping_offset(i)` if
+    /// the mask element is `!0`, and takes it from `values` otherwise.
+    fn codegen_simd_masked_load(&mut self, mut fargs: Vec<Expr>, p: &Place, loc: Location) -> Stmt {
+        let mask = fargs.remove(0);
+        let ptr = fargs.remove(0);
+        let values = fargs.remove(0);
+        let elems = (0..values.typ().len().unwrap())
+            .map(|i| {
+                let cond = simd_lane(&mask, i).is_zero().not();
+                let offset = Expr::int_constant(i, Type::ssize_t());
+                cond.ternary(ptr.clone().index_ptr(offset), simd_lane(&values, i))
+            })
+            .collect();
+        self.codegen_expr_to_place_stable(p, Expr::vector_expr(values.typ().clone(), elems), loc)
+    }
+
+    /// `simd_masked_store(mask, ptr, values)` writes element `i` of `values` to
+    /// `ptr.wrapping_offset(i)` if the mask element is `!0`.
+    fn codegen_simd_masked_store(&mut self, mut fargs: Vec<Expr>, loc: Location) -> Stmt {
+        let mask = fargs.remove(0);
+        let ptr = fargs.remove(0);
+        let values = fargs.remove(0);
+        let stmts = (0..values.typ().len().unwrap())
+            .map(|i| {
+                let cond = simd_lane(&mask, i).is_zero().not();
+                let offset = Expr::int_constant(i, Type::ssize_t());
+                let store = ptr.clone().index_ptr(offset).assign(simd_lane(&values, i), loc);
+                Stmt::if_then_else(cond, store, None, loc)
+            })
+            .collect();
+        Stmt::block(stmts, loc)
+    }
+
+    /// The `simd_reduce_*` intrinsics combine all the elements of a vector into a single value.
+    ///
+    /// The elements are always combined from first to last. This is the required order for the
+    /// `_ordered` versions, which also take an initial accumulator. For the `_unordered` versions
+    /// of floating point reductions, this is only one of the valid orders, so rounding
+    /// differences caused by other orders aren't considered. Integer reductions wrap around on
+    /// overflow.
+    fn codegen_simd_reduce(
+        &mut self,
+        intrinsic: &str,
+        mut fargs: Vec<Expr>,
+        p: &Place,
+        ret_typ: Type,
+        loc: Location,
+    ) -> Stmt {
+        let vector = fargs.remove(0);
+        let lanes = (0..vector.typ().len().unwrap()).map(|i| simd_lane(&vector, i));
+        let reduced = match intrinsic {
+            "simd_reduce_add_ordered" => lanes.fold(fargs.remove(0), Expr::plus),
+            "simd_reduce_mul_ordered" => lanes.fold(fargs.remove(0), Expr::mul),
+            "simd_reduce_add_unordered" => lanes.reduce(Expr::plus).unwrap(),
+            "simd_reduce_mul_unordered" => lanes.reduce(Expr::mul).unwrap(),
+            "simd_reduce_and" => lanes.reduce(Expr::bitand).unwrap(),
+            "simd_reduce_or" => lanes.reduce(Expr::bitor).unwrap(),
+            "simd_reduce_xor" => lanes.reduce(Expr::bitxor).unwrap(),
+            "simd_reduce_max" => {
+                lanes.reduce(|acc, lane| acc.clone().ge(lane.clone()).ternary(acc, lane)).unwrap()
+            }
+            "simd_reduce_min" => {
+                lanes.reduce(|acc, lane| acc.clone().le(lane.clone()).ternary(acc, lane)).unwrap()
+            }
+            "simd_reduce_all" => {
+                lanes.map(|lane| lane.is_zero().not()).reduce(Expr::and).unwrap().cast_to(ret_typ)
+            }
+            "simd_reduce_any" => {
+                lanes.map(|lane| lane.is_zero().not()).reduce(Expr::or).unwrap().cast_to(ret_typ)
+            }
+            _ => {
+                return self.codegen_unimplemented_stmt(
+                    &format!("'{intrinsic}' intrinsic"),
+                    loc,
+                    "https://github.com/model-checking/kani/issues/new/choose",
+                );
+            }
+        };
+        self.codegen_expr_to_place_stable(p, reduced, loc)
+    }
+
+    /// Apply the scalar operation `op` to the lanes of the same index of the vectors `fargs`, and
+    /// assign the vector of the results to `p`.
+    ///
+    /// This is used for the intrinsics that CBMC doesn't support on vectors, e.g.,
+    /// `simd_saturating_add`. `simd_neg` wraps around for `-<int>::MIN` like `wrapping_neg`,
+    /// since we don't check scalar negations for overflow.
+    fn codegen_simd_lanewise<F>(
+        &mut self,
+        fargs: Vec<Expr>,
+        p: &Place,
+        loc: Location,
+        op: F,
+    ) -> Stmt
+    where
+        F: Fn(Vec<Expr>) -> Expr,
+    {
+        let typ = fargs[0].typ().clone();
+        let elems = (0..typ.len().unwrap())
+            .map(|i| op(fargs.iter().map(|vector| simd_lane(vector, i)).collect()))
+            .collect();
+        self.codegen_expr_to_place_stable(p, Expr::vector_expr(typ, elems), loc)
+    }
+
     /// `simd_shuffle` constructs a new vector from the elements of two input
     /// vectors, choosing values according to an input array of indexes.
     ///
@@ -1925,8 +2288,12 @@ fn codegen_write_bytes(
             loc,
         );
 
+        let uninit_init = self.codegen_uninit_init_bytes(&dst, count_bytes.clone(), loc);
         let memset_call = BuiltinFn::Memset.call(vec![dst, val, count_bytes], loc);
-        Stmt::block(vec![align_check, overflow_check, memset_call.as_stmt(loc)], loc)
+        let mut stmts = vec![align_check, overflow_check];
+        stmts.extend(uninit_init);
+        stmts.push(memset_call.as_stmt(loc));
+        Stmt::block(stmts, loc)
     }
 
     /// Computes (multiplies) the equivalent of a memory-related number (e.g., an offset) in bytes.
@@ -2025,3 +2392,8 @@ fn instance_args(instance: &Instance) -> GenericArgs {
     };
     args
 }
+
+/// The element `i` of the SIMD vector `vector`.
+pub fn simd_lane(vector: &Expr, i: u64) -> Expr {
+    vector.clone().index_array(Expr::int_constant(i, Type::ssize_t()))
+}
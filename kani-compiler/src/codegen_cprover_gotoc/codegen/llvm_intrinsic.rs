@@ -0,0 +1,136 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! This module implements the LLVM intrinsics that `core::arch` declares as foreign functions.
+//!
+//! Most target specific intrinsics in `core::arch` are implemented in Rust with the portable
+//! `simd_*` intrinsics. The remaining ones are declared as foreign functions whose link name is
+//! an LLVM intrinsic, e.g., `llvm.x86.ssse3.pshuf.b.128`. We generate a body for the ones that
+//! are commonly used by SIMD accelerated parsing and hashing code. Calls to other LLVM
+//! intrinsics are still reported as unsupported foreign functions.
+use crate::codegen_cprover_gotoc::codegen::intrinsic::simd_lane;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use stable_mir::abi::PassMode;
+use stable_mir::mir::mono::Instance;
+use tracing::debug;
+
+/// The reflected polynomial of the CRC-32C (Castagnoli) checksum computed by `crc32`.
+const CRC32C_POLYNOMIAL: u64 = 0x82F6_3B78;
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Generate the body of the foreign function `instance` if it's a supported LLVM intrinsic.
+    pub fn codegen_llvm_intrinsic(&mut self, instance: Instance, loc: Location) -> Option<Stmt> {
+        let fn_name = self.symbol_name_stable(instance);
+        let intrinsic = fn_name.strip_prefix("llvm.")?;
+        let fn_abi = instance.fn_abi().unwrap();
+        let params: Vec<Expr> = fn_abi
+            .args
+            .iter()
+            .enumerate()
+            .filter(|&(_, arg)| (arg.mode != PassMode::Ignore))
+            .map(|(idx, arg)| {
+                Expr::symbol_expression(
+                    format!("{fn_name}::param_{idx}"),
+                    self.codegen_ty_stable(arg.ty),
+                )
+            })
+            .collect();
+        let ret_typ = self.codegen_ty_stable(fn_abi.ret.ty);
+        debug!(?intrinsic, ?params, ?ret_typ, "codegen_llvm_intrinsic");
+
+        let mut parts = intrinsic.split('.');
+        let body = match (parts.next()?, parts.next()?, parts.next()?) {
+            ("x86", "ssse3", "pshuf") | ("x86", "avx2", "pshuf") => {
+                let result = byte_shuffle(&params[1], &ret_typ, |idx, i| {
+                    // Each 128-bit lane is shuffled independently.
+                    let lane_start = Expr::int_constant(i - i % 16, idx.typ().clone());
+                    let in_lane = idx.clone().bitand(Expr::int_constant(0x0F, idx.typ().clone()));
+                    let is_zeroed = idx.clone().bitand(Expr::int_constant(0x80, idx.typ().clone()));
+                    (is_zeroed.is_zero(), params[0].clone().index_array(in_lane.bitor(lane_start)))
+                })?;
+                vec![Stmt::ret(Some(result), loc)]
+            }
+            ("aarch64", "neon", "tbl1") => {
+                let table_len = params[0].typ().len()?;
+                let result = byte_shuffle(&params[1], &ret_typ, |idx, _| {
+                    let in_bounds =
+                        idx.clone().lt(Expr::int_constant(table_len, idx.typ().clone()));
+                    (in_bounds, params[0].clone().index_array(idx))
+                })?;
+                vec![Stmt::ret(Some(result), loc)]
+            }
+            ("aarch64", "neon", reduction @ ("umaxv" | "uminv" | "smaxv" | "sminv")) => {
+                let vector = &params[0];
+                let width = vector.typ().base_type()?.width()?;
+                let lane_typ = if reduction.starts_with('u') {
+                    Type::unsigned_int(width)
+                } else {
+                    Type::signed_int(width)
+                };
+                let is_max = reduction.ends_with("maxv");
+                let result = (0..vector.typ().len()?)
+                    .map(|i| simd_lane(vector, i).cast_to(lane_typ.clone()))
+                    .reduce(|acc, lane| {
+                        let keep_acc = if is_max {
+                            acc.clone().ge(lane.clone())
+                        } else {
+                            acc.clone().le(lane.clone())
+                        };
+                        keep_acc.ternary(acc, lane)
+                    })?;
+                vec![Stmt::ret(Some(result.cast_to(ret_typ)), loc)]
+            }
+            ("x86", "sse42", "crc32") => {
+                // CRC-32C of the bytes of the value, one bit at a time. The intermediate
+                // values are stored in the parameter to keep the expressions small.
+                let crc = params[0].clone();
+                let value = &params[1];
+                let typ = crc.typ().clone();
+                let low_bits = Expr::int_constant(u32::MAX, typ.clone());
+                let mut stmts = vec![crc.clone().assign(
+                    crc.clone().bitand(low_bits).bitxor(value.clone().cast_to(typ.clone())),
+                    loc,
+                )];
+                for _ in 0..value.typ().width()? {
+                    let one = Expr::int_constant(1, typ.clone());
+                    let feedback = crc.clone().bitand(one.clone()).is_zero().ternary(
+                        Expr::int_constant(0, typ.clone()),
+                        Expr::int_constant(CRC32C_POLYNOMIAL, typ.clone()),
+                    );
+                    stmts.push(crc.clone().assign(crc.clone().lshr(one).bitxor(feedback), loc));
+                }
+                stmts.push(Stmt::ret(Some(crc.cast_to(ret_typ)), loc));
+                stmts
+            }
+            _ => return None,
+        };
+        Some(Stmt::block(body, loc))
+    }
+}
+
+/// Build a vector of bytes of type `ret_typ`, where each byte is selected by the corresponding
+/// byte of `indexes`. The `select` function returns, for an index and its position, whether the
+/// byte is taken from the table, and the table element. Otherwise, the byte is zero.
+///
+/// Returns `None` if the types aren't vectors of bytes with the same length.
+fn byte_shuffle<F: FnMut(Expr, u64) -> (Expr, Expr)>(
+    indexes: &Expr,
+    ret_typ: &Type,
+    mut select: F,
+) -> Option<Expr> {
+    let len = ret_typ.len()?;
+    let elem_typ = ret_typ.base_type()?.clone();
+    if indexes.typ().len()? != len || elem_typ.width()? != 8 {
+        return None;
+    }
+    let elems = (0..len)
+        .map(|i| {
+            // Indexes are unsigned, even if the intrinsic is declared with signed bytes.
+            let idx = simd_lane(indexes, i).cast_to(Type::unsigned_int(8));
+            let (is_selected, value) = select(idx, i);
+            is_selected
+                .ternary(value.cast_to(elem_typ.clone()), Expr::int_constant(0, elem_typ.clone()))
+        })
+        .collect();
+    Some(Expr::vector_expr(ret_typ.clone(), elems))
+}
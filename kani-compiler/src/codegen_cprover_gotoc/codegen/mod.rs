@@ -4,17 +4,24 @@
 //! This module does that actual translation of MIR constructs to goto constructs.
 //! Each subfile is named for the MIR construct it translates.
 
+mod alignment;
 mod assert;
 mod block;
+mod fn_ptr;
 mod foreign_function;
 mod function;
+mod inline_asm;
 mod intrinsic;
+mod llvm_intrinsic;
 mod operand;
 mod place;
+mod provenance;
 mod rvalue;
 mod span;
 mod statement;
 mod static_var;
+mod uninit;
+mod unwind;
 
 // Visible for all codegen module.
 pub mod contract;
@@ -2,14 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 use crate::codegen_cprover_gotoc::utils::slice_fat_ptr;
 use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::is_nested_static;
 use crate::unwrap_or_return_codegen_unimplemented;
-use cbmc::goto_program::{DatatypeComponent, Expr, ExprValue, Location, Stmt, Symbol, Type};
+use cbmc::goto_program::{
+    DatatypeComponent, Expr, ExprValue, Location, Stmt, Symbol, SymbolValues, Type,
+};
 use rustc_middle::ty::Const as ConstInternal;
 use rustc_smir::rustc_internal;
 use rustc_span::Span as SpanInternal;
 use stable_mir::mir::alloc::{AllocId, GlobalAlloc};
 use stable_mir::mir::mono::{Instance, StaticDef};
-use stable_mir::mir::Operand;
+use stable_mir::mir::{Mutability, Operand};
 use stable_mir::ty::{
     Allocation, Const, ConstantKind, FloatTy, FnDef, GenericArgs, IntTy, RigidTy, Size, Span, Ty,
     TyKind, UintTy,
@@ -334,6 +337,7 @@ fn codegen_alloc_pointer(
         span: Option<Span>,
     ) -> Expr {
         debug!(?res_t, ?alloc_id, "codegen_alloc_pointer");
+        let alloc_id = self.intern_alloc_id(alloc_id);
         let base_addr = match GlobalAlloc::from(alloc_id) {
             GlobalAlloc::Function(instance) => {
                 // We want to return the function pointer (not to be confused with function item)
@@ -383,6 +387,19 @@ fn codegen_static_pointer(&mut self, def: StaticDef) -> Expr {
         self.codegen_instance_pointer(Instance::from(def), false)
     }
 
+    /// Generate the goto type of a static variable.
+    ///
+    /// Nested statics don't have a type, so like rustc, we use the type of their initializer.
+    pub fn codegen_static_type(&mut self, def: StaticDef) -> Type {
+        if is_nested_static(self.tcx, def) {
+            let alloc = def.eval_initializer().unwrap();
+            let alloc_data = self.codegen_allocation_data(&alloc);
+            self.codegen_alloc_struct_type(&alloc_data, &Instance::from(def).mangled_name())
+        } else {
+            self.codegen_ty_stable(def.ty())
+        }
+    }
+
     /// Generate a goto expression for a pointer to a thread-local variable.
     ///
     /// These are not initialized here, see `codegen_static`.
@@ -393,6 +410,7 @@ pub fn codegen_thread_local_pointer(&mut self, def: CrateItem) -> Expr {
 
     /// Generate a goto expression for a pointer to a static or thread-local variable.
     fn codegen_instance_pointer(&mut self, instance: Instance, is_thread_local: bool) -> Expr {
+        let is_new = !self.symbol_table.contains(instance.mangled_name().into());
         let sym = self.ensure(&instance.mangled_name(), |ctx, name| {
             // Rust has a notion of "extern static" variables. These are in an "extern" block,
             // and so aren't initialized in the current codegen unit. For example (from std):
@@ -412,16 +430,25 @@ fn codegen_instance_pointer(&mut self, instance: Instance, is_thread_local: bool
             let is_extern = instance.is_foreign_item();
 
             let span = instance.def.span();
+            let typ = match StaticDef::try_from(instance) {
+                Ok(def) => ctx.codegen_static_type(def),
+                Err(_) => ctx.codegen_ty_stable(instance.ty()),
+            };
             Symbol::static_variable(
                 name.to_string(),
                 name.to_string(),
-                ctx.codegen_ty_stable(instance.ty()),
+                typ,
                 ctx.codegen_span_stable(span),
             )
             .with_is_extern(is_extern)
             .with_is_thread_local(is_thread_local)
+            .with_pretty_name(instance.name())
         });
-        sym.clone().to_expr().address_of()
+        let var = sym.clone().to_expr();
+        if is_new && instance.is_foreign_item() && !is_thread_local {
+            self.codegen_foreign_static_init(instance, var.clone());
+        }
+        var.address_of()
     }
 
     /// Generate an expression that represents the address for a constant allocation.
@@ -433,19 +460,58 @@ fn codegen_instance_pointer(&mut self, instance: Instance, is_thread_local: bool
     /// used scattered throughout the source
     fn codegen_const_allocation(&mut self, alloc: &Allocation, name: Option<String>) -> Expr {
         debug!(?name, "codegen_const_allocation");
-        let alloc_name = match self.alloc_map.get(alloc) {
-            None => {
+        let alloc = self.intern_allocation(alloc);
+        // Only immutable allocations with the same content can share their memory.
+        let alloc_name = match self.alloc_map.get(&alloc) {
+            Some(name) if alloc.mutability == Mutability::Not => name.clone(),
+            _ => {
                 let alloc_name = if let Some(name) = name { name } else { self.next_global_name() };
-                self.codegen_alloc_in_memory(alloc.clone(), alloc_name.clone());
+                if !self.symbol_table.contains(alloc_name.clone().into()) {
+                    self.codegen_alloc_in_memory(alloc, alloc_name.clone());
+                }
                 alloc_name
             }
-            Some(name) => name.clone(),
         };
 
         let mem_place = self.symbol_table.lookup(alloc_name).unwrap().to_expr();
         mem_place.address_of()
     }
 
+    /// The allocation with its pointers to constant memory replaced by pointers to the first
+    /// allocation with the same content.
+    ///
+    /// Rustc may store copies of the same constant in different allocations, e.g., the file name
+    /// of the panic locations of a function, so the constants that point to them, like the panic
+    /// locations, would otherwise differ and be generated once per copy.
+    fn intern_allocation(&mut self, alloc: &Allocation) -> Allocation {
+        let mut alloc = alloc.clone();
+        for (_, prov) in alloc.provenance.ptrs.iter_mut() {
+            prov.0 = self.intern_alloc_id(prov.0);
+        }
+        alloc
+    }
+
+    /// The id of the first immutable memory allocation with the same content as `alloc_id`, which
+    /// is used to generate all of them. Mutable allocations are never shared, since writing to one
+    /// of them must not change the others.
+    fn intern_alloc_id(&mut self, alloc_id: AllocId) -> AllocId {
+        if let Some(interned) = self.interned_alloc_ids.get(&alloc_id) {
+            return *interned;
+        }
+        let interned = match GlobalAlloc::from(alloc_id) {
+            GlobalAlloc::Memory(alloc) if alloc.mutability == Mutability::Not => {
+                let alloc = self.intern_allocation(&alloc);
+                *self.alloc_ids.entry(alloc).or_insert(alloc_id)
+            }
+            GlobalAlloc::Memory(_)
+            | GlobalAlloc::Function(_)
+            | GlobalAlloc::Static(_)
+            | GlobalAlloc::VTable(..) => alloc_id,
+        };
+        self.interned_alloc_ids.insert(alloc_id, interned);
+        interned
+    }
+
     /// Insert an allocation into the goto symbol table, and generate a goto function that will
     /// initialize it.
     ///
@@ -453,7 +519,6 @@ fn codegen_const_allocation(&mut self, alloc: &Allocation, name: Option<String>)
     /// in our goto binaries.
     pub fn codegen_alloc_in_memory(&mut self, alloc: Allocation, name: String) {
         debug!(?alloc, ?name, "codegen_alloc_in_memory");
-        let struct_name = &format!("{name}::struct");
 
         // The declaration of a static variable may have one type and the constant initializer for
         // a static variable may have a different type. This is because Rust uses bit patterns for
@@ -461,19 +526,7 @@ pub fn codegen_alloc_in_memory(&mut self, alloc: Allocation, name: String) {
         // CBool and the initializer will be a single byte (a one-character array) representing the
         // bit pattern for the boolean value.
         let alloc_data = self.codegen_allocation_data(&alloc);
-        let alloc_typ_ref = self.ensure_struct(struct_name, struct_name, |_, _| {
-            alloc_data
-                .iter()
-                .enumerate()
-                .map(|(i, d)| match d {
-                    AllocData::Bytes(bytes) => DatatypeComponent::field(
-                        i.to_string(),
-                        Type::unsigned_int(8).array_of(bytes.len()),
-                    ),
-                    AllocData::Expr(e) => DatatypeComponent::field(i.to_string(), e.typ().clone()),
-                })
-                .collect()
-        });
+        let alloc_typ_ref = self.codegen_alloc_struct_type(&alloc_data, &name);
 
         // The global static variable may not be in the symbol table if we are dealing
         // with a literal that can be statically allocated.
@@ -509,20 +562,51 @@ pub fn codegen_alloc_in_memory(&mut self, alloc: Allocation, name: String) {
                 .collect(),
             &self.symbol_table,
         );
-        let fn_name = Self::initializer_fn_name(&name);
-        let temp_var = self.gen_function_local_variable(0, &fn_name, alloc_typ_ref).to_expr();
-        let body = Stmt::block(
-            vec![
-                Stmt::decl(temp_var.clone(), Some(val), Location::none()),
-                var.assign(temp_var.transmute_to(var_typ, &self.symbol_table), Location::none()),
-            ],
-            Location::none(),
-        );
-        self.register_initializer(&name, body);
+        let symbol = self.symbol_table.lookup(&name).unwrap();
+        if symbol.is_thread_local {
+            // CBMC initializes the copy of a thread-local variable of each thread with the value of
+            // its symbol, so it can't be initialized by a function.
+            let mut symbol = symbol.clone();
+            symbol.value = SymbolValues::Expr(val.transmute_to(var_typ, &self.symbol_table));
+            self.symbol_table.replace(|old| old.is_some(), symbol);
+        } else {
+            let fn_name = Self::initializer_fn_name(&name);
+            let temp_var = self.gen_function_local_variable(0, &fn_name, alloc_typ_ref).to_expr();
+            let body = Stmt::block(
+                vec![
+                    Stmt::decl(temp_var.clone(), Some(val), Location::none()),
+                    var.assign(
+                        temp_var.transmute_to(var_typ, &self.symbol_table),
+                        Location::none(),
+                    ),
+                ],
+                Location::none(),
+            );
+            self.register_initializer(&name, body);
+        }
 
         self.alloc_map.insert(alloc, name);
     }
 
+    /// Generate the struct type of the global variable that stores an allocation, with one field
+    /// for each sequence of bytes and each pointer of the allocation.
+    fn codegen_alloc_struct_type(&mut self, alloc_data: &[AllocData], name: &str) -> Type {
+        let struct_name = &format!("{name}::struct");
+        self.ensure_struct(struct_name, struct_name, |_, _| {
+            alloc_data
+                .iter()
+                .enumerate()
+                .map(|(i, d)| match d {
+                    AllocData::Bytes(bytes) => DatatypeComponent::field(
+                        i.to_string(),
+                        Type::unsigned_int(8).array_of(bytes.len()),
+                    ),
+                    AllocData::Expr(e) => DatatypeComponent::field(i.to_string(), e.typ().clone()),
+                })
+                .collect()
+        })
+    }
+
     /// This is an internal helper function for `codegen_alloc_in_memory`.
     ///
     /// We codegen global statics as their own unique struct types, and this creates a field-by-field
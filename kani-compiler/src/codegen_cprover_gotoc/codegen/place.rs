@@ -398,6 +398,54 @@ pub fn codegen_local(&mut self, l: Local) -> Expr {
         Expr::symbol_expression(vname, self.codegen_ty_stable(local_ty))
     }
 
+    /// Codegen the projection to the unsized tail of a struct when the tail has a trait object.
+    ///
+    /// The offset of such field depends on the alignment of the concrete type of the trait object,
+    /// which is only known at runtime. The goto struct places the field at the offset that rustc
+    /// computes for an alignment of 1, so we compute the offset from the vtable instead, following
+    /// `rustc_codegen_ssa::mir::place::PlaceRef::project_field`.
+    ///
+    /// Returns `None` if the field isn't a trait object tail or if its offset doesn't depend on the
+    /// alignment.
+    fn codegen_dyn_tail_field(
+        &mut self,
+        before: &ProjectedPlace,
+        field_idx: FieldIdx,
+        field_ty: Ty,
+    ) -> Option<Expr> {
+        let TypeOrVariant::Type(parent_ty) = before.mir_typ_or_variant else { return None };
+        let TyKind::RigidTy(RigidTy::Adt(def, _)) = parent_ty.kind() else { return None };
+        let field_ty_internal = rustc_internal::internal(self.tcx, field_ty);
+        if !self.is_unsized(field_ty_internal) || !self.use_vtable_fat_pointer(field_ty_internal) {
+            return None;
+        }
+        let offset = self.layout_of_stable(parent_ty).fields.offset(field_idx).bytes();
+        if offset == 0 {
+            return None;
+        }
+        let fat_ptr = before.fat_ptr_goto_expr.clone()?;
+        let mut align = self.size_and_align_of_dst(field_ty, fat_ptr).align;
+        if let Some(pack) = rustc_internal::internal(self.tcx, def).repr().pack {
+            let pack = Expr::int_constant(pack.bytes(), Type::size_t());
+            align = align.clone().le(pack.clone()).ternary(align, pack);
+        }
+        // Round the offset up to the alignment, which is a power of two: (offset + align - 1) & -align
+        let offset = Expr::int_constant(offset, Type::size_t())
+            .plus(align.clone().sub(Expr::int_constant(1, Type::size_t())))
+            .bitand(align.neg());
+        let field_typ = self.codegen_ty_stable(field_ty);
+        Some(
+            before
+                .goto_expr
+                .clone()
+                .address_of()
+                .cast_to(Type::unsigned_int(8).to_pointer())
+                .plus(offset)
+                .cast_to(field_typ.to_pointer())
+                .dereference(),
+        )
+    }
+
     /// A projection is an operation that translates an lvalue to another lvalue.
     /// E.g. dereference, follow a field, etc.
     /// This function codegens a single step of a projection.
@@ -487,8 +535,11 @@ fn codegen_projection(
             }
             ProjectionElem::Field(idx, ty) => {
                 let typ = TypeOrVariant::Type(*ty);
-                let expr =
-                    self.codegen_field(before.goto_expr, before.mir_typ_or_variant, *idx, typ)?;
+                let expr = if let Some(expr) = self.codegen_dyn_tail_field(&before, *idx, *ty) {
+                    expr
+                } else {
+                    self.codegen_field(before.goto_expr, before.mir_typ_or_variant, *idx, typ)?
+                };
                 ProjectedPlace::try_new(
                     expr,
                     typ,
@@ -0,0 +1,120 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the provenance checks enabled with `-Z provenance-checks`.
+//!
+//! These checks report the following operations, even if the resulting pointer is never
+//! dereferenced:
+//!  - Pointer arithmetic with `offset`, `add` or `sub` whose result is outside the bounds of the
+//!    allocation of the original pointer, which is UB.
+//!  - Integer-to-pointer casts that create a pointer into an allocation whose address was never
+//!    exposed, e.g., an address obtained with `ptr.addr()`, and transmutes of integers into
+//!    pointers into an allocation. The resulting pointer doesn't have the provenance of the
+//!    allocation, so any access through it is UB.
+//!
+//! Exposed allocations are tracked with the object of the goto model. Instead of tracking every
+//! allocation, we select a nondeterministic object `__KANI_provenance_tracked_object`, and track
+//! whether its address was exposed in `__KANI_provenance_exposed`. Since the tracked object is
+//! nondeterministic, a violation is found for every allocation.
+
+use crate::args::ExtraChecks;
+use crate::codegen_cprover_gotoc::codegen::PropertyClass;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+
+const TRACKED_OBJECT: &str = "__KANI_provenance_tracked_object";
+const EXPOSED: &str = "__KANI_provenance_exposed";
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z provenance-checks` is enabled.
+    pub fn is_provenance_check_enabled(&self) -> bool {
+        self.queries.args().ub_check.contains(&ExtraChecks::Provenance)
+    }
+
+    /// Whether `ptr` points to the tracked object. The null object is never tracked, since it's
+    /// the object of the pointers created from small integers.
+    fn is_tracked_object(&mut self, ptr: Expr) -> Expr {
+        let loc = Location::none();
+        let tracked =
+            self.ensure_global_var(TRACKED_OBJECT, false, Type::size_t(), loc, |_, var| {
+                Some(var.assign(Type::size_t().nondet(), loc))
+            });
+        tracked.clone().is_zero().not().and(ptr.pointer_object().eq(tracked))
+    }
+
+    fn exposed_flag(&mut self) -> Expr {
+        let loc = Location::none();
+        self.ensure_global_var(EXPOSED, false, Type::bool(), loc, |_, var| {
+            Some(var.assign(Expr::bool_false(), loc))
+        })
+    }
+
+    /// Record that the address of the allocation of `ptr` was exposed.
+    pub fn codegen_expose_provenance(&mut self, ptr: Expr, loc: Location) -> Option<Stmt> {
+        if !self.is_provenance_check_enabled() || !ptr.typ().is_pointer() {
+            return None;
+        }
+        let is_tracked = self.is_tracked_object(ptr);
+        let exposed = self.exposed_flag();
+        Some(Stmt::if_then_else(is_tracked, exposed.assign(Expr::bool_true(), loc), None, loc))
+    }
+
+    /// Check that the pointer `ptr`, which was created from an integer, can have the provenance
+    /// of its allocation. If `from_transmute` is set, the pointer never has any provenance.
+    pub fn codegen_int_to_ptr_check(
+        &mut self,
+        ptr: Expr,
+        from_transmute: bool,
+        loc: Location,
+    ) -> Option<Stmt> {
+        if !self.is_provenance_check_enabled() || !ptr.typ().is_pointer() {
+            return None;
+        }
+        let not_tracked = self.is_tracked_object(ptr).not();
+        let (cond, msg) = if from_transmute {
+            (
+                not_tracked,
+                "transmuting an integer into a pointer loses the provenance of the allocation",
+            )
+        } else {
+            (
+                not_tracked.or(self.exposed_flag()),
+                "integer-to-pointer cast of an address whose provenance was never exposed",
+            )
+        };
+        Some(self.codegen_assert_assume(cond, PropertyClass::SafetyCheck, msg, loc))
+    }
+
+    /// Check that `result`, which was computed by adding `offset_bytes` to a pointer, is inside
+    /// the bounds of the allocation, or one byte past its end. Zero offsets are always allowed.
+    pub fn codegen_offset_in_bounds_check(
+        &mut self,
+        result: Expr,
+        offset_bytes: Expr,
+        loc: Location,
+    ) -> Option<Stmt> {
+        if !self.is_provenance_check_enabled() || !result.typ().is_pointer() {
+            return None;
+        }
+        let offset = result.clone().pointer_offset();
+        let object_size = result.object_size().cast_to(Type::ssize_t());
+        let in_bounds = offset.clone().is_non_negative().and(offset.le(object_size));
+        Some(self.codegen_assert_assume(
+            offset_bytes.is_zero().or(in_bounds),
+            PropertyClass::SafetyCheck,
+            "Undefined Behavior: attempt to compute an offset outside the bounds of the allocation",
+            loc,
+        ))
+    }
+}
+
+/// Execute `stmt`, if any, before evaluating `expr`.
+pub fn with_stmt_before(expr: Expr, stmt: Option<Stmt>, loc: Location) -> Expr {
+    match stmt {
+        Some(stmt) => {
+            let typ = expr.typ().clone();
+            Expr::statement_expression(vec![stmt, expr.as_stmt(loc)], typ)
+        }
+        None => expr,
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::codegen_cprover_gotoc::codegen::place::ProjectedPlace;
+use crate::codegen_cprover_gotoc::codegen::provenance::with_stmt_before;
 use crate::codegen_cprover_gotoc::codegen::ty_stable::pointee_type_stable;
 use crate::codegen_cprover_gotoc::codegen::PropertyClass;
 use crate::codegen_cprover_gotoc::utils::{dynamic_fat_ptr, slice_fat_ptr};
@@ -17,13 +18,14 @@
 use cbmc::MachineModel;
 use cbmc::{btree_string_map, InternString, InternedString};
 use num::bigint::BigInt;
-use rustc_middle::ty::{TyCtxt, VtblEntry};
+use rustc_middle::ty::{self, ExistentialPredicateStableCmpExt, TyCtxt, VtblEntry};
 use rustc_smir::rustc_internal;
 use rustc_target::abi::{FieldsShape, TagEncoding, Variants};
 use stable_mir::abi::{Primitive, Scalar, ValueAbi};
 use stable_mir::mir::mono::Instance;
 use stable_mir::mir::{
-    AggregateKind, BinOp, CastKind, NullOp, Operand, Place, PointerCoercion, Rvalue, UnOp,
+    AggregateKind, BinOp, CastKind, Mutability, NullOp, Operand, Place, PointerCoercion, Rvalue,
+    UnOp,
 };
 use stable_mir::ty::{ClosureKind, Const, IntTy, RigidTy, Size, Ty, TyKind, UintTy, VariantIdx};
 use std::collections::BTreeMap;
@@ -417,7 +419,8 @@ fn codegen_rvalue_binary_op(
                 // https://doc.rust-lang.org/std/primitive.pointer.html#method.offset
                 // These checks may allow a wrapping-around behavior in CBMC:
                 // https://github.com/model-checking/kani/issues/1150
-                let overflow_res = ce1.clone().cast_to(Type::ssize_t()).add_overflow(offset_bytes);
+                let overflow_res =
+                    ce1.clone().cast_to(Type::ssize_t()).add_overflow(offset_bytes.clone());
                 let overflow_check = self.codegen_assert_assume(
                     overflow_res.overflowed.not(),
                     PropertyClass::ArithmeticOverflow,
@@ -425,10 +428,10 @@ fn codegen_rvalue_binary_op(
                     loc,
                 );
                 let res = ce1.clone().plus(ce2);
-                Expr::statement_expression(
-                    vec![bytes_overflow_check, overflow_check, res.as_stmt(loc)],
-                    ce1.typ().clone(),
-                )
+                let mut stmts = vec![bytes_overflow_check, overflow_check];
+                stmts.extend(self.codegen_offset_in_bounds_check(res.clone(), offset_bytes, loc));
+                stmts.push(res.as_stmt(loc));
+                Expr::statement_expression(stmts, ce1.typ().clone())
             }
         }
     }
@@ -681,34 +684,22 @@ fn codegen_rvalue_aggregate(
                 let layout = self.layout_of_stable(res_ty);
                 assert!(layout.ty.is_unsafe_ptr());
                 let data = self.codegen_operand_stable(&operands[0]);
-                match pointee_ty.kind() {
-                    TyKind::RigidTy(RigidTy::Slice(inner_ty)) => {
-                        let pointee_goto_typ = self.codegen_ty_stable(inner_ty);
-                        // cast data to pointer with specified type
-                        let data_cast =
-                            data.cast_to(Type::Pointer { typ: Box::new(pointee_goto_typ) });
-                        let meta = self.codegen_operand_stable(&operands[1]);
+                // The shape of the pointer depends on the metadata of the pointee, not on its
+                // kind, e.g., a pointer to a struct with a slice tail is a slice fat pointer.
+                if self.use_thin_pointer_stable(pointee_ty) {
+                    data.cast_to(typ)
+                } else {
+                    let data_cast =
+                        data.cast_to(typ.lookup_field_type("data", &self.symbol_table).unwrap());
+                    let meta = self.codegen_operand_stable(&operands[1]);
+                    if self.use_vtable_fat_pointer_stable(pointee_ty) {
+                        let vtable_expr = meta
+                            .member("vtable_ptr", &self.symbol_table)
+                            .cast_to(typ.lookup_field_type("vtable", &self.symbol_table).unwrap());
+                        dynamic_fat_ptr(typ, data_cast, vtable_expr, &self.symbol_table)
+                    } else {
                         slice_fat_ptr(typ, data_cast, meta, &self.symbol_table)
                     }
-                    TyKind::RigidTy(RigidTy::Adt(..)) => {
-                        let pointee_goto_typ = self.codegen_ty_stable(pointee_ty);
-                        let data_cast =
-                            data.cast_to(Type::Pointer { typ: Box::new(pointee_goto_typ) });
-                        let meta = self.codegen_operand_stable(&operands[1]);
-                        if meta.typ().sizeof(&self.symbol_table) == 0 {
-                            data_cast
-                        } else {
-                            let vtable_expr =
-                                meta.member("vtable_ptr", &self.symbol_table).cast_to(
-                                    typ.lookup_field_type("vtable", &self.symbol_table).unwrap(),
-                                );
-                            dynamic_fat_ptr(typ, data_cast, vtable_expr, &self.symbol_table)
-                        }
-                    }
-                    _ => {
-                        let pointee_goto_typ = self.codegen_ty_stable(pointee_ty);
-                        data.cast_to(Type::Pointer { typ: Box::new(pointee_goto_typ) })
-                    }
                 }
             }
             AggregateKind::Coroutine(_, _, _) => self.codegen_rvalue_coroutine(&operands, res_ty),
@@ -731,12 +722,20 @@ pub fn codegen_rvalue_stable(&mut self, rv: &Rvalue, loc: Location) -> Expr {
                 | CastKind::FloatToInt
                 | CastKind::IntToFloat
                 | CastKind::FnPtrToPtr
-                | CastKind::PtrToPtr
-                | CastKind::PointerExposeAddress
-                | CastKind::PointerWithExposedProvenance,
+                | CastKind::PtrToPtr,
                 e,
                 t,
             ) => self.codegen_misc_cast(e, *t),
+            Rvalue::Cast(CastKind::PointerExposeAddress, e, t) => {
+                let ptr = self.codegen_operand_stable(e);
+                let expose = self.codegen_expose_provenance(ptr, loc);
+                with_stmt_before(self.codegen_misc_cast(e, *t), expose, loc)
+            }
+            Rvalue::Cast(CastKind::PointerWithExposedProvenance, e, t) => {
+                let res = self.codegen_misc_cast(e, *t);
+                let check = self.codegen_int_to_ptr_check(res.clone(), false, loc);
+                with_stmt_before(res, check, loc)
+            }
             Rvalue::Cast(CastKind::DynStar, _, _) => {
                 let ty = self.codegen_ty_stable(res_ty);
                 self.codegen_unimplemented_expr(
@@ -751,7 +750,15 @@ pub fn codegen_rvalue_stable(&mut self, rv: &Rvalue, loc: Location) -> Expr {
             }
             Rvalue::Cast(CastKind::Transmute, operand, ty) => {
                 let goto_typ = self.codegen_ty_stable(*ty);
-                self.codegen_operand_stable(operand).transmute_to(goto_typ, &self.symbol_table)
+                let src = self.codegen_operand_stable(operand);
+                let from_int = src.typ().is_integer();
+                let res = src.transmute_to(goto_typ, &self.symbol_table);
+                let check = if from_int {
+                    self.codegen_int_to_ptr_check(res.clone(), true, loc)
+                } else {
+                    None
+                };
+                with_stmt_before(res, check, loc)
             }
             Rvalue::BinaryOp(op, e1, e2) => self.codegen_rvalue_binary_op(res_ty, op, e1, e2, loc),
             Rvalue::CheckedBinaryOp(op, e1, e2) => {
@@ -855,7 +862,7 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty, res_ty: Ty) -> Expr
                     .map_or(index.as_u32() as u128, |discr| discr.val);
                 Expr::int_constant(discr_val, self.codegen_ty_stable(res_ty))
             }
-            Variants::Multiple { tag_encoding, .. } => match tag_encoding {
+            Variants::Multiple { tag_encoding, tag_field, .. } => match tag_encoding {
                 TagEncoding::Direct => {
                     self.codegen_discriminant_field(e, ty).cast_to(self.codegen_ty_stable(res_ty))
                 }
@@ -865,7 +872,7 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty, res_ty: Ty) -> Expr
                     // See also the cranelift backend:
                     // https://github.com/rust-lang/rust/blob/05d22212e89588e7c443cc6b9bc0e4e02fdfbc8d/compiler/rustc_codegen_cranelift/src/discriminant.rs#L116
                     let offset = match &layout.fields {
-                        FieldsShape::Arbitrary { offsets, .. } => offsets[0usize.into()],
+                        FieldsShape::Arbitrary { offsets, .. } => offsets[(*tag_field).into()],
                         _ => unreachable!("niche encoding must have arbitrary fields"),
                     };
 
@@ -881,8 +888,7 @@ pub fn codegen_get_discriminant(&mut self, e: Expr, ty: Ty, res_ty: Ty) -> Expr
                     let discr_mir_ty = self.codegen_enum_discr_typ_stable(ty);
                     let discr_type = self.codegen_ty_stable(discr_mir_ty);
                     let niche_val = self.codegen_get_niche(e, offset.bytes() as usize, discr_type);
-                    let relative_discr =
-                        wrapping_sub(&niche_val, u64::try_from(*niche_start).unwrap());
+                    let relative_discr = wrapping_sub(&niche_val, *niche_start);
                     let relative_max =
                         niche_variants.end().as_u32() - niche_variants.start().as_u32();
                     let is_niche = if relative_max == 0 {
@@ -1236,7 +1242,8 @@ fn codegen_vtable_method_field(&mut self, instance: Instance, ty: Ty, idx: usize
             if self.vtable_ctx.emit_vtable_restrictions {
                 // Add to the possible method names for this trait type
                 self.vtable_ctx.add_possible_method(
-                    self.normalized_trait_name(rustc_internal::internal(self.tcx, ty)).into(),
+                    self.vtable_restriction_trait_name(rustc_internal::internal(self.tcx, ty))
+                        .into(),
                     idx,
                     fn_name.into(),
                 );
@@ -1273,7 +1280,7 @@ fn codegen_vtable_drop_in_place(&mut self, ty: Ty, trait_ty: Ty) -> Expr {
             if self.vtable_ctx.emit_vtable_restrictions {
                 // Add to the possible method names for this trait type
                 self.vtable_ctx.add_possible_method(
-                    self.normalized_trait_name(trait_ty).into(),
+                    self.vtable_restriction_trait_name(trait_ty).into(),
                     VtableCtx::drop_index(),
                     drop_sym_name,
                 );
@@ -1384,9 +1391,9 @@ fn codegen_vtable(&mut self, src_mir_type: Ty, dst_mir_type: Ty) -> Expr {
                         VtblEntry::MetadataSize => Some(vt_size.clone()),
                         VtblEntry::MetadataAlign => Some(vt_align.clone()),
                         VtblEntry::Vacant => None,
-                        // TODO: trait upcasting
-                        // https://github.com/model-checking/kani/issues/358
-                        VtblEntry::TraitVPtr(_trait_ref) => None,
+                        VtblEntry::TraitVPtr(trait_ref) => {
+                            Some(ctx.codegen_vtable_supertrait_ptr(src_mir_type, *trait_ref))
+                        }
                         VtblEntry::Method(instance) => Some(ctx.codegen_vtable_method_field(
                             rustc_internal::stable(instance),
                             trait_type,
@@ -1407,6 +1414,72 @@ fn codegen_vtable(&mut self, src_mir_type: Ty, dst_mir_type: Ty) -> Expr {
         )
     }
 
+    /// Generate the vtable entry that points to the vtable of the supertrait `trait_ref` for
+    /// `src_mir_type`. This entry is used to upcast a `dyn Trait` to `dyn Supertrait`.
+    fn codegen_vtable_supertrait_ptr(
+        &mut self,
+        src_mir_type: Ty,
+        trait_ref: ty::PolyTraitRef<'tcx>,
+    ) -> Expr {
+        let trait_ref = self.tcx.instantiate_bound_regions_with_erased(trait_ref);
+        let trait_predicate = ty::ExistentialPredicate::Trait(
+            ty::ExistentialTraitRef::erase_self_ty(self.tcx, trait_ref),
+        );
+        // The associated types of the supertrait are part of its trait object type, e.g.,
+        // `dyn Iterator<Item = usize>`, so they're resolved for the type that implements it.
+        let projections = self
+            .tcx
+            .associated_items(trait_ref.def_id)
+            .in_definition_order()
+            .filter(|item| item.kind == ty::AssocKind::Type)
+            .map(|item| {
+                let projection = ty::Ty::new_projection(self.tcx, item.def_id, trait_ref.args);
+                let term =
+                    self.tcx.normalize_erasing_regions(ty::ParamEnv::reveal_all(), projection);
+                ty::ExistentialPredicate::Projection(ty::ExistentialProjection {
+                    def_id: item.def_id,
+                    args: self.tcx.mk_args(&trait_ref.args[1..]),
+                    term: term.into(),
+                })
+            });
+        let mut predicates: Vec<_> = [trait_predicate].into_iter().chain(projections).collect();
+        predicates.sort_by(|a, b| a.stable_cmp(self.tcx, b));
+        let predicates: Vec<_> = predicates.into_iter().map(ty::Binder::dummy).collect();
+        let supertrait_ty = rustc_internal::stable(ty::Ty::new_dynamic(
+            self.tcx,
+            self.tcx.mk_poly_existential_predicates(&predicates),
+            self.tcx.lifetimes.re_erased,
+            ty::Dyn,
+        ));
+        // Ensure that the vtable type of the supertrait is in the symbol table.
+        self.codegen_ty_stable(Ty::new_ptr(supertrait_ty, Mutability::Not));
+        self.codegen_vtable(src_mir_type, supertrait_ty).address_of().cast_to(Type::void_pointer())
+    }
+
+    /// Generate the vtable of an upcast from `src_vtable`, which is a pointer to the vtable of
+    /// the trait object type `src_ty`, to the trait object type `dst_ty`.
+    ///
+    /// If the principal trait of `dst_ty` is a prefix of the vtable of `src_ty`, e.g., it's the
+    /// first supertrait or it's the same trait, the vtable can be reused. Otherwise, the vtable of
+    /// `src_ty` stores a pointer to the vtable of the supertrait.
+    fn codegen_upcast_vtable(&mut self, src_vtable: Expr, src_ty: Ty, dst_ty: Ty) -> Expr {
+        let src_ty = rustc_internal::internal(self.tcx, src_ty);
+        let dst_ty = rustc_internal::internal(self.tcx, dst_ty);
+        let (ty::Dynamic(src_data, ..), ty::Dynamic(dst_data, ..)) = (src_ty.kind(), dst_ty.kind())
+        else {
+            unreachable!("Expected trait objects, but found `{src_ty}` and `{dst_ty}`")
+        };
+        if src_data.principal_def_id() == dst_data.principal_def_id() {
+            return src_vtable;
+        }
+        match self.tcx.vtable_trait_upcasting_coercion_new_vptr_slot((src_ty, dst_ty)) {
+            Some(idx) => {
+                src_vtable.dereference().member(self.vtable_field_name(idx), &self.symbol_table)
+            }
+            None => src_vtable,
+        }
+    }
+
     /// Cast a pointer to a fat pointer.
     /// The fat pointer will have two elements:
     ///  1. `data` which will point to the same address as the source object.
@@ -1454,11 +1527,14 @@ fn codegen_cast_to_fat_pointer(
                 let src_data = src_goto_expr.to_owned().member("data", &self.symbol_table);
                 let dst_data = src_data.cast_to(dst_data_type);
 
-                // Retrieve the vtable and cast the vtable type.
+                // Retrieve the vtable, which is a different one for trait upcasting, and cast
+                // the vtable type.
                 let src_vtable = src_goto_expr.member("vtable", &self.symbol_table);
                 let vtable_name = self.vtable_name_stable(metadata_dst_type);
                 let vtable_ty = Type::struct_tag(vtable_name).to_pointer();
-                let dst_vtable = src_vtable.cast_to(vtable_ty);
+                let dst_vtable = self
+                    .codegen_upcast_vtable(src_vtable, metadata_src_type, metadata_dst_type)
+                    .cast_to(vtable_ty);
 
                 // Construct a fat pointer with the same (casted) fields and new type
                 dynamic_fat_ptr(fat_ptr_type, dst_data, dst_vtable, &self.symbol_table)
@@ -1539,7 +1615,7 @@ fn comparison_expr(
 /// Perform a wrapping subtraction of an Expr with a constant "expr - constant"
 /// where "-" is wrapping subtraction, i.e., the result should be interpreted as
 /// an unsigned value (2's complement).
-fn wrapping_sub(expr: &Expr, constant: u64) -> Expr {
+fn wrapping_sub(expr: &Expr, constant: u128) -> Expr {
     let unsigned_expr = if expr.typ().is_pointer() {
         expr.clone()
     } else {
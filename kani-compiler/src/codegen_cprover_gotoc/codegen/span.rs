@@ -3,11 +3,13 @@
 
 //! MIR Span related functions
 
+use crate::args::CoverageMacroLocation;
 use crate::codegen_cprover_gotoc::GotocCtx;
 use cbmc::goto_program::Location;
 use rustc_smir::rustc_internal;
-use rustc_span::Span;
-use stable_mir::ty::Span as SpanStable;
+use rustc_span::hygiene::{ExpnKind, MacroKind};
+use rustc_span::{FileName, Span};
+use stable_mir::ty::{LineInfo, Span as SpanStable};
 
 impl<'tcx> GotocCtx<'tcx> {
     pub fn codegen_span(&self, sp: &Span) -> Location {
@@ -15,7 +17,7 @@ pub fn codegen_span(&self, sp: &Span) -> Location {
     }
 
     pub fn codegen_span_stable(&self, sp: SpanStable) -> Location {
-        let loc = sp.get_lines();
+        let loc = self.doctest_lines(sp).unwrap_or_else(|| sp.get_lines());
         Location::new(
             sp.get_filename().to_string(),
             self.current_fn.as_ref().map(|x| x.readable_name().to_string()),
@@ -42,4 +44,98 @@ pub fn codegen_caller_span(&self, span: &Span) -> Location {
         let topmost = span.ctxt().outer_expn().expansion_cause().unwrap_or(*span);
         self.codegen_span(&topmost)
     }
+
+    /// Get the span where a coverage check should be reported.
+    ///
+    /// Code generated by a macro expansion is reported at the outermost macro call site by
+    /// default, since the expanded code doesn't have meaningful lines in the user code.
+    /// With `--coverage-macro-location=definition`, the code is reported at the body of the
+    /// function-like macro that generated it instead. Code from compiler desugarings, attribute
+    /// and derive macros is still reported at its call site, since it has no definition source.
+    pub fn coverage_span(&self, sp: SpanStable) -> Span {
+        let mut span = rustc_internal::internal(self.tcx, sp);
+        match self.queries.args().coverage_macro_location {
+            CoverageMacroLocation::CallSite => {
+                span.ctxt().outer_expn().expansion_cause().unwrap_or(span)
+            }
+            CoverageMacroLocation::Definition => {
+                while !span.ctxt().is_root() {
+                    let expn_data = span.ctxt().outer_expn_data();
+                    if matches!(expn_data.kind, ExpnKind::Macro(MacroKind::Bang, _))
+                        && !span.is_dummy()
+                    {
+                        break;
+                    }
+                    span = expn_data.call_site;
+                }
+                span
+            }
+        }
+    }
+
+    /// Whether the span starts in a line that contains only punctuation, such as a closing brace.
+    ///
+    /// These lines have no executable code of their own, so coverage checks in them are emitted
+    /// as gap regions, which don't count as uncovered code.
+    pub fn is_gap_line(&self, span: Span) -> bool {
+        if span.is_dummy() {
+            return false;
+        }
+        let Ok(file_and_line) = self.tcx.sess.source_map().lookup_line(span.lo()) else {
+            return false;
+        };
+        let Some(text) = file_and_line.sf.get_line(file_and_line.line) else { return false };
+        let text = text.trim();
+        !text.is_empty()
+            && text
+                .chars()
+                .all(|c| (c.is_ascii_punctuation() && c != '"' && c != '\'') || c.is_whitespace())
+    }
+
+    /// Map the lines and columns of a span inside a doctest to the position of the code in the
+    /// documented source file.
+    ///
+    /// Rustdoc compiles each doc example as a standalone file whose name records the original
+    /// path and the line offset of the example. Lines can be fixed with that offset, but columns
+    /// are shifted by the doc comment prefix (e.g.: `    /// `) that rustdoc strips. To recover
+    /// them, we compare each line of the doctest against the original line, which should end with
+    /// the doctest line.
+    ///
+    /// Returns `None` if the span does not belong to a doctest.
+    fn doctest_lines(&self, sp: SpanStable) -> Option<LineInfo> {
+        let span = rustc_internal::internal(self.tcx, sp);
+        if span.is_dummy() {
+            return None;
+        }
+        let source_map = self.tcx.sess.source_map();
+        let doctest = source_map.lookup_source_file(span.lo());
+        let FileName::DocTest(path, _) = &doctest.name else { return None };
+        // The original file is usually not part of the doctest session, so load it on demand.
+        // The source map caches it for later lookups.
+        let original = source_map
+            .get_source_file(&FileName::from(path.clone()))
+            .or_else(|| source_map.load_file(path).ok());
+
+        let loc = sp.get_lines();
+        let map_position = |line: usize, col: usize| {
+            let original_line = source_map.doctest_offset_line(&doctest.name, line);
+            let col_offset = original
+                .as_ref()
+                .and_then(|original| {
+                    // `get_line` takes a 0-based line index while spans have 1-based lines.
+                    let doctest_text = doctest.get_line(line.checked_sub(1)?)?;
+                    let original_text = original.get_line(original_line.checked_sub(1)?)?;
+                    let doctest_text = doctest_text.trim_end();
+                    let original_text = original_text.trim_end();
+                    original_text
+                        .ends_with(doctest_text)
+                        .then(|| original_text.chars().count() - doctest_text.chars().count())
+                })
+                .unwrap_or(0);
+            (original_line, col + col_offset)
+        };
+        let (start_line, start_col) = map_position(loc.start_line, loc.start_col);
+        let (end_line, end_col) = map_position(loc.end_line, loc.end_col);
+        Some(LineInfo { start_line, start_col, end_line, end_col })
+    }
 }
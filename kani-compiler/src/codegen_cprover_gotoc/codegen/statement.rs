@@ -148,25 +148,7 @@ pub fn codegen_terminator(&mut self, term: &Terminator) -> Stmt {
                 loc,
                 "https://github.com/model-checking/kani/issues/692",
             ),
-            TerminatorKind::Return => {
-                let rty = self.current_fn().instance_stable().fn_abi().unwrap().ret.ty;
-                if rty.kind().is_unit() {
-                    self.codegen_ret_unit(loc)
-                } else {
-                    let place = Place::from(RETURN_LOCAL);
-                    let place_expr = unwrap_or_return_codegen_unimplemented_stmt!(
-                        self,
-                        self.codegen_place_stable(&place)
-                    )
-                    .goto_expr;
-                    assert_eq!(rty, self.place_ty_stable(&place), "Unexpected return type");
-                    if rty.kind().is_bool() {
-                        place_expr.cast_to(Type::c_bool()).ret(loc)
-                    } else {
-                        place_expr.ret(loc)
-                    }
-                }
-            }
+            TerminatorKind::Return => self.codegen_return(loc),
             TerminatorKind::Unreachable => self.codegen_assert_assume_false(
                 PropertyClass::Unreachable,
                 "unreachable code",
@@ -178,6 +160,15 @@ pub fn codegen_terminator(&mut self, term: &Terminator) -> Stmt {
             TerminatorKind::Call { func, args, destination, target, .. } => {
                 self.codegen_funcall(func, args, destination, target, term.span)
             }
+            TerminatorKind::Assert {
+                msg: AssertMessage::MisalignedPointerDereference { .. },
+                target,
+                ..
+            } if self.is_alignment_check_enabled() => {
+                // The alignment checks added by rustc are subsumed by the ones of
+                // `-Z alignment-checks`, which also cover the creation of references.
+                Stmt::goto(bb_label(*target), loc)
+            }
             TerminatorKind::Assert { cond, expected, msg, target, .. } => {
                 let cond = {
                     let r = self.codegen_operand_stable(cond);
@@ -216,6 +207,11 @@ pub fn codegen_terminator(&mut self, term: &Terminator) -> Stmt {
                     loc,
                 )
             }
+            TerminatorKind::InlineAsm { operands, options, destination, .. }
+                if self.is_inline_asm_enabled() =>
+            {
+                self.codegen_inline_asm(operands, options, *destination, loc)
+            }
             TerminatorKind::InlineAsm { .. } => self.codegen_unimplemented_stmt(
                 "TerminatorKind::InlineAsm",
                 loc,
@@ -239,7 +235,7 @@ pub fn codegen_set_discriminant(
         let layout = self.layout_of(dest_ty_internal);
         match &layout.variants {
             Variants::Single { .. } => Stmt::skip(location),
-            Variants::Multiple { tag, tag_encoding, .. } => match tag_encoding {
+            Variants::Multiple { tag, tag_encoding, tag_field, .. } => match tag_encoding {
                 TagEncoding::Direct => {
                     let discr = dest_ty_internal
                         .discriminant_for_variant(self.tcx, variant_index_internal)
@@ -266,19 +262,28 @@ pub fn codegen_set_discriminant(
                 TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
                     if *untagged_variant != variant_index_internal {
                         let offset = match &layout.fields {
-                            FieldsShape::Arbitrary { offsets, .. } => offsets[0usize.into()],
+                            FieldsShape::Arbitrary { offsets, .. } => offsets[(*tag_field).into()],
                             _ => unreachable!("niche encoding must have arbitrary fields"),
                         };
                         let discr_ty = self.codegen_enum_discr_typ(dest_ty_internal);
                         let discr_ty = self.codegen_ty(discr_ty);
                         let niche_value =
                             variant_index_internal.as_u32() - niche_variants.start().as_u32();
-                        let niche_value = (niche_value as u128).wrapping_add(*niche_start);
+                        // The niche range may wrap around the end of the tag range, so the value
+                        // has to be truncated to the size of the tag.
+                        let tag_size = tag.size(self);
+                        let niche_value =
+                            tag_size.truncate((niche_value as u128).wrapping_add(*niche_start));
                         trace!(val=?niche_value, typ=?discr_ty, "codegen_set_discriminant niche");
                         let value = if niche_value == 0
                             && matches!(tag.primitive(), Primitive::Pointer(_))
                         {
                             discr_ty.null()
+                        } else if matches!(tag.primitive(), Primitive::Int(_, true)) {
+                            Expr::int_constant(
+                                tag_size.sign_extend(niche_value) as i128,
+                                discr_ty.clone(),
+                            )
                         } else {
                             Expr::int_constant(niche_value, discr_ty.clone())
                         };
@@ -460,6 +465,27 @@ fn codegen_untupled_args(&mut self, op: &Operand, args_abi: &[ArgAbi]) -> Vec<Ex
 
     /// Because function calls terminate basic blocks, to "end" a function call, we
     /// must jump to the next basic block.
+    /// Return the value of the return local from the current function.
+    pub fn codegen_return(&mut self, loc: Location) -> Stmt {
+        let rty = self.current_fn().instance_stable().fn_abi().unwrap().ret.ty;
+        if rty.kind().is_unit() {
+            self.codegen_ret_unit(loc)
+        } else {
+            let place = Place::from(RETURN_LOCAL);
+            let place_expr = unwrap_or_return_codegen_unimplemented_stmt!(
+                self,
+                self.codegen_place_stable(&place)
+            )
+            .goto_expr;
+            assert_eq!(rty, self.place_ty_stable(&place), "Unexpected return type");
+            if rty.kind().is_bool() {
+                place_expr.cast_to(Type::c_bool()).ret(loc)
+            } else {
+                place_expr.ret(loc)
+            }
+        }
+    }
+
     fn codegen_end_call(&self, target: Option<BasicBlockIdx>, loc: Location) -> Stmt {
         if let Some(next_bb) = target {
             Stmt::goto(bb_label(next_bb), loc)
@@ -532,9 +558,8 @@ fn codegen_funcall(
             fn_def @ TyKind::RigidTy(RigidTy::FnDef(..)) => {
                 let instance = instance_opt.unwrap();
                 let fn_abi = instance.fn_abi().unwrap();
-                let mut fargs = if args.is_empty()
-                    || fn_def.fn_sig().unwrap().value.abi != Abi::RustCall
-                {
+                let is_rust_call = fn_def.fn_sig().unwrap().value.abi == Abi::RustCall;
+                let mut fargs = if args.is_empty() || !is_rust_call {
                     self.codegen_funcall_args(&fn_abi, &args)
                 } else {
                     let (untupled, first_args) = args.split_last().unwrap();
@@ -546,14 +571,24 @@ fn codegen_funcall(
                 };
 
                 if let Some(hk) = self.hooks.hook_applies(self.tcx, instance) {
-                    return hk.handle(self, instance, fargs, destination, *target, span);
+                    let uninit = self.codegen_uninit_builtin_call(None, args, destination, loc);
+                    let hook = hk.handle(self, instance, fargs, destination, *target, span);
+                    return match uninit {
+                        Some(uninit) => Stmt::block(vec![uninit, hook], loc),
+                        None => hook,
+                    };
                 }
 
-                let mut stmts: Vec<Stmt> = match instance.kind {
-                    // Here an empty drop glue is invoked; we just ignore it.
-                    InstanceKind::Shim if instance.is_empty_shim() => {
-                        return Stmt::goto(bb_label(target.unwrap()), loc);
-                    }
+                // Here an empty drop glue is invoked; we just ignore it.
+                if matches!(instance.kind, InstanceKind::Shim) && instance.is_empty_shim() {
+                    return Stmt::goto(bb_label(target.unwrap()), loc);
+                }
+
+                let is_alloc = instance.is_foreign_item()
+                    && self.symbol_name_stable(instance) == "__rust_alloc";
+                let (mut stmts, uninit_after) =
+                    self.codegen_uninit_call(args, is_rust_call, destination, is_alloc, loc);
+                stmts.extend(match instance.kind {
                     // Handle a virtual function call via a vtable lookup
                     InstanceKind::Virtual { idx } => {
                         let self_ty = self.operand_ty_stable(&args[0]);
@@ -574,12 +609,15 @@ fn codegen_funcall(
                             )]
                         }
                     }
-                };
+                });
+                stmts.extend(uninit_after);
+                stmts.extend(self.codegen_unwinding_check(loc));
                 stmts.push(self.codegen_end_call(*target, loc));
                 Stmt::block(stmts, loc)
             }
             // Function call through a pointer
             TyKind::RigidTy(RigidTy::FnPtr(fn_sig)) => {
+                let is_rust_call = fn_sig.value.abi == Abi::RustCall;
                 let fn_sig_internal = rustc_internal::internal(self.tcx, fn_sig);
                 let fn_ptr_abi = rustc_internal::stable(
                     self.tcx
@@ -589,15 +627,29 @@ fn codegen_funcall(
                         .unwrap(),
                 );
                 let fargs = self.codegen_funcall_args(&fn_ptr_abi, &args);
-                let func_expr = self.codegen_operand_stable(func).dereference();
+                let fn_ptr = self.codegen_operand_stable(func);
+                let (mut stmts, uninit_after) =
+                    self.codegen_uninit_call(args, is_rust_call, destination, false, loc);
                 // Actually generate the function call and return.
-                Stmt::block(
-                    vec![
-                        self.codegen_expr_to_place_stable(destination, func_expr.call(fargs), loc),
-                        Stmt::goto(bb_label(target.unwrap()), loc),
-                    ],
-                    loc,
-                )
+                if let Some(targets) = self.fn_ptr_call_targets(func, fn_sig_internal) {
+                    stmts.push(self.codegen_restricted_fn_ptr_call(
+                        fn_ptr,
+                        &targets,
+                        fargs,
+                        destination,
+                        loc,
+                    ));
+                } else {
+                    stmts.push(self.codegen_expr_to_place_stable(
+                        destination,
+                        fn_ptr.dereference().call(fargs),
+                        loc,
+                    ));
+                }
+                stmts.extend(uninit_after);
+                stmts.extend(self.codegen_unwinding_check(loc));
+                stmts.push(Stmt::goto(bb_label(target.unwrap()), loc));
+                Stmt::block(stmts, loc)
             }
             x => unreachable!("Function call where the function was of unexpected type: {:?}", x),
         }
@@ -4,7 +4,9 @@
 //! This file contains functions related to codegenning MIR static variables into gotoc
 
 use crate::codegen_cprover_gotoc::GotocCtx;
-use cbmc::goto_program::Symbol;
+use crate::kani_middle::transform::{ty_validity, ValidValueReq};
+use cbmc::goto_program::{Expr, Stmt, Symbol, Type};
+use rustc_smir::rustc_internal;
 use stable_mir::mir::mono::{Instance, StaticDef};
 use stable_mir::CrateDef;
 use tracing::debug;
@@ -31,15 +33,68 @@ pub fn declare_static(&mut self, def: StaticDef) {
         let pretty_name = instance.name();
         debug!(?def, ?symbol_name, ?pretty_name, "declare_static");
 
-        let typ = self.codegen_ty_stable(instance.ty());
+        let typ = self.codegen_static_type(def);
         let location = self.codegen_span_stable(def.span());
         // Contracts instrumentation relies on `--nondet-static-exclude` to properly
         // havoc static variables. Kani uses the location and pretty name to identify
         // the correct variables. If the wrong name is used, CBMC may fail silently.
         // More details at https://github.com/diffblue/cbmc/issues/8225.
+        let is_thread_local =
+            self.tcx.is_thread_local_static(rustc_internal::internal(self.tcx, def.def_id()));
         let symbol = Symbol::static_variable(symbol_name.clone(), symbol_name, typ, location)
             .with_is_hidden(false) // Static items are always user defined.
+            .with_is_thread_local(is_thread_local)
             .with_pretty_name(pretty_name);
         self.symbol_table.insert(symbol);
     }
+
+    /// Initialize the foreign static `instance`, which is declared in an `extern` block and
+    /// represented by `var`.
+    ///
+    /// CBMC initializes extern variables without a value with a nondeterministic value, so
+    /// foreign statics can have any value that is valid for their type. We restrict the value
+    /// to the valid ones, e.g., a `bool` can only be `0` or `1`. Users can further restrict it
+    /// in the harness or in the preconditions of a function contract.
+    pub fn codegen_foreign_static_init(&mut self, instance: Instance, var: Expr) {
+        let loc = self.codegen_span_stable(instance.def.span());
+        let reqs = match ty_validity(self.tcx, instance.ty()) {
+            Ok(reqs) => reqs,
+            Err(reason) => {
+                debug!(?instance, ?reason, "codegen_foreign_static_init");
+                return;
+            }
+        };
+        if reqs.is_empty() {
+            return;
+        }
+        let bytes = var.address_of().cast_to(Type::unsigned_int(8).to_pointer());
+        let assumptions = reqs
+            .iter()
+            .map(|req| {
+                let valid = req.conditions.iter().fold(
+                    valid_range_expr(&bytes, req),
+                    |valid, condition| {
+                        // The requirement is satisfied if the condition doesn't hold.
+                        valid_range_expr(&bytes, &condition.negate()).or(valid)
+                    },
+                );
+                Stmt::assume(valid, loc)
+            })
+            .collect();
+        self.register_initializer(&instance.mangled_name(), Stmt::block(assumptions, loc));
+    }
+}
+
+/// Whether the value at the offset of the requirement `req` from the byte pointer `bytes` is in
+/// its valid range, ignoring its conditions.
+fn valid_range_expr(bytes: &Expr, req: &ValidValueReq) -> Expr {
+    let typ = Type::unsigned_int(req.size.bits() as u64);
+    let value = bytes
+        .clone()
+        .plus(Expr::int_constant(req.offset, Type::size_t()))
+        .cast_to(typ.clone().to_pointer())
+        .dereference();
+    let start = value.clone().ge(Expr::int_constant(req.valid_range.start, typ.clone()));
+    let end = value.le(Expr::int_constant(req.valid_range.end, typ));
+    if req.valid_range.wraps_around() { start.or(end) } else { start.and(end) }
 }
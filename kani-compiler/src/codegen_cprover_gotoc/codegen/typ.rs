@@ -4,6 +4,7 @@
 use cbmc::goto_program::{DatatypeComponent, Expr, Location, Parameter, Symbol, SymbolTable, Type};
 use cbmc::utils::aggr_tag;
 use cbmc::{InternString, InternedString};
+use kani_metadata::{AdtField, AdtLayout, AdtMap, AdtVariant};
 use rustc_ast::ast::Mutability;
 use rustc_index::IndexVec;
 use rustc_middle::ty::layout::LayoutOf;
@@ -26,6 +27,28 @@
 use stable_mir::mir::Body;
 use tracing::{debug, trace, warn};
 
+/// The fields of `variant` in declaration order, with the components of the goto struct that
+/// represents it. The tag of a field is the tag of the struct it stores, or of the structs stored
+/// in its elements if it's an array.
+fn adt_fields(variant: &VariantDef, components: &[DatatypeComponent]) -> Vec<AdtField> {
+    fn value_tag(typ: &Type) -> Option<String> {
+        match typ {
+            Type::Array { typ, .. } => value_tag(typ),
+            Type::StructTag(tag) => Some(tag.to_string()),
+            _ => None,
+        }
+    }
+    variant
+        .fields
+        .iter()
+        .map(|field| {
+            let name = field.name.to_string();
+            let component = components.iter().find(|component| component.name() == name);
+            AdtField { tag: component.and_then(|component| value_tag(&component.typ())), name }
+        })
+        .collect()
+}
+
 /// Map the unit type to an empty struct
 ///
 /// Mapping unit to `void` works for functions with no return type but not for variables with type
@@ -130,6 +153,8 @@ fn debug_write_type(
                 Type::Empty => todo!(),
                 Type::FlexibleArray { .. } => todo!(),
                 Type::Float => write!(out, "f32")?,
+                Type::Float16 => write!(out, "f16")?,
+                Type::Float128 => write!(out, "f128")?,
                 Type::IncompleteStruct { .. } => todo!(),
                 Type::IncompleteUnion { .. } => todo!(),
                 Type::InfiniteArray { .. } => todo!(),
@@ -380,9 +405,13 @@ fn trait_vtable_field_types(&mut self, t: ty::Ty<'tcx>) -> Vec<DatatypeComponent
                         VtblEntry::Method(instance) => {
                             Some(self.trait_method_vtable_field_type(instance, idx))
                         }
-                        // TODO: trait upcasting
-                        // https://github.com/model-checking/kani/issues/358
-                        VtblEntry::TraitVPtr(..) => None,
+                        // The vtable of a supertrait, which is used for trait upcasting. Its
+                        // type depends on the auto traits of the target of the upcast, so we
+                        // store it as a void pointer and cast it when upcasting.
+                        VtblEntry::TraitVPtr(..) => Some(DatatypeComponent::field(
+                            self.vtable_field_name(idx),
+                            Type::void_pointer(),
+                        )),
                         VtblEntry::MetadataDropInPlace
                         | VtblEntry::MetadataSize
                         | VtblEntry::MetadataAlign
@@ -405,13 +434,27 @@ pub fn normalized_trait_name(&self, t: Ty<'tcx>) -> String {
         self.ty_mangled_name(t).to_string()
     }
 
+    /// Gives the name of the trait of a dynamic type `dyn T` in the vtable restrictions, which
+    /// ignores its auto traits: the dynamic types that only differ by their auto traits, e.g.,
+    /// `dyn Error` and `dyn Error + Send`, have the same vtable entries, and coercing a trait object
+    /// to a type with fewer auto traits keeps its vtable.
+    pub fn vtable_restriction_trait_name(&self, t: Ty<'tcx>) -> String {
+        let TyKind::Dynamic(predicates, _, kind) = t.kind() else {
+            unreachable!("Type {t} must be a trait type (a dynamic type)")
+        };
+        let predicates: Vec<_> = predicates
+            .iter()
+            .filter(|predicate| {
+                !matches!(predicate.skip_binder(), ty::ExistentialPredicate::AutoTrait(_))
+            })
+            .collect();
+        let predicates = self.tcx.mk_poly_existential_predicates(&predicates);
+        let principal = Ty::new_dynamic(self.tcx, predicates, self.tcx.lifetimes.re_erased, *kind);
+        self.normalized_trait_name(principal)
+    }
+
     /// Gives the vtable name for a type.
     /// In some cases, we have &T, in other cases T, so normalize.
-    ///
-    /// TODO: to handle trait upcasting, this will need to use a
-    /// poly existential trait type as a part of the key as well.
-    /// See compiler/rustc_middle/src/ty/vtable.rs
-    /// <https://github.com/model-checking/kani/issues/358>
     pub fn vtable_name(&self, t: Ty<'tcx>) -> String {
         format!("{}::vtable", self.normalized_trait_name(t))
     }
@@ -540,11 +583,10 @@ fn codegen_ty_inner(&mut self, ty: Ty<'tcx>) -> Type {
             ty::Char => Type::signed_int(32),
             ty::Uint(k) => self.codegen_uint(*k),
             ty::Float(k) => match k {
+                FloatTy::F16 => Type::float16(),
                 FloatTy::F32 => Type::float(),
                 FloatTy::F64 => Type::double(),
-                // `F16` and `F128` are not yet handled.
-                // Tracked here: <https://github.com/model-checking/kani/issues/3069>
-                FloatTy::F16 | FloatTy::F128 => unimplemented!(),
+                FloatTy::F128 => Type::float128(),
             },
             ty::Adt(def, _) if def.repr().simd() => self.codegen_vector(ty),
             ty::Adt(def, subst) => {
@@ -1361,11 +1403,10 @@ pub fn codegen_prim_typ(&self, primitive: Primitive) -> Ty<'tcx> {
 
     pub fn codegen_float_type(&self, f: Float) -> Ty<'tcx> {
         match f {
+            Float::F16 => self.tcx.types.f16,
             Float::F32 => self.tcx.types.f32,
             Float::F64 => self.tcx.types.f64,
-            // `F16` and `F128` are not yet handled.
-            // Tracked here: <https://github.com/model-checking/kani/issues/3069>
-            Float::F16 | Float::F128 => unimplemented!(),
+            Float::F128 => self.tcx.types.f128,
         }
     }
 
@@ -1429,6 +1470,67 @@ fn codegen_enum_case_struct(
         })
     }
 
+    /// The layout of the structs and enums of the type map, which is used to render their values
+    /// in the traces.
+    pub fn adt_map(&self) -> AdtMap {
+        self.type_map
+            .iter()
+            .filter_map(|(tag, ty)| Some((tag.to_string(), self.adt_layout(*tag, *ty)?)))
+            .collect()
+    }
+
+    fn adt_layout(&self, tag: InternedString, ty: Ty<'tcx>) -> Option<AdtLayout> {
+        let ty::Adt(def, _) = ty.kind() else { return None };
+        let components = self.symbol_table.lookup(tag)?.typ.components()?;
+        let layout = self.layout_of(ty);
+        match &layout.variants {
+            _ if def.is_struct() => Some(AdtLayout::Struct {
+                name: self.tcx.item_name(def.did()).to_string(),
+                fields: adt_fields(def.non_enum_variant(), components),
+            }),
+            // An enum with a single variant is represented like a struct.
+            Variants::Single { index } if def.is_enum() => {
+                let variant = def.variants().get(*index)?;
+                Some(AdtLayout::Struct {
+                    name: variant.name.to_string(),
+                    fields: adt_fields(variant, components),
+                })
+            }
+            Variants::Multiple { tag: discr, tag_encoding: TagEncoding::Direct, .. }
+                if def.is_enum() =>
+            {
+                let cases = components
+                    .iter()
+                    .find(|component| component.name() == "cases")?
+                    .typ()
+                    .lookup_components(&self.symbol_table)?;
+                let discr_size = discr.size(self);
+                let variants = def
+                    .variants()
+                    .iter_enumerated()
+                    .map(|(index, variant)| {
+                        let discriminant =
+                            ty.discriminant_for_variant(self.tcx, index).map_or(0, |d| d.val);
+                        // The variants without fields don't have a component in the union.
+                        let fields = cases
+                            .iter()
+                            .find(|case| case.name() == variant.name.as_str())
+                            .and_then(|case| case.typ().lookup_components(&self.symbol_table))
+                            .map_or(vec![], |components| adt_fields(variant, components));
+                        AdtVariant {
+                            name: variant.name.to_string(),
+                            discriminant: discr_size.truncate(discriminant),
+                            fields,
+                        }
+                    })
+                    .collect();
+                Some(AdtLayout::Enum { variants })
+            }
+            _ if def.is_enum() => Some(AdtLayout::Opaque),
+            _ => None,
+        }
+    }
+
     fn codegen_vector(&mut self, ty: Ty<'tcx>) -> Type {
         let layout = &self.layout_of(ty).layout.abi();
         debug! {"handling simd with layout {:?}", layout};
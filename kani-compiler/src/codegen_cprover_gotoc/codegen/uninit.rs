@@ -0,0 +1,439 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the uninitialized memory checks enabled with `-Z uninit-checks`.
+//!
+//! Reads of values that must be initialized, e.g. integers, are checked against the bytes that
+//! are uninitialized. Uninitialized bytes are created by allocations, by locals whose storage
+//! starts, by unions and enum variants that don't cover the whole value, by constants with
+//! uninitialized bytes, and by the padding of a value that is written.
+//!
+//! Instead of keeping a shadow byte per byte of memory, we only track the initialization state of
+//! a single byte: the byte at the nondeterministic offset `__KANI_uninit_tracked_offset` inside
+//! the nondeterministic object `__KANI_uninit_tracked_object`. Since the tracked byte is
+//! nondeterministic, a violation is found for every byte of memory. The state is one global
+//! flag, which is much cheaper than a shadow memory for the solver.
+//!
+//! Values that may hold uninitialized bytes, e.g. a `MaybeUninit<u32>`, are copied byte-wise: if
+//! the tracked byte is part of the copied value, the tracked byte nondeterministically follows
+//! the copy. Arguments and return values use `__KANI_uninit_transit` to do the same across calls.
+//!
+//! The places to check are computed by [crate::kani_middle::uninit], which also skips the reads
+//! that a dataflow analysis found to be initialized.
+
+use crate::args::ExtraChecks;
+use crate::codegen_cprover_gotoc::codegen::ty_stable::pointee_type_stable;
+use crate::codegen_cprover_gotoc::codegen::PropertyClass;
+use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::uninit::{uninit_bytes_written, TypeBytes};
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use stable_mir::mir::{
+    BasicBlockIdx, Body, Operand, Place, Rvalue, Statement, StatementKind, Terminator,
+    TerminatorKind, RETURN_LOCAL,
+};
+use std::ops::Range;
+
+const TRACKED_OBJECT: &str = "__KANI_uninit_tracked_object";
+const TRACKED_OFFSET: &str = "__KANI_uninit_tracked_offset";
+const IS_UNINIT: &str = "__KANI_uninit_state";
+const TRANSIT: &str = "__KANI_uninit_transit";
+const TRANSIT_SLOT: &str = "__KANI_uninit_transit_slot";
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z uninit-checks` is enabled.
+    pub fn is_uninit_check_enabled(&self) -> bool {
+        self.queries.args().ub_check.contains(&ExtraChecks::Uninit)
+    }
+
+    fn uninit_global(&mut self, name: &str, typ: Type, init: Expr) -> Expr {
+        let loc = Location::none();
+        self.ensure_global_var(name, false, typ, loc, |_, var| Some(var.assign(init, loc)))
+    }
+
+    fn tracked_object(&mut self) -> Expr {
+        self.uninit_global(TRACKED_OBJECT, Type::size_t(), Type::size_t().nondet())
+    }
+
+    fn tracked_offset(&mut self) -> Expr {
+        self.uninit_global(TRACKED_OFFSET, Type::ssize_t(), Type::ssize_t().nondet())
+    }
+
+    fn uninit_flag(&mut self) -> Expr {
+        self.uninit_global(IS_UNINIT, Type::bool(), Expr::bool_false())
+    }
+
+    /// The offset of the tracked byte inside the value being passed to or returned from a
+    /// function, or `-1` if the tracked byte isn't in transit.
+    fn transit_offset(&mut self) -> Expr {
+        self.uninit_global(TRANSIT, Type::ssize_t(), Expr::int_constant(-1, Type::ssize_t()))
+    }
+
+    /// The local of the callee that receives the value in transit.
+    fn transit_slot(&mut self) -> Expr {
+        self.uninit_global(TRANSIT_SLOT, Type::size_t(), Expr::int_constant(0, Type::size_t()))
+    }
+
+    /// The offset of the tracked byte relative to `ptr`, which is only meaningful if they are in
+    /// the same object.
+    fn tracked_index(&mut self, ptr: &Expr) -> (Expr, Expr) {
+        let same_object = self.tracked_object().eq(ptr.clone().pointer_object());
+        let index = self.tracked_offset().sub(ptr.clone().pointer_offset());
+        (same_object, index)
+    }
+
+    /// Whether the tracked byte is in the `size` bytes starting at `ptr`.
+    fn is_tracked_in_bytes(&mut self, ptr: &Expr, size: Expr) -> Expr {
+        let (same_object, index) = self.tracked_index(ptr);
+        same_object
+            .and(index.clone().is_non_negative())
+            .and(index.lt(size.cast_to(Type::ssize_t())))
+    }
+
+    /// Whether the tracked byte is in one of the given ranges of bytes starting at `ptr`.
+    fn is_tracked_in(&mut self, ptr: &Expr, ranges: &[Range<usize>]) -> Expr {
+        if ranges.is_empty() {
+            return Expr::bool_false();
+        }
+        let (same_object, index) = self.tracked_index(ptr);
+        let in_ranges = ranges
+            .iter()
+            .map(|range| {
+                let start = Expr::int_constant(range.start, Type::ssize_t());
+                let end = Expr::int_constant(range.end, Type::ssize_t());
+                index.clone().ge(start).and(index.clone().lt(end))
+            })
+            .reduce(Expr::or)
+            .unwrap();
+        same_object.and(in_ranges)
+    }
+
+    fn uninit_place_address(&mut self, place: &Place) -> Option<Expr> {
+        Some(self.codegen_place_stable(place).ok()?.goto_expr.address_of())
+    }
+
+    /// Generate the checks and the updates of the tracked byte before a statement.
+    pub fn codegen_uninit_statement(
+        &mut self,
+        bb: BasicBlockIdx,
+        idx: usize,
+        stmt: &Statement,
+    ) -> Option<Stmt> {
+        let info = self.current_fn().uninit_info()?;
+        let loc = self.codegen_span_stable(stmt.span);
+        let reads = info.statement_reads(self.current_fn().locals(), stmt);
+        let mut stmts = reads
+            .iter()
+            .filter(|place| !info.is_redundant(bb, idx, place))
+            .filter_map(|place| self.codegen_uninit_check(place, loc))
+            .collect::<Vec<_>>();
+        match &stmt.kind {
+            StatementKind::Assign(place, rvalue) if info.is_trackable(place) => {
+                stmts.extend(self.codegen_uninit_write(place, rvalue, loc))
+            }
+            StatementKind::StorageLive(local) if info.is_candidate(*local) => {
+                stmts.extend(self.codegen_uninit_mark(&Place::from(*local), loc))
+            }
+            StatementKind::Deinit(place) if info.is_trackable(place) => {
+                stmts.extend(self.codegen_uninit_mark(place, loc))
+            }
+            StatementKind::SetDiscriminant { place, .. } if info.is_trackable(place) => {
+                stmts.extend(self.codegen_uninit_set_tag(place, loc))
+            }
+            _ => {}
+        }
+        (!stmts.is_empty()).then(|| Stmt::block(stmts, loc))
+    }
+
+    /// Generate the checks before a terminator, and pass the return value to the caller.
+    pub fn codegen_uninit_terminator(
+        &mut self,
+        bb: BasicBlockIdx,
+        idx: usize,
+        term: &Terminator,
+    ) -> Option<Stmt> {
+        let info = self.current_fn().uninit_info()?;
+        let loc = self.codegen_span_stable(term.span);
+        let reads = info.terminator_reads(self.current_fn().locals(), term);
+        let mut stmts = reads
+            .iter()
+            .filter(|place| !info.is_redundant(bb, idx, place))
+            .filter_map(|place| self.codegen_uninit_check(place, loc))
+            .collect::<Vec<_>>();
+        if let TerminatorKind::Return = term.kind {
+            stmts.extend(self.codegen_uninit_send(RETURN_LOCAL, &Place::from(RETURN_LOCAL), loc));
+        }
+        (!stmts.is_empty()).then(|| Stmt::block(stmts, loc))
+    }
+
+    /// Receive the arguments of the current function whose tracked byte is in transit.
+    pub fn codegen_uninit_prelude(&mut self, body: &Body) -> Option<Stmt> {
+        self.current_fn().uninit_info()?;
+        let loc = Location::none();
+        let stmts = body
+            .arg_locals()
+            .iter()
+            .enumerate()
+            .map(|(idx, _)| idx + 1)
+            .filter(|local| Some(*local) != body.spread_arg())
+            .filter_map(|local| self.codegen_uninit_receive(local, &Place::from(local), loc))
+            .collect::<Vec<_>>();
+        (!stmts.is_empty()).then(|| Stmt::block(stmts, loc))
+    }
+
+    /// Generate the statements to execute before and after a function call.
+    ///
+    /// The arguments that may be uninitialized are passed to the callee, except for the spread
+    /// argument of a "rust-call", and the return value is received from the callee.
+    pub fn codegen_uninit_call(
+        &mut self,
+        args: &[Operand],
+        is_rust_call: bool,
+        destination: &Place,
+        is_alloc: bool,
+        loc: Location,
+    ) -> (Vec<Stmt>, Vec<Stmt>) {
+        let Some(info) = self.current_fn().uninit_info() else { return (vec![], vec![]) };
+        let passed_args = if is_rust_call { &args[..args.len().saturating_sub(1)] } else { args };
+        let before = passed_args
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, arg)| match arg {
+                Operand::Copy(place) | Operand::Move(place) => {
+                    self.codegen_uninit_send(idx + 1, place, loc)
+                }
+                Operand::Constant(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let mut after = vec![];
+        if let Some(receive) = self.codegen_uninit_receive(RETURN_LOCAL, destination, loc) {
+            after.push(receive);
+        } else if info.is_trackable(destination) {
+            after.extend(self.codegen_uninit_result(destination, loc));
+        }
+        if !before.is_empty() || !after.is_empty() {
+            // Drop the tracked byte if the callee didn't receive it.
+            let transit = self.transit_offset();
+            after.push(transit.assign(Expr::int_constant(-1, Type::ssize_t()), loc));
+        }
+        if is_alloc && let Some(ptr) = self.codegen_place_stable(destination).ok() {
+            after.extend(self.codegen_uninit_alloc(ptr.goto_expr, loc));
+        }
+        (before, after)
+    }
+
+    /// Update the tracked byte for a call to an intrinsic or to a function replaced by a hook,
+    /// which aren't instrumented: their result is initialized, and so is the memory written by
+    /// the intrinsics that store a value through a pointer.
+    pub fn codegen_uninit_builtin_call(
+        &mut self,
+        intrinsic: Option<&str>,
+        args: &[Operand],
+        destination: &Place,
+        loc: Location,
+    ) -> Option<Stmt> {
+        let info = self.current_fn().uninit_info()?;
+        let mut stmts = vec![];
+        if info.is_trackable(destination) {
+            stmts.extend(self.codegen_uninit_result(destination, loc));
+        }
+        if let Some(name) = intrinsic
+            && ["atomic_store", "atomic_xchg", "atomic_cxchg", "volatile_store"]
+                .iter()
+                .chain(&["unaligned_volatile_store", "nontemporal_store"])
+                .any(|prefix| name.starts_with(prefix))
+            && let Some(dst_ty) = pointee_type_stable(self.operand_ty_stable(&args[0]))
+            && let Some(bytes) = TypeBytes::of(dst_ty)
+        {
+            let dst = self.codegen_operand_stable(&args[0]);
+            let size = Expr::int_constant(bytes.size, Type::size_t());
+            stmts.extend(self.codegen_uninit_init_bytes(&dst, size, loc));
+        }
+        (!stmts.is_empty()).then(|| Stmt::block(stmts, loc))
+    }
+
+    /// The value written to `destination` is initialized, except for its padding.
+    fn codegen_uninit_result(&mut self, destination: &Place, loc: Location) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(destination))?;
+        if bytes.size == 0 {
+            return None;
+        }
+        let dst = self.uninit_place_address(destination)?;
+        Some(self.codegen_uninit_set(&dst, bytes.size, &bytes.padding, loc))
+    }
+
+    /// Mark the memory of a new allocation as uninitialized.
+    pub fn codegen_uninit_alloc(&mut self, ptr: Expr, loc: Location) -> Option<Stmt> {
+        self.current_fn().uninit_info()?;
+        let is_tracked = self.tracked_object().eq(ptr.pointer_object());
+        let flag = self.uninit_flag();
+        Some(Stmt::if_then_else(is_tracked, flag.assign(Expr::bool_true(), loc), None, loc))
+    }
+
+    /// Copy the initialization state of `size` bytes from `src` to `dst`.
+    pub fn codegen_uninit_copy(
+        &mut self,
+        dst: &Expr,
+        src: &Expr,
+        size: Expr,
+        loc: Location,
+    ) -> Option<Stmt> {
+        self.current_fn().uninit_info()?;
+        let in_src = self.is_tracked_in_bytes(src, size.clone());
+        let in_dst = self.is_tracked_in_bytes(dst, size);
+        let (_, src_index) = self.tracked_index(src);
+        let follow = Stmt::block(
+            vec![
+                self.tracked_offset().assign(dst.clone().pointer_offset().plus(src_index), loc),
+                self.tracked_object().assign(dst.clone().pointer_object(), loc),
+            ],
+            loc,
+        );
+        let flag = self.uninit_flag();
+        let init = Stmt::if_then_else(in_dst, flag.assign(Expr::bool_false(), loc), None, loc);
+        let cond = in_src.and(Type::bool().nondet());
+        Some(Stmt::if_then_else(cond, follow, Some(init), loc))
+    }
+
+    /// Mark the `size` bytes starting at `dst` as initialized.
+    pub fn codegen_uninit_init_bytes(
+        &mut self,
+        dst: &Expr,
+        size: Expr,
+        loc: Location,
+    ) -> Option<Stmt> {
+        self.current_fn().uninit_info()?;
+        let in_dst = self.is_tracked_in_bytes(dst, size);
+        let flag = self.uninit_flag();
+        Some(Stmt::if_then_else(in_dst, flag.assign(Expr::bool_false(), loc), None, loc))
+    }
+
+    /// Check that the bytes of `place` that must be initialized are initialized.
+    fn codegen_uninit_check(&mut self, place: &Place, loc: Location) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(place))?;
+        let ptr = self.uninit_place_address(place)?;
+        let is_uninit = self.uninit_flag().and(self.is_tracked_in(&ptr, &bytes.required));
+        Some(self.codegen_assert_assume(
+            is_uninit.not(),
+            PropertyClass::SafetyCheck,
+            "Undefined Behavior: reading uninitialized memory",
+            loc,
+        ))
+    }
+
+    /// Update the state of the tracked byte when `rvalue` is written to `place`.
+    fn codegen_uninit_write(
+        &mut self,
+        place: &Place,
+        rvalue: &Rvalue,
+        loc: Location,
+    ) -> Option<Stmt> {
+        let ty = self.place_ty_stable(place);
+        let bytes = TypeBytes::of(ty)?;
+        if bytes.size == 0 {
+            return None;
+        }
+        let dst = self.uninit_place_address(place)?;
+        match rvalue {
+            Rvalue::Use(Operand::Copy(src) | Operand::Move(src)) if bytes.may_be_uninit => {
+                let src = self.uninit_place_address(src)?;
+                let size = Expr::int_constant(bytes.size, Type::size_t());
+                self.codegen_uninit_copy(&dst, &src, size, loc)
+            }
+            _ => {
+                let uninit = uninit_bytes_written(ty, &bytes, rvalue);
+                Some(self.codegen_uninit_set(&dst, bytes.size, &uninit, loc))
+            }
+        }
+    }
+
+    /// Mark all the bytes of `place` as uninitialized.
+    fn codegen_uninit_mark(&mut self, place: &Place, loc: Location) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(place))?;
+        let ptr = self.uninit_place_address(place)?;
+        let all = 0..bytes.size;
+        Some(self.codegen_uninit_set(&ptr, bytes.size, std::slice::from_ref(&all), loc))
+    }
+
+    /// Mark the tag of the enum in `place` as initialized. The tag is the only required part of
+    /// an enum with multiple variants.
+    fn codegen_uninit_set_tag(&mut self, place: &Place, loc: Location) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(place))?;
+        let ptr = self.uninit_place_address(place)?;
+        let in_tag = self.is_tracked_in(&ptr, &bytes.required);
+        let flag = self.uninit_flag();
+        Some(flag.clone().assign(flag.and(in_tag.not()), loc))
+    }
+
+    /// If the tracked byte is in the `size` bytes starting at `ptr`, update its state according
+    /// to the given uninitialized ranges.
+    fn codegen_uninit_set(
+        &mut self,
+        ptr: &Expr,
+        size: usize,
+        uninit: &[Range<usize>],
+        loc: Location,
+    ) -> Stmt {
+        let in_value = self.is_tracked_in_bytes(ptr, Expr::int_constant(size, Type::size_t()));
+        let flag = self.uninit_flag();
+        let new_flag = if uninit.is_empty() {
+            flag.clone().and(in_value.not())
+        } else if uninit.len() == 1 && uninit[0] == (0..size) {
+            flag.clone().or(in_value)
+        } else {
+            in_value.ternary(self.is_tracked_in(ptr, uninit), flag.clone())
+        };
+        flag.assign(new_flag, loc)
+    }
+
+    /// Nondeterministically pass the tracked byte, if it's in the value of `place`, to the local
+    /// `slot` of the callee or to the caller if `slot` is the return local.
+    fn codegen_uninit_send(&mut self, slot: usize, place: &Place, loc: Location) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(place))?;
+        if !bytes.may_be_uninit || bytes.size == 0 {
+            return None;
+        }
+        let src = self.uninit_place_address(place)?;
+        let size = Expr::int_constant(bytes.size, Type::size_t());
+        let cond = self.is_tracked_in_bytes(&src, size).and(Type::bool().nondet());
+        let (_, index) = self.tracked_index(&src);
+        let send = Stmt::block(
+            vec![
+                self.transit_offset().assign(index, loc),
+                self.transit_slot().assign(Expr::int_constant(slot, Type::size_t()), loc),
+                // The tracked byte isn't in any object until it's received.
+                self.tracked_object().assign(Expr::int_constant(0, Type::size_t()), loc),
+            ],
+            loc,
+        );
+        Some(Stmt::if_then_else(cond, send, None, loc))
+    }
+
+    /// Receive the tracked byte in `place` if it was sent to `slot`.
+    fn codegen_uninit_receive(
+        &mut self,
+        slot: usize,
+        place: &Place,
+        loc: Location,
+    ) -> Option<Stmt> {
+        let bytes = TypeBytes::of(self.place_ty_stable(place))?;
+        if !bytes.may_be_uninit || bytes.size == 0 {
+            return None;
+        }
+        let dst = self.uninit_place_address(place)?;
+        let transit = self.transit_offset();
+        let cond = transit
+            .clone()
+            .is_non_negative()
+            .and(self.transit_slot().eq(Expr::int_constant(slot, Type::size_t())));
+        let receive = Stmt::block(
+            vec![
+                self.tracked_offset()
+                    .assign(dst.clone().pointer_offset().plus(transit.clone()), loc),
+                self.tracked_object().assign(dst.pointer_object(), loc),
+                transit.assign(Expr::int_constant(-1, Type::ssize_t()), loc),
+            ],
+            loc,
+        );
+        Some(Stmt::if_then_else(cond, receive, None, loc))
+    }
+}
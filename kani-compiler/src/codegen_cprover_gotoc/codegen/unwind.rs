@@ -0,0 +1,100 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements the recovery from panics inside `catch_unwind`, which is enabled with
+//! `-Z catch-unwind` for harnesses annotated with `#[kani::catch_unwind]`.
+//!
+//! Kani compiles programs with `-C panic=abort`, so there are no unwinding edges in the MIR.
+//! Instead, we model unwinding with the following global variables:
+//!  - `__KANI_catch_unwind`: Whether the current harness recovers from panics. It's set at the
+//!    beginning of the harnesses annotated with `#[kani::catch_unwind]`.
+//!  - `__KANI_catch_depth`: The number of `catch_unwind` calls that are executing.
+//!  - `__KANI_unwinding`: Whether a panic is unwinding the stack.
+//!
+//! A panic that can be recovered sets `__KANI_unwinding` and returns from the current function.
+//! Every function call is followed by a check that returns from the caller while the stack
+//! unwinds, until the call to the closure in the `catch_unwind` model of the Kani library, which
+//! resets the flag. Destructors aren't executed while the stack unwinds.
+
+use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::attributes::{is_catch_unwind_harness, matches_diagnostic};
+use cbmc::goto_program::{Expr, Location, Stmt, Type};
+use stable_mir::mir::Place;
+
+const CATCH_UNWIND: &str = "__KANI_catch_unwind";
+const CATCH_DEPTH: &str = "__KANI_catch_depth";
+const UNWINDING: &str = "__KANI_unwinding";
+
+impl<'tcx> GotocCtx<'tcx> {
+    /// Whether `-Z catch-unwind` is enabled. Otherwise, every panic terminates the execution.
+    pub fn is_catch_unwind_enabled(&self) -> bool {
+        self.queries.args().unstable_features.iter().any(|feature| feature == "catch-unwind")
+    }
+
+    /// A global variable of the unwinding model, which is initialized to zero.
+    fn unwind_global(&mut self, name: &str, typ: Type) -> Expr {
+        let loc = Location::none();
+        self.ensure_global_var(name, false, typ.clone(), loc, |_, var| {
+            Some(var.assign(typ.zero(), loc))
+        })
+    }
+
+    /// Enable the recovery from panics at the beginning of a `#[kani::catch_unwind]` harness.
+    pub fn codegen_catch_unwind_prelude(&mut self) -> Option<Stmt> {
+        let instance = self.current_fn().instance_stable();
+        if !self.is_catch_unwind_enabled() || !is_catch_unwind_harness(self.tcx, instance) {
+            return None;
+        }
+        let flag = self.unwind_global(CATCH_UNWIND, Type::c_bool());
+        Some(flag.assign(Expr::c_true(), Location::none()))
+    }
+
+    /// Return from the current function if a panic is unwinding the stack after a function call.
+    pub fn codegen_unwinding_check(&mut self, loc: Location) -> Option<Stmt> {
+        let instance = self.current_fn().instance_stable();
+        if !self.is_catch_unwind_enabled()
+            || matches_diagnostic(self.tcx, instance.def, "KaniCatchUnwind")
+        {
+            return None;
+        }
+        let unwinding = self.unwind_global(UNWINDING, Type::c_bool());
+        let ret = self.codegen_return(loc);
+        Some(Stmt::if_then_else(unwinding.cast_to(Type::bool()), ret, None, loc))
+    }
+
+    /// Replace the panic `panic_stmt` by a return from the current function if it's inside a
+    /// `catch_unwind` call of a harness that recovers from panics.
+    pub fn codegen_recoverable_panic(&mut self, panic_stmt: Stmt, loc: Location) -> Stmt {
+        if !self.is_catch_unwind_enabled() {
+            return panic_stmt;
+        }
+        let catch_unwind = self.unwind_global(CATCH_UNWIND, Type::c_bool());
+        let depth = self.unwind_global(CATCH_DEPTH, Type::size_t());
+        let unwinding = self.unwind_global(UNWINDING, Type::c_bool());
+        let is_caught = catch_unwind.cast_to(Type::bool()).and(depth.is_zero().not());
+        let unwind =
+            Stmt::block(vec![unwinding.assign(Expr::c_true(), loc), self.codegen_return(loc)], loc);
+        Stmt::if_then_else(is_caught, unwind, Some(panic_stmt), loc)
+    }
+
+    /// Codegen the entry into a `catch_unwind` call.
+    pub fn codegen_catch_unwind_enter(&mut self, loc: Location) -> Stmt {
+        let depth = self.unwind_global(CATCH_DEPTH, Type::size_t());
+        depth.clone().assign(depth.plus(Expr::int_constant(1, Type::size_t())), loc)
+    }
+
+    /// Codegen the exit from a `catch_unwind` call, which assigns to `place` whether a panic
+    /// was caught, and stops the unwinding.
+    pub fn codegen_catch_unwind_exit(&mut self, place: &Place, loc: Location) -> Stmt {
+        let depth = self.unwind_global(CATCH_DEPTH, Type::size_t());
+        let unwinding = self.unwind_global(UNWINDING, Type::c_bool());
+        Stmt::block(
+            vec![
+                depth.clone().assign(depth.sub(Expr::int_constant(1, Type::size_t())), loc),
+                self.codegen_expr_to_place_stable(place, unwinding.clone(), loc),
+                unwinding.assign(Expr::c_false(), loc),
+            ],
+            loc,
+        )
+    }
+}
@@ -7,22 +7,25 @@
 use crate::codegen_cprover_gotoc::GotocCtx;
 use crate::kani_middle::analysis;
 use crate::kani_middle::attributes::{is_test_harness_description, KaniAttributes};
+use crate::kani_middle::fn_ptr::FnPtrTargets;
 use crate::kani_middle::metadata::{canonical_mangled_name, gen_test_metadata};
 use crate::kani_middle::provide;
 use crate::kani_middle::reachability::{
-    collect_reachable_items, filter_const_crate_items, filter_crate_items,
+    collect_reachable_items, filter_const_crate_items, filter_crate_harnesses, filter_crate_items,
 };
 use crate::kani_middle::transform::BodyTransformation;
 use crate::kani_middle::{check_reachable_items, dump_mir_items};
 use crate::kani_queries::QueryDb;
-use cbmc::goto_program::Location;
-use cbmc::irep::goto_binary_serde::write_goto_binary_file;
+use cbmc::goto_program::{Location, SymbolTable};
+use cbmc::irep;
+use cbmc::irep::goto_binary_serde::{write_goto_binary_file, write_irep_goto_binary_file};
 use cbmc::{InternString, RoundingMode};
 use cbmc::{InternedString, MachineModel};
 use kani_metadata::artifact::convert_type;
 use kani_metadata::UnsupportedFeature;
-use kani_metadata::{ArtifactType, HarnessMetadata, KaniMetadata};
-use kani_metadata::{AssignsContract, CompilerArtifactStub};
+use kani_metadata::{AdtMap, ArtifactType, HarnessMetadata, KaniMetadata};
+use kani_metadata::{AssignsContract, CompilerArtifactStub, VtableCtxResults};
+use rayon::prelude::*;
 use rustc_codegen_ssa::back::archive::{
     get_native_object_symbols, ArArchiveBuilder, ArchiveBuilder,
 };
@@ -77,7 +80,8 @@ pub fn new(queries: Arc<Mutex<QueryDb>>) -> Self {
         GotocCodegenBackend { queries }
     }
 
-    /// Generate code that is reachable from the given starting points.
+    /// Generate code that is reachable from the given starting points, and write it to
+    /// `symtab_goto`.
     ///
     /// Invariant: iff `check_contract.is_some()` then `return.2.is_some()`
     fn codegen_items<'tcx>(
@@ -87,19 +91,131 @@ fn codegen_items<'tcx>(
         symtab_goto: &Path,
         machine_model: &MachineModel,
         check_contract: Option<InternalDefId>,
+        transformer: BodyTransformation,
+    ) -> (GotocCtx<'tcx>, Vec<MonoItem>, Option<AssignsContract>) {
+        let (mut gcx, items, contract_info) =
+            self.translate_items(tcx, starting_items, machine_model, check_contract, transformer);
+        dump_mir_items(tcx, &items, &symtab_goto.with_extension("kani.mir"));
+
+        // Get the vtable function pointer restrictions if requested
+        let vtable_restrictions = if gcx.vtable_ctx.emit_vtable_restrictions {
+            Some(gcx.vtable_ctx.get_virtual_function_restrictions())
+        } else {
+            None
+        };
+        write_goto_model(tcx, &gcx, &gcx.symbol_table, symtab_goto, vtable_restrictions);
+
+        (gcx, items, contract_info)
+    }
+
+    /// Generate the code of the harnesses in `harnesses` at once, and write the model of each
+    /// harness with only the part of the code it reaches.
+    ///
+    /// Harnesses in the same crate usually reach a lot of common code, which is only translated
+    /// once this way. This can't be used for the harnesses that check a contract, since their
+    /// instrumentation depends on the harness, or with vtable restrictions, which aren't sliced.
+    ///
+    /// Only the models of the harnesses are sliced and written in parallel. The translation stays
+    /// sequential: it queries the stable MIR, whose context is only set on the compiler thread and
+    /// isn't thread-safe.
+    fn codegen_shared_items<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        harnesses: &[Instance],
+        machine_model: &MachineModel,
+        transformer: BodyTransformation,
+    ) -> (GotocCtx<'tcx>, Vec<MonoItem>) {
+        let start = Instant::now();
+        let starting_items: Vec<_> =
+            harnesses.iter().map(|harness| MonoItem::Fn(*harness)).collect();
+        let (gcx, items, _) =
+            self.translate_items(tcx, &starting_items, machine_model, None, transformer);
+        // The time to translate the shared code is split between the harnesses.
+        let shared_time = start.elapsed() / harnesses.len() as u32;
+        let models: Vec<_> = harnesses
+            .iter()
+            .map(|harness| {
+                let model_path = self
+                    .queries
+                    .lock()
+                    .unwrap()
+                    .harness_model_path(&harness.mangled_name())
+                    .unwrap()
+                    .clone();
+                (canonical_mangled_name(*harness), model_path)
+            })
+            .collect();
+        // The harnesses share the same items, so their MIR is only printed once.
+        if tcx.sess.opts.output_types.contains_key(&OutputType::Mir) {
+            let mir_path = |model_path: &Path| model_path.with_extension("kani.mir");
+            let (first, others) = models.split_first().unwrap();
+            dump_mir_items(tcx, &items, &mir_path(&first.1));
+            for (_, model_path) in others {
+                std::fs::copy(mir_path(&first.1), mir_path(model_path)).unwrap();
+            }
+        }
+        if let Some(writer) = GotoModelWriter::new(tcx, &gcx) {
+            let index = with_timer(|| gcx.symbol_table.slice_index(), "codegen slice index");
+            let results: Vec<_> = with_timer(
+                || {
+                    models
+                        .par_iter()
+                        .map(|(harness_name, model_path)| {
+                            let start = Instant::now();
+                            let slice = with_timer(
+                                || index.slice([harness_name.as_str()]),
+                                "codegen slicing",
+                            );
+                            writer
+                                .write_slice(&gcx.symbol_table, &slice, model_path)
+                                .map(|_| start.elapsed())
+                        })
+                        .collect()
+                },
+                "writing the harness models",
+            );
+            for ((harness_name, _), result) in models.iter().zip(results) {
+                match result {
+                    Ok(write_time) => self
+                        .queries
+                        .lock()
+                        .unwrap()
+                        .register_codegen_time(harness_name.intern(), shared_time + write_time),
+                    Err(err) => {
+                        tcx.dcx().err(err);
+                    }
+                }
+            }
+            tcx.dcx().abort_if_errors();
+        }
+        (gcx, items)
+    }
+
+    /// Generate code that is reachable from the given starting points.
+    fn translate_items<'tcx>(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        starting_items: &[MonoItem],
+        machine_model: &MachineModel,
+        check_contract: Option<InternalDefId>,
         mut transformer: BodyTransformation,
     ) -> (GotocCtx<'tcx>, Vec<MonoItem>, Option<AssignsContract>) {
         let items = with_timer(
             || collect_reachable_items(tcx, &mut transformer, starting_items),
             "codegen reachability analysis",
         );
-        dump_mir_items(tcx, &items, &symtab_goto.with_extension("kani.mir"));
 
         // Follow rustc naming convention (cx is abbrev for context).
         // https://rustc-dev-guide.rust-lang.org/conventions.html#naming-conventions
         let mut gcx =
             GotocCtx::new(tcx, (*self.queries.lock().unwrap()).clone(), machine_model, transformer);
         check_reachable_items(gcx.tcx, &gcx.queries, &items);
+        if gcx.is_fn_ptr_restriction_enabled() {
+            gcx.fn_ptr_targets = Some(with_timer(
+                || FnPtrTargets::new(tcx, &mut gcx.transformer, &items),
+                "function pointer analysis",
+            ));
+        }
 
         let contract_info = with_timer(
             || {
@@ -154,39 +270,113 @@ fn codegen_items<'tcx>(
             "codegen",
         );
 
-        // Map from name to prettyName for all symbols
-        let pretty_name_map: BTreeMap<InternedString, Option<InternedString>> =
-            BTreeMap::from_iter(gcx.symbol_table.iter().map(|(k, s)| (*k, s.pretty_name)));
+        (gcx, items, contract_info)
+    }
+}
 
-        // Map MIR types to GotoC types
-        let type_map: BTreeMap<InternedString, InternedString> =
+/// Write the model with the symbols of `symbol_table`, which were generated by `gcx`, to
+/// `symtab_goto`.
+fn write_goto_model(
+    tcx: TyCtxt,
+    gcx: &GotocCtx,
+    symbol_table: &SymbolTable,
+    symtab_goto: &Path,
+    vtable_restrictions: Option<VtableCtxResults>,
+) {
+    let Some(writer) = GotoModelWriter::new(tcx, gcx) else { return };
+    if let Err(err) = writer.write(symbol_table, symtab_goto, vtable_restrictions) {
+        tcx.dcx().err(err);
+        tcx.dcx().abort_if_errors();
+    }
+}
+
+/// Writes the models of the symbols generated by a `GotocCtx`.
+///
+/// It doesn't depend on the compiler, so it can be used from other threads.
+struct GotoModelWriter {
+    /// Map MIR types to GotoC types
+    type_map: BTreeMap<InternedString, InternedString>,
+    /// The layout of the structs and enums, indexed by the tag of their goto type
+    adt_map: AdtMap,
+    pretty: bool,
+    write_json_symtab: bool,
+}
+
+impl GotoModelWriter {
+    /// The writer of the models generated by `gcx`, if an output should be generated.
+    fn new(tcx: TyCtxt, gcx: &GotocCtx) -> Option<Self> {
+        // No output should be generated if user selected no_codegen.
+        if tcx.sess.opts.unstable_opts.no_codegen || !tcx.sess.opts.output_types.should_codegen() {
+            return None;
+        }
+        let type_map =
             BTreeMap::from_iter(gcx.type_map.iter().map(|(k, v)| (*k, v.to_string().into())));
+        let args = gcx.queries.args();
+        Some(GotoModelWriter {
+            type_map,
+            adt_map: gcx.adt_map(),
+            pretty: args.output_pretty_json,
+            write_json_symtab: args.write_json_symtab,
+        })
+    }
 
-        // Get the vtable function pointer restrictions if requested
-        let vtable_restrictions = if gcx.vtable_ctx.emit_vtable_restrictions {
-            Some(gcx.vtable_ctx.get_virtual_function_restrictions())
+    /// Write the model with the symbols of `symbol_table` to `symtab_goto`.
+    fn write(
+        &self,
+        symbol_table: &SymbolTable,
+        symtab_goto: &Path,
+        vtable_restrictions: Option<VtableCtxResults>,
+    ) -> Result<(), String> {
+        // Map from name to prettyName for all symbols
+        let pretty_name_map: BTreeMap<InternedString, Option<InternedString>> =
+            BTreeMap::from_iter(symbol_table.iter().map(|(k, s)| (*k, s.pretty_name)));
+
+        let pretty = self.pretty;
+        write_file(&symtab_goto, ArtifactType::PrettyNameMap, &pretty_name_map, pretty);
+        if self.write_json_symtab {
+            write_file(&symtab_goto, ArtifactType::SymTab, symbol_table, pretty);
+            symbol_table_to_gotoc(&symtab_goto)?;
         } else {
-            None
-        };
+            write_goto_binary_file(symtab_goto, symbol_table);
+        }
+        self.write_maps(symtab_goto, vtable_restrictions);
+        Ok(())
+    }
 
-        // No output should be generated if user selected no_codegen.
-        if !tcx.sess.opts.unstable_opts.no_codegen && tcx.sess.opts.output_types.should_codegen() {
-            let pretty = self.queries.lock().unwrap().args().output_pretty_json;
-            write_file(&symtab_goto, ArtifactType::PrettyNameMap, &pretty_name_map, pretty);
-            if gcx.queries.args().write_json_symtab {
-                write_file(&symtab_goto, ArtifactType::SymTab, &gcx.symbol_table, pretty);
-                symbol_table_to_gotoc(&tcx, &symtab_goto);
-            } else {
-                write_goto_binary_file(symtab_goto, &gcx.symbol_table);
-            }
-            write_file(&symtab_goto, ArtifactType::TypeMap, &type_map, pretty);
-            // If they exist, write out vtable virtual call function pointer restrictions
-            if let Some(restrictions) = vtable_restrictions {
-                write_file(&symtab_goto, ArtifactType::VTableRestriction, &restrictions, pretty);
-            }
+    /// Write the model with the symbols of `slice`, a slice of `symbol_table`, to `symtab_goto`.
+    fn write_slice(
+        &self,
+        symbol_table: &SymbolTable,
+        slice: &irep::SymbolTable,
+        symtab_goto: &Path,
+    ) -> Result<(), String> {
+        let pretty_name_map: BTreeMap<InternedString, Option<InternedString>> = slice
+            .symbol_table
+            .keys()
+            .map(|name| (*name, symbol_table.lookup(*name).unwrap().pretty_name))
+            .collect();
+
+        let pretty = self.pretty;
+        write_file(&symtab_goto, ArtifactType::PrettyNameMap, &pretty_name_map, pretty);
+        if self.write_json_symtab {
+            write_file(&symtab_goto, ArtifactType::SymTab, slice, pretty);
+            symbol_table_to_gotoc(&symtab_goto)?;
+        } else {
+            write_irep_goto_binary_file(symtab_goto, slice);
         }
+        self.write_maps(symtab_goto, None);
+        Ok(())
+    }
 
-        (gcx, items, contract_info)
+    /// Write the type maps of the model, and the vtable restrictions if they exist.
+    fn write_maps(&self, symtab_goto: &Path, vtable_restrictions: Option<VtableCtxResults>) {
+        let pretty = self.pretty;
+        write_file(&symtab_goto, ArtifactType::TypeMap, &self.type_map, pretty);
+        write_file(&symtab_goto, ArtifactType::AdtMap, &self.adt_map, pretty);
+        // If they exist, write out vtable virtual call function pointer restrictions
+        if let Some(restrictions) = vtable_restrictions {
+            write_file(&symtab_goto, ArtifactType::VTableRestriction, &restrictions, pretty);
+        }
     }
 }
 
@@ -225,6 +415,7 @@ fn codegen_crate(
             // Codegen all items that need to be processed according to the selected reachability mode:
             //
             // - Harnesses: Generate one model per local harnesses (marked with `kani::proof` attribute).
+            //   The code reachable from several harnesses is only translated once.
             // - Tests: Generate one model per test harnesses.
             // - PubFns: Generate code for all reachable logic starting from the local public functions.
             // - None: Don't generate code. This is used to compile dependencies.
@@ -239,14 +430,36 @@ fn codegen_crate(
                     let harnesses = queries.target_harnesses();
                     let mut items: HashSet<_> = HashSet::with_capacity(harnesses.len());
                     items.extend(harnesses);
-                    let harnesses = filter_crate_items(tcx, |_, instance| {
+                    let harnesses = filter_crate_harnesses(tcx, |_, instance| {
                         items.contains(&instance.mangled_name().intern())
                     });
+                    // The code of the harnesses that don't check a contract is generated at once,
+                    // unless there's a single one.
+                    let (shared, harnesses): (Vec<_>, Vec<_>) =
+                        harnesses.into_iter().partition(|harness| {
+                            !queries.args().emit_vtable_restrictions
+                                && contract_metadata_for_harness(tcx, harness.def.def_id())
+                                    .unwrap()
+                                    .is_none()
+                        });
+                    let harnesses = if shared.len() > 1 {
+                        let (gcx, items) = self.codegen_shared_items(
+                            tcx,
+                            &shared,
+                            &results.machine_model,
+                            transformer,
+                        );
+                        transformer = results.extend(gcx, items, None);
+                        harnesses
+                    } else {
+                        shared.into_iter().chain(harnesses).collect()
+                    };
                     for harness in harnesses {
                         let model_path =
                             queries.harness_model_path(&harness.mangled_name()).unwrap();
                         let contract_metadata =
                             contract_metadata_for_harness(tcx, harness.def.def_id()).unwrap();
+                        let start = Instant::now();
                         let (gcx, items, contract_info) = self.codegen_items(
                             tcx,
                             &[MonoItem::Fn(harness)],
@@ -255,12 +468,13 @@ fn codegen_crate(
                             contract_metadata,
                             transformer,
                         );
+                        let codegen_time = start.elapsed();
                         transformer = results.extend(gcx, items, None);
+                        let harness_name = canonical_mangled_name(harness).intern();
+                        let mut query_db = self.queries.lock().unwrap();
+                        query_db.register_codegen_time(harness_name, codegen_time);
                         if let Some(assigns_contract) = contract_info {
-                            self.queries.lock().unwrap().register_assigns_contract(
-                                canonical_mangled_name(harness).intern(),
-                                assigns_contract,
-                            );
+                            query_db.register_assigns_contract(harness_name, assigns_contract);
                         }
                     }
                 }
@@ -515,7 +729,7 @@ fn codegen_results(
     ))
 }
 
-fn symbol_table_to_gotoc(tcx: &TyCtxt, base_path: &Path) -> PathBuf {
+fn symbol_table_to_gotoc(base_path: &Path) -> Result<PathBuf, String> {
     let output_filename = base_path.to_path_buf();
     let input_filename = convert_type(base_path, ArtifactType::SymTabGoto, ArtifactType::SymTab);
 
@@ -539,14 +753,12 @@ fn symbol_table_to_gotoc(tcx: &TyCtxt, base_path: &Path) -> PathBuf {
     if !result.status.success() {
         error!("Symtab error output:\n{}", String::from_utf8_lossy(&result.stderr));
         error!("Symtab output:\n{}", String::from_utf8_lossy(&result.stdout));
-        let err_msg = format!(
+        return Err(format!(
             "Failed to generate goto model:\n\tsymtab2gb failed on file {}.",
             input_filename.display()
-        );
-        tcx.dcx().err(err_msg);
-        tcx.dcx().abort_if_errors();
+        ));
     };
-    output_filename
+    Ok(output_filename)
 }
 
 pub fn write_file<T>(base_path: &Path, file_type: ArtifactType, source: &T, pretty: bool)
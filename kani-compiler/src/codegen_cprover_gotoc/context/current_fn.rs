@@ -2,6 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::codegen_cprover_gotoc::GotocCtx;
+use crate::kani_middle::attributes::is_coverage_enabled;
+use crate::kani_middle::fn_ptr::LocalFnPtrs;
+use crate::kani_middle::uninit::UninitInfo;
 use cbmc::goto_program::Stmt;
 use cbmc::InternedString;
 use rustc_middle::ty::Instance as InstanceInternal;
@@ -10,6 +13,7 @@
 use stable_mir::mir::{Body, Local, LocalDecl};
 use stable_mir::CrateDef;
 use std::collections::HashMap;
+use std::rc::Rc;
 
 /// This structure represents useful data about the function we are currently compiling.
 #[derive(Debug)]
@@ -32,6 +36,12 @@ pub struct CurrentFnCtx<'tcx> {
     readable_name: String,
     /// A counter to enable creating temporary variables
     temp_var_counter: u64,
+    /// Whether we should instrument this function with coverage checks
+    check_coverage: bool,
+    /// The places relevant to the uninitialized memory checks, if they are enabled
+    uninit_info: Option<Rc<UninitInfo>>,
+    /// The function pointers stored in the locals, if the restriction of their targets is enabled
+    local_fn_ptrs: Option<Rc<LocalFnPtrs>>,
 }
 
 /// Constructor
@@ -47,6 +57,10 @@ pub fn new(instance: Instance, gcx: &GotocCtx<'tcx>, body: &Body) -> Self {
             .iter()
             .filter_map(|info| info.local().map(|local| (local, (&info.name).into())))
             .collect::<HashMap<_, _>>();
+        let check_coverage =
+            gcx.queries.args().check_coverage && is_coverage_enabled(gcx.tcx, instance.def);
+        let uninit_info = gcx.is_uninit_check_enabled().then(|| Rc::new(UninitInfo::new(body)));
+        let local_fn_ptrs = gcx.fn_ptr_targets.is_some().then(|| Rc::new(LocalFnPtrs::new(body)));
         Self {
             block: vec![],
             instance,
@@ -57,6 +71,9 @@ pub fn new(instance: Instance, gcx: &GotocCtx<'tcx>, body: &Body) -> Self {
             name,
             readable_name,
             temp_var_counter: 0,
+            check_coverage,
+            uninit_info,
+            local_fn_ptrs,
         }
     }
 }
@@ -107,6 +124,21 @@ pub fn locals(&self) -> &[LocalDecl] {
     pub fn local_name(&self, local: Local) -> Option<InternedString> {
         self.local_names.get(&local).copied()
     }
+
+    /// Whether coverage checks should be emitted for the current function
+    pub fn check_coverage(&self) -> bool {
+        self.check_coverage
+    }
+
+    /// The information used by the uninitialized memory checks, if they are enabled
+    pub fn uninit_info(&self) -> Option<Rc<UninitInfo>> {
+        self.uninit_info.clone()
+    }
+
+    /// The function pointers stored in the locals, if the restriction of their targets is enabled
+    pub fn local_fn_ptrs(&self) -> Option<Rc<LocalFnPtrs>> {
+        self.local_fn_ptrs.clone()
+    }
 }
 
 /// Utility functions
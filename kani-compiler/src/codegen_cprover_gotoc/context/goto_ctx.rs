@@ -18,6 +18,7 @@
 use crate::codegen_cprover_gotoc::overrides::{fn_hooks, GotocHooks};
 use crate::codegen_cprover_gotoc::utils::full_crate_name;
 use crate::codegen_cprover_gotoc::UnsupportedConstructs;
+use crate::kani_middle::fn_ptr::FnPtrTargets;
 use crate::kani_middle::transform::BodyTransformation;
 use crate::kani_queries::QueryDb;
 use cbmc::goto_program::{DatatypeComponent, Expr, Location, Stmt, Symbol, SymbolTable, Type};
@@ -34,6 +35,7 @@
 use rustc_span::Span;
 use rustc_target::abi::call::FnAbi;
 use rustc_target::abi::{HasDataLayout, TargetDataLayout};
+use stable_mir::mir::alloc::AllocId;
 use stable_mir::mir::mono::Instance;
 use stable_mir::mir::Body;
 use stable_mir::ty::Allocation;
@@ -53,6 +55,11 @@ pub struct GotocCtx<'tcx> {
     pub global_var_count: u64,
     /// map a global allocation to a name in the symbol table
     pub alloc_map: FxHashMap<Allocation, String>,
+    /// map an immutable allocation to the first allocation with the same content, see
+    /// `intern_alloc_id`
+    pub interned_alloc_ids: FxHashMap<AllocId, AllocId>,
+    /// map the content of an allocation to the first allocation with this content
+    pub alloc_ids: FxHashMap<Allocation, AllocId>,
     /// map (trait, method) pairs to possible implementations
     pub vtable_ctx: VtableCtx,
     pub current_fn: Option<CurrentFnCtx<'tcx>>,
@@ -70,6 +77,9 @@ pub struct GotocCtx<'tcx> {
     pub concurrent_constructs: UnsupportedConstructs,
     /// The body transformation agent.
     pub transformer: BodyTransformation,
+    /// The possible targets of calls through function pointers, if `-Z restrict-fn-ptrs` is
+    /// enabled.
+    pub fn_ptr_targets: Option<FnPtrTargets<'tcx>>,
 }
 
 /// Constructor
@@ -91,6 +101,8 @@ pub fn new(
             full_crate_name: full_crate_name(tcx),
             global_var_count: 0,
             alloc_map: FxHashMap::default(),
+            interned_alloc_ids: FxHashMap::default(),
+            alloc_ids: FxHashMap::default(),
             vtable_ctx: VtableCtx::new(emit_vtable_restrictions),
             current_fn: None,
             type_map: FxHashMap::default(),
@@ -99,6 +111,7 @@ pub fn new(
             unsupported_constructs: FxHashMap::default(),
             concurrent_constructs: FxHashMap::default(),
             transformer,
+            fn_ptr_targets: None,
         }
     }
 }
@@ -65,10 +65,11 @@ pub fn add_possible_method(
         assert!(self.emit_vtable_restrictions);
         let key = TraitDefinedMethod { trait_name, vtable_idx: method };
 
-        if let Some(possibilities) = self.possible_methods.get_mut(&key) {
+        // The vtables of the dynamic types that only differ by their auto traits share the same
+        // possible methods.
+        let possibilities = self.possible_methods.entry(key).or_default();
+        if !possibilities.contains(&imp) {
             possibilities.push(imp);
-        } else {
-            self.possible_methods.insert(key, vec![imp]);
         }
     }
 
@@ -121,7 +122,8 @@ pub fn virtual_call_with_restricted_fn_ptr(
         // Retrieve the MIR for `&dyn T` and normalize the name.
         assert!(trait_ref.is_struct_tag());
         let trait_ref_mir_type = self.type_map.get(&trait_ref.tag().unwrap()).unwrap();
-        let trait_name = self.normalized_trait_name(pointee_type(*trait_ref_mir_type).unwrap());
+        let trait_name =
+            self.vtable_restriction_trait_name(pointee_type(*trait_ref_mir_type).unwrap());
 
         // Label
         self.vtable_ctx.add_call_site(
@@ -12,15 +12,20 @@
 use crate::codegen_cprover_gotoc::GotocCtx;
 use crate::kani_middle::attributes::matches_diagnostic as matches_function;
 use crate::unwrap_or_return_codegen_unimplemented_stmt;
-use cbmc::goto_program::{BuiltinFn, Expr, Stmt, Type};
+use cbmc::goto_program::{BuiltinFn, Expr, Location, Stmt, Symbol, Type};
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_middle::ty::TyCtxt;
 use rustc_smir::rustc_internal;
 use stable_mir::mir::mono::Instance;
 use stable_mir::mir::{BasicBlockIdx, Place};
-use stable_mir::{ty::Span, CrateDef};
+use stable_mir::ty::{Span, Ty};
+use stable_mir::CrateDef;
 use std::rc::Rc;
 use tracing::debug;
 
+/// The hook of `kani_lib.c` that is called with the bytes of every symbolic value.
+const NONDET_CREATED: &str = "__KANI_nondet_created";
+
 pub trait GotocHook {
     /// if the hook applies, it means the codegen would do something special to it
     fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool;
@@ -113,36 +118,70 @@ fn handle(
         &self,
         gcx: &mut GotocCtx,
         _instance: Instance,
-        mut fargs: Vec<Expr>,
+        fargs: Vec<Expr>,
         _assign_to: &Place,
         target: Option<BasicBlockIdx>,
         span: Span,
     ) -> Stmt {
-        assert_eq!(fargs.len(), 2);
-        let cond = fargs.remove(0).cast_to(Type::bool());
-        let msg = fargs.remove(0);
-        let msg = gcx.extract_const_message(&msg).unwrap();
-        let target = target.unwrap();
-        let caller_loc = gcx.codegen_caller_span_stable(span);
+        codegen_assert_hook(gcx, fargs, target, span, PropertyClass::Assertion)
+    }
+}
 
-        let (msg, reach_stmt) = gcx.codegen_reachability_check(msg, span);
+/// The check of the validity of a value read from a union field, which is added by
+/// `-Z valid-value-checks`.
+struct UnionReadCheck;
 
-        // Since `cond` might have side effects, assign it to a temporary
-        // variable so that it's evaluated once, then assert and assume it
-        // TODO: I don't think `cond` can have side effects, this is MIR, it's going to be temps
-        let (tmp, decl) = gcx.decl_temp_variable(cond.typ().clone(), Some(cond), caller_loc);
-        Stmt::block(
-            vec![
-                reach_stmt,
-                decl,
-                gcx.codegen_assert_assume(tmp, PropertyClass::Assertion, &msg, caller_loc),
-                Stmt::goto(bb_label(target), caller_loc),
-            ],
-            caller_loc,
-        )
+impl GotocHook for UnionReadCheck {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
+        matches_function(tcx, instance.def, "KaniUnionReadCheck")
+    }
+
+    fn handle(
+        &self,
+        gcx: &mut GotocCtx,
+        _instance: Instance,
+        fargs: Vec<Expr>,
+        _assign_to: &Place,
+        target: Option<BasicBlockIdx>,
+        span: Span,
+    ) -> Stmt {
+        codegen_assert_hook(gcx, fargs, target, span, PropertyClass::InvalidUnionRead)
     }
 }
 
+/// Codegen a call to a function with a `(cond: bool, msg: &'static str)` signature that asserts
+/// and assumes `cond`.
+fn codegen_assert_hook(
+    gcx: &mut GotocCtx,
+    mut fargs: Vec<Expr>,
+    target: Option<BasicBlockIdx>,
+    span: Span,
+    property_class: PropertyClass,
+) -> Stmt {
+    assert_eq!(fargs.len(), 2);
+    let cond = fargs.remove(0).cast_to(Type::bool());
+    let msg = fargs.remove(0);
+    let msg = gcx.extract_const_message(&msg).unwrap();
+    let target = target.unwrap();
+    let caller_loc = gcx.codegen_caller_span_stable(span);
+
+    let (msg, reach_stmt) = gcx.codegen_reachability_check(msg, span);
+
+    // Since `cond` might have side effects, assign it to a temporary
+    // variable so that it's evaluated once, then assert and assume it
+    // TODO: I don't think `cond` can have side effects, this is MIR, it's going to be temps
+    let (tmp, decl) = gcx.decl_temp_variable(cond.typ().clone(), Some(cond), caller_loc);
+    let mut stmts = vec![reach_stmt, decl];
+    // A failed assertion is a panic at runtime, which unwinds if it's inside `catch_unwind`.
+    if property_class == PropertyClass::Assertion && gcx.is_catch_unwind_enabled() {
+        let unwind = gcx.codegen_recoverable_panic(Stmt::skip(caller_loc), caller_loc);
+        stmts.push(Stmt::if_then_else(tmp.clone().not(), unwind, None, caller_loc));
+    }
+    stmts.push(gcx.codegen_assert_assume(tmp, property_class, &msg, caller_loc));
+    stmts.push(Stmt::goto(bb_label(target), caller_loc));
+    Stmt::block(stmts, caller_loc)
+}
+
 struct Nondet;
 
 impl GotocHook for Nondet {
@@ -171,17 +210,37 @@ fn handle(
                 gcx.codegen_place_stable(assign_to)
             )
             .goto_expr;
-            Stmt::block(
-                vec![
-                    pe.assign(gcx.codegen_ty_stable(pt).nondet(), loc),
-                    Stmt::goto(bb_label(target), loc),
-                ],
-                loc,
-            )
+            let mut stmts = vec![pe.clone().assign(gcx.codegen_ty_stable(pt).nondet(), loc)];
+            if gcx.queries.args().minimize_counterexamples {
+                stmts.push(codegen_nondet_created(gcx, pe, pt, loc));
+            }
+            stmts.push(Stmt::goto(bb_label(target), loc));
+            Stmt::block(stmts, loc)
         }
     }
 }
 
+/// Pass the bytes of the symbolic value `value` to the `__KANI_nondet_created` hook of
+/// `kani_lib.c`, which the driver replaces to minimize the counterexamples.
+fn codegen_nondet_created(gcx: &mut GotocCtx, value: Expr, ty: Ty, loc: Location) -> Stmt {
+    let size = gcx.layout_of_stable(ty).size.bytes();
+    if size == 0 {
+        return Stmt::skip(loc);
+    }
+    let bytes_ptr = Type::unsigned_int(8).to_pointer();
+    let unit = gcx.codegen_ty_unit();
+    let hook = gcx.ensure(NONDET_CREATED, |_, name| {
+        let params = vec![
+            bytes_ptr.clone().as_parameter(None, Some("value".into())),
+            Type::size_t().as_parameter(None, Some("size".into())),
+        ];
+        Symbol::function(name, Type::code(params, unit), None, name, Location::none())
+    });
+    let args =
+        vec![value.address_of().cast_to(bytes_ptr), Expr::int_constant(size, Type::size_t())];
+    hook.to_expr().call(args).as_stmt(loc)
+}
+
 struct Panic;
 
 impl GotocHook for Panic {
@@ -203,7 +262,9 @@ fn handle(
         _target: Option<BasicBlockIdx>,
         span: Span,
     ) -> Stmt {
-        gcx.codegen_panic(span, fargs)
+        let panic_stmt = gcx.codegen_panic(span, fargs);
+        let loc = gcx.codegen_span_stable(span);
+        gcx.codegen_recoverable_panic(panic_stmt, loc)
     }
 }
 
@@ -245,10 +306,12 @@ fn handle(
 struct RustAlloc;
 // Removing this hook causes regression failures.
 // https://github.com/model-checking/kani/issues/1170
+// The hook doesn't apply if the crate defines a `#[global_allocator]`, since allocations must go
+// through it.
 impl GotocHook for RustAlloc {
-    fn hook_applies(&self, _tcx: TyCtxt, instance: Instance) -> bool {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
         let full_name = instance.name();
-        full_name == "alloc::alloc::exchange_malloc"
+        full_name == "alloc::alloc::exchange_malloc" && !tcx.has_global_allocator(LOCAL_CRATE)
     }
 
     fn handle(
@@ -264,23 +327,26 @@ fn handle(
         let loc = gcx.codegen_span_stable(span);
         let target = target.unwrap();
         let size = fargs.remove(0);
-        Stmt::block(
-            vec![
-                unwrap_or_return_codegen_unimplemented_stmt!(
-                    gcx,
-                    gcx.codegen_place_stable(assign_to)
-                )
-                .goto_expr
-                .assign(
-                    BuiltinFn::Malloc
-                        .call(vec![size], loc)
-                        .cast_to(Type::unsigned_int(8).to_pointer()),
-                    loc,
-                ),
-                Stmt::goto(bb_label(target), loc),
-            ],
+        let place =
+            unwrap_or_return_codegen_unimplemented_stmt!(gcx, gcx.codegen_place_stable(assign_to))
+                .goto_expr;
+        let mut stmts = vec![place.clone().assign(
+            BuiltinFn::Malloc.call(vec![size], loc).cast_to(Type::unsigned_int(8).to_pointer()),
             loc,
-        )
+        )];
+        stmts.extend(gcx.codegen_uninit_alloc(place.clone(), loc));
+        if gcx.queries.args().allocator_may_fail {
+            // `exchange_malloc` calls `handle_alloc_error` if the allocation fails, which
+            // aborts the program.
+            stmts.push(gcx.codegen_assert_assume(
+                place.is_nonnull(),
+                PropertyClass::Assertion,
+                "memory allocation failed",
+                loc,
+            ));
+        }
+        stmts.push(Stmt::goto(bb_label(target), loc));
+        Stmt::block(stmts, loc)
     }
 }
 
@@ -390,18 +456,152 @@ fn handle(
     }
 }
 
+/// A hook for Kani's `start_thread` function (declared in `library/kani/src/thread.rs`).
+/// The function takes a function pointer and its argument. The hook codegens the call as a
+/// statement labeled with `__CPROVER_ASYNC_<n>`, which CBMC runs in a new thread.
+struct StartThread;
+
+impl GotocHook for StartThread {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
+        matches_function(tcx, instance.def, "KaniStartThread")
+    }
+
+    fn handle(
+        &self,
+        gcx: &mut GotocCtx,
+        _instance: Instance,
+        mut fargs: Vec<Expr>,
+        _assign_to: &Place,
+        target: Option<BasicBlockIdx>,
+        span: Span,
+    ) -> Stmt {
+        assert_eq!(fargs.len(), 2);
+        let function = fargs.remove(0);
+        let arg = fargs.remove(0);
+        let target = target.unwrap();
+        let loc = gcx.codegen_span_stable(span);
+        // Labels must be unique in a function body.
+        let label = format!("__CPROVER_ASYNC_{}", gcx.current_fn_mut().get_and_incr_counter());
+        Stmt::block(
+            vec![
+                function.dereference().call(vec![arg]).as_stmt(loc).with_label(label),
+                Stmt::goto(bb_label(target), loc),
+            ],
+            loc,
+        )
+    }
+}
+
+/// Hooks for the functions that the `catch_unwind` model of the Kani library (declared in
+/// `library/kani/src/internal.rs`) calls before and after running its closure.
+/// See the `unwind` module of the codegen for more details.
+struct CatchUnwindEnter;
+
+impl GotocHook for CatchUnwindEnter {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
+        matches_function(tcx, instance.def, "KaniCatchUnwindEnter")
+    }
+
+    fn handle(
+        &self,
+        gcx: &mut GotocCtx,
+        _instance: Instance,
+        fargs: Vec<Expr>,
+        _assign_to: &Place,
+        target: Option<BasicBlockIdx>,
+        span: Span,
+    ) -> Stmt {
+        assert!(fargs.is_empty());
+        let loc = gcx.codegen_span_stable(span);
+        Stmt::block(
+            vec![gcx.codegen_catch_unwind_enter(loc), Stmt::goto(bb_label(target.unwrap()), loc)],
+            loc,
+        )
+    }
+}
+
+struct CatchUnwindExit;
+
+impl GotocHook for CatchUnwindExit {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
+        matches_function(tcx, instance.def, "KaniCatchUnwindExit")
+    }
+
+    fn handle(
+        &self,
+        gcx: &mut GotocCtx,
+        _instance: Instance,
+        fargs: Vec<Expr>,
+        assign_to: &Place,
+        target: Option<BasicBlockIdx>,
+        span: Span,
+    ) -> Stmt {
+        assert!(fargs.is_empty());
+        let loc = gcx.codegen_span_stable(span);
+        Stmt::block(
+            vec![
+                gcx.codegen_catch_unwind_exit(assign_to, loc),
+                Stmt::goto(bb_label(target.unwrap()), loc),
+            ],
+            loc,
+        )
+    }
+}
+
+/// A hook for the function of the standard library that registers the destructor of a
+/// thread-local variable, which is called the first time the variable is accessed.
+/// Kani doesn't run these destructors, so the hook skips the call. Note that the standard library
+/// doesn't guarantee that they run either, e.g., for the main thread.
+///
+/// The function has no diagnostic item, and the module that defines it depends on the platform,
+/// e.g., `std::sys::pal::unix::thread_local_dtor`. So the hook matches the function of `std`
+/// whose definition path ends with `thread_local_dtor::register_dtor`.
+struct RegisterThreadLocalDtor;
+
+impl GotocHook for RegisterThreadLocalDtor {
+    fn hook_applies(&self, tcx: TyCtxt, instance: Instance) -> bool {
+        let def_id = rustc_internal::internal(tcx, instance.def.def_id());
+        if tcx.crate_name(def_id.krate) != rustc_span::sym::std {
+            return false;
+        }
+        let def_path = tcx.def_path(def_id);
+        let names: Vec<_> =
+            def_path.data.iter().filter_map(|data| data.data.get_opt_name()).collect();
+        matches!(names[..], [.., module, name]
+            if module.as_str() == "thread_local_dtor" && name.as_str() == "register_dtor")
+    }
+
+    fn handle(
+        &self,
+        gcx: &mut GotocCtx,
+        _instance: Instance,
+        _fargs: Vec<Expr>,
+        _assign_to: &Place,
+        target: Option<BasicBlockIdx>,
+        span: Span,
+    ) -> Stmt {
+        let loc = gcx.codegen_span_stable(span);
+        Stmt::goto(bb_label(target.unwrap()), loc)
+    }
+}
+
 pub fn fn_hooks() -> GotocHooks {
     GotocHooks {
         hooks: vec![
             Rc::new(Panic),
             Rc::new(Assume),
             Rc::new(Assert),
+            Rc::new(UnionReadCheck),
             Rc::new(Cover),
             Rc::new(Nondet),
             Rc::new(IsReadOk),
             Rc::new(RustAlloc),
             Rc::new(MemCmp),
             Rc::new(UntrackedDeref),
+            Rc::new(StartThread),
+            Rc::new(RegisterThreadLocalDtor),
+            Rc::new(CatchUnwindEnter),
+            Rc::new(CatchUnwindExit),
         ],
     }
 }
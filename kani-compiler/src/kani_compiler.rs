@@ -18,10 +18,9 @@
 use crate::args::{Arguments, ReachabilityType};
 #[cfg(feature = "cprover")]
 use crate::codegen_cprover_gotoc::GotocCodegenBackend;
-use crate::kani_middle::attributes::is_proof_harness;
 use crate::kani_middle::check_crate_items;
 use crate::kani_middle::metadata::gen_proof_metadata;
-use crate::kani_middle::reachability::filter_crate_items;
+use crate::kani_middle::reachability::filter_crate_harnesses;
 use crate::kani_middle::stubbing::{self, harness_stub_map};
 use crate::kani_queries::QueryDb;
 use crate::session::init_session;
@@ -57,7 +56,7 @@ pub fn run(args: Vec<String>) -> ExitCode {
     }
 }
 
-/// Configure the cprover backend that generate goto-programs.
+/// Configure the cprover backend that generates goto-programs.
 #[cfg(feature = "cprover")]
 fn backend(queries: Arc<Mutex<QueryDb>>) -> Box<dyn CodegenBackend> {
     Box::new(GotocCodegenBackend::new(queries))
@@ -65,7 +64,7 @@ fn backend(queries: Arc<Mutex<QueryDb>>) -> Box<dyn CodegenBackend> {
 
 /// Fallback backend. It will trigger an error if no backend has been enabled.
 #[cfg(not(feature = "cprover"))]
-fn backend(queries: Arc<Mutex<QueryDb>>) -> Box<CodegenBackend> {
+fn backend(_queries: Arc<Mutex<QueryDb>>) -> Box<dyn CodegenBackend> {
     compile_error!("No backend is available. Only supported value today is `cprover`");
 }
 
@@ -188,22 +187,22 @@ pub fn run(&mut self, orig_args: Vec<String>) -> Result<(), ErrorGuaranteed> {
             // Because this modifies `self.stage` we need to run this before
             // borrowing `&self.stage` immutably
             if let CompilationStage::Done { metadata: Some((metadata, _)), .. } = &mut self.stage {
-                let mut contracts = self
-                    .queries
-                    .lock()
-                    .unwrap()
+                let queries = self.queries.lock().unwrap();
+                let mut contracts = queries
                     .assigns_contracts()
                     .map(|(k, v)| (*k, v.clone()))
                     .collect::<FxHashMap<_, _>>();
                 for harness in
                     metadata.proof_harnesses.iter_mut().chain(metadata.test_harnesses.iter_mut())
                 {
-                    if let Some(modifies_contract) =
-                        contracts.remove(&(&harness.mangled_name).intern())
-                    {
+                    let harness_name = (&harness.mangled_name).intern();
+                    if let Some(modifies_contract) = contracts.remove(&harness_name) {
                         harness.contract = modifies_contract.into();
                     }
+                    harness.codegen_time_secs =
+                        queries.codegen_time(harness_name).map(|time| time.as_secs_f64());
                 }
+                drop(queries);
                 assert!(
                     contracts.is_empty(),
                     "Invariant broken: not all contracts have been handled."
@@ -306,28 +305,36 @@ fn process_harnesses(&self, tcx: TyCtxt) -> CompilationStage {
         {
             let base_filepath = tcx.output_filenames(()).path(OutputType::Object);
             let base_filename = base_filepath.as_path();
-            let harnesses = filter_crate_items(tcx, |_, instance| is_proof_harness(tcx, instance));
+            let harnesses = filter_crate_harnesses(tcx, |_, _| true);
+            let args = self.queries.lock().unwrap().args().clone();
             let all_harnesses = harnesses
                 .into_iter()
                 .map(|harness| {
                     let def_path = harness.mangled_name().intern();
-                    let metadata = gen_proof_metadata(tcx, harness, &base_filename);
+                    let mut metadata = gen_proof_metadata(tcx, harness, &base_filename);
+                    // The stubs given in the command line apply to every harness.
+                    metadata.attributes.stubs.extend(args.stubs.iter().cloned());
+                    // The metadata of every harness is generated, but only the code of the
+                    // harnesses selected by `--harness` and `--exclude-harness`.
+                    if !args.selects_harness(&metadata) {
+                        metadata.goto_file = None;
+                    }
                     let stub_map = harness_stub_map(tcx, harness, &metadata);
                     (def_path, HarnessInfo { metadata, stub_map })
                 })
                 .collect::<HashMap<_, _>>();
 
-            let (no_stubs, with_stubs): (Vec<_>, Vec<_>) =
-                if self.queries.lock().unwrap().args().stubbing_enabled {
-                    // Partition harnesses that don't have stub with the ones with stub.
-                    all_harnesses
-                        .keys()
-                        .cloned()
-                        .partition(|harness| all_harnesses[harness].stub_map.is_empty())
-                } else {
-                    // Generate code without stubs.
-                    (all_harnesses.keys().cloned().collect(), vec![])
-                };
+            let selected = all_harnesses
+                .keys()
+                .cloned()
+                .filter(|harness| all_harnesses[harness].metadata.goto_file.is_some());
+            let (no_stubs, with_stubs): (Vec<_>, Vec<_>) = if args.stubbing_enabled {
+                // Partition harnesses that don't have stub with the ones with stub.
+                selected.partition(|harness| all_harnesses[harness].stub_map.is_empty())
+            } else {
+                // Generate code without stubs.
+                (selected.collect(), vec![])
+            };
 
             // Even if no_stubs is empty we still need to store rustc metadata.
             CompilationStage::CodegenNoStubs {
@@ -494,6 +501,7 @@ fn mock_metadata(name: String, krate: String) -> HarnessMetadata {
             goto_file: None,
             attributes: HarnessAttributes::default(),
             contract: Default::default(),
+            codegen_time_secs: None,
         }
     }
 
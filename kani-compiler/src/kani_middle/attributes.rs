@@ -2,13 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! This module contains code for processing Rust attributes (like `kani::proof`).
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use kani_metadata::{CbmcSolver, HarnessAttributes, Stub};
+use kani_metadata::{CbmcSlicing, CbmcSolver, ContractClauses, HarnessAttributes, Stub};
 use rustc_ast::{
     attr,
-    token::Token,
-    token::TokenKind,
+    token::{self, BinOpToken, Token, TokenKind},
     tokenstream::{TokenStream, TokenTree},
     AttrArgs, AttrArgsEq, AttrKind, Attribute, ExprKind, LitKind, MetaItem, MetaItemKind,
     NestedMetaItem,
@@ -18,7 +17,9 @@
     def::DefKind,
     def_id::{DefId, LocalDefId},
 };
-use rustc_middle::ty::{Instance, TyCtxt, TyKind};
+use rustc_middle::ty::{
+    Const, GenericArg, GenericParamDefKind, Instance, ParamEnv, Ty, TyCtxt, TyKind,
+};
 use rustc_session::Session;
 use rustc_smir::rustc_internal;
 use rustc_span::{Span, Symbol};
@@ -37,6 +38,12 @@
 enum KaniAttributeKind {
     Proof,
     ShouldPanic,
+    /// Attribute used to recover from panics inside `catch_unwind` in a harness.
+    CatchUnwind,
+    /// Attribute used to verify a harness for a range of values of a const generic parameter.
+    ConstGeneric,
+    /// Attribute used to select the CBMC slicing options of a harness.
+    Slicing,
     Solver,
     Stub,
     /// Attribute used to mark unstable APIs.
@@ -74,6 +81,13 @@ enum KaniAttributeKind {
     /// We use this attribute to properly instantiate `kani::any_modifies` in
     /// cases when recursion is present given our contracts instrumentation.
     Recursion,
+    /// Attribute used to turn coverage instrumentation on or off for an item and every item
+    /// nested in it, e.g.: `#[kanitool::coverage(off)]`.
+    Coverage,
+    /// A clause of the contract of a function, as written in the source, e.g.,
+    /// `#[kanitool::contract_clause = "requires(divisor != 0)"]`. Placed on the original function
+    /// by the expansion of each contract attribute.
+    ContractClause,
 }
 
 impl KaniAttributeKind {
@@ -82,17 +96,22 @@ pub fn is_harness_only(self) -> bool {
         match self {
             KaniAttributeKind::Proof
             | KaniAttributeKind::ShouldPanic
+            | KaniAttributeKind::CatchUnwind
+            | KaniAttributeKind::ConstGeneric
+            | KaniAttributeKind::Slicing
             | KaniAttributeKind::Solver
             | KaniAttributeKind::Stub
             | KaniAttributeKind::ProofForContract
             | KaniAttributeKind::StubVerified
             | KaniAttributeKind::Unwind => true,
             KaniAttributeKind::Unstable
+            | KaniAttributeKind::Coverage
             | KaniAttributeKind::Recursion
             | KaniAttributeKind::ReplacedWith
             | KaniAttributeKind::CheckedWith
             | KaniAttributeKind::Modifies
             | KaniAttributeKind::InnerCheck
+            | KaniAttributeKind::ContractClause
             | KaniAttributeKind::IsContractGenerated => false,
         }
     }
@@ -247,6 +266,24 @@ pub fn replaced_with(&self) -> Option<Result<Symbol, ErrorGuaranteed>> {
             .map(|target| expect_key_string_value(self.tcx.sess, target))
     }
 
+    /// The clauses of the contract of this function, as written in the source.
+    pub fn contract_clauses(&self) -> ContractClauses {
+        let mut clauses = ContractClauses::default();
+        let attrs = self.map.get(&KaniAttributeKind::ContractClause).into_iter().flatten();
+        for attr in attrs {
+            let Ok(value) = expect_key_string_value(self.tcx.sess, attr) else { continue };
+            let Some((kind, clause)) = value.as_str().split_once('(') else { continue };
+            let clause = clause.strip_suffix(')').unwrap_or(clause).to_string();
+            match kind {
+                "requires" => clauses.requires.push(clause),
+                "ensures" => clauses.ensures.push(clause),
+                "modifies" => clauses.modifies.push(clause),
+                _ => {}
+            }
+        }
+        clauses
+    }
+
     /// Retrieves the global, static recursion tracker variable.
     pub fn checked_with_id(&self) -> Option<Result<DefId, ErrorGuaranteed>> {
         self.eval_sibling_attribute(KaniAttributeKind::CheckedWith)
@@ -316,7 +353,7 @@ pub(super) fn check_attributes(&self) {
                 ));
             }
             match kind {
-                KaniAttributeKind::ShouldPanic => {
+                KaniAttributeKind::ShouldPanic | KaniAttributeKind::CatchUnwind => {
                     expect_single(self.tcx, kind, &attrs);
                     attrs.iter().for_each(|attr| {
                         expect_no_args(self.tcx, kind, attr);
@@ -334,9 +371,39 @@ pub(super) fn check_attributes(&self) {
                         parse_solver(self.tcx, attr);
                     })
                 }
+                KaniAttributeKind::Slicing => {
+                    expect_single(self.tcx, kind, &attrs);
+                    attrs.iter().for_each(|attr| {
+                        parse_slicing(self.tcx, attr);
+                    })
+                }
+                KaniAttributeKind::Coverage => {
+                    expect_single(self.tcx, kind, &attrs);
+                    attrs.iter().for_each(|attr| {
+                        if parse_coverage(attr).is_none() {
+                            self.tcx.dcx().span_err(
+                                attr.span,
+                                "invalid argument for `coverage` attribute, expected `on` or `off`",
+                            );
+                        }
+                    })
+                }
                 KaniAttributeKind::Stub => {
                     parse_stubs(self.tcx, self.item, attrs);
                 }
+                KaniAttributeKind::ConstGeneric => {
+                    let mut names = BTreeSet::new();
+                    for attr in attrs {
+                        if let Some((name, _)) = parse_const_generic(self.tcx, attr)
+                            && !names.insert(name)
+                        {
+                            self.tcx.dcx().span_err(
+                                attr.span,
+                                format!("duplicate `const_generic` attribute for `{name}`"),
+                            );
+                        }
+                    }
+                }
                 KaniAttributeKind::Unwind => {
                     expect_single(self.tcx, kind, &attrs);
                     attrs.iter().for_each(|attr| {
@@ -381,6 +448,9 @@ pub(super) fn check_attributes(&self) {
                 KaniAttributeKind::InnerCheck => {
                     self.inner_check();
                 }
+                KaniAttributeKind::ContractClause => attrs.iter().for_each(|attr| {
+                    let _ = expect_key_string_value(self.tcx.sess, attr);
+                }),
             }
         }
     }
@@ -412,6 +482,28 @@ pub fn check_unstable_features(&self, enabled_features: &[String]) {
             }
         }
 
+        if !enabled_features.iter().any(|feature| feature == "catch-unwind")
+            && let Some(attr) =
+                self.map.get(&KaniAttributeKind::CatchUnwind).and_then(|a| a.first())
+        {
+            self.tcx.dcx().span_err(
+                attr.span,
+                "Using the catch_unwind attribute requires activating the unstable `catch-unwind` \
+                feature",
+            );
+        }
+
+        if !enabled_features.iter().any(|feature| feature == "const-generic-harness")
+            && let Some(attr) =
+                self.map.get(&KaniAttributeKind::ConstGeneric).and_then(|a| a.first())
+        {
+            self.tcx.dcx().span_err(
+                attr.span,
+                "Using the const_generic attribute requires activating the unstable \
+                `const-generic-harness` feature",
+            );
+        }
+
         if let Some(unstable_attrs) = self.map.get(&KaniAttributeKind::Unstable) {
             for attr in unstable_attrs {
                 let unstable_attr = UnstableAttribute::try_from(*attr).unwrap();
@@ -464,12 +556,16 @@ pub fn harness_attributes(&self) -> HarnessAttributes {
         self.map.iter().fold(HarnessAttributes::default(), |mut harness, (kind, attributes)| {
             match kind {
                 KaniAttributeKind::ShouldPanic => harness.should_panic = true,
+                KaniAttributeKind::CatchUnwind => harness.catch_unwind = true,
                 KaniAttributeKind::Recursion => {
                     self.tcx.dcx().span_err(self.tcx.def_span(self.item), "The attribute `kani::recursion` should only be used in combination with function contracts.");
                 },
                 KaniAttributeKind::Solver => {
                     harness.solver = parse_solver(self.tcx, attributes[0]);
                 }
+                KaniAttributeKind::Slicing => {
+                    harness.slicing = parse_slicing(self.tcx, attributes[0]);
+                }
                 KaniAttributeKind::Stub => {
                     harness.stubs.extend_from_slice(&parse_stubs(self.tcx, self.item, attributes));
                 }
@@ -483,6 +579,15 @@ pub fn harness_attributes(&self) -> HarnessAttributes {
                     // Internal attribute which shouldn't exist here.
                     unreachable!()
                 }
+                KaniAttributeKind::Coverage => {
+                    // Only affects coverage instrumentation, which is handled during codegen.
+                }
+                KaniAttributeKind::ConstGeneric => {
+                    // Only affects which instances of the harness are verified.
+                }
+                KaniAttributeKind::ContractClause => {
+                    // Only recorded on the function under contract.
+                }
                 KaniAttributeKind::CheckedWith
                 | KaniAttributeKind::IsContractGenerated
                 | KaniAttributeKind::Modifies
@@ -518,6 +623,8 @@ fn handle_proof_for_contract(&self, harness: &mut HarnessAttributes) {
             return;
         };
         harness.stubs.push(self.stub_for_relative_item(name, replacement_name));
+        harness.proof_for_contract = Some(name.to_string());
+        harness.contract_clauses = KaniAttributes::for_item(self.tcx, id).contract_clauses();
     }
 
     fn handle_stub_verified(&self, harness: &mut HarnessAttributes) {
@@ -552,7 +659,8 @@ fn handle_stub_verified(&self, harness: &mut HarnessAttributes) {
                 Some(Ok(replacement_name)) => replacement_name,
                 Some(Err(_)) => continue,
             };
-            harness.stubs.push(self.stub_for_relative_item(name, replacement_name))
+            harness.stubs.push(self.stub_for_relative_item(name, replacement_name));
+            harness.verified_stubs.push(name.to_string());
         }
     }
 
@@ -567,17 +675,119 @@ fn check_proof_attribute(&self, proof_attribute: &Attribute) {
         expect_no_args(tcx, KaniAttributeKind::Proof, proof_attribute);
         if tcx.def_kind(self.item) != DefKind::Fn {
             tcx.dcx().span_err(span, "the `proof` attribute can only be applied to functions");
-        } else if tcx.generics_of(self.item).requires_monomorphization(tcx) {
-            tcx.dcx()
-                .span_err(span, "the `proof` attribute cannot be applied to generic functions");
-        } else {
-            let instance = Instance::mono(tcx, self.item);
-            if !super::fn_abi(tcx, instance).args.is_empty() {
+        } else if let Some(instance) = self.harness_instances_internal(span).first() {
+            if !super::fn_abi(tcx, *instance).args.is_empty() {
                 tcx.dcx().span_err(span, "functions used as harnesses cannot have any arguments");
             }
         }
     }
 
+    /// The instances of this harness that should be verified.
+    ///
+    /// A harness is a monomorphic function, unless its const generic parameters are annotated with
+    /// `#[kani::const_generic]`. In that case, there is one instance for each combination of the
+    /// values of the parameters.
+    pub fn harness_instances(&self) -> Vec<InstanceStable> {
+        self.harness_instances_internal(self.tcx.def_span(self.item))
+            .into_iter()
+            .map(rustc_internal::stable)
+            .collect()
+    }
+
+    /// Implementation of [Self::harness_instances], which reports invalid harnesses at `span`.
+    fn harness_instances_internal(&self, span: Span) -> Vec<Instance<'tcx>> {
+        let tcx = self.tcx;
+        let generics = tcx.generics_of(self.item);
+        let attrs =
+            self.map.get(&KaniAttributeKind::ConstGeneric).map_or([].as_slice(), Vec::as_slice);
+        let mut ranges: BTreeMap<Symbol, (Span, Vec<i128>)> = attrs
+            .iter()
+            .filter_map(|attr| {
+                parse_const_generic(tcx, attr).map(|(name, values)| (name, (attr.span, values)))
+            })
+            .collect();
+        if ranges.len() != attrs.len() {
+            // The invalid attributes have already been reported.
+            return vec![];
+        }
+        if ranges.is_empty() && !generics.requires_monomorphization(tcx) {
+            return vec![Instance::mono(tcx, self.item)];
+        }
+        let unknown = ranges.iter().find(|(name, _)| {
+            !generics.own_params.iter().any(|param| {
+                param.name == **name && matches!(param.kind, GenericParamDefKind::Const { .. })
+            })
+        });
+        if let Some((name, (attr_span, _))) = unknown {
+            tcx.dcx().span_err(
+                *attr_span,
+                format!("`{name}` is not a const generic parameter of this harness"),
+            );
+            return vec![];
+        }
+
+        let generic_error = || {
+            tcx.dcx()
+                .struct_span_err(
+                    span,
+                    "the `proof` attribute cannot be applied to generic functions",
+                )
+                .with_help(
+                    "const generic parameters can be verified for a range of values with \
+                    `#[kani::const_generic(N, 0..=8)]`",
+                )
+                .emit();
+        };
+        if generics.parent.is_some() {
+            generic_error();
+            return vec![];
+        }
+        let instances =
+            ranges.values().fold(1usize, |count, (_, values)| count.saturating_mul(values.len()));
+        if instances > MAX_CONST_GENERIC_INSTANCES {
+            tcx.dcx().span_err(
+                span,
+                format!(
+                    "the `#[kani::const_generic]` attributes of this harness have {instances} \
+                    combinations of values, more than the maximum of {MAX_CONST_GENERIC_INSTANCES}"
+                ),
+            );
+            return vec![];
+        }
+        let mut all_args: Vec<Vec<GenericArg<'tcx>>> = vec![vec![]];
+        for param in &generics.own_params {
+            let values = match param.kind {
+                GenericParamDefKind::Lifetime => vec![tcx.lifetimes.re_erased.into()],
+                GenericParamDefKind::Const { .. } if ranges.contains_key(&param.name) => {
+                    let (attr_span, values) = ranges.remove(&param.name).unwrap();
+                    let ty = tcx.type_of(param.def_id).instantiate_identity();
+                    match const_generic_values(tcx, ty, &values) {
+                        Ok(values) => values,
+                        Err(msg) => {
+                            tcx.dcx().span_err(attr_span, msg);
+                            return vec![];
+                        }
+                    }
+                }
+                GenericParamDefKind::Const { .. } | GenericParamDefKind::Type { .. } => {
+                    generic_error();
+                    return vec![];
+                }
+            };
+            all_args = all_args
+                .into_iter()
+                .flat_map(|args| {
+                    values.iter().map(move |value| {
+                        let mut args = args.clone();
+                        args.push(*value);
+                        args
+                    })
+                })
+                .collect();
+        }
+        all_args.into_iter().map(|args| Instance::new(self.item, tcx.mk_args(&args))).collect()
+    }
+
     fn stub_for_relative_item(&self, anchor: Symbol, replacement: Symbol) -> Stub {
         let local_id = self.item.expect_local();
         let current_module = self.tcx.parent_module_from_def_id(local_id);
@@ -678,13 +888,41 @@ fn has_kani_attribute<F: Fn(KaniAttributeKind) -> bool>(
 
 /// Same as [`KaniAttributes::is_harness`] but more efficient because less
 /// attribute parsing is performed.
-pub fn is_proof_harness(tcx: TyCtxt, instance: InstanceStable) -> bool {
-    let def_id = rustc_internal::internal(tcx, instance.def.def_id());
+pub fn is_proof_harness(tcx: TyCtxt, item: impl CrateDef) -> bool {
+    let def_id = rustc_internal::internal(tcx, item.def_id());
     has_kani_attribute(tcx, def_id, |a| {
         matches!(a, KaniAttributeKind::Proof | KaniAttributeKind::ProofForContract)
     })
 }
 
+/// Does the given harness recover from panics inside `catch_unwind`?
+pub fn is_catch_unwind_harness(tcx: TyCtxt, instance: InstanceStable) -> bool {
+    let def_id = rustc_internal::internal(tcx, instance.def.def_id());
+    has_kani_attribute(tcx, def_id, |a| matches!(a, KaniAttributeKind::CatchUnwind))
+}
+
+/// Is coverage instrumentation enabled for the given item?
+///
+/// Coverage can be disabled with `#[kani::coverage(off)]` on the item itself or on any item that
+/// encloses it, such as a function (for closures), an `impl` block or a module.
+/// The closest attribute wins, so `#[kani::coverage(on)]` re-enables coverage for an item nested
+/// inside a disabled one. Coverage is enabled by default.
+pub fn is_coverage_enabled(tcx: TyCtxt, item: impl CrateDef) -> bool {
+    let mut current = Some(rustc_internal::internal(tcx, item.def_id()));
+    while let Some(def_id) = current {
+        let enabled = tcx
+            .get_attrs_unchecked(def_id)
+            .iter()
+            .filter(|attr| attr_kind(tcx, attr) == Some(KaniAttributeKind::Coverage))
+            .find_map(parse_coverage);
+        if let Some(enabled) = enabled {
+            return enabled;
+        }
+        current = tcx.opt_parent(def_id);
+    }
+    true
+}
+
 /// Does this `def_id` have `#[rustc_test_marker]`?
 pub fn is_test_harness_description(tcx: TyCtxt, item: impl CrateDef) -> bool {
     let def_id = rustc_internal::internal(tcx, item.def_id());
@@ -891,6 +1129,130 @@ fn parse_stubs(tcx: TyCtxt, harness: DefId, attributes: &[&Attribute]) -> Vec<St
         .collect()
 }
 
+fn parse_slicing(tcx: TyCtxt, attr: &Attribute) -> Option<CbmcSlicing> {
+    const ATTRIBUTE: &str = "#[kani::slicing]";
+    let slicing = match attr.meta_item_list().as_deref() {
+        Some([NestedMetaItem::MetaItem(item)]) if item.is_word() => {
+            item.ident().and_then(|ident| CbmcSlicing::from_str(ident.as_str()).ok())
+        }
+        _ => None,
+    };
+    if slicing.is_none() {
+        tcx.dcx().span_err(
+            attr.span,
+            format!(
+                "invalid argument for `{ATTRIBUTE}` attribute, expected one of `none`, \
+                `formula`, `reachability` or `full`"
+            ),
+        );
+    }
+    slicing
+}
+
+/// The maximum number of values of a `#[kani::const_generic]` attribute.
+const MAX_CONST_GENERIC_VALUES: i128 = 256;
+
+/// The maximum number of instances of a harness with `#[kani::const_generic]` attributes, i.e.,
+/// of combinations of the values of its const generic parameters.
+const MAX_CONST_GENERIC_INSTANCES: usize = 1024;
+
+/// Parse a `#[kani::const_generic(N, 0..=8)]` attribute into the name of the const generic
+/// parameter and the values in the range. The range can be exclusive (`0..8`) or inclusive
+/// (`0..=8`).
+fn parse_const_generic(tcx: TyCtxt, attr: &Attribute) -> Option<(Symbol, Vec<i128>)> {
+    fn parse_int<'a>(trees: &mut impl Iterator<Item = &'a TokenTree>) -> Option<i128> {
+        let mut tree = trees.next()?;
+        let negative = matches!(
+            tree,
+            TokenTree::Token(Token { kind: TokenKind::BinOp(BinOpToken::Minus), .. }, _)
+        );
+        if negative {
+            tree = trees.next()?;
+        }
+        let TokenTree::Token(Token { kind: TokenKind::Literal(lit), .. }, _) = tree else {
+            return None;
+        };
+        if lit.kind != token::LitKind::Integer {
+            return None;
+        }
+        let value: i128 = lit.symbol.as_str().replace('_', "").parse().ok()?;
+        Some(if negative { -value } else { value })
+    }
+
+    let parse = || {
+        let AttrArgs::Delimited(args) = &attr.get_normal_item().args else { return None };
+        let mut trees = args.tokens.trees();
+        let TokenTree::Token(Token { kind: TokenKind::Ident(name, _), .. }, _) = trees.next()?
+        else {
+            return None;
+        };
+        if !matches!(trees.next()?, comma_tok!()) {
+            return None;
+        }
+        let start = parse_int(&mut trees)?;
+        let inclusive = match trees.next()? {
+            TokenTree::Token(Token { kind: TokenKind::DotDot, .. }, _) => false,
+            TokenTree::Token(Token { kind: TokenKind::DotDotEq, .. }, _) => true,
+            _ => return None,
+        };
+        let end = parse_int(&mut trees)?;
+        trees.next().is_none().then_some((*name, start, if inclusive { end + 1 } else { end }))
+    };
+    let Some((name, start, end)) = parse() else {
+        tcx.dcx().span_err(
+            attr.span,
+            "invalid arguments for `#[kani::const_generic]` attribute, expected the name of a \
+            const generic parameter and a range of integers, e.g., `N, 0..=8`",
+        );
+        return None;
+    };
+    if start >= end {
+        tcx.dcx().span_err(attr.span, "the range of `#[kani::const_generic]` is empty");
+        None
+    } else if end - start > MAX_CONST_GENERIC_VALUES {
+        tcx.dcx().span_err(
+            attr.span,
+            format!(
+                "the range of `#[kani::const_generic]` has more than \
+                {MAX_CONST_GENERIC_VALUES} values"
+            ),
+        );
+        None
+    } else {
+        Some((name, (start..end).collect()))
+    }
+}
+
+/// Create the constants of type `ty` for the values of a const generic parameter.
+fn const_generic_values<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    ty: Ty<'tcx>,
+    values: &[i128],
+) -> Result<Vec<GenericArg<'tcx>>, String> {
+    if !ty.is_integral() {
+        return Err(format!(
+            "`#[kani::const_generic]` only supports integer parameters, but found `{ty}`"
+        ));
+    }
+    let size = tcx.layout_of(ParamEnv::reveal_all().and(ty)).unwrap().size;
+    values
+        .iter()
+        .map(|&value| {
+            let bits = size.truncate(value as u128);
+            let fits = if ty.is_signed() {
+                size.sign_extend(bits) as i128 == value
+            } else {
+                value >= 0 && bits == value as u128
+            };
+            if fits {
+                Ok(Const::from_bits(tcx, bits, ParamEnv::empty().and(ty)).into())
+            } else {
+                Err(format!("the value `{value}` doesn't fit in the type `{ty}`"))
+            }
+        })
+        .collect()
+}
+
 fn parse_solver(tcx: TyCtxt, attr: &Attribute) -> Option<CbmcSolver> {
     // TODO: Argument validation should be done as part of the `kani_macros` crate
     // <https://github.com/model-checking/kani/issues/2192>
@@ -943,6 +1305,19 @@ fn parse_solver(tcx: TyCtxt, attr: &Attribute) -> Option<CbmcSolver> {
     }
 }
 
+/// Return whether the `coverage` attribute turns coverage `on` (`true`) or `off` (`false`).
+/// Return `None` if the argument is invalid.
+fn parse_coverage(attr: &Attribute) -> Option<bool> {
+    match attr.meta_item_list()?.as_slice() {
+        [NestedMetaItem::MetaItem(item)] if item.is_word() => match item.ident()?.as_str() {
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Extracts the integer value argument from the attribute provided
 /// For example, `unwind(8)` return `Some(8)`
 fn parse_integer(attr: &Attribute) -> Option<u128> {
@@ -0,0 +1,318 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! MIR analyses used to narrow the possible targets of calls through function pointers, which is
+//! enabled with `-Z restrict-fn-ptrs`.
+//!
+//! By default, CBMC replaces a call through a function pointer by a case split over every function
+//! whose address is taken and whose C signature is compatible with the type of the pointer. In a
+//! Rust program, that includes most of the functions stored in vtables, and the case split blows
+//! up symbolic execution of code that uses callbacks. Instead, we compute:
+//!  - The functions that may be converted to a function pointer in the reachable code, grouped by
+//!    their Rust signature ([FnPtrTargets]).
+//!  - The function pointers that may be stored in the locals of a function whose address isn't
+//!    taken ([LocalFnPtrs]). This is a flow-insensitive analysis, since these locals are usually
+//!    assigned once.
+//!
+//! Codegen then emits a dispatch over the possible targets of each call. A pointer that doesn't
+//! match any of them, e.g., one that was forged with a transmute, makes the call fail a check.
+
+use crate::kani_middle::transform::BodyTransformation;
+use rustc_data_structures::fx::{FxIndexMap, FxIndexSet};
+use rustc_hir::Unsafety;
+use rustc_middle::ty::{self, FnSig, InstanceDef, ParamEnv, PolyFnSig, TyCtxt};
+use rustc_smir::rustc_internal;
+use stable_mir::mir::alloc::GlobalAlloc;
+use stable_mir::mir::mono::{Instance, MonoItem};
+use stable_mir::mir::visit::Location;
+use stable_mir::mir::{
+    Body, CastKind, Constant, Local, MirVisitor, Operand, Place, PointerCoercion, Rvalue,
+    StatementKind, TerminatorKind,
+};
+use stable_mir::ty::{Allocation, ClosureKind, ConstantKind, RigidTy, Ty, TyKind};
+use std::collections::HashMap;
+
+/// The functions that may be the target of a call through a function pointer.
+#[derive(Debug, Default)]
+pub struct FnPtrTargets<'tcx> {
+    /// The functions converted to a function pointer, indexed by their normalized signature.
+    by_sig: FxIndexMap<FnSig<'tcx>, FxIndexSet<Instance>>,
+    /// The functions whose signature we couldn't compute. These may be the target of any call.
+    unknown_sig: FxIndexSet<Instance>,
+}
+
+impl<'tcx> FnPtrTargets<'tcx> {
+    /// Collect the functions whose address may be taken in the given reachable items.
+    pub fn new(
+        tcx: TyCtxt<'tcx>,
+        transformer: &mut BodyTransformation,
+        items: &[MonoItem],
+    ) -> FnPtrTargets<'tcx> {
+        let mut targets = FnPtrTargets::default();
+        for item in items {
+            match item {
+                MonoItem::Fn(instance) if instance.has_body() => {
+                    let body = transformer.body(tcx, *instance);
+                    AddressTakenVisitor { tcx, body: &body, targets: &mut targets }
+                        .visit_body(&body);
+                }
+                MonoItem::Static(def) => {
+                    if let Ok(init) = def.eval_initializer() {
+                        targets.add_allocation(tcx, &init);
+                    }
+                }
+                MonoItem::Fn(_) | MonoItem::GlobalAsm(_) => {}
+            }
+        }
+        targets
+    }
+
+    /// The functions that a pointer with the given signature may point to.
+    pub fn targets(&self, tcx: TyCtxt<'tcx>, sig: PolyFnSig<'tcx>) -> Vec<Instance> {
+        let sig = normalize_sig(tcx, sig);
+        self.by_sig.get(&sig).into_iter().flatten().chain(&self.unknown_sig).copied().collect()
+    }
+
+    fn add(&mut self, tcx: TyCtxt<'tcx>, instance: Instance, sig: Option<PolyFnSig<'tcx>>) {
+        match sig {
+            Some(sig) => {
+                self.by_sig.entry(normalize_sig(tcx, sig)).or_default().insert(instance);
+            }
+            None => {
+                self.unknown_sig.insert(instance);
+            }
+        }
+    }
+
+    /// Add the functions referenced by an allocation, e.g., by a table of function pointers.
+    ///
+    /// Statics are collected as items, and vtables are only accessed by virtual calls, so we don't
+    /// traverse them.
+    fn add_allocation(&mut self, tcx: TyCtxt<'tcx>, allocation: &Allocation) {
+        for (_, prov) in &allocation.provenance.ptrs {
+            match GlobalAlloc::from(prov.0) {
+                GlobalAlloc::Function(instance) => {
+                    self.add(tcx, instance, instance_sig(tcx, instance));
+                }
+                GlobalAlloc::Memory(alloc) => self.add_allocation(tcx, &alloc),
+                GlobalAlloc::Static(_) | GlobalAlloc::VTable(..) => {}
+            }
+        }
+    }
+}
+
+/// Collect the functions that are converted to a function pointer in a body.
+struct AddressTakenVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    body: &'a Body,
+    targets: &'a mut FnPtrTargets<'tcx>,
+}
+
+impl<'a, 'tcx> MirVisitor for AddressTakenVisitor<'a, 'tcx> {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue, location: Location) {
+        if let Rvalue::Cast(CastKind::PointerCoercion(coercion), operand, target_ty) = rvalue
+            && let Some(instance) =
+                reified_instance(coercion, operand.ty(self.body.locals()).unwrap())
+        {
+            let sig = rustc_internal::internal(self.tcx, target_ty).fn_sig(self.tcx);
+            self.targets.add(self.tcx, instance, Some(sig));
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_constant(&mut self, constant: &Constant, location: Location) {
+        if let ConstantKind::Allocated(allocation) = constant.literal.kind() {
+            self.targets.add_allocation(self.tcx, allocation);
+        }
+        self.super_constant(constant, location);
+    }
+}
+
+/// The function pointers that may be stored in each local of a function.
+#[derive(Debug)]
+pub struct LocalFnPtrs {
+    /// The possible values of the function pointer locals whose value is known.
+    targets: HashMap<Local, Vec<Instance>>,
+}
+
+/// The value assigned to a function pointer local.
+enum FnPtrValue {
+    /// One of the given functions.
+    Fns(Vec<Instance>),
+    /// The value stored in another local.
+    Local(Local),
+    /// A value we don't know anything about, e.g., the result of a call.
+    Unknown,
+}
+
+impl LocalFnPtrs {
+    pub fn new(body: &Body) -> LocalFnPtrs {
+        let is_fn_ptr = |local: usize| {
+            matches!(body.locals()[local].ty.kind(), TyKind::RigidTy(RigidTy::FnPtr(_)))
+        };
+        let mut values: HashMap<_, Vec<FnPtrValue>> = (0..body.locals().len())
+            .filter(|local| is_fn_ptr(*local))
+            .map(|local| (local, vec![]))
+            .collect();
+        // The arguments are initialized by the caller.
+        for arg in body.arg_locals().iter().enumerate().map(|(idx, _)| idx + 1) {
+            values.entry(arg).and_modify(|defs| defs.push(FnPtrValue::Unknown));
+        }
+        let mut set_unknown = |place: &Place| {
+            values.entry(place.local).and_modify(|defs| defs.push(FnPtrValue::Unknown));
+        };
+        let mut assignments = vec![];
+        for bb in &body.blocks {
+            for stmt in &bb.statements {
+                if let StatementKind::Assign(place, rvalue) = &stmt.kind {
+                    match rvalue {
+                        // The local may be modified through the reference.
+                        Rvalue::Ref(_, _, borrowed) | Rvalue::AddressOf(_, borrowed) => {
+                            set_unknown(borrowed)
+                        }
+                        _ => {}
+                    }
+                    if place.projection.is_empty() && is_fn_ptr(place.local) {
+                        assignments.push((place.local, fn_ptr_value(body, rvalue)));
+                    }
+                }
+            }
+            match &bb.terminator.kind {
+                TerminatorKind::Call { destination, .. } => set_unknown(destination),
+                TerminatorKind::InlineAsm { operands, .. } => operands
+                    .iter()
+                    .filter_map(|op| op.out_place.as_ref())
+                    .for_each(&mut set_unknown),
+                _ => {}
+            }
+        }
+        for (local, value) in assignments {
+            values.get_mut(&local).unwrap().push(value);
+        }
+
+        // Propagate the values through copies until we reach a fixpoint.
+        let mut targets: HashMap<_, Option<Vec<Instance>>> =
+            values.keys().map(|local| (*local, Some(vec![]))).collect();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (local, defs) in &values {
+                let mut result = Some(vec![]);
+                for def in defs {
+                    let fns = match def {
+                        FnPtrValue::Fns(fns) => Some(fns.clone()),
+                        FnPtrValue::Local(other) => targets[other].clone(),
+                        FnPtrValue::Unknown => None,
+                    };
+                    result = result.zip(fns).map(|(mut result, fns)| {
+                        for instance in fns {
+                            if !result.contains(&instance) {
+                                result.push(instance);
+                            }
+                        }
+                        result
+                    });
+                }
+                if targets[local] != result {
+                    targets.insert(*local, result);
+                    changed = true;
+                }
+            }
+        }
+        LocalFnPtrs {
+            targets: targets
+                .into_iter()
+                .filter_map(|(local, fns)| {
+                    fns.filter(|fns| !fns.is_empty()).map(|fns| (local, fns))
+                })
+                .collect(),
+        }
+    }
+
+    /// The functions that a function pointer operand may point to, if they are known.
+    pub fn targets(&self, operand: &Operand) -> Option<Vec<Instance>> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) if place.projection.is_empty() => {
+                self.targets.get(&place.local).cloned()
+            }
+            Operand::Constant(constant) => constant_fn(constant).map(|instance| vec![instance]),
+            Operand::Copy(_) | Operand::Move(_) => None,
+        }
+    }
+}
+
+/// The value that an rvalue of function pointer type evaluates to.
+fn fn_ptr_value(body: &Body, rvalue: &Rvalue) -> FnPtrValue {
+    let operand_value = |operand: &Operand| match operand {
+        Operand::Copy(place) | Operand::Move(place) if place.projection.is_empty() => {
+            FnPtrValue::Local(place.local)
+        }
+        Operand::Constant(constant) => constant_fn(constant)
+            .map_or(FnPtrValue::Unknown, |instance| FnPtrValue::Fns(vec![instance])),
+        Operand::Copy(_) | Operand::Move(_) => FnPtrValue::Unknown,
+    };
+    match rvalue {
+        Rvalue::Cast(CastKind::PointerCoercion(PointerCoercion::UnsafeFnPointer), operand, _)
+        | Rvalue::Use(operand) => operand_value(operand),
+        Rvalue::Cast(CastKind::PointerCoercion(coercion), operand, _) => {
+            reified_instance(coercion, operand.ty(body.locals()).unwrap())
+                .map_or(FnPtrValue::Unknown, |instance| FnPtrValue::Fns(vec![instance]))
+        }
+        _ => FnPtrValue::Unknown,
+    }
+}
+
+/// The function that a constant function pointer points to.
+fn constant_fn(constant: &Constant) -> Option<Instance> {
+    let ConstantKind::Allocated(allocation) = constant.literal.kind() else { return None };
+    match allocation.provenance.ptrs.as_slice() {
+        [(0, prov)] => match GlobalAlloc::from(prov.0) {
+            GlobalAlloc::Function(instance) => Some(instance),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The function that a cast from a function item or a closure to a function pointer points to.
+///
+/// This has to match the function that codegen takes the address of.
+fn reified_instance(coercion: &PointerCoercion, source_ty: Ty) -> Option<Instance> {
+    match (coercion, source_ty.kind()) {
+        (PointerCoercion::ReifyFnPointer, TyKind::RigidTy(RigidTy::FnDef(def, args))) => {
+            Instance::resolve(def, &args).ok()
+        }
+        (PointerCoercion::ClosureFnPointer(_), TyKind::RigidTy(RigidTy::Closure(def, args))) => {
+            Instance::resolve_closure(def, &args, ClosureKind::FnOnce).ok()
+        }
+        _ => None,
+    }
+}
+
+/// The signature of the function pointer that points to the given function, if we can compute it.
+fn instance_sig<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance) -> Option<PolyFnSig<'tcx>> {
+    let instance = rustc_internal::internal(tcx, instance);
+    let closure_sig = |closure_ty: ty::Ty<'tcx>| match closure_ty.kind() {
+        ty::Closure(_, args) => {
+            Some(tcx.signature_unclosure(args.as_closure().sig(), Unsafety::Normal))
+        }
+        _ => None,
+    };
+    match instance.def {
+        // The function pointer to a closure points to its `FnOnce::call_once` shim.
+        InstanceDef::ClosureOnceShim { .. } => closure_sig(instance.args.type_at(0)),
+        _ => {
+            let ty = instance.ty(tcx, ParamEnv::reveal_all());
+            match ty.kind() {
+                ty::FnDef(..) => Some(ty.fn_sig(tcx)),
+                _ => closure_sig(ty),
+            }
+        }
+    }
+}
+
+/// Erase the lifetimes and the safety of a signature, which don't change the possible targets.
+fn normalize_sig<'tcx>(tcx: TyCtxt<'tcx>, sig: PolyFnSig<'tcx>) -> FnSig<'tcx> {
+    let sig = tcx.erase_regions(tcx.instantiate_bound_regions_with_erased(sig));
+    FnSig { unsafety: Unsafety::Normal, ..sig }
+}
@@ -21,7 +21,7 @@
 use rustc_span::Span;
 use rustc_target::abi::call::FnAbi;
 use rustc_target::abi::{HasDataLayout, TargetDataLayout};
-use stable_mir::mir::mono::{InstanceKind, MonoItem};
+use stable_mir::mir::mono::{Instance, InstanceKind, MonoItem, StaticDef};
 use stable_mir::ty::{FnDef, RigidTy, Span as SpanStable, TyKind};
 use stable_mir::{CrateDef, DefId};
 use std::fs::File;
@@ -33,6 +33,7 @@
 pub mod analysis;
 pub mod attributes;
 pub mod coercion;
+pub mod fn_ptr;
 mod intrinsics;
 pub mod metadata;
 pub mod provide;
@@ -40,6 +41,7 @@
 pub mod resolve;
 pub mod stubbing;
 pub mod transform;
+pub mod uninit;
 
 /// Check that all crate items are supported and there's no misconfiguration.
 /// This method will exhaustively print any error / warning and it will abort at the end if any
@@ -67,6 +69,47 @@ pub fn check_crate_items(tcx: TyCtxt, ignore_asm: bool) {
     tcx.dcx().abort_if_errors();
 }
 
+/// Check that the body of `instance` doesn't use `f16` or `f128`, which the stable MIR of the
+/// current toolchain can't represent yet, and report an error otherwise.
+///
+/// Retrieving the body of such an instance in stable MIR ICEs, so only reachable instances are
+/// checked, before their body is collected, using internal APIs.
+/// Tracked here: <https://github.com/model-checking/kani/issues/3069>
+pub fn check_unsupported_floats(tcx: TyCtxt, instance: Instance) -> bool {
+    if !instance.has_body() {
+        return true;
+    }
+    let internal_instance = rustc_internal::internal(tcx, instance);
+    let body = tcx.instance_mir(internal_instance.def);
+    let unsupported = body.local_decls.iter().find_map(|local| {
+        let ty = internal_instance.instantiate_mir_and_normalize_erasing_regions(
+            tcx,
+            ty::ParamEnv::reveal_all(),
+            ty::EarlyBinder::bind(local.ty),
+        );
+        ty.walk()
+            .any(|arg| {
+                matches!(
+                    arg.as_type().map(|ty| ty.kind()),
+                    Some(ty::Float(ty::FloatTy::F16 | ty::FloatTy::F128))
+                )
+            })
+            .then_some((local.source_info.span, ty))
+    });
+    if let Some((span, ty)) = unsupported {
+        tcx.dcx().span_err(
+            span,
+            format!(
+                "`{}` uses `{ty}`, which is not supported by Kani yet.",
+                tcx.def_path_str(internal_instance.def_id())
+            ),
+        );
+        false
+    } else {
+        true
+    }
+}
+
 /// Check that all given items are supported and there's no misconfiguration.
 /// This method will exhaustively print any error / warning and it will abort at the end if any
 /// error was found.
@@ -76,6 +119,8 @@ pub fn check_reachable_items(tcx: TyCtxt, queries: &QueryDb, items: &[MonoItem])
     for item in items.iter().filter(|i| matches!(i, MonoItem::Fn(..) | MonoItem::Static(..))) {
         let def_id = match item {
             MonoItem::Fn(instance) => instance.def.def_id(),
+            // Nested statics are synthesized by rustc, so they can't have attributes.
+            MonoItem::Static(def) if is_nested_static(tcx, *def) => continue,
             MonoItem::Static(def) => def.def_id(),
             MonoItem::GlobalAsm(_) => {
                 unreachable!()
@@ -146,6 +191,17 @@ pub fn new(span: SpanStable) -> Self {
     }
 }
 
+/// Whether the static was created by rustc for the memory nested in the initializer of another
+/// static, e.g., the array in `static mut S: &mut [u8] = &mut [1, 2, 3]`.
+///
+/// These statics don't have a type, only an evaluated initializer.
+pub fn is_nested_static(tcx: TyCtxt, def: StaticDef) -> bool {
+    matches!(
+        tcx.def_kind(rustc_internal::internal(tcx, def.def_id())),
+        DefKind::Static { nested: true, .. }
+    )
+}
+
 /// Get the FnAbi of a given instance with no extra variadic arguments.
 /// TODO: Get rid of this. Use instance.fn_abi() instead.
 /// <https://github.com/model-checking/kani/issues/1365>
@@ -21,6 +21,7 @@
 use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::FxHashSet;
 use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_middle::ty::{TyCtxt, VtblEntry};
 use rustc_smir::rustc_internal;
 use stable_mir::mir::alloc::{AllocId, GlobalAlloc};
@@ -34,10 +35,12 @@
 use stable_mir::{CrateDef, ItemKind};
 
 use crate::kani_middle::attributes::matches_diagnostic as matches_function;
+use crate::kani_middle::attributes::{is_proof_harness, KaniAttributes};
 use crate::kani_middle::coercion;
 use crate::kani_middle::coercion::CoercionBase;
 use crate::kani_middle::stubbing::{get_stub, validate_instance};
 use crate::kani_middle::transform::BodyTransformation;
+use crate::kani_middle::{check_unsupported_floats, is_nested_static};
 
 /// Collect all reachable items starting from the given starting points.
 pub fn collect_reachable_items(
@@ -51,6 +54,11 @@ pub fn collect_reachable_items(
     for item in starting_points {
         collector.collect(item.clone());
     }
+    // Calls to the allocation functions are calls to foreign items, so we need to collect the
+    // definitions generated for a `#[global_allocator]` explicitly.
+    for instance in global_allocator_fns(tcx) {
+        collector.collect(MonoItem::Fn(instance));
+    }
 
     #[cfg(debug_assertions)]
     collector
@@ -67,6 +75,24 @@ pub fn collect_reachable_items(
     sorted_items
 }
 
+/// The allocation functions that are generated for a `#[global_allocator]`.
+const GLOBAL_ALLOCATOR_FNS: [&str; 4] =
+    ["__rust_alloc", "__rust_alloc_zeroed", "__rust_dealloc", "__rust_realloc"];
+
+/// Collect the allocation functions generated for the `#[global_allocator]` of this crate, if it
+/// defines one.
+///
+/// These definitions replace the default ones from `kani_lib.c`. A global allocator defined by a
+/// dependency isn't collected, and the default allocation functions are used instead.
+fn global_allocator_fns(tcx: TyCtxt) -> Vec<Instance> {
+    if !tcx.has_global_allocator(LOCAL_CRATE) {
+        return vec![];
+    }
+    filter_crate_items(tcx, |_, instance| {
+        GLOBAL_ALLOCATOR_FNS.contains(&instance.mangled_name().as_str())
+    })
+}
+
 /// Collect all (top-level) items in the crate that matches the given predicate.
 /// An item can only be a root if they are a non-generic function.
 pub fn filter_crate_items<F>(tcx: TyCtxt, predicate: F) -> Vec<Instance>
@@ -93,6 +119,23 @@ pub fn filter_crate_items<F>(tcx: TyCtxt, predicate: F) -> Vec<Instance>
         .collect::<Vec<_>>()
 }
 
+/// Collect all the proof harnesses in the crate that match the given predicate.
+///
+/// Unlike [filter_crate_items], this includes harnesses with const generic parameters annotated
+/// with `#[kani::const_generic]`, which yield one instance per combination of the values of their
+/// parameters.
+pub fn filter_crate_harnesses<F>(tcx: TyCtxt, predicate: F) -> Vec<Instance>
+where
+    F: Fn(TyCtxt, Instance) -> bool,
+{
+    stable_mir::all_local_items()
+        .iter()
+        .filter(|item| matches!(item.kind(), ItemKind::Fn) && is_proof_harness(tcx, **item))
+        .flat_map(|item| KaniAttributes::for_def_id(tcx, item.def_id()).harness_instances())
+        .filter(|instance| predicate(tcx, *instance))
+        .collect()
+}
+
 /// Use a predicate to find `const` declarations, then extract all items reachable from them.
 ///
 /// Probably only specifically useful with a predicate to find `TestDescAndFn` const declarations from
@@ -185,7 +228,7 @@ fn reachable_items(&mut self) {
     /// Visit a function and collect all mono-items reachable from its instructions.
     fn visit_fn(&mut self, instance: Instance) -> Vec<MonoItem> {
         let _guard = debug_span!("visit_fn", function=?instance).entered();
-        if validate_instance(self.tcx, instance) {
+        if validate_instance(self.tcx, instance) && check_unsupported_floats(self.tcx, instance) {
             let body = self.transformer.body(self.tcx, instance);
             let mut collector = MonoItemsFnCollector {
                 tcx: self.tcx,
@@ -205,10 +248,12 @@ fn visit_static(&mut self, def: StaticDef) -> Vec<MonoItem> {
         let _guard = debug_span!("visit_static", ?def).entered();
         let mut next_items = vec![];
 
-        // Collect drop function.
-        let static_ty = def.ty();
-        let instance = Instance::resolve_drop_in_place(static_ty);
-        next_items.push(instance.into());
+        // Collect drop function. Nested statics don't have a type, and they are never dropped.
+        if !is_nested_static(self.tcx, def) {
+            let static_ty = def.ty();
+            let instance = Instance::resolve_drop_in_place(static_ty);
+            next_items.push(instance.into());
+        }
 
         // Collect initialization.
         let alloc = def.eval_initializer().unwrap();
@@ -474,8 +519,8 @@ fn visit_terminator(&mut self, terminator: &Terminator, location: Location) {
                 self.collect_instance(instance, true);
             }
             TerminatorKind::InlineAsm { .. } => {
-                // We don't support inline assembly. This shall be replaced by an unsupported
-                // construct during codegen.
+                // Inline assembly doesn't call any Rust function. It's either replaced by an
+                // unsupported construct or modeled with nondeterministic outputs during codegen.
             }
             TerminatorKind::Abort { .. } | TerminatorKind::Assert { .. } => {
                 // We generate code for this without invoking any lang item.
@@ -185,6 +185,32 @@ pub fn add_check(
         }
     }
 
+    /// Add a call to the given function before the source instruction.
+    ///
+    /// Similar to [MutableBody::add_check], the basic block will be split, and the source
+    /// instruction will be adjusted to point to the first instruction in the new basic block.
+    pub fn add_call(
+        &mut self,
+        callee: &Instance,
+        source: &mut SourceInstruction,
+        args: Vec<Operand>,
+        destination: Place,
+    ) {
+        let new_bb = self.blocks.len();
+        let span = source.span(&self.blocks);
+        let callee_op =
+            Operand::Copy(Place::from(self.new_local(callee.ty(), span, Mutability::Not)));
+        let kind = TerminatorKind::Call {
+            func: callee_op,
+            args,
+            destination,
+            target: Some(new_bb),
+            unwind: UnwindAction::Terminate,
+        };
+        let terminator = Terminator { kind, span };
+        self.split_bb(source, terminator);
+    }
+
     /// Split a basic block right before the source location and use the new terminator
     /// in the basic block that was split.
     ///
@@ -270,6 +296,6 @@ pub fn span(&self, blocks: &[BasicBlock]) -> Span {
     }
 }
 
-fn find_instance(tcx: TyCtxt, diagnostic: &str) -> Option<Instance> {
+pub fn find_instance(tcx: TyCtxt, diagnostic: &str) -> Option<Instance> {
     Instance::resolve(find_fn_def(tcx, diagnostic)?, &GenericArgs(vec![])).ok()
 }
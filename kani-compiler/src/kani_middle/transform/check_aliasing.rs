@@ -0,0 +1,499 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Implement a transformation pass that instrument the code to detect violations of the Rust
+//! aliasing model, which are considered UB.
+//!
+//! The checks follow the Tree Borrows model: <https://perso.crans.org/vanille/treebor/>.
+//! Every reference created from a raw pointer dereference, e.g. `&mut *ptr`, is tracked with a
+//! new local that stores its permission. Accesses through the reference, or through pointers
+//! derived from it, are "child" accesses, while all other accesses to the same memory are
+//! "foreign" accesses. A foreign write disables the reference, and any later access through it
+//! is reported as UB. A foreign read freezes a mutable reference that has been written to, and
+//! any later write through it is reported as UB.
+//!
+//! Note that the analysis is still limited:
+//!   1. It is intra-procedural. References are only tracked inside the function where they are
+//!      created from a raw pointer, and accesses done by other functions aren't considered.
+//!   2. A local is considered derived from a reference if it is ever assigned a value derived
+//!      from it, regardless of the control flow.
+//!   3. Only accesses through a single dereference, e.g. `(*ptr).field`, or direct accesses to
+//!      locals whose address was taken, are instrumented.
+//!   4. References to types with interior mutability and to unsized types aren't tracked.
+use crate::args::ExtraChecks;
+use crate::kani_middle::transform::body::{
+    find_instance, CheckType, MutableBody, SourceInstruction,
+};
+use crate::kani_middle::transform::{TransformPass, TransformationType};
+use crate::kani_queries::QueryDb;
+use rustc_middle::ty::{ParamEnv, TyCtxt};
+use rustc_smir::rustc_internal;
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::{
+    BinOp, Body, BorrowKind, Constant, Local, LocalDecl, Mutability, NonDivergingIntrinsic,
+    Operand, Place, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
+};
+use stable_mir::ty::{Const, Span, Ty, UintTy};
+use std::collections::HashSet;
+use tracing::{debug, trace};
+
+/// Instrument the code with checks for aliasing violations.
+#[derive(Debug)]
+pub struct AliasingPass {
+    check_type: CheckType,
+    /// The functions of the `kani` library that implement the permission updates.
+    model: Option<AliasingModel>,
+}
+
+#[derive(Debug)]
+struct AliasingModel {
+    retag: Instance,
+    child_access: Instance,
+    foreign_access: Instance,
+}
+
+impl AliasingPass {
+    pub fn new(tcx: TyCtxt) -> Self {
+        let model = || {
+            Some(AliasingModel {
+                retag: find_instance(tcx, "KaniAliasingRetag")?,
+                child_access: find_instance(tcx, "KaniAliasingChildAccess")?,
+                foreign_access: find_instance(tcx, "KaniAliasingForeignAccess")?,
+            })
+        };
+        AliasingPass { check_type: CheckType::new(tcx), model: model() }
+    }
+}
+
+impl TransformPass for AliasingPass {
+    fn transformation_type() -> TransformationType
+    where
+        Self: Sized,
+    {
+        TransformationType::Instrumentation
+    }
+
+    fn is_enabled(&self, query_db: &QueryDb) -> bool
+    where
+        Self: Sized,
+    {
+        let args = query_db.args();
+        args.ub_check.contains(&ExtraChecks::Aliasing)
+    }
+
+    /// Add the permission updates and checks for every reference created from a raw pointer.
+    fn transform(&self, tcx: TyCtxt, body: Body, instance: Instance) -> (bool, Body) {
+        trace!(function=?instance.name(), "transform");
+        let Some(model) = &self.model else {
+            // The `kani` library isn't available.
+            return (false, body);
+        };
+        let mut new_body = MutableBody::from(body);
+        let analysis = AliasingAnalysis::new(tcx, &new_body);
+        if analysis.tracked.is_empty() {
+            return (false, new_body.into());
+        }
+        debug!(tracked=?analysis.tracked, "transform");
+
+        let span = new_body.blocks()[0]
+            .statements
+            .first()
+            .map_or_else(|| new_body.blocks()[0].terminator.span, |stmt| stmt.span);
+        let states: Vec<_> = analysis
+            .tracked
+            .iter()
+            .map(|_| new_body.new_local(Ty::unsigned_ty(UintTy::U8), span, Mutability::Mut))
+            .collect();
+
+        // Instrument the instructions in reverse order, so the positions of the instructions that
+        // haven't been instrumented yet aren't affected by the new statements.
+        let orig_len = new_body.blocks().len();
+        for bb in (0..orig_len).rev() {
+            let num_stmts = new_body.blocks()[bb].statements.len();
+            let mut source = SourceInstruction::Terminator { bb };
+            let accesses = analysis.terminator_accesses(&new_body.blocks()[bb].terminator);
+            self.instrument_accesses(tcx, &mut new_body, &analysis, &states, &mut source, accesses);
+            for idx in (0..num_stmts).rev() {
+                let stmt = new_body.blocks()[bb].statements[idx].clone();
+                if let Some((tracked, is_mut)) = analysis.retag(&stmt) {
+                    // Initialize the permission right after the reference is created.
+                    let mut after = SourceInstruction::Statement { idx: idx + 1, bb };
+                    if idx + 1 == new_body.blocks()[bb].statements.len() {
+                        after = SourceInstruction::Terminator { bb };
+                    }
+                    new_body.add_call(
+                        &model.retag,
+                        &mut after,
+                        vec![bool_operand(is_mut, stmt.span)],
+                        Place::from(states[tracked]),
+                    );
+                }
+                let mut source = SourceInstruction::Statement { idx, bb };
+                let accesses = analysis.statement_accesses(&stmt);
+                self.instrument_accesses(
+                    tcx,
+                    &mut new_body,
+                    &analysis,
+                    &states,
+                    &mut source,
+                    accesses,
+                );
+            }
+        }
+
+        // Every reference starts untracked.
+        let mut first = SourceInstruction::Statement { idx: 0, bb: 0 };
+        if new_body.blocks()[0].statements.is_empty() {
+            first = SourceInstruction::Terminator { bb: 0 };
+        }
+        for state in states {
+            let untracked = new_body.new_const_operand(0, UintTy::U8, span);
+            let stmt = Statement {
+                kind: StatementKind::Assign(Place::from(state), Rvalue::Use(untracked)),
+                span,
+            };
+            new_body.insert_stmt(stmt, &mut first);
+        }
+        (true, new_body.into())
+    }
+}
+
+impl AliasingPass {
+    /// Add the instrumentation for the given accesses before the source instruction.
+    fn instrument_accesses(
+        &self,
+        tcx: TyCtxt,
+        body: &mut MutableBody,
+        analysis: &AliasingAnalysis,
+        states: &[Local],
+        source: &mut SourceInstruction,
+        accesses: Vec<Access>,
+    ) {
+        let model = self.model.as_ref().unwrap();
+        for access in accesses {
+            let span = source.span(body.blocks());
+            let Some(size) = place_size(body.locals(), &access.place) else { continue };
+            for (tracked, reference) in analysis.tracked.iter().enumerate() {
+                let child = match access.ptr {
+                    Some(ptr) => reference.family.contains(&ptr),
+                    None => false,
+                };
+                let state_ptr = body.new_assignment(
+                    Rvalue::AddressOf(Mutability::Mut, Place::from(states[tracked])),
+                    source,
+                );
+                if child {
+                    let allowed = body.new_local(Ty::bool_ty(), span, Mutability::Not);
+                    body.add_call(
+                        &model.child_access,
+                        source,
+                        vec![move_local(state_ptr), bool_operand(access.is_write, span)],
+                        Place::from(allowed),
+                    );
+                    let msg = if access.is_write {
+                        "Undefined Behavior: write through a reference that was invalidated by a \
+                        conflicting access"
+                    } else {
+                        "Undefined Behavior: read through a reference that was invalidated by a \
+                        conflicting write"
+                    };
+                    body.add_check(tcx, &self.check_type, source, allowed, msg);
+                } else {
+                    let tracked_ptr = body.new_assignment(
+                        Rvalue::AddressOf(
+                            Mutability::Not,
+                            Place {
+                                local: reference.local,
+                                projection: vec![ProjectionElem::Deref],
+                            },
+                        ),
+                        source,
+                    );
+                    let tracked_bytes = body.new_cast_ptr(
+                        move_local(tracked_ptr),
+                        Ty::unsigned_ty(UintTy::U8),
+                        Mutability::Not,
+                        source,
+                    );
+                    let access_ptr = body.new_assignment(
+                        Rvalue::AddressOf(Mutability::Not, access.place.clone()),
+                        source,
+                    );
+                    let access_bytes = body.new_cast_ptr(
+                        move_local(access_ptr),
+                        Ty::unsigned_ty(UintTy::U8),
+                        Mutability::Not,
+                        source,
+                    );
+                    let args = vec![
+                        move_local(state_ptr),
+                        move_local(tracked_bytes),
+                        body.new_const_operand(reference.size as _, UintTy::Usize, span),
+                        move_local(access_bytes),
+                        body.new_const_operand(size as _, UintTy::Usize, span),
+                        bool_operand(access.is_write, span),
+                    ];
+                    let ret = body.new_local(Ty::new_tuple(&[]), span, Mutability::Not);
+                    body.add_call(&model.foreign_access, source, args, Place::from(ret));
+                }
+            }
+        }
+    }
+}
+
+/// A reference created from a raw pointer dereference.
+#[derive(Clone, Debug)]
+struct TrackedRef {
+    /// The local that stores the reference.
+    local: Local,
+    /// The size of the referenced memory.
+    size: usize,
+    /// The locals that may be derived from the reference, including the reference itself.
+    family: HashSet<Local>,
+}
+
+/// A memory access done by an instruction.
+#[derive(Clone, Debug)]
+struct Access {
+    place: Place,
+    /// The pointer dereferenced by the place, or `None` for a direct access to a local.
+    ptr: Option<Local>,
+    is_write: bool,
+}
+
+/// The result of the analysis of the original function body.
+struct AliasingAnalysis {
+    /// The locals of the original function body.
+    locals: Vec<LocalDecl>,
+    tracked: Vec<TrackedRef>,
+    /// The locals whose address is taken.
+    exposed: HashSet<Local>,
+}
+
+impl AliasingAnalysis {
+    fn new(tcx: TyCtxt, body: &MutableBody) -> Self {
+        let locals = body.locals().to_vec();
+        let mut analysis = AliasingAnalysis { locals, tracked: vec![], exposed: HashSet::new() };
+        let assignments: Vec<_> = body
+            .blocks()
+            .iter()
+            .flat_map(|bb| &bb.statements)
+            .filter_map(|stmt| match &stmt.kind {
+                StatementKind::Assign(place, rvalue) => Some((place, rvalue)),
+                _ => None,
+            })
+            .collect();
+        for (place, rvalue) in &assignments {
+            if let Rvalue::Ref(_, _, borrowed) | Rvalue::AddressOf(_, borrowed) = rvalue {
+                if !borrowed.projection.contains(&ProjectionElem::Deref) {
+                    analysis.exposed.insert(borrowed.local);
+                }
+            }
+            let Some(reference) = analysis.tracked_ref(tcx, place, rvalue) else { continue };
+            if !analysis.tracked.iter().any(|tracked| tracked.local == reference.local) {
+                analysis.tracked.push(reference);
+            }
+        }
+
+        // Compute the locals derived from each reference until we reach a fixed point.
+        for tracked in &mut analysis.tracked {
+            let mut changed = true;
+            while changed {
+                changed = false;
+                for (place, rvalue) in &assignments {
+                    if place.projection.is_empty()
+                        && !tracked.family.contains(&place.local)
+                        && derived_from(rvalue).is_some_and(|src| tracked.family.contains(&src))
+                    {
+                        tracked.family.insert(place.local);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        analysis
+    }
+
+    /// Return the reference created by this assignment if it should be tracked.
+    fn tracked_ref(&self, tcx: TyCtxt, place: &Place, rvalue: &Rvalue) -> Option<TrackedRef> {
+        let Rvalue::Ref(_, BorrowKind::Mut { .. } | BorrowKind::Shared, borrowed) = rvalue else {
+            return None;
+        };
+        if !place.projection.is_empty()
+            || self.deref_ptr(borrowed).is_none()
+            || !self.locals[borrowed.local].ty.kind().is_raw_ptr()
+        {
+            return None;
+        }
+        let pointee_ty = borrowed.ty(&self.locals).ok()?;
+        let size = ty_size(pointee_ty)?;
+        let internal_ty = rustc_internal::internal(tcx, pointee_ty);
+        if size == 0 || !internal_ty.is_freeze(tcx, ParamEnv::reveal_all()) {
+            return None;
+        }
+        Some(TrackedRef { local: place.local, size, family: HashSet::from([place.local]) })
+    }
+
+    /// If the statement creates a tracked reference, return its index, and whether it's mutable.
+    fn retag(&self, stmt: &Statement) -> Option<(usize, bool)> {
+        let StatementKind::Assign(place, Rvalue::Ref(_, kind, borrowed)) = &stmt.kind else {
+            return None;
+        };
+        if !place.projection.is_empty() || self.deref_ptr(borrowed).is_none() {
+            return None;
+        }
+        let tracked = self.tracked.iter().position(|tracked| tracked.local == place.local)?;
+        Some((tracked, matches!(kind, BorrowKind::Mut { .. })))
+    }
+
+    /// The pointer dereferenced by the place, if the place has a single dereference that is
+    /// the first projection.
+    fn deref_ptr(&self, place: &Place) -> Option<Local> {
+        match place.projection.split_first() {
+            Some((ProjectionElem::Deref, rest)) if !rest.contains(&ProjectionElem::Deref) => {
+                Some(place.local)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the access to the given place if it should be instrumented.
+    fn access(&self, place: &Place, is_write: bool) -> Option<Access> {
+        if let Some(ptr) = self.deref_ptr(place) {
+            Some(Access { place: place.clone(), ptr: Some(ptr), is_write })
+        } else if !place.projection.contains(&ProjectionElem::Deref)
+            && self.exposed.contains(&place.local)
+        {
+            Some(Access { place: place.clone(), ptr: None, is_write })
+        } else {
+            None
+        }
+    }
+
+    fn operand_access(&self, operand: &Operand) -> Option<Access> {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.access(place, false),
+            Operand::Constant(_) => None,
+        }
+    }
+
+    /// The memory accesses done by the statement, in the order they happen.
+    fn statement_accesses(&self, stmt: &Statement) -> Vec<Access> {
+        // Leave it as an exhaustive match to be notified when a new kind is added.
+        match &stmt.kind {
+            StatementKind::Assign(place, rvalue) => {
+                let mut accesses = self.rvalue_accesses(rvalue);
+                accesses.extend(self.access(place, true));
+                accesses
+            }
+            StatementKind::SetDiscriminant { place, .. } | StatementKind::Deinit(place) => {
+                self.access(place, true).into_iter().collect()
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(operand)) => {
+                self.operand_access(operand).into_iter().collect()
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+                // The copied memory isn't tracked, only the access to the pointers.
+                [&copy.src, &copy.dst, &copy.count]
+                    .into_iter()
+                    .filter_map(|operand| self.operand_access(operand))
+                    .collect()
+            }
+            StatementKind::FakeRead(_, _)
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::Retag(_, _)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => vec![],
+        }
+    }
+
+    fn rvalue_accesses(&self, rvalue: &Rvalue) -> Vec<Access> {
+        // Leave it as an exhaustive match to be notified when a new kind is added.
+        match rvalue {
+            Rvalue::Use(operand)
+            | Rvalue::UnaryOp(_, operand)
+            | Rvalue::Cast(_, operand, _)
+            | Rvalue::Repeat(operand, _)
+            | Rvalue::ShallowInitBox(operand, _) => {
+                self.operand_access(operand).into_iter().collect()
+            }
+            Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+                [lhs, rhs].into_iter().filter_map(|operand| self.operand_access(operand)).collect()
+            }
+            Rvalue::Aggregate(_, operands) => {
+                operands.iter().filter_map(|operand| self.operand_access(operand)).collect()
+            }
+            Rvalue::CopyForDeref(place) | Rvalue::Discriminant(place) | Rvalue::Len(place) => {
+                self.access(place, false).into_iter().collect()
+            }
+            // Creating a reference or a raw pointer doesn't access the memory.
+            Rvalue::Ref(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::ThreadLocalRef(_)
+            | Rvalue::NullaryOp(..) => vec![],
+        }
+    }
+
+    /// The memory accesses done by the terminator, in the order they happen.
+    fn terminator_accesses(&self, term: &Terminator) -> Vec<Access> {
+        // Leave it as an exhaustive match to be notified when a new kind is added.
+        match &term.kind {
+            TerminatorKind::Call { func, args, destination, .. } => {
+                let mut accesses: Vec<_> = std::iter::once(func)
+                    .chain(args)
+                    .filter_map(|operand| self.operand_access(operand))
+                    .collect();
+                accesses.extend(self.access(destination, true));
+                accesses
+            }
+            TerminatorKind::SwitchInt { discr: operand, .. }
+            | TerminatorKind::Assert { cond: operand, .. } => {
+                self.operand_access(operand).into_iter().collect()
+            }
+            TerminatorKind::Drop { place, .. } => self.access(place, true).into_iter().collect(),
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::InlineAsm { .. } => vec![],
+        }
+    }
+}
+
+/// The local that the value assigned by the rvalue may be derived from, if it's a pointer.
+fn derived_from(rvalue: &Rvalue) -> Option<Local> {
+    match rvalue {
+        Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+        | Rvalue::Cast(_, Operand::Copy(place) | Operand::Move(place), _)
+        | Rvalue::BinaryOp(BinOp::Offset, Operand::Copy(place) | Operand::Move(place), _) => {
+            (!place.projection.contains(&ProjectionElem::Deref)).then_some(place.local)
+        }
+        Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) | Rvalue::CopyForDeref(place) => {
+            Some(place.local)
+        }
+        _ => None,
+    }
+}
+
+/// The size of the accessed memory. Accesses to unsized or zero-sized places are ignored.
+fn place_size(locals: &[LocalDecl], place: &Place) -> Option<usize> {
+    ty_size(place.ty(locals).ok()?).filter(|size| *size != 0)
+}
+
+fn ty_size(ty: Ty) -> Option<usize> {
+    let shape = ty.layout().ok()?.shape();
+    shape.is_sized().then(|| shape.size.bytes())
+}
+
+fn move_local(local: Local) -> Operand {
+    Operand::Move(Place::from(local))
+}
+
+fn bool_operand(value: bool, span: Span) -> Operand {
+    Operand::Constant(Constant { span, user_ty: None, literal: Const::from_bool(value) })
+}
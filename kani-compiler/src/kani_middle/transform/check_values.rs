@@ -14,13 +14,17 @@
 //!   1. We could merge the invalid values by the offset.
 //!   2. We could avoid checking places that have been checked before.
 use crate::args::ExtraChecks;
-use crate::kani_middle::transform::body::{CheckType, MutableBody, SourceInstruction};
+use crate::kani_middle::transform::body::{
+    find_instance, CheckType, MutableBody, SourceInstruction,
+};
 use crate::kani_middle::transform::check_values::SourceOp::UnsupportedCheck;
 use crate::kani_middle::transform::{TransformPass, TransformationType};
 use crate::kani_queries::QueryDb;
 use rustc_middle::ty::TyCtxt;
 use rustc_smir::rustc_internal;
-use stable_mir::abi::{FieldsShape, Scalar, TagEncoding, ValueAbi, VariantsShape, WrappingRange};
+use stable_mir::abi::{
+    FieldsShape, LayoutShape, Scalar, TagEncoding, ValueAbi, VariantsShape, WrappingRange,
+};
 use stable_mir::mir::mono::{Instance, InstanceKind};
 use stable_mir::mir::visit::{Location, PlaceContext, PlaceRef};
 use stable_mir::mir::{
@@ -29,8 +33,7 @@
     Statement, StatementKind, Terminator, TerminatorKind,
 };
 use stable_mir::target::{MachineInfo, MachineSize};
-use stable_mir::ty::{AdtKind, Const, IndexedVal, RigidTy, Ty, TyKind, UintTy};
-use stable_mir::CrateDef;
+use stable_mir::ty::{AdtKind, Const, IndexedVal, RigidTy, Ty, TyKind, UintTy, VariantIdx};
 use std::fmt::{Debug, Formatter};
 use strum_macros::AsRefStr;
 use tracing::{debug, trace};
@@ -38,11 +41,17 @@
 /// Instrument the code with checks for invalid values.
 pub struct ValidValuePass {
     check_type: CheckType,
+    /// The check used for reads of a union field, which are reported with their own property
+    /// class when the `kani` crate is available.
+    union_check_type: CheckType,
 }
 
 impl ValidValuePass {
     pub fn new(tcx: TyCtxt) -> Self {
-        ValidValuePass { check_type: CheckType::new(tcx) }
+        let check_type = CheckType::new(tcx);
+        let union_check_type = find_instance(tcx, "KaniUnionReadCheck")
+            .map_or_else(|| check_type.clone(), CheckType::Assert);
+        ValidValuePass { check_type, union_check_type }
     }
 }
 
@@ -71,7 +80,7 @@ fn transform(&self, tcx: TyCtxt, body: Body, instance: Instance) -> (bool, Body)
         // Do not cache body.blocks().len() since it will change as we add new checks.
         for bb_idx in 0..new_body.blocks().len() {
             let Some(candidate) =
-                CheckValueVisitor::find_next(&new_body, bb_idx, bb_idx >= orig_len)
+                CheckValueVisitor::find_next(tcx, &new_body, bb_idx, bb_idx >= orig_len)
             else {
                 continue;
             };
@@ -108,6 +117,20 @@ fn build_check(&self, tcx: TyCtxt, body: &mut MutableBody, instruction: UnsafeIn
                         body.add_check(tcx, &self.check_type, &mut source, result, &msg);
                     }
                 }
+                SourceOp::UnionReadValidity { field_ty, rvalue, ranges } => {
+                    let value = body.new_assignment(rvalue, &mut source);
+                    let rvalue_ptr = Rvalue::AddressOf(Mutability::Not, Place::from(value));
+                    for range in ranges {
+                        let result =
+                            self.build_limits(body, &range, rvalue_ptr.clone(), &mut source);
+                        let msg = format!(
+                            "Undefined Behavior: Invalid value of type `{}` read from a union field",
+                            // TODO: Fix pretty_ty
+                            rustc_internal::internal(tcx, field_ty)
+                        );
+                        body.add_check(tcx, &self.union_check_type, &mut source, result, &msg);
+                    }
+                }
                 SourceOp::DerefValidity { pointee_ty, rvalue, ranges } => {
                     for range in ranges {
                         let result = self.build_limits(body, &range, rvalue.clone(), &mut source);
@@ -136,9 +159,26 @@ fn build_limits(
         req: &ValidValueReq,
         rvalue_ptr: Rvalue,
         source: &mut SourceInstruction,
+    ) -> Local {
+        let valid = self.build_range_limits(body, req, rvalue_ptr.clone(), source);
+        req.conditions.iter().fold(valid, |valid, condition| {
+            // The requirement is satisfied if the condition doesn't hold.
+            let inactive =
+                self.build_range_limits(body, &condition.negate(), rvalue_ptr.clone(), source);
+            body.new_binary_op(BinOp::BitOr, move_local(inactive), move_local(valid), source)
+        })
+    }
+
+    /// Check the range of the requirement, ignoring its conditions.
+    fn build_range_limits(
+        &self,
+        body: &mut MutableBody,
+        req: &ValidValueReq,
+        rvalue_ptr: Rvalue,
+        source: &mut SourceInstruction,
     ) -> Local {
         let span = source.span(body.blocks());
-        debug!(?req, ?rvalue_ptr, ?span, "build_limits");
+        debug!(?req, ?rvalue_ptr, ?span, "build_range_limits");
         let primitive_ty = uint_ty(req.size.bytes());
         let start_const = body.new_const_operand(req.valid_range.start, primitive_ty, span);
         let end_const = body.new_const_operand(req.valid_range.end, primitive_ty, span);
@@ -216,13 +256,18 @@ fn uint_ty(bytes: usize) -> UintTy {
 
 /// Represent a requirement for the value stored in the given offset.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
-struct ValidValueReq {
+pub struct ValidValueReq {
     /// Offset in bytes.
-    offset: usize,
+    pub offset: usize,
     /// Size of this requirement.
-    size: MachineSize,
+    pub size: MachineSize,
     /// The range restriction is represented by a Scalar.
-    valid_range: WrappingRange,
+    pub valid_range: WrappingRange,
+    /// The requirement only applies if all these conditions are satisfied.
+    ///
+    /// This is used for the fields of an enumeration variant, which only have to be valid if
+    /// the tag of the enumeration corresponds to that variant.
+    pub conditions: Vec<ValidValueReq>,
 }
 
 // TODO: Optimize checks by merging requirements whenever possible.
@@ -242,7 +287,12 @@ pub fn try_from_ty(machine_info: &MachineInfo, ty: Ty) -> Option<ValidValueReq>
         match shape.abi {
             ValueAbi::Scalar(Scalar::Initialized { value, valid_range })
             | ValueAbi::ScalarPair(Scalar::Initialized { value, valid_range }, _) => {
-                Some(ValidValueReq { offset: 0, size: value.size(machine_info), valid_range })
+                Some(ValidValueReq {
+                    offset: 0,
+                    size: value.size(machine_info),
+                    valid_range,
+                    conditions: vec![],
+                })
             }
             ValueAbi::Scalar(_)
             | ValueAbi::ScalarPair(_, _)
@@ -252,6 +302,26 @@ pub fn try_from_ty(machine_info: &MachineInfo, ty: Ty) -> Option<ValidValueReq>
         }
     }
 
+    /// Move this requirement and its conditions by `offset` bytes.
+    pub fn shift(&mut self, offset: usize) {
+        self.offset += offset;
+        for condition in &mut self.conditions {
+            condition.shift(offset);
+        }
+    }
+
+    /// The requirement that the value is outside of this range.
+    ///
+    /// This range cannot be full.
+    pub fn negate(&self) -> ValidValueReq {
+        let max = self.size.unsigned_int_max().unwrap();
+        let valid_range = WrappingRange {
+            start: self.valid_range.end.wrapping_add(1) & max,
+            end: self.valid_range.start.wrapping_sub(1) & max,
+        };
+        ValidValueReq { valid_range, conditions: vec![], ..*self }
+    }
+
     /// Check if range is full.
     pub fn is_full(&self) -> bool {
         self.valid_range.is_full(self.size).unwrap()
@@ -285,12 +355,17 @@ enum SourceOp {
     ///  - Transmute
     ///  - Field assignment
     ///  - Aggregate assignment
-    ///  - Union Access
     ///
     /// Each range is a pair of offset and scalar that represents the valid values.
     /// Note that the same offset may have multiple ranges that may require being joined.
     BytesValidity { target_ty: Ty, rvalue: Rvalue, ranges: Vec<ValidValueReq> },
 
+    /// Similar to BytesValidity, but for the read of a union field, whose bytes may have been
+    /// written through a field of a different type.
+    ///
+    /// The ranges are the valid values of the field type, and the rvalue is the union.
+    UnionReadValidity { field_ty: Ty, rvalue: Rvalue, ranges: Vec<ValidValueReq> },
+
     /// Similar to BytesValidity, but it stores any dereference that may be unsafe.
     ///
     /// This can happen for:
@@ -334,7 +409,8 @@ struct UnsafeInstruction {
 ///   - Transmute
 ///   - MemCopy
 ///   - Cast
-struct CheckValueVisitor<'a> {
+struct CheckValueVisitor<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
     locals: &'a [LocalDecl],
     /// Whether we should skip the next instruction, since it might've been instrumented already.
     /// When we instrument an instruction, we partition the basic block, and the instruction that
@@ -351,13 +427,15 @@ struct CheckValueVisitor<'a> {
     machine: MachineInfo,
 }
 
-impl<'a> CheckValueVisitor<'a> {
+impl<'a, 'tcx> CheckValueVisitor<'a, 'tcx> {
     fn find_next(
+        tcx: TyCtxt<'tcx>,
         body: &'a MutableBody,
         bb: BasicBlockIdx,
         skip_first: bool,
     ) -> Option<UnsafeInstruction> {
         let mut visitor = CheckValueVisitor {
+            tcx,
             locals: body.locals(),
             skip_next: skip_first,
             current: SourceInstruction::Statement { idx: 0, bb },
@@ -377,7 +455,7 @@ fn push_target(&mut self, op: SourceOp) {
     }
 }
 
-impl<'a> MirVisitor for CheckValueVisitor<'a> {
+impl<'a, 'tcx> MirVisitor for CheckValueVisitor<'a, 'tcx> {
     fn visit_statement(&mut self, stmt: &Statement, location: Location) {
         if self.skip_next {
             self.skip_next = false;
@@ -433,8 +511,11 @@ fn visit_terminator(&mut self, term: &Terminator, location: Location) {
                     // Note: For transmute, both Src and Dst must be valid type.
                     // In this case, we need to save the Dst, and invoke super_terminator.
                     self.super_terminator(term, location);
-                    let instance = expect_instance(self.locals, func);
-                    if instance.kind == InstanceKind::Intrinsic {
+                    // Calls through function pointers cannot target intrinsics.
+                    let instance = fn_def_instance(self.locals, func);
+                    if let Some(instance) = instance
+                        && instance.kind == InstanceKind::Intrinsic
+                    {
                         match instance.intrinsic_name().unwrap().as_str() {
                             "write_bytes" => {
                                 // The write bytes intrinsic may trigger UB in safe code.
@@ -452,7 +533,8 @@ fn visit_terminator(&mut self, term: &Terminator, location: Location) {
                                 else {
                                     unreachable!()
                                 };
-                                let validity = ty_validity_per_offset(&self.machine, target_ty, 0);
+                                let validity =
+                                    ty_validity_per_offset(self.tcx, &self.machine, target_ty, 0);
                                 match validity {
                                     Ok(ranges) if ranges.is_empty() => {}
                                     Ok(ranges) => {
@@ -502,7 +584,8 @@ fn visit_place(&mut self, place: &Place, ptx: PlaceContext, location: Location)
                     let ptr_ty = place_ref.ty(self.locals).unwrap();
                     if ptr_ty.kind().is_raw_ptr() {
                         let target_ty = elem.ty(ptr_ty).unwrap();
-                        let validity = ty_validity_per_offset(&self.machine, target_ty, 0);
+                        let validity =
+                            ty_validity_per_offset(self.tcx, &self.machine, target_ty, 0);
                         match validity {
                             Ok(ranges) if !ranges.is_empty() => {
                                 self.push_target(SourceOp::DerefValidity {
@@ -525,15 +608,18 @@ fn visit_place(&mut self, place: &Place, ptx: PlaceContext, location: Location)
                         }
                     }
                 }
-                ProjectionElem::Field(idx, target_ty) => {
-                    if target_ty.kind().is_union()
+                ProjectionElem::Field(_, target_ty) => {
+                    // All the fields of a union are at offset 0, so the validity of the field
+                    // type applies to the bytes of the union.
+                    if place_ref.ty(self.locals).unwrap().kind().is_union()
                         && (!ptx.is_mutating() || place.projection.len() > idx + 1)
                     {
-                        let validity = ty_validity_per_offset(&self.machine, *target_ty, 0);
+                        let validity =
+                            ty_validity_per_offset(self.tcx, &self.machine, *target_ty, 0);
                         match validity {
                             Ok(ranges) if !ranges.is_empty() => {
-                                self.push_target(SourceOp::BytesValidity {
-                                    target_ty: *target_ty,
+                                self.push_target(SourceOp::UnionReadValidity {
+                                    field_ty: *target_ty,
                                     rvalue: Rvalue::Use(Operand::Copy(Place {
                                         local: place_ref.local,
                                         projection: place_ref.projection.to_vec(),
@@ -579,11 +665,11 @@ fn visit_rvalue(&mut self, rvalue: &Rvalue, location: Location) {
                         unreachable!()
                     };
                     if let Ok(src_validity) =
-                        ty_validity_per_offset(&self.machine, src_pointee_ty, 0)
+                        ty_validity_per_offset(self.tcx, &self.machine, src_pointee_ty, 0)
                     {
                         if !src_validity.is_empty() {
                             if let Ok(dest_validity) =
-                                ty_validity_per_offset(&self.machine, dest_pointee_ty, 0)
+                                ty_validity_per_offset(self.tcx, &self.machine, dest_pointee_ty, 0)
                             {
                                 if dest_validity != src_validity {
                                     self.push_target(SourceOp::UnsupportedCheck {
@@ -610,7 +696,9 @@ fn visit_rvalue(&mut self, rvalue: &Rvalue, location: Location) {
                     // For transmute, we care about the destination type only.
                     // This could be optimized to only add a check if the requirements of the
                     // destination type are stricter than the source.
-                    if let Ok(dest_validity) = ty_validity_per_offset(&self.machine, *dest_ty, 0) {
+                    if let Ok(dest_validity) =
+                        ty_validity_per_offset(self.tcx, &self.machine, *dest_ty, 0)
+                    {
                         trace!(?dest_validity, "transmute");
                         if !dest_validity.is_empty() {
                             self.push_target(SourceOp::BytesValidity {
@@ -773,21 +861,31 @@ fn assignment_check_points(
     invalid_ranges
 }
 
-/// Retrieve instance for the given function operand.
+/// Retrieve instance for the given function operand, or `None` if the operand is a function
+/// pointer.
 ///
-/// This will panic if the operand is not a function or if it cannot be resolved.
-fn expect_instance(locals: &[LocalDecl], func: &Operand) -> Instance {
+/// This will panic if the function cannot be resolved.
+fn fn_def_instance(locals: &[LocalDecl], func: &Operand) -> Option<Instance> {
     let ty = func.ty(locals).unwrap();
     match ty.kind() {
-        TyKind::RigidTy(RigidTy::FnDef(def, args)) => Instance::resolve(def, &args).unwrap(),
+        TyKind::RigidTy(RigidTy::FnDef(def, args)) => Some(Instance::resolve(def, &args).unwrap()),
+        TyKind::RigidTy(RigidTy::FnPtr(_)) => None,
         _ => unreachable!(),
     }
 }
 
+/// The requirements that the bytes of a value of type `ty` must satisfy for the value to be valid.
+///
+/// Not all values are currently supported. For those not supported, we return Error.
+pub fn ty_validity(tcx: TyCtxt, ty: Ty) -> Result<Vec<ValidValueReq>, String> {
+    ty_validity_per_offset(tcx, &MachineInfo::target(), ty, 0)
+}
+
 /// Traverse the type and find all invalid values and their location in memory.
 ///
 /// Not all values are currently supported. For those not supported, we return Error.
 fn ty_validity_per_offset(
+    tcx: TyCtxt,
     machine_info: &MachineInfo,
     ty: Ty,
     current_offset: usize,
@@ -807,7 +905,7 @@ fn ty_validity_per_offset(
         FieldsShape::Primitive => Ok(ty_req()),
         FieldsShape::Array { stride, count } if count > 0 => {
             let TyKind::RigidTy(RigidTy::Array(elem_ty, _)) = ty.kind() else { unreachable!() };
-            let elem_validity = ty_validity_per_offset(machine_info, elem_ty, current_offset)?;
+            let elem_validity = ty_validity_per_offset(tcx, machine_info, elem_ty, current_offset)?;
             let mut result = vec![];
             if !elem_validity.is_empty() {
                 for idx in 0..count {
@@ -817,7 +915,7 @@ fn ty_validity_per_offset(
                         .iter()
                         .cloned()
                         .map(|mut req| {
-                            req.offset += elem_offset;
+                            req.shift(elem_offset);
                             req
                         })
                         .collect::<Vec<_>>();
@@ -842,6 +940,7 @@ fn ty_validity_per_offset(
                                         let field_offset = offsets[idx].bytes();
                                         let field_ty = fields[idx].ty_with_args(&args);
                                         fields_validity.append(&mut ty_validity_per_offset(
+                                            tcx,
                                             machine_info,
                                             field_ty,
                                             field_offset + current_offset,
@@ -850,35 +949,22 @@ fn ty_validity_per_offset(
                                     Ok(fields_validity)
                                 }
                                 VariantsShape::Multiple {
-                                    tag_encoding: TagEncoding::Niche { .. },
-                                    ..
-                                } => {
-                                    Err(format!("Unsupported Enum `{}` check", def.trimmed_name()))?
-                                }
-                                VariantsShape::Multiple { variants, .. } => {
-                                    let enum_validity = ty_req();
-                                    let mut fields_validity = vec![];
-                                    for (index, variant) in variants.iter().enumerate() {
-                                        let fields = ty_variants[index].fields();
-                                        for field_idx in variant.fields.fields_by_offset_order() {
-                                            let field_offset = offsets[field_idx].bytes();
-                                            let field_ty = fields[field_idx].ty_with_args(&args);
-                                            fields_validity.append(&mut ty_validity_per_offset(
-                                                machine_info,
-                                                field_ty,
-                                                field_offset + current_offset,
-                                            )?);
-                                        }
-                                    }
-                                    if fields_validity.is_empty() {
-                                        Ok(enum_validity)
-                                    } else {
-                                        Err(format!(
-                                            "Unsupported Enum `{}` check",
-                                            def.trimmed_name()
-                                        ))
-                                    }
-                                }
+                                    ref tag,
+                                    ref tag_encoding,
+                                    tag_field,
+                                    ref variants,
+                                } => enum_validity(
+                                    tcx,
+                                    machine_info,
+                                    ty,
+                                    EnumLayout {
+                                        tag,
+                                        tag_encoding,
+                                        tag_offset: offsets[tag_field].bytes() + current_offset,
+                                        variants,
+                                    },
+                                    current_offset,
+                                ),
                             }
                         }
                         AdtKind::Union => unreachable!(),
@@ -890,6 +976,7 @@ fn ty_validity_per_offset(
                                 let field_offset = offsets[idx].bytes();
                                 let field_ty = fields[idx].ty_with_args(&args);
                                 struct_validity.append(&mut ty_validity_per_offset(
+                                    tcx,
                                     machine_info,
                                     field_ty,
                                     field_offset + current_offset,
@@ -902,7 +989,12 @@ fn ty_validity_per_offset(
                 RigidTy::Pat(base_ty, ..) => {
                     // This is similar to a structure with one field and with niche defined.
                     let mut pat_validity = ty_req();
-                    pat_validity.append(&mut ty_validity_per_offset(machine_info, *base_ty, 0)?);
+                    pat_validity.append(&mut ty_validity_per_offset(
+                        tcx,
+                        machine_info,
+                        *base_ty,
+                        current_offset,
+                    )?);
                     Ok(pat_validity)
                 }
                 RigidTy::Tuple(tys) => {
@@ -911,6 +1003,7 @@ fn ty_validity_per_offset(
                         let field_offset = offsets[idx].bytes();
                         let field_ty = tys[idx];
                         tuple_validity.append(&mut ty_validity_per_offset(
+                            tcx,
                             machine_info,
                             field_ty,
                             field_offset + current_offset,
@@ -918,6 +1011,37 @@ fn ty_validity_per_offset(
                     }
                     Ok(tuple_validity)
                 }
+                RigidTy::Closure(..) | RigidTy::Coroutine(..) => {
+                    // The fields of a closure, and the fields that a coroutine stores before its
+                    // state, are the captured variables. The variables that a coroutine saves
+                    // across suspension points aren't checked.
+                    let mut validity = vec![];
+                    for (idx, upvar_ty) in upvar_tys(tcx, ty).into_iter().enumerate() {
+                        validity.append(&mut ty_validity_per_offset(
+                            tcx,
+                            machine_info,
+                            upvar_ty,
+                            offsets[idx].bytes() + current_offset,
+                        )?);
+                    }
+                    if let VariantsShape::Multiple {
+                        tag: Scalar::Initialized { value, valid_range },
+                        tag_field,
+                        ..
+                    } = layout.variants
+                    {
+                        let state_validity = ValidValueReq {
+                            offset: offsets[tag_field].bytes() + current_offset,
+                            size: value.size(machine_info),
+                            valid_range,
+                            conditions: vec![],
+                        };
+                        if !state_validity.is_full() {
+                            validity.push(state_validity);
+                        }
+                    }
+                    Ok(validity)
+                }
                 RigidTy::Bool
                 | RigidTy::Char
                 | RigidTy::Int(_)
@@ -935,8 +1059,6 @@ fn ty_validity_per_offset(
                 }
                 RigidTy::FnDef(_, _)
                 | RigidTy::FnPtr(_)
-                | RigidTy::Closure(_, _)
-                | RigidTy::Coroutine(_, _, _)
                 | RigidTy::CoroutineWitness(_, _)
                 | RigidTy::Foreign(_)
                 | RigidTy::Dynamic(_, _, _) => Err(format!("Unsupported {ty:?}")),
@@ -948,3 +1070,122 @@ fn ty_validity_per_offset(
         }
     }
 }
+
+/// The types of the variables captured by a closure or a coroutine.
+fn upvar_tys(tcx: TyCtxt, ty: Ty) -> Vec<Ty> {
+    let upvar_tys = match rustc_internal::internal(tcx, ty).kind() {
+        rustc_middle::ty::Closure(_, args) => args.as_closure().upvar_tys(),
+        rustc_middle::ty::Coroutine(_, args) => args.as_coroutine().upvar_tys(),
+        _ => unreachable!("Expected closure or coroutine, but found {ty:?}"),
+    };
+    upvar_tys.iter().map(rustc_internal::stable).collect()
+}
+
+/// The layout of an enumeration with multiple variants.
+struct EnumLayout<'a> {
+    tag: &'a Scalar,
+    tag_encoding: &'a TagEncoding,
+    /// The offset of the tag in bytes.
+    tag_offset: usize,
+    variants: &'a [LayoutShape],
+}
+
+/// Find the requirements of an enumeration with multiple variants.
+///
+/// The tag must correspond to one of the variants, and the fields of each variant only have to be
+/// valid if the variant is the active one. For niche encoding, the tag is stored in the fields of
+/// the untagged variant, which is active when the tag isn't one of the niche values.
+fn enum_validity(
+    tcx: TyCtxt,
+    machine_info: &MachineInfo,
+    ty: Ty,
+    layout: EnumLayout,
+    current_offset: usize,
+) -> Result<Vec<ValidValueReq>, String> {
+    let TyKind::RigidTy(RigidTy::Adt(def, args)) = ty.kind() else { unreachable!() };
+    let (size, tag_range) = match layout.tag {
+        Scalar::Initialized { value, valid_range } => (value.size(machine_info), *valid_range),
+        Scalar::Union { .. } => unreachable!("Expected initialized tag for {ty:?}"),
+    };
+    let max = size.unsigned_int_max().unwrap();
+    let tag_req = |start, end| ValidValueReq {
+        offset: layout.tag_offset,
+        size,
+        valid_range: WrappingRange { start, end },
+        conditions: vec![],
+    };
+    let mut validity = vec![];
+    let tag_validity = tag_req(tag_range.start, tag_range.end);
+    if !tag_validity.is_full() {
+        validity.push(tag_validity);
+    }
+
+    // The condition for each variant to be active, or `None` if the variant cannot be active.
+    let variant_conditions: Vec<Option<ValidValueReq>> = match layout.tag_encoding {
+        TagEncoding::Direct => {
+            let internal_ty = rustc_internal::internal(tcx, ty);
+            let discriminants = (0..layout.variants.len())
+                .map(|idx| {
+                    let idx = rustc_internal::internal(tcx, VariantIdx::to_val(idx));
+                    internal_ty.discriminant_for_variant(tcx, idx).unwrap().val & max
+                })
+                .collect::<Vec<_>>();
+            // Values inside the tag range may not be the discriminant of any variant.
+            let mut sorted = discriminants.clone();
+            sorted.sort_unstable();
+            sorted.dedup();
+            for pair in sorted.windows(2) {
+                if pair[1] - pair[0] > 1 {
+                    validity.push(tag_req(pair[0] + 1, pair[1] - 1).negate());
+                }
+            }
+            discriminants.into_iter().map(|discr| Some(tag_req(discr, discr))).collect()
+        }
+        TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+            let untagged_variant = untagged_variant.to_index();
+            let niche_variants =
+                niche_variants.start().to_index()..=niche_variants.end().to_index();
+            let niche_value = |idx: usize| {
+                let relative = (idx - niche_variants.start()) as u128;
+                relative.wrapping_add(*niche_start) & max
+            };
+            let niche_range = tag_req(*niche_start, niche_value(*niche_variants.end()));
+            if niche_variants.contains(&untagged_variant) {
+                // The niche value of the untagged variant is reserved, but it is never used.
+                let reserved = niche_value(untagged_variant);
+                validity.push(tag_req(reserved, reserved).negate());
+            }
+            (0..layout.variants.len())
+                .map(|idx| {
+                    if idx == untagged_variant {
+                        Some(niche_range.negate())
+                    } else if niche_variants.contains(&idx) {
+                        Some(tag_req(niche_value(idx), niche_value(idx)))
+                    } else {
+                        // Variants without a niche value are uninhabited.
+                        None
+                    }
+                })
+                .collect()
+        }
+    };
+
+    let ty_variants = def.variants();
+    for (idx, condition) in variant_conditions.into_iter().enumerate() {
+        let Some(condition) = condition else { continue };
+        let variant_layout = &layout.variants[idx];
+        let FieldsShape::Arbitrary { ref offsets } = variant_layout.fields else { continue };
+        let fields = ty_variants[idx].fields();
+        for field_idx in variant_layout.fields.fields_by_offset_order() {
+            let field_offset = offsets[field_idx].bytes();
+            let field_ty = fields[field_idx].ty_with_args(&args);
+            for mut req in
+                ty_validity_per_offset(tcx, machine_info, field_ty, field_offset + current_offset)?
+            {
+                req.conditions.push(condition.clone());
+                validity.push(req);
+            }
+        }
+    }
+    Ok(validity)
+}
@@ -0,0 +1,329 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Implement an optimization pass that folds the constants of a function body, and prunes the
+//! branches that are statically dead, which is disabled with `--no-prepass`.
+//!
+//! The bodies are monomorphized, so the conditions that only depend on the type parameters, e.g.,
+//! `mem::size_of::<T>() == 0`, are constants, and so are the `ub_checks()` of the standard library,
+//! which are disabled in the generated code. We find the locals that are assigned a known value
+//! once and are never borrowed, fold their comparisons and logical operations, and replace the
+//! branches and the assertions whose condition is known by a jump to their target. The blocks that
+//! are no longer reachable are emptied, so the functions they call are neither collected nor
+//! verified.
+//!
+//! The pruned branches are never taken, and the removed assertions always hold, so the result of
+//! the verification doesn't change. Arithmetic operations aren't folded, so their overflow checks
+//! are kept.
+
+use crate::kani_middle::transform::{TransformPass, TransformationType};
+use crate::kani_queries::QueryDb;
+use rustc_middle::ty::TyCtxt;
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::{
+    BinOp, Body, Constant, Local, NullOp, Operand, Rvalue, StatementKind, TerminatorKind, UnOp,
+};
+use stable_mir::ty::{Const, ConstantKind, RigidTy, Ty, TyKind, UintTy};
+use std::collections::HashMap;
+use tracing::trace;
+
+/// Fold the constants and prune the dead branches of the function bodies.
+#[derive(Debug)]
+pub struct ConstPropPass;
+
+impl TransformPass for ConstPropPass {
+    fn transformation_type() -> TransformationType
+    where
+        Self: Sized,
+    {
+        TransformationType::Optimization
+    }
+
+    fn is_enabled(&self, query_db: &QueryDb) -> bool
+    where
+        Self: Sized,
+    {
+        // The coverage of the lines of the dead branches is reported as uncovered, so they are
+        // kept with `--coverage`.
+        let args = query_db.args();
+        !args.no_prepass && !args.check_coverage
+    }
+
+    fn transform(&self, _tcx: TyCtxt, body: Body, instance: Instance) -> (bool, Body) {
+        trace!(function=?instance.name(), "transform");
+        let values = known_values(&body);
+        let mut blocks = body.blocks.clone();
+        let mut modified = false;
+        for block in &mut blocks {
+            for stmt in &mut block.statements {
+                let StatementKind::Assign(place, rvalue) = &mut stmt.kind else { continue };
+                if !place.projection.is_empty()
+                    || matches!(rvalue, Rvalue::Use(Operand::Constant(_)))
+                {
+                    continue;
+                }
+                if let Some(literal) = values.get(&place.local).and_then(|value| value.to_const()) {
+                    *rvalue = Rvalue::Use(Operand::Constant(Constant {
+                        span: stmt.span,
+                        user_ty: None,
+                        literal,
+                    }));
+                    modified = true;
+                }
+            }
+            let target = match &block.terminator.kind {
+                TerminatorKind::SwitchInt { discr, targets } => {
+                    let Some(value) = eval_operand(discr, &values).and_then(Value::switch_value)
+                    else {
+                        continue;
+                    };
+                    targets
+                        .branches()
+                        .find_map(|(branch, target)| (branch == value).then_some(target))
+                        .unwrap_or(targets.otherwise())
+                }
+                TerminatorKind::Assert { cond, expected, target, .. }
+                    if eval_operand(cond, &values) == Some(Value::Bool(*expected)) =>
+                {
+                    *target
+                }
+                _ => continue,
+            };
+            block.terminator.kind = TerminatorKind::Goto { target };
+            modified = true;
+        }
+
+        // Empty the blocks that are no longer reachable.
+        let mut reachable = vec![false; blocks.len()];
+        let mut stack = vec![0];
+        while let Some(bb) = stack.pop() {
+            if !reachable[bb] {
+                reachable[bb] = true;
+                stack.extend(blocks[bb].terminator.successors());
+            }
+        }
+        for (block, _) in blocks.iter_mut().zip(reachable).filter(|(_, reachable)| !reachable) {
+            if !block.statements.is_empty()
+                || !matches!(block.terminator.kind, TerminatorKind::Unreachable)
+            {
+                block.statements.clear();
+                block.terminator.kind = TerminatorKind::Unreachable;
+                modified = true;
+            }
+        }
+
+        if !modified {
+            return (false, body);
+        }
+        let locals = body.locals().to_vec();
+        let arg_count = body.arg_locals().len();
+        let spread_arg = body.spread_arg();
+        let new_body =
+            Body::new(blocks, locals, arg_count, body.var_debug_info, spread_arg, body.span);
+        (true, new_body)
+    }
+}
+
+/// A value known at compile time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Value {
+    Bool(bool),
+    Uint(u128, UintTy),
+    Int(i128),
+}
+
+impl Value {
+    fn from_const(constant: &Const) -> Option<Value> {
+        let ConstantKind::Allocated(alloc) = constant.kind() else { return None };
+        match constant.ty().kind() {
+            TyKind::RigidTy(RigidTy::Bool) => Some(Value::Bool(alloc.read_bool().ok()?)),
+            TyKind::RigidTy(RigidTy::Uint(uint_ty)) => {
+                Some(Value::Uint(alloc.read_uint().ok()?, uint_ty))
+            }
+            TyKind::RigidTy(RigidTy::Int(_)) => {
+                // The value is read without sign extension.
+                let unused_bits = 128 - 8 * alloc.bytes.len() as u32;
+                let value = alloc.read_int().ok()?;
+                Some(Value::Int(value.checked_shl(unused_bits)? >> unused_bits))
+            }
+            _ => None,
+        }
+    }
+
+    /// The constant with this value, if it can be built.
+    fn to_const(self) -> Option<Const> {
+        match self {
+            Value::Bool(value) => Some(Const::from_bool(value)),
+            Value::Uint(value, uint_ty) => Const::try_from_uint(value, uint_ty).ok(),
+            Value::Int(_) => None,
+        }
+    }
+
+    /// The value compared to the branches of a `SwitchInt`.
+    fn switch_value(self) -> Option<u128> {
+        match self {
+            Value::Bool(value) => Some(value.into()),
+            Value::Uint(value, _) => Some(value),
+            // The branches of signed integers are truncated to the size of the type.
+            Value::Int(_) => None,
+        }
+    }
+}
+
+/// The value of the locals that are assigned once, with a value known at compile time, and that
+/// are neither borrowed nor modified otherwise.
+fn known_values(body: &Body) -> HashMap<Local, Value> {
+    let num_locals = body.locals().len();
+    let mut definitions: Vec<Vec<&Rvalue>> = vec![vec![]; num_locals];
+    let mut escaped = vec![false; num_locals];
+    // The arguments are assigned by the caller.
+    escaped[1..=body.arg_locals().len()].fill(true);
+    for block in &body.blocks {
+        for stmt in &block.statements {
+            match &stmt.kind {
+                StatementKind::Assign(place, rvalue) => {
+                    if place.projection.is_empty() {
+                        definitions[place.local].push(rvalue);
+                    } else {
+                        escaped[place.local] = true;
+                    }
+                    if let Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) = rvalue {
+                        escaped[place.local] = true;
+                    }
+                }
+                StatementKind::SetDiscriminant { place, .. } | StatementKind::Deinit(place) => {
+                    escaped[place.local] = true;
+                }
+                StatementKind::FakeRead(..)
+                | StatementKind::StorageLive(_)
+                | StatementKind::StorageDead(_)
+                | StatementKind::Retag(..)
+                | StatementKind::PlaceMention(_)
+                | StatementKind::AscribeUserType { .. }
+                | StatementKind::Coverage(_)
+                | StatementKind::Intrinsic(_)
+                | StatementKind::ConstEvalCounter
+                | StatementKind::Nop => {}
+            }
+        }
+        match &block.terminator.kind {
+            TerminatorKind::Call { destination: place, .. }
+            | TerminatorKind::Drop { place, .. } => escaped[place.local] = true,
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for place in operands.iter().filter_map(|operand| operand.out_place.as_ref()) {
+                    escaped[place.local] = true;
+                }
+            }
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Assert { .. } => {}
+        }
+    }
+
+    // The value of a local may depend on the value of other locals, so iterate until no new value
+    // is found.
+    let mut values = HashMap::new();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (local, definitions) in definitions.iter().enumerate() {
+            let [rvalue] = definitions.as_slice() else { continue };
+            if escaped[local] || values.contains_key(&local) {
+                continue;
+            }
+            if let Some(value) = eval_rvalue(rvalue, &values) {
+                values.insert(local, value);
+                changed = true;
+            }
+        }
+    }
+    values
+}
+
+fn eval_operand(operand: &Operand, values: &HashMap<Local, Value>) -> Option<Value> {
+    match operand {
+        Operand::Constant(constant) => Value::from_const(&constant.literal),
+        Operand::Copy(place) | Operand::Move(place) => {
+            if place.projection.is_empty() {
+                values.get(&place.local).copied()
+            } else {
+                None
+            }
+        }
+    }
+}
+
+fn eval_rvalue(rvalue: &Rvalue, values: &HashMap<Local, Value>) -> Option<Value> {
+    match rvalue {
+        Rvalue::Use(operand) => eval_operand(operand, values),
+        Rvalue::BinaryOp(bin_op, lhs, rhs) => {
+            eval_binary_op(*bin_op, eval_operand(lhs, values)?, eval_operand(rhs, values)?)
+        }
+        Rvalue::UnaryOp(UnOp::Not, operand) => match eval_operand(operand, values)? {
+            Value::Bool(value) => Some(Value::Bool(!value)),
+            Value::Uint(..) | Value::Int(_) => None,
+        },
+        Rvalue::NullaryOp(null_op, ty) => eval_nullary_op(null_op, *ty),
+        _ => None,
+    }
+}
+
+/// Evaluate the binary operations that can't fail.
+fn eval_binary_op(bin_op: BinOp, lhs: Value, rhs: Value) -> Option<Value> {
+    let ordering = match (lhs, rhs) {
+        (Value::Bool(lhs), Value::Bool(rhs)) => lhs.cmp(&rhs),
+        (Value::Uint(lhs, _), Value::Uint(rhs, _)) => lhs.cmp(&rhs),
+        (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(&rhs),
+        _ => return None,
+    };
+    let value = match bin_op {
+        BinOp::Eq => Value::Bool(ordering.is_eq()),
+        BinOp::Ne => Value::Bool(ordering.is_ne()),
+        BinOp::Lt => Value::Bool(ordering.is_lt()),
+        BinOp::Le => Value::Bool(ordering.is_le()),
+        BinOp::Gt => Value::Bool(ordering.is_gt()),
+        BinOp::Ge => Value::Bool(ordering.is_ge()),
+        BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => {
+            let apply = |lhs: u128, rhs: u128| match bin_op {
+                BinOp::BitAnd => lhs & rhs,
+                BinOp::BitOr => lhs | rhs,
+                _ => lhs ^ rhs,
+            };
+            match (lhs, rhs) {
+                (Value::Bool(lhs), Value::Bool(rhs)) => {
+                    Value::Bool(apply(lhs.into(), rhs.into()) != 0)
+                }
+                (Value::Uint(lhs, uint_ty), Value::Uint(rhs, _)) => {
+                    Value::Uint(apply(lhs, rhs), uint_ty)
+                }
+                _ => return None,
+            }
+        }
+        // The arithmetic operations may overflow or divide by zero, which is checked.
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn eval_nullary_op(null_op: &NullOp, ty: Ty) -> Option<Value> {
+    match null_op {
+        NullOp::SizeOf | NullOp::AlignOf => {
+            let shape = ty.layout().ok()?.shape();
+            if shape.is_unsized() {
+                return None;
+            }
+            let value = match null_op {
+                NullOp::SizeOf => shape.size.bytes() as u128,
+                _ => shape.abi_align.into(),
+            };
+            Some(Value::Uint(value, UintTy::Usize))
+        }
+        // The UB checks of the standard library are disabled, as in the generated code.
+        NullOp::UbChecks => Some(Value::Bool(false)),
+        NullOp::OffsetOf(_) => None,
+    }
+}
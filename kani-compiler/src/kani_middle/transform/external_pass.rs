@@ -0,0 +1,179 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Implement the instrumentation passes that are loaded from a dynamic library with
+//! `--instrumentation-pass <PATH>`, which requires `-Z instrumentation-passes`.
+//!
+//! This allows users to add their own checks, e.g., an information-flow analysis, without
+//! modifying Kani. The library must be a Rust `dylib` that exports three `extern "C"` functions:
+//! - [ABI_VERSION_SYMBOL], which returns the version of this interface it implements,
+//!   i.e., [ABI_VERSION].
+//! - [RUSTC_VERSION_SYMBOL], which returns the version of the compiler it was built with.
+//! - [INSTRUMENT_SYMBOL], with the signature [InstrumentFn].
+//!
+//! ```ignore
+//! #![feature(rustc_private)]
+//! extern crate rustc_driver;
+//! extern crate rustc_interface;
+//! extern crate stable_mir;
+//!
+//! use stable_mir::mir::mono::Instance;
+//! use stable_mir::mir::Body;
+//! use std::ffi::{c_char, CString};
+//!
+//! #[no_mangle]
+//! pub extern "C" fn kani_instrument_abi_version() -> u32 {
+//!     1
+//! }
+//!
+//! #[no_mangle]
+//! pub extern "C" fn kani_instrument_rustc_version() -> *const c_char {
+//!     let version = rustc_interface::util::rustc_version_str().unwrap_or_default();
+//!     CString::new(version).unwrap().into_raw()
+//! }
+//!
+//! #[no_mangle]
+//! pub unsafe extern "C" fn kani_instrument(
+//!     instance: *const Instance,
+//!     body: *const Body,
+//!     new_body: *mut Body,
+//! ) -> bool {
+//!     // Write the new body of `*instance` to `new_body` and return `true`, or return `false`
+//!     // to keep it unchanged.
+//!     false
+//! }
+//! ```
+//!
+//! The StableMIR types are passed by pointer, so their layout must be the same in the library and
+//! in Kani. This is only the case if they're built with the same compiler, which is why Kani
+//! rejects a library whose compiler version differs from its own. The library can be built with
+//! `rustc --crate-type dylib -C prefer-dynamic`, so it shares the compiler libraries with Kani, and
+//! the StableMIR APIs can be used to inspect the crate being verified.
+//!
+//! The function is called for every function body that is reachable from the harnesses, after
+//! Kani's own passes. The code added by the library is collected and translated as any other code,
+//! but it isn't instrumented by Kani's checks.
+
+use crate::kani_middle::transform::{TransformPass, TransformationType};
+use crate::kani_queries::QueryDb;
+use libloading::Library;
+use rustc_interface::util::rustc_version_str;
+use rustc_middle::ty::TyCtxt;
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::Body;
+use std::ffi::{c_char, CStr};
+use std::mem::MaybeUninit;
+use std::path::{Path, PathBuf};
+use tracing::trace;
+
+/// The version of the interface between Kani and the instrumentation libraries.
+pub const ABI_VERSION: u32 = 1;
+
+/// The function that returns the version of the interface implemented by the library.
+pub const ABI_VERSION_SYMBOL: &str = "kani_instrument_abi_version";
+
+/// The function that returns the version of the compiler that built the library, as a
+/// NUL-terminated string.
+pub const RUSTC_VERSION_SYMBOL: &str = "kani_instrument_rustc_version";
+
+/// The function that instruments a body.
+pub const INSTRUMENT_SYMBOL: &str = "kani_instrument";
+
+type AbiVersionFn = unsafe extern "C" fn() -> u32;
+type RustcVersionFn = unsafe extern "C" fn() -> *const c_char;
+
+/// The signature of the [INSTRUMENT_SYMBOL] function. If the body of `instance` is modified, it
+/// writes the new body to `new_body` and returns `true`. Otherwise, it returns `false` and leaves
+/// `new_body` uninitialized.
+pub type InstrumentFn =
+    unsafe extern "C" fn(instance: *const Instance, body: *const Body, new_body: *mut Body) -> bool;
+
+/// A pass that runs the instrumentation function of a dynamic library.
+#[derive(Debug)]
+pub struct ExternalPass {
+    path: PathBuf,
+    instrument: InstrumentFn,
+    /// Keep the library loaded while the pass may be used.
+    _library: Library,
+}
+
+impl ExternalPass {
+    /// Load the library at `path`, or report an error if it doesn't implement the current
+    /// interface with the same compiler as Kani.
+    pub fn load(tcx: TyCtxt, path: &Path) -> Option<Self> {
+        Self::load_library(path)
+            .map_err(|err| {
+                tcx.dcx()
+                    .err(format!("failed to load instrumentation pass `{}`: {err}", path.display()))
+            })
+            .ok()
+    }
+
+    fn load_library(path: &Path) -> Result<Self, String> {
+        let symbol = |name: &str| format!("{name}\0").into_bytes();
+        // SAFETY: The library is trusted by the user. The version functions have a C signature,
+        // and the instrumentation function is only used once the library has been checked to be
+        // built by the same compiler as Kani.
+        unsafe {
+            let library = Library::new(path).map_err(|err| err.to_string())?;
+            let abi_version = library
+                .get::<AbiVersionFn>(&symbol(ABI_VERSION_SYMBOL))
+                .map_err(|err| err.to_string())?();
+            if abi_version != ABI_VERSION {
+                return Err(format!(
+                    "the library implements version {abi_version} of the instrumentation \
+                    interface, but Kani requires version {ABI_VERSION}"
+                ));
+            }
+            let rustc_version = library
+                .get::<RustcVersionFn>(&symbol(RUSTC_VERSION_SYMBOL))
+                .map_err(|err| err.to_string())?();
+            let rustc_version = if rustc_version.is_null() {
+                ""
+            } else {
+                CStr::from_ptr(rustc_version).to_str().unwrap_or_default()
+            };
+            let kani_rustc_version = rustc_version_str().unwrap_or_default();
+            if rustc_version != kani_rustc_version {
+                return Err(format!(
+                    "the library was built with rustc `{rustc_version}`, but Kani uses rustc \
+                    `{kani_rustc_version}`"
+                ));
+            }
+            let instrument = *library
+                .get::<InstrumentFn>(&symbol(INSTRUMENT_SYMBOL))
+                .map_err(|err| err.to_string())?;
+            Ok(ExternalPass { path: path.to_path_buf(), instrument, _library: library })
+        }
+    }
+}
+
+impl TransformPass for ExternalPass {
+    fn transformation_type() -> TransformationType
+    where
+        Self: Sized,
+    {
+        TransformationType::Instrumentation
+    }
+
+    fn is_enabled(&self, _query_db: &QueryDb) -> bool
+    where
+        Self: Sized,
+    {
+        true
+    }
+
+    fn transform(&self, _tcx: TyCtxt, body: Body, instance: Instance) -> (bool, Body) {
+        trace!(function=?instance.name(), path=?self.path, "transform");
+        let mut new_body = MaybeUninit::<Body>::uninit();
+        // SAFETY: The library was built with the same compiler as Kani, and it only initializes
+        // `new_body` if it returns `true`.
+        unsafe {
+            if (self.instrument)(&instance, &body, new_body.as_mut_ptr()) {
+                (true, new_body.assume_init())
+            } else {
+                (false, body)
+            }
+        }
+    }
+}
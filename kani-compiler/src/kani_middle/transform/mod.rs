@@ -16,7 +16,11 @@
 //!
 //! For all instrumentation passes, always use exhaustive matches to ensure soundness in case a new
 //! case is added.
+use crate::kani_middle::transform::check_aliasing::AliasingPass;
 use crate::kani_middle::transform::check_values::ValidValuePass;
+use crate::kani_middle::transform::const_prop::ConstPropPass;
+use crate::kani_middle::transform::external_pass::ExternalPass;
+use crate::kani_middle::transform::panic_fmt::PanicFmtPass;
 use crate::kani_queries::QueryDb;
 use rustc_middle::ty::TyCtxt;
 use stable_mir::mir::mono::Instance;
@@ -25,7 +29,13 @@
 use std::fmt::Debug;
 
 mod body;
+mod check_aliasing;
 mod check_values;
+mod const_prop;
+mod external_pass;
+mod panic_fmt;
+
+pub use check_values::{ty_validity, ValidValueReq};
 
 /// Object used to retrieve a transformed instance body.
 /// The transformations to be applied may be controlled by user options.
@@ -50,7 +60,15 @@ pub fn new(queries: &QueryDb, tcx: TyCtxt) -> Self {
             inst_passes: vec![],
             cache: Default::default(),
         };
+        transformer.add_pass(queries, ConstPropPass);
+        transformer.add_pass(queries, PanicFmtPass::new(tcx));
         transformer.add_pass(queries, ValidValuePass::new(tcx));
+        transformer.add_pass(queries, AliasingPass::new(tcx));
+        for path in &queries.args().instrumentation_passes {
+            if let Some(pass) = ExternalPass::load(tcx, path) {
+                transformer.add_pass(queries, pass);
+            }
+        }
         transformer
     }
 
@@ -93,9 +111,7 @@ fn add_pass<P: TransformPass + 'static>(&mut self, query_db: &QueryDb, pass: P)
         if pass.is_enabled(&query_db) {
             match P::transformation_type() {
                 TransformationType::Instrumentation => self.inst_passes.push(Box::new(pass)),
-                TransformationType::Optimization => {
-                    unreachable!()
-                }
+                TransformationType::Optimization => self.opt_passes.push(Box::new(pass)),
             }
         }
     }
@@ -107,7 +123,6 @@ enum TransformationType {
     /// Should only add assertion checks to ensure the program is correct.
     Instrumentation,
     /// May replace inefficient code with more performant but equivalent code.
-    #[allow(dead_code)]
     Optimization,
 }
 
@@ -0,0 +1,317 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Implement a transformation pass that abstracts the code that can only lead to a panic, which is
+//! enabled with `-Z abstract-panic-fmt`.
+//!
+//! The panic functions of the standard library, e.g., `core::panicking::assert_failed` or
+//! `core::result::unwrap_failed`, format their message with the `core::fmt` machinery, and the
+//! `Debug` and `Display` implementations of their arguments become reachable. Their message
+//! doesn't matter for verification, but the formatting code is expensive to verify. For example,
+//! every `fmt` method whose address is taken is a possible target of the calls through function
+//! pointers of `core::fmt::write`.
+//!
+//! We find the basic blocks from which every path leads to a call to one of these functions. In
+//! these blocks, the calls to the `core::fmt` functions that build the message are skipped, and
+//! the panic is replaced by a call to `kani::panic` with a placeholder message. The temporaries
+//! that were only computed for the message are then removed, so the formatting code is neither
+//! collected nor verified. Panics whose arguments are all constants are kept, since they don't
+//! need any formatting.
+//!
+//! The rest of the code on the path to the panic is kept, e.g., the calls to `kani::assume` or
+//! `kani::cover`, the safety checks and the drop of the local variables.
+
+use crate::kani_middle::transform::body::find_instance;
+use crate::kani_middle::transform::{TransformPass, TransformationType};
+use crate::kani_queries::QueryDb;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::visit::{Location, PlaceContext};
+use stable_mir::mir::{
+    BasicBlock, BasicBlockIdx, Body, Constant, Local, LocalDecl, MirVisitor, Mutability, Operand,
+    Place, ProjectionElem, Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
+    UnwindAction, VarDebugInfoContents,
+};
+use stable_mir::ty::{Const, RigidTy, Ty, TyKind};
+use stable_mir::CrateDef;
+use std::collections::HashSet;
+use tracing::trace;
+
+/// The message of the panics whose arguments are removed.
+const PLACEHOLDER_MSG: &str =
+    "This is a placeholder message; Kani doesn't support message formatted at runtime";
+
+/// The crates whose panic functions are abstracted.
+const STD_CRATES: [&str; 3] = ["core", "alloc", "std"];
+
+/// The prefix of the functions that build the message of the panics.
+const FMT_PREFIX: &str = "core::fmt::";
+
+/// Replace the code that can only lead to a panic of the standard library.
+#[derive(Debug)]
+pub struct PanicFmtPass {
+    /// The `kani::panic` function, which is only available when the `kani` crate is.
+    kani_panic: Option<Instance>,
+}
+
+impl PanicFmtPass {
+    pub fn new(tcx: TyCtxt) -> Self {
+        PanicFmtPass { kani_panic: find_instance(tcx, "KaniPanic") }
+    }
+}
+
+impl TransformPass for PanicFmtPass {
+    fn transformation_type() -> TransformationType
+    where
+        Self: Sized,
+    {
+        TransformationType::Optimization
+    }
+
+    fn is_enabled(&self, query_db: &QueryDb) -> bool
+    where
+        Self: Sized,
+    {
+        self.kani_panic.is_some()
+            && query_db
+                .args()
+                .unstable_features
+                .iter()
+                .any(|feature| feature == "abstract-panic-fmt")
+    }
+
+    fn transform(&self, tcx: TyCtxt, body: Body, instance: Instance) -> (bool, Body) {
+        trace!(function=?instance.name(), "transform");
+        let kani_panic = self.kani_panic.unwrap();
+        let panic_targets = panic_targets(tcx, &body);
+        if panic_targets.iter().all(Option::is_none) {
+            return (false, body);
+        }
+        let mut blocks = body.blocks.clone();
+        let mut locals = body.locals().to_vec();
+        let mut modified = false;
+        let on_panic_path: Vec<bool> = panic_targets.iter().map(Option::is_some).collect();
+        for (bb, target) in panic_targets.into_iter().enumerate() {
+            let Some(target) = target else { continue };
+            let span = blocks[bb].terminator.span;
+            if bb != target {
+                // Skip the calls that build the message of the panic.
+                if let TerminatorKind::Call { func, target: Some(next), .. } =
+                    &blocks[bb].terminator.kind
+                    && is_fmt_call(body.locals(), func)
+                {
+                    let kind = TerminatorKind::Goto { target: *next };
+                    blocks[bb].terminator = Terminator { kind, span };
+                    modified = true;
+                }
+            } else if !has_constant_args(&blocks[bb].terminator) {
+                let mut new_local = |ty| {
+                    locals.push(LocalDecl { ty, span, mutability: Mutability::Not });
+                    locals.len() - 1
+                };
+                let func = Operand::Copy(Place::from(new_local(kani_panic.ty())));
+                let destination = Place::from(new_local(Ty::from_rigid_kind(RigidTy::Never)));
+                let msg = Operand::Constant(Constant {
+                    span,
+                    user_ty: None,
+                    literal: Const::from_str(PLACEHOLDER_MSG),
+                });
+                let kind = TerminatorKind::Call {
+                    func,
+                    args: vec![msg],
+                    destination,
+                    target: None,
+                    unwind: UnwindAction::Terminate,
+                };
+                blocks[bb].terminator = Terminator { kind, span };
+                modified = true;
+            }
+        }
+        if modified {
+            remove_unused_temporaries(&mut blocks, &body, &on_panic_path);
+        }
+        let arg_count = body.arg_locals().len();
+        let spread_arg = body.spread_arg();
+        let new_body =
+            Body::new(blocks, locals, arg_count, body.var_debug_info, spread_arg, body.span);
+        (modified, new_body)
+    }
+}
+
+/// For each basic block from which every path leads to a panic, the block that calls the panic
+/// function.
+fn panic_targets(tcx: TyCtxt, body: &Body) -> Vec<Option<BasicBlockIdx>> {
+    let mut targets: Vec<Option<BasicBlockIdx>> = body
+        .blocks
+        .iter()
+        .enumerate()
+        .map(|(bb, block)| is_panic_call(tcx, body.locals(), &block.terminator).then_some(bb))
+        .collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (bb, block) in body.blocks.iter().enumerate() {
+            if targets[bb].is_some() {
+                continue;
+            }
+            let successors = normal_successors(&block.terminator);
+            if let Some(first) = successors.first()
+                && successors.iter().all(|succ| targets[*succ].is_some())
+            {
+                targets[bb] = targets[*first];
+                changed = true;
+            }
+        }
+    }
+    targets
+}
+
+/// The successors of a terminator that don't unwind. This is `None` for terminators that
+/// return from the function, or that diverge without panicking.
+fn normal_successors(terminator: &Terminator) -> Vec<BasicBlockIdx> {
+    match &terminator.kind {
+        TerminatorKind::Goto { target }
+        | TerminatorKind::Drop { target, .. }
+        | TerminatorKind::Assert { target, .. }
+        | TerminatorKind::Call { target: Some(target), .. } => vec![*target],
+        TerminatorKind::SwitchInt { targets, .. } => targets.all_targets(),
+        TerminatorKind::Call { target: None, .. }
+        | TerminatorKind::Resume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::InlineAsm { .. } => vec![],
+    }
+}
+
+/// Whether the terminator calls a panic function of the standard library, i.e., a cold function
+/// that tracks its caller and never returns.
+fn is_panic_call(tcx: TyCtxt, locals: &[LocalDecl], terminator: &Terminator) -> bool {
+    let TerminatorKind::Call { func, target: None, .. } = &terminator.kind else { return false };
+    let func_ty = func.ty(locals).unwrap().kind();
+    let TyKind::RigidTy(RigidTy::FnDef(def, _)) = func_ty else {
+        return false;
+    };
+    if !STD_CRATES.contains(&def.krate().name.as_str())
+        || func_ty.fn_sig().unwrap().skip_binder().output().kind()
+            != TyKind::RigidTy(RigidTy::Never)
+    {
+        return false;
+    }
+    let flags = tcx.codegen_fn_attrs(rustc_internal::internal(tcx, def.def_id())).flags;
+    flags.contains(CodegenFnAttrFlags::COLD | CodegenFnAttrFlags::TRACK_CALLER)
+}
+
+/// Whether `func` is one of the `core::fmt` functions that build the message of a panic.
+fn is_fmt_call(locals: &[LocalDecl], func: &Operand) -> bool {
+    let TyKind::RigidTy(RigidTy::FnDef(def, _)) = func.ty(locals).unwrap().kind() else {
+        return false;
+    };
+    def.name().starts_with(FMT_PREFIX)
+}
+
+/// Remove the assignments of the blocks on the path to a panic to the temporaries that are no
+/// longer read, e.g., the arguments of the skipped formatting calls. The assignments that read
+/// through a pointer are kept, since they may be checked, and so are the user variables.
+fn remove_unused_temporaries(blocks: &mut [BasicBlock], body: &Body, on_panic_path: &[bool]) {
+    let user_vars: HashSet<Local> = body
+        .var_debug_info
+        .iter()
+        .filter_map(|info| match &info.value {
+            VarDebugInfoContents::Place(place) => Some(place.local),
+            VarDebugInfoContents::Const(_) => None,
+        })
+        .collect();
+    let first_temp = body.arg_locals().len() + 1;
+    let mut changed = true;
+    while changed {
+        let mut used = UsedLocals::default();
+        blocks.iter().for_each(|block| used.visit_basic_block(block));
+        changed = false;
+        for (block, _) in blocks.iter_mut().zip(on_panic_path).filter(|(_, on_path)| **on_path) {
+            let len = block.statements.len();
+            block.statements.retain(|stmt| {
+                let StatementKind::Assign(place, rvalue) = &stmt.kind else { return true };
+                !place.projection.is_empty()
+                    || place.local < first_temp
+                    || user_vars.contains(&place.local)
+                    || used.locals.contains(&place.local)
+                    || has_deref(rvalue)
+            });
+            changed |= block.statements.len() != len;
+        }
+    }
+}
+
+/// Whether the value of `rvalue` is read through a pointer.
+fn has_deref(rvalue: &Rvalue) -> bool {
+    let place_has_deref =
+        |place: &Place| place.projection.iter().any(|elem| matches!(elem, ProjectionElem::Deref));
+    let operand_has_deref = |operand: &Operand| match operand {
+        Operand::Copy(place) | Operand::Move(place) => place_has_deref(place),
+        Operand::Constant(_) => false,
+    };
+    match rvalue {
+        Rvalue::Use(operand)
+        | Rvalue::Repeat(operand, _)
+        | Rvalue::Cast(_, operand, _)
+        | Rvalue::UnaryOp(_, operand)
+        | Rvalue::ShallowInitBox(operand, _) => operand_has_deref(operand),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+            operand_has_deref(lhs) || operand_has_deref(rhs)
+        }
+        Rvalue::Aggregate(_, operands) => operands.iter().any(operand_has_deref),
+        Rvalue::Ref(_, _, place)
+        | Rvalue::AddressOf(_, place)
+        | Rvalue::Len(place)
+        | Rvalue::Discriminant(place)
+        | Rvalue::CopyForDeref(place) => place_has_deref(place),
+        Rvalue::ThreadLocalRef(_) | Rvalue::NullaryOp(..) => false,
+    }
+}
+
+/// Collect the locals that are read, or written through a projection, by the code.
+#[derive(Default)]
+struct UsedLocals {
+    locals: HashSet<Local>,
+}
+
+impl MirVisitor for UsedLocals {
+    fn visit_statement(&mut self, stmt: &Statement, location: Location) {
+        match &stmt.kind {
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) => {}
+            StatementKind::Assign(place, rvalue) if place.projection.is_empty() => {
+                self.visit_rvalue(rvalue, location)
+            }
+            _ => self.super_statement(stmt, location),
+        }
+    }
+
+    fn visit_terminator(&mut self, term: &Terminator, location: Location) {
+        if let TerminatorKind::Drop { place, .. } = &term.kind {
+            self.locals.insert(place.local);
+        }
+        self.super_terminator(term, location)
+    }
+
+    fn visit_place(&mut self, place: &Place, ptx: PlaceContext, location: Location) {
+        if !place.projection.is_empty() {
+            self.locals.insert(place.local);
+        }
+        self.super_place(place, ptx, location)
+    }
+
+    fn visit_local(&mut self, local: &Local, ptx: PlaceContext, _location: Location) {
+        if !ptx.is_mutating() {
+            self.locals.insert(*local);
+        }
+    }
+}
+
+/// Whether the arguments of a call are all constants.
+fn has_constant_args(terminator: &Terminator) -> bool {
+    let TerminatorKind::Call { args, .. } = &terminator.kind else { unreachable!() };
+    args.iter().all(|arg| matches!(arg, Operand::Constant(_)))
+}
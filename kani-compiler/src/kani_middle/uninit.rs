@@ -0,0 +1,540 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! MIR analyses used by the uninitialized memory checks enabled with `-Z uninit-checks`.
+//!
+//! The instrumentation itself is generated during codegen. This module computes the information
+//! that only depends on the MIR of a function:
+//!  - The layout of the initialized bytes of a type ([TypeBytes]).
+//!  - The locals whose memory may hold uninitialized bytes, and the reads that must be checked.
+//!  - A forward dataflow analysis that finds reads that don't need to be checked, because the
+//!    place was already checked or written with initialized data on every path, and nothing that
+//!    may have uninitialized it was executed since.
+
+use stable_mir::abi::{FieldsShape, LayoutShape, Scalar, TagEncoding, VariantsShape};
+use stable_mir::mir::{
+    AggregateKind, BasicBlockIdx, Body, Local, LocalDecl, Operand, Place, ProjectionElem, Rvalue,
+    Statement, StatementKind, Terminator, TerminatorKind,
+};
+use stable_mir::target::MachineInfo;
+use stable_mir::ty::{AdtKind, ConstantKind, IndexedVal, RigidTy, Ty, TyKind, VariantIdx};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Arrays with more elements than this that aren't made of plain bytes only have the bytes of
+/// their first elements described, which under-approximates the bytes that are checked.
+const MAX_EXPANDED_ELEMS: u64 = 64;
+
+/// The bytes of a sized type that are relevant to the uninitialized memory checks.
+#[derive(Clone, Debug, Default)]
+pub struct TypeBytes {
+    /// The size of the type in bytes.
+    pub size: usize,
+    /// The bytes that must be initialized in any value of this type.
+    pub required: Vec<Range<usize>>,
+    /// The bytes that become uninitialized when a value of this type is written.
+    pub padding: Vec<Range<usize>>,
+    /// Whether a value of this type may legitimately hold uninitialized bytes outside of its
+    /// padding, for example if it contains a union or an enum variant smaller than the enum.
+    pub may_be_uninit: bool,
+}
+
+impl TypeBytes {
+    /// Compute the bytes of the given type. Return `None` for unsized types.
+    pub fn of(ty: Ty) -> Option<TypeBytes> {
+        let layout = ty.layout().ok()?.shape();
+        if layout.is_unsized() {
+            return None;
+        }
+        let mut bytes = TypeBytes { size: layout.size.bytes(), ..Default::default() };
+        bytes.add(ty, &layout, 0);
+        normalize(&mut bytes.required);
+        normalize(&mut bytes.padding);
+        Some(bytes)
+    }
+
+    /// Whether every byte of the type is required, i.e., the type is like an integer.
+    pub fn is_plain(&self) -> bool {
+        !self.may_be_uninit && self.padding.is_empty()
+    }
+
+    /// Add the bytes of a field of type `ty` located at `offset`.
+    fn add(&mut self, ty: Ty, layout: &LayoutShape, offset: usize) {
+        let size = layout.size.bytes();
+        match &layout.fields {
+            FieldsShape::Primitive => self.required.push(offset..offset + size),
+            FieldsShape::Union(_) => self.may_be_uninit = true,
+            FieldsShape::Array { stride, count } => {
+                let TyKind::RigidTy(RigidTy::Array(elem_ty, _)) = ty.kind() else {
+                    // SIMD vectors only contain scalars.
+                    self.required.push(offset..offset + size);
+                    return;
+                };
+                let Some(elem) = TypeBytes::of(elem_ty) else { return };
+                if elem.is_plain() && elem.required.len() == 1 && elem.required[0] == (0..elem.size)
+                {
+                    self.required.push(offset..offset + size);
+                } else {
+                    self.may_be_uninit |= elem.may_be_uninit;
+                    for idx in 0..(*count).min(MAX_EXPANDED_ELEMS) {
+                        let elem_offset = offset + idx as usize * stride.bytes();
+                        self.required.extend(shift(&elem.required, elem_offset));
+                        self.padding.extend(shift(&elem.padding, elem_offset));
+                    }
+                }
+            }
+            FieldsShape::Arbitrary { offsets } => match &layout.variants {
+                VariantsShape::Multiple { tag, tag_field, variants, .. } => {
+                    // Only the tag is required, the bytes of the payload depend on the variant.
+                    let tag_offset = offset + offsets[*tag_field].bytes();
+                    let tag_size = scalar_size(tag);
+                    self.required.push(tag_offset..tag_offset + tag_size);
+                    self.may_be_uninit |= (0..variants.len()).any(|idx| {
+                        enum_uninit_bytes(ty, VariantIdx::to_val(idx))
+                            .map_or(true, |uninit| !uninit.is_empty())
+                    });
+                }
+                VariantsShape::Single { index } => {
+                    let Some(field_tys) = field_types(ty, *index) else {
+                        if matches!(
+                            ty.kind(),
+                            TyKind::RigidTy(RigidTy::RawPtr(..) | RigidTy::Ref(..))
+                        ) {
+                            // Wide pointers have no padding.
+                            self.required.push(offset..offset + size);
+                        } else {
+                            // We don't know the fields of closures and coroutines.
+                            self.may_be_uninit = true;
+                        }
+                        return;
+                    };
+                    let mut fields = vec![];
+                    for (idx, field_ty) in field_tys.into_iter().enumerate() {
+                        let Ok(field_layout) = field_ty.layout().map(|l| l.shape()) else {
+                            self.may_be_uninit = true;
+                            return;
+                        };
+                        let field_offset = offset + offsets[idx].bytes();
+                        fields.push(field_offset..field_offset + field_layout.size.bytes());
+                        self.add(field_ty, &field_layout, field_offset);
+                    }
+                    self.padding.extend(complement(&fields, offset..offset + size));
+                }
+            },
+        }
+    }
+}
+
+/// The types of the fields of the given variant, if known.
+fn field_types(ty: Ty, variant: VariantIdx) -> Option<Vec<Ty>> {
+    match ty.kind() {
+        TyKind::RigidTy(RigidTy::Adt(def, args)) => Some(
+            def.variant(variant)?.fields().iter().map(|field| field.ty_with_args(&args)).collect(),
+        ),
+        TyKind::RigidTy(RigidTy::Tuple(tys)) => Some(tys),
+        _ => None,
+    }
+}
+
+/// Compute the bytes that are uninitialized after writing the value of `rvalue` to a place of
+/// the type described by `bytes`.
+///
+/// This doesn't apply to copies of types that may be uninitialized, which copy the
+/// initialization state of each byte instead.
+pub fn uninit_bytes_written(ty: Ty, bytes: &TypeBytes, rvalue: &Rvalue) -> Vec<Range<usize>> {
+    match rvalue {
+        Rvalue::Use(Operand::Constant(constant)) => match constant.literal.kind() {
+            ConstantKind::Allocated(alloc) => {
+                let uninit = alloc
+                    .bytes
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, byte)| byte.is_none())
+                    .map(|(idx, _)| idx..idx + 1)
+                    .collect::<Vec<_>>();
+                let mut uninit = [uninit, bytes.padding.clone()].concat();
+                normalize(&mut uninit);
+                uninit
+            }
+            _ => bytes.padding.clone(),
+        },
+        Rvalue::Aggregate(AggregateKind::Adt(def, variant, args, _, active_field), _) => {
+            match def.kind() {
+                AdtKind::Union => {
+                    // Only the active field is initialized.
+                    let active = &def.variants()[0].fields()[active_field.unwrap()];
+                    let written = TypeBytes::of(active.ty_with_args(args))
+                        .map(|field| complement(&field.padding, 0..field.size))
+                        .unwrap_or_default();
+                    complement(&written, 0..bytes.size)
+                }
+                AdtKind::Enum => enum_uninit_bytes(ty, *variant).unwrap_or_default(),
+                AdtKind::Struct => bytes.padding.clone(),
+            }
+        }
+        _ => bytes.padding.clone(),
+    }
+}
+
+/// The bytes of an enum that are uninitialized when the given variant is created.
+fn enum_uninit_bytes(ty: Ty, variant: VariantIdx) -> Option<Vec<Range<usize>>> {
+    let layout = ty.layout().ok()?.shape();
+    let size = layout.size.bytes();
+    let VariantsShape::Multiple { tag, tag_field, tag_encoding, variants } = &layout.variants
+    else {
+        return Some(TypeBytes::of(ty)?.padding);
+    };
+    let FieldsShape::Arbitrary { offsets } = &layout.fields else { return None };
+    let mut written = vec![];
+    let untagged = match tag_encoding {
+        TagEncoding::Direct => false,
+        TagEncoding::Niche { untagged_variant, .. } => *untagged_variant == variant,
+    };
+    if !untagged {
+        let tag_offset = offsets[*tag_field].bytes();
+        written.push(tag_offset..tag_offset + scalar_size(tag));
+    }
+    let variant_layout = &variants[variant.to_index()];
+    let FieldsShape::Arbitrary { offsets: field_offsets } = &variant_layout.fields else {
+        return None;
+    };
+    for (idx, field_ty) in field_types(ty, variant)?.into_iter().enumerate() {
+        let field = TypeBytes::of(field_ty)?;
+        let field_offset = field_offsets[idx].bytes();
+        written.extend(shift(&complement(&field.padding, 0..field.size), field_offset));
+    }
+    normalize(&mut written);
+    Some(complement(&written, 0..size))
+}
+
+fn scalar_size(scalar: &Scalar) -> usize {
+    let (Scalar::Initialized { value, .. } | Scalar::Union { value }) = scalar;
+    value.size(&MachineInfo::target()).bytes()
+}
+
+/// Information about the places of a function body that the checks deal with.
+#[derive(Debug)]
+pub struct UninitInfo {
+    /// The locals whose memory may hold uninitialized bytes: the locals that may be accessed
+    /// through pointers and the locals whose type may be uninitialized.
+    candidates: Vec<bool>,
+    /// The reads that don't need to be checked, indexed by their basic block and instruction.
+    redundant: HashMap<(BasicBlockIdx, usize), Vec<Place>>,
+}
+
+impl UninitInfo {
+    pub fn new(body: &Body) -> UninitInfo {
+        let locals = body.locals();
+        let mut candidates = locals
+            .iter()
+            .map(|decl| TypeBytes::of(decl.ty).is_some_and(|bytes| bytes.may_be_uninit))
+            .collect::<Vec<_>>();
+        for bb in &body.blocks {
+            for stmt in &bb.statements {
+                if let StatementKind::Assign(
+                    _,
+                    Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place),
+                ) = &stmt.kind
+                    && !place.projection.contains(&ProjectionElem::Deref)
+                {
+                    candidates[place.local] = true;
+                }
+            }
+        }
+        let mut info = UninitInfo { candidates, redundant: HashMap::new() };
+        info.redundant = InitAnalysis { info: &info, locals }.run(body);
+        info
+    }
+
+    /// Whether the given local may hold uninitialized bytes.
+    pub fn is_candidate(&self, local: Local) -> bool {
+        self.candidates[local]
+    }
+
+    /// Whether the given place may hold uninitialized bytes.
+    pub fn is_trackable(&self, place: &Place) -> bool {
+        self.is_candidate(place.local) || place.projection.contains(&ProjectionElem::Deref)
+    }
+
+    /// The places read by a statement that must be initialized, in the order they are read.
+    pub fn statement_reads(&self, locals: &[LocalDecl], stmt: &Statement) -> Vec<Place> {
+        let mut reads = vec![];
+        match &stmt.kind {
+            StatementKind::Assign(_, rvalue) => self.rvalue_reads(locals, rvalue, &mut reads),
+            StatementKind::Intrinsic(_)
+            | StatementKind::FakeRead(..)
+            | StatementKind::SetDiscriminant { .. }
+            | StatementKind::Deinit(_)
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::Retag(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+        reads
+    }
+
+    /// The places read by a terminator that must be initialized, in the order they are read.
+    pub fn terminator_reads(&self, locals: &[LocalDecl], term: &Terminator) -> Vec<Place> {
+        let mut reads = vec![];
+        match &term.kind {
+            TerminatorKind::SwitchInt { discr: op, .. }
+            | TerminatorKind::Assert { cond: op, .. } => self.operand_reads(locals, op, &mut reads),
+            TerminatorKind::Call { func, args, .. } => {
+                self.operand_reads(locals, func, &mut reads);
+                args.iter().for_each(|arg| self.operand_reads(locals, arg, &mut reads));
+            }
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Drop { .. }
+            | TerminatorKind::InlineAsm { .. } => {}
+        }
+        reads
+    }
+
+    /// Whether the read of `place` by the given instruction doesn't need to be checked.
+    pub fn is_redundant(&self, bb: BasicBlockIdx, idx: usize, place: &Place) -> bool {
+        self.redundant.get(&(bb, idx)).is_some_and(|places| places.contains(place))
+    }
+
+    fn rvalue_reads(&self, locals: &[LocalDecl], rvalue: &Rvalue, reads: &mut Vec<Place>) {
+        match rvalue {
+            Rvalue::Use(op)
+            | Rvalue::Repeat(op, _)
+            | Rvalue::Cast(_, op, _)
+            | Rvalue::UnaryOp(_, op)
+            | Rvalue::ShallowInitBox(op, _) => self.operand_reads(locals, op, reads),
+            Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+                self.operand_reads(locals, lhs, reads);
+                self.operand_reads(locals, rhs, reads);
+            }
+            Rvalue::Aggregate(_, ops) => {
+                ops.iter().for_each(|op| self.operand_reads(locals, op, reads))
+            }
+            Rvalue::Discriminant(place) | Rvalue::CopyForDeref(place) => {
+                self.place_read(locals, place, reads)
+            }
+            Rvalue::AddressOf(..)
+            | Rvalue::Ref(..)
+            | Rvalue::Len(_)
+            | Rvalue::NullaryOp(..)
+            | Rvalue::ThreadLocalRef(_) => {}
+        }
+    }
+
+    fn operand_reads(&self, locals: &[LocalDecl], op: &Operand, reads: &mut Vec<Place>) {
+        match op {
+            Operand::Copy(place) | Operand::Move(place) => self.place_read(locals, place, reads),
+            Operand::Constant(_) => {}
+        }
+    }
+
+    fn place_read(&self, locals: &[LocalDecl], place: &Place, reads: &mut Vec<Place>) {
+        if self.is_trackable(place)
+            && let Ok(ty) = place.ty(locals)
+            && TypeBytes::of(ty).is_some_and(|bytes| !bytes.required.is_empty())
+        {
+            reads.push(place.clone());
+        }
+    }
+}
+
+/// The dataflow analysis that computes the places that are known to be initialized before each
+/// instruction.
+///
+/// This is a must analysis: a place is initialized at the start of a basic block only if it's
+/// initialized at the end of all of its predecessors. Any operation that may write uninitialized
+/// bytes through a pointer, including function calls, invalidates all the facts.
+struct InitAnalysis<'a> {
+    info: &'a UninitInfo,
+    locals: &'a [LocalDecl],
+}
+
+impl InitAnalysis<'_> {
+    fn run(&self, body: &Body) -> HashMap<(BasicBlockIdx, usize), Vec<Place>> {
+        // `None` represents a block that hasn't been reached yet.
+        let mut entry: Vec<Option<Vec<Place>>> = vec![None; body.blocks.len()];
+        entry[0] = Some(vec![]);
+        let mut worklist = vec![0];
+        while let Some(bb) = worklist.pop() {
+            let mut facts = entry[bb].clone().unwrap();
+            self.transfer_block(body, bb, &mut facts, &mut HashMap::new());
+            for succ in body.blocks[bb].terminator.successors() {
+                let changed = match &mut entry[succ] {
+                    Some(succ_facts) => {
+                        let len = succ_facts.len();
+                        succ_facts.retain(|place| facts.contains(place));
+                        succ_facts.len() != len
+                    }
+                    succ_facts @ None => {
+                        *succ_facts = Some(facts.clone());
+                        true
+                    }
+                };
+                if changed && !worklist.contains(&succ) {
+                    worklist.push(succ);
+                }
+            }
+        }
+
+        let mut redundant = HashMap::new();
+        for (bb, facts) in entry.into_iter().enumerate() {
+            if let Some(mut facts) = facts {
+                self.transfer_block(body, bb, &mut facts, &mut redundant);
+            }
+        }
+        redundant
+    }
+
+    fn transfer_block(
+        &self,
+        body: &Body,
+        bb: BasicBlockIdx,
+        facts: &mut Vec<Place>,
+        redundant: &mut HashMap<(BasicBlockIdx, usize), Vec<Place>>,
+    ) {
+        let block = &body.blocks[bb];
+        for (idx, stmt) in block.statements.iter().enumerate() {
+            let reads = self.info.statement_reads(self.locals, stmt);
+            self.transfer_reads(reads, facts, redundant.entry((bb, idx)).or_default());
+            self.transfer_statement(stmt, facts);
+        }
+        let reads = self.info.terminator_reads(self.locals, &block.terminator);
+        let idx = block.statements.len();
+        self.transfer_reads(reads, facts, redundant.entry((bb, idx)).or_default());
+        match &block.terminator.kind {
+            TerminatorKind::Call { .. }
+            | TerminatorKind::Drop { .. }
+            | TerminatorKind::InlineAsm { .. } => facts.clear(),
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::SwitchInt { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::Assert { .. } => {}
+        }
+    }
+
+    /// A read that was already checked is redundant, and the place is initialized after a read
+    /// that passed the check.
+    fn transfer_reads(
+        &self,
+        reads: Vec<Place>,
+        facts: &mut Vec<Place>,
+        redundant: &mut Vec<Place>,
+    ) {
+        for place in reads {
+            if facts.contains(&place) {
+                redundant.push(place);
+            } else {
+                facts.push(place);
+            }
+        }
+    }
+
+    fn transfer_statement(&self, stmt: &Statement, facts: &mut Vec<Place>) {
+        match &stmt.kind {
+            StatementKind::Assign(place, _) => {
+                if place.projection.is_empty() {
+                    facts.retain(|fact| !mentions(fact, place.local));
+                }
+                let plain = place
+                    .ty(self.locals)
+                    .ok()
+                    .and_then(TypeBytes::of)
+                    .is_some_and(|bytes| bytes.is_plain());
+                if !plain && self.info.is_trackable(place) {
+                    facts.clear();
+                }
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                if self.info.is_candidate(*local) {
+                    facts.clear();
+                } else {
+                    facts.retain(|fact| !mentions(fact, *local));
+                }
+            }
+            StatementKind::Deinit(_) | StatementKind::Intrinsic(_) => facts.clear(),
+            StatementKind::FakeRead(..)
+            | StatementKind::SetDiscriminant { .. }
+            | StatementKind::Retag(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+    }
+}
+
+/// Whether the value of `local` is used to compute the address of `place`.
+fn mentions(place: &Place, local: Local) -> bool {
+    place.local == local
+        || place
+            .projection
+            .iter()
+            .any(|elem| matches!(elem, ProjectionElem::Index(idx) if *idx == local))
+}
+
+/// Sort the given ranges, and merge the ones that overlap or are adjacent.
+fn normalize(ranges: &mut Vec<Range<usize>>) {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges.drain(..) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    *ranges = merged;
+}
+
+/// The bytes of `within` that aren't in any of the given ranges.
+fn complement(ranges: &[Range<usize>], within: Range<usize>) -> Vec<Range<usize>> {
+    let mut ranges = ranges.to_vec();
+    normalize(&mut ranges);
+    let mut result = vec![];
+    let mut start = within.start;
+    for range in ranges {
+        if range.start > start {
+            result.push(start..range.start.min(within.end));
+        }
+        start = start.max(range.end);
+    }
+    if start < within.end {
+        result.push(start..within.end);
+    }
+    result.retain(|range| !range.is_empty());
+    result
+}
+
+fn shift(ranges: &[Range<usize>], offset: usize) -> impl Iterator<Item = Range<usize>> + '_ {
+    ranges.iter().map(move |range| range.start + offset..range.end + offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complement, normalize};
+
+    #[test]
+    fn test_normalize() {
+        let mut ranges = vec![4..6, 0..2, 2..3, 5..8, 9..9];
+        normalize(&mut ranges);
+        assert_eq!(ranges, vec![0..3, 4..8]);
+    }
+
+    #[test]
+    fn test_complement() {
+        assert_eq!(complement(&[1..2, 4..6], 0..8), vec![0..1, 2..4, 6..8]);
+        assert!(complement(&[0..4, 4..8], 0..8).is_empty());
+        assert_eq!(complement(&[], 2..4), vec![2..4]);
+    }
+}
@@ -8,6 +8,7 @@
     collections::HashMap,
     path::PathBuf,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use crate::args::Arguments;
@@ -19,6 +20,7 @@ pub struct QueryDb {
     /// Information about all target harnesses.
     pub harnesses_info: HashMap<InternedString, PathBuf>,
     modifies_contracts: HashMap<InternedString, AssignsContract>,
+    codegen_times: HashMap<InternedString, Duration>,
 }
 
 impl QueryDb {
@@ -63,4 +65,15 @@ pub fn register_assigns_contract(
     pub fn assigns_contracts(&self) -> impl Iterator<Item = (&InternedString, &AssignsContract)> {
         self.modifies_contracts.iter()
     }
+
+    /// Register the time spent generating the goto model of a harness.
+    pub fn register_codegen_time(&mut self, harness_name: InternedString, time: Duration) {
+        self.codegen_times.insert(harness_name, time);
+    }
+
+    /// The time spent generating the goto model of a harness, if it was registered with
+    /// [`Self::register_codegen_time`].
+    pub fn codegen_time(&self, harness_name: InternedString) -> Option<Duration> {
+        self.codegen_times.get(&harness_name).copied()
+    }
 }
@@ -31,6 +31,8 @@
 extern crate rustc_span;
 extern crate rustc_target;
 extern crate stable_mir;
+// The instrumentation passes are loaded with the same libloading as rustc.
+extern crate libloading;
 // We can't add this directly as a dependency because we need the version to match rustc
 extern crate tempfile;
 
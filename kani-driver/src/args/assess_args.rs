@@ -16,6 +16,10 @@ pub struct AssessArgs {
     /// Write Assess metadata (unstable file format) to the given file
     #[arg(long, hide = true)]
     pub emit_metadata: Option<PathBuf>,
+
+    /// Also assess the path dependencies of the assessed packages that aren't workspace members
+    #[arg(long)]
+    pub path_dependencies: bool,
 }
 
 /// `cargo kani assess` takes optional subcommands to request specialized behavior
@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Implements the subcommand handling of the list subcommand
+
+use clap::ValueEnum;
+
+/// List the proof harnesses of a local package with their attributes.
+#[derive(Debug, clap::Parser)]
+pub struct CargoListArgs {
+    /// The format of the list.
+    #[arg(long, value_enum, default_value = "pretty")]
+    pub format: ListFormat,
+}
+
+/// The formats of `cargo kani list`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// A human-readable list of the harnesses.
+    Pretty,
+    /// A JSON document with the attributes of each harness, whose layout is identified by its
+    /// `schema_version` field.
+    Json,
+}
@@ -0,0 +1,37 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Implements the subcommand handling of the merge-results subcommand
+
+use crate::args::{CommonArgs, ValidateArgs};
+use clap::error::ErrorKind;
+use clap::Error;
+use std::path::PathBuf;
+
+/// Combine the results printed by `--output-format json` for each `--shard` of a run.
+#[derive(Debug, clap::Parser)]
+pub struct MergeResultsArgs {
+    /// The JSON results of the shards.
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Write the combined results to the given file instead of the standard output.
+    #[arg(long, short, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Common args always available to Kani subcommands.
+    #[command(flatten)]
+    pub common_opts: CommonArgs,
+}
+
+impl ValidateArgs for MergeResultsArgs {
+    fn validate(&self) -> Result<(), Error> {
+        self.common_opts.validate()?;
+        if let Some(file) = self.files.iter().find(|file| !file.is_file()) {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!("Invalid argument: `{}` is not a file", file.display()),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -5,17 +5,24 @@
 pub mod assess_args;
 pub mod cargo;
 pub mod common;
+pub mod list_args;
+pub mod merge_results_args;
 pub mod playback_args;
+pub mod replay_goto_args;
+pub mod report_args;
 
 pub use assess_args::*;
 
 use self::common::*;
 use crate::args::cargo::CargoTargetArgs;
+use crate::json_report::TraceExcerpt;
+use crate::severity::SeverityLevel;
+use crate::shard::Shard;
 use crate::util::warning;
 use cargo::CargoCommonArgs;
 use clap::builder::{PossibleValue, TypedValueParser};
 use clap::{error::ContextKind, error::ContextValue, error::Error, error::ErrorKind, ValueEnum};
-use kani_metadata::CbmcSolver;
+use kani_metadata::{CbmcSlicing, CbmcSolver, HarnessFilter};
 use std::ffi::OsString;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -59,6 +66,9 @@ pub fn print_deprecated(verbosity: &CommonArgs, option: &str, alternative: &str)
 // By default we configure CBMC to use 16 bits to represent the object bits in pointers.
 const DEFAULT_OBJECT_BITS: u32 = 16;
 
+// By default, the terse output format prints the last 10 steps of the trace of each failure.
+const DEFAULT_TRACE_LENGTH: usize = 10;
+
 #[derive(Debug, clap::Parser)]
 #[command(
     version,
@@ -90,6 +100,12 @@ pub struct StandaloneArgs {
 pub enum StandaloneSubcommand {
     /// Execute concrete playback testcases of a local crate.
     Playback(Box<playback_args::KaniPlaybackArgs>),
+
+    /// Verify the goto binaries exported with `--keep-goto`.
+    ReplayGoto(Box<replay_goto_args::ReplayGotoArgs>),
+
+    /// Combine the JSON results of the shards of a run.
+    MergeResults(Box<merge_results_args::MergeResultsArgs>),
 }
 
 #[derive(Debug, clap::Parser)]
@@ -115,6 +131,21 @@ pub enum CargoKaniSubcommand {
 
     /// Execute concrete playback testcases of a local package.
     Playback(Box<playback_args::CargoPlaybackArgs>),
+
+    /// Run the proof harnesses of a local package as unit tests with trivial values.
+    Test(Box<playback_args::CargoHarnessTestArgs>),
+
+    /// List the proof harnesses of a local package with their attributes.
+    List(Box<list_args::CargoListArgs>),
+
+    /// Verify the goto binaries exported with `--keep-goto`.
+    ReplayGoto(Box<replay_goto_args::ReplayGotoArgs>),
+
+    /// Render the results of the last verification as an HTML dashboard.
+    Report(Box<report_args::CargoReportArgs>),
+
+    /// Combine the JSON results of the shards of a run.
+    MergeResults(Box<merge_results_args::MergeResultsArgs>),
 }
 
 // Common arguments for invoking Kani for verification purpose. This gets put into KaniContext,
@@ -140,10 +171,27 @@ pub struct VerificationArgs {
         value_enum
     )]
     pub concrete_playback: Option<ConcretePlaybackMode>,
+    /// Only generate the concrete playback unit tests of the given satisfied cover statements,
+    /// which are identified by their property id, e.g., `check.cover.1`, or by their message.
+    /// The unit tests then play back an example that reaches the cover statement.
+    /// This option can be repeated.
+    #[arg(long = "playback-cover", value_name = "ID", requires("concrete_playback"))]
+    pub playback_covers: Vec<String>,
     /// Keep temporary files generated throughout Kani process. This is already the default
-    /// behavior for `cargo-kani`.
+    /// behavior for `cargo-kani`. Use `--keep-artifacts` to collect the artifacts of each
+    /// harness in a single directory instead.
     #[arg(long, hide_short_help = true)]
     pub keep_temps: bool,
+    /// Directory where the artifacts kept by `--keep-artifacts` are written, with one
+    /// `<CRATE>/<HARNESS>` sub-directory per harness. Defaults to `<target-dir>/kani/artifacts`
+    /// for cargo projects and to `kani-artifacts` next to the input file otherwise.
+    #[arg(long, value_name = "DIR")]
+    pub artifacts_dir: Option<PathBuf>,
+    /// Keep the instrumented goto binary, the results with their traces, and the log of the
+    /// harnesses whose verification failed, or of all the harnesses. Defaults to `all` if
+    /// `--artifacts-dir` is given, and to `none` otherwise.
+    #[arg(long, value_enum, value_name = "HARNESSES")]
+    pub keep_artifacts: Option<KeepArtifacts>,
 
     /// Generate C file equivalent to inputted program.
     /// This feature is unstable and it requires `--enable-unstable` to be used
@@ -162,6 +210,54 @@ pub struct VerificationArgs {
     /// Toggle between different styles of output
     #[arg(long, default_value = "regular", ignore_case = true, value_enum)]
     pub output_format: OutputFormat,
+    /// The number of trace steps shown for each failed check, which are the last assignments to
+    /// user variables before the failure. Defaults to 10, and 0 omits the traces. The regular
+    /// output format only prints the traces when this is set.
+    #[arg(long, value_name = "STEPS")]
+    pub trace_length: Option<usize>,
+    /// Print the assignments in the standard library in the traces of the failed checks, which
+    /// are otherwise collapsed into a single step.
+    #[arg(long)]
+    pub full_trace: bool,
+
+    /// Write the verification failures to the given file, using the SARIF format consumed by code
+    /// scanning tools.
+    #[arg(long)]
+    pub sarif: Option<PathBuf>,
+
+    /// A TOML file that lists the harnesses that are expected to fail. The verification succeeds
+    /// if the failing harnesses are exactly the listed ones that were verified.
+    #[arg(long)]
+    pub baseline: Option<PathBuf>,
+
+    /// Set whether an outcome of a harness fails the run, e.g., `--severity undetermined=warning`
+    /// reports the harnesses that time out without failing the run. The outcomes are
+    /// `undetermined`, `unsatisfiable-cover` and `unreachable-cover`, and the levels are `error`,
+    /// `warning` and `allow`. Failed properties always fail the run.
+    #[arg(long, value_name = "OUTCOME>=<LEVEL")]
+    pub severity: Vec<SeverityLevel>,
+
+    /// Print the time spent in each phase of the verification and the peak memory usage of each
+    /// harness, sorted by the given column in decreasing order.
+    #[arg(
+        long,
+        value_name = "COLUMN",
+        num_args(0..=1),
+        require_equals = true,
+        default_missing_value = "total",
+        value_enum
+    )]
+    pub profile: Option<ProfileSort>,
+
+    /// Only verify the harnesses assigned to the given shard, e.g., `--shard 2/4` verifies the
+    /// second of four disjoint sets of harnesses. Running every shard verifies every harness once.
+    #[arg(long, value_name = "INDEX>/<COUNT")]
+    pub shard: Option<Shard>,
+
+    /// The JSON results of a previous run, whose verification times are used to balance the
+    /// harnesses of the shards. All the shards must use the same file.
+    #[arg(long, value_name = "FILE", requires("shard"))]
+    pub shard_timings: Option<PathBuf>,
 
     #[command(flatten)]
     pub checks: CheckArgs,
@@ -172,6 +268,8 @@ pub struct VerificationArgs {
     pub function: Option<String>,
     /// If specified, only run harnesses that match this filter. This option can be provided
     /// multiple times, which will run all tests matching any of the filters.
+    /// A filter that contains `*`, `?` or `[` is a glob pattern, e.g., `parser::*`, and a filter
+    /// that starts with `regex:` is a regular expression matched against the harness names.
     /// If used with --exact, the harness filter will only match the exact fully qualified name of a harness.
     #[arg(
         long = "harness",
@@ -185,16 +283,52 @@ pub struct VerificationArgs {
     #[arg(long, requires("harnesses"))]
     pub exact: bool,
 
+    /// Don't run the harnesses that match this filter, even if they match a `--harness` filter.
+    /// This option can be provided multiple times. The filter is matched like the `--harness` one.
+    #[arg(long = "exclude-harness", num_args(1), value_name = "HARNESS_FILTER")]
+    pub exclude_harnesses: Vec<String>,
+
+    /// Replace a function by another one in every harness, as if each harness had the attribute
+    /// `#[kani::stub(ORIGINAL, REPLACEMENT)]`. This option can be provided multiple times.
+    /// This is an unstable feature and requires `-Z stubbing` to be used.
+    #[arg(long = "stub", value_name = "ORIGINAL=REPLACEMENT", hide_short_help = true)]
+    pub stubs: Vec<String>,
+
     /// Link external C files referenced by Rust code.
     /// This is an experimental feature and requires `-Z c-ffi` to be used
     #[arg(long, hide = true, num_args(1..))]
     pub c_lib: Vec<PathBuf>,
+    /// Run the instrumentation pass implemented by the given dynamic library over the function
+    /// bodies before they are translated. This option can be repeated.
+    /// This is an unstable feature and requires `-Z instrumentation-passes` to be used.
+    #[arg(long, hide_short_help = true)]
+    pub instrumentation_pass: Vec<PathBuf>,
     /// Enable test function verification. Only use this option when the entry point is a test function
     #[arg(long)]
     pub tests: bool,
     /// Kani will only compile the crate. No verification will be performed
     #[arg(long, hide_short_help = true)]
     pub only_codegen: bool,
+    /// Kani will only generate the verification conditions of each harness without solving
+    /// them. The conditions are written as SMT-LIB2 files to the `--smt2-out` directory.
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("smt2_out"),
+        conflicts_with_all(["only_codegen", "visualize", "concrete_playback", "coverage"])
+    )]
+    pub only_vcgen: bool,
+    /// Directory where `--only-vcgen` writes one `<HARNESS>.smt2` file per harness.
+    #[arg(long, requires("only_vcgen"), value_name = "DIR")]
+    pub smt2_out: Option<PathBuf>,
+    /// Export the instrumented goto binary of each harness, together with its properties and
+    /// the CBMC options to verify it, to the `--goto-out` directory.
+    /// Use `kani replay-goto <DIR>` to verify the exported harnesses.
+    #[arg(long, hide_short_help = true, requires("goto_out"))]
+    pub keep_goto: bool,
+    /// Directory where `--keep-goto` writes one sub-directory per harness.
+    #[arg(long, requires("keep_goto"), value_name = "DIR")]
+    pub goto_out: Option<PathBuf>,
 
     /// Deprecated flag. This is a no-op since we no longer support the legacy linker and
     /// it will be removed in a future Kani release.
@@ -210,10 +344,147 @@ pub struct VerificationArgs {
     /// Specify the value used for loop unwinding for the specified harness in CBMC
     #[arg(long, requires("harnesses"))]
     pub unwind: Option<u32>,
+
+    /// Select how harnesses are proven. `k-induction` proves the properties of harnesses with
+    /// unbounded loops for any number of iterations when they are k-inductive.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = ProofMode::Bounded,
+        conflicts_with_all(["visualize", "concrete_playback", "only_vcgen", "coverage"])
+    )]
+    pub proof_mode: ProofMode,
+    /// The depth `k` used by `--proof-mode k-induction`.
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub k_induction_depth: u32,
+
+    /// When a harness fails, report the inputs of the failing traces together with candidate
+    /// preconditions that would exclude them. The preconditions are a heuristic, and the harness
+    /// isn't verified again with them.
+    #[arg(long, conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub suggest_assumptions: bool,
+
+    /// Write the formula generated for each harness to the given directory, together with a
+    /// file that maps the formula variables to the program expressions they encode.
+    #[arg(long, value_name = "DIR", conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub dump_formula: Option<PathBuf>,
+    /// The format of the formulas written by `--dump-formula`.
+    #[arg(long, value_enum, default_value_t = FormulaFormat::Dimacs, requires("dump_formula"))]
+    pub formula_format: FormulaFormat,
+
+    /// Model that heap allocations may fail by returning a null pointer. This allows verifying the
+    /// code paths that handle allocation failures, e.g., `Vec::try_reserve`. Other allocations,
+    /// such as the ones in `Vec::push` or `Box::new`, abort the program when they fail, which is
+    /// reported as a verification failure.
+    #[arg(long)]
+    pub allocator_may_fail: bool,
+
+    /// Check the harnesses under the given weak memory model instead of sequential consistency.
+    /// Atomic operations are then only ordered as their `Ordering` argument requires, so the
+    /// result of lock-free code that uses `Relaxed`, `Acquire` or `Release` operations depends on
+    /// the interleavings the model allows. This only affects harnesses that spawn threads.
+    /// This is an unstable feature and requires `-Z weak-memory`.
+    #[arg(long, hide_short_help = true, value_enum)]
+    pub memory_model: Option<MemoryModel>,
+
+    /// Verify all harnesses again instead of reusing the results of previous runs. Results are
+    /// only reused for `cargo kani` projects, when neither the harness, the code it reaches nor
+    /// the verification options changed.
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Only verify the harnesses that changed since their last successful verification. A harness
+    /// changes when the code it reaches, its attributes or the verification options change. Only
+    /// supported by `cargo kani`.
+    #[arg(long)]
+    pub incremental: bool,
+
+    /// Verify the project again every time one of its source files changes, and report how the
+    /// results changed. Implies `--incremental`. Only supported by `cargo kani`.
+    #[arg(long)]
+    pub watch: bool,
+
+    /// Stop verifying a harness after the given number of seconds. The harness verification
+    /// fails, but the properties checked before the timeout are reported when used with
+    /// `--property-at-a-time`.
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub harness_timeout: Option<u64>,
+
+    /// Stop the verification after the first harness that fails, and cancel the harnesses that
+    /// are still running. Same as `--max-failures 1`.
+    #[arg(long, conflicts_with = "max_failures")]
+    pub fail_fast: bool,
+
+    /// Stop the verification after the given number of harnesses failed, and cancel the
+    /// harnesses that are still running.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_failures: Option<u64>,
+
+    /// Check the properties of each harness one at a time with a separate CBMC run. This is
+    /// usually slower, but the properties checked before a `--harness-timeout` are still
+    /// reported.
+    #[arg(long, conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub property_at_a_time: bool,
+
+    /// Split the properties of each harness into the given number of batches, and check them
+    /// with parallel CBMC runs. This helps harnesses with many independent checks, which are
+    /// otherwise checked by a single solver call.
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(2..),
+        conflicts_with_all(["visualize", "only_vcgen", "property_at_a_time"])
+    )]
+    pub property_batches: Option<u64>,
+
+    /// Package the evidence of the verification of each harness into the given archive: the
+    /// tool versions, the verification options, the checksum of the goto program, and the
+    /// properties with their results. The archive must be a `.tar`, `.tar.gz` or `.tar.zst` file.
+    #[arg(long, value_name = "FILE", conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub evidence_bundle: Option<PathBuf>,
+    /// Sign the manifest of the `--evidence-bundle` with the given GPG key.
+    #[arg(long, value_name = "KEY", requires("evidence_bundle"))]
+    pub evidence_signing_key: Option<String>,
+
+    /// Before reporting the counterexamples, search for smaller ones with further solver
+    /// queries: the values that can be zero are set to zero, and the magnitude of the other
+    /// integers is reduced as much as possible, from the first value to the last one.
+    #[arg(long, conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub minimize_counterexamples: bool,
+
+    /// When a check fails, show the pointers of the harness together with the objects they
+    /// point to at the failure point, instead of the raw values of the trace.
+    #[arg(long, conflicts_with_all(["visualize", "only_vcgen"]))]
+    pub heap_graph: bool,
+
+    /// After verification, check each property of a harness on its own and report the given
+    /// number of properties that took the longest to solve.
+    #[arg(long, conflicts_with_all(["visualize", "only_vcgen"]), value_name = "N")]
+    pub hardest_properties: Option<usize>,
+
+    /// Use CBMC's incremental solving to unwind the loop with the given id one iteration at a
+    /// time up to the unwinding bound, reusing the solver state between unwindings.
+    /// The other loops are still bounded by the unwinding bound. Run `cbmc --show-loops` on the
+    /// goto model kept with `--keep-temps` to list the loop ids of a harness.
+    #[arg(long, hide_short_help = true, requires("enable_unstable"), value_name = "LOOP_ID")]
+    pub incremental_unwind: Option<String>,
     /// Specify the CBMC solver to use. Overrides the harness `solver` attribute.
     /// If no solver is specified (with --solver or harness attribute), Kani will use CaDiCaL.
+    /// Use `portfolio:<SOLVER>,<SOLVER>,...` to run several solvers in parallel and keep the
+    /// result of the first one to finish.
     #[arg(long, value_parser = CbmcSolverValueParser::new(CbmcSolver::VARIANTS))]
     pub solver: Option<CbmcSolver>,
+    /// Use the SAT solver binary at the given path. Overrides the harness `solver` attribute.
+    /// The solver gets the DIMACS file to solve as its only argument, and must print its
+    /// result in the SAT competition format, i.e., an `s SATISFIABLE` or `s UNSATISFIABLE`
+    /// line, followed by the `v` lines of the model.
+    #[arg(
+        long,
+        conflicts_with("solver"),
+        value_parser = parse_external_sat_solver,
+        value_name = "PATH"
+    )]
+    pub external_sat_solver: Option<CbmcSolver>,
     /// Pass through directly to CBMC; must be the last flag.
     /// This feature is unstable and it requires `--enable_unstable` to be used
     #[arg(
@@ -225,8 +496,11 @@ pub struct VerificationArgs {
     // consumes everything
     pub cbmc_args: Vec<OsString>,
 
-    /// Number of parallel jobs, defaults to 1
-    #[arg(short, long, hide = true, requires("enable_unstable"))]
+    /// Number of harnesses to verify in parallel, which defaults to the number of cores, like `-j`
+    /// without a value. The output of each harness is printed when the harness is done. The
+    /// harnesses are verified one at a time by default with `--concrete-playback` and
+    /// `--output-format old`, which don't support parallel verification.
+    #[arg(short, long)]
     pub jobs: Option<Option<usize>>,
 
     /// Enable extra pointer checks such as invalid pointers in relation operations and pointer
@@ -264,6 +538,11 @@ pub struct VerificationArgs {
     #[arg(long, hide_short_help = true)]
     pub write_json_symtab: bool,
 
+    /// Don't fold the constants and prune the statically dead branches of the code before
+    /// generating the goto program, e.g., to debug the pre-pass.
+    #[arg(long, hide_short_help = true)]
+    pub no_prepass: bool,
+
     /// Execute CBMC's sanity checks to ensure the goto-program we generate is correct.
     #[arg(long, hide_short_help = true, requires("enable_unstable"))]
     pub run_sanity_checks: bool,
@@ -272,6 +551,11 @@ pub struct VerificationArgs {
     #[arg(long, hide_short_help = true, requires("enable_unstable"))]
     pub no_slice_formula: bool,
 
+    /// Select the CBMC slicing options. Overrides the harness `slicing` attribute.
+    /// Kani checks that the selected options are compatible with the other options.
+    #[arg(long, value_enum, conflicts_with("no_slice_formula"), value_name = "SLICING")]
+    pub slicing: Option<CbmcSlicing>,
+
     /// Synthesize loop contracts for all loops.
     #[arg(
         long,
@@ -304,6 +588,44 @@ pub struct VerificationArgs {
     #[arg(long, hide_short_help = true)]
     pub coverage: bool,
 
+    /// Where to report the coverage of code generated by macro expansions: at the outermost
+    /// macro invocation (`call-site`) or at the macro definition (`definition`).
+    #[arg(
+        long,
+        hide_short_help = true,
+        requires("coverage"),
+        value_enum,
+        default_value = "call-site"
+    )]
+    pub coverage_macro_location: CoverageMacroLocation,
+
+    /// Only print the coverage of each file with the ranges of lines that aren't covered by any
+    /// harness, instead of the coverage of every line for every harness.
+    #[arg(long, hide_short_help = true, requires("coverage"))]
+    pub summary_only: bool,
+
+    /// After verification, list the functions with the most code that isn't covered by any
+    /// harness, as candidates for new harnesses.
+    #[arg(long, hide_short_help = true, requires("coverage"))]
+    pub suggest_harnesses: bool,
+
+    /// Write the coverage results of all harnesses to the given file, using the JSON format
+    /// produced by `llvm-cov export`.
+    #[arg(long, hide_short_help = true, requires("coverage"))]
+    pub coverage_export: Option<PathBuf>,
+
+    /// Minimum percentage of lines that must be covered by all harnesses.
+    /// This is only enforced when running with `--coverage`.
+    #[arg(long, hide_short_help = true, value_parser = parse_percentage)]
+    pub min_line_coverage: Option<f64>,
+
+    /// Minimum percentage of lines that must be covered for the source files under a given path,
+    /// e.g.: `--min-line-coverage-for src/parser=90`. This option can be provided multiple times.
+    /// This is only enforced when running with `--coverage`, and a path without any file with
+    /// coverage checks is an error.
+    #[arg(long, hide_short_help = true, value_parser = parse_path_percentage)]
+    pub min_line_coverage_for: Vec<(PathBuf, f64)>,
+
     /// Arguments to pass down to Cargo
     #[command(flatten)]
     pub cargo: CargoCommonArgs,
@@ -336,15 +658,53 @@ pub fn cbmc_object_bits(&self) -> Option<u32> {
         }
     }
 
-    /// Computes how many threads should be used to verify harnesses.
+    /// The number of failed harnesses after which the verification stops, if any.
+    pub fn max_failures(&self) -> Option<usize> {
+        if self.fail_fast { Some(1) } else { self.max_failures.map(|max| max as usize) }
+    }
+
+    /// Computes how many threads should be used to verify harnesses, where `None` is the number
+    /// of cores.
     pub fn jobs(&self) -> Option<usize> {
         match self.jobs {
-            None => Some(1),          // no argument, default 1
-            Some(None) => None,       // -j
-            Some(Some(x)) => Some(x), // -j=x
+            // no argument, with options that need a sequential verification
+            None if self.concrete_playback.is_some() || self.output_format == OutputFormat::Old => {
+                Some(1)
+            }
+            None | Some(None) => None, // no argument or -j
+            Some(Some(x)) => Some(x),  // -j=x
+        }
+    }
+
+    /// The harnesses whose artifacts are kept, given `--keep-artifacts` and `--artifacts-dir`.
+    pub fn keep_artifacts(&self) -> KeepArtifacts {
+        match (self.keep_artifacts, &self.artifacts_dir) {
+            (Some(keep), _) => keep,
+            (None, Some(_)) => KeepArtifacts::All,
+            (None, None) => KeepArtifacts::None,
         }
     }
 
+    /// The number of trace steps shown for each failed check.
+    pub fn trace_length(&self) -> usize {
+        self.trace_length.unwrap_or(DEFAULT_TRACE_LENGTH)
+    }
+
+    /// The steps of the traces of the failed checks shown by the output format, which are the
+    /// same for every format, or `None` if the format doesn't show the traces.
+    pub fn trace_excerpt(&self) -> Option<TraceExcerpt> {
+        let shown = match self.output_format {
+            OutputFormat::Regular => self.trace_length.is_some(),
+            OutputFormat::Old => false,
+            OutputFormat::Terse
+            | OutputFormat::Json
+            | OutputFormat::Junit
+            | OutputFormat::Github => true,
+        };
+        (shown && self.trace_length() > 0)
+            .then_some(TraceExcerpt { len: self.trace_length(), full_trace: self.full_trace })
+    }
+
     /// Are experimental function contracts enabled?
     pub fn is_function_contracts_enabled(&self) -> bool {
         self.common_args.unstable_features.contains(UnstableFeature::FunctionContracts)
@@ -366,11 +726,93 @@ pub enum ConcretePlaybackMode {
     InPlace,
 }
 
+/// The harnesses whose artifacts are kept by `--keep-artifacts`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum KeepArtifacts {
+    /// Don't keep any artifact.
+    None,
+    /// Keep the artifacts of the harnesses that fail the run.
+    Failed,
+    /// Keep the artifacts of every harness.
+    All,
+}
+
+/// How Kani proves the properties of a harness.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProofMode {
+    /// Check the properties up to the unwinding bounds of the harness.
+    Bounded,
+    /// Prove the properties for any number of loop iterations with k-induction.
+    KInduction,
+}
+
+/// The weak memory models supported by `--memory-model`, from the strongest to the weakest.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, strum_macros::AsRefStr)]
+#[strum(serialize_all = "lowercase")]
+pub enum MemoryModel {
+    /// Total Store Order, as implemented by x86 processors.
+    Tso,
+    /// Partial Store Order, which also allows reordering stores to different locations.
+    Pso,
+    /// Relaxed Memory Order, which also allows reordering loads.
+    Rmo,
+    /// The memory model of Power and ARM processors.
+    Power,
+}
+
+/// The format of the formulas written by `--dump-formula`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FormulaFormat {
+    /// The CNF formula in the DIMACS format, as passed to SAT solvers.
+    Dimacs,
+    /// The SMT-LIB2 formula, as passed to SMT solvers.
+    Smt2,
+}
+
+/// The column used to sort the table printed by `--profile`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ProfileSort {
+    /// The time spent in all phases.
+    Total,
+    /// The time spent generating the goto model.
+    Codegen,
+    /// The time spent instrumenting the goto model with `goto-instrument`.
+    Instrument,
+    /// The time CBMC spent in symbolic execution.
+    Symex,
+    /// The time CBMC spent in the decision procedure.
+    Solver,
+    /// The peak memory usage of CBMC.
+    Memory,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CoverageMacroLocation {
+    CallSite,
+    Definition,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ValueEnum)]
 pub enum OutputFormat {
     Regular,
+    /// One line per harness, with the details of the failed checks only.
     Terse,
     Old,
+    /// A JSON document with the results of every harness, printed at the end of the run.
+    Json,
+    /// A JUnit XML report where each harness is a test case, printed at the end of the run.
+    Junit,
+    /// The terse output, followed by a GitHub Actions workflow command for each failed check at
+    /// the end of the run, which GitHub shows as an annotation at the location of the check.
+    Github,
+}
+
+impl OutputFormat {
+    /// Whether the result of each harness is printed on a single line, as with the terse output
+    /// format.
+    pub fn is_terse(&self) -> bool {
+        matches!(self, OutputFormat::Terse | OutputFormat::Github)
+    }
 }
 
 #[derive(Debug, clap::Args)]
@@ -411,6 +853,27 @@ pub struct CheckArgs {
     /// Turn off default unwinding checks
     #[arg(long)]
     pub no_unwinding_checks: bool,
+
+    /// Turn on checks that aren't enabled by default. This option can be provided multiple times.
+    #[arg(long = "check", value_enum, value_name = "CHECK")]
+    pub extra_checks: Vec<ExtraCheck>,
+
+    /// Check that the call stack never has more than the given number of frames, including the
+    /// frame of the harness. This can find unbounded recursion that overflows the stack, as long
+    /// as the unwinding value is larger than the depth.
+    #[arg(long, value_name = "DEPTH", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_call_depth: Option<u32>,
+}
+
+/// The checks that can be enabled with `--check`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExtraCheck {
+    /// Check that threads don't access the same memory location concurrently, with at least one
+    /// of the accesses being a write, unless both accesses are atomic.
+    DataRaces,
+    /// Check that all heap allocations are freed, or still reachable from a static, when the
+    /// harness returns.
+    Leaks,
 }
 
 impl CheckArgs {
@@ -427,6 +890,12 @@ pub fn undefined_function_on(&self) -> bool {
     pub fn unwinding_on(&self) -> bool {
         !self.no_default_checks && !self.no_unwinding_checks || self.unwinding_checks
     }
+    pub fn data_races_on(&self) -> bool {
+        self.extra_checks.contains(&ExtraCheck::DataRaces)
+    }
+    pub fn leaks_on(&self) -> bool {
+        self.extra_checks.contains(&ExtraCheck::Leaks)
+    }
 }
 
 /// Utility function to error out on arguments that are invalid Cargo specific.
@@ -434,6 +903,13 @@ pub fn unwinding_on(&self) -> bool {
 /// We currently define a bunch of cargo specific arguments as part of the overall arguments,
 /// however, they are invalid in the Kani standalone usage. Explicitly check them for now.
 /// TODO: Remove this as part of <https://github.com/model-checking/kani/issues/1831>
+/// Whether a `--stub` value has the form `ORIGINAL=REPLACEMENT`.
+fn is_valid_stub(stub: &str) -> bool {
+    stub.split_once('=').is_some_and(|(original, replacement)| {
+        !original.trim().is_empty() && !replacement.trim().is_empty() && !replacement.contains('=')
+    })
+}
+
 fn check_no_cargo_opt(is_set: bool, name: &str) -> Result<(), Error> {
     if is_set {
         Err(Error::raw(
@@ -460,6 +936,8 @@ fn validate(&self) -> Result<(), Error> {
         check_no_cargo_opt(!self.verify_opts.cargo.exclude.is_empty(), "--exclude")?;
         check_no_cargo_opt(self.verify_opts.cargo.workspace, "--workspace")?;
         check_no_cargo_opt(self.verify_opts.cargo.manifest_path.is_some(), "--manifest-path")?;
+        check_no_cargo_opt(self.verify_opts.incremental, "--incremental")?;
+        check_no_cargo_opt(self.verify_opts.watch, "--watch")?;
         if let Some(input) = &self.input {
             if !input.is_file() {
                 return Err(Error::raw(
@@ -490,6 +968,11 @@ fn validate(&self) -> Result<(), Error> {
             // Assess doesn't implement validation yet.
             CargoKaniSubcommand::Assess(_) => Ok(()),
             CargoKaniSubcommand::Playback(playback) => playback.validate(),
+            CargoKaniSubcommand::Test(test) => test.validate(),
+            CargoKaniSubcommand::List(_) => Ok(()),
+            CargoKaniSubcommand::ReplayGoto(replay) => replay.common_opts.validate(),
+            CargoKaniSubcommand::Report(report) => report.validate(),
+            CargoKaniSubcommand::MergeResults(merge) => merge.validate(),
         }
     }
 }
@@ -568,6 +1051,31 @@ fn validate(&self) -> Result<(), Error> {
                 "Conflicting options: --concrete-playback=print and --quiet.",
             ));
         }
+        if matches!(self.output_format, OutputFormat::Json | OutputFormat::Junit) {
+            // These formats are printed at the end of the run, and must be the only output.
+            let format = self.output_format.to_possible_value().unwrap();
+            let format = format.get_name();
+            if self.concrete_playback == Some(ConcretePlaybackMode::Print) {
+                return Err(Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "Conflicting options: --concrete-playback=print and --output-format={format}."
+                    ),
+                ));
+            }
+            if self.watch {
+                return Err(Error::raw(
+                    ErrorKind::ArgumentConflict,
+                    format!("Conflicting options: --watch and --output-format={format}."),
+                ));
+            }
+        }
+        if self.trace_length.is_some() && self.output_format == OutputFormat::Old {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "Conflicting options: --trace-length and --output-format=old.",
+            ));
+        }
         if self.concrete_playback.is_some() && self.output_format == OutputFormat::Old {
             return Err(Error::raw(
                 ErrorKind::ArgumentConflict,
@@ -582,12 +1090,26 @@ fn validate(&self) -> Result<(), Error> {
                 "Conflicting options: --concrete-playback isn't compatible with --jobs.",
             ));
         }
-        if self.jobs.is_some() && self.output_format != OutputFormat::Terse {
-            // More verbose output formats make it hard to interpret output right now when run in parallel.
-            // This can be removed when we change up how results are printed.
+        if self.minimize_counterexamples && self.output_format == OutputFormat::Old {
             return Err(Error::raw(
                 ErrorKind::ArgumentConflict,
-                "Conflicting options: --jobs requires `--output-format=terse`",
+                "Conflicting options: --minimize-counterexamples isn't compatible with \
+                --output-format=old.",
+            ));
+        }
+        if self.minimize_counterexamples && self.proof_mode == ProofMode::KInduction {
+            // The counterexamples of the base case are found in a different model.
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "Conflicting options: --minimize-counterexamples isn't compatible with \
+                --proof-mode=k-induction.",
+            ));
+        }
+        if self.jobs() != Some(1) && self.output_format == OutputFormat::Old {
+            // The old output format prints the output of CBMC as it's produced.
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "Conflicting options: --jobs isn't compatible with --output-format=old.",
             ));
         }
         if let Some(out_dir) = &self.target_dir {
@@ -601,6 +1123,36 @@ fn validate(&self) -> Result<(), Error> {
                 ));
             }
         }
+        for filter in self.harnesses.iter().chain(&self.exclude_harnesses) {
+            if let Err(err) = HarnessFilter::parse(filter) {
+                return Err(Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!("Invalid harness filter `{filter}`: {err}"),
+                ));
+            }
+        }
+        if let Some(timings) = &self.shard_timings {
+            if !timings.is_file() {
+                return Err(Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "Invalid argument: `--shard-timings` argument `{}` is not a file",
+                        timings.display()
+                    ),
+                ));
+            }
+        }
+        if let Some(baseline) = &self.baseline {
+            if !baseline.is_file() {
+                return Err(Error::raw(
+                    ErrorKind::InvalidValue,
+                    format!(
+                        "Invalid argument: `--baseline` argument `{}` is not a file",
+                        baseline.display()
+                    ),
+                ));
+            }
+        }
 
         if self.enable_stubbing {
             print_deprecated(&self.common_args, "--enable-stubbing", "-Z stubbing");
@@ -634,6 +1186,58 @@ fn validate(&self) -> Result<(), Error> {
             }
         }
 
+        if !self.instrumentation_pass.is_empty()
+            && !self.common_args.unstable_features.contains(UnstableFeature::InstrumentationPasses)
+        {
+            return Err(Error::raw(
+                ErrorKind::MissingRequiredArgument,
+                "The `--instrumentation-pass` argument is unstable and requires `-Z \
+                instrumentation-passes` to be used.",
+            ));
+        }
+
+        if !self.stubs.is_empty() && !self.is_stubbing_enabled() {
+            return Err(Error::raw(
+                ErrorKind::MissingRequiredArgument,
+                "The `--stub` argument is unstable and requires `-Z stubbing` to be used.",
+            ));
+        }
+
+        if let Some(stub) = self.stubs.iter().find(|stub| !is_valid_stub(stub)) {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!(
+                    "Invalid stub `{stub}`. Expected `ORIGINAL=REPLACEMENT`, where both are \
+                    function paths."
+                ),
+            ));
+        }
+
+        if let Some(path) = self.instrumentation_pass.iter().find(|path| !path.is_file()) {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!("Instrumentation pass `{}` doesn't exist.", path.display()),
+            ));
+        }
+
+        if self.checks.data_races_on() && self.memory_model.is_some() {
+            return Err(Error::raw(
+                ErrorKind::ArgumentConflict,
+                "`--check data-races` can't be used with `--memory-model`, since data races are \
+                checked under sequential consistency.",
+            ));
+        }
+
+        if self.memory_model.is_some()
+            && !self.common_args.unstable_features.contains(UnstableFeature::WeakMemory)
+        {
+            return Err(Error::raw(
+                ErrorKind::MissingRequiredArgument,
+                "The `--memory-model` argument is unstable and requires `-Z weak-memory` to be \
+                used.",
+            ));
+        }
+
         if self.coverage
             && !self.common_args.unstable_features.contains(UnstableFeature::LineCoverage)
         {
@@ -648,6 +1252,35 @@ fn validate(&self) -> Result<(), Error> {
     }
 }
 
+/// clap parser for the path of an external SAT solver binary.
+fn parse_external_sat_solver(value: &str) -> Result<CbmcSolver, String> {
+    use std::os::unix::fs::PermissionsExt;
+    let path = std::path::Path::new(value);
+    let metadata = path.metadata().map_err(|_| format!("`{value}` does not exist"))?;
+    if !metadata.is_file() || metadata.permissions().mode() & 0o111 == 0 {
+        return Err(format!("`{value}` is not an executable file"));
+    }
+    // CBMC runs the solver from a different process, so make the path independent of it.
+    let path = path.canonicalize().map_err(|err| err.to_string())?;
+    Ok(CbmcSolver::Binary(path.to_string_lossy().into_owned()))
+}
+
+/// clap parser for a percentage between 0 and 100.
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(percentage) if (0.0..=100.0).contains(&percentage) => Ok(percentage),
+        _ => Err(format!("expected a percentage between 0 and 100, but found `{value}`")),
+    }
+}
+
+/// clap parser for a `<PATH>=<PERCENTAGE>` pair.
+fn parse_path_percentage(value: &str) -> Result<(PathBuf, f64), String> {
+    let Some((path, percentage)) = value.rsplit_once('=') else {
+        return Err(format!("expected `<PATH>=<PERCENTAGE>`, but found `{value}`"));
+    };
+    Ok((PathBuf::from(path), parse_percentage(percentage)?))
+}
+
 /// clap parser for `CbmcSolver`
 #[derive(Clone, Debug)]
 pub struct CbmcSolverValueParser(Vec<PossibleValue>);
@@ -668,23 +1301,20 @@ fn parse_ref(
         value: &std::ffi::OsStr,
     ) -> Result<Self::Value, clap::error::Error> {
         let value = value.to_str().unwrap();
-        // `value` is one of the possible `CbmcSolver` values or `bin=<binary>`
-        let segments: Vec<&str> = value.split('=').collect();
 
         let mut err = clap::Error::new(ErrorKind::InvalidValue).with_cmd(cmd);
         err.insert(ContextKind::InvalidArg, ContextValue::String(arg.unwrap().to_string()));
         err.insert(ContextKind::InvalidValue, ContextValue::String(value.to_string()));
 
-        if segments.len() == 2 {
-            if segments[0] != "bin" {
-                return Err(err);
-            }
-            return Ok(CbmcSolver::Binary(segments[1].into()));
-        } else if segments.len() == 1 {
-            let solver = CbmcSolver::from_str(value);
-            return solver.map_err(|_| err);
+        // `value` is either `portfolio:<solver>,<solver>,...` or a single solver
+        if let Some(solvers) = value.strip_prefix("portfolio:") {
+            let solvers: Option<Vec<_>> = solvers.split(',').map(parse_single_solver).collect();
+            return match solvers {
+                Some(solvers) if !solvers.is_empty() => Ok(CbmcSolver::Portfolio(solvers)),
+                _ => Err(err),
+            };
         }
-        Err(err)
+        parse_single_solver(value).ok_or(err)
     }
 
     /// Used for the help message
@@ -693,6 +1323,16 @@ fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>
     }
 }
 
+/// Parse one of the possible `CbmcSolver` values or `bin=<binary>`.
+fn parse_single_solver(value: &str) -> Option<CbmcSolver> {
+    let segments: Vec<&str> = value.split('=').collect();
+    match segments[..] {
+        ["bin", binary] => Some(CbmcSolver::Binary(binary.into())),
+        [solver] => CbmcSolver::from_str(solver).ok(),
+        _ => None,
+    }
+}
+
 impl<I, T> From<I> for CbmcSolverValueParser
 where
     I: IntoIterator<Item = T>,
@@ -791,6 +1431,412 @@ macro_rules! check_opt {
         };
     }
 
+    #[test]
+    fn check_coverage_macro_location() {
+        let args = "kani input.rs -Z line-coverage --coverage --coverage-macro-location definition";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.coverage_macro_location, CoverageMacroLocation::Definition);
+
+        let default = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(default.verify_opts.coverage_macro_location, CoverageMacroLocation::CallSite);
+
+        let result = StandaloneArgs::try_parse_from(
+            "kani input.rs --coverage-macro-location definition".split(" "),
+        );
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_solver_portfolio() {
+        let args = "kani input.rs --solver portfolio:minisat,cadical,bin=my_solver";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(
+            parsed.verify_opts.solver,
+            Some(CbmcSolver::Portfolio(vec![
+                CbmcSolver::Minisat,
+                CbmcSolver::Cadical,
+                CbmcSolver::Binary("my_solver".into())
+            ]))
+        );
+
+        for invalid in ["portfolio:", "portfolio:minisat,foo", "portfolio:portfolio:minisat"] {
+            let result = StandaloneArgs::try_parse_from(["kani", "input.rs", "--solver", invalid]);
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidValue);
+        }
+    }
+
+    #[test]
+    fn check_only_vcgen() {
+        let args = "kani input.rs --only-vcgen --smt2-out out/";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert!(parsed.verify_opts.only_vcgen);
+        assert_eq!(parsed.verify_opts.smt2_out, Some(PathBuf::from("out/")));
+
+        for missing in ["kani input.rs --only-vcgen", "kani input.rs --smt2-out out/"] {
+            let result = StandaloneArgs::try_parse_from(missing.split(" "));
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+        }
+
+        let result = StandaloneArgs::try_parse_from(
+            "kani input.rs --only-vcgen --smt2-out out/ --visualize".split(" "),
+        );
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_incremental_unwind() {
+        let args = "kani input.rs --enable-unstable --incremental-unwind main.0 --default-unwind 5";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.incremental_unwind.as_deref(), Some("main.0"));
+
+        let result =
+            StandaloneArgs::try_parse_from("kani input.rs --incremental-unwind main.0".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_proof_mode() {
+        let default = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(default.verify_opts.proof_mode, ProofMode::Bounded);
+        assert_eq!(default.verify_opts.k_induction_depth, 1);
+
+        let args = "kani input.rs --proof-mode k-induction --k-induction-depth 3";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.proof_mode, ProofMode::KInduction);
+        assert_eq!(parsed.verify_opts.k_induction_depth, 3);
+
+        let result =
+            StandaloneArgs::try_parse_from("kani input.rs --k-induction-depth 0".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn check_dump_formula() {
+        let args = "kani input.rs --dump-formula out/ --formula-format smt2";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.dump_formula, Some(PathBuf::from("out/")));
+        assert_eq!(parsed.verify_opts.formula_format, FormulaFormat::Smt2);
+
+        let default = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(default.verify_opts.formula_format, FormulaFormat::Dimacs);
+
+        let args = "kani input.rs --formula-format smt2";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+    }
+
+    #[test]
+    fn check_evidence_bundle() {
+        let args = "kani input.rs --evidence-bundle out.tar.zst --evidence-signing-key alice";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.evidence_bundle, Some(PathBuf::from("out.tar.zst")));
+        assert_eq!(parsed.verify_opts.evidence_signing_key.as_deref(), Some("alice"));
+
+        let args = "kani input.rs --evidence-signing-key alice";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+
+        let args = "kani input.rs --evidence-bundle out.tar --visualize";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_stub() {
+        let args = parse_unstable_disabled("--stub a::f=b::g").unwrap();
+        let err = args.verify_opts.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+
+        let args = parse_unstable_disabled("-Z stubbing --stub a::f=b::g --stub c=d").unwrap();
+        assert_eq!(args.verify_opts.stubs, ["a::f=b::g", "c=d"]);
+        assert!(args.verify_opts.validate().is_ok());
+
+        for invalid in ["a::f", "=b", "a=", "a=b=c"] {
+            let args = parse_unstable_disabled(&format!("-Z stubbing --stub {invalid}")).unwrap();
+            let err = args.verify_opts.validate().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::InvalidValue);
+        }
+    }
+
+    #[test]
+    fn check_instrumentation_pass() {
+        let args = "kani input.rs -Z instrumentation-passes --instrumentation-pass Cargo.toml";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.instrumentation_pass, vec![PathBuf::from("Cargo.toml")]);
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        expect_validation_error(
+            "kani input.rs --instrumentation-pass Cargo.toml",
+            ErrorKind::MissingRequiredArgument,
+        );
+        expect_validation_error(
+            "kani input.rs -Z instrumentation-passes --instrumentation-pass missing.so",
+            ErrorKind::InvalidValue,
+        );
+    }
+
+    #[test]
+    fn check_jobs() {
+        let parsed = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.jobs(), None);
+        let args = "kani input.rs --output-format old";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.jobs(), Some(1));
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        let parsed = StandaloneArgs::try_parse_from("kani input.rs -j".split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.jobs(), None);
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        let args = "kani input.rs -j 4 --output-format regular";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.jobs(), Some(4));
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        expect_validation_error(
+            "kani input.rs -j 4 --output-format old",
+            ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn check_memory_model() {
+        let args = "kani input.rs -Z weak-memory --memory-model pso";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.memory_model, Some(MemoryModel::Pso));
+        assert_eq!(MemoryModel::Pso.as_ref(), "pso");
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        expect_validation_error(
+            "kani input.rs --memory-model tso",
+            ErrorKind::MissingRequiredArgument,
+        );
+    }
+
+    #[test]
+    fn check_keep_artifacts() {
+        let keep = |args: &str| {
+            StandaloneArgs::try_parse_from(args.split(" ")).unwrap().verify_opts.keep_artifacts()
+        };
+        assert_eq!(keep("kani input.rs"), KeepArtifacts::None);
+        assert_eq!(keep("kani input.rs --artifacts-dir out"), KeepArtifacts::All);
+        assert_eq!(keep("kani input.rs --keep-artifacts failed"), KeepArtifacts::Failed);
+        assert_eq!(
+            keep("kani input.rs --artifacts-dir out --keep-artifacts none"),
+            KeepArtifacts::None
+        );
+        assert!(
+            StandaloneArgs::try_parse_from("kani input.rs --keep-artifacts some".split(" "))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn check_trace_length() {
+        let args = "kani input.rs --output-format terse --trace-length 3";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.trace_length(), 3);
+        assert!(parsed.verify_opts.validate().is_ok());
+        let parsed = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.trace_length(), DEFAULT_TRACE_LENGTH);
+
+        expect_validation_error(
+            "kani input.rs --output-format old --trace-length 3",
+            ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn check_extra_checks() {
+        let parsed = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert!(!parsed.verify_opts.checks.data_races_on());
+        assert!(!parsed.verify_opts.checks.leaks_on());
+
+        let args = "kani input.rs --check data-races --check leaks";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert!(parsed.verify_opts.checks.data_races_on());
+        assert!(parsed.verify_opts.checks.leaks_on());
+        assert!(parsed.verify_opts.validate().is_ok());
+
+        expect_validation_error(
+            "kani input.rs --check data-races -Z weak-memory --memory-model tso",
+            ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn check_max_call_depth() {
+        let parsed = StandaloneArgs::try_parse_from("kani input.rs".split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.checks.max_call_depth, None);
+
+        let args = "kani input.rs --max-call-depth 10";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.checks.max_call_depth, Some(10));
+
+        let result = StandaloneArgs::try_parse_from("kani input.rs --max-call-depth 0".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+    }
+
+    #[test]
+    fn check_keep_goto() {
+        let args = "kani input.rs --keep-goto --goto-out out/";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert!(parsed.verify_opts.keep_goto);
+        assert_eq!(parsed.verify_opts.goto_out, Some(PathBuf::from("out/")));
+
+        let result = StandaloneArgs::try_parse_from("kani input.rs --keep-goto".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+
+        let args = StandaloneArgs::try_parse_from("kani replay-goto out/ --harness foo".split(" "))
+            .unwrap();
+        let Some(StandaloneSubcommand::ReplayGoto(replay)) = args.command else {
+            panic!("expected the `replay-goto` subcommand")
+        };
+        assert_eq!(replay.goto_dir, PathBuf::from("out/"));
+        assert_eq!(replay.harnesses, ["foo"]);
+
+        let args = CargoKaniArgs::try_parse_from("cargo-kani replay-goto out/".split(" ")).unwrap();
+        assert!(matches!(args.command, Some(CargoKaniSubcommand::ReplayGoto(..))));
+    }
+
+    #[test]
+    fn check_shard() {
+        let parsed =
+            StandaloneArgs::try_parse_from("kani input.rs --shard 2/3".split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.shard, Some(Shard { index: 2, count: 3 }));
+
+        let result = StandaloneArgs::try_parse_from("kani input.rs --shard 4/3".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+
+        let args = "kani input.rs --shard-timings timings.json";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+
+        let args = "kani input.rs --shard 1/2 --shard-timings missing.json";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.validate().unwrap_err().kind(), ErrorKind::InvalidValue);
+
+        let args = "kani merge-results 1.json 2.json -o all.json";
+        let args = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        let Some(StandaloneSubcommand::MergeResults(merge)) = args.command else {
+            panic!("expected the `merge-results` subcommand")
+        };
+        assert_eq!(merge.files, [PathBuf::from("1.json"), PathBuf::from("2.json")]);
+        assert_eq!(merge.output, Some(PathBuf::from("all.json")));
+
+        let args = "cargo-kani merge-results 1.json";
+        let args = CargoKaniArgs::try_parse_from(args.split(" ")).unwrap();
+        assert!(matches!(args.command, Some(CargoKaniSubcommand::MergeResults(..))));
+        assert_eq!(args.validate().unwrap_err().kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn check_report_subcommand() {
+        let args =
+            CargoKaniArgs::try_parse_from("cargo-kani report --html out/".split(" ")).unwrap();
+        let Some(CargoKaniSubcommand::Report(report)) = &args.command else {
+            panic!("expected the `report` subcommand")
+        };
+        assert_eq!(report.html, PathBuf::from("out/"));
+        assert_eq!(report.results, None);
+        assert!(args.validate().is_ok());
+
+        let result = CargoKaniArgs::try_parse_from("cargo-kani report".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+
+        let args = "cargo-kani report --html out/ --results missing.json";
+        let args = CargoKaniArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(args.validate().unwrap_err().kind(), ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn check_harness_timeout() {
+        let args = "kani input.rs --harness-timeout 60 --property-at-a-time";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.harness_timeout, Some(60));
+        assert!(parsed.verify_opts.property_at_a_time);
+
+        let result = StandaloneArgs::try_parse_from("kani input.rs --harness-timeout 0".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+
+        let args = "kani input.rs --property-at-a-time --visualize";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_max_failures() {
+        let max_failures = |args: &str| {
+            StandaloneArgs::try_parse_from(args.split(" ")).unwrap().verify_opts.max_failures()
+        };
+        assert_eq!(max_failures("kani input.rs"), None);
+        assert_eq!(max_failures("kani input.rs --fail-fast"), Some(1));
+        assert_eq!(max_failures("kani input.rs --max-failures 3"), Some(3));
+
+        let result = StandaloneArgs::try_parse_from("kani input.rs --max-failures 0".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+        let args = "kani input.rs --fail-fast --max-failures 2";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_property_batches() {
+        let args = "kani input.rs --property-batches 4";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.property_batches, Some(4));
+
+        let result =
+            StandaloneArgs::try_parse_from("kani input.rs --property-batches 1".split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+
+        let args = "kani input.rs --property-batches 4 --property-at-a-time";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_external_sat_solver() {
+        let args = "kani input.rs --external-sat-solver /bin/sh";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        let expected = std::fs::canonicalize("/bin/sh").unwrap();
+        assert_eq!(
+            parsed.verify_opts.external_sat_solver,
+            Some(CbmcSolver::Binary(expected.to_string_lossy().into_owned()))
+        );
+
+        for invalid in ["does/not/exist", "Cargo.toml", "src"] {
+            let result = StandaloneArgs::try_parse_from([
+                "kani",
+                "input.rs",
+                "--external-sat-solver",
+                invalid,
+            ]);
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+        }
+
+        let args = "kani input.rs --external-sat-solver /bin/sh --solver kissat";
+        let result = StandaloneArgs::try_parse_from(args.split(" "));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn check_min_line_coverage() {
+        let args = "kani input.rs --min-line-coverage 80 --min-line-coverage-for src/parser=90.5";
+        let parsed = StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+        assert_eq!(parsed.verify_opts.min_line_coverage, Some(80.0));
+        assert_eq!(
+            parsed.verify_opts.min_line_coverage_for,
+            vec![(PathBuf::from("src/parser"), 90.5)]
+        );
+
+        for invalid in ["--min-line-coverage 101", "--min-line-coverage-for src/parser"] {
+            let result =
+                StandaloneArgs::try_parse_from(format!("kani input.rs {invalid}").split(" "));
+            assert_eq!(result.unwrap_err().kind(), ErrorKind::ValueValidation);
+        }
+    }
+
     #[test]
     fn check_dry_run_fails() {
         // We don't support --dry-run anymore but we print a friendly reminder for now.
@@ -874,6 +1920,79 @@ fn check_concrete_playback_conflicts() {
         );
     }
 
+    #[test]
+    fn check_minimize_counterexamples_conflicts() {
+        let args = "kani test.rs --minimize-counterexamples --concrete-playback=print -Z \
+            concrete-playback";
+        let args = StandaloneArgs::try_parse_from(args.split_whitespace()).unwrap();
+        assert!(args.verify_opts.validate().is_ok());
+        expect_validation_error(
+            "kani --minimize-counterexamples --output-format=old test.rs",
+            ErrorKind::ArgumentConflict,
+        );
+        expect_validation_error(
+            "kani --minimize-counterexamples --proof-mode k-induction test.rs",
+            ErrorKind::ArgumentConflict,
+        );
+    }
+
+    #[test]
+    fn check_playback_cover_requires_concrete_playback() {
+        let args = "kani test.rs --playback-cover check.cover.1";
+        let result = StandaloneArgs::try_parse_from(args.split_whitespace());
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::MissingRequiredArgument);
+        let args = "kani test.rs -Z concrete-playback --concrete-playback=inplace \
+            --playback-cover check.cover.1 --playback-cover Ok";
+        let args = StandaloneArgs::try_parse_from(args.split_whitespace()).unwrap();
+        assert_eq!(args.verify_opts.playback_covers, ["check.cover.1", "Ok"]);
+    }
+
+    #[test]
+    fn check_harness_filters() {
+        let args = "kani input.rs --harness parser::* --harness regex:^check_(a|b)$";
+        let args = StandaloneArgs::try_parse_from(args.split_whitespace()).unwrap();
+        assert!(args.verify_opts.validate().is_ok());
+        expect_validation_error("kani input.rs --harness regex:check_(", ErrorKind::InvalidValue);
+        expect_validation_error("kani input.rs --exclude-harness check_[", ErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn check_trace_excerpt() {
+        let excerpt = |args: &str| {
+            let args = StandaloneArgs::try_parse_from(args.split_whitespace()).unwrap();
+            args.verify_opts.trace_excerpt().map(|excerpt| (excerpt.len, excerpt.full_trace))
+        };
+        assert_eq!(excerpt("kani input.rs"), None);
+        assert_eq!(excerpt("kani input.rs --trace-length 3"), Some((3, false)));
+        assert_eq!(excerpt("kani input.rs --output-format json --full-trace"), Some((10, true)));
+        assert_eq!(excerpt("kani input.rs --output-format junit --trace-length 0"), None);
+        assert_eq!(excerpt("kani input.rs --output-format old"), None);
+    }
+
+    #[test]
+    fn check_report_output_conflicts() {
+        expect_validation_error(
+            "kani --concrete-playback=print --output-format=json -Z concrete-playback test.rs",
+            ErrorKind::ArgumentConflict,
+        );
+        expect_validation_error(
+            "kani --concrete-playback=print --output-format=junit -Z concrete-playback test.rs",
+            ErrorKind::ArgumentConflict,
+        );
+        for format in ["json", "junit"] {
+            let args = format!("cargo-kani --watch --output-format={format}");
+            let args = CargoKaniArgs::try_parse_from(args.split_whitespace()).unwrap();
+            assert_eq!(
+                args.verify_opts.validate().unwrap_err().kind(),
+                ErrorKind::ArgumentConflict
+            );
+        }
+        let args =
+            "kani --concrete-playback=inplace --output-format=json -Z concrete-playback test.rs";
+        let args = StandaloneArgs::try_parse_from(args.split_whitespace()).unwrap();
+        assert!(args.verify_opts.validate().is_ok());
+    }
+
     #[test]
     fn check_enable_stubbing() {
         check_unstable_flag!("--enable-stubbing --harness foo", enable_stubbing);
@@ -929,5 +2048,7 @@ fn check_invalid_args<'a, I>(args: I)
         check_invalid_args("kani input.rs --workspace".split_whitespace());
         check_invalid_args("kani input.rs --package foo".split_whitespace());
         check_invalid_args("kani input.rs --exclude bar --workspace".split_whitespace());
+        check_invalid_args("kani input.rs --incremental".split_whitespace());
+        check_invalid_args("kani input.rs --watch".split_whitespace());
     }
 }
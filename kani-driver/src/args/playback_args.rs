@@ -20,6 +20,17 @@ pub struct CargoPlaybackArgs {
     pub cargo: CargoTestArgs,
 }
 
+/// Run the proof harnesses of a local package as unit tests.
+#[derive(Debug, Parser)]
+pub struct CargoHarnessTestArgs {
+    #[command(flatten)]
+    pub playback: PlaybackArgs,
+
+    /// Arguments to pass down to Cargo that are specific to tests.
+    #[command(flatten)]
+    pub cargo: CargoTestArgs,
+}
+
 /// Execute concrete playback testcases of a local crate.
 #[derive(Debug, Parser)]
 pub struct KaniPlaybackArgs {
@@ -68,6 +79,13 @@ fn validate(&self) -> Result<(), Error> {
     }
 }
 
+impl ValidateArgs for CargoHarnessTestArgs {
+    fn validate(&self) -> Result<(), Error> {
+        self.playback.validate_subcommand("test")?;
+        self.cargo.validate()
+    }
+}
+
 impl ValidateArgs for KaniPlaybackArgs {
     fn validate(&self) -> Result<(), Error> {
         self.playback.validate()?;
@@ -86,12 +104,21 @@ fn validate(&self) -> Result<(), Error> {
 
 impl ValidateArgs for PlaybackArgs {
     fn validate(&self) -> Result<(), Error> {
+        self.validate_subcommand("playback")
+    }
+}
+
+impl PlaybackArgs {
+    /// Validate the arguments of `subcommand`, which builds the tests like `playback`.
+    fn validate_subcommand(&self, subcommand: &str) -> Result<(), Error> {
         self.common_opts.validate()?;
         if !self.common_opts.unstable_features.contains(UnstableFeature::ConcretePlayback) {
             return Err(Error::raw(
                 ErrorKind::MissingRequiredArgument,
-                "The `playback` subcommand is unstable and requires `-Z concrete-playback` \
-                to be used.",
+                format!(
+                    "The `{subcommand}` subcommand is unstable and requires \
+                    `-Z concrete-playback` to be used."
+                ),
             ));
         }
         Ok(())
@@ -148,6 +175,20 @@ fn check_kani_parse_extra_args() {
         assert_eq!(args.playback.test_args, ["TEST_NAME", "--exact", "--nocapture"])
     }
 
+    #[test]
+    fn check_cargo_parse_harness_test_works() {
+        let input = "test -Z concrete-playback -p PKG_NAME -- check_".split_whitespace();
+        let args = CargoHarnessTestArgs::try_parse_from(input).unwrap();
+        args.validate().unwrap();
+        assert_eq!(&args.cargo.common.package, &["PKG_NAME"]);
+        assert_eq!(args.playback.test_args, ["check_"]);
+
+        let args = CargoHarnessTestArgs::try_parse_from(["test"]).unwrap();
+        let err = args.validate().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument);
+        assert!(err.to_string().contains("The `test` subcommand is unstable"));
+    }
+
     #[test]
     fn check_kani_no_unstable_fails() {
         let input = "playback input.rs".split_whitespace();
@@ -0,0 +1,22 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Implements the subcommand handling of the replay-goto subcommand
+
+use crate::args::CommonArgs;
+use std::path::PathBuf;
+
+/// Verify the goto binaries exported with `--keep-goto --goto-out <DIR>`.
+#[derive(Debug, clap::Parser)]
+pub struct ReplayGotoArgs {
+    /// The directory given to `--goto-out`.
+    pub goto_dir: PathBuf,
+
+    /// Common args always available to Kani subcommands.
+    #[command(flatten)]
+    pub common_opts: CommonArgs,
+
+    /// If specified, only replay the harnesses that match one of these filters, which are matched
+    /// like the `--harness` filters of the verification.
+    #[arg(long = "harness", num_args(1), value_name = "HARNESS_FILTER")]
+    pub harnesses: Vec<String>,
+}
@@ -0,0 +1,51 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Implements the subcommand handling of the report subcommand
+
+use crate::args::{CommonArgs, ValidateArgs};
+use clap::error::ErrorKind;
+use clap::Error;
+use std::path::PathBuf;
+
+/// Render the results of the last verification of a local package.
+#[derive(Debug, clap::Parser)]
+pub struct CargoReportArgs {
+    /// Write a static HTML dashboard of the results to the given directory.
+    #[arg(long, value_name = "DIR")]
+    pub html: PathBuf,
+
+    /// Render the document printed by `--output-format json` instead of the results of the last
+    /// verification.
+    #[arg(long, value_name = "FILE")]
+    pub results: Option<PathBuf>,
+
+    /// Path to Cargo.toml
+    #[arg(long, name = "PATH")]
+    pub manifest_path: Option<PathBuf>,
+
+    /// The `--target-dir` of the verification.
+    #[arg(long)]
+    pub target_dir: Option<PathBuf>,
+
+    /// Common args always available to Kani subcommands.
+    #[command(flatten)]
+    pub common_opts: CommonArgs,
+}
+
+impl ValidateArgs for CargoReportArgs {
+    fn validate(&self) -> Result<(), Error> {
+        self.common_opts.validate()?;
+        if let Some(results) = &self.results
+            && !results.is_file()
+        {
+            return Err(Error::raw(
+                ErrorKind::InvalidValue,
+                format!(
+                    "Invalid argument: `--results` argument `{}` is not a file",
+                    results.display()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
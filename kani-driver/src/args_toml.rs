@@ -5,25 +5,83 @@
 use clap::Parser;
 use std::collections::BTreeMap;
 use std::ffi::OsString;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use toml::value::Table;
 use toml::Value;
 
+/// The name of the Kani configuration file, which may be placed next to a `Cargo.toml`.
+const KANI_TOML: &str = "kani.toml";
+
+/// The sections supported in a `kani.toml` file. They match the entries of the Kani tables in
+/// `Cargo.toml`.
+const KANI_TOML_SECTIONS: [&str; 6] =
+    ["flags", "unstable", "coverage", "harnesses", "stubs", "severity"];
+
 /// Produce the list of arguments to pass to ourself (cargo-kani).
 ///
-/// The arguments passed via command line have precedence over the ones from the Cargo.toml.
+/// The arguments passed via command line have precedence over the ones from the `kani.toml`,
+/// which have precedence over the ones from the Cargo.toml.
 pub fn join_args(input_args: Vec<OsString>) -> Result<Vec<OsString>> {
     let toml_path = cargo_locate_project(&input_args);
     if toml_path.is_err() {
         // We're not inside a Cargo project. Don't error... yet.
         return Ok(input_args);
     }
-    let file = std::fs::read_to_string(toml_path?)?;
-    let (kani_args, cbmc_args) = toml_to_args(&file)?;
+    let toml_path = toml_path?;
+    let file = std::fs::read_to_string(&toml_path)?;
+    let kani_file = read_kani_toml(&toml_path)?;
+    let (kani_args, cbmc_args) = toml_to_args(&file, kani_file.as_deref())?;
+    merge_args(input_args, kani_args, cbmc_args)
+}
+
+/// Produce the list of arguments to verify the workspace member with the manifest `member_toml`,
+/// in a workspace with the manifest `workspace_toml`.
+///
+/// The arguments passed via command line have precedence over the ones from the configuration
+/// files.
+pub fn join_member_args(
+    input_args: Vec<OsString>,
+    workspace_toml: &Path,
+    member_toml: &Path,
+) -> Result<Vec<OsString>> {
+    let workspace_file = std::fs::read_to_string(workspace_toml)?;
+    let workspace_kani_file = read_kani_toml(workspace_toml)?;
+    let member_file = std::fs::read_to_string(member_toml)?;
+    // The `kani.toml` of the workspace root is already used for the whole workspace.
+    let member_kani_file = if workspace_toml.parent() == member_toml.parent() {
+        None
+    } else {
+        read_kani_toml(member_toml)?
+    };
+    let (kani_args, cbmc_args) = member_toml_to_args(
+        (&workspace_file, workspace_kani_file.as_deref()),
+        (&member_file, member_kani_file.as_deref()),
+    )?;
     merge_args(input_args, kani_args, cbmc_args)
 }
 
+/// Read the `kani.toml` file next to the manifest `cargo_toml`, if there is one.
+fn read_kani_toml(cargo_toml: &Path) -> Result<Option<String>> {
+    let path = cargo_toml.with_file_name(KANI_TOML);
+    if path.is_file() { Ok(Some(std::fs::read_to_string(path)?)) } else { Ok(None) }
+}
+
+/// Parse the content of a `kani.toml` file, and check that it only has supported sections.
+fn parse_kani_toml(data: &str) -> Result<Value> {
+    let config = data.parse::<Value>()?;
+    if let Some(table) = config.as_table() {
+        if let Some(section) = table.keys().find(|key| !KANI_TOML_SECTIONS.contains(&key.as_str()))
+        {
+            bail!(
+                "Unknown section `{section}` in `{KANI_TOML}`. The supported sections are: {}",
+                KANI_TOML_SECTIONS.join(", ")
+            );
+        }
+    }
+    Ok(config)
+}
+
 /// Join the arguments passed via command line with the ones found in the Cargo.toml.
 ///
 /// The arguments passed via command line have precedence over the ones from the Cargo.toml. Thus,
@@ -90,18 +148,67 @@ fn cargo_locate_project(input_args: &[OsString]) -> Result<PathBuf> {
 /// We currently support the following entries:
 /// - flags: Flags that get directly passed to Kani.
 /// - unstable: Unstable features (it will be passed using `-Z` flag).
+/// - coverage: Minimum coverage required when running with `--coverage`.
+/// - harnesses: Harnesses to `include` and to `exclude`.
+/// - stubs: Functions to replace in every harness, mapped to their replacement.
+/// - severity: Whether each outcome of a harness fails the run.
 /// The tables supported are:
 /// "workspace.metadata.kani", "package.metadata.kani", "kani"
-fn toml_to_args(tomldata: &str) -> Result<(Vec<OsString>, Vec<OsString>)> {
+/// The entries of the optional `kani.toml` file next to the Cargo.toml are the same as the ones of
+/// these tables, and they have precedence.
+fn toml_to_args(
+    tomldata: &str,
+    kani_tomldata: Option<&str>,
+) -> Result<(Vec<OsString>, Vec<OsString>)> {
     let config = tomldata.parse::<Value>()?;
+    let kani_config = kani_tomldata.map(parse_kani_toml).transpose()?;
+    let tables = ["workspace.metadata.kani", "package.metadata.kani", "kani"];
+    tables_to_args(
+        tables
+            .iter()
+            .map(|table| (&config, *table))
+            .chain(kani_config.iter().map(|kani_config| (kani_config, ""))),
+    )
+}
+
+/// Produce the list of arguments to verify a member of a workspace, given the contents of the
+/// workspace and the member manifests, and of their optional `kani.toml` files.
+///
+/// The configuration of the member has precedence over the `[workspace.metadata.kani]`
+/// configuration of the workspace. The package configuration of the workspace root isn't used,
+/// since it only applies to the root package.
+fn member_toml_to_args(
+    (workspace_data, workspace_kani_data): (&str, Option<&str>),
+    (member_data, member_kani_data): (&str, Option<&str>),
+) -> Result<(Vec<OsString>, Vec<OsString>)> {
+    let workspace = workspace_data.parse::<Value>()?;
+    let workspace_kani = workspace_kani_data.map(parse_kani_toml).transpose()?;
+    let member = member_data.parse::<Value>()?;
+    let member_kani = member_kani_data.map(parse_kani_toml).transpose()?;
+    fn kani_table(config: &Option<Value>) -> Vec<(&Value, &str)> {
+        config.iter().map(|config| (config, "")).collect()
+    }
+    tables_to_args(
+        [(&workspace, "workspace.metadata.kani")]
+            .into_iter()
+            .chain(kani_table(&workspace_kani))
+            .chain([(&member, "package.metadata.kani"), (&member, "kani")])
+            .chain(kani_table(&member_kani)),
+    )
+}
+
+/// Extract the arguments from the given tables. The flags of later tables override the ones of
+/// earlier tables.
+fn tables_to_args<'a>(
+    tables: impl IntoIterator<Item = (&'a Value, &'a str)>,
+) -> Result<(Vec<OsString>, Vec<OsString>)> {
     // To make testing easier, our function contract is to produce a stable ordering of flags for a given input.
     // Consequently, we use BTreeMap instead of HashMap here.
     let mut map: BTreeMap<String, Value> = BTreeMap::new();
-    let tables = ["workspace.metadata.kani", "package.metadata.kani", "kani"];
     let mut args = Vec::new();
 
-    for table in tables {
-        if let Some(table) = get_table(&config, table) {
+    for (config, table) in tables {
+        if let Some(table) = get_table(config, table) {
             if let Some(entry) = table.get("flags") {
                 if let Some(val) = entry.as_table() {
                     map.extend(val.iter().map(|(x, y)| (x.to_owned(), y.to_owned())));
@@ -118,6 +225,30 @@ fn toml_to_args(tomldata: &str) -> Result<(Vec<OsString>, Vec<OsString>)> {
                     );
                 }
             }
+
+            if let Some(entry) = table.get("coverage") {
+                if let Some(val) = entry.as_table() {
+                    args.append(&mut coverage_args(val)?);
+                }
+            }
+
+            if let Some(entry) = table.get("harnesses") {
+                if let Some(val) = entry.as_table() {
+                    args.append(&mut harnesses_args(val)?);
+                }
+            }
+
+            if let Some(entry) = table.get("stubs") {
+                if let Some(val) = entry.as_table() {
+                    args.append(&mut stubs_args(val)?);
+                }
+            }
+
+            if let Some(entry) = table.get("severity") {
+                if let Some(val) = entry.as_table() {
+                    args.append(&mut severity_args(val)?);
+                }
+            }
         }
     }
 
@@ -145,6 +276,129 @@ fn unstable_entry(name: &String, value: &Value) -> Result<Option<OsString>> {
     }
 }
 
+/// Translate the coverage table into the arguments that set the minimum coverage.
+///
+/// The `line` entry sets the minimum line coverage for all files, while the `modules` table sets
+/// it per module path, e.g.:
+/// ```toml
+/// [package.metadata.kani.coverage]
+/// line = 80
+/// [package.metadata.kani.coverage.modules."src/parser"]
+/// line = 90
+/// ```
+fn coverage_args(table: &Table) -> Result<Vec<OsString>> {
+    let mut args = Vec::new();
+    for (key, value) in table {
+        match key.as_str() {
+            "line" => {
+                args.push("--min-line-coverage".into());
+                args.push(coverage_percentage(key, value)?.into());
+            }
+            "modules" => {
+                let Some(modules) = value.as_table() else {
+                    bail!("Expected a table of module paths for `coverage.modules`")
+                };
+                for (path, thresholds) in modules {
+                    let Some(thresholds) = thresholds.as_table() else {
+                        bail!("Expected a table of coverage settings for module `{path}`")
+                    };
+                    for (kind, value) in thresholds {
+                        if kind != "line" {
+                            unsupported_coverage_setting(kind)?;
+                        }
+                        args.push("--min-line-coverage-for".into());
+                        args.push(format!("{path}={}", coverage_percentage(kind, value)?).into());
+                    }
+                }
+            }
+            _ => unsupported_coverage_setting(key)?,
+        }
+    }
+    Ok(args)
+}
+
+fn unsupported_coverage_setting(name: &str) -> Result<()> {
+    if name == "branch" {
+        bail!("Branch coverage thresholds are not supported since Kani only reports line coverage")
+    }
+    bail!("Unknown coverage setting `{name}`")
+}
+
+/// Translate the harnesses table into the harness filters, e.g.:
+/// ```toml
+/// [harnesses]
+/// include = ["parser::"]
+/// exclude = ["parser::slow_check"]
+/// ```
+fn harnesses_args(table: &Table) -> Result<Vec<OsString>> {
+    let mut args = Vec::new();
+    for (key, value) in table {
+        let flag = match key.as_str() {
+            "include" => "--harness",
+            "exclude" => "--exclude-harness",
+            _ => bail!("Unknown harnesses setting `{key}`. Expected `include` or `exclude`"),
+        };
+        let Some(filters) = value.as_array() else {
+            bail!("Expected a list of harness filters for `harnesses.{key}`")
+        };
+        for filter in filters {
+            let Some(filter) = filter.as_str() else {
+                bail!("harnesses.{key} contains non-string values")
+            };
+            args.push(flag.into());
+            args.push(filter.into());
+        }
+    }
+    Ok(args)
+}
+
+/// Translate the stubs table, which maps each function to its replacement, into `--stub`
+/// arguments, e.g.:
+/// ```toml
+/// [stubs]
+/// "crate::net::connect" = "crate::mocks::connect"
+/// ```
+fn stubs_args(table: &Table) -> Result<Vec<OsString>> {
+    let mut args = Vec::new();
+    for (original, replacement) in table {
+        let Some(replacement) = replacement.as_str() else {
+            bail!(
+                "Expected the path of a function to replace `{original}` but found `{replacement}`"
+            )
+        };
+        args.push("--stub".into());
+        args.push(format!("{original}={replacement}").into());
+    }
+    Ok(args)
+}
+
+/// Translate the severity table, which maps each outcome to its level, into `--severity`
+/// arguments, e.g.:
+/// ```toml
+/// [severity]
+/// undetermined = "warning"
+/// ```
+fn severity_args(table: &Table) -> Result<Vec<OsString>> {
+    let mut args = Vec::new();
+    for (outcome, level) in table {
+        let Some(level) = level.as_str() else {
+            bail!("Expected the severity level of `{outcome}` but found `{level}`")
+        };
+        args.push("--severity".into());
+        args.push(format!("{outcome}={level}").into());
+    }
+    Ok(args)
+}
+
+/// Convert a percentage in the coverage table into a string argument.
+fn coverage_percentage(name: &str, value: &Value) -> Result<String> {
+    match value {
+        Value::Integer(i) => Ok(i.to_string()),
+        Value::Float(f) => Ok(f.to_string()),
+        _ => bail!("Expected a percentage for coverage setting `{name}` but found `{value}`"),
+    }
+}
+
 /// Translates one toml entry (flag, value) into arguments and inserts it into `args`
 fn insert_arg_from_toml(flag: &str, value: &Value, args: &mut Vec<OsString>) -> Result<()> {
     match value {
@@ -172,6 +426,10 @@ fn insert_arg_from_toml(flag: &str, value: &Value, args: &mut Vec<OsString>) ->
             args.push(format!("--{flag}").into());
             args.push(s.into());
         }
+        Value::Integer(i) => {
+            args.push(format!("--{flag}").into());
+            args.push(i.to_string().into());
+        }
         _ => {
             bail!("Unknown key type {}", flag);
         }
@@ -208,6 +466,10 @@ fn cbmc_arg_from_toml(value: &Value) -> Result<Vec<OsString>> {
 
 /// Take 'a.b.c' and turn it into 'start['a']['b']['c']' reliably, and interpret the result as a table
 fn get_table<'a>(start: &'a Value, table: &str) -> Option<&'a Table> {
+    if table.is_empty() {
+        // The whole file, e.g., a `kani.toml` file.
+        return start.as_table();
+    }
     let mut current = start;
     for key in table.split('.') {
         current = current.get(key)?;
@@ -225,13 +487,68 @@ mod tests {
     fn check_toml_parsing() {
         let a = "[workspace.metadata.kani]
                       flags = { default-checks = false, default-unwind = \"2\", cbmc-args = [\"--fake\"] }";
-        let b = toml_to_args(a).unwrap();
+        let b = toml_to_args(a, None).unwrap();
         // default first, then unwind thanks to btree ordering.
         // cbmc-args always last.
         assert_eq!(b.0, vec!["--no-default-checks", "--default-unwind", "2"]);
         assert_eq!(b.1, vec!["--cbmc-args", "--fake"]);
     }
 
+    #[test]
+    fn check_member_toml_parsing() {
+        let workspace = "[workspace.metadata.kani]
+                      flags = { default-unwind = \"2\", output-format = \"terse\" }
+                      [package.metadata.kani]
+                      flags = { no-default-checks = true }";
+        let member = "[package.metadata.kani]
+                      flags = { default-unwind = \"4\" }
+                      unstable = { stubbing = true }";
+        let (args, cbmc_args) = member_toml_to_args((workspace, None), (member, None)).unwrap();
+        // The member flags override the workspace ones, and the root package flags are ignored.
+        assert_eq!(args, vec!["-Zstubbing", "--default-unwind", "4", "--output-format", "terse"]);
+        assert!(cbmc_args.is_empty());
+    }
+
+    #[test]
+    fn check_kani_toml_parsing() {
+        let cargo = "[package.metadata.kani]
+                      flags = { default-unwind = \"2\", solver = \"minisat\" }";
+        let kani = "[flags]
+                    default-unwind = 4
+                    [unstable]
+                    stubbing = true
+                    [harnesses]
+                    include = [\"parser::\"]
+                    exclude = [\"parser::slow\"]
+                    [stubs]
+                    \"crate::io::read\" = \"crate::mocks::read\"";
+        let (args, _) = toml_to_args(cargo, Some(kani)).unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "-Zstubbing",
+                "--exclude-harness",
+                "parser::slow",
+                "--harness",
+                "parser::",
+                "--stub",
+                "crate::io::read=crate::mocks::read",
+                "--default-unwind",
+                "4",
+                "--solver",
+                "minisat",
+            ]
+        );
+    }
+
+    #[test]
+    fn check_kani_toml_unknown_section() {
+        let err = toml_to_args("", Some("[flag]\ndefault-unwind = \"4\"")).unwrap_err();
+        assert!(err.to_string().contains("Unknown section `flag`"));
+        let err = toml_to_args("", Some("[harnesses]\nskip = []")).unwrap_err();
+        assert!(err.to_string().contains("Unknown harnesses setting `skip`"));
+    }
+
     #[test]
     fn check_merge_args_with_only_command_line_args() {
         let cmd_args: Vec<OsString> =
@@ -277,7 +594,7 @@ fn check_multiple_table_works() {
                          [workspace.metadata.kani.flags]
                          kani-arg=\"value\"
                          cbmc-args=[\"--dummy\"]";
-        let (kani_args, cbmc_args) = toml_to_args(data).unwrap();
+        let (kani_args, cbmc_args) = toml_to_args(data, None).unwrap();
         assert_eq!(kani_args, vec!["-Zenabled-feature", "--kani-arg", "value"]);
         assert_eq!(cbmc_args, vec!["--cbmc-args", "--dummy"]);
     }
@@ -287,11 +604,51 @@ fn check_unstable_table_works() {
         let data = "[workspace.metadata.kani.unstable]
                          disabled-feature=false
                          enabled-feature=true";
-        let (kani_args, cbmc_args) = toml_to_args(data).unwrap();
+        let (kani_args, cbmc_args) = toml_to_args(data, None).unwrap();
         assert_eq!(kani_args, vec!["-Zenabled-feature"]);
         assert!(cbmc_args.is_empty());
     }
 
+    #[test]
+    fn check_coverage_table_works() {
+        let data = "[package.metadata.kani.coverage]
+                         line = 80
+                         [package.metadata.kani.coverage.modules.\"src/parser\"]
+                         line = 92.5";
+        let (kani_args, cbmc_args) = toml_to_args(data, None).unwrap();
+        assert_eq!(
+            kani_args,
+            vec!["--min-line-coverage", "80", "--min-line-coverage-for", "src/parser=92.5"]
+        );
+        assert!(cbmc_args.is_empty());
+    }
+
+    #[test]
+    fn check_coverage_table_invalid() {
+        let branch = "[package.metadata.kani.coverage]
+                           branch = 80";
+        assert!(toml_to_args(branch, None).is_err());
+        let not_a_number = "[package.metadata.kani.coverage]
+                                 line = \"high\"";
+        assert!(toml_to_args(not_a_number, None).is_err());
+    }
+
+    #[test]
+    fn check_severity_table_works() {
+        let data = "[package.metadata.kani.severity]
+                         undetermined = \"warning\"
+                         unsatisfiable-cover = \"error\"";
+        let (kani_args, cbmc_args) = toml_to_args(data, None).unwrap();
+        assert_eq!(
+            kani_args,
+            vec!["--severity", "undetermined=warning", "--severity", "unsatisfiable-cover=error"]
+        );
+        assert!(cbmc_args.is_empty());
+        let not_a_level = "[package.metadata.kani.severity]
+                                undetermined = true";
+        assert!(toml_to_args(not_a_level, None).is_err());
+    }
+
     #[test]
     fn check_unstable_entry_enabled() -> Result<()> {
         let name = String::from("feature");
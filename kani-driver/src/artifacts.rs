@@ -0,0 +1,141 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--keep-artifacts` and `--artifacts-dir`, which keep the artifacts of each
+//! harness in a predictable place, e.g., to collect them in CI.
+//!
+//! The artifacts directory contains one `<crate>/<harness>` sub-directory per harness, where
+//! the `::` of the harness name are replaced by `-`, with:
+//!  - `harness.goto`: The fully-linked and instrumented goto binary that was verified.
+//!  - `properties.json`: The properties of the harness with their results, and the traces of the
+//!    failed properties.
+//!  - `log.txt`: The CBMC command and the verification output of the harness.
+//!
+//! The sub-directory of a harness is replaced every time the harness is verified, and removed if
+//! its artifacts aren't kept, so the directory only contains the artifacts of the last run.
+
+use anyhow::{Context, Result};
+use kani_metadata::HarnessMetadata;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use crate::args::{KeepArtifacts, OutputFormat};
+use crate::call_cargo::kani_target_dir;
+use crate::call_cbmc::{cbmc_command, VerificationResult, VerificationStatus};
+use crate::project::Project;
+use crate::session::KaniSession;
+use crate::severity::SeverityPolicy;
+use crate::util::render_command;
+
+const GOTO_FILE: &str = "harness.goto";
+const PROPERTIES_FILE: &str = "properties.json";
+const LOG_FILE: &str = "log.txt";
+
+/// Where and which artifacts of the harnesses are kept.
+pub struct HarnessArtifacts {
+    dir: PathBuf,
+    keep: KeepArtifacts,
+    policy: SeverityPolicy,
+}
+
+impl HarnessArtifacts {
+    /// The artifacts to keep for the harnesses of `project`, if any.
+    pub fn new(session: &KaniSession, project: &Project) -> Option<Self> {
+        let keep = session.args.keep_artifacts();
+        if keep == KeepArtifacts::None {
+            return None;
+        }
+        let dir = match (&session.args.artifacts_dir, &project.cargo_metadata) {
+            (Some(dir), _) => dir.clone(),
+            (None, Some(metadata)) => {
+                kani_target_dir(session.args.target_dir.as_deref(), metadata).join("artifacts")
+            }
+            (None, None) => project.outdir.join("kani-artifacts"),
+        };
+        Some(HarnessArtifacts { dir, keep, policy: SeverityPolicy::new(&session.args.severity) })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Keep the artifacts of `harness`, whose goto binary is `goto_file`, if it's selected by
+    /// `--keep-artifacts`.
+    pub fn keep(
+        &self,
+        session: &KaniSession,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+        result: &VerificationResult,
+    ) -> Result<()> {
+        let harness_dir = harness_dir(&self.dir, harness);
+        if harness_dir.exists() {
+            fs::remove_dir_all(&harness_dir)?;
+        }
+        let keep = match self.keep {
+            KeepArtifacts::None => false,
+            KeepArtifacts::Failed => {
+                self.policy.judge(result).status == VerificationStatus::Failure
+            }
+            KeepArtifacts::All => true,
+        };
+        if !keep {
+            return Ok(());
+        }
+        self.write(session, goto_file, harness, result, &harness_dir).with_context(|| {
+            format!("failed to write the artifacts of harness `{}`", harness.pretty_name)
+        })
+    }
+
+    fn write(
+        &self,
+        session: &KaniSession,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+        result: &VerificationResult,
+        harness_dir: &Path,
+    ) -> Result<()> {
+        fs::create_dir_all(harness_dir)?;
+        fs::copy(goto_file, harness_dir.join(GOTO_FILE))?;
+
+        let properties = result.results.as_deref().unwrap_or_default();
+        let writer = BufWriter::new(File::create(harness_dir.join(PROPERTIES_FILE))?);
+        serde_json::to_writer_pretty(writer, properties)?;
+
+        let mut cbmc_args = session.cbmc_flags(goto_file, harness)?;
+        cbmc_args.pop();
+        let mut cmd = cbmc_command();
+        cmd.args(cbmc_args).arg(GOTO_FILE);
+        let output = result.render(
+            &OutputFormat::Regular,
+            harness.attributes.should_panic,
+            session.args.coverage,
+        );
+        fs::write(
+            harness_dir.join(LOG_FILE),
+            format!("$ {}\n{output}\n", render_command(&cmd).to_string_lossy()),
+        )?;
+        Ok(())
+    }
+}
+
+/// The directory of the artifacts of `harness`.
+fn harness_dir(dir: &Path, harness: &HarnessMetadata) -> PathBuf {
+    dir.join(&harness.crate_name).join(harness.pretty_name.replace("::", "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::mock_proof_harness;
+
+    #[test]
+    fn check_harness_dir() {
+        let harness = mock_proof_harness("module::check_one", None, Some("my_crate"), None);
+        assert_eq!(
+            harness_dir(Path::new("out"), &harness),
+            Path::new("out/my_crate/module-check_one")
+        );
+    }
+}
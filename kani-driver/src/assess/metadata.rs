@@ -16,6 +16,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::table_builder::TableBuilder;
+use super::table_crate_readiness::CrateReadinessTableRow;
 use super::table_failure_reasons::FailureReasonsTableRow;
 use super::table_promising_tests::PromisingTestsTableRow;
 use super::table_unsupported_features::UnsupportedFeaturesTableRow;
@@ -38,6 +39,9 @@ pub struct AssessMetadata {
     pub failure_reasons: TableBuilder<FailureReasonsTableRow>,
     /// Report on the tests that Kani can successfully analyze
     pub promising_tests: TableBuilder<PromisingTestsTableRow>,
+    /// Report on how ready each package is to be verified
+    #[serde(default = "TableBuilder::new")]
+    pub crate_readiness: TableBuilder<CrateReadinessTableRow>,
 }
 
 impl AssessMetadata {
@@ -45,6 +49,7 @@ pub fn new(
         unsupported_features: TableBuilder<UnsupportedFeaturesTableRow>,
         failure_reasons: TableBuilder<FailureReasonsTableRow>,
         promising_tests: TableBuilder<PromisingTestsTableRow>,
+        crate_readiness: TableBuilder<CrateReadinessTableRow>,
     ) -> AssessMetadata {
         AssessMetadata {
             version: env!("CARGO_PKG_VERSION").to_string(),
@@ -52,6 +57,7 @@ pub fn new(
             unsupported_features,
             failure_reasons,
             promising_tests,
+            crate_readiness,
         }
     }
 
@@ -66,6 +72,7 @@ pub fn from_error(err: &dyn std::error::Error) -> AssessMetadata {
             unsupported_features: TableBuilder::new(),
             failure_reasons: TableBuilder::new(),
             promising_tests: TableBuilder::new(),
+            crate_readiness: TableBuilder::new(),
         }
     }
     pub fn empty() -> AssessMetadata {
@@ -75,6 +82,7 @@ pub fn empty() -> AssessMetadata {
             unsupported_features: TableBuilder::new(),
             failure_reasons: TableBuilder::new(),
             promising_tests: TableBuilder::new(),
+            crate_readiness: TableBuilder::new(),
         }
     }
 }
@@ -118,6 +126,9 @@ pub(crate) fn aggregate_metadata(metas: Vec<AssessMetadata>) -> AssessMetadata {
         for item in meta.promising_tests.build() {
             result.promising_tests.add(item.clone());
         }
+        for item in meta.crate_readiness.build() {
+            result.crate_readiness.add(item.clone());
+        }
     }
     result
 }
@@ -3,9 +3,11 @@
 
 use self::metadata::{write_metadata, AssessMetadata};
 use anyhow::{bail, Result};
+use cargo_metadata::DependencyKind;
 use kani_metadata::KaniMetadata;
 
 use crate::assess::table_builder::TableBuilder;
+use crate::call_cargo::packages_to_verify;
 use crate::metadata::merge_kani_metadata;
 use crate::project;
 use crate::session::KaniSession;
@@ -15,6 +17,7 @@
 mod metadata;
 mod scan;
 mod table_builder;
+mod table_crate_readiness;
 mod table_failure_reasons;
 mod table_promising_tests;
 mod table_unsupported_features;
@@ -27,7 +30,7 @@ pub(crate) fn run_assess(session: KaniSession, args: AssessArgs) -> Result<()> {
         return scan::assess_scan_main(session, args);
     }
 
-    let result = assess_project(session);
+    let result = assess_project(session, &args);
     match result {
         Ok(metadata) => write_metadata(&args, metadata),
         Err(err) => {
@@ -38,7 +41,7 @@ pub(crate) fn run_assess(session: KaniSession, args: AssessArgs) -> Result<()> {
     }
 }
 
-fn assess_project(mut session: KaniSession) -> Result<AssessMetadata> {
+fn assess_project(mut session: KaniSession, args: &AssessArgs) -> Result<AssessMetadata> {
     // Fix (as in "make unchanging/unchangable") some settings.
     // This is a temporary hack to make things work, until we get around to refactoring how arguments
     // work generally in kani-driver. These arguments, for instance, are all prepended to the subcommand,
@@ -49,9 +52,20 @@ fn assess_project(mut session: KaniSession) -> Result<AssessMetadata> {
     session.codegen_tests = true;
     if session.args.jobs.is_none() {
         // assess will default to fully parallel instead of single-threaded.
-        // can be overridden with e.g. `cargo kani --enable-unstable -j 8 assess`
+        // can be overridden with e.g. `cargo kani -j 8 assess`
         session.args.jobs = Some(None); // -j, num_cpu
     }
+    // assess covers the whole workspace unless some packages are selected with `-p`.
+    if session.args.cargo.package.is_empty() {
+        session.args.cargo.workspace = true;
+    }
+    if args.path_dependencies {
+        let cargo_metadata = session.cargo_metadata(env!("TARGET"))?;
+        let packages = with_path_dependencies(&session, &cargo_metadata)?;
+        session.args.cargo.workspace = false;
+        session.args.cargo.exclude.clear();
+        session.args.cargo.package = packages;
+    }
 
     let project = project::cargo_project(&session, true)?;
     let cargo_metadata = project.cargo_metadata.as_ref().expect("built with cargo");
@@ -90,10 +104,13 @@ fn assess_project(mut session: KaniSession) -> Result<AssessMetadata> {
     }
 
     if session.args.only_codegen {
+        let crate_readiness = table_crate_readiness::build(&packages_metadata, &[]);
+        println!("{}", crate_readiness.render());
         return Ok(AssessMetadata::new(
             unsupported_features,
             TableBuilder::new(),
             TableBuilder::new(),
+            crate_readiness,
         ));
     }
 
@@ -119,7 +136,41 @@ fn assess_project(mut session: KaniSession) -> Result<AssessMetadata> {
     let promising_tests = table_promising_tests::build(&results);
     println!("{}", promising_tests.render());
 
-    Ok(AssessMetadata::new(unsupported_features, failure_reasons, promising_tests))
+    // 3. "How ready is each package to be verified", to choose where to start.
+    let crate_readiness = table_crate_readiness::build(&packages_metadata, &results);
+    println!("{}", crate_readiness.render());
+
+    Ok(AssessMetadata::new(unsupported_features, failure_reasons, promising_tests, crate_readiness))
+}
+
+/// The names of the packages selected for assess, followed by their path dependencies (direct or
+/// not) that aren't workspace members, e.g., crates vendored next to the workspace.
+fn with_path_dependencies(
+    session: &KaniSession,
+    cargo_metadata: &cargo_metadata::Metadata,
+) -> Result<Vec<String>> {
+    let mut packages = packages_to_verify(&session.args, cargo_metadata)?;
+    let mut next = 0;
+    while next < packages.len() {
+        let package = packages[next];
+        next += 1;
+        for dependency in &package.dependencies {
+            if dependency.path.is_none() || dependency.kind != DependencyKind::Normal {
+                continue;
+            }
+            let found = cargo_metadata.packages.iter().find(|candidate| {
+                candidate.name == dependency.name
+                    && candidate.source.is_none()
+                    && dependency.req.matches(&candidate.version)
+            });
+            if let Some(found) = found {
+                if !packages.iter().any(|package| package.id == found.id) {
+                    packages.push(found);
+                }
+            }
+        }
+    }
+    Ok(packages.into_iter().map(|package| package.name.clone()).collect())
 }
 
 /// Merges a collection of Kani metadata by figuring out which package each belongs to, from cargo metadata.
@@ -137,21 +188,17 @@ fn reconstruct_metadata_structure(
 ) -> Result<Vec<KaniMetadata>> {
     let mut remaining_metas = kani_metadata.to_owned();
     let mut package_metas = vec![];
-    for package in cargo_metadata.workspace_packages() {
-        if !session.args.cargo.package.is_empty() {
-            // If a specific package (set) is requested, skip all other packages.
-            // This is a necessary workaround because we're reconstructing which metas go to which packages
-            // based on the "crate name" given to the target, and the same workspace can have two
-            // packages with targets that have the same crate name.
-            // This is just an inherent problem with trying to reconstruct this information, and should
-            // be fixed by the issue linked in the function description.
-            // The best we can do for now is ignore packages we know we didn't build, to reduce the amount
-            // of confusion we might suffer here (which at least solves the problem for 'scan' which only
-            // builds 1 package at a time.)
-            if !session.args.cargo.package.contains(&package.name) {
-                continue;
-            }
-        }
+    // Only consider the packages that were built, i.e., the selected workspace members and path
+    // dependencies.
+    // This is a necessary workaround because we're reconstructing which metas go to which packages
+    // based on the "crate name" given to the target, and the same workspace can have two
+    // packages with targets that have the same crate name.
+    // This is just an inherent problem with trying to reconstruct this information, and should
+    // be fixed by the issue linked in the function description.
+    // The best we can do for now is ignore packages we know we didn't build, to reduce the amount
+    // of confusion we might suffer here (which at least solves the problem for 'scan' which only
+    // builds 1 package at a time.)
+    for package in packages_to_verify(&session.args, cargo_metadata)? {
         let mut package_artifacts = vec![];
         for target in &package.targets {
             // cargo_metadata doesn't provide name mangling help here?
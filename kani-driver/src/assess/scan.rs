@@ -147,6 +147,7 @@ pub(crate) fn assess_scan_main(session: KaniSession, args: &ScanArgs) -> Result<
         println!("{}", results.failure_reasons.render());
         println!("{}", results.promising_tests.render());
     }
+    println!("{}", results.crate_readiness.render());
 
     if let Some(path) = &args.emit_metadata {
         let out_file = std::fs::File::create(path)?;
@@ -0,0 +1,185 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use kani_metadata::KaniMetadata;
+use serde::{Deserialize, Serialize};
+
+use crate::harness_runner::HarnessResult;
+
+use super::table_builder::{ColumnType, RenderableTableRow, TableBuilder, TableRow};
+
+/// Reports how ready each package is to be verified, from the most to the least ready, to help
+/// choose where to start writing proofs.
+///
+/// The readiness of a package is the percentage of its analyzed tests that Kani verified
+/// successfully, i.e., the tests that are promising starting points for proof harnesses. Tests
+/// that use unsupported features, or that Kani can't analyze for another reason, lower the
+/// readiness of the package. A package whose tests weren't analyzed, e.g., with `--only-codegen`,
+/// has no readiness.
+///
+/// For example:
+///
+/// ```text
+/// ================================================================================
+///  Package    | Unsupported | Instances | Tests | Analyzed | Promising | Readiness
+///             |    features |    of use |       |    tests |     tests |
+/// ------------+-------------+-----------+-------+----------+-----------+-----------
+///  my-parser  |           0 |         0 |    12 |       12 |        10 |       83%
+///  my-runtime |           3 |        17 |    20 |       20 |         4 |       20%
+/// ================================================================================
+/// ```
+pub(crate) fn build(
+    metadata: &[KaniMetadata],
+    results: &[HarnessResult],
+) -> TableBuilder<CrateReadinessTableRow> {
+    let results: HashMap<_, _> = results
+        .iter()
+        .map(|r| ((r.harness.crate_name.as_str(), r.harness.mangled_name.as_str()), r))
+        .collect();
+    let mut builder = TableBuilder::new();
+
+    for package_metadata in metadata {
+        let mut row = CrateReadinessTableRow {
+            package: package_metadata.crate_name.clone(),
+            tests: package_metadata.test_harnesses.len(),
+            ..Default::default()
+        };
+        for item in &package_metadata.unsupported_features {
+            // Aggregate the "feature for <instance of use>" features like the unsupported
+            // features table does.
+            let feature = item.feature.split_once(" for ").map_or(&*item.feature, |(f, _)| f);
+            row.unsupported_features.insert(feature.to_string());
+            row.instances_of_use += item.locations.len();
+        }
+        for harness in &package_metadata.test_harnesses {
+            let key = (harness.crate_name.as_str(), harness.mangled_name.as_str());
+            if let Some(result) = results.get(&key) {
+                row.analyzed_tests += 1;
+                if result.result.results.is_ok() && result.result.failed_properties().is_empty() {
+                    row.promising_tests += 1;
+                }
+            }
+        }
+        builder.add(row);
+    }
+
+    builder
+}
+
+/// The readiness of a package to be verified.
+///
+/// See [`build`]
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct CrateReadinessTableRow {
+    /// The name of the package
+    pub package: String,
+    /// The unsupported features used by the package
+    pub unsupported_features: HashSet<String>,
+    /// The total count of the uses of unsupported features
+    pub instances_of_use: usize,
+    /// The number of tests of the package
+    pub tests: usize,
+    /// The number of tests that Kani analyzed
+    pub analyzed_tests: usize,
+    /// The number of analyzed tests that Kani verified successfully
+    pub promising_tests: usize,
+}
+
+impl CrateReadinessTableRow {
+    /// The percentage of the analyzed tests that are promising, if any test was analyzed.
+    pub fn readiness(&self) -> Option<usize> {
+        (self.analyzed_tests > 0).then(|| self.promising_tests * 100 / self.analyzed_tests)
+    }
+}
+
+impl TableRow for CrateReadinessTableRow {
+    type Key = String;
+
+    fn key(&self) -> Self::Key {
+        self.package.clone()
+    }
+
+    fn merge(&mut self, new: Self) {
+        self.unsupported_features.extend(new.unsupported_features);
+        self.instances_of_use += new.instances_of_use;
+        self.tests += new.tests;
+        self.analyzed_tests += new.analyzed_tests;
+        self.promising_tests += new.promising_tests;
+    }
+
+    fn compare(&self, right: &Self) -> Ordering {
+        self.readiness()
+            .cmp(&right.readiness())
+            .reverse()
+            .then_with(|| self.unsupported_features.len().cmp(&right.unsupported_features.len()))
+            .then_with(|| self.package.cmp(&right.package))
+    }
+}
+
+impl RenderableTableRow for CrateReadinessTableRow {
+    fn headers() -> Vec<&'static str> {
+        vec![
+            "Package",
+            "Unsupported\nfeatures",
+            "Instances\nof use",
+            "Tests",
+            "Analyzed\ntests",
+            "Promising\ntests",
+            "Readiness",
+        ]
+    }
+
+    fn columns() -> Vec<ColumnType> {
+        use ColumnType::*;
+        vec![Text, Number, Number, Number, Number, Number, Number]
+    }
+
+    fn row(&self) -> Vec<String> {
+        vec![
+            self.package.clone(),
+            self.unsupported_features.len().to_string(),
+            self.instances_of_use.to_string(),
+            self.tests.to_string(),
+            self.analyzed_tests.to_string(),
+            self.promising_tests.to_string(),
+            self.readiness().map_or("-".to_string(), |readiness| format!("{readiness}%")),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_row_lengths() {
+        use CrateReadinessTableRow as Row;
+        assert_eq!(Row::columns().len(), Row::headers().len());
+        assert_eq!(Row::columns().len(), Row::row(&Default::default()).len());
+    }
+
+    #[test]
+    fn check_readiness_order() {
+        let row = |package: &str, analyzed_tests, promising_tests| CrateReadinessTableRow {
+            package: package.to_string(),
+            tests: analyzed_tests,
+            analyzed_tests,
+            promising_tests,
+            ..Default::default()
+        };
+        let mut builder = TableBuilder::new();
+        builder.add(row("untested", 0, 0));
+        builder.add(row("half", 4, 2));
+        builder.add(row("ready", 3, 3));
+        // Packages aggregated from several assess runs are merged.
+        builder.add(row("half", 2, 1));
+        let rows = builder.build();
+        let packages: Vec<_> = rows.iter().map(|row| row.package.as_str()).collect();
+        assert_eq!(packages, ["ready", "half", "untested"]);
+        assert_eq!(rows[1].readiness(), Some(50));
+        assert_eq!(rows[2].row().last().unwrap(), "-");
+    }
+}
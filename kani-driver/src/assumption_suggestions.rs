@@ -0,0 +1,155 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--suggest-assumptions`, which helps finding missing preconditions of a failing
+//! harness.
+//!
+//! The trace of each failed check is scanned for the values assigned to the harness inputs,
+//! i.e., to the variables initialized with `kani::any()`. Each of these values gives a candidate
+//! precondition that excludes the failing input.
+//!
+//! This is a heuristic: the harness isn't verified again with the suggested preconditions, which
+//! only exclude the inputs of the failing traces, so the harness may still fail with others.
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use crate::cbmc_output_parser::{CheckStatus, Property, SourceLocation};
+
+/// The value of a harness input in the trace of a failed check.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FailingInput {
+    pub variable: String,
+    pub value: String,
+    pub location: String,
+}
+
+/// Collect the inputs of the failing traces in `properties`. `source_line` returns the text of
+/// a line of code, which is used to find the assignments of `kani::any()` values.
+pub fn failing_inputs(
+    properties: &[Property],
+    source_line: impl Fn(&SourceLocation) -> Option<String>,
+) -> BTreeSet<FailingInput> {
+    properties
+        .iter()
+        .filter(|prop| prop.status == CheckStatus::Failure && prop.property_class() != "unwind")
+        .filter_map(|prop| prop.trace.as_ref())
+        .flatten()
+        .filter(|step| step.step_type == "assignment")
+        .filter_map(|step| {
+            let variable = step.lhs.as_ref()?;
+            let location = step.source_location.as_ref()?;
+            let value = step.value.as_ref()?.data.as_ref()?.to_string();
+            let is_input = is_user_variable(variable)
+                && source_line(location).is_some_and(|line| line.contains("kani::any"));
+            is_input.then(|| FailingInput {
+                variable: variable.clone(),
+                value,
+                location: location.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Read the line of code at `location` from the file system.
+pub fn read_source_line(location: &SourceLocation) -> Option<String> {
+    let line: usize = location.line.as_ref()?.parse().ok()?;
+    let content = std::fs::read_to_string(location.file.as_ref()?).ok()?;
+    content.lines().nth(line.checked_sub(1)?).map(str::to_string)
+}
+
+/// Whether `name` looks like a variable declared by the user, as opposed to temporaries
+/// introduced by the compiler or CBMC.
+pub fn is_user_variable(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !name.starts_with("var_")
+        && !name.starts_with("temp_")
+}
+
+/// Format the candidate preconditions for the failing inputs of a harness. They're labeled as
+/// a heuristic since they aren't verified.
+pub fn format_assumption_suggestions(inputs: &BTreeSet<FailingInput>) -> String {
+    let mut result = String::from("\nAssumption Suggestions (heuristic):\n");
+    if inputs.is_empty() {
+        result.push_str(" - No harness inputs were found in the failing traces.\n");
+        return result;
+    }
+    result.push_str(" - The harness fails with the following inputs:\n");
+    for input in inputs {
+        writeln!(result, "   - `{} = {}` ({})", input.variable, input.value, input.location)
+            .unwrap();
+    }
+    result.push_str(
+        " - If these inputs are invalid, consider adding preconditions that exclude them, \
+        e.g.:\n",
+    );
+    for input in inputs {
+        writeln!(result, "   kani::assume({} != {});", input.variable, input.value).unwrap();
+    }
+    result.push_str(
+        " - These preconditions weren't verified, and they only exclude the inputs above. \
+        Verify the harness again after adding them.\n",
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::{TraceData, TraceItem, TraceValue};
+
+    fn location(line: &str) -> SourceLocation {
+        SourceLocation {
+            function: Some("check_div".to_string()),
+            file: Some("src/lib.rs".to_string()),
+            column: None,
+            line: Some(line.to_string()),
+        }
+    }
+
+    fn assignment(lhs: &str, value: &str, line: &str) -> TraceItem {
+        TraceItem {
+            step_type: "assignment".to_string(),
+            lhs: Some(lhs.to_string()),
+            source_location: Some(location(line)),
+            value: Some(TraceValue {
+                binary: None,
+                data: Some(TraceData::NonBool(value.to_string())),
+                name: None,
+                width: Some(8),
+                members: None,
+                member: None,
+                elements: None,
+            }),
+            raw_lhs: None,
+        }
+    }
+
+    #[test]
+    fn check_failing_inputs() {
+        let property = Property::mock("assertion", CheckStatus::Failure)
+            .with_description("attempt to divide by zero")
+            .with_id("check_div", 1)
+            .with_location("src/lib.rs", 3)
+            .with_trace(vec![
+                assignment("y", "0", "2"),
+                assignment("var_3", "0", "2"),
+                assignment("z", "5", "3"),
+            ]);
+        let source_line = |location: &SourceLocation| match location.line.as_deref() {
+            Some("2") => Some("let y: u8 = kani::any();".to_string()),
+            Some("3") => Some("let z = 10 / y;".to_string()),
+            _ => None,
+        };
+
+        let inputs = failing_inputs(&[property], source_line);
+        assert_eq!(inputs.iter().map(|input| input.variable.as_str()).collect::<Vec<_>>(), ["y"]);
+        let formatted = format_assumption_suggestions(&inputs);
+        assert!(formatted.contains("`y = 0`"));
+        assert!(formatted.starts_with("\nAssumption Suggestions (heuristic):\n"));
+        assert!(formatted.contains("kani::assume(y != 0);"));
+        assert!(formatted.contains("These preconditions weren't verified"));
+    }
+}
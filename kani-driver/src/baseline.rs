@@ -0,0 +1,175 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--baseline <FILE>`, which lists the harnesses that are known to fail.
+//!
+//! This allows adopting Kani on a code base with known issues: the run succeeds if the failing
+//! harnesses are exactly the ones listed in the baseline that were verified. A harness that fails
+//! without being listed is a regression, and a listed harness that succeeds must be removed from
+//! the baseline, so it can't regress again unnoticed. The file is a TOML table that maps the name
+//! of each harness to the reason why it fails:
+//!
+//! ```toml
+//! [expected-failures]
+//! "parser::check_overflow" = "The parser doesn't bound the nesting depth yet"
+//! ```
+//!
+//! Listed harnesses that weren't verified, e.g., because of `--harness`, are ignored.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::call_cbmc::VerificationStatus;
+
+/// The content of a baseline file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Baseline {
+    /// The reason why each harness fails, indexed by the harness name.
+    #[serde(default)]
+    expected_failures: BTreeMap<String, String>,
+}
+
+/// How the results of a run compare to the baseline.
+#[derive(Debug, Default, PartialEq)]
+struct Comparison {
+    /// The failing harnesses listed in the baseline, with the reason why they fail.
+    expected_failures: Vec<(String, String)>,
+    /// The failing harnesses that aren't listed in the baseline.
+    new_failures: Vec<String>,
+    /// The harnesses listed in the baseline that succeeded.
+    unexpected_successes: Vec<String>,
+}
+
+impl Baseline {
+    fn compare<'a>(
+        &self,
+        results: impl IntoIterator<Item = (&'a str, VerificationStatus)>,
+    ) -> Comparison {
+        let mut comparison = Comparison::default();
+        for (harness, status) in results {
+            match (self.expected_failures.get(harness), status) {
+                (Some(reason), VerificationStatus::Failure) => {
+                    comparison.expected_failures.push((harness.to_string(), reason.clone()))
+                }
+                (Some(_), VerificationStatus::Success) => {
+                    comparison.unexpected_successes.push(harness.to_string())
+                }
+                (None, VerificationStatus::Failure) => {
+                    comparison.new_failures.push(harness.to_string())
+                }
+                (None, VerificationStatus::Success) => {}
+            }
+        }
+        comparison.expected_failures.sort();
+        comparison.new_failures.sort();
+        comparison.unexpected_successes.sort();
+        comparison
+    }
+}
+
+impl Comparison {
+    fn matches(&self) -> bool {
+        self.new_failures.is_empty() && self.unexpected_successes.is_empty()
+    }
+
+    fn print(&self, path: &Path) {
+        println!("Comparison with the baseline `{}`:", path.display());
+        for (harness, reason) in &self.expected_failures {
+            println!(" - Expected failure: {harness} ({reason})");
+        }
+        for harness in &self.new_failures {
+            println!(" - New failure: {harness}");
+        }
+        for harness in &self.unexpected_successes {
+            println!(" - Unexpected success: {harness} (remove it from the baseline)");
+        }
+        if self.matches() {
+            println!(
+                "Baseline check passed: all {} failures are expected.",
+                self.expected_failures.len()
+            );
+        } else {
+            println!(
+                "Baseline check failed: {} new failures, {} unexpected successes.",
+                self.new_failures.len(),
+                self.unexpected_successes.len()
+            );
+        }
+    }
+}
+
+fn read_baseline(path: &Path) -> Result<Baseline> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read the baseline `{}`", path.display()))?;
+    toml::from_str(&content)
+        .with_context(|| format!("failed to parse the baseline `{}`", path.display()))
+}
+
+/// Whether the run failed, given the status of each verified harness. Without a baseline, the run
+/// fails if any harness failed. With a baseline, it fails if the failing harnesses don't match the
+/// expected failures, and the differences are printed unless `quiet` is set.
+pub fn run_failed<'a>(
+    baseline: Option<&Path>,
+    quiet: bool,
+    results: impl IntoIterator<Item = (&'a str, VerificationStatus)>,
+) -> Result<bool> {
+    let Some(path) = baseline else {
+        return Ok(results.into_iter().any(|(_, status)| status == VerificationStatus::Failure));
+    };
+    let comparison = read_baseline(path)?.compare(results);
+    if !quiet {
+        comparison.print(path);
+    }
+    Ok(!comparison.matches())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_baseline() {
+        let baseline: Baseline = toml::from_str(
+            r#"
+            [expected-failures]
+            "parser::check_overflow" = "known overflow"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(baseline.expected_failures["parser::check_overflow"], "known overflow");
+        assert!(toml::from_str::<Baseline>("[expected-failure]").is_err());
+        assert!(toml::from_str::<Baseline>("").unwrap().expected_failures.is_empty());
+    }
+
+    #[test]
+    fn check_compare_baseline() {
+        let baseline: Baseline = toml::from_str(
+            r#"
+            [expected-failures]
+            known = "reason"
+            fixed = "fixed since"
+            skipped = "not verified"
+            "#,
+        )
+        .unwrap();
+        let comparison = baseline.compare([
+            ("known", VerificationStatus::Failure),
+            ("fixed", VerificationStatus::Success),
+            ("new", VerificationStatus::Failure),
+            ("ok", VerificationStatus::Success),
+        ]);
+        assert_eq!(
+            comparison,
+            Comparison {
+                expected_failures: vec![("known".to_string(), "reason".to_string())],
+                new_failures: vec!["new".to_string()],
+                unexpected_successes: vec!["fixed".to_string()],
+            }
+        );
+        assert!(!comparison.matches());
+        assert!(baseline.compare([("known", VerificationStatus::Failure)]).matches());
+    }
+}
@@ -15,7 +15,7 @@
 use std::fs::{self, File};
 use std::io::BufReader;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use tracing::{debug, trace};
 
@@ -47,13 +47,7 @@ impl KaniSession {
     pub fn cargo_build(&self, keep_going: bool) -> Result<CargoOutputs> {
         let build_target = env!("TARGET"); // see build.rs
         let metadata = self.cargo_metadata(build_target)?;
-        let target_dir = self
-            .args
-            .target_dir
-            .as_ref()
-            .unwrap_or(&metadata.target_directory.clone().into())
-            .clone()
-            .join("kani");
+        let target_dir = kani_target_dir(self.args.target_dir.as_deref(), &metadata);
         let outdir = target_dir.join(build_target).join("debug/deps");
 
         if self.args.force_build && target_dir.exists() {
@@ -149,7 +143,7 @@ pub fn cargo_build(&self, keep_going: bool) -> Result<CargoOutputs> {
         })
     }
 
-    fn cargo_metadata(&self, build_target: &str) -> Result<Metadata> {
+    pub(crate) fn cargo_metadata(&self, build_target: &str) -> Result<Metadata> {
         let mut cmd = MetadataCommand::new();
 
         // restrict metadata command to host platform. References:
@@ -271,6 +265,12 @@ fn same_target(t1: &Target, t2: &Target) -> bool {
     }
 }
 
+/// The directory where Kani writes its artifacts for a cargo project, which is the `kani`
+/// directory of `target_dir` or else of the target directory of the workspace.
+pub fn kani_target_dir(target_dir: Option<&Path>, metadata: &Metadata) -> PathBuf {
+    target_dir.unwrap_or(metadata.target_directory.as_std_path()).join("kani")
+}
+
 pub fn cargo_config_args() -> Vec<OsString> {
     [
         "--target",
@@ -325,7 +325,7 @@ fn validate_package_names(package_names: &[String], packages: &[Package]) -> Res
 /// In addition, if either `--package <pkg>` or `--exclude <pkg>` is given,
 /// validate that `<pkg>` is a package name in the workspace, or return an error
 /// otherwise.
-fn packages_to_verify<'b>(
+pub(crate) fn packages_to_verify<'b>(
     args: &VerificationArgs,
     metadata: &'b Metadata,
 ) -> Result<Vec<&'b Package>> {
@@ -1,26 +1,71 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use anyhow::{bail, Result};
-use kani_metadata::{CbmcSolver, HarnessMetadata};
+use anyhow::{bail, Context, Result};
+use kani_metadata::{CbmcSlicing, CbmcSolver, HarnessMetadata};
+use serde::{Deserialize, Serialize};
 use std::ffi::OsString;
 use std::fmt::Write;
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::OnceLock;
+use std::thread;
 use std::time::{Duration, Instant};
 
 use crate::args::{OutputFormat, VerificationArgs};
 use crate::cbmc_output_parser::{
-    extract_results, process_cbmc_output, CheckStatus, Property, VerificationOutput,
+    extract_results, is_stopped, parse_cbmc_output, process_cbmc_output_until, process_status,
+    wait_time, CheckStatus, ParserItem, PhaseTiming, Property, VerificationOutput,
 };
-use crate::cbmc_property_renderer::{format_coverage, format_result, kani_cbmc_output_filter};
+#[cfg(test)]
+use crate::cbmc_output_parser::{PropertyId, SourceLocation, TraceItem};
+use crate::cbmc_property_renderer::{
+    format_coverage, format_result, format_result_terse, has_object_bits_error,
+    kani_cbmc_output_filter, print_cbmc_messages, ProgressLine,
+};
+use crate::json_report::TraceExcerpt;
 use crate::session::KaniSession;
+use crate::util::{render_command, warning};
+
+/// The CBMC options that make its model of `malloc` fail nondeterministically by returning a
+/// null pointer.
+pub const MALLOC_MAY_FAIL_ARGS: [&str; 2] = ["--malloc-may-fail", "--malloc-fail-null"];
 
 /// We will use Cadical by default since it performed better than MiniSAT in our analysis.
 /// Note: Kissat was marginally better, but it is an external solver which could be more unstable.
 static DEFAULT_SOLVER: CbmcSolver = CbmcSolver::Cadical;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The maximum number of object bits Kani retries with when a harness has too many objects.
+/// Pointers are 64 bits wide, so more object bits leave too few bits for offsets.
+const MAX_OBJECT_BITS: u32 = 32;
+/// How many object bits Kani adds on each retry.
+const OBJECT_BITS_STEP: u32 = 4;
+
+/// The command that runs CBMC. Every CBMC invocation goes through it, so the reported version is
+/// the one of the binary that verified the harnesses.
+pub fn cbmc_command() -> Command {
+    // TODO get cbmc path from the session
+    Command::new("cbmc")
+}
+
+/// The version of CBMC, as printed by `cbmc --version`. It's only queried once per run.
+pub fn cbmc_version() -> Result<String> {
+    static VERSION: OnceLock<String> = OnceLock::new();
+    if let Some(version) = VERSION.get() {
+        return Ok(version.clone());
+    }
+    let output = cbmc_command().arg("--version").output().context("Failed to invoke cbmc")?;
+    if !output.status.success() {
+        bail!("failed to get the version of cbmc");
+    }
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(VERSION.get_or_init(|| version).clone())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
 pub enum VerificationStatus {
     Success,
     Failure,
@@ -54,53 +99,452 @@ pub struct VerificationResult {
     pub runtime: Duration,
     /// Whether concrete playback generated a test
     pub generated_concrete_test: bool,
+    /// The time CBMC spent in each of its phases, as reported by CBMC.
+    pub phase_timings: Vec<PhaseTiming>,
+    /// The peak memory usage of CBMC in kB, if it could be measured.
+    pub peak_memory_kb: Option<u64>,
+    /// Whether CBMC was stopped by `--harness-timeout`.
+    pub timed_out: bool,
+    /// Whether CBMC was stopped because the verification was cancelled by `--max-failures`.
+    pub cancelled: bool,
+    /// The solver of the portfolio that finished first, if the harness used a portfolio.
+    pub portfolio_solver: Option<String>,
 }
 
 impl KaniSession {
     /// Verify a goto binary that's been prepared with goto-instrument
     pub fn run_cbmc(&self, file: &Path, harness: &HarnessMetadata) -> Result<VerificationResult> {
-        let args: Vec<OsString> = self.cbmc_flags(file, harness)?;
-
-        // TODO get cbmc path from self
-        let mut cmd = Command::new("cbmc");
-        cmd.args(args);
-
         let start_time = Instant::now();
 
         let verification_results = if self.args.output_format == crate::args::OutputFormat::Old {
+            if matches!(self.args.solver, Some(CbmcSolver::Portfolio(_))) {
+                bail!("the solver portfolio is not supported with `--output-format old`");
+            }
+            if self.args.harness_timeout.is_some()
+                || self.args.property_at_a_time
+                || self.args.property_batches.is_some()
+            {
+                bail!(
+                    "`--harness-timeout`, `--property-at-a-time` and `--property-batches` are \
+                    not supported with `--output-format old`"
+                );
+            }
+            let mut cmd = cbmc_command();
+            cmd.args(self.cbmc_flags(file, harness)?);
             if self.run_terminal(cmd).is_err() {
                 VerificationResult::mock_failure()
             } else {
                 VerificationResult::mock_success()
             }
         } else {
-            // Add extra argument to receive the output in JSON format.
-            // Done here because `--visualize` uses the XML format instead.
-            cmd.arg("--json-ui");
+            // Retry with more object bits if the harness has too many objects for the
+            // number of object bits selected by Kani.
+            let deadline =
+                self.args.harness_timeout.map(|secs| start_time + Duration::from_secs(secs));
+            let mut object_bits = self.args.cbmc_object_bits();
+            loop {
+                let (output, timed_out) =
+                    if self.args.property_at_a_time || self.args.property_batches.is_some() {
+                        self.run_cbmc_property_batches(file, harness, object_bits, deadline)?
+                    } else {
+                        self.run_cbmc_json(file, harness, object_bits, deadline)?
+                    };
+                if let Some(bits) = object_bits
+                    && needs_more_object_bits(object_bits, timed_out, &output.processed_items)
+                {
+                    let new_bits = (bits + OBJECT_BITS_STEP).min(MAX_OBJECT_BITS);
+                    if !self.args.common_args.quiet {
+                        warning(&format!(
+                            "harness `{}` uses too many objects for {bits} object bits. \
+                            Retrying with `--object-bits {new_bits}`.",
+                            harness.pretty_name
+                        ));
+                    }
+                    object_bits = Some(new_bits);
+                    continue;
+                }
+                if let Some(bits) = object_bits
+                    && object_bits != self.args.cbmc_object_bits()
+                    && !self.args.common_args.quiet
+                {
+                    warning(&format!(
+                        "harness `{}` required `--object-bits {bits}`. More object bits leave \
+                        fewer bits for pointer offsets, and may increase memory usage and \
+                        verification time. Use `--enable-unstable --cbmc-args --object-bits \
+                        {bits}` to skip the retries.",
+                        harness.pretty_name
+                    ));
+                }
+                let mut result =
+                    VerificationResult::from(output, harness.attributes.should_panic, start_time);
+                if timed_out && self.is_cancelled() {
+                    // The verification is incomplete, so it can't be successful.
+                    result.status = VerificationStatus::Failure;
+                    result.cancelled = true;
+                } else if timed_out {
+                    if !self.args.common_args.quiet {
+                        let hint = if self.args.property_at_a_time
+                            || self.args.property_batches.is_some()
+                        {
+                            "The properties that weren't checked are reported as undetermined."
+                        } else {
+                            "Use `--property-at-a-time` to report the properties checked before \
+                            the timeout."
+                        };
+                        warning(&format!(
+                            "harness `{}` timed out after {}s. {hint}",
+                            harness.pretty_name,
+                            self.args.harness_timeout.unwrap()
+                        ));
+                    }
+                    // The verification is incomplete, so it can't be successful.
+                    result.status = VerificationStatus::Failure;
+                    result.timed_out = true;
+                }
+                break result;
+            }
+        };
 
-            // Spawn the CBMC process and process its output below
-            let cbmc_process_opt = self.run_piped(cmd)?;
-            let cbmc_process = cbmc_process_opt.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
-            let output = process_cbmc_output(cbmc_process, |i| {
+        if let Err(exit_status) = &verification_results.results
+            && let Some(CbmcSolver::Binary(solver)) = &self.args.external_sat_solver
+        {
+            warning(&format!(
+                "CBMC exited with status {exit_status} without results while using the external \
+                SAT solver `{solver}`. Check that the solver accepts a DIMACS file as its only \
+                argument and prints its result in the SAT competition format."
+            ));
+        }
+
+        Ok(verification_results)
+    }
+
+    /// Run CBMC with the JSON output format, and process its output. CBMC is killed if it's
+    /// still running at `deadline`, which is reported by the returned boolean.
+    fn run_cbmc_json(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        object_bits: Option<u32>,
+        deadline: Option<Instant>,
+    ) -> Result<(VerificationOutput, bool)> {
+        let solver = match self.resolve_solver(&harness.attributes.solver) {
+            CbmcSolver::Portfolio(solvers) => {
+                return self.run_cbmc_portfolio(file, harness, solvers, object_bits, deadline);
+            }
+            solver => solver,
+        };
+
+        let mut cmd = cbmc_command();
+        cmd.args(self.cbmc_flags_with_solver(file, harness, Some(solver), object_bits)?);
+        // Add extra argument to receive the output in JSON format.
+        // Done here because `--visualize` uses the XML format instead.
+        cmd.arg("--json-ui");
+
+        // Spawn the CBMC process and process its output below
+        let cbmc_process_opt = self.run_piped(cmd)?;
+        let cbmc_process = cbmc_process_opt.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
+        let progress = self.progress_line(harness);
+        // The messages of an attempt that may be retried with more object bits are only printed
+        // once we know that it's the last attempt.
+        let may_retry = object_bits.is_some_and(|bits| bits < MAX_OBJECT_BITS);
+        let (output, timed_out) =
+            process_cbmc_output_until(cbmc_process, deadline, self.cancellation(), |i| {
+                if let Some(progress) = &progress {
+                    progress.update(&i);
+                }
                 kani_cbmc_output_filter(
                     i,
                     self.args.extra_pointer_checks,
-                    self.args.common_args.quiet,
+                    self.args.common_args.quiet || may_retry,
                     &self.args.output_format,
                 )
             })?;
+        if may_retry
+            && !self.args.common_args.quiet
+            && !needs_more_object_bits(object_bits, timed_out, &output.processed_items)
+        {
+            print_cbmc_messages(&output.processed_items, &self.args.output_format);
+        }
+        Ok((output, timed_out))
+    }
+
+    /// A live progress line for the harness, unless CBMC messages are already printed, nothing
+    /// should be printed, or several harnesses are verified in parallel.
+    fn progress_line(&self, harness: &HarnessMetadata) -> Option<ProgressLine> {
+        if !self.args.output_format.is_terse()
+            || self.args.common_args.quiet
+            || self.args.jobs() != Some(1)
+        {
+            return None;
+        }
+        ProgressLine::new(&harness.pretty_name)
+    }
+
+    /// Run one CBMC process per solver of the portfolio in parallel, and return the output of
+    /// the first one to produce results. The remaining processes are killed at that point, or
+    /// all of them once `deadline` is reached.
+    fn run_cbmc_portfolio(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        solvers: &[CbmcSolver],
+        object_bits: Option<u32>,
+        deadline: Option<Instant>,
+    ) -> Result<(VerificationOutput, bool)> {
+        let mut processes = Vec::with_capacity(solvers.len());
+        for solver in solvers {
+            let mut cmd = cbmc_command();
+            cmd.args(self.cbmc_flags_with_solver(file, harness, Some(solver), object_bits)?);
+            cmd.arg("--json-ui");
+            let cbmc_process = self.run_piped(cmd)?;
+            processes.push(cbmc_process.ok_or(anyhow::Error::msg("Failed to run cbmc"))?);
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let first = thread::scope(|scope| {
+            for (idx, process) in processes.iter_mut().enumerate() {
+                let mut stdout = process.stdout.take().unwrap();
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    // The output of each solver is parsed quietly. Printing it as we go would
+                    // interleave the messages of all solvers.
+                    let items = parse_cbmc_output(&mut stdout, |i| {
+                        kani_cbmc_output_filter(
+                            i,
+                            self.args.extra_pointer_checks,
+                            true,
+                            &self.args.output_format,
+                        )
+                    });
+                    // The receiver is gone once a winner is picked, so ignore errors.
+                    let _ = sender.send((idx, items));
+                });
+            }
+            drop(sender);
+
+            // If no solver produces results (e.g. they all crashed), keep the last output so
+            // the failure gets reported as usual.
+            let mut last = None;
+            let mut timed_out = false;
+            let cancellation = self.cancellation();
+            loop {
+                let received = if !timed_out && (deadline.is_some() || cancellation.is_some()) {
+                    receiver
+                        .recv_timeout(wait_time(deadline))
+                        .map_err(|err| err == RecvTimeoutError::Timeout)
+                } else {
+                    receiver.recv().map_err(|_| false)
+                };
+                let (idx, items) = match received {
+                    Ok(received) => received,
+                    Err(true) if is_stopped(deadline, cancellation) => {
+                        for process in processes.iter_mut() {
+                            let _ = process.kill();
+                        }
+                        timed_out = true;
+                        continue;
+                    }
+                    Err(true) => continue,
+                    Err(false) => break,
+                };
+                if items.iter().any(|item| matches!(item, ParserItem::Result { .. })) {
+                    for (other, process) in processes.iter_mut().enumerate() {
+                        if other != idx {
+                            // The process may have exited already, which is fine.
+                            let _ = process.kill();
+                        }
+                    }
+                    return (Some((idx, items)), timed_out);
+                }
+                last = Some((idx, items));
+            }
+            (last, timed_out)
+        });
 
-            VerificationResult::from(output, harness.attributes.should_panic, start_time)
+        let mut statuses = Vec::with_capacity(processes.len());
+        for process in &mut processes {
+            statuses.push(process.wait()?);
+        }
+        let (first, timed_out) = first;
+        let (idx, processed_items) = first.expect("portfolio should have at least one solver");
+        // The memory of each solver isn't measured, since they run at the same time.
+        let output = VerificationOutput {
+            process_status: process_status(statuses[idx]),
+            processed_items,
+            peak_memory_kb: None,
+            portfolio_solver: (!timed_out).then(|| solver_name(&solvers[idx]).to_string()),
         };
+        Ok((output, timed_out))
+    }
 
-        Ok(verification_results)
+    /// Check the properties of the harness in batches, each one with a separate CBMC run, and
+    /// merge their results. With `--property-at-a-time`, each batch has a single property and
+    /// the batches are checked in sequence. Otherwise, the properties are split into
+    /// `--property-batches` batches that are checked in parallel.
+    ///
+    /// The properties that weren't checked by `deadline` are reported as undetermined.
+    fn run_cbmc_property_batches(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        object_bits: Option<u32>,
+        deadline: Option<Instant>,
+    ) -> Result<(VerificationOutput, bool)> {
+        let solver = self.resolve_sequential_solver(&harness.attributes.solver);
+        let flags = self.cbmc_flags_with_solver(file, harness, Some(solver), object_bits)?;
+        let properties = self.list_properties(&flags, harness)?;
+        let batch_size = match self.args.property_batches {
+            Some(batches) => properties.len().div_ceil(batches as usize).max(1),
+            None => 1,
+        };
+        let batches: Vec<_> = properties.chunks(batch_size).collect();
+
+        // A batch has no output if it wasn't started before the deadline.
+        let outputs: Vec<Option<(VerificationOutput, bool)>> =
+            if self.args.property_batches.is_some() {
+                thread::scope(|scope| {
+                    let handles: Vec<_> = batches
+                        .iter()
+                        .map(|batch| {
+                            scope.spawn(|| self.run_property_batch(&flags, batch, deadline))
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .map(|handle| handle.join().unwrap().map(Some))
+                        .collect::<Result<_>>()
+                })?
+            } else {
+                let mut outputs = vec![];
+                for batch in &batches {
+                    if is_stopped(deadline, self.cancellation()) {
+                        outputs.push(None);
+                    } else {
+                        outputs.push(Some(self.run_property_batch(&flags, batch, deadline)?));
+                    }
+                }
+                outputs
+            };
+
+        let mut timed_out = false;
+        let mut process_status = 0;
+        let mut peak_memory_kb = None;
+        let mut processed_items = vec![];
+        let mut results = vec![];
+        for (batch, output) in batches.iter().zip(outputs) {
+            let Some((output, killed)) = output else {
+                timed_out = true;
+                results.extend(batch.iter().map(|(_, undetermined)| undetermined.clone()));
+                continue;
+            };
+            peak_memory_kb = peak_memory_kb.max(output.peak_memory_kb);
+            let (items, result) = extract_results(output.processed_items);
+            processed_items.extend(items);
+            match result {
+                Some(result) if !killed => results.extend(result),
+                _ => {
+                    timed_out |= killed;
+                    process_status = output.process_status;
+                    results.extend(batch.iter().map(|(_, undetermined)| undetermined.clone()));
+                }
+            }
+        }
+        // Results are postprocessed once all properties are merged, since the postprocessing
+        // looks at several properties together (e.g. reachability checks).
+        let result = kani_cbmc_output_filter(
+            ParserItem::Result { result: results },
+            self.args.extra_pointer_checks,
+            self.args.common_args.quiet,
+            &self.args.output_format,
+        );
+        processed_items.extend(result);
+        let output = VerificationOutput {
+            process_status,
+            processed_items,
+            peak_memory_kb,
+            portfolio_solver: None,
+        };
+        Ok((output, timed_out))
+    }
+
+    /// Check the properties of `batch` with a CBMC run. The output isn't postprocessed, and it
+    /// includes whether CBMC was killed at `deadline`.
+    fn run_property_batch(
+        &self,
+        flags: &[OsString],
+        batch: &[(String, Property)],
+        deadline: Option<Instant>,
+    ) -> Result<(VerificationOutput, bool)> {
+        let mut cmd = cbmc_command();
+        cmd.args(flags);
+        for (name, _) in batch {
+            cmd.arg("--property").arg(name);
+        }
+        cmd.arg("--json-ui");
+        let cbmc_process = self.run_piped(cmd)?;
+        let cbmc_process = cbmc_process.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
+        process_cbmc_output_until(cbmc_process, deadline, self.cancellation(), Some)
+    }
+
+    /// List the properties CBMC checks when run with `flags`. Each property is returned with
+    /// its name and an undetermined result for it.
+    fn list_properties(
+        &self,
+        flags: &[OsString],
+        harness: &HarnessMetadata,
+    ) -> Result<Vec<(String, Property)>> {
+        let mut cmd = cbmc_command();
+        cmd.args(flags).args(["--show-properties", "--json-ui"]);
+        if self.args.common_args.verbose {
+            println!("[Kani] Running: `{}`", render_command(&cmd).to_string_lossy());
+        }
+        let output = cmd.output()?;
+        let Ok(serde_json::Value::Array(items)) = serde_json::from_slice(&output.stdout) else {
+            bail!("failed to list the properties of harness `{}`", harness.pretty_name);
+        };
+        let mut properties = vec![];
+        for shown in items.iter().filter_map(|item| item.get("properties")?.as_array()).flatten() {
+            let Some(name) = shown.get("name").and_then(|name| name.as_str()) else { continue };
+            let undetermined = serde_json::from_value(serde_json::json!({
+                "description": shown.get("description").cloned().unwrap_or_else(|| "".into()),
+                "property": name,
+                "sourceLocation": shown
+                    .get("sourceLocation")
+                    .cloned()
+                    .unwrap_or_else(|| serde_json::json!({})),
+                "status": "UNDETERMINED",
+            }))?;
+            properties.push((name.to_string(), undetermined));
+        }
+        Ok(properties)
+    }
+
+    /// Generate the verification conditions of a harness as an SMT-LIB2 file in the
+    /// `--smt2-out` directory, without solving them.
+    pub fn run_vcgen(&self, file: &Path, harness: &HarnessMetadata) -> Result<PathBuf> {
+        let out_dir = self.args.smt2_out.as_ref().expect("`--only-vcgen` requires `--smt2-out`");
+        fs::create_dir_all(out_dir)?;
+        let harness_filename = harness.pretty_name.replace("::", "-");
+        let smt2_file = out_dir.join(format!("{harness_filename}.smt2"));
+
+        // The SMT backend doesn't use the SAT solver, so skip the solver flags.
+        let mut args =
+            self.cbmc_flags_with_solver(file, harness, None, self.args.cbmc_object_bits())?;
+        args.push("--smt2".into());
+        args.push("--outfile".into());
+        args.push(smt2_file.clone().into_os_string());
+
+        let mut cmd = cbmc_command();
+        cmd.args(args);
+        self.run_suppress(cmd)?;
+
+        Ok(smt2_file)
     }
 
     /// used by call_cbmc_viewer, invokes different variants of CBMC.
     // TODO: this could use some cleanup and refactoring.
     pub fn call_cbmc(&self, args: Vec<OsString>, output: &Path) -> Result<()> {
-        // TODO get cbmc path from self
-        let mut cmd = Command::new("cbmc");
+        let mut cmd = cbmc_command();
         cmd.args(args);
 
         let result = self.run_redirect(cmd, output)?;
@@ -118,35 +562,99 @@ pub fn cbmc_flags(
         &self,
         file: &Path,
         harness_metadata: &HarnessMetadata,
+    ) -> Result<Vec<OsString>> {
+        let solver = self.resolve_sequential_solver(&harness_metadata.attributes.solver);
+        self.cbmc_flags_with_solver(
+            file,
+            harness_metadata,
+            Some(solver),
+            self.args.cbmc_object_bits(),
+        )
+    }
+
+    /// The flags to CBMC for running the given harness with the given solver and object bits.
+    /// No solver flags are added if `solver` is `None`.
+    pub fn cbmc_flags_with_solver(
+        &self,
+        file: &Path,
+        harness_metadata: &HarnessMetadata,
+        solver: Option<&CbmcSolver>,
+        object_bits: Option<u32>,
     ) -> Result<Vec<OsString>> {
         let mut args = self.cbmc_check_flags();
 
-        if let Some(object_bits) = self.args.cbmc_object_bits() {
+        if let Some(object_bits) = object_bits {
             args.push("--object-bits".into());
             args.push(object_bits.to_string().into());
         }
 
-        if let Some(unwind_value) = resolve_unwind_value(&self.args, harness_metadata) {
+        let unwind_value = resolve_unwind_value(&self.args, harness_metadata);
+        if let Some(loop_id) = &self.args.incremental_unwind {
+            // Unwind the loop one iteration at a time, checking the properties after each
+            // unwinding with the same solver instance, up to the harness unwinding bound.
+            let Some(unwind_max) = unwind_value else {
+                bail!(
+                    "`--incremental-unwind` requires an unwinding bound for harness `{}`. \
+                    Use `--default-unwind`, `--unwind` or `#[kani::unwind]` to provide one.",
+                    harness_metadata.pretty_name
+                );
+            };
+            args.push("--incremental-loop".into());
+            args.push(loop_id.into());
+            args.push("--unwind-min".into());
+            args.push("1".into());
+            args.push("--unwind-max".into());
+            args.push(unwind_max.to_string().into());
+        }
+        // The other loops are still bounded by the unwinding bound.
+        if let Some(unwind_value) = unwind_value {
             args.push("--unwind".into());
             args.push(unwind_value.to_string().into());
         }
 
-        self.handle_solver_args(&harness_metadata.attributes.solver, &mut args)?;
+        if let Some(solver) = solver {
+            push_solver_args(solver, &mut args)?;
+        }
+
+        if self.args.allocator_may_fail {
+            args.extend(MALLOC_MAY_FAIL_ARGS.map(OsString::from));
+        }
 
         if self.args.run_sanity_checks {
             args.push("--validate-goto-model".into());
             args.push("--validate-ssa-equation".into());
         }
 
-        if !self.args.visualize
-            && self.args.concrete_playback.is_none()
-            && !self.args.no_slice_formula
-        {
-            args.push("--slice-formula".into());
+        let slicing = resolve_slicing(&self.args, harness_metadata);
+        let needs_traces = self.args.visualize
+            || self.args.concrete_playback.is_some()
+            || self.args.suggest_assumptions
+            || self.args.minimize_counterexamples
+            || self.args.heap_graph
+            || self.args.checks.leaks_on();
+        if slicing == CbmcSlicing::Full && needs_traces {
+            bail!(
+                "the `full` slicing selected for harness `{}` removes assignments needed to build \
+                traces, so it cannot be used with `--visualize`, `--concrete-playback`, \
+                `--suggest-assumptions`, `--minimize-counterexamples`, `--heap-graph` or \
+                `--check leaks`",
+                harness_metadata.pretty_name
+            );
         }
+        args.extend(slicing_flags(slicing, needs_traces));
 
-        if self.args.concrete_playback.is_some() {
-            args.push("--trace".into());
+        // The output formats that show an excerpt of the trace of each failure don't need the
+        // values of the variables removed by formula slicing, unlike the options above.
+        if self.args.concrete_playback.is_some()
+            || self.args.suggest_assumptions
+            || self.args.minimize_counterexamples
+            || self.args.heap_graph
+            || self.args.checks.leaks_on()
+            || self.args.trace_excerpt().is_some()
+        {
+            // The extended traces have the type of the assigned expressions, which is used to
+            // render the values of structs and enums.
+            args.extend(["--trace", "--trace-json-extended"].map(OsString::from));
         }
 
         args.extend(self.args.cbmc_args.iter().cloned());
@@ -185,6 +693,12 @@ pub fn cbmc_check_flags(&self) -> Vec<OsString> {
             args.push("--unwinding-assertions".into());
         }
 
+        if self.args.checks.leaks_on() {
+            // Only reports the allocations that are no longer reachable from a global variable
+            // when the harness returns. Use `--memory-cleanup-check` to report all of them.
+            args.push("--memory-leak-check".into());
+        }
+
         if self.args.extra_pointer_checks {
             // This was adding a lot of false positives with std dangling pointer. We should
             // still catch any invalid dereference with --pointer-check. Thus, only enable them
@@ -201,38 +715,75 @@ pub fn handle_solver_args(
         harness_solver: &Option<CbmcSolver>,
         args: &mut Vec<OsString>,
     ) -> Result<()> {
-        let solver = if let Some(solver) = &self.args.solver {
-            // `--solver` option takes precedence over attributes
+        push_solver_args(self.resolve_sequential_solver(harness_solver), args)
+    }
+
+    /// The solver to use for a harness with the given `solver` attribute.
+    fn resolve_solver<'a>(&'a self, harness_solver: &'a Option<CbmcSolver>) -> &'a CbmcSolver {
+        if let Some(solver) = self.args.external_sat_solver.as_ref().or(self.args.solver.as_ref()) {
+            // `--solver` and `--external-sat-solver` options take precedence over attributes
             solver
         } else if let Some(solver) = harness_solver {
             solver
         } else {
             &DEFAULT_SOLVER
-        };
+        }
+    }
 
-        match solver {
-            CbmcSolver::Cadical => {
-                args.push("--sat-solver".into());
-                args.push("cadical".into());
-            }
-            CbmcSolver::Kissat => {
-                args.push("--external-sat-solver".into());
-                args.push("kissat".into());
-            }
-            CbmcSolver::Minisat => {
-                // Minisat is currently CBMC's default solver, so no need to
-                // pass any arguments
-            }
-            CbmcSolver::Binary(solver_binary) => {
-                // Check if the specified binary exists in path
-                if which::which(solver_binary).is_err() {
-                    bail!("the specified solver \"{solver_binary}\" was not found in path")
-                }
-                args.push("--external-sat-solver".into());
-                args.push(solver_binary.into());
+    /// Same as `resolve_solver`, but for a single CBMC invocation. Commands that don't support
+    /// a solver portfolio use its first solver.
+    fn resolve_sequential_solver<'a>(
+        &'a self,
+        harness_solver: &'a Option<CbmcSolver>,
+    ) -> &'a CbmcSolver {
+        match self.resolve_solver(harness_solver) {
+            CbmcSolver::Portfolio(solvers) => &solvers[0],
+            solver => solver,
+        }
+    }
+}
+
+/// Add the CBMC arguments needed to use `solver`.
+fn push_solver_args(solver: &CbmcSolver, args: &mut Vec<OsString>) -> Result<()> {
+    match solver {
+        CbmcSolver::Cadical => {
+            args.push("--sat-solver".into());
+            args.push("cadical".into());
+        }
+        CbmcSolver::Kissat => {
+            args.push("--external-sat-solver".into());
+            args.push("kissat".into());
+        }
+        CbmcSolver::Minisat => {
+            // Minisat is currently CBMC's default solver, so no need to
+            // pass any arguments
+        }
+        CbmcSolver::Binary(solver_binary) => {
+            // Check if the specified binary exists in path
+            if which::which(solver_binary).is_err() {
+                bail!("the specified solver \"{solver_binary}\" was not found in path")
             }
+            args.push("--external-sat-solver".into());
+            args.push(solver_binary.into());
         }
-        Ok(())
+        CbmcSolver::Portfolio(_) => bail!("a solver portfolio cannot contain another portfolio"),
+    }
+    Ok(())
+}
+
+/// Whether CBMC must be run again with more object bits than `object_bits`, which is the case when
+/// it ran out of object bits before the maximum was reached.
+fn needs_more_object_bits(object_bits: Option<u32>, timed_out: bool, items: &[ParserItem]) -> bool {
+    object_bits.is_some_and(|bits| bits < MAX_OBJECT_BITS)
+        && !timed_out
+        && has_object_bits_error(items)
+}
+
+/// The name used to refer to `solver` in messages.
+fn solver_name(solver: &CbmcSolver) -> &str {
+    match solver {
+        CbmcSolver::Binary(solver_binary) => solver_binary,
+        solver => solver.as_ref(),
     }
 }
 
@@ -245,12 +796,16 @@ impl VerificationResult {
     ///       (CBMC will regularly report "failure" but that's just our cover checks.)
     ///   2. Positively checking for the presence of results.
     ///       (Do not mistake lack of results for success: report it as failure.)
-    fn from(
+    pub fn from(
         output: VerificationOutput,
         should_panic: bool,
         start_time: Instant,
     ) -> VerificationResult {
         let runtime = start_time.elapsed();
+        let phase_timings =
+            output.processed_items.iter().filter_map(ParserItem::phase_timing).collect();
+        let peak_memory_kb = output.peak_memory_kb;
+        let portfolio_solver = output.portfolio_solver;
         let (_, results) = extract_results(output.processed_items);
 
         if let Some(results) = results {
@@ -262,6 +817,11 @@ fn from(
                 results: Ok(results),
                 runtime,
                 generated_concrete_test: false,
+                phase_timings,
+                peak_memory_kb,
+                timed_out: false,
+                cancelled: false,
+                portfolio_solver: portfolio_solver.clone(),
             }
         } else {
             // We never got results from CBMC - something went wrong (e.g. crash) so it's failure
@@ -271,10 +831,38 @@ fn from(
                 results: Err(output.process_status),
                 runtime,
                 generated_concrete_test: false,
+                phase_timings,
+                peak_memory_kb,
+                timed_out: false,
+                cancelled: false,
+                portfolio_solver,
             }
         }
     }
 
+    /// Build the result of a harness from its postprocessed properties, e.g., when the
+    /// properties were cached by a previous run.
+    pub fn from_properties(
+        results: Vec<Property>,
+        should_panic: bool,
+        runtime: Duration,
+    ) -> VerificationResult {
+        let (status, failed_properties) =
+            verification_outcome_from_properties(&results, should_panic);
+        VerificationResult {
+            status,
+            failed_properties,
+            results: Ok(results),
+            runtime,
+            generated_concrete_test: false,
+            phase_timings: vec![],
+            peak_memory_kb: None,
+            timed_out: false,
+            cancelled: false,
+            portfolio_solver: None,
+        }
+    }
+
     pub fn mock_success() -> VerificationResult {
         VerificationResult {
             status: VerificationStatus::Success,
@@ -282,6 +870,11 @@ pub fn mock_success() -> VerificationResult {
             results: Ok(vec![]),
             runtime: Duration::from_secs(0),
             generated_concrete_test: false,
+            phase_timings: vec![],
+            peak_memory_kb: None,
+            timed_out: false,
+            cancelled: false,
+            portfolio_solver: None,
         }
     }
 
@@ -295,9 +888,21 @@ fn mock_failure() -> VerificationResult {
             results: Err(42),
             runtime: Duration::from_secs(0),
             generated_concrete_test: false,
+            phase_timings: vec![],
+            peak_memory_kb: None,
+            timed_out: false,
+            cancelled: false,
+            portfolio_solver: None,
         }
     }
 
+    /// The result of a harness with the given properties, which took a second to verify. This is
+    /// used by unit tests, together with [Property::mock].
+    #[cfg(test)]
+    pub fn mock_with_properties(properties: Vec<Property>) -> VerificationResult {
+        VerificationResult::from_properties(properties, false, Duration::from_secs(1))
+    }
+
     pub fn render(
         &self,
         output_format: &OutputFormat,
@@ -336,6 +941,32 @@ pub fn render(
         }
     }
 
+    /// Render the result of harness `name` with the terse output format.
+    pub fn render_terse(
+        &self,
+        name: &str,
+        should_panic: bool,
+        excerpt: Option<TraceExcerpt>,
+    ) -> String {
+        match &self.results {
+            Ok(results) => format_result_terse(
+                name,
+                results,
+                self.status,
+                should_panic,
+                self.failed_properties,
+                self.runtime,
+                excerpt,
+            ),
+            Err(_) => format!(
+                "Harness {name}: {} ({:.2}s){}",
+                console::style("FAILED").red(),
+                self.runtime.as_secs_f32(),
+                self.render(&OutputFormat::Terse, should_panic, false)
+            ),
+        }
+    }
+
     /// Find the failed properties from this verification run
     pub fn failed_properties(&self) -> Vec<&Property> {
         if let Ok(properties) = &self.results {
@@ -347,6 +978,50 @@ pub fn failed_properties(&self) -> Vec<&Property> {
     }
 }
 
+/// A builder of properties for unit tests. The property has no location, trace or description
+/// until they're set by the `with_*` methods.
+#[cfg(test)]
+impl Property {
+    pub fn mock(class: &str, status: CheckStatus) -> Property {
+        Property {
+            description: String::new(),
+            property_id: PropertyId { fn_name: None, class: class.to_string(), id: 1 },
+            source_location: SourceLocation {
+                column: None,
+                file: None,
+                function: None,
+                line: None,
+            },
+            status,
+            reach: None,
+            trace: None,
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Property {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Set the function and the index that identify the property, e.g., `check.assertion.2`.
+    pub fn with_id(mut self, fn_name: &str, id: u32) -> Property {
+        self.property_id.fn_name = Some(fn_name.to_string());
+        self.property_id.id = id;
+        self
+    }
+
+    pub fn with_location(mut self, file: &str, line: usize) -> Property {
+        self.source_location.file = Some(file.to_string());
+        self.source_location.line = Some(line.to_string());
+        self
+    }
+
+    pub fn with_trace(mut self, trace: Vec<TraceItem>) -> Property {
+        self.trace = Some(trace);
+        self
+    }
+}
+
 /// We decide if verification succeeded based on properties, not (typically) on exit code
 fn verification_outcome_from_properties(
     properties: &[Property],
@@ -397,6 +1072,32 @@ pub fn resolve_unwind_value(
     args.unwind.or(harness_metadata.attributes.unwind_value).or(args.default_unwind)
 }
 
+/// Resolve the slicing options for a harness. `--no-slice-formula` disables all slicing, and
+/// `--slicing` takes precedence over the harness attribute.
+pub fn resolve_slicing(args: &VerificationArgs, harness_metadata: &HarnessMetadata) -> CbmcSlicing {
+    if args.no_slice_formula {
+        CbmcSlicing::None
+    } else {
+        args.slicing.or(harness_metadata.attributes.slicing).unwrap_or(CbmcSlicing::Formula)
+    }
+}
+
+/// The CBMC flags for the given slicing options.
+/// Formula slicing prevents values from being assigned to redundant variables in traces, so it
+/// is skipped if traces are needed.
+fn slicing_flags(slicing: CbmcSlicing, needs_traces: bool) -> Vec<OsString> {
+    let mut args: Vec<OsString> = match slicing {
+        CbmcSlicing::None => return vec![],
+        CbmcSlicing::Formula => vec![],
+        CbmcSlicing::Reachability => vec!["--reachability-slice".into()],
+        CbmcSlicing::Full => vec!["--full-slice".into()],
+    };
+    if !needs_traces {
+        args.push("--slice-formula".into());
+    }
+    args
+}
+
 #[cfg(test)]
 mod tests {
     use crate::args;
@@ -405,6 +1106,51 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn check_render_terse() {
+        use crate::cbmc_output_parser::{TraceData, TraceValue};
+        let assignment = |lhs: &str, value: &str| TraceItem {
+            step_type: "assignment".to_string(),
+            lhs: Some(lhs.to_string()),
+            source_location: None,
+            value: Some(TraceValue {
+                binary: None,
+                data: Some(TraceData::NonBool(value.to_string())),
+                name: None,
+                width: Some(8),
+                members: None,
+                member: None,
+                elements: None,
+            }),
+            raw_lhs: None,
+        };
+        let mut property = Property::mock("assertion", CheckStatus::Success)
+            .with_description("assertion failed: x < y")
+            .with_id("check", 1);
+        let runtime = Duration::from_millis(250);
+        let excerpt = |len| Some(TraceExcerpt { len, full_trace: false });
+
+        let success = VerificationResult::from_properties(vec![property.clone()], false, runtime);
+        let rendered = success.render_terse("check", false, excerpt(10));
+        assert_eq!(rendered.lines().count(), 1);
+        assert!(rendered.starts_with("Harness check: "));
+        assert!(rendered.contains("SUCCESSFUL"));
+        assert!(rendered.ends_with("(0.25s)"));
+
+        property.status = CheckStatus::Failure;
+        property.trace =
+            Some(vec![assignment("x", "1"), assignment("y", "2"), assignment("x", "3")]);
+        let failure = VerificationResult::from_properties(vec![property], false, runtime);
+        let rendered = failure.render_terse("check", false, excerpt(2));
+        assert!(rendered.contains("FAILED"));
+        assert!(rendered.contains("Failed Checks: assertion failed: x < y"));
+        assert!(
+            rendered
+                .contains("Trace of check.assertion.1 (last 2 assignments):\n  y = 2\n  x = 3\n")
+        );
+        assert!(!failure.render_terse("check", false, None).contains("Trace of"));
+    }
+
     #[test]
     fn check_resolve_unwind_value() {
         // Command line unwind value for specific harnesses take precedence over default annotation value
@@ -436,4 +1182,35 @@ fn resolve(args: &[&str], harness: &HarnessMetadata) -> Option<u32> {
         assert_eq!(resolve(&args_only_harness, &harness_some), Some(1));
         assert_eq!(resolve(&args_both, &harness_some), Some(1));
     }
+
+    #[test]
+    fn check_resolve_slicing() {
+        let mut harness = mock_proof_harness("check_one", None, None, None);
+        let resolve = |args: &str, harness: &HarnessMetadata| {
+            let args = args::StandaloneArgs::try_parse_from(args.split(" ")).unwrap();
+            resolve_slicing(&args.verify_opts, harness)
+        };
+
+        assert_eq!(resolve("kani x.rs", &harness), CbmcSlicing::Formula);
+        harness.attributes.slicing = Some(CbmcSlicing::Reachability);
+        assert_eq!(resolve("kani x.rs", &harness), CbmcSlicing::Reachability);
+        assert_eq!(resolve("kani x.rs --slicing full", &harness), CbmcSlicing::Full);
+        assert_eq!(
+            resolve("kani x.rs --enable-unstable --no-slice-formula", &harness),
+            CbmcSlicing::None
+        );
+    }
+
+    #[test]
+    fn check_slicing_flags() {
+        assert!(slicing_flags(CbmcSlicing::None, false).is_empty());
+        assert_eq!(slicing_flags(CbmcSlicing::Formula, false), ["--slice-formula"]);
+        assert!(slicing_flags(CbmcSlicing::Formula, true).is_empty());
+        assert_eq!(
+            slicing_flags(CbmcSlicing::Reachability, false),
+            ["--reachability-slice", "--slice-formula"]
+        );
+        assert_eq!(slicing_flags(CbmcSlicing::Reachability, true), ["--reachability-slice"]);
+        assert_eq!(slicing_flags(CbmcSlicing::Full, false), ["--full-slice", "--slice-formula"]);
+    }
 }
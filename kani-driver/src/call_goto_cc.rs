@@ -32,6 +32,17 @@ pub fn link_goto_binary(&self, inputs: &[PathBuf], output: &Path) -> Result<()>
         Ok(())
     }
 
+    /// Produce `output` by linking the goto binary of a proof harness (`input`) with a C file,
+    /// whose definitions replace the weak definitions of `input`.
+    pub fn link_c_file(&self, input: &Path, c_file: &Path, output: &Path) -> Result<()> {
+        let mut cmd = Command::new("goto-cc");
+        cmd.arg(input).arg(c_file).arg("-o").arg(output);
+
+        self.run_suppress(cmd)?;
+
+        Ok(())
+    }
+
     /// Produce a goto binary with its entry point set to a particular proof harness.
     pub fn specialize_to_proof_harness(
         &self,
@@ -8,6 +8,9 @@
 use std::path::Path;
 use std::process::Command;
 
+use crate::args::MemoryModel;
+use crate::call_cbmc::MALLOC_MAY_FAIL_ARGS;
+use crate::k_induction::KInductionCase;
 use crate::metadata::collect_and_link_function_pointer_restrictions;
 use crate::project::Project;
 use crate::session::KaniSession;
@@ -41,11 +44,10 @@ pub fn instrument_model(
 
         if self.args.checks.undefined_function_on() {
             self.add_library(output)?;
-            self.undefined_functions(output)?;
-        } else {
-            self.just_drop_unused_functions(output)?;
         }
 
+        self.instrument_functions(output)?;
+
         self.rewrite_back_edges(output)?;
 
         if self.args.gen_c {
@@ -90,43 +92,48 @@ pub fn apply_vtable_restrictions(&self, goto_file: &Path, restrictions: &Path) -
     /// Normally this happens implicitly, but we use this explicitly
     /// before we invoke `undefined_functions` below, otherwise these
     /// functions appear undefined.
+    ///
+    /// The library model of `malloc` returns a null pointer on failure when running with
+    /// `--allocator-may-fail`.
     fn add_library(&self, file: &Path) -> Result<()> {
-        let args: Vec<OsString> = vec![
-            "--add-library".into(),
-            file.to_owned().into_os_string(), // input
-            file.to_owned().into_os_string(), // output
-        ];
+        let mut args: Vec<OsString> = vec!["--add-library".into()];
+        if self.args.allocator_may_fail {
+            args.extend(MALLOC_MAY_FAIL_ARGS.map(OsString::from));
+        }
+        args.push(file.to_owned().into_os_string()); // input
+        args.push(file.to_owned().into_os_string()); // output
 
         self.call_goto_instrument(args)
     }
 
-    /// Instruct CBMC to "assert false" when invoking an undefined function.
-    /// (This contrasts with its default behavior of returning `nondet`, which is
-    /// unsound in the face of side-effects.)
-    /// Then remove unused functions. (Oddly, it seems CBMC will both see some
-    /// functions as unused and remove them, and also as used and so would
-    /// generate "assert false". So it's essential to do this afterwards.)
-    fn undefined_functions(&self, file: &Path) -> Result<()> {
-        let args: Vec<OsString> = vec![
-            "--generate-function-body-options".into(),
-            "assert-false-assume-false".into(),
-            "--generate-function-body".into(),
-            ".*".into(),
-            "--drop-unused-functions".into(),
-            file.to_owned().into_os_string(), // input
-            file.to_owned().into_os_string(), // output
-        ];
+    /// Remove the functions unreachable from the current proof harness, and add the checks
+    /// selected by the user to the remaining ones.
+    ///
+    /// These transformations are done by a single call to goto-instrument, so the model is only
+    /// read and written once. goto-instrument removes the unused functions after the other
+    /// transformations, which doesn't change the result since the checks added to the removed
+    /// functions are unreachable.
+    fn instrument_functions(&self, file: &Path) -> Result<()> {
+        let mut args: Vec<OsString> = if self.args.checks.undefined_function_on() {
+            undefined_functions_args()
+        } else {
+            vec!["--drop-unused-functions".into()]
+        };
 
-        self.call_goto_instrument(args)
-    }
+        if self.args.checks.data_races_on() {
+            args.extend(data_race_check_args());
+        }
 
-    /// Remove all functions unreachable from the current proof harness.
-    fn just_drop_unused_functions(&self, file: &Path) -> Result<()> {
-        let args: Vec<OsString> = vec![
-            "--drop-unused-functions".into(),
-            file.to_owned().into_os_string(), // input
-            file.to_owned().into_os_string(), // output
-        ];
+        if let Some(depth) = self.args.checks.max_call_depth {
+            args.extend(stack_depth_check_args(depth));
+        }
+
+        if let Some(memory_model) = self.args.memory_model {
+            args.extend(memory_model_args(memory_model));
+        }
+
+        args.push(file.to_owned().into_os_string()); // input
+        args.push(file.to_owned().into_os_string()); // output
 
         self.call_goto_instrument(args)
     }
@@ -151,6 +158,25 @@ fn goto_sanity_check(&self, file: &Path) -> Result<()> {
         self.call_goto_instrument(args)
     }
 
+    /// Instrument the base or the step case of a k-induction proof with depth `k`.
+    pub fn instrument_k_induction(
+        &self,
+        input: &Path,
+        output: &Path,
+        k: u32,
+        case: KInductionCase,
+    ) -> Result<()> {
+        let args: Vec<OsString> = vec![
+            "--k-induction".into(),
+            k.to_string().into(),
+            case.flag().into(),
+            input.to_owned().into_os_string(),  // input
+            output.to_owned().into_os_string(), // output
+        ];
+
+        self.call_goto_instrument(args)
+    }
+
     /// Generate a .c file from a goto binary (i.e. --gen-c)
     pub fn gen_c(&self, file: &Path, output_file: &Path) -> Result<()> {
         let args: Vec<OsString> = vec![
@@ -219,3 +245,38 @@ fn call_goto_instrument<S: AsRef<OsStr>>(
         self.run_suppress(cmd)
     }
 }
+
+/// Instruct CBMC to "assert false" when invoking an undefined function.
+/// (This contrasts with its default behavior of returning `nondet`, which is
+/// unsound in the face of side-effects.)
+/// Then remove unused functions. (Oddly, it seems CBMC will both see some
+/// functions as unused and remove them, and also as used and so would
+/// generate "assert false". So it's essential to do this afterwards.)
+fn undefined_functions_args() -> Vec<OsString> {
+    vec![
+        "--generate-function-body-options".into(),
+        "assert-false-assume-false".into(),
+        "--generate-function-body".into(),
+        ".*".into(),
+        "--drop-unused-functions".into(),
+    ]
+}
+
+/// Add assertions that fail if two threads access the same shared memory location, and one of
+/// the accesses is a write. Accesses in atomic blocks, such as atomic intrinsics, can't race.
+fn data_race_check_args() -> Vec<OsString> {
+    vec!["--race-check".into()]
+}
+
+/// Add assertions that fail if the call stack has more than `depth` frames. The depth is
+/// tracked by a global counter that is incremented on function entry and decremented on
+/// return.
+fn stack_depth_check_args(depth: u32) -> Vec<OsString> {
+    vec!["--stack-depth".into(), depth.to_string().into()]
+}
+
+/// Instrument the shared memory accesses with the store buffers of a weak memory model. The
+/// fences that implement the ordering of atomic operations are generated by the compiler.
+fn memory_model_args(memory_model: MemoryModel) -> Vec<OsString> {
+    vec!["--mm".into(), memory_model.as_ref().into()]
+}
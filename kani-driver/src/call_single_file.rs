@@ -7,7 +7,8 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use crate::session::{lib_folder, KaniSession};
+use crate::args::CoverageMacroLocation;
+use crate::session::{lib_folder, KaniSession, ReachabilityMode};
 
 impl KaniSession {
     /// Used by `kani` and not `cargo-kani` to process a single Rust file into a `.symtab.json`
@@ -19,7 +20,7 @@ pub fn compile_single_rust_file(
         outdir: &Path,
     ) -> Result<()> {
         let mut kani_args = self.kani_compiler_flags();
-        kani_args.push(format!("--reachability={}", self.reachability_mode()));
+        kani_args.extend(self.reachability_flags());
 
         let mut rustc_args = self.kani_rustc_flags();
         rustc_args.push(file.into());
@@ -62,7 +63,43 @@ pub fn compile_single_rust_file(
 
     /// Create a compiler option that represents the reachability mod.
     pub fn reachability_arg(&self) -> String {
-        to_rustc_arg(vec![format!("--reachability={}", self.reachability_mode())])
+        to_rustc_arg(self.reachability_flags())
+    }
+
+    /// The compiler options that select the code to generate: the reachability mode and, for
+    /// proof harnesses, the harness filters, so only the code of the selected harnesses is
+    /// generated.
+    fn reachability_flags(&self) -> Vec<String> {
+        let mut flags = vec![format!("--reachability={}", self.reachability_mode())];
+        if self.reachability_mode() != ReachabilityMode::ProofHarnesses {
+            return flags;
+        }
+        if self.metadata_only {
+            flags.push("--metadata-only".into());
+            return flags;
+        }
+        let filters: Vec<_> = self
+            .args
+            .harnesses
+            .iter()
+            .map(|filter| format!("--harness={filter}"))
+            .chain(
+                self.args
+                    .exclude_harnesses
+                    .iter()
+                    .map(|filter| format!("--exclude-harness={filter}")),
+            )
+            .collect();
+        // The compiler options are split at whitespaces, so the code of every harness is
+        // generated if a filter contains one.
+        if !filters.is_empty() && !filters.iter().any(|filter| filter.contains(char::is_whitespace))
+        {
+            if self.args.exact {
+                flags.push("--exact".into());
+            }
+            flags.extend(filters);
+        }
+        flags
     }
 
     /// These arguments are arguments passed to kani-compiler that are `kani` compiler specific.
@@ -99,16 +136,61 @@ pub fn kani_compiler_flags(&self) -> Vec<String> {
 
         if self.args.coverage {
             flags.push("--coverage-checks".into());
+            if self.args.coverage_macro_location == CoverageMacroLocation::Definition {
+                flags.push("--coverage-macro-location=definition".into());
+            }
         }
 
         if self.args.common_args.unstable_features.contains(UnstableFeature::ValidValueChecks) {
             flags.push("--ub-check=validity".into())
         }
 
+        if self.args.common_args.unstable_features.contains(UnstableFeature::AliasingChecks) {
+            flags.push("--ub-check=aliasing".into())
+        }
+
+        if self.args.common_args.unstable_features.contains(UnstableFeature::ProvenanceChecks) {
+            flags.push("--ub-check=provenance".into())
+        }
+
+        if self.args.common_args.unstable_features.contains(UnstableFeature::UninitChecks) {
+            flags.push("--ub-check=uninit".into())
+        }
+
+        if self.args.common_args.unstable_features.contains(UnstableFeature::AlignmentChecks) {
+            flags.push("--ub-check=alignment".into())
+        }
+
+        if self.args.allocator_may_fail {
+            flags.push("--allocator-may-fail".into());
+        }
+
+        if self.args.memory_model.is_some() {
+            flags.push("--weak-memory".into());
+        }
+
         if self.args.ignore_locals_lifetime {
             flags.push("--ignore-storage-markers".into())
         }
 
+        if self.args.minimize_counterexamples {
+            flags.push("--minimize-counterexamples".into())
+        }
+
+        if self.args.no_prepass {
+            flags.push("--no-prepass".into())
+        }
+
+        for stub in &self.args.stubs {
+            flags.push(format!("--stub={stub}"));
+        }
+
+        for path in &self.args.instrumentation_pass {
+            // The compiler may run from a different directory, e.g., with `cargo kani`.
+            let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+            flags.push(format!("--instrumentation-pass={}", path.display()));
+        }
+
         flags.extend(self.args.common_args.unstable_features.as_arguments().map(str::to_string));
 
         // This argument will select the Kani flavour of the compiler. It will be removed before
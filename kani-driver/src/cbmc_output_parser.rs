@@ -27,13 +27,17 @@
 use console::style;
 use pathdiff::diff_paths;
 use rustc_demangle::demangle;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use std::env;
 use std::io::{BufRead, BufReader};
 use std::os::unix::process::ExitStatusExt;
 use std::path::PathBuf;
-use std::process::{Child, ChildStdout};
+use std::process::{Child, ChildStdout, ExitStatus};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
 
 const RESULT_ITEM_PREFIX: &str = "  {\n    \"result\":";
 
@@ -59,6 +63,43 @@ pub enum ParserItem {
     },
 }
 
+impl ParserItem {
+    /// The time spent by CBMC in one of its phases, if this is one of the
+    /// `Runtime <phase>: <seconds>s` messages CBMC prints at the end of each phase.
+    pub fn phase_timing(&self) -> Option<PhaseTiming> {
+        let ParserItem::Message { message_text, .. } = self else { return None };
+        let (phase, runtime) = message_text.strip_prefix("Runtime ")?.split_once(": ")?;
+        let runtime_secs = runtime.strip_suffix('s')?.parse().ok()?;
+        Some(PhaseTiming { phase: phase.to_string(), runtime_secs })
+    }
+
+    /// The phase CBMC is starting, if this message announces a new one.
+    pub fn progress_phase(&self) -> Option<String> {
+        let ParserItem::Message { message_text, .. } = self else { return None };
+        if message_text.starts_with("Starting Bounded Model Checking") {
+            Some("symbolic execution".to_string())
+        } else if message_text.starts_with("Generated ") && message_text.contains("VCC(s)") {
+            // E.g.: "Generated 12 VCC(s), 3 remaining after simplification"
+            Some(message_text.to_string())
+        } else if message_text.starts_with("Running propositional reduction") {
+            Some("propositional reduction".to_string())
+        } else if message_text.starts_with("Solving with") {
+            Some(message_text.to_lowercase())
+        } else if message_text.starts_with("Building error trace") {
+            Some("building error traces".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// The time CBMC spent in one of its phases (e.g. `Symex` or `Solver`).
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub runtime_secs: f64,
+}
+
 /// Struct that is equivalent to `ParserItem::Result`.
 ///
 /// Note: this struct is only used to provide better error messages when there
@@ -74,7 +115,7 @@ struct ResultStruct {
 ///
 /// Note: `reach` is not part of the parsed data, but it's useful to annotate
 /// its reachability status.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Property {
     pub description: String,
     #[serde(rename = "property")]
@@ -87,7 +128,7 @@ pub struct Property {
 }
 
 /// CBMC's somewhat-ish consistent format for naming properties.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PropertyId {
     pub fn_name: Option<String>,
     pub class: String,
@@ -97,6 +138,7 @@ pub struct PropertyId {
 impl Property {
     const COVER_PROPERTY_CLASS: &'static str = "cover";
     const COVERAGE_PROPERTY_CLASS: &'static str = "code_coverage";
+    const GAP_COVERAGE_DESCRIPTION: &'static str = "code coverage for gap region";
 
     pub fn property_class(&self) -> String {
         self.property_id.class.clone()
@@ -107,18 +149,19 @@ pub fn is_code_coverage_property(&self) -> bool {
         self.property_id.class == Self::COVERAGE_PROPERTY_CLASS
     }
 
+    /// Returns true if this is a code_coverage check in a line without executable code (e.g., a
+    /// line with only a closing brace). These don't count as uncovered code.
+    pub fn is_gap_coverage_property(&self) -> bool {
+        self.is_code_coverage_property() && self.description == Self::GAP_COVERAGE_DESCRIPTION
+    }
+
     /// Returns true if this is a cover property
     pub fn is_cover_property(&self) -> bool {
         self.property_id.class == Self::COVER_PROPERTY_CLASS
     }
 
     pub fn property_name(&self) -> String {
-        let class = &self.property_id.class;
-        let id = self.property_id.id;
-        match &self.property_id.fn_name {
-            Some(fn_name) => format!("{fn_name}.{class}.{id}"),
-            None => format!("{class}.{id}"),
-        }
+        self.property_id.to_string()
     }
 
     pub fn has_property_class_format(string: &str) -> bool {
@@ -126,6 +169,28 @@ pub fn has_property_class_format(string: &str) -> bool {
     }
 }
 
+impl std::fmt::Display for PropertyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let class = &self.class;
+        let id = self.id;
+        match &self.fn_name {
+            Some(fn_name) => write!(f, "{fn_name}.{class}.{id}"),
+            None => write!(f, "{class}.{id}"),
+        }
+    }
+}
+
+impl Serialize for PropertyId {
+    /// Serialize the property ID with the format it is parsed from, i.e.,
+    /// `<function>.<class>.<counter>`. The function name is already demangled.
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        s.serialize_str(&self.to_string())
+    }
+}
+
 impl<'de> serde::Deserialize<'de> for PropertyId {
     /// Gets all property attributes from the property ID.
     ///
@@ -216,7 +281,7 @@ fn deserialize<D>(d: D) -> Result<Self, D::Error>
 ///
 /// Source locations may be completely empty, which is why
 /// all members are optional.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct SourceLocation {
     pub column: Option<String>,
     pub file: Option<String>,
@@ -284,28 +349,70 @@ fn filepath(file: String) -> String {
 ///
 /// In general, traces may include more information than this, but this is not
 /// documented anywhere. So we ignore the rest for now.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TraceItem {
     pub step_type: String,
     pub lhs: Option<String>,
     pub source_location: Option<SourceLocation>,
     pub value: Option<TraceValue>,
+    /// The assigned expression with its type, which is only printed with
+    /// `--trace-json-extended`.
+    #[serde(skip_serializing)]
+    pub raw_lhs: Option<serde_json::Value>,
+}
+
+impl TraceItem {
+    /// The tag of the struct assigned by this step, or of the structs in the assigned array.
+    pub fn lhs_tag(&self) -> Option<&str> {
+        let mut typ = self.raw_lhs.as_ref()?.pointer("/namedSub/type")?;
+        while typ["id"] == "array" {
+            typ = typ.pointer("/sub/0")?;
+        }
+        if typ["id"] == "struct_tag" {
+            typ.pointer("/namedSub/identifier/id")?.as_str()
+        } else {
+            None
+        }
+    }
 }
 
 /// Struct that represents a trace value.
 ///
 /// Note: this struct can have a lot of different fields depending on the value type.
-/// The fields included right now are relevant to primitive types.
-#[derive(Clone, Debug, Deserialize)]
+/// The fields included right now are relevant to primitive types, and to the components of
+/// structs, unions and arrays, whose `data` is rendered by Kani from these components.
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct TraceValue {
     pub binary: Option<String>,
     pub data: Option<TraceData>,
+    /// The kind of value, e.g., `integer` or `pointer`.
+    pub name: Option<String>,
     pub width: Option<u32>,
+    /// The components of a struct.
+    pub members: Option<Vec<TraceMember>>,
+    /// The component of a union, which is picked by CBMC among the components of its type.
+    pub member: Option<Box<TraceMember>>,
+    /// The elements of an array.
+    pub elements: Option<Vec<TraceElement>>,
+}
+
+/// A component of a struct or union value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TraceMember {
+    pub name: String,
+    pub value: TraceValue,
+}
+
+/// An element of an array value.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct TraceElement {
+    pub index: usize,
+    pub value: TraceValue,
 }
 
 /// Enum that represents a trace data item.
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(untagged)]
 pub enum TraceData {
     NonBool(String),
@@ -321,13 +428,16 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
-#[derive(Copy, Clone, Debug, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum CheckStatus {
     Failure,
     Covered,   // for `code_coverage` properties only
     Satisfied, // for `cover` properties only
     Success,
+    // CBMC reports `UNKNOWN` for the properties it hasn't decided yet, e.g., in the results of
+    // the first unwindings with `--incremental-loop`.
+    #[serde(alias = "UNKNOWN")]
     Undetermined,
     Unreachable,
     Uncovered,     // for `code_coverage` properties only
@@ -489,6 +599,11 @@ fn next(&mut self) -> Option<Self::Item> {
                     if len == 0 {
                         return None;
                     }
+                    if !input.ends_with('\n') {
+                        // The process was killed while it printed this line, e.g., because
+                        // another solver of the portfolio finished first.
+                        return None;
+                    }
                     let item = self.process_line(input);
                     if item.is_some() {
                         return item;
@@ -508,6 +623,10 @@ fn next(&mut self) -> Option<Self::Item> {
 pub struct VerificationOutput {
     pub process_status: i32,
     pub processed_items: Vec<ParserItem>,
+    /// The peak memory usage of the CBMC process in kB, if it could be measured.
+    pub peak_memory_kb: Option<u64>,
+    /// The solver of the portfolio that produced this output, if it finished first.
+    pub portfolio_solver: Option<String>,
 }
 
 /// The main function to process CBMC's output.
@@ -520,17 +639,118 @@ pub struct VerificationOutput {
 /// The cbmc process status is returned, along with the (post-filter) items.
 pub fn process_cbmc_output(
     mut process: Child,
-    eager_filter: impl FnMut(ParserItem) -> Option<ParserItem>,
+    mut eager_filter: impl FnMut(ParserItem) -> Option<ParserItem>,
 ) -> Result<VerificationOutput> {
-    let stdout = process.stdout.as_mut().unwrap();
+    let pid = process.id();
+    let mut peak_memory_kb = None;
+    let processed_items = parse_cbmc_output(process.stdout.as_mut().unwrap(), |item| {
+        peak_memory_kb = peak_memory_kb.max(process_peak_memory_kb(pid));
+        eager_filter(item)
+    });
+    // This will get us the process's exit code
+    let status = process.wait()?;
+    Ok(VerificationOutput {
+        process_status: process_status(status),
+        processed_items,
+        peak_memory_kb,
+        portfolio_solver: None,
+    })
+}
+
+/// Same as [process_cbmc_output], but kill the process if it's still running at `deadline`, or
+/// once `cancelled` is set. The items printed by CBMC until then are still returned, together
+/// with whether the process was killed.
+pub fn process_cbmc_output_until(
+    mut process: Child,
+    deadline: Option<Instant>,
+    cancelled: Option<&AtomicBool>,
+    mut eager_filter: impl FnMut(ParserItem) -> Option<ParserItem> + Send,
+) -> Result<(VerificationOutput, bool)> {
+    if deadline.is_none() && cancelled.is_none() {
+        return Ok((process_cbmc_output(process, eager_filter)?, false));
+    }
+    let pid = process.id();
+    let mut peak_memory_kb = None;
+    let mut stdout = process.stdout.take().unwrap();
+    let (sender, receiver) = mpsc::channel();
+    let (processed_items, timed_out) = thread::scope(|scope| {
+        let peak_memory_kb = &mut peak_memory_kb;
+        scope.spawn(move || {
+            let items = parse_cbmc_output(&mut stdout, |item| {
+                *peak_memory_kb = (*peak_memory_kb).max(process_peak_memory_kb(pid));
+                eager_filter(item)
+            });
+            let _ = sender.send(items);
+        });
+        loop {
+            match receiver.recv_timeout(wait_time(deadline)) {
+                Ok(items) => break (items, false),
+                Err(RecvTimeoutError::Timeout) => {
+                    if !is_stopped(deadline, cancelled) {
+                        continue;
+                    }
+                    // Killing the process closes its output, which lets the parser finish.
+                    let _ = process.kill();
+                    break (receiver.recv().unwrap_or_default(), true);
+                }
+                Err(RecvTimeoutError::Disconnected) => break (vec![], false),
+            }
+        }
+    });
+    let status = process.wait()?;
+    let output = VerificationOutput {
+        process_status: process_status(status),
+        processed_items,
+        peak_memory_kb,
+        portfolio_solver: None,
+    };
+    Ok((output, timed_out))
+}
+
+/// How often a running process checks whether the verification was cancelled.
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long to wait for the output of a process before checking whether it must be stopped with
+/// [is_stopped].
+pub fn wait_time(deadline: Option<Instant>) -> Duration {
+    match deadline {
+        Some(deadline) => {
+            deadline.saturating_duration_since(Instant::now()).min(CANCELLATION_POLL_INTERVAL)
+        }
+        None => CANCELLATION_POLL_INTERVAL,
+    }
+}
+
+/// Whether a process must be stopped because `deadline` was reached or the verification was
+/// cancelled.
+pub fn is_stopped(deadline: Option<Instant>, cancelled: Option<&AtomicBool>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+        || cancelled.is_some_and(|cancelled| cancelled.load(Ordering::Relaxed))
+}
+
+/// The peak resident set size of a running process in kB. We sample it whenever the process
+/// prints a message, since it can't be read anymore once the process exited. This is only
+/// supported on Linux.
+fn process_peak_memory_kb(pid: u32) -> Option<u64> {
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Parse the items printed by CBMC to `stdout` until it gets closed.
+pub fn parse_cbmc_output(
+    stdout: &mut ChildStdout,
+    eager_filter: impl FnMut(ParserItem) -> Option<ParserItem>,
+) -> Vec<ParserItem> {
     let mut stdout_reader = BufReader::new(stdout);
     let parser = Parser::new(&mut stdout_reader);
     // This should run until stdout is closed (which should mean the process exited)
-    let processed_items: Vec<_> = parser.filter_map(eager_filter).collect();
-    // This will get us the process's exit code
-    let status = process.wait()?;
+    parser.filter_map(eager_filter).collect()
+}
 
-    let process_status = match (status.code(), status.signal()) {
+/// Convert the exit status of a CBMC process into an exit code.
+pub fn process_status(status: ExitStatus) -> i32 {
+    match (status.code(), status.signal()) {
         // normal unix exit codes (cbmc uses currently 0-10)
         // https://github.com/diffblue/cbmc/blob/develop/src/util/exit_codes.h
         (Some(x), _) => x,
@@ -540,32 +760,98 @@ pub fn process_cbmc_output(
         (_, Some(x)) => 128 + x,
         // I think this shouldn't happen? either exit or signal, right?
         (None, None) => unreachable!("Process exited with neither status code nor signal?"),
-    };
-
-    Ok(VerificationOutput { process_status, processed_items })
+    }
 }
 
 /// Takes (by ownership) a vector of messages, and returns that vector with the `Result`
 /// (if any) removed from it and returned separately.
-pub fn extract_results(mut items: Vec<ParserItem>) -> (Vec<ParserItem>, Option<Vec<Property>>) {
-    let result_idx = items.iter().position(|x| matches!(x, ParserItem::Result { .. }));
-    if let Some(result_idx) = result_idx {
-        let result = items.remove(result_idx);
-        if let ParserItem::Result { result } = result {
-            (items, Some(result))
-        } else {
-            unreachable!() // We filtered for this to be true
+///
+/// With `--incremental-loop`, CBMC prints a `Result` after each unwinding of the loop. Each
+/// property then has the status of the last `Result` that includes it.
+pub fn extract_results(items: Vec<ParserItem>) -> (Vec<ParserItem>, Option<Vec<Property>>) {
+    let mut results: Option<Vec<Property>> = None;
+    let mut other_items = vec![];
+    for item in items {
+        let ParserItem::Result { result } = item else {
+            other_items.push(item);
+            continue;
+        };
+        let Some(properties) = &mut results else {
+            results = Some(result);
+            continue;
+        };
+        for property in result {
+            match properties.iter_mut().find(|prop| prop.property_id == property.property_id) {
+                Some(previous) => *previous = property,
+                None => properties.push(property),
+            }
         }
-    } else {
-        // No results
-        (items, None)
     }
+    (other_items, results)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn message(text: &str) -> ParserItem {
+        ParserItem::Message {
+            message_text: text.to_string(),
+            message_type: "STATUS-MESSAGE".to_string(),
+        }
+    }
+
+    #[test]
+    fn check_property_serialization() {
+        let data = r#"{
+            "description": "assertion failed: x < 10",
+            "property": "check.assertion.1",
+            "sourceLocation": { "file": "src/lib.rs", "line": "4" },
+            "status": "FAILURE",
+            "trace": [{ "stepType": "assignment", "lhs": "x", "value": { "data": "12" } }]
+        }"#;
+        let property: Property = serde_json::from_str(data).unwrap();
+        let serialized = serde_json::to_string(&property).unwrap();
+        let deserialized: Property = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.property_name(), "check.assertion.1");
+        assert_eq!(deserialized.status, CheckStatus::Failure);
+        assert_eq!(deserialized.source_location.to_string(), property.source_location.to_string());
+        assert_eq!(deserialized.trace.unwrap()[0].lhs.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn check_phase_timing() {
+        assert_eq!(
+            message("Runtime Symex: 0.0123s").phase_timing(),
+            Some(PhaseTiming { phase: "Symex".to_string(), runtime_secs: 0.0123 })
+        );
+        assert_eq!(
+            message("Runtime decision procedure: 2.5s").phase_timing(),
+            Some(PhaseTiming { phase: "decision procedure".to_string(), runtime_secs: 2.5 })
+        );
+        assert_eq!(message("Runtime Symex: unknown").phase_timing(), None);
+        assert_eq!(message("Solving with CaDiCaL sc2021").phase_timing(), None);
+    }
+
+    #[test]
+    fn check_progress_phase() {
+        assert_eq!(
+            message("Starting Bounded Model Checking").progress_phase().as_deref(),
+            Some("symbolic execution")
+        );
+        assert_eq!(
+            message("Generated 12 VCC(s), 3 remaining after simplification")
+                .progress_phase()
+                .as_deref(),
+            Some("Generated 12 VCC(s), 3 remaining after simplification")
+        );
+        assert_eq!(
+            message("Solving with CaDiCaL sc2021").progress_phase().as_deref(),
+            Some("solving with cadical sc2021")
+        );
+        assert_eq!(message("Runtime Symex: 0.0123s").progress_phase(), None);
+    }
+
     #[test]
     fn check_property_id_deserialization_general() {
         let prop_id_string = "\"alloc::raw_vec::RawVec::<u8>::allocate_in.sanity_check.1\"";
@@ -761,4 +1047,26 @@ fn check_result_deserialization_works() {
         assert!(parser_item.is_ok());
         assert!(result_struct.is_ok());
     }
+
+    #[test]
+    fn check_incremental_results() {
+        let result = |status: &str| -> ParserItem {
+            serde_json::from_value(serde_json::json!({ "result": [{
+                "description": "assertion failed: i < 3",
+                "property": "check.assertion.1",
+                "sourceLocation": { "file": "src/lib.rs", "line": "6" },
+                "status": status,
+            }] }))
+            .unwrap()
+        };
+        let items = vec![result("UNKNOWN"), message("Unwinding loop check.0"), result("FAILURE")];
+        let (items, results) = extract_results(items);
+        assert_eq!(items.len(), 1);
+        let results = results.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].status, CheckStatus::Failure);
+
+        let (_, results) = extract_results(vec![result("UNKNOWN")]);
+        assert_eq!(results.unwrap()[0].status, CheckStatus::Undetermined);
+    }
 }
@@ -3,12 +3,15 @@
 
 use crate::args::OutputFormat;
 use crate::call_cbmc::{FailedProperties, VerificationStatus};
-use crate::cbmc_output_parser::{CheckStatus, ParserItem, Property, TraceItem};
-use console::style;
+use crate::cbmc_output_parser::{CheckStatus, ParserItem, Property, SourceLocation, TraceItem};
+use crate::json_report::{trace_excerpt, TraceExcerpt};
+use console::{style, Term};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use rustc_demangle::demangle;
 use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::time::{Duration, Instant};
 use strum_macros::{AsRefStr, Display};
 
 type CbmcAltDescriptions = HashMap<&'static str, Vec<(&'static str, Option<&'static str>)>>;
@@ -159,10 +162,54 @@ enum CoverageStatus {
     None,
 }
 
+#[derive(PartialEq, Eq, PartialOrd, Ord, AsRefStr, Clone, Copy, Display)]
+#[strum(serialize_all = "UPPERCASE")]
+// The status of a line reported by Kani when combining its coverage with the results of the
+// properties on that line. Variants are ordered by priority, from lowest to highest.
+enum PropertyCoverageStatus {
+    /// The line is covered and all of its properties were proven.
+    Proven,
+    /// The line is covered but at least one of its properties is unreachable.
+    #[strum(serialize = "PROPERTY UNREACHABLE")]
+    PropertyUnreachable,
+    /// The line is covered but at least one of its properties is undetermined.
+    Undetermined,
+    /// The line is covered and at least one of its properties fails.
+    Failed,
+    /// The line is never reached by the harness.
+    #[strum(serialize = "NEVER REACHED")]
+    NeverReached,
+}
+
+impl PropertyCoverageStatus {
+    fn from_check_status(status: CheckStatus) -> Option<Self> {
+        match status {
+            CheckStatus::Success => Some(Self::Proven),
+            CheckStatus::Unreachable => Some(Self::PropertyUnreachable),
+            CheckStatus::Undetermined => Some(Self::Undetermined),
+            CheckStatus::Failure => Some(Self::Failed),
+            CheckStatus::Covered
+            | CheckStatus::Uncovered
+            | CheckStatus::Satisfied
+            | CheckStatus::Unsatisfiable => None,
+        }
+    }
+}
+
 const UNSUPPORTED_CONSTRUCT_DESC: &str = "is not currently supported by Kani";
 const UNWINDING_ASSERT_DESC: &str = "unwinding assertion loop";
 const UNWINDING_ASSERT_REC_DESC: &str = "recursion unwinding assertion";
 const DEFAULT_ASSERTION: &str = "assertion";
+const DATA_RACE_DESC: &str = " data race on ";
+/// The class of the checks added by `--check data-races`, which CBMC reports as assertions.
+const DATA_RACE_CLASS: &str = "data_race";
+const STACK_DEPTH_DESC: &str = "Stack depth exceeds ";
+/// The class of the checks added by `--max-call-depth`, which CBMC reports as assertions.
+const STACK_DEPTH_CLASS: &str = "stack_depth";
+/// The class of the checks added by `--check leaks`.
+const MEMORY_LEAK_CLASS: &str = "memory-leak";
+/// The variable that CBMC uses to track a nondeterministic allocation that may leak.
+const MEMORY_LEAK_VAR: &str = "__CPROVER_memory_leak";
 
 impl ParserItem {
     /// Determines if an item must be skipped or not.
@@ -171,6 +218,41 @@ fn must_be_skipped(&self) -> bool {
     }
 }
 
+/// A live progress line for a harness, printed to `stderr` and updated with the elapsed time
+/// whenever CBMC starts a new phase. The line is cleared once it is dropped.
+pub struct ProgressLine {
+    term: Term,
+    harness: String,
+    start_time: Instant,
+}
+
+impl ProgressLine {
+    /// Create a progress line, unless `stderr` isn't a terminal.
+    pub fn new(harness: &str) -> Option<ProgressLine> {
+        let term = Term::stderr();
+        term.is_term().then(|| ProgressLine {
+            term,
+            harness: harness.to_string(),
+            start_time: Instant::now(),
+        })
+    }
+
+    pub fn update(&self, item: &ParserItem) {
+        if let Some(phase) = item.progress_phase() {
+            let elapsed = self.start_time.elapsed().as_secs_f32();
+            // Failing to print progress shouldn't fail verification.
+            let _ = self.term.clear_line();
+            let _ = self.term.write_str(&format!("{}: {phase} ({elapsed:.1}s)", self.harness));
+        }
+    }
+}
+
+impl Drop for ProgressLine {
+    fn drop(&mut self) {
+        let _ = self.term.clear_line();
+    }
+}
+
 /// This is called "live" as CBMC output is streamed in, and we
 /// filter and transform it into the format we expect.
 ///
@@ -201,6 +283,14 @@ pub fn kani_cbmc_output_filter(
     Some(processed_item)
 }
 
+/// Print the items that [kani_cbmc_output_filter] would have printed, for items that were
+/// filtered quietly.
+pub fn print_cbmc_messages(items: &[ParserItem], output_format: &OutputFormat) {
+    for formatted_item in items.iter().filter_map(|item| format_item(item, output_format)) {
+        println!("{formatted_item}");
+    }
+}
+
 /// Processes a `ParserItem`. In general, all items are returned as they are,
 /// except for:
 ///  * Error messages, which may be edited.
@@ -223,7 +313,9 @@ fn format_item(item: &ParserItem, output_format: &OutputFormat) -> Option<String
     match output_format {
         OutputFormat::Old => todo!(),
         OutputFormat::Regular => format_item_regular(item),
-        OutputFormat::Terse => format_item_terse(item),
+        OutputFormat::Terse | OutputFormat::Github => format_item_terse(item),
+        // The results are only printed at the end of the run.
+        OutputFormat::Json | OutputFormat::Junit => None,
     }
 }
 
@@ -393,22 +485,8 @@ pub fn format_result(
         result_str.push_str(&failure_message);
     }
 
-    let verification_result = if status == VerificationStatus::Success {
-        style("SUCCESSFUL").green()
-    } else {
-        style("FAILED").red()
-    };
-    let should_panic_info = if should_panic {
-        match failed_properties {
-            FailedProperties::None => " (encountered no panics, but at least one was expected)",
-            FailedProperties::PanicsOnly => " (encountered one or more panics as expected)",
-            FailedProperties::Other => {
-                " (encountered failures other than panics, which were unexpected)"
-            }
-        }
-    } else {
-        ""
-    };
+    let verification_result = format_status(status);
+    let should_panic_info = should_panic_info(should_panic, failed_properties);
     let overall_result = format!("\nVERIFICATION:- {verification_result}{should_panic_info}\n");
     result_str.push_str(&overall_result);
 
@@ -431,6 +509,80 @@ pub fn format_result(
     result_str
 }
 
+/// Formats the result of a harness for the terse output format: a single line if the
+/// verification succeeded, and otherwise the summary of the failed checks with the last
+/// steps of their traces selected by `excerpt`, if any.
+pub fn format_result_terse(
+    name: &str,
+    properties: &Vec<Property>,
+    status: VerificationStatus,
+    should_panic: bool,
+    failed_properties: FailedProperties,
+    runtime: Duration,
+    excerpt: Option<TraceExcerpt>,
+) -> String {
+    let mut result_str = format!(
+        "Harness {name}: {}{} ({:.2}s)",
+        format_status(status),
+        should_panic_info(should_panic, failed_properties),
+        runtime.as_secs_f32()
+    );
+    if status == VerificationStatus::Success {
+        return result_str;
+    }
+    result_str.push('\n');
+    result_str.push_str(&format_result(properties, status, should_panic, failed_properties, false));
+    if let Some(excerpt) = excerpt {
+        result_str.push_str(&format_trace_excerpts(properties, excerpt));
+    }
+    result_str
+}
+
+/// Formats the last steps of the traces of the failed checks selected by `excerpt`, which is
+/// shared by the output formats that print the traces.
+pub fn format_trace_excerpts(properties: &[Property], excerpt: TraceExcerpt) -> String {
+    let mut result_str = String::new();
+    for prop in properties.iter().filter(|prop| prop.status == CheckStatus::Failure) {
+        let steps = trace_excerpt(prop, excerpt);
+        if steps.is_empty() {
+            continue;
+        }
+        writeln!(
+            result_str,
+            "Trace of {} (last {} assignments):",
+            prop.property_name(),
+            steps.len()
+        )
+        .unwrap();
+        for step in steps {
+            writeln!(result_str, "  {step}").unwrap();
+        }
+    }
+    result_str
+}
+
+fn format_status(status: VerificationStatus) -> console::StyledObject<&'static str> {
+    if status == VerificationStatus::Success {
+        style("SUCCESSFUL").green()
+    } else {
+        style("FAILED").red()
+    }
+}
+
+fn should_panic_info(should_panic: bool, failed_properties: FailedProperties) -> &'static str {
+    if should_panic {
+        match failed_properties {
+            FailedProperties::None => " (encountered no panics, but at least one was expected)",
+            FailedProperties::PanicsOnly => " (encountered one or more panics as expected)",
+            FailedProperties::Other => {
+                " (encountered failures other than panics, which were unexpected)"
+            }
+        }
+    } else {
+        ""
+    }
+}
+
 /// Separate checks into coverage and non-coverage based on property class and format them separately for --coverage. We report both verification and processed coverage
 /// results
 pub fn format_coverage(
@@ -446,7 +598,10 @@ pub fn format_coverage(
     let verification_output =
         format_result(&non_coverage_checks, status, should_panic, failed_properties, show_checks);
     let coverage_output = format_result_coverage(&coverage_checks);
-    let result = format!("{}\n{}", verification_output, coverage_output);
+    let property_coverage_output =
+        format_result_property_coverage(&coverage_checks, &non_coverage_checks);
+    let result =
+        format!("{}\n{}{}", verification_output, coverage_output, property_coverage_output);
 
     result
 }
@@ -464,6 +619,25 @@ fn format_result_coverage(properties: &[Property]) -> String {
     let mut formatted_output = String::new();
     formatted_output.push_str("\nCoverage Results:\n");
 
+    let coverage_results = line_coverage(properties);
+
+    // Create formatted string that is returned to the user as output
+    for (file, checks) in coverage_results.iter() {
+        for (line_number, coverage_status) in checks {
+            formatted_output.push_str(&format!("{}, {}, {}\n", file, line_number, coverage_status));
+        }
+        formatted_output.push('\n');
+    }
+
+    formatted_output
+}
+
+/// Aggregate the `code_coverage` checks per file and line (see [`format_result_coverage`]).
+///
+/// Checks in gap regions, i.e., lines without executable code, are reported like any other check,
+/// so an unreachable closing brace is still `NONE`. They only affect the aggregated line coverage
+/// (see [`crate::coverage`]).
+fn line_coverage(properties: &[Property]) -> BTreeMap<String, BTreeMap<usize, CoverageStatus>> {
     let mut coverage_results: BTreeMap<String, BTreeMap<usize, CoverageStatus>> =
         BTreeMap::default();
     for prop in properties {
@@ -485,15 +659,53 @@ fn format_result_coverage(properties: &[Property]) -> String {
             })
             .or_insert(check_status);
     }
+    coverage_results
+}
 
-    // Create formatted string that is returned to the user as output
-    for (file, checks) in coverage_results.iter() {
-        for (line_number, coverage_status) in checks {
-            formatted_output.push_str(&format!("{}, {}, {}\n", file, line_number, coverage_status));
+/// Combine the coverage of each line with the results of the properties on that line.
+/// For every line that contains at least one property, this reports:
+///  - `NEVER REACHED` if the line is not covered, i.e., the code is dead for this harness.
+///  - `FAILED` if the line is covered and at least one of its properties fails.
+///  - `UNDETERMINED` if the line is covered and at least one of its properties is undetermined.
+///  - `PROPERTY UNREACHABLE` if the line is covered but at least one of its properties is
+///    unreachable, which usually indicates that the harness doesn't exercise that property.
+///  - `PROVEN` if the line is covered and all of its properties hold.
+///
+/// `cover` properties are ignored, since they don't prove anything.
+fn format_result_property_coverage(
+    coverage_checks: &[Property],
+    non_coverage_checks: &[Property],
+) -> String {
+    let coverage_results = line_coverage(coverage_checks);
+    let mut property_results: BTreeMap<String, BTreeMap<usize, PropertyCoverageStatus>> =
+        BTreeMap::default();
+    for prop in non_coverage_checks {
+        let src = &prop.source_location;
+        let (Some(file), Some(line)) = (&src.file, &src.line) else { continue };
+        let Some(status) = PropertyCoverageStatus::from_check_status(prop.status) else {
+            continue;
+        };
+        let Ok(line) = line.parse::<usize>() else { continue };
+        let status = match coverage_results.get(file).and_then(|lines| lines.get(&line)) {
+            Some(CoverageStatus::None) => PropertyCoverageStatus::NeverReached,
+            _ => status,
+        };
+        property_results
+            .entry(file.clone())
+            .or_default()
+            .entry(line)
+            .and_modify(|line_status| *line_status = (*line_status).max(status))
+            .or_insert(status);
+    }
+
+    let mut formatted_output = String::new();
+    formatted_output.push_str("\nProperty Coverage Results:\n");
+    for (file, lines) in property_results.iter() {
+        for (line_number, status) in lines {
+            formatted_output.push_str(&format!("{}, {}, {}\n", file, line_number, status));
         }
         formatted_output.push('\n');
     }
-
     formatted_output
 }
 
@@ -545,6 +757,15 @@ fn postprocess_error_message(message: ParserItem) -> ParserItem {
     }
 }
 
+/// Whether CBMC failed because the harness has more objects than the number of object bits
+/// allows.
+pub fn has_object_bits_error(items: &[ParserItem]) -> bool {
+    items.iter().any(|item| {
+        matches!(item, ParserItem::Message { message_text, message_type }
+            if message_type == "ERROR" && message_text.contains("--object-bits"))
+    })
+}
+
 /// Postprocess verification results to check for certain cases (e.g. a reachable unsupported construct or a failed
 /// unwinding assertion), and update the results of impacted checks accordingly.
 ///
@@ -562,6 +783,7 @@ fn postprocess_error_message(message: ParserItem) -> ParserItem {
 ///     Additionally, print a message at the end of the output that indicates if any
 ///     of the special cases above was hit.
 pub fn postprocess_result(properties: Vec<Property>, extra_ptr_checks: bool) -> Vec<Property> {
+    let properties = classify_goto_instrument_checks(properties);
     // First, determine if there are reachable unsupported constructs or unwinding assertions
     let has_reachable_unsupported_constructs =
         has_check_failure(&properties, UNSUPPORTED_CONSTRUCT_DESC);
@@ -593,7 +815,78 @@ pub fn postprocess_result(properties: Vec<Property>, extra_ptr_checks: bool) ->
     let updated_properties =
         update_properties_with_reach_status(properties_filtered, has_fundamental_failures);
     let results_after_code_coverage = update_results_of_code_covererage_checks(updated_properties);
-    update_results_of_cover_checks(results_after_code_coverage)
+    let results_after_cover_checks = update_results_of_cover_checks(results_after_code_coverage);
+    annotate_leak_sites(results_after_cover_checks)
+}
+
+/// Report the failures of the checks added by `--check leaks` at the location where the leaked
+/// memory was allocated, instead of the end of the harness.
+fn annotate_leak_sites(mut properties: Vec<Property>) -> Vec<Property> {
+    for prop in &mut properties {
+        if prop.status == CheckStatus::Failure
+            && prop.property_class() == MEMORY_LEAK_CLASS
+            && let Some(site) = prop.trace.as_deref().and_then(find_leak_site)
+        {
+            prop.source_location = site;
+        }
+    }
+    properties
+}
+
+/// Find the allocation site of the memory leaked in a trace.
+///
+/// CBMC tracks a nondeterministic allocation in `__CPROVER_memory_leak`, which is the one leaked
+/// in a failing trace. We return the closest user code location before the allocation that was
+/// tracked, since the allocation itself happens in the standard library or in the C library.
+fn find_leak_site(trace: &[TraceItem]) -> Option<SourceLocation> {
+    let mut tracked_value = None;
+    let mut alloc_step = None;
+    for (idx, step) in trace.iter().enumerate() {
+        if step.step_type == "assignment" && step.lhs.as_deref() == Some(MEMORY_LEAK_VAR) {
+            let value = step
+                .value
+                .as_ref()
+                .and_then(|value| value.data.as_ref())
+                .map(|data| data.to_string());
+            if value != tracked_value && value.as_deref() != Some("NULL") {
+                alloc_step = Some(idx);
+            }
+            tracked_value = value;
+        }
+    }
+    trace[..alloc_step?]
+        .iter()
+        .rev()
+        .filter_map(|step| step.source_location.as_ref())
+        .find(|loc| loc.file.as_deref().is_some_and(is_user_file))
+        .cloned()
+}
+
+/// Whether `file` is a Rust source file outside of the standard library.
+fn is_user_file(file: &str) -> bool {
+    file.ends_with(".rs") && !is_std_file(file)
+}
+
+/// Whether `file` is a source file of the standard library, either from the toolchain or from
+/// the remapped paths of its build.
+pub fn is_std_file(file: &str) -> bool {
+    file.starts_with("/rustc/") || file.contains("/rustlib/src/rust/")
+}
+
+/// Move the data race and stack depth checks added by goto-instrument into their own property
+/// class. The descriptions of data race checks look like `W/W data race on x` or
+/// `R/W data race on x`, and the ones of stack depth checks look like `Stack depth exceeds 10`.
+fn classify_goto_instrument_checks(mut properties: Vec<Property>) -> Vec<Property> {
+    for prop in &mut properties {
+        if (prop.description.starts_with("W/W") || prop.description.starts_with("R/W"))
+            && prop.description.contains(DATA_RACE_DESC)
+        {
+            prop.property_id.class = DATA_RACE_CLASS.to_string();
+        } else if prop.description.starts_with(STACK_DEPTH_DESC) {
+            prop.property_id.class = STACK_DEPTH_CLASS.to_string();
+        }
+    }
+    properties
 }
 
 /// Determines if there is property with status `FAILURE` and the given description
@@ -2,9 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 //! Implements the logic related to the playback subcommand
 //! This can be achieved with <kani|cargo kani> playback --test <test_name>
+//!
+//! This also implements `cargo kani test`, which runs the proof harnesses as unit tests with
+//! trivial values, along with the concrete playback testcases.
 
+use crate::args::cargo::CargoTestArgs;
 use crate::args::common::Verbosity;
-use crate::args::playback_args::{CargoPlaybackArgs, KaniPlaybackArgs, MessageFormat};
+use crate::args::playback_args::{
+    CargoHarnessTestArgs, CargoPlaybackArgs, KaniPlaybackArgs, MessageFormat, PlaybackArgs,
+};
 use crate::call_cargo::cargo_config_args;
 use crate::call_single_file::base_rustc_flags;
 use crate::session::{lib_playback_folder, setup_cargo_command, InstallType};
@@ -17,7 +23,13 @@
 use tracing::debug;
 
 pub fn playback_cargo(args: CargoPlaybackArgs) -> Result<()> {
-    cargo_test(args)
+    cargo_test(&args.playback, &args.cargo, false)
+}
+
+/// Run the proof harnesses as unit tests. Under `cfg(kani_playback)`, every harness gets a unit
+/// test that calls it with trivial values.
+pub fn test_cargo(args: CargoHarnessTestArgs) -> Result<()> {
+    cargo_test(&args.playback, &args.cargo, true)
 }
 
 pub fn playback_standalone(args: KaniPlaybackArgs) -> Result<()> {
@@ -91,35 +103,40 @@ fn build_test(install: &InstallType, args: &KaniPlaybackArgs) -> Result<PathBuf>
     Ok(PathBuf::from(TEST_BIN_NAME).canonicalize()?)
 }
 
-/// Invokes cargo test using Kani compiler and the provided arguments.
-fn cargo_test(args: CargoPlaybackArgs) -> Result<()> {
+/// Invokes cargo test using Kani compiler and the provided arguments. With `harness_tests`, the
+/// unit tests of the proof harnesses are also built.
+fn cargo_test(playback: &PlaybackArgs, cargo: &CargoTestArgs, harness_tests: bool) -> Result<()> {
     let install = InstallType::new()?;
     let mut cmd = setup_cargo_command()?;
 
-    let rustc_args = base_rustc_flags(lib_playback_folder()?);
+    let mut rustc_args = base_rustc_flags(lib_playback_folder()?);
+    rustc_args.push("--check-cfg=cfg(kani, kani_playback)".into());
+    if harness_tests {
+        rustc_args.push("--cfg=kani_playback".into());
+    }
     let mut cargo_args: Vec<OsString> = vec!["test".into()];
 
-    if args.playback.common_opts.verbose() {
+    if playback.common_opts.verbose() {
         cargo_args.push("-vv".into());
-    } else if args.playback.common_opts.quiet {
+    } else if playback.common_opts.quiet {
         cargo_args.push("--quiet".into())
     }
 
-    if args.playback.message_format == MessageFormat::Json {
+    if playback.message_format == MessageFormat::Json {
         cargo_args.push("--message-format=json".into());
     }
 
-    if args.playback.only_codegen {
+    if playback.only_codegen {
         cargo_args.push("--no-run".into());
     }
 
-    cargo_args.append(&mut args.cargo.to_cargo_args());
+    cargo_args.append(&mut cargo.to_cargo_args());
     cargo_args.append(&mut cargo_config_args());
 
     // These have to be the last arguments to cargo test.
-    if !args.playback.test_args.is_empty() {
+    if !playback.test_args.is_empty() {
         cargo_args.push("--".into());
-        cargo_args.extend(args.playback.test_args.iter().map(|arg| arg.into()));
+        cargo_args.extend(playback.test_args.iter().map(|arg| arg.into()));
     }
 
     // Arguments that will only be passed to the target package.
@@ -130,6 +147,6 @@ fn cargo_test(args: CargoPlaybackArgs) -> Result<()> {
         .env("CARGO_ENCODED_RUSTFLAGS", rustc_args.join(&OsString::from("\x1f")))
         .env("CARGO_TERM_PROGRESS_WHEN", "never");
 
-    session::run_terminal(&args.playback.common_opts, cmd)?;
+    session::run_terminal(&playback.common_opts, cmd)?;
     Ok(())
 }
@@ -3,17 +3,33 @@
 
 //! Module for parsing concrete values from CBMC output traces,
 //! generating concrete playback unit tests, and adding them to the user's source code.
+//!
+//! With `--concrete-playback=inplace`, the tests of the harnesses of a module are written to a
+//! dedicated playback file, `kani_playback/<file>-<module>.rs` next to the source file of the
+//! harnesses, which is included by a `kani_playback` module added to the module of the harnesses:
+//!
+//! ```ignore
+//! #[cfg(test)]
+//! mod kani_playback {
+//!     use super::*;
+//!     include!("kani_playback/lib-verify.rs");
+//! }
+//! ```
+//!
+//! The name of a test is derived from the harness and the concrete values, so a test that already
+//! exists isn't added again. A test that plays back a check for which the file already has a test,
+//! e.g., because the check fails with other values after the code changed, replaces the previous
+//! test in place.
 
 use crate::args::ConcretePlaybackMode;
 use crate::call_cbmc::VerificationResult;
+use crate::cbmc_output_parser::CheckStatus;
 use crate::session::KaniSession;
 use anyhow::{Context, Result};
-use concrete_vals_extractor::{extract_harness_values, ConcreteVal};
+use concrete_vals_extractor::{extract_harness_values, is_selected_cover, ConcreteVal};
 use kani_metadata::HarnessMetadata;
-use std::collections::hash_map::DefaultHasher;
 use std::ffi::OsString;
 use std::fs::{read_to_string, File};
-use std::hash::{Hash, Hasher};
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 use std::process::Command;
@@ -32,20 +48,37 @@ pub fn gen_and_add_concrete_playback(
         };
 
         if let Ok(result_items) = &verification_result.results {
-            let harness_values: Vec<Vec<ConcreteVal>> = extract_harness_values(result_items);
-
-            if harness_values.is_empty() {
+            let selected_covers = &self.args.playback_covers;
+            let harness_values: Vec<(String, Vec<ConcreteVal>)> =
+                extract_harness_values(result_items, selected_covers);
+
+            // The other harnesses may have the selected covers, so only the selected covers of
+            // this harness are reported.
+            for cover in result_items.iter().filter(|prop| {
+                is_selected_cover(prop, selected_covers) && prop.status != CheckStatus::Satisfied
+            }) {
                 println!(
-                    "WARNING: Kani could not produce a concrete playback for `{}` because there \
-                    were no failing panic checks or satisfiable cover statements.",
+                    "WARNING: Kani could not produce a concrete playback for the cover `{}` of \
+                    `{}` because it isn't satisfiable.",
+                    cover.property_name(),
                     harness.pretty_name
-                )
+                );
+            }
+
+            if harness_values.is_empty() {
+                if selected_covers.is_empty() {
+                    println!(
+                        "WARNING: Kani could not produce a concrete playback for `{}` because \
+                        there were no failing panic checks or satisfiable cover statements.",
+                        harness.pretty_name
+                    )
+                }
             } else {
                 let mut unit_tests: Vec<UnitTest> = harness_values
                     .iter()
-                    .map(|concrete_vals| {
+                    .map(|(check, concrete_vals)| {
                         let pretty_name = harness.get_harness_name_unqualified();
-                        format_unit_test(&pretty_name, &concrete_vals)
+                        format_unit_test(&pretty_name, check, &concrete_vals)
                     })
                     .collect();
                 unit_tests.dedup_by(|a, b| a.name == b.name);
@@ -69,7 +102,7 @@ pub fn gen_and_add_concrete_playback(
                     ConcretePlaybackMode::InPlace => {
                         if !self.args.common_args.quiet && !unit_tests.is_empty() {
                             println!(
-                                "INFO: Now modifying the source code to include the concrete playback unit test:{}.",
+                                "INFO: Now adding the concrete playback unit test to the playback file of the harness:{}.",
                                 unit_tests
                                     .iter()
                                     .map(|generated_unit_test| format!(
@@ -80,12 +113,7 @@ pub fn gen_and_add_concrete_playback(
                                     .join("")
                             );
                         }
-                        self.modify_src_code(
-                            &harness.original_file,
-                            harness.original_end_line,
-                            unit_tests,
-                        )
-                        .expect(&format!(
+                        self.modify_src_code(harness, unit_tests).expect(&format!(
                             "Failed to modify source code for the file `{}`",
                             &harness.original_file
                         ));
@@ -97,92 +125,99 @@ pub fn gen_and_add_concrete_playback(
         Ok(())
     }
 
-    /// Add the unit test to the user's source code, format it, and short circuit if code already present.
-    fn modify_src_code(
-        &self,
-        src_path: &str,
-        proof_harness_end_line: usize,
-        unit_tests: Vec<UnitTest>,
-    ) -> Result<()> {
-        // compute range to run rustfmt on.
-        let concrete_playback_num_lines: usize =
-            unit_tests.iter().map(|unit_test| unit_test.code.len()).sum();
-
-        let is_new_injection =
-            self.add_tests_inplace(src_path, proof_harness_end_line, unit_tests)?;
-
-        if is_new_injection {
-            let unit_test_start_line = proof_harness_end_line + 1;
-            let unit_test_end_line = unit_test_start_line + concrete_playback_num_lines - 1;
-            let src_path = Path::new(src_path);
-            let (path, file_name) = extract_parent_dir_and_src_file(src_path)?;
-            let file_line_ranges = vec![FileLineRange {
-                file: file_name,
-                line_range: Some((unit_test_start_line, unit_test_end_line)),
-            }];
-            self.run_rustfmt(&file_line_ranges, Some(&path))
-                .unwrap_or_else(|err| println!("WARNING: {}", err));
+    /// Add the unit tests to the playback file of the harness, and include this file in the module
+    /// of the harness if it isn't already. The tests that are already present are skipped.
+    fn modify_src_code(&self, harness: &HarnessMetadata, unit_tests: Vec<UnitTest>) -> Result<()> {
+        let src_path = Path::new(&harness.original_file);
+        let (src_dir, src_file) = extract_parent_dir_and_src_file(src_path)?;
+        let playback_file = playback_file_name(harness);
+        let playback_path = Path::new(&src_dir).join(&playback_file);
+        let source = read_to_string(src_path)?;
+        let mut playback = PlaybackFile::read(&playback_path)?;
+
+        let mut is_new_injection = false;
+        for unit_test in unit_tests {
+            let found_in = if source.contains(&unit_test.name) {
+                Some(&harness.original_file)
+            } else if playback.tests.iter().any(|test| test.name == unit_test.name) {
+                Some(&playback_file)
+            } else {
+                None
+            };
+            if let Some(file) = found_in {
+                if !self.args.common_args.quiet {
+                    println!(
+                        "Concrete playback unit test `{}` already found in `{file}`, so skipping it.",
+                        unit_test.name,
+                    );
+                }
+            } else {
+                playback.insert(unit_test);
+                is_new_injection = true;
+            }
         }
+        if !is_new_injection {
+            return Ok(());
+        }
+        playback.write(&playback_path, harness)?;
+
+        // Format the whole playback file, and the inclusion of the playback file if it's new.
+        let mut file_line_ranges = vec![FileLineRange {
+            file: playback_file.clone(),
+            line_range: Some((1, read_to_string(&playback_path)?.lines().count())),
+        }];
+        if let Some(line_range) =
+            self.include_playback_file(src_path, harness.original_end_line, &playback_file)?
+        {
+            file_line_ranges.push(FileLineRange { file: src_file, line_range: Some(line_range) });
+        }
+        self.run_rustfmt(&file_line_ranges, Some(&src_dir))
+            .unwrap_or_else(|err| println!("WARNING: {}", err));
 
         Ok(())
     }
 
-    /// Writes the new source code to a user's source file using a tempfile as the means.
-    /// Returns whether new unit test was injected.
-    fn add_tests_inplace(
+    /// Add a `kani_playback` module that includes the playback file after the harness, unless the
+    /// source file already includes it.
+    /// Returns the range of the lines that were added, if any.
+    fn include_playback_file(
         &self,
-        source_path: &str,
+        source_path: &Path,
         proof_harness_end_line: usize,
-        mut unit_tests: Vec<UnitTest>,
-    ) -> Result<bool> {
-        // Read from source
-        let source_file = File::open(source_path).unwrap();
-        let source_reader = BufReader::new(source_file);
-        let source_string = read_to_string(source_path).unwrap();
-
-        // filter out existing harnesses.
-        unit_tests.retain(|unit_test| {
-            if source_string.contains(&unit_test.name) {
-                if !self.args.common_args.quiet {
-                    println!(
-                        "Concrete playback unit test `{}/{}` already found in source code, so skipping modification.",
-                        source_path, unit_test.name,
-                    );
-                }
+        playback_file: &str,
+    ) -> Result<Option<(usize, usize)>> {
+        let include = format!("include!(\"{playback_file}\");");
+        if read_to_string(source_path)?.contains(&include) {
+            return Ok(None);
+        }
+        let module = [
+            "#[cfg(test)]".to_string(),
+            "mod kani_playback {".to_string(),
+            format!("{:<4}use super::*;", " "),
+            format!("{:<4}{include}", " "),
+            "}".to_string(),
+        ];
 
-                false
-            } else {
-                true
-            }
-        });
-
-        // Create temp file
-        if !unit_tests.is_empty() {
-            let source_basedir = Path::new(source_path).parent().unwrap_or(Path::new("."));
-            let mut temp_file = NamedTempFile::with_prefix_in("concrete_playback", source_basedir)?;
-            let mut curr_line_num = 0;
-
-            // Use a buffered reader/writer to generate the unit test line by line
-            for line in source_reader.lines().map_while(Result::ok) {
-                curr_line_num += 1;
-                writeln!(temp_file, "{line}")?;
-                if curr_line_num == proof_harness_end_line {
-                    for unit_test in unit_tests.iter() {
-                        for unit_test_line in unit_test.code.iter() {
-                            curr_line_num += 1;
-                            writeln!(temp_file, "{unit_test_line}")?;
-                        }
-                    }
+        // Read from source
+        let source_reader = BufReader::new(File::open(source_path)?);
+        let source_basedir = source_path.parent().unwrap_or(Path::new("."));
+        let mut temp_file = NamedTempFile::with_prefix_in("concrete_playback", source_basedir)?;
+
+        // Use a buffered reader/writer to add the module line by line
+        for (line_num, line) in source_reader.lines().map_while(Result::ok).enumerate() {
+            writeln!(temp_file, "{line}")?;
+            if line_num + 1 == proof_harness_end_line {
+                for module_line in &module {
+                    writeln!(temp_file, "{module_line}")?;
                 }
             }
-
-            // Renames are usually automic, so we won't corrupt the user's source file during a
-            // crash; but first flush all updates to disk, which persist wouldn't take care of.
-            temp_file.as_file().sync_all()?;
-            temp_file.persist(source_path).expect("Could not rename file");
         }
 
-        Ok(!unit_tests.is_empty())
+        // Renames are usually automic, so we won't corrupt the user's source file during a
+        // crash; but first flush all updates to disk, which persist wouldn't take care of.
+        temp_file.as_file().sync_all()?;
+        temp_file.persist(source_path).expect("Could not rename file");
+        Ok(Some((proof_harness_end_line + 1, proof_harness_end_line + module.len())))
     }
 
     /// Run rustfmt on the given src file, and optionally on only the specific lines.
@@ -231,13 +266,10 @@ fn run_rustfmt(
     }
 }
 
-/// Generate a formatted unit test from a list of concrete values.
-fn format_unit_test(harness_name: &str, concrete_vals: &[ConcreteVal]) -> UnitTest {
-    // Hash the concrete values along with the proof harness name.
-    let mut hasher = DefaultHasher::new();
-    harness_name.hash(&mut hasher);
-    concrete_vals.hash(&mut hasher);
-    let hash = hasher.finish();
+/// Generate a formatted unit test that plays back the check `check` from a list of concrete
+/// values.
+fn format_unit_test(harness_name: &str, check: &str, concrete_vals: &[ConcreteVal]) -> UnitTest {
+    let hash = stable_hash(harness_name, concrete_vals);
     let func_name = format!("kani_concrete_playback_{harness_name}_{hash}");
 
     let func_before_concrete_vals = [
@@ -259,7 +291,124 @@ fn format_unit_test(harness_name: &str, concrete_vals: &[ConcreteVal]) -> UnitTe
         .chain(func_after_concrete_vals)
         .collect();
 
-    UnitTest { code: full_func, name: func_name }
+    // The same check may be reached by several harnesses of a module.
+    let check = format!("{check} in {harness_name}");
+    UnitTest { code: full_func, name: func_name, check }
+}
+
+/// Hash the concrete values along with the proof harness name.
+///
+/// This uses FNV-1a, whose result doesn't depend on the version of Rust, so the name of a test
+/// remains the same across runs, and the existing tests can be found.
+fn stable_hash(harness_name: &str, concrete_vals: &[ConcreteVal]) -> u64 {
+    fn update(hash: u64, bytes: &[u8]) -> u64 {
+        bytes.iter().fold(hash, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3))
+    }
+    let mut hash = update(0xcbf29ce484222325, harness_name.as_bytes());
+    for concrete_val in concrete_vals {
+        // Hash the lengths too, so the values can't be split differently with the same hash.
+        hash = update(hash, &concrete_val.byte_arr.len().to_le_bytes());
+        hash = update(hash, &concrete_val.byte_arr);
+        hash = update(hash, &concrete_val.interp_val.len().to_le_bytes());
+        hash = update(hash, concrete_val.interp_val.as_bytes());
+    }
+    hash
+}
+
+/// The path of the playback file with the tests of the harnesses of the module of `harness`,
+/// relative to the directory of the source file of the harness.
+fn playback_file_name(harness: &HarnessMetadata) -> String {
+    let stem = Path::new(&harness.original_file).file_stem().unwrap().to_string_lossy();
+    match harness.pretty_name.rsplit_once("::") {
+        Some((module, _)) => format!("kani_playback/{stem}-{}.rs", module.replace("::", "-")),
+        None => format!("kani_playback/{stem}.rs"),
+    }
+}
+
+/// The prefix of the comment that precedes each test in a playback file, followed by the check
+/// that the test plays back.
+const CHECK_COMMENT: &str = "// Plays back: ";
+
+/// The tests of a playback file, in the order of the file.
+#[derive(Default)]
+struct PlaybackFile {
+    tests: Vec<UnitTest>,
+}
+
+impl PlaybackFile {
+    /// Read the tests of a playback file, if it exists.
+    fn read(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(PlaybackFile::default());
+        }
+        Ok(Self::parse(&read_to_string(path)?))
+    }
+
+    /// Split the content of a playback file into its tests. Each test starts with a comment with
+    /// the check it plays back, and the header of the file is discarded.
+    fn parse(content: &str) -> Self {
+        let mut tests: Vec<UnitTest> = vec![];
+        for line in content.lines() {
+            if let Some(check) = line.strip_prefix(CHECK_COMMENT) {
+                tests.push(UnitTest {
+                    code: vec![],
+                    name: String::new(),
+                    check: check.to_string(),
+                });
+            } else if let Some(test) = tests.last_mut() {
+                if test.name.is_empty() {
+                    if let Some(name) = line.strip_prefix("fn ").and_then(|f| f.split_once('(')) {
+                        test.name = name.0.to_string();
+                    }
+                }
+                test.code.push(line.to_string());
+            }
+        }
+        for test in &mut tests {
+            while test.code.last().is_some_and(|line| line.trim().is_empty()) {
+                test.code.pop();
+            }
+        }
+        PlaybackFile { tests }
+    }
+
+    /// Add a test, which replaces the test of the same check if there's one.
+    fn insert(&mut self, unit_test: UnitTest) {
+        match self.tests.iter_mut().find(|test| test.check == unit_test.check) {
+            Some(test) => *test = unit_test,
+            None => self.tests.push(unit_test),
+        }
+    }
+
+    fn render(&self, harness: &HarnessMetadata) -> String {
+        let harnesses = match harness.pretty_name.rsplit_once("::") {
+            Some((module, _)) => format!("the harnesses of `{module}`"),
+            None => "the root harnesses".to_string(),
+        };
+        let mut content = format!(
+            "// Concrete playback tests generated by Kani for {harnesses} in `{}`.\n// The \
+            tests are updated when they are generated again, so this file shouldn't be \
+            modified by hand.\n",
+            Path::new(&harness.original_file).file_name().unwrap().to_string_lossy()
+        );
+        for test in &self.tests {
+            content.push('\n');
+            content.push_str(CHECK_COMMENT);
+            content.push_str(&test.check);
+            content.push('\n');
+            for line in &test.code {
+                content.push_str(line);
+                content.push('\n');
+            }
+        }
+        content
+    }
+
+    fn write(&self, path: &Path, harness: &HarnessMetadata) -> Result<()> {
+        std::fs::create_dir_all(path.parent().unwrap())?;
+        std::fs::write(path, self.render(harness))?;
+        Ok(())
+    }
 }
 
 /// Format an initializer expression for a number of concrete values.
@@ -280,8 +429,10 @@ fn format_concrete_vals(concrete_vals: &[ConcreteVal]) -> impl Iterator<Item = S
 }
 
 /// Suppose `src_path` was `/path/to/file.txt`. This function extracts this into `/path/to` and `file.txt`.
+/// The parent of a relative path without a directory, e.g., `file.txt`, is `.`.
 fn extract_parent_dir_and_src_file(src_path: &Path) -> Result<(String, String)> {
-    let parent_dir_as_path = src_path.parent().unwrap();
+    let parent_dir_as_path =
+        src_path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
     let parent_dir = parent_dir_as_path.to_string_lossy().to_string();
     let src_file_name_as_osstr = src_path.file_name();
     let src_file = src_file_name_as_osstr.unwrap().to_string_lossy().to_string();
@@ -296,6 +447,8 @@ struct FileLineRange {
 struct UnitTest {
     code: Vec<String>,
     name: String,
+    /// The check that the test plays back, and its harness.
+    check: String,
 }
 
 /// Extract concrete values from the CBMC output processed items.
@@ -312,10 +465,9 @@ struct UnitTest {
 ///         ..., ] }
 ///     ..., ] }
 /// ```
-mod concrete_vals_extractor {
+pub(crate) mod concrete_vals_extractor {
     use crate::cbmc_output_parser::{CheckStatus, Property, TraceItem};
 
-    #[derive(Hash)]
     pub struct ConcreteVal {
         pub byte_arr: Vec<u8>,
         pub interp_val: String,
@@ -323,13 +475,22 @@ pub struct ConcreteVal {
 
     /// Extract a set of concrete values that trigger one assertion
     /// failure. Each element of the outer vector corresponds to
-    /// inputs triggering one assertion failure or cover statement.
-    pub fn extract_harness_values(result_items: &[Property]) -> Vec<Vec<ConcreteVal>> {
+    /// inputs triggering one assertion failure or cover statement, along with the name of this
+    /// check. If `selected_covers` isn't empty, only the values of these satisfied covers are
+    /// extracted, see [is_selected_cover].
+    pub fn extract_harness_values(
+        result_items: &[Property],
+        selected_covers: &[String],
+    ) -> Vec<(String, Vec<ConcreteVal>)> {
         result_items
             .iter()
             .filter(|prop| {
-                (prop.property_class() != "unwind" && prop.status == CheckStatus::Failure)
-                    || (prop.property_class() == "cover" && prop.status == CheckStatus::Satisfied)
+                if !selected_covers.is_empty() {
+                    is_selected_cover(prop, selected_covers)
+                        && prop.status == CheckStatus::Satisfied
+                } else {
+                    has_concrete_values(prop)
+                }
             })
             .map(|property| {
                 // Extract values for each assertion that has failed.
@@ -340,13 +501,29 @@ pub fn extract_harness_values(result_items: &[Property]) -> Vec<Vec<ConcreteVal>
                 let concrete_vals: Vec<ConcreteVal> =
                     trace.iter().filter_map(&extract_from_trace_item).collect();
 
-                concrete_vals
+                (property.property_name(), concrete_vals)
             })
             .collect()
     }
 
+    /// Whether the trace of `property` leads to a failure or to a satisfied cover statement, so
+    /// its concrete values can be played back.
+    pub fn has_concrete_values(property: &Property) -> bool {
+        (property.property_class() != "unwind" && property.status == CheckStatus::Failure)
+            || (property.property_class() == "cover" && property.status == CheckStatus::Satisfied)
+    }
+
+    /// Whether `property` is a cover statement selected with `--playback-cover`, by its property
+    /// id or its message.
+    pub fn is_selected_cover(property: &Property, selected_covers: &[String]) -> bool {
+        property.property_class() == "cover"
+            && selected_covers
+                .iter()
+                .any(|cover| *cover == property.property_name() || *cover == property.description)
+    }
+
     /// Extracts individual bytes returned by kani::any() calls.
-    fn extract_from_trace_item(trace_item: &TraceItem) -> Option<ConcreteVal> {
+    pub fn extract_from_trace_item(trace_item: &TraceItem) -> Option<ConcreteVal> {
         if let (Some(lhs), Some(source_location), Some(value)) =
             (&trace_item.lhs, &trace_item.source_location, &trace_item.value)
         {
@@ -411,8 +588,9 @@ mod tests {
     use super::concrete_vals_extractor::*;
     use super::*;
     use crate::cbmc_output_parser::{
-        CheckStatus, Property, PropertyId, SourceLocation, TraceData, TraceItem, TraceValue,
+        CheckStatus, Property, SourceLocation, TraceData, TraceItem, TraceValue,
     };
+    use crate::metadata::mock_proof_harness;
 
     /// util function for unit tests taht generates the rustfmt args used for formatting specific lines inside specific files.
     /// note - adding this within the test mod because it gives a lint warning without it.
@@ -486,7 +664,7 @@ fn split_unit_test_name(unit_test_name: &str) -> SplitUnitTestName {
     fn format_unit_test_full_func() {
         let harness_name = "test_proof_harness";
         let concrete_vals = [ConcreteVal { byte_arr: vec![0, 0], interp_val: "0".to_string() }];
-        let unit_test = format_unit_test(harness_name, &concrete_vals);
+        let unit_test = format_unit_test(harness_name, "check", &concrete_vals);
         let full_func = unit_test.code;
         let split_unit_test_name = split_unit_test_name(&unit_test.name);
         let expected_after_func_name = vec![
@@ -509,7 +687,7 @@ fn format_unit_test_full_func() {
 
     /// Generates a unit test and returns its hash.
     fn extract_hash_from_unit_test(harness_name: &str, concrete_vals: &[ConcreteVal]) -> String {
-        let unit_test = format_unit_test(harness_name, concrete_vals);
+        let unit_test = format_unit_test(harness_name, "check", concrete_vals);
         split_unit_test_name(&unit_test.name).hash
     }
 
@@ -532,6 +710,51 @@ fn check_hashes_are_unique() {
         assert_ne!(hash_base, hash_diff_interp_val);
     }
 
+    /// The names of the tests shouldn't change between runs or versions of Rust, so the existing
+    /// tests are found.
+    #[test]
+    fn check_hashes_are_stable() {
+        let concrete_vals = [ConcreteVal { byte_arr: vec![0, 1], interp_val: "256".to_string() }];
+        assert_eq!(stable_hash("harness", &concrete_vals), 9378566413397341642);
+        assert_eq!(stable_hash("harness", &[]), 2386850331520872875);
+    }
+
+    #[test]
+    fn check_playback_file_name() {
+        let harness = mock_proof_harness("verify::inner::check", None, None, None);
+        assert_eq!(playback_file_name(&harness), "kani_playback/<unknown>-verify-inner.rs");
+        let mut harness = mock_proof_harness("check", None, None, None);
+        harness.original_file = "src/lib.rs".to_string();
+        assert_eq!(playback_file_name(&harness), "kani_playback/lib.rs");
+    }
+
+    /// Check that the tests of a playback file are read back, and that a test of the same check
+    /// replaces the previous one in place.
+    #[test]
+    fn check_playback_file_update() {
+        let harness = mock_proof_harness("verify::check", None, None, None);
+        let val = |byte: u8| ConcreteVal { byte_arr: vec![byte], interp_val: byte.to_string() };
+        let mut playback = PlaybackFile::default();
+        playback.insert(format_unit_test("check", "check.assertion.1", &[val(1)]));
+        playback.insert(format_unit_test("check", "check.assertion.2", &[val(2)]));
+
+        let mut parsed = PlaybackFile::parse(&playback.render(&harness));
+        let names = |file: &PlaybackFile| -> Vec<_> {
+            file.tests.iter().map(|test| test.name.clone()).collect()
+        };
+        assert_eq!(names(&parsed), names(&playback));
+        assert_eq!(parsed.tests[1].code, playback.tests[1].code);
+        assert_eq!(parsed.tests[1].check, "check.assertion.2 in check");
+
+        let updated = format_unit_test("check", "check.assertion.1", &[val(3)]);
+        let updated_name = updated.name.clone();
+        parsed.insert(updated);
+        parsed.insert(format_unit_test("check", "check.cover.1", &[val(4)]));
+        assert_eq!(parsed.tests.len(), 3);
+        assert_eq!(parsed.tests[0].name, updated_name);
+        assert_eq!(parsed.tests[1].name, playback.tests[1].name);
+    }
+
     #[test]
     fn check_rustfmt_args_no_line_ranges() {
         let file_line_ranges = [FileLineRange { file: "file1".to_string(), line_range: None }];
@@ -567,46 +790,70 @@ fn check_extract_parent_dir_and_src_file() {
         let (path, file_name) = extract_parent_dir_and_src_file(src_path).unwrap();
         assert_eq!(path, "/path/to");
         assert_eq!(file_name, "file.txt");
+
+        let (path, file_name) = extract_parent_dir_and_src_file(Path::new("file.txt")).unwrap();
+        assert_eq!(path, ".");
+        assert_eq!(file_name, "file.txt");
     }
 
-    /// Test util functions which extract the counter example values from a property.
-    #[test]
-    fn check_concrete_vals_extractor() {
-        let processed_items = [Property {
-            description: "".to_string(),
-            property_id: PropertyId {
-                fn_name: Some("".to_string()),
-                class: "assertion".to_string(),
-                id: 1,
-            },
-            status: CheckStatus::Failure,
-            reach: None,
-            source_location: SourceLocation {
+    /// A property with a trace that assigns `385` to a `kani::any()` value.
+    fn mock_property(class: &str, id: u32, status: CheckStatus) -> Property {
+        Property::mock(class, status).with_id("", id).with_trace(vec![TraceItem {
+            step_type: "assignment".to_string(),
+            lhs: Some("goto_symex$$return_value".to_string()),
+            source_location: Some(SourceLocation {
                 column: None,
                 file: None,
-                function: None,
+                function: Some("kani::any_raw_internal::<u8>".to_string()),
                 line: None,
-            },
-            trace: Some(vec![TraceItem {
-                step_type: "assignment".to_string(),
-                lhs: Some("goto_symex$$return_value".to_string()),
-                source_location: Some(SourceLocation {
-                    column: None,
-                    file: None,
-                    function: Some("kani::any_raw_internal::<u8>".to_string()),
-                    line: None,
-                }),
-                value: Some(TraceValue {
-                    binary: Some("0000001100000001".to_string()),
-                    data: Some(TraceData::NonBool("385".to_string())),
-                    width: Some(16),
-                }),
-            }]),
-        }];
-        let concrete_vals = extract_harness_values(&processed_items).pop().unwrap();
+            }),
+            value: Some(TraceValue {
+                binary: Some("0000001100000001".to_string()),
+                data: Some(TraceData::NonBool("385".to_string())),
+                name: None,
+                width: Some(16),
+                members: None,
+                member: None,
+                elements: None,
+            }),
+            raw_lhs: None,
+        }])
+    }
+
+    /// Test util functions which extract the counter example values from a property.
+    #[test]
+    fn check_concrete_vals_extractor() {
+        let processed_items = [mock_property("assertion", 1, CheckStatus::Failure)];
+        let (check, concrete_vals) = extract_harness_values(&processed_items, &[]).pop().unwrap();
         let concrete_val = &concrete_vals[0];
 
+        assert_eq!(check, ".assertion.1");
+
         assert_eq!(concrete_val.byte_arr, vec![1, 3]);
         assert_eq!(concrete_val.interp_val, "385");
     }
+
+    #[test]
+    fn check_selected_covers_extractor() {
+        let mut message_cover = mock_property("cover", 2, CheckStatus::Satisfied);
+        message_cover.description = "reaches the end".to_string();
+        let processed_items = [
+            mock_property("assertion", 1, CheckStatus::Failure),
+            message_cover,
+            mock_property("cover", 3, CheckStatus::Satisfied),
+            mock_property("cover", 4, CheckStatus::Unsatisfiable),
+        ];
+        let checks = |selected_covers: &[&str]| {
+            let selected_covers: Vec<_> = selected_covers.iter().map(|c| c.to_string()).collect();
+            extract_harness_values(&processed_items, &selected_covers)
+                .into_iter()
+                .map(|(check, _)| check)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(checks(&[]), [".assertion.1", ".cover.2", ".cover.3"]);
+        assert_eq!(checks(&[".cover.3"]), [".cover.3"]);
+        assert_eq!(checks(&["reaches the end", ".cover.4"]), [".cover.2"]);
+        // Only the covers can be selected.
+        assert!(checks(&[".assertion.1"]).is_empty());
+    }
 }
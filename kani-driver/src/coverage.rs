@@ -0,0 +1,770 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Post-process the coverage results of all harnesses. This module enforces the minimum line
+//! coverage configured by the user, either with command line arguments or with the
+//! `[package.metadata.kani.coverage]` table, and exports the results to other coverage tools.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+use kani_metadata::{
+    CoverageRegion, CoverageRegionKind, FunctionCoverage, HarnessCoverage, HarnessMetadata,
+    COVERAGE_SCHEMA_VERSION,
+};
+
+use crate::args::VerificationArgs;
+use crate::cbmc_output_parser::{CheckStatus, Property, SourceLocation};
+use crate::harness_runner::HarnessResult;
+
+/// Whether each line of each file was covered by at least one harness.
+type LineMap = BTreeMap<PathBuf, BTreeMap<usize, bool>>;
+
+/// The number of lines covered out of the total number of lines with coverage checks.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct LineCoverage {
+    covered: usize,
+    total: usize,
+}
+
+impl LineCoverage {
+    fn percentage(&self) -> f64 {
+        if self.total == 0 { 100.0 } else { self.covered as f64 * 100.0 / self.total as f64 }
+    }
+
+    fn add(&mut self, other: LineCoverage) {
+        self.covered += other.covered;
+        self.total += other.total;
+    }
+}
+
+impl std::fmt::Display for LineCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.2}% ({}/{} lines)", self.percentage(), self.covered, self.total)
+    }
+}
+
+/// Check that the coverage of all harnesses meets the configured thresholds.
+///
+/// A line is considered covered if any harness reaches at least one of its coverage checks.
+/// On failure, this prints a breakdown of the coverage of every file under each failing path.
+pub(crate) fn check_coverage_thresholds(
+    args: &VerificationArgs,
+    results: &[HarnessResult<'_>],
+) -> Result<()> {
+    let mut thresholds: Vec<(Option<&Path>, f64)> =
+        args.min_line_coverage.iter().map(|min| (None, *min)).collect();
+    thresholds
+        .extend(args.min_line_coverage_for.iter().map(|(path, min)| (Some(path.as_path()), *min)));
+    if thresholds.is_empty() {
+        return Ok(());
+    }
+
+    let properties = results.iter().flat_map(|r| r.result.results.iter().flatten());
+    let lines = line_coverage(properties);
+    // A path without covered files, e.g., with a typo, would otherwise always meet its threshold.
+    let unmatched: Vec<_> = thresholds
+        .iter()
+        .filter_map(|(path, _)| path.filter(|path| files_under(&lines, Some(path)).is_empty()))
+        .map(|path| path.display().to_string())
+        .collect();
+    if !unmatched.is_empty() {
+        bail!(
+            "no file with coverage checks is under the line coverage threshold paths: {}",
+            unmatched.join(", ")
+        )
+    }
+    let failures: Vec<_> = thresholds
+        .into_iter()
+        .filter_map(|(path, min)| {
+            let files = files_under(&lines, path);
+            let mut coverage = LineCoverage::default();
+            files.values().for_each(|file| coverage.add(*file));
+            (coverage.percentage() < min).then_some((path, min, coverage, files))
+        })
+        .collect();
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    if !args.common_args.quiet {
+        println!("Line coverage thresholds not met:");
+        for (path, min, coverage, files) in &failures {
+            println!(" - {}: {coverage}, expected at least {min}%", threshold_name(*path));
+            for (file, file_coverage) in files {
+                println!("     {}: {file_coverage}", file.display());
+            }
+        }
+    }
+    let names: Vec<_> = failures.iter().map(|(path, ..)| threshold_name(*path)).collect();
+    bail!("line coverage is below the configured minimum for: {}", names.join(", "))
+}
+
+/// Print the coverage of each file and the ranges of lines that aren't covered by any harness.
+/// This is used with `--summary-only`.
+pub(crate) fn print_coverage_summary(results: &[HarnessResult<'_>]) {
+    let properties = results.iter().flat_map(|r| r.result.results.iter().flatten());
+    println!("{}", format_coverage_summary(&line_coverage(properties)));
+}
+
+fn format_coverage_summary(lines: &LineMap) -> String {
+    let mut output = String::from("Coverage Summary:\n");
+    for (file, coverage) in files_under(lines, None) {
+        output.push_str(&format!("{}: {coverage}", file.display()));
+        let ranges = uncovered_ranges(&lines[&file]);
+        if !ranges.is_empty() {
+            let ranges: Vec<_> = ranges
+                .iter()
+                .map(
+                    |(start, end)| {
+                        if start == end { start.to_string() } else { format!("{start}-{end}") }
+                    },
+                )
+                .collect();
+            output.push_str(&format!(", uncovered lines: {}", ranges.join(", ")));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Compute the ranges of uncovered lines. Lines without coverage checks (e.g., comments) don't
+/// interrupt a range, so it only ends at the next covered line.
+fn uncovered_ranges(lines: &BTreeMap<usize, bool>) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    let mut in_range = false;
+    for (line, covered) in lines {
+        if *covered {
+            in_range = false;
+        } else if in_range {
+            ranges.last_mut().unwrap().1 = *line;
+        } else {
+            ranges.push((*line, *line));
+            in_range = true;
+        }
+    }
+    ranges
+}
+
+fn threshold_name(path: Option<&Path>) -> String {
+    path.map_or("<all files>".to_string(), |path| path.display().to_string())
+}
+
+/// The file and line of a coverage check, if it has both. The checks of blocks generated by the
+/// compiler, e.g., an implicit `else`, may be at line 0, which isn't a line of the file, so they
+/// are skipped as well.
+fn check_file_line(src: &SourceLocation) -> Option<(&str, usize)> {
+    let line = src.line.as_ref()?.parse().ok().filter(|line| *line > 0)?;
+    Some((src.file.as_deref()?, line))
+}
+
+/// Aggregate the `code_coverage` checks of all harnesses per file and line.
+fn line_coverage<'a>(properties: impl Iterator<Item = &'a Property>) -> LineMap {
+    let mut lines = LineMap::default();
+    for prop in properties.filter(|prop| prop.is_code_coverage_property()) {
+        let Some((file, line)) = check_file_line(&prop.source_location) else { continue };
+        let covered = prop.status == CheckStatus::Covered;
+        if !covered && prop.is_gap_coverage_property() {
+            // Lines without executable code only count if they are reached.
+            continue;
+        }
+        let entry = lines.entry(PathBuf::from(file)).or_default().entry(line).or_default();
+        *entry |= covered;
+    }
+    lines
+}
+
+/// Compute the coverage of every file under the given path (or all files if `None`).
+fn files_under(lines: &LineMap, path: Option<&Path>) -> BTreeMap<PathBuf, LineCoverage> {
+    lines
+        .iter()
+        .filter(|(file, _)| path.map_or(true, |path| file.starts_with(path)))
+        .map(|(file, lines)| {
+            let covered = lines.values().filter(|covered| **covered).count();
+            (file.clone(), LineCoverage { covered, total: lines.len() })
+        })
+        .collect()
+}
+
+/// A coverage check location, identified by its file, function, line and column.
+type CheckLocation = (PathBuf, String, usize, usize);
+
+/// The coverage of a check location across all harnesses.
+#[derive(Debug, Default, Clone, Copy)]
+struct CheckCoverage {
+    covered: bool,
+    /// Whether the location is in a line without executable code.
+    gap: bool,
+}
+
+/// Aggregate the `code_coverage` checks of all harnesses per location.
+fn check_coverage<'a>(
+    properties: impl Iterator<Item = &'a Property>,
+) -> BTreeMap<CheckLocation, CheckCoverage> {
+    let mut checks = BTreeMap::<CheckLocation, CheckCoverage>::default();
+    for prop in properties.filter(|prop| prop.is_code_coverage_property()) {
+        let src = &prop.source_location;
+        let Some((file, line)) = check_file_line(src) else { continue };
+        let column = src.column.as_ref().and_then(|c| c.parse().ok()).unwrap_or(1);
+        let function = parent_function(src.function.as_deref().unwrap_or_default()).to_string();
+        let check = checks.entry((PathBuf::from(file), function, line, column)).or_default();
+        check.covered |= prop.status == CheckStatus::Covered;
+        check.gap |= prop.is_gap_coverage_property();
+    }
+    checks
+}
+
+/// The maximum number of functions listed by [`print_harness_suggestions`].
+const MAX_HARNESS_SUGGESTIONS: usize = 10;
+
+/// The line coverage of a function across all harnesses.
+#[derive(Debug, PartialEq, Eq)]
+struct UncoveredFunction {
+    name: String,
+    file: PathBuf,
+    uncovered: usize,
+    total: usize,
+}
+
+/// Print the functions with the most uncovered lines as candidates for new harnesses.
+/// This is used with `--suggest-harnesses`.
+pub(crate) fn print_harness_suggestions(results: &[HarnessResult<'_>]) {
+    let properties = results.iter().flat_map(|r| r.result.results.iter().flatten());
+    let suggestions = harness_suggestions(&check_coverage(properties));
+    if suggestions.is_empty() {
+        println!("Harness Suggestions: all reachable code is covered by the existing harnesses.");
+        return;
+    }
+    println!("Harness Suggestions:");
+    println!("The following functions have the most code that isn't covered by any harness.");
+    println!("Consider writing a harness that calls them directly:");
+    for function in suggestions {
+        println!(
+            " - {} ({}): {} of {} lines uncovered",
+            function.name,
+            function.file.display(),
+            function.uncovered,
+            function.total
+        );
+    }
+}
+
+/// Rank the functions by their number of uncovered lines.
+///
+/// We only consider functions that were instrumented with coverage checks, i.e., functions that
+/// are reachable from at least one harness, and that are defined in the local workspace. Code
+/// from the standard library and other dependencies usually has an absolute path.
+fn harness_suggestions(checks: &BTreeMap<CheckLocation, CheckCoverage>) -> Vec<UncoveredFunction> {
+    let mut lines = BTreeMap::<(&Path, &str), BTreeMap<usize, bool>>::default();
+    for ((file, function, line, _), check) in checks {
+        if file.is_absolute() || function.is_empty() || (check.gap && !check.covered) {
+            continue;
+        }
+        *lines.entry((file, function)).or_default().entry(*line).or_default() |= check.covered;
+    }
+    let mut functions: Vec<_> = lines
+        .into_iter()
+        .map(|((file, name), lines)| UncoveredFunction {
+            name: name.to_string(),
+            file: file.to_path_buf(),
+            uncovered: lines.values().filter(|covered| !**covered).count(),
+            total: lines.len(),
+        })
+        .filter(|function| function.uncovered > 0)
+        .collect();
+    functions.sort_by(|a, b| b.uncovered.cmp(&a.uncovered).then_with(|| a.name.cmp(&b.name)));
+    functions.truncate(MAX_HARNESS_SUGGESTIONS);
+    functions
+}
+
+/// Return the function that encloses the given closure, coroutine or async block.
+///
+/// These items are compiled as separate functions named after their parent function, e.g.:
+/// `main::{closure#0}` or `main::{closure#0}::{closure#1}`. We report their coverage under the
+/// parent function, since that's where their code is written. Their checks remain distinct regions
+/// since they have different source locations.
+fn parent_function(name: &str) -> &str {
+    let mut name = name;
+    while let Some((parent, last)) = name.rsplit_once("::")
+        && last.starts_with('{')
+        && last.ends_with('}')
+    {
+        name = parent;
+    }
+    name
+}
+
+/// Write the coverage results of all harnesses to the file given by `--coverage-export`.
+///
+/// The results use the JSON format produced by `llvm-cov export`, so they can be consumed by
+/// tools that already support it. Kani checks whether each line is reachable rather than counting
+/// executions, so every line with coverage checks is reported as a segment and an empty region at
+/// its first check, with an execution count of `1` if it's covered, and `0` otherwise. Each line
+/// is also counted as a region in the summaries.
+pub(crate) fn export_coverage(
+    args: &VerificationArgs,
+    results: &[HarnessResult<'_>],
+) -> Result<()> {
+    let Some(path) = &args.coverage_export else { return Ok(()) };
+    let properties = results.iter().flat_map(|r| r.result.results.iter().flatten());
+    let export = LlvmCovExport::new(&check_coverage(properties));
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, &export)?;
+    Ok(())
+}
+
+/// Write the coverage results of a harness to `<outdir>/coverage/<harness>.json`, using the
+/// format defined by [`HarnessCoverage`].
+pub(crate) fn write_harness_coverage(
+    outdir: &Path,
+    harness: &HarnessMetadata,
+    properties: &[Property],
+) -> Result<()> {
+    let path = harness_coverage_file(outdir, harness);
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let coverage = harness_coverage(&harness.pretty_name, &check_coverage(properties.iter()));
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, &coverage)?;
+    Ok(())
+}
+
+/// The file where [write_harness_coverage] writes the coverage results of `harness`.
+pub(crate) fn harness_coverage_file(outdir: &Path, harness: &HarnessMetadata) -> PathBuf {
+    outdir.join("coverage").join(format!("{}.json", harness.pretty_name.replace("::", "-")))
+}
+
+fn harness_coverage(
+    harness: &str,
+    checks: &BTreeMap<CheckLocation, CheckCoverage>,
+) -> HarnessCoverage {
+    let mut files: Vec<PathBuf> = vec![];
+    let mut functions: Vec<FunctionCoverage> = vec![];
+    for ((file, function, line, column), check) in checks {
+        // Checks are sorted by file and function, so we only need to look at the last entry.
+        if files.last() != Some(file) {
+            files.push(file.clone());
+        }
+        let file_idx = files.len() - 1;
+        if !functions.last().is_some_and(|f| f.file == file_idx && &f.name == function) {
+            functions.push(FunctionCoverage {
+                name: function.clone(),
+                file: file_idx,
+                regions: vec![],
+            });
+        }
+        functions.last_mut().unwrap().regions.push(CoverageRegion {
+            line: *line,
+            column: *column,
+            count: check.covered as u64,
+            kind: if check.gap { CoverageRegionKind::Gap } else { CoverageRegionKind::Code },
+        });
+    }
+    HarnessCoverage {
+        schema_version: COVERAGE_SCHEMA_VERSION,
+        harness: harness.to_string(),
+        files,
+        functions,
+    }
+}
+
+/// The top level object of the `llvm-cov export` JSON format.
+#[derive(Debug, Serialize)]
+struct LlvmCovExport {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    version: &'static str,
+    data: [LlvmCovData; 1],
+}
+
+#[derive(Debug, Serialize)]
+struct LlvmCovData {
+    files: Vec<LlvmCovFile>,
+    functions: Vec<LlvmCovFunction>,
+    totals: LlvmCovSummary,
+}
+
+/// A segment in the format `[line, column, count, has_count, is_region_entry, is_gap_region]`.
+type LlvmCovSegment = (usize, usize, u64, bool, bool, bool);
+
+/// A region in the format
+/// `[line_start, column_start, line_end, column_end, count, file_id, expanded_file_id, kind]`.
+type LlvmCovRegion = (usize, usize, usize, usize, u64, usize, usize, usize);
+
+#[derive(Debug, Serialize)]
+struct LlvmCovFile {
+    filename: String,
+    segments: Vec<LlvmCovSegment>,
+    branches: Vec<()>,
+    expansions: Vec<()>,
+    summary: LlvmCovSummary,
+}
+
+#[derive(Debug, Serialize)]
+struct LlvmCovFunction {
+    name: String,
+    count: u64,
+    regions: Vec<LlvmCovRegion>,
+    filenames: Vec<String>,
+    branches: Vec<()>,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct LlvmCovSummary {
+    lines: LlvmCovCount,
+    functions: LlvmCovCount,
+    instantiations: LlvmCovCount,
+    regions: LlvmCovCount,
+    branches: LlvmCovCount,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+struct LlvmCovCount {
+    count: usize,
+    covered: usize,
+    percent: f64,
+}
+
+impl LlvmCovCount {
+    fn new<'a>(covered: impl Iterator<Item = &'a bool>) -> Self {
+        let (count, covered) = covered.fold((0, 0), |(count, covered), is_covered| {
+            (count + 1, covered + *is_covered as usize)
+        });
+        let coverage = LineCoverage { covered, total: count };
+        LlvmCovCount { count, covered, percent: coverage.percentage() }
+    }
+
+    fn add(&mut self, other: LlvmCovCount) {
+        self.count += other.count;
+        self.covered += other.covered;
+        self.percent = LineCoverage { covered: self.covered, total: self.count }.percentage();
+    }
+}
+
+impl LlvmCovSummary {
+    fn add(&mut self, other: &LlvmCovSummary) {
+        self.lines.add(other.lines);
+        self.functions.add(other.functions);
+        self.instantiations.add(other.instantiations);
+        self.regions.add(other.regions);
+        self.branches.add(other.branches);
+    }
+}
+
+impl LlvmCovExport {
+    /// The kind of a code region in `llvm-cov` regions.
+    const CODE_REGION: usize = 0;
+
+    fn new(checks: &BTreeMap<CheckLocation, CheckCoverage>) -> Self {
+        // The coverage of each line of each function, and the column of its first check.
+        let mut per_function = BTreeMap::<(&Path, &str), BTreeMap<usize, (usize, bool)>>::default();
+        for ((file, function, line, column), check) in checks {
+            // Gap regions don't count as uncovered lines.
+            if check.gap && !check.covered {
+                continue;
+            }
+            let entry = per_function
+                .entry((file, function))
+                .or_default()
+                .entry(*line)
+                .or_insert((*column, false));
+            entry.0 = entry.0.min(*column);
+            entry.1 |= check.covered;
+        }
+        let mut per_file = BTreeMap::<&Path, Vec<(&str, &BTreeMap<usize, (usize, bool)>)>>::new();
+        for ((file, function), lines) in &per_function {
+            per_file.entry(file).or_default().push((function, lines));
+        }
+
+        let mut totals = LlvmCovSummary::default();
+        let files = per_file
+            .into_iter()
+            .map(|(file, functions)| {
+                let mut lines = BTreeMap::<usize, (usize, bool)>::default();
+                for (line, (column, covered)) in functions.iter().flat_map(|(_, lines)| *lines) {
+                    let entry = lines.entry(*line).or_insert((*column, false));
+                    entry.0 = entry.0.min(*column);
+                    entry.1 |= covered;
+                }
+                let functions: Vec<_> = functions
+                    .iter()
+                    .map(|(_, lines)| lines.values().any(|(_, covered)| *covered))
+                    .collect();
+                let line_counts: Vec<_> = lines.values().map(|(_, covered)| *covered).collect();
+                let summary = LlvmCovSummary {
+                    lines: LlvmCovCount::new(line_counts.iter()),
+                    functions: LlvmCovCount::new(functions.iter()),
+                    instantiations: LlvmCovCount::new(functions.iter()),
+                    regions: LlvmCovCount::new(line_counts.iter()),
+                    branches: LlvmCovCount::default(),
+                };
+                totals.add(&summary);
+                LlvmCovFile {
+                    filename: file.display().to_string(),
+                    segments: lines
+                        .iter()
+                        .map(|(line, (column, covered))| {
+                            (*line, *column, *covered as u64, true, true, false)
+                        })
+                        .collect(),
+                    branches: vec![],
+                    expansions: vec![],
+                    summary,
+                }
+            })
+            .collect();
+
+        let functions = per_function
+            .into_iter()
+            .map(|((file, name), lines)| LlvmCovFunction {
+                name: name.to_string(),
+                count: lines.values().any(|(_, covered)| *covered) as u64,
+                // Kani only knows where the checks of a line are, so the regions are empty.
+                regions: lines
+                    .iter()
+                    .map(|(line, (column, covered))| {
+                        let count = *covered as u64;
+                        (*line, *column, *line, *column, count, 0, 0, Self::CODE_REGION)
+                    })
+                    .collect(),
+                filenames: vec![file.display().to_string()],
+                branches: vec![],
+            })
+            .collect();
+
+        LlvmCovExport {
+            kind: "llvm.coverage.json.export",
+            version: "2.0.1",
+            data: [LlvmCovData { files, functions, totals }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coverage_check(file: &str, line: usize, status: CheckStatus) -> Property {
+        Property::mock("code_coverage", status)
+            .with_description("code coverage for location")
+            .with_id("", line as u32)
+            .with_location(file, line)
+    }
+
+    #[test]
+    fn check_line_covered_by_any_check() {
+        let properties = [
+            coverage_check("src/lib.rs", 1, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 2, CheckStatus::Uncovered),
+            coverage_check("src/lib.rs", 2, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 3, CheckStatus::Uncovered),
+            coverage_check("src/lib.rs", 0, CheckStatus::Covered),
+        ];
+        let lines = line_coverage(properties.iter());
+        let files = files_under(&lines, None);
+        assert_eq!(files[Path::new("src/lib.rs")], LineCoverage { covered: 2, total: 3 });
+        let checks = check_coverage(properties.iter());
+        assert_eq!(checks.keys().map(|(_, _, line, _)| *line).collect::<Vec<_>>(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn check_files_under_path() {
+        let properties = [
+            coverage_check("src/lib.rs", 1, CheckStatus::Covered),
+            coverage_check("src/parser/mod.rs", 1, CheckStatus::Uncovered),
+            coverage_check("src/parser/lexer.rs", 1, CheckStatus::Covered),
+            coverage_check("src/parser_utils.rs", 1, CheckStatus::Covered),
+        ];
+        let lines = line_coverage(properties.iter());
+        let files = files_under(&lines, Some(Path::new("src/parser")));
+        assert_eq!(
+            files.keys().collect::<Vec<_>>(),
+            vec![Path::new("src/parser/lexer.rs"), Path::new("src/parser/mod.rs")]
+        );
+        assert_eq!(files_under(&lines, None).len(), 4);
+    }
+
+    #[test]
+    fn check_uncovered_gap_lines_are_ignored() {
+        let mut covered_gap = coverage_check("src/lib.rs", 2, CheckStatus::Covered);
+        covered_gap.description = "code coverage for gap region".to_string();
+        let mut uncovered_gap = coverage_check("src/lib.rs", 3, CheckStatus::Uncovered);
+        uncovered_gap.description = "code coverage for gap region".to_string();
+        let properties =
+            [coverage_check("src/lib.rs", 1, CheckStatus::Uncovered), covered_gap, uncovered_gap];
+        let lines = line_coverage(properties.iter());
+        assert_eq!(lines[Path::new("src/lib.rs")], BTreeMap::from([(1, false), (2, true)]));
+
+        let export = LlvmCovExport::new(&check_coverage(properties.iter()));
+        let summary = &export.data[0].totals;
+        assert_eq!((summary.lines.count, summary.lines.covered), (2, 1));
+        assert_eq!((summary.regions.count, summary.regions.covered), (2, 1));
+        assert_eq!(
+            export.data[0].files[0].segments,
+            vec![(1, 1, 0, true, true, false), (2, 1, 1, true, true, false)]
+        );
+    }
+
+    #[test]
+    fn check_harness_suggestions() {
+        let check = |file: &str, function: &str, line: usize, status: CheckStatus| {
+            let mut property = coverage_check(file, line, status);
+            property.source_location.function = Some(function.to_string());
+            property
+        };
+        let properties = [
+            check("src/lib.rs", "foo", 1, CheckStatus::Covered),
+            check("src/lib.rs", "foo", 2, CheckStatus::Uncovered),
+            check("src/lib.rs", "bar", 5, CheckStatus::Uncovered),
+            check("src/lib.rs", "bar::{closure#0}", 6, CheckStatus::Uncovered),
+            check("src/lib.rs", "baz", 9, CheckStatus::Covered),
+            check("/rustc/library/core/src/lib.rs", "core::foo", 1, CheckStatus::Uncovered),
+        ];
+        let suggestions = harness_suggestions(&check_coverage(properties.iter()));
+        let summary: Vec<_> =
+            suggestions.iter().map(|f| (f.name.as_str(), f.uncovered, f.total)).collect();
+        assert_eq!(summary, vec![("bar", 2, 2), ("foo", 1, 2)]);
+    }
+
+    #[test]
+    fn check_harness_coverage() {
+        let check = |file: &str, function: &str, line: usize, status: CheckStatus| {
+            let mut property = coverage_check(file, line, status);
+            property.source_location.function = Some(function.to_string());
+            property
+        };
+        let properties = [
+            check("src/lib.rs", "foo", 1, CheckStatus::Covered),
+            check("src/lib.rs", "foo::{closure#0}", 2, CheckStatus::Uncovered),
+            check("src/lib.rs", "bar", 5, CheckStatus::Uncovered),
+            check("src/main.rs", "main", 1, CheckStatus::Covered),
+        ];
+        let coverage = harness_coverage("harness", &check_coverage(properties.iter()));
+        assert_eq!(coverage.schema_version, COVERAGE_SCHEMA_VERSION);
+        assert_eq!(coverage.files, vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")]);
+        let functions: Vec<_> = coverage
+            .functions
+            .iter()
+            .map(|f| (f.name.as_str(), f.file, f.regions.iter().map(|r| r.count).collect()))
+            .collect();
+        assert_eq!(
+            functions,
+            vec![("bar", 0, vec![0]), ("foo", 0, vec![1, 0]), ("main", 1, vec![1])]
+        );
+    }
+
+    #[test]
+    fn check_coverage_summary() {
+        let properties = [
+            coverage_check("src/lib.rs", 1, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 2, CheckStatus::Uncovered),
+            coverage_check("src/lib.rs", 4, CheckStatus::Uncovered),
+            coverage_check("src/lib.rs", 5, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 7, CheckStatus::Uncovered),
+            coverage_check("src/main.rs", 1, CheckStatus::Covered),
+        ];
+        let summary = format_coverage_summary(&line_coverage(properties.iter()));
+        assert_eq!(
+            summary,
+            "Coverage Summary:\n\
+             src/lib.rs: 40.00% (2/5 lines), uncovered lines: 2-4, 7\n\
+             src/main.rs: 100.00% (1/1 lines)\n"
+        );
+    }
+
+    #[test]
+    fn check_closures_under_parent_function() {
+        assert_eq!(parent_function("main"), "main");
+        assert_eq!(parent_function("main::{closure#0}"), "main");
+        assert_eq!(parent_function("foo::bar::{closure#0}::{closure#1}"), "foo::bar");
+        assert_eq!(parent_function("<Vec<u8> as Foo>::foo::{closure#2}"), "<Vec<u8> as Foo>::foo");
+
+        let mut closure = coverage_check("src/lib.rs", 2, CheckStatus::Covered);
+        closure.source_location.function = Some("main::{closure#0}".to_string());
+        closure.source_location.column = Some("20".to_string());
+        let mut parent = coverage_check("src/lib.rs", 2, CheckStatus::Uncovered);
+        parent.source_location.function = Some("main".to_string());
+        let export = LlvmCovExport::new(&check_coverage([closure, parent].iter()));
+        let functions = &export.data[0].functions;
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].name, "main");
+        // The line is covered by the closure, and its region starts at the first check.
+        assert_eq!(functions[0].regions, vec![(2, 1, 2, 1, 1, 0, 0, 0)]);
+        assert_eq!(functions[0].count, 1);
+    }
+
+    #[test]
+    fn check_llvm_cov_export() {
+        let properties = [
+            coverage_check("src/lib.rs", 1, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 2, CheckStatus::Uncovered),
+            coverage_check("src/main.rs", 1, CheckStatus::Uncovered),
+        ];
+        let export = LlvmCovExport::new(&check_coverage(properties.iter()));
+        let json = serde_json::to_value(export).unwrap();
+        assert_eq!(json["type"], "llvm.coverage.json.export");
+        let data = &json["data"][0];
+        assert_eq!(data["files"].as_array().unwrap().len(), 2);
+        assert_eq!(data["files"][0]["filename"], "src/lib.rs");
+        assert_eq!(
+            data["files"][0]["segments"][0],
+            serde_json::json!([1, 1, 1, true, true, false])
+        );
+        assert_eq!(data["files"][0]["summary"]["lines"]["covered"], 1);
+        assert_eq!(data["totals"]["lines"]["count"], 3);
+        assert_eq!(data["totals"]["lines"]["covered"], 1);
+    }
+
+    /// Check that the export has the fields of the `llvm-cov export` JSON format, and no others.
+    #[test]
+    fn check_llvm_cov_export_schema() {
+        let mut gap = coverage_check("src/lib.rs", 3, CheckStatus::Uncovered);
+        gap.description = "code coverage for gap region".to_string();
+        let properties = [
+            coverage_check("src/lib.rs", 1, CheckStatus::Covered),
+            coverage_check("src/lib.rs", 2, CheckStatus::Uncovered),
+            gap,
+        ];
+        let export = LlvmCovExport::new(&check_coverage(properties.iter()));
+        let json = serde_json::to_value(export).unwrap();
+        let keys = |value: &serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(keys(&json), ["data", "type", "version"]);
+        let data = &json["data"][0];
+        assert_eq!(keys(data), ["files", "functions", "totals"]);
+        let file = &data["files"][0];
+        assert_eq!(keys(file), ["branches", "expansions", "filename", "segments", "summary"]);
+        let function = &data["functions"][0];
+        assert_eq!(keys(function), ["branches", "count", "filenames", "name", "regions"]);
+        for summary in [&file["summary"], &data["totals"]] {
+            assert_eq!(
+                keys(summary),
+                ["branches", "functions", "instantiations", "lines", "regions"]
+            );
+            for count in summary.as_object().unwrap().values() {
+                assert_eq!(keys(count), ["count", "covered", "percent"]);
+            }
+        }
+        // Only the lines with code are reported, with the count given by their status.
+        assert_eq!(
+            file["segments"],
+            serde_json::json!([[1, 1, 1, true, true, false], [2, 1, 0, true, true, false]])
+        );
+        assert_eq!(
+            function["regions"],
+            serde_json::json!([[1, 1, 1, 1, 1, 0, 0, 0], [2, 1, 2, 1, 0, 0, 0, 0]])
+        );
+    }
+
+    #[test]
+    fn check_empty_coverage_is_complete() {
+        assert_eq!(LineCoverage::default().percentage(), 100.0);
+        assert_eq!(LineCoverage { covered: 1, total: 4 }.percentage(), 25.0);
+    }
+}
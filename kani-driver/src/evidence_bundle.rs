@@ -0,0 +1,237 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--evidence-bundle`, which packages the verification results in an archive that
+//! can be stored for audit and certification workflows.
+//!
+//! The archive has the following layout:
+//!  - `manifest.json`: The Kani and CBMC versions, the harnesses in the bundle, and the SHA-256
+//!    checksum of every other file in the bundle.
+//!  - `manifest.json.asc`: A detached GPG signature of the manifest, if a signing key was given
+//!    with `--evidence-signing-key`.
+//!  - `<harness>/evidence.json`: The CBMC options, the checksum of the goto program, and the
+//!    properties of the harness with their results.
+//!  - `<harness>/coverage/<harness>.json`: The coverage results, if `--coverage` was used.
+//!
+//! Signatures and the archive are created with the `gpg` and `tar` tools, which must be
+//! installed.
+
+use anyhow::{bail, Context, Result};
+use kani_metadata::HarnessMetadata;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+use tempfile::TempDir;
+
+use crate::call_cbmc::{cbmc_version, VerificationResult, VerificationStatus};
+use crate::coverage::write_harness_coverage;
+use crate::session::KaniSession;
+use crate::util::warning;
+use crate::version::KANI_VERSION;
+
+/// The version of the evidence bundle format.
+const EVIDENCE_BUNDLE_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const EVIDENCE_FILE: &str = "evidence.json";
+
+/// The top-level description of the bundle.
+#[derive(Debug, Serialize)]
+struct Manifest {
+    version: u32,
+    kani_version: String,
+    cbmc_version: String,
+    harnesses: Vec<String>,
+    /// The SHA-256 checksum of each file in the bundle, by its path in the bundle.
+    checksums: BTreeMap<String, String>,
+}
+
+/// The evidence of the verification of a single harness.
+#[derive(Debug, Serialize)]
+struct HarnessEvidence {
+    harness: String,
+    /// The options passed to CBMC, without the goto program.
+    cbmc_args: Vec<String>,
+    /// The SHA-256 checksum of the instrumented goto program that was verified.
+    goto_sha256: String,
+    status: &'static str,
+    runtime_secs: f64,
+    properties: Vec<PropertyEvidence>,
+}
+
+#[derive(Debug, Serialize)]
+struct PropertyEvidence {
+    name: String,
+    description: String,
+    location: String,
+    status: String,
+}
+
+/// An evidence bundle under construction. Harnesses can be added in parallel.
+pub struct EvidenceBundle {
+    /// The archive to create.
+    output: PathBuf,
+    staging: TempDir,
+    harnesses: Mutex<Vec<String>>,
+}
+
+impl EvidenceBundle {
+    pub fn new(output: &Path) -> Result<Self> {
+        // Check the archive format before verifying anything.
+        compression_flag(output)?;
+        let staging = tempfile::Builder::new().prefix("kani-evidence").tempdir()?;
+        Ok(EvidenceBundle { output: output.to_path_buf(), staging, harnesses: Mutex::new(vec![]) })
+    }
+
+    /// Record the verification of `harness`, whose instrumented goto program is `goto_file`.
+    pub fn add_harness(
+        &self,
+        session: &KaniSession,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+        result: &VerificationResult,
+    ) -> Result<()> {
+        let harness_dir = self.staging.path().join(harness.pretty_name.replace("::", "-"));
+        fs::create_dir_all(&harness_dir)?;
+
+        let mut cbmc_args = session.cbmc_flags(goto_file, harness)?;
+        cbmc_args.pop();
+        let properties = result.results.as_ref().map(Vec::as_slice).unwrap_or_default();
+        let evidence = HarnessEvidence {
+            harness: harness.pretty_name.clone(),
+            cbmc_args: cbmc_args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect(),
+            goto_sha256: sha256(goto_file)?,
+            status: match result.status {
+                VerificationStatus::Success => "SUCCESS",
+                VerificationStatus::Failure => "FAILURE",
+            },
+            runtime_secs: result.runtime.as_secs_f64(),
+            properties: properties
+                .iter()
+                .map(|prop| PropertyEvidence {
+                    name: prop.property_name(),
+                    description: prop.description.clone(),
+                    location: prop.source_location.to_string(),
+                    status: format!("{:?}", prop.status).to_uppercase(),
+                })
+                .collect(),
+        };
+        serde_json::to_writer_pretty(File::create(harness_dir.join(EVIDENCE_FILE))?, &evidence)?;
+        if session.args.coverage {
+            write_harness_coverage(&harness_dir, harness, properties)?;
+        }
+        self.harnesses.lock().unwrap().push(harness.pretty_name.clone());
+        Ok(())
+    }
+
+    /// Write the manifest, sign it if requested, and create the archive.
+    pub fn finish(self, session: &KaniSession) -> Result<()> {
+        let output = self.output.as_path();
+        let mut checksums = BTreeMap::new();
+        for file in files_under(self.staging.path())? {
+            let name = file.strip_prefix(self.staging.path())?.to_string_lossy().into_owned();
+            checksums.insert(name, sha256(&file)?);
+        }
+        let mut harnesses = self.harnesses.into_inner().unwrap();
+        harnesses.sort();
+        let manifest = Manifest {
+            version: EVIDENCE_BUNDLE_VERSION,
+            kani_version: KANI_VERSION.to_string(),
+            cbmc_version: cbmc_version()?,
+            harnesses,
+            checksums,
+        };
+        let manifest_file = self.staging.path().join(MANIFEST_FILE);
+        serde_json::to_writer_pretty(File::create(&manifest_file)?, &manifest)?;
+
+        if let Some(key) = &session.args.evidence_signing_key {
+            let mut cmd = Command::new("gpg");
+            cmd.args(["--batch", "--yes", "--armor", "--detach-sign", "--local-user"])
+                .arg(key)
+                .arg(&manifest_file);
+            session.run_suppress(cmd).context("Failed to sign the evidence bundle")?;
+        } else if !session.args.common_args.quiet {
+            warning(
+                "the evidence bundle isn't signed. Use `--evidence-signing-key` to sign it with \
+                a GPG key.",
+            );
+        }
+
+        let mut cmd = Command::new("tar");
+        cmd.args(compression_flag(output)?).arg("-cf").arg(output);
+        cmd.arg("-C").arg(self.staging.path()).arg(".");
+        session.run_suppress(cmd).context("Failed to archive the evidence bundle")?;
+        if !session.args.common_args.quiet {
+            println!("Evidence bundle written to {}", output.display());
+        }
+        Ok(())
+    }
+}
+
+/// The `tar` option to compress the archive according to the extension of `output`.
+fn compression_flag(output: &Path) -> Result<Option<&'static str>> {
+    let name = output.to_string_lossy();
+    if name.ends_with(".tar.zst") {
+        Ok(Some("--zstd"))
+    } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Ok(Some("--gzip"))
+    } else if name.ends_with(".tar") {
+        Ok(None)
+    } else {
+        bail!(
+            "unsupported evidence bundle `{}`. Expected a `.tar`, `.tar.gz` or `.tar.zst` file",
+            output.display()
+        )
+    }
+}
+
+/// All the files under `dir`, in a deterministic order.
+fn files_under(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut entries: Vec<_> =
+        fs::read_dir(dir)?.map(|entry| entry.map(|e| e.path())).collect::<Result<_, _>>()?;
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            files.extend(files_under(&entry)?);
+        } else {
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+/// The SHA-256 checksum of `file`, in hexadecimal.
+fn sha256(file: &Path) -> Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(file)?, &mut hasher)
+        .with_context(|| format!("failed to compute the checksum of `{}`", file.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_compression_flag() {
+        assert_eq!(compression_flag(Path::new("out.tar.zst")).unwrap(), Some("--zstd"));
+        assert_eq!(compression_flag(Path::new("out/evidence.tgz")).unwrap(), Some("--gzip"));
+        assert_eq!(compression_flag(Path::new("out.tar")).unwrap(), None);
+        assert!(compression_flag(Path::new("out.zip")).is_err());
+    }
+
+    #[test]
+    fn check_sha256() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), "abc").unwrap();
+        assert_eq!(
+            sha256(file.path()).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--dump-formula`, which writes the formula CBMC generates for a harness so it
+//! can be inspected with external tools.
+//!
+//! The variables of the formula are named after the SSA form of the goto program, which uses
+//! the mangled names of the Rust items. Each formula comes with a mapping file that relates its
+//! variables to the program symbols and to their Rust names, when they are known.
+
+use anyhow::Result;
+use kani_metadata::artifact::convert_type;
+use kani_metadata::{ArtifactType, HarnessMetadata};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::args::FormulaFormat;
+use crate::call_cbmc::cbmc_command;
+use crate::session::KaniSession;
+
+/// A variable of the formula, and the program expression it represents.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct FormulaVariable {
+    /// The SSA expression CBMC encoded in the formula, e.g., `main::1::x!0@1#2`.
+    pub expression: String,
+    /// The program symbol of the expression, i.e., without the SSA renaming.
+    pub symbol: String,
+    /// The Rust name of the symbol, if it's different from the symbol name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pretty_name: Option<String>,
+    /// The DIMACS literals that encode the expression, one per bit.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub literals: Vec<String>,
+}
+
+impl KaniSession {
+    /// Write the formula of a harness to the `--dump-formula` directory, and return the paths
+    /// of the formula and of its mapping file.
+    pub fn dump_formula(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+    ) -> Result<(PathBuf, PathBuf)> {
+        let out_dir = self.args.dump_formula.as_ref().expect("`--dump-formula` is set");
+        fs::create_dir_all(out_dir)?;
+        let harness_filename = harness.pretty_name.replace("::", "-");
+        let (format_flag, extension) = match self.args.formula_format {
+            FormulaFormat::Dimacs => ("--dimacs", "cnf"),
+            FormulaFormat::Smt2 => ("--smt2", "smt2"),
+        };
+        let formula_file = out_dir.join(format!("{harness_filename}.{extension}"));
+        let map_file = out_dir.join(format!("{harness_filename}.{extension}-map.json"));
+
+        // The formula is generated instead of being solved, so skip the solver flags.
+        let mut args =
+            self.cbmc_flags_with_solver(file, harness, None, self.args.cbmc_object_bits())?;
+        args.push(format_flag.into());
+        args.push("--outfile".into());
+        args.push(formula_file.clone().into_os_string());
+
+        let mut cmd = cbmc_command();
+        cmd.args(args);
+        self.run_suppress(cmd)?;
+
+        let formula = fs::read_to_string(&formula_file)?;
+        let pretty_names = harness
+            .goto_file
+            .as_ref()
+            .map(|goto| convert_type(goto, ArtifactType::SymTabGoto, ArtifactType::PrettyNameMap))
+            .and_then(|map_path| read_pretty_names(&map_path))
+            .unwrap_or_default();
+        let variables = match self.args.formula_format {
+            FormulaFormat::Dimacs => dimacs_variables(&formula, &pretty_names),
+            FormulaFormat::Smt2 => smt2_variables(&formula, &pretty_names),
+        };
+        serde_json::to_writer_pretty(File::create(&map_file)?, &variables)?;
+        Ok((formula_file, map_file))
+    }
+}
+
+/// Read the map from symbols to their Rust names generated by the compiler.
+fn read_pretty_names(path: &Path) -> Option<HashMap<String, String>> {
+    let map: HashMap<String, Option<String>> =
+        serde_json::from_reader(File::open(path).ok()?).ok()?;
+    Some(map.into_iter().filter_map(|(name, pretty)| Some((name, pretty?))).collect())
+}
+
+/// The variables of a DIMACS formula, which CBMC lists in comments with the format
+/// `c <expression> <literal>...`.
+pub fn dimacs_variables(
+    formula: &str,
+    pretty_names: &HashMap<String, String>,
+) -> Vec<FormulaVariable> {
+    formula
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.strip_prefix("c ")?.split_whitespace();
+            let expression = words.next()?;
+            let literals: Vec<_> = words.map(str::to_string).collect();
+            (!literals.is_empty()).then(|| variable(expression, literals, pretty_names))
+        })
+        .collect()
+}
+
+/// The variables of an SMT-LIB2 formula, i.e., the constants declared with `declare-fun`.
+pub fn smt2_variables(
+    formula: &str,
+    pretty_names: &HashMap<String, String>,
+) -> Vec<FormulaVariable> {
+    formula
+        .lines()
+        .filter_map(|line| {
+            let declaration = line.trim().strip_prefix("(declare-fun ")?;
+            let expression = match declaration.strip_prefix('|') {
+                Some(quoted) => quoted.split('|').next()?,
+                None => declaration.split_whitespace().next()?,
+            };
+            Some(variable(expression, vec![], pretty_names))
+        })
+        .collect()
+}
+
+fn variable(
+    expression: &str,
+    literals: Vec<String>,
+    pretty_names: &HashMap<String, String>,
+) -> FormulaVariable {
+    let symbol = ssa_symbol(expression);
+    let pretty_name = pretty_name(symbol, pretty_names).filter(|pretty| pretty != symbol);
+    FormulaVariable {
+        expression: expression.to_string(),
+        symbol: symbol.to_string(),
+        pretty_name,
+        literals,
+    }
+}
+
+/// Remove the SSA renaming of an expression, i.e., the `!<thread>`, `@<frame>` and `#<version>`
+/// suffixes CBMC adds to symbol names.
+fn ssa_symbol(expression: &str) -> &str {
+    let end = expression.find(['!', '@', '#']).unwrap_or(expression.len());
+    &expression[..end]
+}
+
+/// The Rust name of `symbol`. Local variables are named `<function>::<scope>::<variable>`, so
+/// their Rust name uses the name of their function.
+fn pretty_name(symbol: &str, pretty_names: &HashMap<String, String>) -> Option<String> {
+    if let Some(pretty) = pretty_names.get(symbol) {
+        return Some(pretty.clone());
+    }
+    let (function, local) = symbol.split_once("::")?;
+    pretty_names.get(function).map(|pretty| format!("{pretty}::{local}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pretty_names() -> HashMap<String, String> {
+        HashMap::from([("_RNvCs1_4test5check".to_string(), "check".to_string())])
+    }
+
+    #[test]
+    fn check_dimacs_variables() {
+        let formula = "c _RNvCs1_4test5check::1::x!0@1#2 3 4 5\n\
+            c goto_symex::\\guard#1 FALSE\n\
+            c no_literals\n\
+            p cnf 5 2\n\
+            1 -2 0\n";
+        let variables = dimacs_variables(formula, &pretty_names());
+        assert_eq!(variables.len(), 2);
+        assert_eq!(
+            variables[0],
+            FormulaVariable {
+                expression: "_RNvCs1_4test5check::1::x!0@1#2".to_string(),
+                symbol: "_RNvCs1_4test5check::1::x".to_string(),
+                pretty_name: Some("check::1::x".to_string()),
+                literals: vec!["3".to_string(), "4".to_string(), "5".to_string()],
+            }
+        );
+        assert_eq!(variables[1].symbol, "goto_symex::\\guard");
+        assert_eq!(variables[1].pretty_name, None);
+    }
+
+    #[test]
+    fn check_smt2_variables() {
+        let formula = "(set-logic QF_AUFBV)\n\
+            (declare-fun |_RNvCs1_4test5check::1::x!0@1#2| () (_ BitVec 8))\n\
+            (declare-fun B0 () Bool)\n";
+        let variables = smt2_variables(formula, &pretty_names());
+        assert_eq!(variables.len(), 2);
+        assert_eq!(variables[0].pretty_name.as_deref(), Some("check::1::x"));
+        assert!(variables[0].literals.is_empty());
+        assert_eq!(variables[1].symbol, "B0");
+    }
+}
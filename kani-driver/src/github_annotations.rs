@@ -0,0 +1,162 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The [workflow commands](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions)
+//! printed at the end of the run by `--output-format github`.
+//!
+//! GitHub Actions turns every `::error file=<FILE>,line=<LINE>::<MESSAGE>` line of the output of
+//! a step into an annotation at that location, which is shown inline on pull requests. Like the
+//! SARIF results of `--sarif`, every failed property of a failing harness is reported at its
+//! location, and a failing harness without any failed property is reported at the location of
+//! the harness.
+
+use std::path::Path;
+
+use crate::call_cbmc::VerificationStatus;
+use crate::cbmc_output_parser::CheckStatus;
+use crate::json_report::HarnessReport;
+
+/// Print the annotations of the failures of `harnesses` to the standard output.
+pub fn print_annotations(harnesses: &[HarnessReport]) {
+    for annotation in annotations(harnesses) {
+        println!("{annotation}");
+    }
+}
+
+fn annotations(harnesses: &[HarnessReport]) -> Vec<String> {
+    harnesses
+        .iter()
+        .filter(|harness| harness.status == VerificationStatus::Failure)
+        .flat_map(harness_annotations)
+        .collect()
+}
+
+/// The annotations of a failing harness.
+fn harness_annotations(harness: &HarnessReport) -> Vec<String> {
+    let failures: Vec<_> = harness
+        .properties
+        .iter()
+        .filter(|property| property.status == CheckStatus::Failure)
+        .map(|property| {
+            let location = &property.location;
+            let title = format!("Kani `{}` check", property.class);
+            let message = format!("{} (harness `{}`)", property.description, harness.name);
+            match (&location.file, location.line) {
+                (Some(file), Some(line)) => error(file, line, location.column, &title, &message),
+                // Fall back to the harness for checks without a location, e.g., unwinding
+                // assertions of the standard library.
+                _ => error(&harness.file, harness.start_line, None, &title, &message),
+            }
+        })
+        .collect();
+    if !failures.is_empty() {
+        return failures;
+    }
+    let message = match harness.cbmc_exit_status {
+        Some(status) => {
+            format!("Verification of harness `{}` failed: CBMC exited with {status}", harness.name)
+        }
+        None => format!("Verification of harness `{}` failed", harness.name),
+    };
+    vec![error(&harness.file, harness.start_line, None, "Kani harness", &message)]
+}
+
+/// An `::error` workflow command. GitHub resolves the file relative to the root of the
+/// repository, so files under the current directory are reported relative to it.
+fn error(file: &str, line: usize, column: Option<usize>, title: &str, message: &str) -> String {
+    let path = Path::new(file);
+    let file = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(|path| path.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| file.to_string());
+    let mut properties = format!("file={},line={line}", escape_property(&file));
+    if let Some(column) = column {
+        properties.push_str(&format!(",col={column}"));
+    }
+    format!("::error {properties},title={}::{}", escape_property(title), escape_data(message))
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25").replace('\r', "%0D").replace('\n', "%0A")
+}
+
+/// Escape the value of a property of a workflow command, which can't contain `:` or `,`.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_report::{PropertyLocation, PropertyReport};
+
+    fn harness(status: VerificationStatus, properties: Vec<PropertyReport>) -> HarnessReport {
+        HarnessReport {
+            name: "check".to_string(),
+            crate_name: "my_crate".to_string(),
+            file: "src/lib.rs".to_string(),
+            start_line: 3,
+            end_line: 8,
+            status,
+            runtime_secs: 1.0,
+            cbmc_exit_status: None,
+            coverage_file: None,
+            properties,
+            profile: Default::default(),
+        }
+    }
+
+    fn property(status: CheckStatus, line: Option<usize>) -> PropertyReport {
+        PropertyReport {
+            id: "check.assertion.1".to_string(),
+            class: "assertion".to_string(),
+            description: "assertion failed: a, b: 100%\nof cases".to_string(),
+            status,
+            location: PropertyLocation {
+                file: Some("src/lib.rs".to_string()),
+                function: Some("check".to_string()),
+                line,
+                column: Some(5),
+            },
+            trace_excerpt: vec![],
+        }
+    }
+
+    #[test]
+    fn check_annotations() {
+        let harnesses = [
+            harness(
+                VerificationStatus::Failure,
+                vec![
+                    property(CheckStatus::Failure, Some(6)),
+                    property(CheckStatus::Success, Some(7)),
+                    property(CheckStatus::Failure, None),
+                ],
+            ),
+            harness(VerificationStatus::Success, vec![property(CheckStatus::Success, Some(6))]),
+        ];
+        let message = "assertion failed: a, b: 100%25%0Aof cases (harness `check`)";
+        assert_eq!(
+            annotations(&harnesses),
+            [
+                format!(
+                    "::error file=src/lib.rs,line=6,col=5,title=Kani `assertion` check::{message}"
+                ),
+                format!("::error file=src/lib.rs,line=3,title=Kani `assertion` check::{message}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_failed_harness_without_properties() {
+        let mut failed = harness(VerificationStatus::Failure, vec![]);
+        failed.cbmc_exit_status = Some(6);
+        assert_eq!(
+            annotations(&[failed]),
+            ["::error file=src/lib.rs,line=3,title=Kani harness::Verification of harness `check` \
+            failed: CBMC exited with 6"
+                .to_string()]
+        );
+    }
+}
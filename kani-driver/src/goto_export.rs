@@ -0,0 +1,172 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Export of the instrumented goto binaries with `--keep-goto`, and their verification with the
+//! `replay-goto` subcommand.
+//!
+//! The export directory contains one sub-directory per harness with:
+//!  - `harness.goto`: The fully-linked and instrumented goto binary.
+//!  - `properties.json`: The properties of the harness, as listed by `cbmc --show-properties`.
+//!  - `manifest.json`: The harness metadata and the CBMC options used to verify it.
+
+use anyhow::{bail, Context, Result};
+use kani_metadata::{HarnessFilter, HarnessMetadata};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::args::replay_goto_args::ReplayGotoArgs;
+use crate::args::OutputFormat;
+use crate::call_cbmc::{cbmc_command, VerificationResult, VerificationStatus};
+use crate::cbmc_output_parser::process_cbmc_output;
+use crate::cbmc_property_renderer::kani_cbmc_output_filter;
+use crate::session::{run_piped, KaniSession};
+
+/// The version of the layout of the export directory. Bump it for any change that isn't
+/// backwards compatible.
+const GOTO_EXPORT_VERSION: u32 = 1;
+const GOTO_FILE: &str = "harness.goto";
+const PROPERTIES_FILE: &str = "properties.json";
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The content of `manifest.json`.
+#[derive(Debug, Serialize, Deserialize)]
+struct GotoManifest {
+    version: u32,
+    harness: HarnessMetadata,
+    /// The options to pass to CBMC besides the goto binary and the output format.
+    cbmc_args: Vec<String>,
+    /// Whether the extra pointer checks were enabled, which affects how results are processed.
+    extra_pointer_checks: bool,
+}
+
+impl KaniSession {
+    /// Export the instrumented goto binary of a harness to the `--goto-out` directory.
+    pub fn export_goto(&self, goto_file: &Path, harness: &HarnessMetadata) -> Result<()> {
+        let goto_out = self.args.goto_out.as_ref().expect("`--keep-goto` requires `--goto-out`");
+        let harness_dir = goto_out.join(harness.pretty_name.replace("::", "-"));
+        fs::create_dir_all(&harness_dir)?;
+
+        let exported_goto = harness_dir.join(GOTO_FILE);
+        fs::copy(goto_file, &exported_goto)?;
+
+        // The last argument is the goto binary, which is replaced when replaying.
+        let mut cbmc_args = self.cbmc_flags(goto_file, harness)?;
+        cbmc_args.pop();
+
+        let mut cmd = cbmc_command();
+        cmd.args(&cbmc_args).args(["--show-properties", "--json-ui"]).arg(&exported_goto);
+        let status = self.run_redirect(cmd, &harness_dir.join(PROPERTIES_FILE))?;
+        if !status.success() {
+            bail!("failed to list the properties of harness `{}`", harness.pretty_name);
+        }
+
+        let manifest = GotoManifest {
+            version: GOTO_EXPORT_VERSION,
+            harness: harness.clone(),
+            cbmc_args: cbmc_args.iter().map(|arg| arg.to_string_lossy().into_owned()).collect(),
+            extra_pointer_checks: self.args.extra_pointer_checks,
+        };
+        let manifest_file = File::create(harness_dir.join(MANIFEST_FILE))?;
+        serde_json::to_writer_pretty(manifest_file, &manifest)?;
+
+        if !self.args.common_args.quiet {
+            println!("Exported goto binary to {}", harness_dir.display());
+        }
+        Ok(())
+    }
+}
+
+/// Verify every harness exported to `args.goto_dir`, and fail if any of them fails.
+pub fn replay_goto(args: ReplayGotoArgs) -> Result<()> {
+    if !args.goto_dir.is_dir() {
+        bail!("`{}` is not a directory", args.goto_dir.display());
+    }
+    let mut harness_dirs: Vec<_> = fs::read_dir(&args.goto_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    harness_dirs.retain(|dir| dir.join(MANIFEST_FILE).is_file());
+    harness_dirs.sort();
+    let filters = args
+        .harnesses
+        .iter()
+        .map(|filter| HarnessFilter::parse(filter))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut total = 0;
+    let mut failures = vec![];
+    for harness_dir in harness_dirs {
+        let manifest = read_manifest(&harness_dir)?;
+        let name = &manifest.harness.pretty_name;
+        if !filters.is_empty()
+            && !filters.iter().any(|filter| filter.matches(&manifest.harness, false))
+        {
+            continue;
+        }
+        total += 1;
+        let result = replay_harness(&args, &harness_dir, &manifest)?;
+        if result.status == VerificationStatus::Failure {
+            failures.push(name.clone());
+        }
+    }
+
+    if !args.common_opts.quiet {
+        for failure in &failures {
+            println!("Verification failed for - {failure}");
+        }
+        let failing = failures.len();
+        let succeeding = total - failing;
+        println!(
+            "Complete - {succeeding} successfully verified harnesses, {failing} failures, {total} total."
+        );
+    }
+    if !failures.is_empty() {
+        bail!("verification failed for {} exported harnesses", failures.len());
+    }
+    Ok(())
+}
+
+fn read_manifest(harness_dir: &Path) -> Result<GotoManifest> {
+    let path = harness_dir.join(MANIFEST_FILE);
+    let reader = BufReader::new(File::open(&path)?);
+    let manifest: GotoManifest = serde_json::from_reader(reader)
+        .with_context(|| format!("failed to read `{}`", path.display()))?;
+    if manifest.version != GOTO_EXPORT_VERSION {
+        bail!(
+            "`{}` was exported with an incompatible version of Kani (found version {}, expected {})",
+            path.display(),
+            manifest.version,
+            GOTO_EXPORT_VERSION
+        );
+    }
+    Ok(manifest)
+}
+
+fn replay_harness(
+    args: &ReplayGotoArgs,
+    harness_dir: &Path,
+    manifest: &GotoManifest,
+) -> Result<VerificationResult> {
+    let quiet = args.common_opts.quiet;
+    let should_panic = manifest.harness.attributes.should_panic;
+    if !quiet {
+        println!("Checking harness {}...", manifest.harness.pretty_name);
+    }
+
+    let mut cmd = cbmc_command();
+    cmd.args(&manifest.cbmc_args).arg("--json-ui").arg(harness_dir.join(GOTO_FILE));
+    let start_time = Instant::now();
+    let cbmc_process = run_piped(&args.common_opts, cmd)?;
+    let cbmc_process = cbmc_process.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
+    let output = process_cbmc_output(cbmc_process, |i| {
+        kani_cbmc_output_filter(i, manifest.extra_pointer_checks, quiet, &OutputFormat::Regular)
+    })?;
+    let result = VerificationResult::from(output, should_panic, start_time);
+
+    if !quiet {
+        println!("{}", result.render(&OutputFormat::Regular, should_panic, false));
+    }
+    Ok(result)
+}
@@ -4,13 +4,34 @@
 use anyhow::{bail, Result};
 use kani_metadata::{ArtifactType, HarnessMetadata};
 use rayon::prelude::*;
+use std::fmt::{Display, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
-use crate::args::OutputFormat;
+use crate::args::{OutputFormat, ProofMode};
+use crate::artifacts::HarnessArtifacts;
+use crate::assumption_suggestions::{
+    failing_inputs, format_assumption_suggestions, read_source_line,
+};
+use crate::baseline::run_failed;
 use crate::call_cbmc::{VerificationResult, VerificationStatus};
+use crate::cbmc_output_parser::CheckStatus;
+use crate::cbmc_property_renderer::format_trace_excerpts;
+use crate::coverage::{
+    check_coverage_thresholds, export_coverage, print_coverage_summary, print_harness_suggestions,
+    write_harness_coverage,
+};
+use crate::evidence_bundle::EvidenceBundle;
+use crate::heap_graph::{format_heap_graph, heap_graph};
+use crate::profile::{format_profiles, HarnessProfile};
 use crate::project::Project;
+use crate::property_stats::format_hardest_properties;
+use crate::scheduler::Scheduler;
 use crate::session::KaniSession;
-use crate::util::error;
+use crate::severity::{Severity, SeverityPolicy};
+use crate::trace_values::{load_adt_map, render_trace_values};
+use crate::util::{error, warning};
 
 /// A HarnessRunner is responsible for checking all proof harnesses. The data in this structure represents
 /// "background information" that the controlling driver (e.g. cargo-kani or kani) computed.
@@ -28,6 +49,32 @@ pub(crate) struct HarnessRunner<'sess, 'pr> {
 pub(crate) struct HarnessResult<'pr> {
     pub harness: &'pr HarnessMetadata,
     pub result: VerificationResult,
+    /// The time spent instrumenting the goto model, if it was instrumented.
+    pub instrument_time: Option<Duration>,
+}
+
+/// The output of a harness. When harnesses are verified in parallel, the output of each harness is
+/// buffered and printed at once when it's dropped, so it isn't interleaved with the output of the
+/// other harnesses.
+struct HarnessOutput {
+    buffer: Option<String>,
+}
+
+impl HarnessOutput {
+    fn println(&mut self, line: impl Display) {
+        match &mut self.buffer {
+            Some(buffer) => writeln!(buffer, "{line}").unwrap(),
+            None => println!("{line}"),
+        }
+    }
+}
+
+impl Drop for HarnessOutput {
+    fn drop(&mut self) {
+        if let Some(buffer) = self.buffer.take() {
+            print!("{buffer}");
+        }
+    }
 }
 
 impl<'sess, 'pr> HarnessRunner<'sess, 'pr> {
@@ -38,6 +85,9 @@ pub(crate) fn check_all_harnesses(
         harnesses: &'pr [&HarnessMetadata],
     ) -> Result<Vec<HarnessResult<'pr>>> {
         self.check_stubbing(harnesses)?;
+        // The session is reused by the runs of `--watch`, so the cancellation of a previous run
+        // is cleared.
+        self.sess.reset_cancellation();
 
         let sorted_harnesses = crate::metadata::sort_harnesses_by_loc(harnesses);
 
@@ -49,27 +99,141 @@ pub(crate) fn check_all_harnesses(
             builder.build()?
         };
 
-        let results = pool.install(|| -> Result<Vec<HarnessResult<'pr>>> {
-            sorted_harnesses
-                .par_iter()
-                .map(|harness| -> Result<HarnessResult<'pr>> {
+        let evidence_bundle =
+            self.sess.args.evidence_bundle.as_deref().map(EvidenceBundle::new).transpose()?;
+        let artifacts = HarnessArtifacts::new(self.sess, self.project);
+        let incremental = self.sess.args.incremental && self.project.cargo_metadata.is_some();
+        let mut scheduler =
+            self.project.cargo_metadata.is_some().then(|| Scheduler::load(self.project));
+        let max_failures = self.sess.args.max_failures();
+        // Concrete playback modifies the sources of the harnesses, so they're kept in the order of
+        // their locations.
+        let order = match &scheduler {
+            Some(scheduler) if self.sess.args.concrete_playback.is_none() => {
+                scheduler.schedule(&sorted_harnesses, max_failures.is_some())
+            }
+            _ => (0..sorted_harnesses.len()).collect(),
+        };
+        let policy = SeverityPolicy::new(&self.sess.args.severity);
+        let failures = AtomicUsize::new(0);
+
+        let results = pool.install(|| -> Result<Vec<Option<(usize, HarnessResult<'pr>)>>> {
+            // Harnesses are started in the scheduled order as jobs become available, until the
+            // verification is cancelled by `--max-failures`.
+            order
+                .into_iter()
+                .par_bridge()
+                .map(|index| -> Result<Option<(usize, HarnessResult<'pr>)>> {
+                    if self.sess.is_cancelled() {
+                        return Ok(None);
+                    }
+                    let harness = sorted_harnesses[index];
                     let harness_filename = harness.pretty_name.replace("::", "-");
                     let report_dir = self.project.outdir.join(format!("report-{harness_filename}"));
                     let goto_file =
                         self.project.get_harness_artifact(&harness, ArtifactType::Goto).unwrap();
 
+                    let incremental_entry = if incremental {
+                        self.sess.incremental_entry(&self.project.outdir, goto_file, harness)?
+                    } else {
+                        None
+                    };
+                    let unchanged = incremental_entry
+                        .as_ref()
+                        .and_then(|entry| self.sess.cached_result(entry, harness));
+                    if let Some(result) = unchanged {
+                        self.sess.print_unchanged_result(harness, &result);
+                        if self.sess.args.coverage
+                            && let Ok(properties) = &result.results
+                        {
+                            write_harness_coverage(&self.project.outdir, harness, properties)?;
+                        }
+                        return Ok(Some((
+                            index,
+                            HarnessResult { harness, result, instrument_time: None },
+                        )));
+                    }
+
+                    let instrument_start = Instant::now();
                     self.sess.instrument_model(goto_file, goto_file, &self.project, &harness)?;
+                    let instrument_time = Some(instrument_start.elapsed());
 
                     if self.sess.args.synthesize_loop_contracts {
                         self.sess.synthesize_loop_contracts(goto_file, &goto_file, &harness)?;
                     }
 
-                    let result = self.sess.check_harness(goto_file, &report_dir, harness)?;
-                    Ok(HarnessResult { harness, result })
+                    if self.sess.args.keep_goto {
+                        self.sess.export_goto(goto_file, harness)?;
+                    }
+
+                    let cache_entry = if self.project.cargo_metadata.is_some() {
+                        self.sess.cache_entry(&self.project.outdir, goto_file, harness)?
+                    } else {
+                        None
+                    };
+                    let cached = cache_entry
+                        .as_ref()
+                        .and_then(|entry| self.sess.cached_result(entry, harness));
+                    let result = if let Some(result) = cached {
+                        self.sess.print_cached_result(harness, &result);
+                        result
+                    } else {
+                        let result = self.sess.check_harness(goto_file, &report_dir, harness)?;
+                        if result.cancelled {
+                            return Ok(None);
+                        }
+                        if let Some(entry) = &cache_entry {
+                            self.sess.cache_result(entry, harness, &result)?;
+                        }
+                        result
+                    };
+                    if let Some(entry) = &incremental_entry {
+                        self.sess.record_incremental_result(entry, harness, &result)?;
+                    }
+                    if self.sess.args.coverage
+                        && let Ok(properties) = &result.results
+                    {
+                        write_harness_coverage(&self.project.outdir, harness, properties)?;
+                    }
+                    if let Some(artifacts) = &artifacts {
+                        artifacts.keep(self.sess, goto_file, harness, &result)?;
+                    }
+                    if let Some(bundle) = &evidence_bundle {
+                        bundle.add_harness(self.sess, goto_file, harness, &result)?;
+                    }
+                    if let Some(max_failures) = max_failures
+                        && policy.judge(&result).status == VerificationStatus::Failure
+                        && failures.fetch_add(1, Ordering::Relaxed) + 1 >= max_failures
+                    {
+                        self.sess.cancel();
+                    }
+                    Ok(Some((index, HarnessResult { harness, result, instrument_time })))
                 })
                 .collect::<Result<Vec<_>>>()
         })?;
+        let mut results: Vec<_> = results.into_iter().flatten().collect();
+        results.sort_by_key(|(index, _)| *index);
+        let results: Vec<_> = results.into_iter().map(|(_, result)| result).collect();
 
+        if let Some(bundle) = evidence_bundle {
+            bundle.finish(self.sess)?;
+        }
+        if let Some(scheduler) = &mut scheduler {
+            scheduler.record(&results, &policy);
+            scheduler.save()?;
+        }
+        let skipped = sorted_harnesses.len() - results.len();
+        if skipped > 0 && !self.sess.args.common_args.quiet {
+            println!(
+                "Stopped after {} failed harnesses: {skipped} harnesses were not verified.",
+                failures.into_inner()
+            );
+        }
+        if let Some(artifacts) = artifacts
+            && !self.sess.args.common_args.quiet
+        {
+            println!("Harness artifacts written to {}", artifacts.dir().display());
+        }
         Ok(results)
     }
 
@@ -102,6 +266,11 @@ fn check_stubbing(&self, harnesses: &[&HarnessMetadata]) -> Result<()> {
 }
 
 impl KaniSession {
+    /// The output of a harness, which is buffered if several harnesses may run in parallel.
+    fn harness_output(&self) -> HarnessOutput {
+        HarnessOutput { buffer: (self.args.jobs() != Some(1)).then(String::new) }
+    }
+
     /// Run the verification process for a single harness
     pub(crate) fn check_harness(
         &self,
@@ -109,42 +278,218 @@ pub(crate) fn check_harness(
         report_dir: &Path,
         harness: &HarnessMetadata,
     ) -> Result<VerificationResult> {
-        if !self.args.common_args.quiet {
-            println!("Checking harness {}...", harness.pretty_name);
+        let mut output = self.harness_output();
+        if !self.args.common_args.quiet && !self.one_line_per_harness() {
+            output.println(format_args!("Checking harness {}...", harness.pretty_name));
         }
 
         if self.args.visualize {
             self.run_visualize(binary, report_dir, harness)?;
             // Strictly speaking, we're faking success here. This is more "no error"
             Ok(VerificationResult::mock_success())
+        } else if self.args.only_vcgen {
+            let smt2_file = self.run_vcgen(binary, harness)?;
+            if !self.args.common_args.quiet {
+                output.println(format_args!("Generated SMT-LIB2 file: {}", smt2_file.display()));
+            }
+            Ok(VerificationResult::mock_success())
         } else {
-            let mut result = self.with_timer(|| self.run_cbmc(binary, harness), "run_cbmc")?;
+            let (mut result, k_induction_message) = if self.args.proof_mode == ProofMode::KInduction
+            {
+                let (result, message) =
+                    self.with_timer(|| self.run_k_induction(binary, harness), "run_k_induction")?;
+                (result, Some(message))
+            } else {
+                (self.with_timer(|| self.run_cbmc(binary, harness), "run_cbmc")?, None)
+            };
+            if result.cancelled {
+                if !self.args.common_args.quiet && !self.one_line_per_harness() {
+                    output.println(format_args!("Cancelled harness {}", harness.pretty_name));
+                }
+                return Ok(result);
+            }
+            if let Some(solver) = &result.portfolio_solver
+                && !self.args.common_args.quiet
+                && !self.one_line_per_harness()
+            {
+                output.println(format_args!("Solver portfolio: `{solver}` finished first"));
+            }
+            if self.args.minimize_counterexamples {
+                let queries = self.with_timer(
+                    || self.minimize_counterexamples(binary, harness, &mut result),
+                    "minimize_counterexamples",
+                )?;
+                if queries > 0 && !self.args.common_args.quiet && !self.one_line_per_harness() {
+                    output.println(format_args!(
+                        "Minimized the counterexamples with {queries} solver queries."
+                    ));
+                }
+            }
+            if let Ok(properties) = &mut result.results
+                && properties.iter().any(|prop| prop.trace.is_some())
+            {
+                render_trace_values(properties, &load_adt_map(binary));
+            }
 
             // When quiet, we don't want to print anything at all.
             // When output is old, we also don't have real results to print.
             if !self.args.common_args.quiet && self.args.output_format != OutputFormat::Old {
-                println!(
-                    "{}",
-                    result.render(
-                        &self.args.output_format,
-                        harness.attributes.should_panic,
-                        self.args.coverage
-                    )
-                );
+                output.println(self.render_result(harness, &result));
+            }
+            if self.args.common_args.verbose && !result.phase_timings.is_empty() {
+                let timings: Vec<_> = result
+                    .phase_timings
+                    .iter()
+                    .map(|timing| format!("{} {}s", timing.phase, timing.runtime_secs))
+                    .collect();
+                output.println(format_args!("CBMC phase timings: {}", timings.join(", ")));
+            }
+            if self.args.suggest_assumptions
+                && result.status == VerificationStatus::Failure
+                && let Ok(properties) = &result.results
+                && !self.args.common_args.quiet
+            {
+                let inputs = failing_inputs(properties, read_source_line);
+                output.println(format_assumption_suggestions(&inputs));
+            }
+            if self.args.heap_graph
+                && let Ok(properties) = &result.results
+                && !self.args.common_args.quiet
+            {
+                for property in properties.iter().filter(|prop| prop.status == CheckStatus::Failure)
+                {
+                    if let Some(trace) = &property.trace {
+                        let graph = heap_graph(trace);
+                        output.println(format_heap_graph(&property.property_name(), &graph));
+                    }
+                }
+            }
+            if let Some(count) = self.args.hardest_properties
+                && let Ok(properties) = &result.results
+            {
+                let stats = self.property_stats(binary, harness, properties)?;
+                if !self.args.common_args.quiet {
+                    output.println(format_hardest_properties(&stats, count));
+                }
+            }
+            if let Some(message) = k_induction_message
+                && !self.args.common_args.quiet
+            {
+                output.println(message);
+            }
+            if self.args.dump_formula.is_some() {
+                let (formula_file, map_file) = self.dump_formula(binary, harness)?;
+                if !self.args.common_args.quiet {
+                    output.println(format_args!(
+                        "Formula written to {} with its variables described in {}",
+                        formula_file.display(),
+                        map_file.display()
+                    ));
+                }
             }
             self.gen_and_add_concrete_playback(harness, &mut result)?;
             Ok(result)
         }
     }
 
+    /// Print the result of a harness that was cached by a previous run.
+    fn print_cached_result(&self, harness: &HarnessMetadata, result: &VerificationResult) {
+        if self.args.common_args.quiet {
+            return;
+        }
+        let mut output = self.harness_output();
+        if self.one_line_per_harness() {
+            output.println(self.render_result(harness, result));
+            return;
+        }
+        output.println(format_args!("Checking harness {}...", harness.pretty_name));
+        output.println(self.render_result(harness, result));
+        output.println("Reused the result of a previous run. Use `--no-cache` to verify it again.");
+    }
+
+    /// Print the result of a harness that was skipped by `--incremental`.
+    fn print_unchanged_result(&self, harness: &HarnessMetadata, result: &VerificationResult) {
+        if self.args.common_args.quiet {
+            return;
+        }
+        let mut output = self.harness_output();
+        if self.one_line_per_harness() {
+            output.println(format_args!(
+                "Harness {}: SKIPPED (unchanged since its last successful verification)",
+                harness.pretty_name
+            ));
+            return;
+        }
+        output.println(format_args!("Checking harness {}...", harness.pretty_name));
+        output.println(format_args!(
+            "Skipped: the harness didn't change since its last successful verification, which \
+            took {:.2}s.",
+            result.runtime.as_secs_f32()
+        ));
+    }
+
+    /// Whether the result of each harness is printed on a single line, which is the case with the
+    /// terse output format unless the coverage results are printed.
+    fn one_line_per_harness(&self) -> bool {
+        self.args.output_format.is_terse() && (!self.args.coverage || self.args.summary_only)
+    }
+
+    /// Render the result of a harness with the output format selected by the user.
+    fn render_result(&self, harness: &HarnessMetadata, result: &VerificationResult) -> String {
+        let should_panic = harness.attributes.should_panic;
+        let excerpt = self.args.trace_excerpt();
+        if self.one_line_per_harness() {
+            return result.render_terse(&harness.pretty_name, should_panic, excerpt);
+        }
+        let mut rendered = result.render(
+            &self.args.output_format,
+            should_panic,
+            self.args.coverage && !self.args.summary_only,
+        );
+        if let Some(excerpt) = excerpt
+            && let Ok(properties) = &result.results
+        {
+            rendered.push_str(&format_trace_excerpts(properties, excerpt));
+        }
+        rendered
+    }
+
     /// Concludes a session by printing a summary report and exiting the process with an
     /// error code (if applicable).
     ///
     /// Note: Takes `self` "by ownership". This function wants to be able to drop before
     /// exiting with an error code, if needed.
     pub(crate) fn print_final_summary(self, results: &[HarnessResult<'_>]) -> Result<()> {
-        let (successes, failures): (Vec<_>, Vec<_>) =
-            results.iter().partition(|r| r.result.status == VerificationStatus::Success);
+        self.print_summary(results)?;
+
+        let coverage_result = if self.args.coverage {
+            check_coverage_thresholds(&self.args, results)
+        } else {
+            Ok(())
+        };
+
+        let policy = SeverityPolicy::new(&self.args.severity);
+        let statuses = results
+            .iter()
+            .map(|r| (r.harness.pretty_name.as_str(), policy.judge(&r.result).status));
+        if run_failed(self.args.baseline.as_deref(), self.args.common_args.quiet, statuses)? {
+            // Failure exit code without additional error message
+            drop(self);
+            std::process::exit(1);
+        }
+
+        coverage_result
+    }
+
+    /// Print the summary report of the verification results, and export the coverage results if
+    /// requested.
+    pub(crate) fn print_summary(&self, results: &[HarnessResult<'_>]) -> Result<()> {
+        // The severity policy decides which harnesses fail the run.
+        let policy = SeverityPolicy::new(&self.args.severity);
+        let judged: Vec<_> = results.iter().map(|r| (r, policy.judge(&r.result))).collect();
+        let (successes, failures): (Vec<_>, Vec<_>) = judged
+            .iter()
+            .partition(|(_, judgement)| judgement.status == VerificationStatus::Success);
 
         let succeeding = successes.len();
         let failing = failures.len();
@@ -159,19 +504,68 @@ pub(crate) fn print_final_summary(self, results: &[HarnessResult<'_>]) -> Result
             )
         }
 
+        if self.args.coverage && self.args.summary_only && !self.args.common_args.quiet {
+            print_coverage_summary(results);
+        }
+
+        if self.args.coverage && self.args.suggest_harnesses && !self.args.common_args.quiet {
+            print_harness_suggestions(results);
+        }
+
         // We currently omit a summary if there was just 1 harness
         if !self.args.common_args.quiet && !self.args.visualize {
+            // The output of each harness doesn't follow the order of the harnesses when they run
+            // in parallel, so we list how long each of them took, slowest first.
+            if self.args.jobs() != Some(1) && total > 1 && !self.args.only_vcgen {
+                let mut timed: Vec<_> = results.iter().collect();
+                timed.sort_by(|a, b| b.result.runtime.cmp(&a.result.runtime));
+                println!("Verification time per harness:");
+                for result in timed {
+                    println!(
+                        " - {}: {:.2}s",
+                        result.harness.pretty_name,
+                        result.result.runtime.as_secs_f32()
+                    );
+                }
+            }
+            if let Some(sort) = self.args.profile {
+                let profiles: Vec<_> = results
+                    .iter()
+                    .map(|result| {
+                        (result.harness.pretty_name.as_str(), HarnessProfile::new(result))
+                    })
+                    .collect();
+                print!("{}", format_profiles(&profiles, sort));
+            }
             if failing > 0 {
                 println!("Summary:");
             }
-            for failure in failures.iter() {
-                println!("Verification failed for - {}", failure.harness.pretty_name);
+            for (failure, judgement) in failures.iter() {
+                match judgement.describe(Severity::Error, &failure.result) {
+                    // The harness was verified, but one of its outcomes is an error.
+                    Some(reason) if failure.result.status == VerificationStatus::Success => {
+                        println!(
+                            "Verification failed for - {} (it {reason})",
+                            failure.harness.pretty_name
+                        )
+                    }
+                    _ => println!("Verification failed for - {}", failure.harness.pretty_name),
+                }
+            }
+            for (result, judgement) in &judged {
+                if let Some(reason) = judgement.describe(Severity::Warning, &result.result) {
+                    warning(&format!("harness `{}` {reason}", result.harness.pretty_name));
+                }
             }
 
-            if total > 0 {
+            if total > 0 && self.args.only_vcgen {
+                println!("Complete - generated verification conditions for {total} harnesses.");
+            } else if total > 0 {
                 println!(
                     "Complete - {succeeding} successfully verified harnesses, {failing} failures, {total} total."
                 );
+            } else if let Some(shard) = self.args.shard {
+                println!("No proof harnesses were assigned to shard {shard}.");
             } else {
                 match (self.args.harnesses.as_slice(), &self.args.function) {
                     ([], None) =>
@@ -197,12 +591,9 @@ pub(crate) fn print_final_summary(self, results: &[HarnessResult<'_>]) -> Result
             }
         }
 
-        if failing > 0 {
-            // Failure exit code without additional error message
-            drop(self);
-            std::process::exit(1);
+        if self.args.coverage {
+            export_coverage(&self.args, results)?;
         }
-
         Ok(())
     }
 }
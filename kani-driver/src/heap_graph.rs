@@ -0,0 +1,287 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--heap-graph`, which summarizes the memory state at the point a check fails.
+//!
+//! Instead of listing the raw values of every assignment, the trace of a failed check is
+//! replayed to find the last value of each pointer declared by the user and of each memory
+//! object reachable from them. The result is a small graph where the objects are the nodes and
+//! the pointers (including the ones stored inside objects) are the edges.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write;
+
+use crate::assumption_suggestions::is_user_variable;
+use crate::cbmc_output_parser::{TraceItem, TraceValue};
+
+/// The value of a pointer at the failure point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerValue {
+    Null,
+    /// A pointer to `object`. The offset is in number of elements when the object is an array,
+    /// and it's only known if CBMC reports it as a constant.
+    Address {
+        object: String,
+        offset: Option<u64>,
+    },
+    /// A pointer value that we couldn't interpret.
+    Unknown(String),
+}
+
+/// The value stored in one of the cells of an object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CellValue {
+    Pointer(PointerValue),
+    Data(String),
+}
+
+/// A memory object reachable from the harness pointers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeapObject {
+    /// The last value assigned to each cell of the object, e.g., `[2]` or `.len`, in the order
+    /// they were first assigned. An empty path represents the whole object.
+    pub cells: Vec<(String, CellValue)>,
+}
+
+impl HeapObject {
+    /// The least number of elements this object has, based on the array cells that were
+    /// assigned in the trace.
+    pub fn min_elements(&self) -> Option<u64> {
+        self.cells.iter().filter_map(|(path, _)| array_index(path)).max().map(|index| index + 1)
+    }
+
+    fn assign(&mut self, path: String, value: CellValue) {
+        if let Some(cell) = self.cells.iter_mut().find(|(cell, _)| *cell == path) {
+            cell.1 = value;
+        } else {
+            self.cells.push((path, value));
+        }
+    }
+}
+
+/// The pointers declared by the user and the objects reachable from them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeapGraph {
+    pub pointers: BTreeMap<String, PointerValue>,
+    pub objects: BTreeMap<String, HeapObject>,
+}
+
+/// Build the heap graph at the end of `trace`, which for failed checks is the failure point.
+pub fn heap_graph(trace: &[TraceItem]) -> HeapGraph {
+    let mut pointers = BTreeMap::new();
+    let mut all_objects: BTreeMap<String, HeapObject> = BTreeMap::new();
+    for step in trace.iter().filter(|step| step.step_type == "assignment") {
+        let (Some(lhs), Some(value)) = (&step.lhs, &step.value) else { continue };
+        let Some(data) = &value.data else { continue };
+        let data = data.to_string();
+        let value = if is_pointer(value, &data) {
+            CellValue::Pointer(parse_pointer(&data))
+        } else {
+            CellValue::Data(data)
+        };
+        let (root, path) = split_lhs(lhs);
+        if path.is_empty()
+            && is_user_variable(root)
+            && let CellValue::Pointer(pointer) = &value
+        {
+            pointers.insert(root.to_string(), pointer.clone());
+        }
+        all_objects.entry(root.to_string()).or_default().assign(path.to_string(), value);
+    }
+
+    // Only keep the objects that are reachable from the user pointers.
+    let mut objects = BTreeMap::new();
+    let mut visited = BTreeSet::new();
+    let mut worklist: VecDeque<_> = pointers.values().filter_map(pointee).collect();
+    while let Some(name) = worklist.pop_front() {
+        if !visited.insert(name.clone()) {
+            continue;
+        }
+        let object = all_objects.get(&name).cloned().unwrap_or_default();
+        worklist.extend(object.cells.iter().filter_map(|(_, value)| match value {
+            CellValue::Pointer(pointer) => pointee(pointer),
+            CellValue::Data(_) => None,
+        }));
+        objects.insert(name, object);
+    }
+    HeapGraph { pointers, objects }
+}
+
+/// Format the heap graph of the failed check `property`.
+pub fn format_heap_graph(property: &str, graph: &HeapGraph) -> String {
+    let mut result = format!("\nHeap graph at the failure of `{property}`:\n");
+    if graph.pointers.is_empty() {
+        result.push_str(" - No pointers were found in the failing trace.\n");
+        return result;
+    }
+    result.push_str(" - Pointers:\n");
+    for (name, pointer) in &graph.pointers {
+        writeln!(result, "   - `{name}` -> {}", format_pointer(pointer)).unwrap();
+    }
+    if !graph.objects.is_empty() {
+        result.push_str(" - Objects:\n");
+    }
+    for (name, object) in &graph.objects {
+        let kind = if is_heap_object(name) { "heap" } else { "stack or static" };
+        let size = match object.min_elements() {
+            Some(1) => ", at least 1 element".to_string(),
+            Some(count) => format!(", at least {count} elements"),
+            None => String::new(),
+        };
+        writeln!(result, "   - `{name}` ({kind}{size})").unwrap();
+        for (path, value) in &object.cells {
+            let path = if path.is_empty() { "value" } else { path.as_str() };
+            match value {
+                CellValue::Pointer(pointer) => {
+                    writeln!(result, "     {path} -> {}", format_pointer(pointer)).unwrap()
+                }
+                CellValue::Data(data) => writeln!(result, "     {path} = {data}").unwrap(),
+            }
+        }
+    }
+    result
+}
+
+fn format_pointer(pointer: &PointerValue) -> String {
+    match pointer {
+        PointerValue::Null => "NULL".to_string(),
+        PointerValue::Address { object, offset: Some(0) } => format!("`{object}`"),
+        PointerValue::Address { object, offset: Some(offset) } => {
+            format!("`{object}` at offset {offset}")
+        }
+        PointerValue::Address { object, offset: None } => {
+            format!("`{object}` at an unknown offset")
+        }
+        PointerValue::Unknown(data) => format!("`{data}`"),
+    }
+}
+
+fn pointee(pointer: &PointerValue) -> Option<String> {
+    match pointer {
+        PointerValue::Address { object, .. } => Some(object.clone()),
+        PointerValue::Null | PointerValue::Unknown(_) => None,
+    }
+}
+
+fn is_pointer(value: &TraceValue, data: &str) -> bool {
+    value.name.as_deref() == Some("pointer") || data.starts_with('&')
+}
+
+/// Objects allocated by CBMC's model of `malloc` are named `dynamic_object`.
+fn is_heap_object(name: &str) -> bool {
+    name.starts_with("dynamic_object")
+}
+
+/// Split an assignment target such as `node.next` or `buf[2]` into the object and the path
+/// of the cell inside it.
+fn split_lhs(lhs: &str) -> (&str, &str) {
+    let end = lhs.find(['[', '.']).unwrap_or(lhs.len());
+    lhs.split_at(end)
+}
+
+/// The index of an array cell path such as `[2]`.
+fn array_index(path: &str) -> Option<u64> {
+    parse_integer(path.strip_prefix('[')?.strip_suffix(']')?)
+}
+
+/// Parse an integer printed by CBMC, which may have a suffix such as `ul`.
+fn parse_integer(text: &str) -> Option<u64> {
+    text.trim().trim_end_matches(['u', 'l', 'U', 'L']).parse().ok()
+}
+
+/// Parse the pointer values printed by CBMC, e.g., `NULL`, `&x`, `&dynamic_object$1[2l]`, or
+/// `(char *)&dynamic_object$1 + 4l`.
+pub fn parse_pointer(data: &str) -> PointerValue {
+    let data = data.trim();
+    if data == "NULL" || data == "0" {
+        return PointerValue::Null;
+    }
+    let Some(start) = data.find('&').map(|index| index + 1).or_else(|| data.find("dynamic_object"))
+    else {
+        return PointerValue::Unknown(data.to_string());
+    };
+    let rest = &data[start..];
+    let end = rest.find(|c: char| "[]().+-* ".contains(c)).unwrap_or(rest.len());
+    let object = &rest[..end];
+    if object.is_empty() {
+        return PointerValue::Unknown(data.to_string());
+    }
+    let rest = rest[end..].trim_start_matches(')').trim();
+    let offset = if rest.is_empty() {
+        Some(0)
+    } else if let Some(index) = rest.strip_prefix('[') {
+        index.split(']').next().and_then(parse_integer)
+    } else if let Some(offset) = rest.strip_prefix('+') {
+        parse_integer(offset)
+    } else {
+        None
+    };
+    PointerValue::Address { object: object.to_string(), offset }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::TraceData;
+
+    fn assignment(lhs: &str, data: &str, name: &str) -> TraceItem {
+        TraceItem {
+            step_type: "assignment".to_string(),
+            lhs: Some(lhs.to_string()),
+            source_location: None,
+            value: Some(TraceValue {
+                binary: None,
+                data: Some(TraceData::NonBool(data.to_string())),
+                name: Some(name.to_string()),
+                width: Some(64),
+                members: None,
+                member: None,
+                elements: None,
+            }),
+            raw_lhs: None,
+        }
+    }
+
+    #[test]
+    fn check_parse_pointer() {
+        assert_eq!(parse_pointer("NULL"), PointerValue::Null);
+        let address =
+            |object: &str, offset| PointerValue::Address { object: object.to_string(), offset };
+        assert_eq!(parse_pointer("&x"), address("x", Some(0)));
+        assert_eq!(parse_pointer("&dynamic_object$1[2l]"), address("dynamic_object$1", Some(2)));
+        assert_eq!(
+            parse_pointer("((char *)&dynamic_object$1) + 4ul"),
+            address("dynamic_object$1", Some(4))
+        );
+        assert_eq!(parse_pointer("&buf[i]"), address("buf", None));
+        assert_eq!(parse_pointer("INVALID-12"), PointerValue::Unknown("INVALID-12".to_string()));
+    }
+
+    #[test]
+    fn check_heap_graph() {
+        let trace = vec![
+            assignment("var_1", "&dynamic_object$0", "pointer"),
+            assignment("head", "&dynamic_object$0", "pointer"),
+            assignment("dynamic_object$0[0]", "1", "integer"),
+            assignment("dynamic_object$0[1]", "&dynamic_object$1", "pointer"),
+            assignment("dynamic_object$1[0]", "2", "integer"),
+            assignment("other", "7", "integer"),
+            assignment("dynamic_object$0[0]", "3", "integer"),
+            assignment("dangling", "NULL", "pointer"),
+        ];
+        let graph = heap_graph(&trace);
+        assert_eq!(graph.pointers.keys().collect::<Vec<_>>(), ["dangling", "head"]);
+        assert_eq!(
+            graph.objects.keys().collect::<Vec<_>>(),
+            ["dynamic_object$0", "dynamic_object$1"]
+        );
+        let head = &graph.objects["dynamic_object$0"];
+        assert_eq!(head.min_elements(), Some(2));
+        assert_eq!(head.cells[0], ("[0]".to_string(), CellValue::Data("3".to_string())));
+
+        let output = format_heap_graph("check.assertion.1", &graph);
+        assert!(output.contains("`dangling` -> NULL"));
+        assert!(output.contains("`dynamic_object$0` (heap, at least 2 elements)"));
+        assert!(output.contains("[1] -> `dynamic_object$1`"));
+    }
+}
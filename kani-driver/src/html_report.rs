@@ -0,0 +1,392 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `cargo kani report --html <DIR>`, which renders the results of the last
+//! verification of a package as a static dashboard that can be published as a CI artifact.
+//!
+//! Every `cargo kani` run saves its results in the JSON format of `--output-format json` (see
+//! [crate::json_report]). The dashboard is a single `index.html` file without external resources,
+//! which lists the status of each harness with its coverage, a chart of the time spent in each
+//! phase of the verification, and the failed properties with the source code around them.
+
+use anyhow::{bail, Result};
+use cargo_metadata::MetadataCommand;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use kani_metadata::{CoverageRegionKind, HarnessCoverage};
+
+use crate::args::report_args::CargoReportArgs;
+use crate::call_cbmc::VerificationStatus;
+use crate::cbmc_output_parser::CheckStatus;
+use crate::json_report::{
+    last_run_file, HarnessReport, JsonReport, PropertyReport, SCHEMA_VERSION,
+};
+use crate::junit::escape;
+
+/// The number of lines shown before and after the line of a failed property.
+const SNIPPET_CONTEXT: usize = 2;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; }
+th, td { padding: 0.3em 0.8em; text-align: left; border-bottom: 1px solid #ddd; }
+.status { font-weight: bold; }
+.success .status { color: #1a7f37; }
+.failure .status { color: #cf222e; }
+.chart .row { display: flex; align-items: center; margin: 0.2em 0; }
+.chart .label { width: 20em; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.chart .bar { display: flex; width: 40em; height: 1em; background: #f0f0f0; }
+.chart .time { margin-left: 0.5em; }
+.legend span { display: inline-block; width: 1em; height: 1em; vertical-align: middle; }
+.codegen { background: #8250df; }
+.instrument { background: #bf8700; }
+.symex { background: #0969da; }
+.solver { background: #cf222e; }
+.other { background: #8c959f; }
+pre.snippet { background: #f6f8fa; padding: 0.5em; }
+pre.snippet .current { background: #ffebe9; display: block; }
+pre.snippet .number { color: #8c959f; display: inline-block; width: 4em; }
+";
+
+/// Write the dashboard of the results selected by `args`.
+pub fn report(args: CargoReportArgs) -> Result<()> {
+    let results = match &args.results {
+        Some(results) => results.clone(),
+        None => {
+            let mut cmd = MetadataCommand::new();
+            if let Some(path) = &args.manifest_path {
+                cmd.manifest_path(path);
+            }
+            last_run_file(args.target_dir.as_deref(), &cmd.no_deps().exec()?)
+        }
+    };
+    if !results.exists() {
+        bail!(
+            "no results found in `{}`. Run `cargo kani` first, or pass the document printed by \
+            `--output-format json` with `--results`.",
+            results.display()
+        );
+    }
+    let report = JsonReport::load(&results)?;
+    if report.schema_version != SCHEMA_VERSION {
+        bail!(
+            "the results in `{}` use version {} of the schema, but this version of Kani only \
+            supports version {SCHEMA_VERSION}",
+            results.display(),
+            report.schema_version
+        );
+    }
+    std::fs::create_dir_all(&args.html)?;
+    let index = args.html.join("index.html");
+    std::fs::write(&index, render_dashboard(&report))?;
+    if !args.common_opts.quiet {
+        println!("Wrote the dashboard of `{}` to `{}`", results.display(), index.display());
+    }
+    Ok(())
+}
+
+fn render_dashboard(report: &JsonReport) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n");
+    html.push_str("<meta charset=\"utf-8\">\n<title>Kani verification results</title>\n");
+    writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>").unwrap();
+    html.push_str("<h1>Kani verification results</h1>\n");
+    let cbmc = report.tools.cbmc.as_deref().unwrap_or("unknown");
+    writeln!(html, "<p>Kani {}, CBMC {}</p>", escape(&report.tools.kani), escape(cbmc)).unwrap();
+    let summary = &report.summary;
+    writeln!(
+        html,
+        "<p>{} harnesses: {} successfully verified, {} failed.</p>",
+        summary.total, summary.successful, summary.failed
+    )
+    .unwrap();
+    write_status_table(&mut html, &report.harnesses);
+    write_timing_chart(&mut html, &report.harnesses);
+    write_failures(&mut html, &report.harnesses);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn write_status_table(html: &mut String, harnesses: &[HarnessReport]) {
+    html.push_str("<h2>Harnesses</h2>\n<table>\n");
+    html.push_str(
+        "<tr><th>Harness</th><th>Crate</th><th>Status</th><th>Time</th><th>Coverage</th></tr>\n",
+    );
+    for (idx, harness) in harnesses.iter().enumerate() {
+        let (class, name) = match harness.status {
+            VerificationStatus::Success => ("success", escape(&harness.name)),
+            // Link to the details of the failure.
+            VerificationStatus::Failure => {
+                ("failure", format!("<a href=\"#harness-{idx}\">{}</a>", escape(&harness.name)))
+            }
+        };
+        let coverage = harness.coverage_file.as_deref().and_then(region_coverage).map_or(
+            "-".to_string(),
+            |(covered, total)| {
+                let percentage =
+                    if total == 0 { 100.0 } else { covered as f64 * 100.0 / total as f64 };
+                format!("{percentage:.2}% ({covered}/{total} regions)")
+            },
+        );
+        writeln!(
+            html,
+            "<tr class=\"{class}\"><td>{name}</td><td>{}</td><td class=\"status\">{}</td>\
+            <td>{:.3}s</td><td>{coverage}</td></tr>",
+            escape(&harness.crate_name),
+            status_name(harness.status),
+            total_secs(harness),
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+/// The time spent verifying a harness, which is only the verification time in documents written
+/// before the harnesses were profiled.
+fn total_secs(harness: &HarnessReport) -> f64 {
+    harness.profile.total_secs.max(harness.runtime_secs)
+}
+
+fn status_name(status: VerificationStatus) -> &'static str {
+    match status {
+        VerificationStatus::Success => "SUCCESS",
+        VerificationStatus::Failure => "FAILURE",
+    }
+}
+
+/// The number of covered code regions and the total number of code regions in the coverage file
+/// of a harness, if it can still be read.
+fn region_coverage(path: &Path) -> Option<(usize, usize)> {
+    let file = std::fs::File::open(path).ok()?;
+    let coverage: HarnessCoverage = serde_json::from_reader(std::io::BufReader::new(file)).ok()?;
+    let regions = coverage
+        .functions
+        .iter()
+        .flat_map(|function| &function.regions)
+        .filter(|region| region.kind == CoverageRegionKind::Code);
+    let (covered, total) = regions.fold((0, 0), |(covered, total), region| {
+        (covered + usize::from(region.count > 0), total + 1)
+    });
+    Some((covered, total))
+}
+
+/// A stacked bar per harness with the time spent in each phase, scaled to the slowest harness.
+fn write_timing_chart(html: &mut String, harnesses: &[HarnessReport]) {
+    html.push_str("<h2>Verification time</h2>\n<p class=\"legend\">");
+    for phase in ["codegen", "instrument", "symex", "solver", "other"] {
+        write!(html, "<span class=\"{phase}\"></span> {phase} ").unwrap();
+    }
+    html.push_str("</p>\n<div class=\"chart\">\n");
+    let max_secs = harnesses.iter().map(total_secs).fold(0.0, f64::max);
+    let mut sorted: Vec<_> = harnesses.iter().collect();
+    sorted.sort_by(|a, b| total_secs(b).total_cmp(&total_secs(a)));
+    for harness in sorted {
+        let profile = &harness.profile;
+        let total = total_secs(harness);
+        let phases = [
+            ("codegen", profile.codegen_secs.unwrap_or_default()),
+            ("instrument", profile.instrument_secs.unwrap_or_default()),
+            ("symex", profile.symex_secs.unwrap_or_default()),
+            ("solver", profile.solver_secs.unwrap_or_default()),
+        ];
+        let other = total - phases.iter().map(|(_, secs)| secs).sum::<f64>();
+        write!(
+            html,
+            "<div class=\"row\"><span class=\"label\">{}</span><div class=\"bar\">",
+            escape(&harness.name)
+        )
+        .unwrap();
+        for (phase, secs) in phases.into_iter().chain([("other", other.max(0.0))]) {
+            if secs > 0.0 {
+                let width = secs * 100.0 / max_secs;
+                write!(
+                    html,
+                    "<span class=\"{phase}\" style=\"width: {width:.2}%\" title=\"{phase}: {secs:.3}s\"></span>"
+                )
+                .unwrap();
+            }
+        }
+        writeln!(html, "</div><span class=\"time\">{total:.3}s</span></div>").unwrap();
+    }
+    html.push_str("</div>\n");
+}
+
+fn write_failures(html: &mut String, harnesses: &[HarnessReport]) {
+    let failures: Vec<_> = harnesses
+        .iter()
+        .enumerate()
+        .filter(|(_, harness)| harness.status == VerificationStatus::Failure)
+        .collect();
+    if failures.is_empty() {
+        return;
+    }
+    html.push_str("<h2>Failures</h2>\n");
+    for (idx, harness) in failures {
+        writeln!(
+            html,
+            "<section id=\"harness-{idx}\">\n<h3>{}</h3>\n<p>Defined in {}:{}</p>",
+            escape(&harness.name),
+            escape(&harness.file),
+            harness.start_line
+        )
+        .unwrap();
+        let failed: Vec<_> =
+            harness.properties.iter().filter(|prop| prop.status == CheckStatus::Failure).collect();
+        if failed.is_empty() {
+            let reason = match harness.cbmc_exit_status {
+                Some(status) => format!("CBMC failed with status {status}."),
+                None => "The verification failed without failed properties.".to_string(),
+            };
+            writeln!(html, "<p>{reason}</p>").unwrap();
+        }
+        for property in failed {
+            write_failed_property(html, harness, property);
+        }
+        html.push_str("</section>\n");
+    }
+}
+
+fn write_failed_property(html: &mut String, harness: &HarnessReport, property: &PropertyReport) {
+    let location = &property.location;
+    writeln!(html, "<h4>{}</h4>", escape(&property.description)).unwrap();
+    let mut position = location.file.clone().unwrap_or_else(|| "unknown file".to_string());
+    if let Some(line) = location.line {
+        write!(position, ":{line}").unwrap();
+    }
+    if let Some(column) = location.column {
+        write!(position, ":{column}").unwrap();
+    }
+    writeln!(
+        html,
+        "<p>Check <code>{}</code> ({}) at {}</p>",
+        escape(&property.id),
+        escape(&property.class),
+        escape(&position)
+    )
+    .unwrap();
+    let snippet = location
+        .file
+        .as_deref()
+        .zip(location.line)
+        .and_then(|(file, line)| Some((resolve_source(&harness.file, file)?, line)))
+        .and_then(|(path, line)| Some((std::fs::read_to_string(path).ok()?, line)));
+    if let Some((source, line)) = snippet {
+        html.push_str(&render_snippet(&source, line));
+    }
+}
+
+/// Find a source file reported by CBMC, which may be relative to the root of the crate of the
+/// harness, defined in `harness_file`.
+fn resolve_source(harness_file: &str, file: &str) -> Option<PathBuf> {
+    let path = Path::new(file);
+    if path.is_absolute() || path.exists() {
+        return path.exists().then(|| path.to_path_buf());
+    }
+    Path::new(harness_file).ancestors().skip(1).map(|dir| dir.join(path)).find(|path| path.exists())
+}
+
+/// The lines around `line` of `source`, with `line` highlighted.
+fn render_snippet(source: &str, line: usize) -> String {
+    let first = line.saturating_sub(SNIPPET_CONTEXT).max(1);
+    let mut snippet = String::from("<pre class=\"snippet\">");
+    for (number, text) in source.lines().enumerate().map(|(idx, text)| (idx + 1, text)) {
+        if number < first || number > line + SNIPPET_CONTEXT {
+            continue;
+        }
+        let class = if number == line { "line current" } else { "line" };
+        write!(
+            snippet,
+            "<span class=\"{class}\"><span class=\"number\">{number}</span>{}</span>",
+            escape(text)
+        )
+        .unwrap();
+        if number != line {
+            snippet.push('\n');
+        }
+    }
+    snippet.push_str("</pre>\n");
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_report::{PropertyLocation, ReportSummary, ToolVersions};
+    use crate::profile::HarnessProfile;
+
+    fn harness(name: &str, status: VerificationStatus, runtime_secs: f64) -> HarnessReport {
+        HarnessReport {
+            name: name.to_string(),
+            crate_name: "my_crate".to_string(),
+            file: "/nonexistent/src/lib.rs".to_string(),
+            start_line: 3,
+            end_line: 8,
+            status,
+            runtime_secs,
+            cbmc_exit_status: None,
+            coverage_file: None,
+            profile: HarnessProfile {
+                codegen_secs: Some(0.5),
+                solver_secs: Some(1.0),
+                total_secs: runtime_secs + 0.5,
+                ..Default::default()
+            },
+            properties: vec![],
+        }
+    }
+
+    #[test]
+    fn check_render_dashboard() {
+        let mut failing = harness("check_fail", VerificationStatus::Failure, 1.5);
+        failing.properties.push(PropertyReport {
+            id: "check_fail.assertion.1".to_string(),
+            class: "assertion".to_string(),
+            description: "assertion failed: x < 10".to_string(),
+            status: CheckStatus::Failure,
+            location: PropertyLocation {
+                file: Some("src/lib.rs".to_string()),
+                function: Some("check_fail".to_string()),
+                line: Some(6),
+                column: Some(5),
+            },
+            trace_excerpt: vec![],
+        });
+        let report = JsonReport {
+            schema_version: SCHEMA_VERSION,
+            tools: ToolVersions { kani: "0.55.0".to_string(), cbmc: None },
+            summary: ReportSummary { total: 2, successful: 1, failed: 1 },
+            coverage_export: None,
+            shard: None,
+            harnesses: vec![failing, harness("check_pass", VerificationStatus::Success, 0.5)],
+        };
+        let html = render_dashboard(&report);
+        assert!(html.contains("<p>Kani 0.55.0, CBMC unknown</p>"));
+        assert!(html.contains("<p>2 harnesses: 1 successfully verified, 1 failed.</p>"));
+        assert!(html.contains(
+            "<tr class=\"failure\"><td><a href=\"#harness-0\">check_fail</a></td><td>my_crate</td>\
+            <td class=\"status\">FAILURE</td><td>2.000s</td><td>-</td></tr>"
+        ));
+        assert!(html.contains("<td class=\"status\">SUCCESS</td><td>1.000s</td>"));
+        // The slowest harness fills the chart.
+        assert!(html.contains("<span class=\"codegen\" style=\"width: 25.00%\""));
+        assert!(html.contains("<span class=\"solver\" style=\"width: 50.00%\""));
+        assert!(html.contains("<section id=\"harness-0\">\n<h3>check_fail</h3>"));
+        assert!(html.contains("<h4>assertion failed: x &lt; 10</h4>"));
+        assert!(html.contains(
+            "<p>Check <code>check_fail.assertion.1</code> (assertion) at src/lib.rs:6:5</p>"
+        ));
+        assert!(!html.contains("harness-1"));
+    }
+
+    #[test]
+    fn check_render_snippet() {
+        let source = "fn f() {\n    let x = 1;\n    assert!(x < 0);\n}\n";
+        assert_eq!(
+            render_snippet(source, 3),
+            "<pre class=\"snippet\"><span class=\"line\"><span class=\"number\">1</span>fn f() {\
+            </span>\n<span class=\"line\"><span class=\"number\">2</span>    let x = 1;</span>\n\
+            <span class=\"line current\"><span class=\"number\">3</span>    assert!(x &lt; 0);\
+            </span><span class=\"line\"><span class=\"number\">4</span>}</span>\n</pre>\n"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Incremental verification with `cargo kani --incremental`: harnesses that were successfully
+//! verified by a previous run aren't verified again if they didn't change.
+//!
+//! The results are stored in the [verification cache](crate::verification_cache), but they are
+//! keyed by the goto program of the harness before its instrumentation, i.e., the code generated
+//! by the compiler linked with `kani_lib.c` and the `--c-lib` files, together with the options
+//! that affect its instrumentation. So unchanged harnesses skip every step after the compilation.
+//!
+//! Only successful results are recorded, so failing harnesses are always verified again.
+
+use anyhow::Result;
+use kani_metadata::HarnessMetadata;
+use std::path::Path;
+
+use crate::call_cbmc::{VerificationResult, VerificationStatus};
+use crate::cbmc_output_parser::CheckStatus;
+use crate::session::KaniSession;
+use crate::verification_cache::{CacheEntry, CacheKey};
+
+impl KaniSession {
+    /// The cache entry of the last successful result of `harness`, whose linked goto program
+    /// before instrumentation is `goto_file`, or `None` if the results of this session can't be
+    /// reused.
+    pub fn incremental_entry(
+        &self,
+        outdir: &Path,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+    ) -> Result<Option<CacheEntry>> {
+        // Some options need the instrumented goto program, even if the result is reused.
+        if !self.reuses_results()
+            || self.args.keep_goto
+            || self.args.synthesize_loop_contracts
+            || self.args.evidence_bundle.is_some()
+        {
+            return Ok(None);
+        }
+        let mut key = CacheKey::default();
+        // Keep these entries apart from the ones of the instrumented programs.
+        key.add("incremental");
+        // Options that change the instrumentation of the goto program.
+        key.add(format!("{:?}", self.args.checks));
+        key.add(format!("{:?}", self.args.memory_model));
+        key.add(format!("{:?}", self.args.common_args.unstable_features));
+        key.add([self.args.allocator_may_fail as u8, self.args.run_sanity_checks as u8]);
+        key.add(format!("{:?}", harness.attributes));
+        Ok(Some(self.entry_with_key(outdir, goto_file, harness, key)?))
+    }
+
+    /// Record the result of verifying `harness` for the next runs, if it's a complete success.
+    pub fn record_incremental_result(
+        &self,
+        entry: &CacheEntry,
+        harness: &HarnessMetadata,
+        result: &VerificationResult,
+    ) -> Result<()> {
+        if is_recorded(result) { self.cache_result(entry, harness, result) } else { Ok(()) }
+    }
+}
+
+/// Whether `result` is recorded, i.e., it's a success where every property was checked.
+fn is_recorded(result: &VerificationResult) -> bool {
+    result.status == VerificationStatus::Success
+        && result.results.as_ref().is_ok_and(|properties| {
+            properties.iter().all(|prop| prop.status != CheckStatus::Undetermined)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::Property;
+
+    fn result(status: CheckStatus) -> VerificationResult {
+        VerificationResult::mock_with_properties(vec![Property::mock("assertion", status)])
+    }
+
+    #[test]
+    fn check_only_record_successes() {
+        assert!(is_recorded(&result(CheckStatus::Success)));
+        assert!(!is_recorded(&result(CheckStatus::Failure)));
+        assert!(!is_recorded(&result(CheckStatus::Undetermined)));
+    }
+}
@@ -0,0 +1,433 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The machine-readable results of `--output-format json`.
+//!
+//! Instead of the output of each harness, Kani prints a single JSON document to the standard
+//! output at the end of the run, so CI systems and dashboards don't need to parse the terminal
+//! output. The document has the following shape:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "tools": { "kani": "0.55.0", "cbmc": "6.1.1 (cbmc-6.1.1)" },
+//!   "summary": { "total": 1, "successful": 0, "failed": 1 },
+//!   "coverage_export": null,
+//!   "harnesses": [
+//!     {
+//!       "name": "check_add",
+//!       "crate": "my_crate",
+//!       "file": "/home/user/my_crate/src/lib.rs",
+//!       "start_line": 10,
+//!       "end_line": 15,
+//!       "status": "FAILURE",
+//!       "runtime_secs": 0.25,
+//!       "cbmc_exit_status": null,
+//!       "coverage_file": null,
+//!       "profile": {
+//!         "codegen_secs": 0.05,
+//!         "instrument_secs": 0.02,
+//!         "symex_secs": 0.01,
+//!         "solver_secs": 0.12,
+//!         "total_secs": 0.32,
+//!         "peak_memory_kb": 41200
+//!       },
+//!       "properties": [
+//!         {
+//!           "id": "check_add.assertion.1",
+//!           "class": "assertion",
+//!           "description": "assertion failed: a + b > a",
+//!           "status": "FAILURE",
+//!           "location": { "file": "src/lib.rs", "function": "check_add", "line": 14, "column": 5 },
+//!           "trace_excerpt": ["a = 255 (src/lib.rs:11:13)", "b = 1 (src/lib.rs:12:13)"]
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! With `--shard`, the document also has a `shard` field with the `index` and `count` of the
+//! verified shard.
+//!
+//! The failed properties have a `trace_excerpt` with the last assignments to user variables of
+//! their trace, selected like in the terse output (`--trace-length` and `--full-trace`). It's
+//! omitted for the other properties.
+//!
+//! The schema is versioned by [SCHEMA_VERSION]: fields may be added without changing the
+//! version, but removing or changing the meaning of a field requires a new version.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+use crate::args::{OutputFormat, VerificationArgs};
+use crate::assumption_suggestions::is_user_variable;
+use crate::call_cargo::kani_target_dir;
+use crate::call_cbmc::{cbmc_version, VerificationStatus};
+use crate::cbmc_output_parser::{CheckStatus, Property};
+use crate::cbmc_property_renderer::is_std_file;
+use crate::coverage::harness_coverage_file;
+use crate::github_annotations::print_annotations;
+use crate::harness_runner::HarnessResult;
+use crate::junit::print_junit;
+use crate::profile::HarnessProfile;
+use crate::sarif::write_sarif;
+use crate::shard::Shard;
+use crate::version::KANI_VERSION;
+
+/// The version of the schema of the document.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The file of the Kani directory of a cargo project where the document of the last run is saved,
+/// so `cargo kani report` can render it.
+const LAST_RUN_FILE: &str = "last-run.json";
+
+/// The results of a run of Kani.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JsonReport {
+    pub schema_version: u32,
+    pub tools: ToolVersions,
+    pub summary: ReportSummary,
+    /// The file written by `--coverage-export`, if any.
+    pub coverage_export: Option<PathBuf>,
+    /// The shard verified with `--shard`, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shard: Option<Shard>,
+    pub harnesses: Vec<HarnessReport>,
+}
+
+/// The versions of the tools used for the verification.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ToolVersions {
+    pub kani: String,
+    /// The output of `cbmc --version`, if it could be determined.
+    pub cbmc: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub total: usize,
+    pub successful: usize,
+    pub failed: usize,
+}
+
+impl From<&[HarnessReport]> for ReportSummary {
+    fn from(harnesses: &[HarnessReport]) -> Self {
+        let failed = harnesses
+            .iter()
+            .filter(|harness| harness.status == VerificationStatus::Failure)
+            .count();
+        ReportSummary { total: harnesses.len(), successful: harnesses.len() - failed, failed }
+    }
+}
+
+/// The result of a harness.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HarnessReport {
+    pub name: String,
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub status: VerificationStatus,
+    pub runtime_secs: f64,
+    /// The exit status of CBMC if it failed without reporting the status of the properties.
+    pub cbmc_exit_status: Option<i32>,
+    /// The coverage results of the harness written with `--coverage`.
+    pub coverage_file: Option<PathBuf>,
+    /// The resources used to verify the harness, as reported by `--profile`.
+    #[serde(default)]
+    pub profile: HarnessProfile,
+    pub properties: Vec<PropertyReport>,
+}
+
+/// The status of a property checked by a harness.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PropertyReport {
+    pub id: String,
+    pub class: String,
+    pub description: String,
+    pub status: CheckStatus,
+    pub location: PropertyLocation,
+    /// The last assignments to user variables in the trace of a failed property, which is empty
+    /// if the output format doesn't show the traces.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trace_excerpt: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PropertyLocation {
+    pub file: Option<String>,
+    pub function: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl HarnessReport {
+    /// The reports of the harnesses of a project whose artifacts are in `outdir`.
+    pub fn from_results(
+        args: &VerificationArgs,
+        outdir: &Path,
+        results: &[HarnessResult<'_>],
+    ) -> Vec<HarnessReport> {
+        results
+            .iter()
+            .map(|harness_result| {
+                let HarnessResult { harness, result, .. } = harness_result;
+                let (properties, cbmc_exit_status) = match &result.results {
+                    Ok(properties) => (
+                        properties
+                            .iter()
+                            .map(|property| PropertyReport::new(property, args.trace_excerpt()))
+                            .collect(),
+                        None,
+                    ),
+                    Err(exit_status) => (vec![], Some(*exit_status)),
+                };
+                HarnessReport {
+                    name: harness.pretty_name.clone(),
+                    crate_name: harness.crate_name.clone(),
+                    file: harness.original_file.clone(),
+                    start_line: harness.original_start_line,
+                    end_line: harness.original_end_line,
+                    status: result.status,
+                    runtime_secs: result.runtime.as_secs_f64(),
+                    cbmc_exit_status,
+                    coverage_file: args
+                        .coverage
+                        .then(|| harness_coverage_file(outdir, harness))
+                        .filter(|path| path.exists()),
+                    properties,
+                    profile: HarnessProfile::new(harness_result),
+                }
+            })
+            .collect()
+    }
+}
+
+impl PropertyReport {
+    fn new(property: &Property, excerpt: Option<TraceExcerpt>) -> Self {
+        let location = &property.source_location;
+        PropertyReport {
+            id: property.property_id.to_string(),
+            class: property.property_class(),
+            description: property.description.clone(),
+            status: property.status,
+            location: PropertyLocation {
+                file: location.file.clone(),
+                function: location.function.clone(),
+                line: location.line.as_ref().and_then(|line| line.parse().ok()),
+                column: location.column.as_ref().and_then(|column| column.parse().ok()),
+            },
+            trace_excerpt: excerpt.map_or(vec![], |excerpt| trace_excerpt(property, excerpt)),
+        }
+    }
+}
+
+/// The steps of the traces kept by [trace_excerpt].
+#[derive(Clone, Copy, Debug)]
+pub struct TraceExcerpt {
+    /// The number of steps.
+    pub len: usize,
+    /// Whether the assignments in the standard library are kept, rather than collapsed into a
+    /// single step.
+    pub full_trace: bool,
+}
+
+/// The last `excerpt.len` assignments to user variables before the failure of `property`, e.g.,
+/// `x = 101 (src/lib.rs:12:5)`. Unless `excerpt.full_trace` is set, the consecutive assignments
+/// in the standard library are collapsed into a single step, e.g., `... 3 assignments in the
+/// standard library`.
+pub fn trace_excerpt(property: &Property, excerpt: TraceExcerpt) -> Vec<String> {
+    let Some(trace) = &property.trace else { return vec![] };
+    let mut assignments = vec![];
+    let mut std_assignments = 0;
+    for step in trace.iter().filter(|step| step.step_type == "assignment") {
+        let (Some(variable), Some(value)) = (
+            step.lhs.as_ref().filter(|lhs| is_user_variable(lhs)),
+            step.value.as_ref().and_then(|value| value.data.as_ref()),
+        ) else {
+            continue;
+        };
+        let location = step.source_location.as_ref();
+        if !excerpt.full_trace
+            && location.and_then(|loc| loc.file.as_deref()).is_some_and(is_std_file)
+        {
+            std_assignments += 1;
+            continue;
+        }
+        if std_assignments > 0 {
+            assignments.push(std_assignments_step(std_assignments));
+            std_assignments = 0;
+        }
+        assignments.push(match location {
+            Some(location) => format!("{variable} = {value} ({location})"),
+            None => format!("{variable} = {value}"),
+        });
+    }
+    if std_assignments > 0 {
+        assignments.push(std_assignments_step(std_assignments));
+    }
+    assignments[assignments.len().saturating_sub(excerpt.len)..].to_vec()
+}
+
+fn std_assignments_step(count: usize) -> String {
+    let plural = if count == 1 { "" } else { "s" };
+    format!("... {count} assignment{plural} in the standard library")
+}
+
+impl JsonReport {
+    pub fn new(args: &VerificationArgs, mut harnesses: Vec<HarnessReport>) -> Self {
+        // Harnesses verified in parallel finish in any order.
+        harnesses.sort_by(|a, b| a.name.cmp(&b.name));
+        JsonReport {
+            schema_version: SCHEMA_VERSION,
+            tools: ToolVersions { kani: KANI_VERSION.to_string(), cbmc: cbmc_version().ok() },
+            summary: harnesses.as_slice().into(),
+            coverage_export: args.coverage_export.clone(),
+            shard: args.shard,
+            harnesses,
+        }
+    }
+
+    /// Print the document to the standard output.
+    pub fn print(&self) -> Result<()> {
+        println!("{}", serde_json::to_string_pretty(self)?);
+        Ok(())
+    }
+
+    /// Read a document written by [Self::save] or printed by `--output-format json`.
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to read the results `{}`", path.display()))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("failed to parse the results `{}`", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+/// The file where the document of the last run on a cargo project is saved, given the
+/// `--target-dir` of the run.
+pub fn last_run_file(target_dir: Option<&Path>, metadata: &cargo_metadata::Metadata) -> PathBuf {
+    kani_target_dir(target_dir, metadata).join(LAST_RUN_FILE)
+}
+
+/// Save the results of a run on a cargo project, given its metadata, so `cargo kani report` can
+/// render them. Standalone projects don't have a fixed directory where they could be found.
+pub fn save_last_run(
+    args: &VerificationArgs,
+    metadata: Option<&cargo_metadata::Metadata>,
+    harnesses: Vec<HarnessReport>,
+) -> Result<()> {
+    let Some(metadata) = metadata else { return Ok(()) };
+    let path = last_run_file(args.target_dir.as_deref(), metadata);
+    JsonReport::new(args, harnesses)
+        .save(&path)
+        .with_context(|| format!("failed to save the results to `{}`", path.display()))
+}
+
+/// Whether the results of the harnesses must be exported, with `--output-format json`,
+/// `--output-format junit`, `--output-format github` or `--sarif`.
+pub fn exports_reports(args: &VerificationArgs) -> bool {
+    matches!(args.output_format, OutputFormat::Json | OutputFormat::Junit | OutputFormat::Github)
+        || args.sarif.is_some()
+}
+
+/// Export the results of the harnesses of a run as requested by [exports_reports].
+pub fn export_reports(args: &VerificationArgs, harnesses: Vec<HarnessReport>) -> Result<()> {
+    if let Some(path) = &args.sarif {
+        write_sarif(path, &harnesses)?;
+    }
+    match args.output_format {
+        OutputFormat::Json => JsonReport::new(args, harnesses).print()?,
+        OutputFormat::Junit => print_junit(&harnesses),
+        OutputFormat::Github if !args.common_args.quiet => print_annotations(&harnesses),
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_cbmc::VerificationResult;
+    use crate::metadata::mock_proof_harness;
+    use clap::Parser;
+
+    #[test]
+    fn check_harness_report() {
+        let harness = mock_proof_harness("check_add", None, Some("my_crate"), None);
+        let property = Property::mock("assertion", CheckStatus::Failure)
+            .with_description("assertion failed: a + b > a")
+            .with_id("check_add", 1)
+            .with_location("src/lib.rs", 14);
+        let result = HarnessResult {
+            harness: &harness,
+            result: VerificationResult::mock_with_properties(vec![property]),
+            instrument_time: None,
+        };
+        let args = crate::args::StandaloneArgs::parse_from(["kani", "lib.rs"]).verify_opts;
+        let reports = HarnessReport::from_results(&args, Path::new("."), &[result]);
+        let report = JsonReport::new(&args, reports);
+        let json = serde_json::to_value(report).unwrap();
+        assert_eq!(json["schema_version"], SCHEMA_VERSION);
+        assert_eq!(json["summary"]["failed"], 1);
+        let harness = &json["harnesses"][0];
+        assert_eq!(harness["name"], "check_add");
+        assert_eq!(harness["crate"], "my_crate");
+        assert_eq!(harness["status"], "FAILURE");
+        assert_eq!(harness["runtime_secs"], 1.0);
+        assert_eq!(harness["profile"]["total_secs"], 1.0);
+        assert!(harness["profile"]["peak_memory_kb"].is_null());
+        let property = &harness["properties"][0];
+        assert_eq!(property["id"], "check_add.assertion.1");
+        assert_eq!(property["status"], "FAILURE");
+        assert_eq!(property["location"]["line"], 14);
+    }
+
+    #[test]
+    fn check_trace_excerpt() {
+        let assignment = |lhs: &str, value: &str, file: &str| {
+            serde_json::json!({
+                "stepType": "assignment",
+                "lhs": lhs,
+                "sourceLocation": { "file": file, "line": "3" },
+                "value": { "data": value },
+            })
+        };
+        let std_file = "/rustc/abc/library/core/src/cmp.rs";
+        let trace = serde_json::json!([
+            assignment("x", "1", "src/lib.rs"),
+            assignment("self", "2", std_file),
+            assignment("other", "3", std_file),
+            assignment("y", "4", "src/lib.rs"),
+            assignment("self", "5", std_file),
+        ]);
+        let property = Property::mock("assertion", CheckStatus::Failure)
+            .with_description("assertion failed: x < y")
+            .with_id("check", 1)
+            .with_location("src/lib.rs", 4)
+            .with_trace(serde_json::from_value(trace).unwrap());
+        assert_eq!(
+            trace_excerpt(&property, TraceExcerpt { len: 10, full_trace: false }),
+            [
+                "x = 1 (src/lib.rs:3)",
+                "... 2 assignments in the standard library",
+                "y = 4 (src/lib.rs:3)",
+                "... 1 assignment in the standard library",
+            ]
+        );
+        let full_trace = trace_excerpt(&property, TraceExcerpt { len: 2, full_trace: true });
+        assert_eq!(full_trace[0], "y = 4 (src/lib.rs:3)");
+        assert!(full_trace[1].starts_with("self = 5 ("));
+    }
+}
@@ -0,0 +1,179 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The JUnit XML report of `--output-format junit`, which is displayed natively by the test
+//! report pages of CI systems such as Jenkins, GitLab or Buildkite.
+//!
+//! Each crate is a test suite, and each harness is a test case of its crate. The failure of a
+//! test case lists the failed checks of the harness, followed by an excerpt of the trace of each
+//! failed check, i.e., the last values assigned to user variables before the failure.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::call_cbmc::VerificationStatus;
+use crate::cbmc_output_parser::CheckStatus;
+use crate::json_report::{HarnessReport, PropertyReport};
+
+/// Print the report of `harnesses` to the standard output.
+pub fn print_junit(harnesses: &[HarnessReport]) {
+    println!("{}", junit_report(harnesses));
+}
+
+fn junit_report(harnesses: &[HarnessReport]) -> String {
+    let mut suites: BTreeMap<&str, Vec<&HarnessReport>> = BTreeMap::new();
+    for harness in harnesses {
+        suites.entry(&harness.crate_name).or_default().push(harness);
+    }
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(report, "<testsuites name=\"kani\" {}>", counters(harnesses.iter())).unwrap();
+    for (crate_name, mut harnesses) in suites {
+        harnesses.sort_by(|a, b| a.name.cmp(&b.name));
+        writeln!(
+            report,
+            "  <testsuite name=\"{}\" {}>",
+            escape(crate_name),
+            counters(harnesses.iter().copied())
+        )
+        .unwrap();
+        for harness in harnesses {
+            write_test_case(&mut report, harness);
+        }
+        report.push_str("  </testsuite>\n");
+    }
+    report.push_str("</testsuites>");
+    report
+}
+
+/// The `tests`, `failures` and `time` attributes of a group of harnesses.
+fn counters<'a>(harnesses: impl Iterator<Item = &'a HarnessReport>) -> String {
+    let (mut tests, mut failures, mut time) = (0, 0, 0.0);
+    for harness in harnesses {
+        tests += 1;
+        failures += usize::from(harness.status == VerificationStatus::Failure);
+        time += harness.runtime_secs;
+    }
+    format!("tests=\"{tests}\" failures=\"{failures}\" time=\"{time:.3}\"")
+}
+
+fn write_test_case(report: &mut String, harness: &HarnessReport) {
+    write!(
+        report,
+        "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\" time=\"{:.3}\"",
+        escape(&harness.name),
+        escape(&harness.crate_name),
+        escape(&harness.file),
+        harness.start_line,
+        harness.runtime_secs
+    )
+    .unwrap();
+    if harness.status == VerificationStatus::Success {
+        report.push_str("/>\n");
+        return;
+    }
+    let failed: Vec<_> =
+        harness.properties.iter().filter(|prop| prop.status == CheckStatus::Failure).collect();
+    let message = match (failed.len(), harness.cbmc_exit_status) {
+        (0, Some(status)) => format!("CBMC failed with status {status}"),
+        (0, None) => "verification failed".to_string(),
+        (count, _) => format!("{count} of {} checks failed", harness.properties.len()),
+    };
+    writeln!(
+        report,
+        ">\n      <failure message=\"{}\" type=\"VerificationFailure\">{}</failure>",
+        escape(&message),
+        escape(&failure_details(&failed))
+    )
+    .unwrap();
+    report.push_str("    </testcase>\n");
+}
+
+/// The failed checks followed by their trace excerpts.
+fn failure_details(failed: &[&PropertyReport]) -> String {
+    let mut details = String::new();
+    for property in failed {
+        let location = &property.location;
+        let line = location.line.map(|line| format!(":{line}")).unwrap_or_default();
+        let file = location.file.as_deref().unwrap_or("unknown file");
+        writeln!(details, "Failed check: {} ({file}{line})", property.description).unwrap();
+        if !property.trace_excerpt.is_empty() {
+            details.push_str("Trace excerpt:\n");
+            for step in &property.trace_excerpt {
+                writeln!(details, "  {step}").unwrap();
+            }
+        }
+    }
+    details
+}
+
+/// Escape the characters that can't appear in XML or HTML attributes and text.
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_report::PropertyLocation;
+
+    fn harness(name: &str, status: VerificationStatus) -> HarnessReport {
+        HarnessReport {
+            name: name.to_string(),
+            crate_name: "my_crate".to_string(),
+            file: "src/lib.rs".to_string(),
+            start_line: 3,
+            end_line: 8,
+            status,
+            runtime_secs: 0.5,
+            cbmc_exit_status: None,
+            coverage_file: None,
+            properties: vec![],
+            profile: Default::default(),
+        }
+    }
+
+    #[test]
+    fn check_junit_report() {
+        let mut failing = harness("check_fail", VerificationStatus::Failure);
+        failing.properties.push(PropertyReport {
+            id: "check_fail.assertion.1".to_string(),
+            class: "assertion".to_string(),
+            description: "x < 100 && y > \"a\"".to_string(),
+            status: CheckStatus::Failure,
+            location: PropertyLocation {
+                file: Some("src/lib.rs".to_string()),
+                function: Some("check_fail".to_string()),
+                line: Some(6),
+                column: Some(5),
+            },
+            trace_excerpt: vec!["x = 101 (src/lib.rs:5:13)".to_string()],
+        });
+        let report = junit_report(&[failing, harness("check_pass", VerificationStatus::Success)]);
+        assert_eq!(
+            report,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="kani" tests="2" failures="1" time="1.000">
+  <testsuite name="my_crate" tests="2" failures="1" time="1.000">
+    <testcase name="check_fail" classname="my_crate" file="src/lib.rs" line="3" time="0.500">
+      <failure message="1 of 1 checks failed" type="VerificationFailure">Failed check: x &lt; 100 &amp;&amp; y &gt; &quot;a&quot; (src/lib.rs:6)
+Trace excerpt:
+  x = 101 (src/lib.rs:5:13)
+</failure>
+    </testcase>
+    <testcase name="check_pass" classname="my_crate" file="src/lib.rs" line="3" time="0.500"/>
+  </testsuite>
+</testsuites>"#
+        );
+    }
+}
@@ -0,0 +1,79 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--proof-mode k-induction`.
+//!
+//! A harness is proven by k-induction in two steps:
+//!  1. The base case checks that the properties hold for the first `k` iterations of every loop.
+//!  2. The step case checks that, if the properties hold for `k` consecutive iterations of a
+//!     loop, then they also hold for the next one.
+//!
+//! The harness is only reported as successful if both cases succeed, in which case the properties
+//! hold for any number of loop iterations. If the step case fails, the result is inconclusive: the
+//! properties are only proven for the first `k` iterations, and the step case counterexamples may
+//! not be reachable from the start of the harness.
+
+use anyhow::Result;
+use kani_metadata::HarnessMetadata;
+use std::path::Path;
+
+use crate::call_cbmc::{VerificationResult, VerificationStatus};
+use crate::session::KaniSession;
+use crate::util::alter_extension;
+
+/// The two cases of a k-induction proof, as instrumented by goto-instrument.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KInductionCase {
+    Base,
+    Step,
+}
+
+impl KInductionCase {
+    pub fn flag(self) -> &'static str {
+        match self {
+            KInductionCase::Base => "--base-case",
+            KInductionCase::Step => "--step-case",
+        }
+    }
+}
+
+impl KaniSession {
+    /// Prove a harness with k-induction. Returns the result of the first case that failed, or the
+    /// result of the base case if both succeeded, along with a message that explains the outcome.
+    pub fn run_k_induction(
+        &self,
+        binary: &Path,
+        harness: &HarnessMetadata,
+    ) -> Result<(VerificationResult, String)> {
+        let k = self.args.k_induction_depth;
+
+        let base_case = alter_extension(binary, "base-case.out");
+        self.record_temporary_file(&base_case);
+        self.instrument_k_induction(binary, &base_case, k, KInductionCase::Base)?;
+        let base_result = self.run_cbmc(&base_case, harness)?;
+        if base_result.status == VerificationStatus::Failure {
+            let message = format!("K-induction: the base case failed for k = {k}.");
+            return Ok((base_result, message));
+        }
+
+        let step_case = alter_extension(binary, "step-case.out");
+        self.record_temporary_file(&step_case);
+        self.instrument_k_induction(binary, &step_case, k, KInductionCase::Step)?;
+        let step_result = self.run_cbmc(&step_case, harness)?;
+        if step_result.status == VerificationStatus::Success {
+            let message = format!(
+                "K-induction: the properties hold for any number of loop iterations \
+                (proven with k = {k})."
+            );
+            Ok((base_result, message))
+        } else {
+            let message = format!(
+                "K-induction: inconclusive, the step case failed for k = {k}. The base case \
+                succeeded, so the properties are only proven for the first {k} loop iterations, \
+                and the failures of the step case may not be reachable. Increasing \
+                `--k-induction-depth` may help proving them for any number of iterations."
+            );
+            Ok((step_result, message))
+        }
+    }
+}
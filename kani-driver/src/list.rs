@@ -0,0 +1,252 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `cargo kani list`, which lists the proof harnesses of a package with their
+//! attributes, so external tools can plan and audit a proof suite without verifying it.
+//!
+//! With `--format json`, the list is printed as a JSON document:
+//!
+//! ```json
+//! {
+//!   "schema_version": 1,
+//!   "kani_version": "0.51.0",
+//!   "harnesses": [
+//!     {
+//!       "name": "proofs::check_div",
+//!       "crate": "my_crate",
+//!       "file": "src/lib.rs",
+//!       "start_line": 12,
+//!       "end_line": 16,
+//!       "kind": "proof_for_contract",
+//!       "checked_contract": "div",
+//!       "requires": ["divisor != 0"],
+//!       "ensures": ["|result| *result <= dividend"],
+//!       "modifies": [],
+//!       "verified_stubs": [],
+//!       "stubs": [{ "original": "rand::random", "replacement": "mock_random" }],
+//!       "unwind": 4
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The `kind` of a harness is `proof_for_contract` if it checks the contract of the
+//! `checked_contract` function, `should_panic` if it's expected to panic, and `proof` otherwise.
+//! The `requires`, `ensures` and `modifies` clauses of the checked contract are listed as they
+//! are written in the source. The `verified_stubs` are the functions replaced by their contract with
+//! `#[kani::stub_verified]`, and the `stubs` are the functions replaced with `#[kani::stub]`.
+//!
+//! The harnesses are listed from the metadata written by the compiler, without generating their
+//! code.
+//!
+//! The schema is versioned by [SCHEMA_VERSION]: fields may be added without changing the
+//! version, but removing or changing the meaning of a field requires a new version.
+
+use anyhow::Result;
+use kani_metadata::{HarnessMetadata, Stub};
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::args::list_args::{CargoListArgs, ListFormat};
+use crate::project::cargo_project;
+use crate::session::KaniSession;
+use crate::version::KANI_VERSION;
+
+/// The version of the schema of the document.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The harnesses of a package.
+#[derive(Debug, Serialize)]
+struct HarnessList {
+    schema_version: u32,
+    kani_version: String,
+    harnesses: Vec<ListedHarness>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum HarnessKind {
+    Proof,
+    ProofForContract,
+    ShouldPanic,
+}
+
+#[derive(Debug, Serialize)]
+struct ListedHarness {
+    name: String,
+    #[serde(rename = "crate")]
+    crate_name: String,
+    file: String,
+    start_line: usize,
+    end_line: usize,
+    kind: HarnessKind,
+    /// The function whose contract is checked by the harness, if any.
+    checked_contract: Option<String>,
+    /// The preconditions of the checked contract.
+    requires: Vec<String>,
+    /// The postconditions of the checked contract.
+    ensures: Vec<String>,
+    /// The memory that the checked function may modify.
+    modifies: Vec<String>,
+    /// The functions replaced by their contract.
+    verified_stubs: Vec<String>,
+    /// The functions replaced by another function.
+    stubs: Vec<Stub>,
+    unwind: Option<u32>,
+}
+
+impl ListedHarness {
+    fn new(harness: &HarnessMetadata) -> Self {
+        let attributes = &harness.attributes;
+        let kind = if attributes.proof_for_contract.is_some() {
+            HarnessKind::ProofForContract
+        } else if attributes.should_panic {
+            HarnessKind::ShouldPanic
+        } else {
+            HarnessKind::Proof
+        };
+        // Contracts are implemented by stubbing the contracted functions, so these stubs are
+        // reported as contracts rather than as stubs.
+        let stubs = attributes
+            .stubs
+            .iter()
+            .filter(|stub| {
+                attributes.proof_for_contract.as_ref() != Some(&stub.original)
+                    && !attributes.verified_stubs.contains(&stub.original)
+            })
+            .cloned()
+            .collect();
+        ListedHarness {
+            name: harness.pretty_name.clone(),
+            crate_name: harness.crate_name.clone(),
+            file: harness.original_file.clone(),
+            start_line: harness.original_start_line,
+            end_line: harness.original_end_line,
+            kind,
+            checked_contract: attributes.proof_for_contract.clone(),
+            requires: attributes.contract_clauses.requires.clone(),
+            ensures: attributes.contract_clauses.ensures.clone(),
+            modifies: attributes.contract_clauses.modifies.clone(),
+            verified_stubs: attributes.verified_stubs.clone(),
+            stubs,
+            unwind: attributes.unwind_value,
+        }
+    }
+}
+
+/// Compile the package without generating the code of its harnesses, and print its harnesses,
+/// including the harnesses selected by `--harness` if any.
+pub fn list_cargo(mut session: KaniSession, args: CargoListArgs) -> Result<()> {
+    session.metadata_only = true;
+    let project = cargo_project(&session, false)?;
+    let mut harnesses = session.determine_targets(&project.get_all_harnesses())?;
+    harnesses.sort_by(|a, b| (&a.crate_name, &a.pretty_name).cmp(&(&b.crate_name, &b.pretty_name)));
+    let list = HarnessList {
+        schema_version: SCHEMA_VERSION,
+        kani_version: KANI_VERSION.to_string(),
+        harnesses: harnesses.into_iter().map(ListedHarness::new).collect(),
+    };
+    match args.format {
+        ListFormat::Pretty => print!("{}", pretty(&list)),
+        ListFormat::Json => println!("{}", serde_json::to_string_pretty(&list)?),
+    }
+    Ok(())
+}
+
+fn pretty(list: &HarnessList) -> String {
+    let mut output = String::new();
+    for harness in &list.harnesses {
+        let kind = match harness.kind {
+            HarnessKind::Proof => "proof",
+            HarnessKind::ProofForContract => "proof for contract",
+            HarnessKind::ShouldPanic => "should panic",
+        };
+        writeln!(output, "{} ({kind}) in {}:{}", harness.name, harness.file, harness.start_line)
+            .unwrap();
+        if let Some(function) = &harness.checked_contract {
+            writeln!(output, "  - Checks the contract of: {function}").unwrap();
+        }
+        for (name, clauses) in [
+            ("Requires", &harness.requires),
+            ("Ensures", &harness.ensures),
+            ("Modifies", &harness.modifies),
+        ] {
+            for clause in clauses {
+                writeln!(output, "    - {name}: {clause}").unwrap();
+            }
+        }
+        if !harness.verified_stubs.is_empty() {
+            let stubs = harness.verified_stubs.join(", ");
+            writeln!(output, "  - Uses the contracts of: {stubs}").unwrap();
+        }
+        for stub in &harness.stubs {
+            writeln!(output, "  - Stubs: {} with {}", stub.original, stub.replacement).unwrap();
+        }
+        if let Some(unwind) = harness.unwind {
+            writeln!(output, "  - Unwind: {unwind}").unwrap();
+        }
+    }
+    writeln!(output, "Found {} harnesses.", list.harnesses.len()).unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::mock_proof_harness;
+
+    fn stub(original: &str, replacement: &str) -> Stub {
+        Stub { original: original.to_string(), replacement: replacement.to_string() }
+    }
+
+    #[test]
+    fn check_list() {
+        let mut contract = mock_proof_harness("check_div", Some(4), Some("my_crate"), None);
+        contract.attributes.proof_for_contract = Some("div".to_string());
+        contract.attributes.contract_clauses.requires = vec!["divisor != 0".to_string()];
+        contract.attributes.contract_clauses.ensures =
+            vec!["|result| *result <= dividend".to_string()];
+        contract.attributes.verified_stubs = vec!["mul".to_string()];
+        contract.attributes.stubs = vec![
+            stub("rand::random", "mock_random"),
+            stub("div", "div_check"),
+            stub("mul", "mul_replace"),
+        ];
+        let mut panics = mock_proof_harness("check_panic", None, Some("my_crate"), None);
+        panics.attributes.should_panic = true;
+
+        let list = HarnessList {
+            schema_version: SCHEMA_VERSION,
+            kani_version: KANI_VERSION.to_string(),
+            harnesses: vec![ListedHarness::new(&contract), ListedHarness::new(&panics)],
+        };
+        let json = serde_json::to_value(&list).unwrap();
+        let listed = &json["harnesses"][0];
+        assert_eq!(listed["crate"], "my_crate");
+        assert_eq!(listed["kind"], "proof_for_contract");
+        assert_eq!(listed["checked_contract"], "div");
+        assert_eq!(listed["requires"], serde_json::json!(["divisor != 0"]));
+        assert_eq!(listed["ensures"], serde_json::json!(["|result| *result <= dividend"]));
+        assert_eq!(listed["modifies"], serde_json::json!([]));
+        assert_eq!(listed["verified_stubs"], serde_json::json!(["mul"]));
+        assert_eq!(
+            listed["stubs"],
+            serde_json::json!([{ "original": "rand::random", "replacement": "mock_random" }])
+        );
+        assert_eq!(listed["unwind"], 4);
+        assert_eq!(json["harnesses"][1]["kind"], "should_panic");
+
+        assert_eq!(
+            pretty(&list),
+            "check_div (proof for contract) in <unknown>:0\n  \
+            - Checks the contract of: div\n    \
+            - Requires: divisor != 0\n    \
+            - Ensures: |result| *result <= dividend\n  \
+            - Uses the contracts of: mul\n  \
+            - Stubs: rand::random with mock_random\n  \
+            - Unwind: 4\n\
+            check_panic (should panic) in <unknown>:0\n\
+            Found 2 harnesses.\n"
+        );
+    }
+}
@@ -6,11 +6,13 @@
 
 use anyhow::Result;
 
+use args::list_args::ListFormat;
 use args::{check_is_valid, CargoKaniSubcommand};
 use args_toml::join_args;
 
 use crate::args::StandaloneSubcommand;
-use crate::concrete_playback::playback::{playback_cargo, playback_standalone};
+use crate::concrete_playback::playback::{playback_cargo, playback_standalone, test_cargo};
+use crate::json_report::{export_reports, exports_reports, save_last_run, HarnessReport};
 use crate::project::Project;
 use crate::session::KaniSession;
 use crate::version::print_kani_version;
@@ -19,7 +21,10 @@
 
 mod args;
 mod args_toml;
+mod artifacts;
 mod assess;
+mod assumption_suggestions;
+mod baseline;
 mod call_cargo;
 mod call_cbmc;
 mod call_cbmc_viewer;
@@ -30,12 +35,35 @@
 mod cbmc_output_parser;
 mod cbmc_property_renderer;
 mod concrete_playback;
+mod coverage;
+mod evidence_bundle;
+mod formula_dump;
+mod github_annotations;
+mod goto_export;
 mod harness_runner;
+mod heap_graph;
+mod html_report;
+mod incremental;
+mod json_report;
+mod junit;
+mod k_induction;
+mod list;
 mod metadata;
+mod minimize;
+mod profile;
 mod project;
+mod property_stats;
+mod sarif;
+mod scheduler;
 mod session;
+mod severity;
+mod shard;
+mod trace_values;
 mod util;
+mod verification_cache;
 mod version;
+mod watch;
+mod workspace;
 
 /// The main function for the `kani-driver`.
 /// The driver can be invoked via `cargo kani` and `kani` commands, which determines what kind of
@@ -60,14 +88,23 @@ fn main() -> ExitCode {
 }
 
 /// The main function for the `cargo kani` command.
-fn cargokani_main(input_args: Vec<OsString>) -> Result<()> {
-    let input_args = join_args(input_args)?;
-    let args = args::CargoKaniArgs::parse_from(&input_args);
+fn cargokani_main(cmd_args: Vec<OsString>) -> Result<()> {
+    let input_args = join_args(cmd_args.clone())?;
+    let mut args = args::CargoKaniArgs::parse_from(&input_args);
     check_is_valid(&args);
-
+    // Watch mode only verifies the harnesses affected by each change.
+    args.verify_opts.incremental |= args.verify_opts.watch;
+
+    // The merged results and the JSON list of harnesses may be printed to the standard output.
+    let prints_results = match &args.command {
+        Some(CargoKaniSubcommand::MergeResults(_)) => true,
+        Some(CargoKaniSubcommand::List(list)) => list.format == ListFormat::Json,
+        _ => false,
+    };
+    args.verify_opts.common_args.quiet |= prints_results;
     let session = session::KaniSession::new(args.verify_opts)?;
 
-    if !session.args.common_args.quiet {
+    if !session.args.common_args.quiet && !prints_results {
         print_kani_version(InvocationType::CargoKani(input_args));
     }
 
@@ -78,6 +115,21 @@ fn cargokani_main(input_args: Vec<OsString>) -> Result<()> {
         Some(CargoKaniSubcommand::Playback(args)) => {
             return playback_cargo(*args);
         }
+        Some(CargoKaniSubcommand::Test(args)) => {
+            return test_cargo(*args);
+        }
+        Some(CargoKaniSubcommand::List(args)) => {
+            return list::list_cargo(session, *args);
+        }
+        Some(CargoKaniSubcommand::ReplayGoto(args)) => {
+            return goto_export::replay_goto(*args);
+        }
+        Some(CargoKaniSubcommand::Report(args)) => {
+            return html_report::report(*args);
+        }
+        Some(CargoKaniSubcommand::MergeResults(args)) => {
+            return shard::merge_results(*args);
+        }
         None => {}
     }
 
@@ -85,6 +137,14 @@ fn cargokani_main(input_args: Vec<OsString>) -> Result<()> {
         return assess::run_assess(session, assess::AssessArgs::default());
     }
 
+    if session.args.watch {
+        return watch::watch(session);
+    }
+
+    if let Some((packages, metadata)) = workspace::selected_packages(&session)? {
+        return workspace::verify_packages(session, &cmd_args, &packages, &metadata);
+    }
+
     let project = project::cargo_project(&session, false)?;
     if session.args.only_codegen { Ok(()) } else { verify_project(project, session) }
 }
@@ -94,8 +154,11 @@ fn standalone_main() -> Result<()> {
     let args = args::StandaloneArgs::parse();
     check_is_valid(&args);
 
-    if let Some(StandaloneSubcommand::Playback(args)) = args.command {
-        return playback_standalone(*args);
+    match args.command {
+        Some(StandaloneSubcommand::Playback(args)) => return playback_standalone(*args),
+        Some(StandaloneSubcommand::ReplayGoto(args)) => return goto_export::replay_goto(*args),
+        Some(StandaloneSubcommand::MergeResults(args)) => return shard::merge_results(*args),
+        None => {}
     }
 
     let session = session::KaniSession::new(args.verify_opts)?;
@@ -118,6 +181,11 @@ fn verify_project(project: Project, session: KaniSession) -> Result<()> {
     let runner = harness_runner::HarnessRunner { sess: &session, project: &project };
     let results = runner.check_all_harnesses(&harnesses)?;
 
+    let harnesses = HarnessReport::from_results(&session.args, &project.outdir, &results);
+    if exports_reports(&session.args) {
+        export_reports(&session.args, harnesses.clone())?;
+    }
+    save_last_run(&session.args, project.cargo_metadata.as_ref(), harnesses)?;
     session.print_final_summary(&results)
 }
 
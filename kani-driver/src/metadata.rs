@@ -6,14 +6,15 @@
 use tracing::{debug, trace};
 
 use kani_metadata::{
-    HarnessAttributes, HarnessMetadata, InternedString, KaniMetadata, TraitDefinedMethod,
-    VtableCtxResults,
+    HarnessAttributes, HarnessFilter, HarnessMetadata, InternedString, KaniMetadata,
+    TraitDefinedMethod, VtableCtxResults,
 };
 use std::collections::{BTreeSet, HashMap};
 use std::fs::File;
 use std::io::{BufReader, BufWriter};
 
 use crate::session::KaniSession;
+use crate::shard::{load_timings, shard_harnesses, HarnessTimings};
 use serde::Deserialize;
 
 /// From either a file or a path with multiple files, output the CBMC restrictions file we should use.
@@ -50,13 +51,30 @@ fn link_function_pointer_restrictions(
     data_per_crate: Vec<VtableCtxResults>,
     output_filename: &Path,
 ) -> Result<()> {
+    let output = restrictions_per_call_site(data_per_crate);
+    let f = File::create(output_filename)?;
+    let f = BufWriter::new(f);
+    serde_json::to_writer(f, &output)?;
+    Ok(())
+}
+
+/// The possible targets of every virtual call site, in CBMC's format.
+fn restrictions_per_call_site(
+    data_per_crate: Vec<VtableCtxResults>,
+) -> HashMap<String, Vec<InternedString>> {
     // Combine all method possibilities into one global mapping
     let mut combined_possible_methods: HashMap<TraitDefinedMethod, Vec<InternedString>> =
         HashMap::new();
     for crate_data in &data_per_crate {
         for entry in &crate_data.possible_methods {
-            combined_possible_methods
-                .insert(entry.trait_method.clone(), entry.possibilities.clone());
+            // The vtables of a trait may be generated by several crates.
+            let possibilities =
+                combined_possible_methods.entry(entry.trait_method.clone()).or_default();
+            for possibility in &entry.possibilities {
+                if !possibilities.contains(possibility) {
+                    possibilities.push(*possibility);
+                }
+            }
         }
     }
 
@@ -75,11 +93,7 @@ fn link_function_pointer_restrictions(
             output.insert(cbmc_call_site_name, possibilities);
         }
     }
-
-    let f = File::create(output_filename)?;
-    let f = BufWriter::new(f);
-    serde_json::to_writer(f, &output)?;
-    Ok(())
+    output
 }
 
 /// Deserialize a json file into a given structure
@@ -111,9 +125,24 @@ pub fn merge_kani_metadata(files: Vec<KaniMetadata>) -> KaniMetadata {
 
 impl KaniSession {
     /// Determine which function to use as entry point, based on command-line arguments and kani-metadata.
+    /// With `--shard`, only the harnesses of the shard are selected.
     pub fn determine_targets<'a>(
         &self,
         all_harnesses: &[&'a HarnessMetadata],
+    ) -> Result<Vec<&'a HarnessMetadata>> {
+        let targets = self.filter_targets(all_harnesses)?;
+        let Some(shard) = self.args.shard else { return Ok(targets) };
+        let timings = match &self.args.shard_timings {
+            Some(path) => load_timings(path)?,
+            None => HarnessTimings::new(),
+        };
+        Ok(shard_harnesses(targets, shard, &timings))
+    }
+
+    /// The harnesses selected by the harness filters.
+    fn filter_targets<'a>(
+        &self,
+        all_harnesses: &[&'a HarnessMetadata],
     ) -> Result<Vec<&'a HarnessMetadata>> {
         let harnesses = if self.args.harnesses.is_empty() {
             BTreeSet::from_iter(self.args.function.iter())
@@ -121,32 +150,44 @@ pub fn determine_targets<'a>(
             BTreeSet::from_iter(self.args.harnesses.iter())
         };
 
-        let total_harnesses = harnesses.len();
         let all_targets = &harnesses;
 
+        let excluded = BTreeSet::from_iter(self.args.exclude_harnesses.iter());
+        let all_harnesses = &all_harnesses
+            .iter()
+            .copied()
+            .filter(|harness| !matches_filters(&excluded, harness, self.args.exact))
+            .collect::<Vec<_>>();
+
         if harnesses.is_empty() {
-            Ok(Vec::from(all_harnesses))
+            Ok(all_harnesses.clone())
         } else {
             let harnesses_found: Vec<&HarnessMetadata> =
                 find_proof_harnesses(&harnesses, all_harnesses, self.args.exact);
 
             // If even one harness was not found with --exact, return an error to user
-            if self.args.exact && harnesses_found.len() < total_harnesses {
-                let harness_found_names: BTreeSet<&String> =
-                    harnesses_found.iter().map(|&h| &h.pretty_name).collect();
-
-                // Check which harnesses are missing from the difference of targets and harnesses_found
-                let harnesses_missing: Vec<&String> =
-                    all_targets.difference(&harness_found_names).cloned().collect();
-                let joined_string = harnesses_missing
+            if self.args.exact {
+                // Check which filters don't match any of the harnesses found
+                let harnesses_missing: Vec<&String> = all_targets
                     .iter()
-                    .map(|&s| (*s).clone())
-                    .collect::<Vec<String>>()
-                    .join("`, `");
+                    .filter(|&&target| {
+                        !harnesses_found.iter().any(|harness| {
+                            matches_filters(&BTreeSet::from([target]), harness, true)
+                        })
+                    })
+                    .copied()
+                    .collect();
+                if !harnesses_missing.is_empty() {
+                    let joined_string = harnesses_missing
+                        .iter()
+                        .map(|&s| (*s).clone())
+                        .collect::<Vec<String>>()
+                        .join("`, `");
 
-                bail!(
-                    "Failed to match the following harness(es):\n{joined_string}\nPlease specify the fully-qualified name of a harness.",
-                );
+                    bail!(
+                        "Failed to match the following harness(es):\n{joined_string}\nPlease specify the fully-qualified name of a harness.",
+                    );
+                }
             }
 
             Ok(harnesses_found)
@@ -185,6 +226,7 @@ pub fn mock_proof_harness(
         attributes: HarnessAttributes { unwind_value, proof: true, ..Default::default() },
         goto_file: model_file,
         contract: Default::default(),
+        codegen_time_secs: None,
     }
 }
 
@@ -199,29 +241,24 @@ fn find_proof_harnesses<'a>(
     debug!(?targets, "find_proof_harness");
     let mut result = vec![];
     for md in all_harnesses.iter() {
-        if exact_filter {
-            // Check for exact match only
-            if targets.contains(&md.pretty_name) {
-                // if exact match found, stop searching
-                result.push(*md);
-            } else {
-                trace!(skip = md.pretty_name, "find_proof_harnesses");
-            }
+        if matches_filters(targets, md, exact_filter) {
+            result.push(*md);
         } else {
-            // Either an exact match, or a substring match. We check the exact first since it's cheaper.
-            if targets.contains(&md.pretty_name)
-                || targets.contains(&md.get_harness_name_unqualified().to_string())
-                || targets.iter().any(|target| md.pretty_name.contains(*target))
-            {
-                result.push(*md);
-            } else {
-                trace!(skip = md.pretty_name, "find_proof_harnesses");
-            }
+            trace!(skip = md.pretty_name, "find_proof_harnesses");
         }
     }
     result
 }
 
+/// Whether a harness matches any of the `targets` filters.
+fn matches_filters(targets: &BTreeSet<&String>, md: &HarnessMetadata, exact_filter: bool) -> bool {
+    // The filters are validated with the command line arguments.
+    targets
+        .iter()
+        .filter_map(|target| HarnessFilter::parse(target).ok())
+        .any(|filter| filter.matches(md, exact_filter))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,4 +348,60 @@ fn check_find_proof_harness_with_exact() {
             "module::not_check_three"
         );
     }
+
+    #[test]
+    fn check_find_proof_harness_with_patterns() {
+        let harnesses = vec![
+            mock_proof_harness("check_one", None, None, None),
+            mock_proof_harness("parser::check_two", None, None, None),
+            mock_proof_harness("parser::nested::check_three", None, None, None),
+            mock_proof_harness("lexer::check_four", None, None, None),
+        ];
+        let ref_harnesses = harnesses.iter().collect::<Vec<_>>();
+        let find = |filter: &str, exact| {
+            find_proof_harnesses(&BTreeSet::from([&filter.to_string()]), &ref_harnesses, exact)
+                .iter()
+                .map(|harness| harness.pretty_name.as_str())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(find("parser::*", false), ["parser::check_two", "parser::nested::check_three"]);
+        assert_eq!(find("parser::check_*", true), ["parser::check_two"]);
+        // Without `--exact`, globs also match the unqualified name.
+        assert_eq!(find("check_t*", false), ["parser::check_two", "parser::nested::check_three"]);
+        assert!(find("check_t*", true).is_empty());
+        assert_eq!(
+            find("regex:^(lexer|parser)::check_", false),
+            ["parser::check_two", "lexer::check_four"]
+        );
+        assert_eq!(find("regex:one$", true), ["check_one"]);
+    }
+
+    #[test]
+    fn check_restrictions_per_call_site() {
+        use kani_metadata::{CallSite, PossibleMethodEntry};
+        let method = |vtable_idx| TraitDefinedMethod { trait_name: "Shape".into(), vtable_idx };
+        let crate_data = |call_sites, possibilities: &[&str]| VtableCtxResults {
+            call_sites,
+            possible_methods: vec![PossibleMethodEntry {
+                trait_method: method(3),
+                possibilities: possibilities.iter().map(|p| (*p).into()).collect(),
+            }],
+        };
+        let call_site = |function_name: &str, vtable_idx| CallSite {
+            trait_method: method(vtable_idx),
+            function_name: function_name.into(),
+            label: "restricted_call_label_1".into(),
+        };
+        let restrictions = restrictions_per_call_site(vec![
+            crate_data(vec![call_site("area", 3), call_site("drop", 0)], &["Square::area"]),
+            crate_data(vec![], &["Rect::area", "Square::area"]),
+        ]);
+        let targets = |call_site: &str| {
+            restrictions[call_site].iter().map(|target| target.to_string()).collect::<Vec<_>>()
+        };
+        // The possible methods of the vtables generated by every crate are combined.
+        assert_eq!(targets("area.restricted_call_label_1"), ["Square::area", "Rect::area"]);
+        assert!(targets("drop.restricted_call_label_1").is_empty());
+    }
 }
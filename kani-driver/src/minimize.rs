@@ -0,0 +1,401 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--minimize-counterexamples`, which searches for smaller counterexamples before
+//! they are reported and played back.
+//!
+//! The solver returns any model of the formula, so the values of a counterexample are often
+//! noise that has nothing to do with the failure, e.g., large integers or long vectors.
+//! With this option, the compiler makes `kani::any()` pass the bytes of every value it creates to
+//! `__KANI_nondet_created`, which does nothing in `kani_lib.c`. To minimize a counterexample, the model of the harness is linked
+//! with a definition of this function that constrains the values, and the property is checked
+//! again on its own. The values are minimized greedily, in the order they're created:
+//!  1. The value is zero, e.g., `false`, the first variant of an enum or the length of an empty
+//!     vector.
+//!  2. Otherwise, if the value is an integer, its magnitude is halved as long as the property can
+//!     still be violated.
+//!
+//! The value is then fixed before minimizing the next one. A value is identified by its offset in
+//! the bytes of all the values, since a nondet array is created at once while the trace reports
+//! each of its elements. The number of solver queries is bounded for each counterexample, so the
+//! result is smaller, but not necessarily minimal.
+
+use anyhow::{bail, Result};
+use kani_metadata::HarnessMetadata;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::call_cbmc::{cbmc_command, VerificationResult};
+use crate::cbmc_output_parser::{extract_results, process_cbmc_output, TraceItem};
+use crate::cbmc_property_renderer::kani_cbmc_output_filter;
+use crate::concrete_playback::test_generator::concrete_vals_extractor::{
+    extract_from_trace_item, has_concrete_values,
+};
+use crate::session::KaniSession;
+use crate::util::alter_extension;
+
+/// The maximum number of solver queries used to minimize a counterexample.
+const MAX_QUERIES: usize = 64;
+
+/// The function of the Kani library that is called with the bytes of every symbolic value.
+const NONDET_HOOK: &str = "__KANI_nondet_created";
+
+/// A symbolic value of a counterexample.
+#[derive(Clone, Debug, PartialEq)]
+struct NondetValue {
+    /// The offset of the value in the bytes of all the values of the trace.
+    offset: usize,
+    /// The bytes of the value, in memory order.
+    bytes: Vec<u8>,
+    /// The value as interpreted by CBMC, e.g., `-3` for an `i8`.
+    interp: String,
+}
+
+impl NondetValue {
+    /// The constraint that halves the magnitude of the value, if it's an integer that can be
+    /// halved.
+    fn halved(&self) -> Option<Constraint> {
+        if ![1, 2, 4, 8, 16].contains(&self.bytes.len()) {
+            return None;
+        }
+        let (magnitude, signed) = match self.interp.strip_prefix('-') {
+            Some(magnitude) => (magnitude.parse::<u128>().ok()?, true),
+            None => (self.interp.parse::<u128>().ok()?, false),
+        };
+        (magnitude > 1).then_some(Constraint::AtMost {
+            offset: self.offset,
+            size: self.bytes.len(),
+            bound: magnitude / 2,
+            signed,
+        })
+    }
+}
+
+/// A constraint on the symbolic value at `offset` in the bytes of all the values.
+#[derive(Clone, Debug, PartialEq)]
+enum Constraint {
+    /// The value has exactly these bytes.
+    Equal { offset: usize, bytes: Vec<u8> },
+    /// The value is an integer of `size` bytes whose magnitude is at most `bound`. The value may
+    /// only be negative if it's `signed`.
+    AtMost { offset: usize, size: usize, bound: u128, signed: bool },
+}
+
+impl Constraint {
+    /// The offset and the size of the constrained value.
+    fn range(&self) -> (usize, usize) {
+        match self {
+            Constraint::Equal { offset, bytes } => (*offset, bytes.len()),
+            Constraint::AtMost { offset, size, .. } => (*offset, *size),
+        }
+    }
+
+    /// The C condition on the bytes `v` of the value.
+    fn condition(&self) -> String {
+        match self {
+            Constraint::Equal { bytes, .. } => bytes
+                .iter()
+                .enumerate()
+                .map(|(idx, byte)| format!("v[{idx}] == {byte}"))
+                .collect::<Vec<_>>()
+                .join(" && "),
+            Constraint::AtMost { size, bound, signed, .. } => {
+                let (unsigned_type, signed_type) = match size {
+                    16 => ("unsigned __int128".to_string(), "__int128".to_string()),
+                    _ => (format!("uint{}_t", 8 * size), format!("int{}_t", 8 * size)),
+                };
+                let value = (0..*size)
+                    .map(|idx| match idx {
+                        0 => format!("({unsigned_type})v[0]"),
+                        _ => format!("({unsigned_type})v[{idx}] << {}", 8 * idx),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+                if *signed {
+                    let bound = c_literal(*bound, &signed_type);
+                    let value = format!("({signed_type})({value})");
+                    format!("-{bound} <= {value} && {value} <= {bound}")
+                } else {
+                    format!("({value}) <= {}", c_literal(*bound, &unsigned_type))
+                }
+            }
+        }
+    }
+}
+
+/// A C literal of the given integer type.
+fn c_literal(value: u128, typ: &str) -> String {
+    match u64::try_from(value) {
+        Ok(value) => format!("({typ}){value}ull"),
+        Err(_) => format!("(({typ}){}ull << 64 | {}ull)", value >> 64, value as u64),
+    }
+}
+
+/// The symbolic values of a trace, with their offset.
+fn nondet_values(trace: &[TraceItem]) -> Vec<NondetValue> {
+    let mut offset = 0;
+    trace
+        .iter()
+        .filter_map(extract_from_trace_item)
+        .map(|value| {
+            let value = NondetValue { offset, bytes: value.byte_arr, interp: value.interp_val };
+            offset += value.bytes.len();
+            value
+        })
+        .filter(|value| !value.bytes.is_empty())
+        .collect()
+}
+
+/// The definition of [NONDET_HOOK] that constrains the symbolic values.
+fn constraints_c_code(constraints: &[Constraint]) -> String {
+    let mut code = format!(
+        "// Generated by Kani to minimize a counterexample.\n\
+        #include <stddef.h>\n\
+        #include <stdint.h>\n\
+        \n\
+        struct Unit;\n\
+        extern struct Unit VoidUnit;\n\
+        size_t __KANI_nondet_offset = 0;\n\
+        \n\
+        struct Unit {NONDET_HOOK}(const uint8_t *value, size_t size)\n\
+        {{\n    \
+            size_t offset = __KANI_nondet_offset;\n    \
+            __KANI_nondet_offset += size;\n"
+    );
+    for constraint in constraints {
+        let (start, size) = constraint.range();
+        let end = start + size;
+        writeln!(code, "    if (offset <= {start} && {end} <= offset + size) {{").unwrap();
+        writeln!(code, "        const uint8_t *v = value + ({start} - offset);").unwrap();
+        writeln!(code, "        __CPROVER_assume({});", constraint.condition()).unwrap();
+        writeln!(code, "    }}").unwrap();
+    }
+    code.push_str("    return VoidUnit;\n}\n");
+    code
+}
+
+/// Minimize the symbolic values of `trace`, where `check` returns the trace of a counterexample
+/// that satisfies the given constraints, if there is one. Returns the smallest trace that was
+/// found, and the number of queries.
+fn minimize_trace(
+    mut trace: Vec<TraceItem>,
+    mut check: impl FnMut(&[Constraint]) -> Result<Option<Vec<TraceItem>>>,
+) -> Result<(Vec<TraceItem>, usize)> {
+    let mut fixed = vec![];
+    let mut queries = 0;
+    let mut index = 0;
+    while let Some(mut value) = nondet_values(&trace).get(index).cloned() {
+        let zero = vec![0; value.bytes.len()];
+        let mut candidate = (value.bytes != zero)
+            .then_some(Constraint::Equal { offset: value.offset, bytes: zero });
+        while let Some(constraint) = candidate.take() {
+            if queries == MAX_QUERIES {
+                return Ok((trace, queries));
+            }
+            queries += 1;
+            let constraints: Vec<_> = fixed.iter().chain([&constraint]).cloned().collect();
+            match check(&constraints)? {
+                Some(new_trace) => {
+                    trace = new_trace;
+                    let Some(new_value) = nondet_values(&trace).get(index).cloned() else {
+                        return Ok((trace, queries));
+                    };
+                    value = new_value;
+                }
+                // The magnitude of the value can't be halved anymore.
+                None if matches!(constraint, Constraint::AtMost { .. }) => break,
+                None => {}
+            }
+            candidate = value.halved();
+        }
+        fixed.push(Constraint::Equal { offset: value.offset, bytes: value.bytes });
+        index += 1;
+    }
+    Ok((trace, queries))
+}
+
+impl KaniSession {
+    /// Replace the counterexamples of `result`, i.e., the traces of its failures and of its
+    /// satisfied cover statements, by smaller ones. Returns the number of solver queries.
+    pub fn minimize_counterexamples(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        result: &mut VerificationResult,
+    ) -> Result<usize> {
+        let Ok(properties) = &mut result.results else { return Ok(0) };
+        let mut queries = 0;
+        for property in properties.iter_mut().filter(|prop| has_concrete_values(prop)) {
+            let Some(trace) = property.trace.take() else { continue };
+            let name = property.property_name();
+            let (trace, property_queries) = minimize_trace(trace, |constraints| {
+                self.check_constrained(file, harness, &name, constraints)
+            })?;
+            property.trace = Some(trace);
+            queries += property_queries;
+        }
+        Ok(queries)
+    }
+
+    /// Check the property `name` of a harness with its symbolic values constrained by
+    /// `constraints`, and return the trace of the counterexample if there is one.
+    fn check_constrained(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        name: &str,
+        constraints: &[Constraint],
+    ) -> Result<Option<Vec<TraceItem>>> {
+        let c_file = alter_extension(file, "minimize.c");
+        let constrained_file = alter_extension(file, "minimize.out");
+        self.record_temporary_files(&[&c_file, &constrained_file]);
+        fs::write(&c_file, constraints_c_code(constraints))?;
+        self.link_c_file(file, &c_file, &constrained_file)?;
+
+        let mut args = self.cbmc_flags(&constrained_file, harness)?;
+        args.push("--property".into());
+        args.push(name.into());
+        args.push("--json-ui".into());
+
+        let mut cmd = cbmc_command();
+        cmd.args(args);
+        let cbmc_process = self.run_piped(cmd)?;
+        let cbmc_process = cbmc_process.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
+        let output = process_cbmc_output(cbmc_process, |i| {
+            kani_cbmc_output_filter(
+                i,
+                self.args.extra_pointer_checks,
+                true,
+                &self.args.output_format,
+            )
+        })?;
+        let (_, Some(results)) = extract_results(output.processed_items) else {
+            bail!(
+                "failed to minimize the counterexample of `{name}` of harness `{}`",
+                harness.pretty_name
+            );
+        };
+        Ok(results
+            .into_iter()
+            .find(|prop| prop.property_name() == name && has_concrete_values(prop))
+            .and_then(|prop| prop.trace)
+            .map(|trace| {
+                // The steps of the constraints aren't part of the counterexample.
+                trace
+                    .into_iter()
+                    .filter(|item| {
+                        item.source_location.as_ref().and_then(|loc| loc.function.as_deref())
+                            != Some(NONDET_HOOK)
+                    })
+                    .collect()
+            }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::{SourceLocation, TraceData, TraceValue};
+
+    /// A trace with a `kani::any()` value of a byte for each of `values`.
+    fn trace(values: &[u8]) -> Vec<TraceItem> {
+        values
+            .iter()
+            .map(|value| TraceItem {
+                step_type: "assignment".to_string(),
+                lhs: Some("goto_symex$$return_value".to_string()),
+                source_location: Some(SourceLocation {
+                    column: None,
+                    file: None,
+                    function: Some("kani::any_raw_internal::<u8>".to_string()),
+                    line: None,
+                }),
+                value: Some(TraceValue {
+                    binary: Some(format!("{value:08b}")),
+                    data: Some(TraceData::NonBool(value.to_string())),
+                    name: None,
+                    width: Some(8),
+                    members: None,
+                    member: None,
+                    elements: None,
+                }),
+                raw_lhs: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn check_minimize_trace() {
+        // The property fails iff `x >= 3 && y != 0`, and the solver returns the largest values
+        // allowed by the constraints.
+        let mut queries = vec![];
+        let (minimized, count) = minimize_trace(trace(&[200, 7]), |constraints| {
+            queries.push(constraints.to_vec());
+            let mut values = [200, 7];
+            for constraint in constraints {
+                let (offset, _) = constraint.range();
+                values[offset] = match constraint {
+                    Constraint::Equal { bytes, .. } => bytes[0],
+                    Constraint::AtMost { bound, .. } => values[offset].min(*bound as u8),
+                };
+            }
+            Ok((values[0] >= 3 && values[1] != 0).then(|| trace(&values)))
+        })
+        .unwrap();
+        let values: Vec<_> =
+            nondet_values(&minimized).into_iter().map(|value| value.bytes[0]).collect();
+        assert_eq!(values, [3, 1]);
+        // `x`: 0, then at most 100, 50, 25, 12, 6, 3 and 1. `y`: 0, then at most 3 and 1.
+        assert_eq!(count, 11);
+        assert_eq!(queries.len(), count);
+        assert_eq!(
+            queries.last().unwrap()[..],
+            [
+                Constraint::Equal { offset: 0, bytes: vec![3] },
+                Constraint::AtMost { offset: 1, size: 1, bound: 1, signed: false }
+            ]
+        );
+    }
+
+    #[test]
+    fn check_halved() {
+        let value = |bytes: Vec<u8>, interp: &str| NondetValue {
+            offset: 4,
+            bytes,
+            interp: interp.to_string(),
+        };
+        assert_eq!(
+            value(vec![0xf9, 0xff], "-7").halved(),
+            Some(Constraint::AtMost { offset: 4, size: 2, bound: 3, signed: true })
+        );
+        assert_eq!(value(vec![1], "1").halved(), None);
+        assert_eq!(value(vec![0, 0, 0xc0, 0x3f], "1.5").halved(), None);
+        assert_eq!(value(vec![0, 0, 1], "65536").halved(), None);
+    }
+
+    #[test]
+    fn check_constraints_c_code() {
+        let code = constraints_c_code(&[
+            Constraint::Equal { offset: 0, bytes: vec![0, 1] },
+            Constraint::AtMost { offset: 2, size: 2, bound: 3, signed: true },
+            Constraint::AtMost { offset: 4, size: 16, bound: 1 << 64, signed: false },
+        ]);
+        assert!(code.contains(
+            "struct Unit __KANI_nondet_created(const uint8_t *value, size_t size)\n{\n    \
+            size_t offset = __KANI_nondet_offset;\n    \
+            __KANI_nondet_offset += size;\n    \
+            if (offset <= 0 && 2 <= offset + size) {\n        \
+            const uint8_t *v = value + (0 - offset);\n        \
+            __CPROVER_assume(v[0] == 0 && v[1] == 1);\n    \
+            }\n"
+        ));
+        assert!(code.contains(
+            "__CPROVER_assume(-(int16_t)3ull <= (int16_t)((uint16_t)v[0] | (uint16_t)v[1] << 8) \
+            && (int16_t)((uint16_t)v[0] | (uint16_t)v[1] << 8) <= (int16_t)3ull);"
+        ));
+        assert!(code.contains(
+            "<= ((unsigned __int128)1ull << 64 | 0ull));\n    }\n    return VoidUnit;\n}\n"
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--profile`, which reports the time spent in each phase of the verification of a
+//! harness and the peak memory usage of CBMC.
+//!
+//! The phases are the generation of the goto model by the compiler, its instrumentation by
+//! `goto-instrument`, and the symbolic execution and the decision procedure of CBMC, whose
+//! runtime is reported by CBMC itself. The profile of each harness is also part of the JSON
+//! output.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::args::ProfileSort;
+use crate::call_cbmc::VerificationResult;
+use crate::harness_runner::HarnessResult;
+
+/// The resources used to verify a harness. A phase is missing if it didn't run, e.g., because the
+/// result was cached, or if its runtime isn't known.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HarnessProfile {
+    pub codegen_secs: Option<f64>,
+    pub instrument_secs: Option<f64>,
+    pub symex_secs: Option<f64>,
+    pub solver_secs: Option<f64>,
+    /// The time spent in all the phases, including the ones that aren't listed, e.g., the
+    /// conversion of the formula by CBMC.
+    pub total_secs: f64,
+    pub peak_memory_kb: Option<u64>,
+}
+
+impl HarnessProfile {
+    pub fn new(result: &HarnessResult<'_>) -> Self {
+        let codegen_secs = result.harness.codegen_time_secs;
+        let instrument_secs = result.instrument_time.map(|time| time.as_secs_f64());
+        let verification = &result.result;
+        HarnessProfile {
+            codegen_secs,
+            instrument_secs,
+            symex_secs: phase_runtime(verification, "Symex"),
+            solver_secs: phase_runtime(verification, "decision procedure"),
+            total_secs: codegen_secs.unwrap_or_default()
+                + instrument_secs.unwrap_or_default()
+                + verification.runtime.as_secs_f64(),
+            peak_memory_kb: verification.peak_memory_kb,
+        }
+    }
+
+    /// The value of the column used to sort the profiles.
+    fn sort_key(&self, sort: ProfileSort) -> Option<f64> {
+        match sort {
+            ProfileSort::Total => Some(self.total_secs),
+            ProfileSort::Codegen => self.codegen_secs,
+            ProfileSort::Instrument => self.instrument_secs,
+            ProfileSort::Symex => self.symex_secs,
+            ProfileSort::Solver => self.solver_secs,
+            ProfileSort::Memory => self.peak_memory_kb.map(|kb| kb as f64),
+        }
+    }
+}
+
+/// The runtime of a CBMC phase, summed over the CBMC invocations of the harness.
+fn phase_runtime(result: &VerificationResult, phase: &str) -> Option<f64> {
+    result
+        .phase_timings
+        .iter()
+        .filter(|timing| timing.phase == phase)
+        .map(|timing| timing.runtime_secs)
+        .reduce(|total, runtime| total + runtime)
+}
+
+/// Format the profiles of the harnesses as a table, sorted by the `sort` column in decreasing
+/// order.
+pub fn format_profiles(profiles: &[(&str, HarnessProfile)], sort: ProfileSort) -> String {
+    let mut sorted: Vec<_> = profiles.iter().collect();
+    sorted.sort_by(|(a_name, a), (b_name, b)| {
+        // Missing values come last.
+        let by_key = match (a.sort_key(sort), b.sort_key(sort)) {
+            (Some(a), Some(b)) => b.total_cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        by_key.then_with(|| a_name.cmp(b_name))
+    });
+    let width = sorted.iter().map(|(name, _)| name.len()).chain(["Harness".len()]).max().unwrap();
+    let mut result = String::from("Verification profile:\n");
+    writeln!(
+        result,
+        "{:<width$}  {:>9}  {:>10}  {:>9}  {:>9}  {:>9}  {:>11}",
+        "Harness", "Codegen", "Instrument", "Symex", "Solver", "Total", "Peak memory"
+    )
+    .unwrap();
+    for (name, profile) in sorted {
+        writeln!(
+            result,
+            "{name:<width$}  {:>9}  {:>10}  {:>9}  {:>9}  {:>9}  {:>11}",
+            format_secs(profile.codegen_secs),
+            format_secs(profile.instrument_secs),
+            format_secs(profile.symex_secs),
+            format_secs(profile.solver_secs),
+            format_secs(Some(profile.total_secs)),
+            profile
+                .peak_memory_kb
+                .map_or("-".to_string(), |kb| format!("{:.1} MB", kb as f64 / 1024.0)),
+        )
+        .unwrap();
+    }
+    result
+}
+
+fn format_secs(secs: Option<f64>) -> String {
+    secs.map_or("-".to_string(), |secs| format!("{secs:.3}s"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::PhaseTiming;
+    use crate::metadata::mock_proof_harness;
+    use std::time::Duration;
+
+    #[test]
+    fn check_harness_profile() {
+        let mut harness = mock_proof_harness("check", None, None, None);
+        harness.codegen_time_secs = Some(0.5);
+        let mut result = VerificationResult::from_properties(vec![], false, Duration::from_secs(2));
+        result.phase_timings = vec![
+            PhaseTiming { phase: "Symex".to_string(), runtime_secs: 0.25 },
+            PhaseTiming { phase: "decision procedure".to_string(), runtime_secs: 1.0 },
+            PhaseTiming { phase: "decision procedure".to_string(), runtime_secs: 0.5 },
+        ];
+        result.peak_memory_kb = Some(2048);
+        let result = HarnessResult {
+            harness: &harness,
+            result,
+            instrument_time: Some(Duration::from_secs(1)),
+        };
+        assert_eq!(
+            HarnessProfile::new(&result),
+            HarnessProfile {
+                codegen_secs: Some(0.5),
+                instrument_secs: Some(1.0),
+                symex_secs: Some(0.25),
+                solver_secs: Some(1.5),
+                total_secs: 3.5,
+                peak_memory_kb: Some(2048),
+            }
+        );
+    }
+
+    #[test]
+    fn check_format_profiles() {
+        let slow = HarnessProfile {
+            codegen_secs: Some(0.5),
+            solver_secs: Some(3.0),
+            total_secs: 4.0,
+            peak_memory_kb: Some(1024),
+            ..Default::default()
+        };
+        let fast = HarnessProfile {
+            codegen_secs: Some(1.0),
+            total_secs: 1.5,
+            peak_memory_kb: Some(512),
+            ..Default::default()
+        };
+        let profiles = [("fast", fast), ("slow_harness", slow)];
+        assert_eq!(
+            format_profiles(&profiles, ProfileSort::Total),
+            "Verification profile:\n\
+            Harness         Codegen  Instrument      Symex     Solver      Total  Peak memory\n\
+            slow_harness     0.500s           -          -     3.000s     4.000s       1.0 MB\n\
+            fast             1.000s           -          -          -     1.500s       0.5 MB\n"
+        );
+        let by_codegen = format_profiles(&profiles, ProfileSort::Codegen);
+        assert!(by_codegen.find("fast").unwrap() < by_codegen.find("slow_harness").unwrap());
+        let by_solver = format_profiles(&profiles, ProfileSort::Solver);
+        assert!(by_solver.find("slow_harness").unwrap() < by_solver.find("fast").unwrap());
+    }
+}
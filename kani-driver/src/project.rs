@@ -118,10 +118,9 @@ fn try_new(
             for harness_metadata in
                 crate_metadata.test_harnesses.iter().chain(crate_metadata.proof_harnesses.iter())
             {
-                let symtab_out = Artifact::try_new(
-                    harness_metadata.goto_file.as_ref().expect("Expected a model file"),
-                    SymTabGoto,
-                )?;
+                // The code of the harnesses that weren't selected isn't generated.
+                let Some(goto_file) = &harness_metadata.goto_file else { continue };
+                let symtab_out = Artifact::try_new(goto_file, SymTabGoto)?;
                 let goto_path = convert_type(&symtab_out.path, symtab_out.typ, Goto);
 
                 // Link
@@ -130,10 +129,12 @@ fn try_new(
 
                 // All other harness artifacts that may have been generated as part of the build.
                 artifacts.extend(
-                    [SymTab, TypeMap, VTableRestriction, PrettyNameMap].iter().filter_map(|typ| {
-                        let artifact = Artifact::try_from(&symtab_out, *typ).ok()?;
-                        Some(artifact)
-                    }),
+                    [SymTab, TypeMap, VTableRestriction, PrettyNameMap, AdtMap].iter().filter_map(
+                        |typ| {
+                            let artifact = Artifact::try_from(&symtab_out, *typ).ok()?;
+                            Some(artifact)
+                        },
+                    ),
                 );
                 artifacts.push(symtab_out);
                 artifacts.push(goto);
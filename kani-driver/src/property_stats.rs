@@ -0,0 +1,129 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--hardest-properties`, which reports the properties of a harness that take the
+//! longest to solve.
+//!
+//! CBMC solves all the properties of a harness together, so each property is checked again
+//! on its own with CBMC's `--property` option to measure how hard it is.
+
+use anyhow::Result;
+use kani_metadata::HarnessMetadata;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::call_cbmc::cbmc_command;
+use crate::cbmc_output_parser::{process_cbmc_output, ParserItem, Property};
+use crate::cbmc_property_renderer::kani_cbmc_output_filter;
+use crate::session::KaniSession;
+
+/// The solving statistics of a single property.
+#[derive(Debug)]
+pub struct PropertyStats {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    /// The time it took CBMC to check the property on its own.
+    pub runtime: Duration,
+    /// The time spent in the decision procedure, as reported by CBMC.
+    pub solver_runtime_secs: Option<f64>,
+}
+
+impl KaniSession {
+    /// Check each property of `properties` on its own and collect their statistics, from the
+    /// slowest to the fastest.
+    pub fn property_stats(
+        &self,
+        file: &Path,
+        harness: &HarnessMetadata,
+        properties: &[Property],
+    ) -> Result<Vec<PropertyStats>> {
+        let mut stats = vec![];
+        for property in properties.iter().filter(|prop| !prop.is_code_coverage_property()) {
+            let name = property.property_name();
+            let mut args = self.cbmc_flags(file, harness)?;
+            args.push("--property".into());
+            args.push((&name).into());
+            args.push("--json-ui".into());
+
+            let mut cmd = cbmc_command();
+            cmd.args(args);
+            let start_time = Instant::now();
+            let cbmc_process = self.run_piped(cmd)?;
+            let cbmc_process = cbmc_process.ok_or(anyhow::Error::msg("Failed to run cbmc"))?;
+            let output = process_cbmc_output(cbmc_process, |i| {
+                kani_cbmc_output_filter(
+                    i,
+                    self.args.extra_pointer_checks,
+                    true,
+                    &self.args.output_format,
+                )
+            })?;
+            let solver_runtime_secs = output
+                .processed_items
+                .iter()
+                .filter_map(ParserItem::phase_timing)
+                .filter(|timing| timing.phase == "decision procedure")
+                .map(|timing| timing.runtime_secs)
+                .reduce(|total, runtime| total + runtime);
+            stats.push(PropertyStats {
+                name,
+                description: property.description.clone(),
+                location: property.source_location.to_string(),
+                runtime: start_time.elapsed(),
+                solver_runtime_secs,
+            });
+        }
+        stats.sort_by(|a, b| b.runtime.cmp(&a.runtime));
+        Ok(stats)
+    }
+}
+
+/// Format the `count` hardest properties of a harness.
+pub fn format_hardest_properties(stats: &[PropertyStats], count: usize) -> String {
+    let mut result = format!("\nHardest Properties (top {count}):\n");
+    for (idx, stat) in stats.iter().take(count).enumerate() {
+        let solver_time = stat
+            .solver_runtime_secs
+            .map_or(String::new(), |secs| format!(", solver time: {secs}s"));
+        writeln!(
+            result,
+            "{}. {}: {} ({})\n   time: {:.3}s{solver_time}",
+            idx + 1,
+            stat.name,
+            stat.description,
+            stat.location,
+            stat.runtime.as_secs_f64(),
+        )
+        .unwrap();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(name: &str, millis: u64, solver_runtime_secs: Option<f64>) -> PropertyStats {
+        PropertyStats {
+            name: name.to_string(),
+            description: format!("{name} description"),
+            location: "src/lib.rs:1:1".to_string(),
+            runtime: Duration::from_millis(millis),
+            solver_runtime_secs,
+        }
+    }
+
+    #[test]
+    fn check_format_hardest_properties() {
+        let stats = [stats("f.assertion.1", 2500, Some(2.25)), stats("f.assertion.2", 10, None)];
+        assert_eq!(
+            format_hardest_properties(&stats, 1),
+            "\nHardest Properties (top 1):\n\
+            1. f.assertion.1: f.assertion.1 description (src/lib.rs:1:1)\n   \
+            time: 2.500s, solver time: 2.25s\n"
+        );
+        assert!(format_hardest_properties(&stats, 5).contains("2. f.assertion.2"));
+    }
+}
@@ -0,0 +1,307 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Export the verification failures with `--sarif <FILE>`, using the
+//! [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) format.
+//!
+//! SARIF is the format consumed by GitHub code scanning and other static analysis dashboards, which
+//! display each result inline at its source location. Every failed property of a failing harness
+//! is reported as a result whose rule is the property class, e.g., `assertion` or
+//! `arithmetic_overflow`. A failing harness without any failed property, e.g., because CBMC
+//! crashed or because a `#[kani::should_panic]` harness didn't panic, is reported at the location
+//! of the harness.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+use crate::call_cbmc::VerificationStatus;
+use crate::cbmc_output_parser::CheckStatus;
+use crate::json_report::HarnessReport;
+use crate::version::KANI_VERSION;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+/// The rule of the results reported at the location of a harness.
+const HARNESS_RULE: &str = "harness";
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<Rule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule {
+    id: String,
+    short_description: Message,
+}
+
+#[derive(Debug, Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    level: &'static str,
+    message: Message,
+    locations: Vec<Location>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    region: Region,
+}
+
+#[derive(Debug, Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_column: Option<usize>,
+}
+
+/// Write the failures of `harnesses` to `path`.
+pub fn write_sarif(path: &Path, harnesses: &[HarnessReport]) -> Result<()> {
+    let log = sarif_log(harnesses);
+    let writer = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(writer, &log)?;
+    Ok(())
+}
+
+fn sarif_log(harnesses: &[HarnessReport]) -> SarifLog {
+    let results: Vec<_> = harnesses
+        .iter()
+        .filter(|harness| harness.status == VerificationStatus::Failure)
+        .flat_map(harness_results)
+        .collect();
+    let rules = results
+        .iter()
+        .map(|result| result.rule_id.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|id| Rule { short_description: Message { text: rule_description(&id) }, id })
+        .collect();
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "Kani",
+                    version: KANI_VERSION,
+                    information_uri: "https://github.com/model-checking/kani",
+                    rules,
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// The results of a failing harness.
+fn harness_results(harness: &HarnessReport) -> Vec<SarifResult> {
+    let failures: Vec<_> = harness
+        .properties
+        .iter()
+        .filter(|property| property.status == CheckStatus::Failure)
+        .map(|property| {
+            let location = &property.location;
+            let locations = match (&location.file, location.line) {
+                (Some(file), Some(line)) => vec![Location::new(file, line, location.column)],
+                // Fall back to the harness for checks without a location, e.g., unwinding
+                // assertions of the standard library.
+                _ => vec![Location::new(&harness.file, harness.start_line, None)],
+            };
+            SarifResult {
+                rule_id: property.class.clone(),
+                level: "error",
+                message: Message {
+                    text: format!("{} (harness `{}`)", property.description, harness.name),
+                },
+                locations,
+            }
+        })
+        .collect();
+    if !failures.is_empty() {
+        return failures;
+    }
+    let text = match harness.cbmc_exit_status {
+        Some(status) => {
+            format!("Verification of harness `{}` failed: CBMC exited with {status}", harness.name)
+        }
+        None => format!("Verification of harness `{}` failed", harness.name),
+    };
+    vec![SarifResult {
+        rule_id: HARNESS_RULE.to_string(),
+        level: "error",
+        message: Message { text },
+        locations: vec![Location::new(&harness.file, harness.start_line, None)],
+    }]
+}
+
+fn rule_description(rule: &str) -> String {
+    if rule == HARNESS_RULE {
+        "Verification of a harness failed".to_string()
+    } else {
+        format!("Kani `{rule}` check")
+    }
+}
+
+impl Location {
+    fn new(file: &str, line: usize, column: Option<usize>) -> Self {
+        Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation { uri: artifact_uri(file) },
+                region: Region { start_line: line, start_column: column },
+            },
+        }
+    }
+}
+
+/// The URI of a source file. SARIF consumers resolve relative URIs against the root of the
+/// repository, so files under the current directory are reported relative to it.
+fn artifact_uri(file: &str) -> String {
+    let path = Path::new(file);
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf));
+    match relative {
+        Some(relative) => relative.to_string_lossy().replace('\\', "/"),
+        None if path.is_absolute() => format!("file://{}", file.replace('\\', "/")),
+        None => file.replace('\\', "/"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_report::{PropertyLocation, PropertyReport};
+
+    fn harness(status: VerificationStatus, properties: Vec<PropertyReport>) -> HarnessReport {
+        HarnessReport {
+            name: "check".to_string(),
+            crate_name: "my_crate".to_string(),
+            file: "src/lib.rs".to_string(),
+            start_line: 3,
+            end_line: 8,
+            status,
+            runtime_secs: 1.0,
+            cbmc_exit_status: None,
+            coverage_file: None,
+            properties,
+            profile: Default::default(),
+        }
+    }
+
+    fn property(class: &str, status: CheckStatus, line: Option<usize>) -> PropertyReport {
+        PropertyReport {
+            id: format!("check.{class}.1"),
+            class: class.to_string(),
+            description: format!("{class} failed"),
+            status,
+            location: PropertyLocation {
+                file: Some("src/lib.rs".to_string()),
+                function: Some("check".to_string()),
+                line,
+                column: Some(5),
+            },
+            trace_excerpt: vec![],
+        }
+    }
+
+    #[test]
+    fn check_failed_properties() {
+        let harnesses = [
+            harness(
+                VerificationStatus::Failure,
+                vec![
+                    property("assertion", CheckStatus::Failure, Some(6)),
+                    property("arithmetic_overflow", CheckStatus::Success, Some(5)),
+                    property("unwind", CheckStatus::Failure, None),
+                ],
+            ),
+            harness(
+                VerificationStatus::Success,
+                vec![property("assertion", CheckStatus::Success, Some(6))],
+            ),
+        ];
+        let log = serde_json::to_value(sarif_log(&harnesses)).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules: Vec<_> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| rule["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(rules, ["assertion", "unwind"]);
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "assertion");
+        assert_eq!(results[0]["message"]["text"], "assertion failed (harness `check`)");
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 6);
+        assert_eq!(region["startColumn"], 5);
+        let region = &results[1]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(region["startLine"], 3);
+    }
+
+    #[test]
+    fn check_failed_harness_without_properties() {
+        let mut failed = harness(VerificationStatus::Failure, vec![]);
+        failed.cbmc_exit_status = Some(6);
+        let log = serde_json::to_value(sarif_log(&[failed])).unwrap();
+        let result = &log["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], HARNESS_RULE);
+        assert_eq!(
+            result["message"]["text"],
+            "Verification of harness `check` failed: CBMC exited with 6"
+        );
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(location["region"]["startLine"], 3);
+    }
+}
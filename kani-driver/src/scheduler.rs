@@ -0,0 +1,151 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Scheduling of the harnesses of cargo projects from the runtimes of the previous runs.
+//!
+//! The runtime of each harness is recorded after it's verified, and kept across runs in the
+//! project output directory, under `target/kani`. The next runs verify the slowest harnesses
+//! first, so a slow harness doesn't start last and keep a single job busy while the others are
+//! idle. Harnesses without a recorded runtime, e.g., new harnesses, are verified before the
+//! others, in their usual order, since they might be slow.
+//!
+//! With `--fail-fast` or `--max-failures`, the harnesses that failed in their last run are
+//! verified first, since they're the most likely to fail again.
+
+use anyhow::Result;
+use kani_metadata::HarnessMetadata;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use crate::call_cbmc::VerificationStatus;
+use crate::harness_runner::HarnessResult;
+use crate::project::Project;
+use crate::severity::SeverityPolicy;
+
+/// The name of the history file inside the project output directory.
+const HISTORY_FILE: &str = "harness-history.json";
+
+/// The last recorded run of each harness, indexed by its crate and name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct History {
+    crates: BTreeMap<String, BTreeMap<String, HarnessHistory>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct HarnessHistory {
+    /// How long the verification took.
+    runtime_secs: f64,
+    /// Whether the harness failed the run.
+    #[serde(default)]
+    failed: bool,
+}
+
+/// The history of the harnesses of a project, which is updated with [Scheduler::record] and
+/// saved with [Scheduler::save].
+pub struct Scheduler {
+    path: PathBuf,
+    history: History,
+}
+
+impl Scheduler {
+    /// Load the history of the previous runs for the project. The history is discarded if it
+    /// can't be read.
+    pub fn load(project: &Project) -> Self {
+        let path = project.outdir.join(HISTORY_FILE);
+        let history = File::open(&path)
+            .ok()
+            .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+            .unwrap_or_default();
+        Scheduler { path, history }
+    }
+
+    fn history(&self, harness: &HarnessMetadata) -> Option<&HarnessHistory> {
+        self.history.crates.get(&harness.crate_name)?.get(&harness.pretty_name)
+    }
+
+    /// The order in which `harnesses` should be verified, as indices in `harnesses`: the
+    /// harnesses without a recorded runtime in their current order, then the others from the
+    /// slowest to the fastest. With `failed_first`, the harnesses that failed in their last run
+    /// come before the others.
+    pub fn schedule(&self, harnesses: &[&HarnessMetadata], failed_first: bool) -> Vec<usize> {
+        let histories: Vec<_> = harnesses.iter().map(|harness| self.history(harness)).collect();
+        let failed = |index: usize| failed_first && histories[index].is_some_and(|h| h.failed);
+        let mut order: Vec<usize> = (0..harnesses.len()).collect();
+        order.sort_by(|&a, &b| {
+            failed(b).cmp(&failed(a)).then_with(|| match (histories[a], histories[b]) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(a), Some(b)) => b.runtime_secs.total_cmp(&a.runtime_secs),
+            })
+        });
+        order
+    }
+
+    /// Record the runtimes of the verified harnesses, and whether they failed according to
+    /// `policy`. The history of the other harnesses is kept, e.g., for harnesses that weren't
+    /// selected by `--harness`.
+    pub fn record(&mut self, results: &[HarnessResult], policy: &SeverityPolicy) {
+        for result in results {
+            let harness = result.harness;
+            let harness_history = HarnessHistory {
+                runtime_secs: result.result.runtime.as_secs_f64(),
+                failed: policy.judge(&result.result).status == VerificationStatus::Failure,
+            };
+            self.history
+                .crates
+                .entry(harness.crate_name.clone())
+                .or_default()
+                .insert(harness.pretty_name.clone(), harness_history);
+        }
+    }
+
+    /// Save the history for the next runs.
+    pub fn save(&self) -> Result<()> {
+        serde_json::to_writer(File::create(&self.path)?, &self.history)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_cbmc::VerificationResult;
+    use crate::metadata::mock_proof_harness;
+    use std::time::Duration;
+
+    #[test]
+    fn check_schedule() {
+        let harnesses = [
+            mock_proof_harness("check_fast", None, Some("my_crate"), None),
+            mock_proof_harness("check_new", None, Some("my_crate"), None),
+            mock_proof_harness("check_slow", None, Some("my_crate"), None),
+            mock_proof_harness("check_other_new", None, Some("my_crate"), None),
+        ];
+        let harnesses: Vec<_> = harnesses.iter().collect();
+        let mut scheduler =
+            Scheduler { path: PathBuf::from(HISTORY_FILE), history: History::default() };
+        assert_eq!(scheduler.schedule(&harnesses, false), [0, 1, 2, 3]);
+
+        let result = |index: usize, secs, status| {
+            let mut result =
+                VerificationResult::from_properties(vec![], false, Duration::from_secs(secs));
+            result.status = status;
+            HarnessResult { harness: harnesses[index], result, instrument_time: None }
+        };
+        let policy = SeverityPolicy::default();
+        scheduler.record(
+            &[
+                result(0, 1, VerificationStatus::Failure),
+                result(2, 10, VerificationStatus::Success),
+            ],
+            &policy,
+        );
+        assert_eq!(scheduler.schedule(&harnesses, false), [1, 3, 2, 0]);
+        assert_eq!(scheduler.schedule(&harnesses, true), [0, 1, 3, 2]);
+    }
+}
@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
 use crate::args::common::Verbosity;
-use crate::args::VerificationArgs;
+use crate::args::{OutputFormat, VerificationArgs};
 use crate::util::render_command;
 use anyhow::{bail, Context, Result};
 use std::io::IsTerminal;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::Instant;
 use strum_macros::Display;
@@ -31,6 +32,10 @@ pub struct KaniSession {
     /// proof attributes.
     pub codegen_tests: bool,
 
+    /// Only generate the metadata of the proof harnesses, without their code. Used to list the
+    /// harnesses.
+    pub metadata_only: bool,
+
     /// The location we found the 'kani_rustc' command
     pub kani_compiler: PathBuf,
     /// The location we found 'kani_lib.c'
@@ -38,6 +43,10 @@ pub struct KaniSession {
 
     /// The temporary files we littered that need to be cleaned up at the end of execution
     pub temporaries: Mutex<Vec<PathBuf>>,
+
+    /// Whether the verification was cancelled by `--max-failures`, which stops the CBMC processes
+    /// that are still running.
+    cancelled: AtomicBool,
 }
 
 /// Represents where we detected Kani, with helper methods for using that information to find critical paths
@@ -51,19 +60,44 @@ pub enum InstallType {
 }
 
 impl KaniSession {
-    pub fn new(args: VerificationArgs) -> Result<Self> {
+    pub fn new(mut args: VerificationArgs) -> Result<Self> {
+        // The JSON document or the JUnit report must be the only output.
+        args.common_args.quiet |=
+            matches!(args.output_format, OutputFormat::Json | OutputFormat::Junit);
         init_logger(&args);
         let install = InstallType::new()?;
 
         Ok(KaniSession {
             args,
             codegen_tests: false,
+            metadata_only: false,
             kani_compiler: install.kani_compiler()?,
             kani_lib_c: install.kani_lib_c()?,
             temporaries: Mutex::new(vec![]),
+            cancelled: AtomicBool::new(false),
         })
     }
 
+    /// Cancel the verification of the harnesses that are still running.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Clear the cancellation of a previous verification, e.g., of a previous run of `--watch`.
+    pub fn reset_cancellation(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The flag the CBMC processes must watch to be killed when the verification is cancelled,
+    /// if it can be.
+    pub fn cancellation(&self) -> Option<&AtomicBool> {
+        self.args.max_failures().map(|_| &self.cancelled)
+    }
+
     /// Record a temporary file so we can cleanup after ourselves at the end.
     /// Note that there will be no failure if the file does not exist.
     pub fn record_temporary_file<T: AsRef<Path>>(&self, temp: &T) {
@@ -92,7 +126,7 @@ pub fn reachability_mode(&self) -> ReachabilityMode {
     }
 }
 
-#[derive(Debug, Copy, Clone, Display)]
+#[derive(Debug, Copy, Clone, Display, PartialEq, Eq)]
 #[strum(serialize_all = "snake_case")]
 pub enum ReachabilityMode {
     #[strum(to_string = "harnesses")]
@@ -361,6 +395,11 @@ fn expect_path(path: PathBuf) -> Result<PathBuf> {
 
 /// Initialize the logger using the KANI_LOG environment variable and `--debug` argument.
 fn init_logger(args: &VerificationArgs) {
+    // The logger is global, so it's only initialized by the first session, e.g., when each
+    // package of a workspace is verified by its own session.
+    if tracing::dispatcher::has_been_set() {
+        return;
+    }
     let filter = EnvFilter::from_env(LOG_ENV_VAR);
     let filter = if args.common_args.debug {
         filter.add_directive(LevelFilter::DEBUG.into())
@@ -0,0 +1,299 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for `--severity <OUTCOME>=<LEVEL>`, which selects the outcomes of a harness that fail
+//! the run.
+//!
+//! By default, a harness that timed out or that has undetermined properties fails the run, while
+//! unsatisfiable and unreachable cover properties don't. Each of these outcomes can be an
+//! `error`, which fails the run, a `warning`, which is reported in the summary without failing
+//! the run, or `allow`ed. A failed property always fails the run. The levels can also be set in
+//! the `severity` table of the configuration, e.g.:
+//!
+//! ```toml
+//! [package.metadata.kani.severity]
+//! undetermined = "warning"
+//! unsatisfiable-cover = "error"
+//! ```
+
+use clap::ValueEnum;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::call_cbmc::{VerificationResult, VerificationStatus};
+use crate::cbmc_output_parser::CheckStatus;
+
+/// An outcome of a harness whose severity can be configured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Outcome {
+    /// The harness timed out, or some of its properties couldn't be checked.
+    Undetermined,
+    /// A cover property is reachable but can't be satisfied.
+    UnsatisfiableCover,
+    /// A cover property is unreachable.
+    UnreachableCover,
+}
+
+/// How an outcome affects the run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum, strum_macros::Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum Severity {
+    /// Fail the run.
+    Error,
+    /// Report the outcome in the summary, without failing the run.
+    Warning,
+    /// Ignore the outcome.
+    Allow,
+}
+
+/// The severity of an outcome given by `--severity <OUTCOME>=<LEVEL>`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeverityLevel {
+    pub outcome: Outcome,
+    pub severity: Severity,
+}
+
+impl FromStr for SeverityLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let Some((outcome, severity)) = value.split_once('=') else {
+            return Err(format!(
+                "expected `<OUTCOME>=<LEVEL>`, e.g. `undetermined=warning`, but found `{value}`"
+            ));
+        };
+        Ok(SeverityLevel {
+            outcome: Outcome::from_str(outcome, false)?,
+            severity: Severity::from_str(severity, false)?,
+        })
+    }
+}
+
+impl Display for SeverityLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.outcome, self.severity)
+    }
+}
+
+/// The severity of each outcome.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SeverityPolicy {
+    undetermined: Severity,
+    unsatisfiable_cover: Severity,
+    unreachable_cover: Severity,
+}
+
+impl Default for SeverityPolicy {
+    fn default() -> Self {
+        SeverityPolicy {
+            undetermined: Severity::Error,
+            unsatisfiable_cover: Severity::Allow,
+            unreachable_cover: Severity::Allow,
+        }
+    }
+}
+
+impl SeverityPolicy {
+    /// The default policy, with the given levels. A later level of an outcome overrides the
+    /// earlier ones, so the command line overrides the configuration.
+    pub fn new(levels: &[SeverityLevel]) -> Self {
+        let mut policy = SeverityPolicy::default();
+        for level in levels {
+            *policy.severity_mut(level.outcome) = level.severity;
+        }
+        policy
+    }
+
+    pub fn severity(&self, outcome: Outcome) -> Severity {
+        match outcome {
+            Outcome::Undetermined => self.undetermined,
+            Outcome::UnsatisfiableCover => self.unsatisfiable_cover,
+            Outcome::UnreachableCover => self.unreachable_cover,
+        }
+    }
+
+    fn severity_mut(&mut self, outcome: Outcome) -> &mut Severity {
+        match outcome {
+            Outcome::Undetermined => &mut self.undetermined,
+            Outcome::UnsatisfiableCover => &mut self.unsatisfiable_cover,
+            Outcome::UnreachableCover => &mut self.unreachable_cover,
+        }
+    }
+
+    /// Whether the harness fails the run, and the outcomes of the harness whose severity decided
+    /// it, with their severity.
+    pub fn judge(&self, result: &VerificationResult) -> Judgement {
+        let outcomes: Vec<_> = outcomes(result)
+            .into_iter()
+            .map(|outcome| (outcome, self.severity(outcome)))
+            .filter(|(_, severity)| *severity != Severity::Allow)
+            .collect();
+        let status = if result.status == VerificationStatus::Failure {
+            // An incomplete verification is only a failure if undetermined results are errors.
+            if failed_for_undetermined_only(result)
+                && self.severity(Outcome::Undetermined) != Severity::Error
+            {
+                VerificationStatus::Success
+            } else {
+                VerificationStatus::Failure
+            }
+        } else if outcomes.iter().any(|(_, severity)| *severity == Severity::Error) {
+            VerificationStatus::Failure
+        } else {
+            VerificationStatus::Success
+        };
+        Judgement { status, outcomes }
+    }
+}
+
+/// Whether a harness fails the run according to the [SeverityPolicy].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Judgement {
+    pub status: VerificationStatus,
+    /// The outcomes of the harness that aren't allowed.
+    pub outcomes: Vec<(Outcome, Severity)>,
+}
+
+impl Judgement {
+    /// A short description of the outcomes with the given severity, e.g. "timed out".
+    pub fn describe(&self, severity: Severity, result: &VerificationResult) -> Option<String> {
+        let descriptions: Vec<_> = self
+            .outcomes
+            .iter()
+            .filter(|(_, outcome_severity)| *outcome_severity == severity)
+            .map(|(outcome, _)| match outcome {
+                Outcome::Undetermined if result.timed_out => "timed out".to_string(),
+                Outcome::Undetermined => "has undetermined properties".to_string(),
+                Outcome::UnsatisfiableCover => {
+                    format!(
+                        "has {} unsatisfiable cover properties",
+                        count_covers(result, CheckStatus::Unsatisfiable)
+                    )
+                }
+                Outcome::UnreachableCover => {
+                    format!(
+                        "has {} unreachable cover properties",
+                        count_covers(result, CheckStatus::Unreachable)
+                    )
+                }
+            })
+            .collect();
+        (!descriptions.is_empty()).then(|| descriptions.join(" and "))
+    }
+}
+
+/// The outcomes of a harness whose severity can be configured.
+fn outcomes(result: &VerificationResult) -> Vec<Outcome> {
+    let properties = result.results.as_deref().unwrap_or_default();
+    let mut outcomes = vec![];
+    if result.timed_out
+        || properties
+            .iter()
+            .any(|prop| !prop.is_cover_property() && prop.status == CheckStatus::Undetermined)
+    {
+        outcomes.push(Outcome::Undetermined);
+    }
+    if count_covers(result, CheckStatus::Unsatisfiable) > 0 {
+        outcomes.push(Outcome::UnsatisfiableCover);
+    }
+    if count_covers(result, CheckStatus::Unreachable) > 0 {
+        outcomes.push(Outcome::UnreachableCover);
+    }
+    outcomes
+}
+
+fn count_covers(result: &VerificationResult, status: CheckStatus) -> usize {
+    let properties = result.results.as_deref().unwrap_or_default();
+    properties.iter().filter(|prop| prop.is_cover_property() && prop.status == status).count()
+}
+
+/// Whether the harness failed only because its verification is incomplete, i.e., it timed out or
+/// has undetermined properties, but no property failed.
+fn failed_for_undetermined_only(result: &VerificationResult) -> bool {
+    match &result.results {
+        Ok(properties) => {
+            properties.iter().all(|prop| prop.status != CheckStatus::Failure)
+                && outcomes(result).contains(&Outcome::Undetermined)
+        }
+        // CBMC was killed before it reported any result.
+        Err(_) => result.timed_out,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::Property;
+
+    #[test]
+    fn check_parse_severity_level() {
+        assert_eq!(
+            "unsatisfiable-cover=error".parse(),
+            Ok(SeverityLevel { outcome: Outcome::UnsatisfiableCover, severity: Severity::Error })
+        );
+        let level = SeverityLevel { outcome: Outcome::Undetermined, severity: Severity::Allow };
+        assert_eq!(level.to_string(), "undetermined=allow");
+        assert!("undetermined".parse::<SeverityLevel>().is_err());
+        assert!("timeout=error".parse::<SeverityLevel>().is_err());
+        assert!("undetermined=fatal".parse::<SeverityLevel>().is_err());
+    }
+
+    #[test]
+    fn check_judge_covers() {
+        let unsatisfiable = VerificationResult::mock_with_properties(vec![
+            Property::mock("assertion", CheckStatus::Success),
+            Property::mock("cover", CheckStatus::Unsatisfiable),
+        ]);
+        assert_eq!(
+            SeverityPolicy::default().judge(&unsatisfiable),
+            Judgement { status: VerificationStatus::Success, outcomes: vec![] }
+        );
+
+        let policy = SeverityPolicy::new(&[
+            "unsatisfiable-cover=warning".parse().unwrap(),
+            "unsatisfiable-cover=error".parse().unwrap(),
+        ]);
+        let judgement = policy.judge(&unsatisfiable);
+        assert_eq!(judgement.status, VerificationStatus::Failure);
+        assert_eq!(
+            judgement.describe(Severity::Error, &unsatisfiable).as_deref(),
+            Some("has 1 unsatisfiable cover properties")
+        );
+        assert_eq!(judgement.describe(Severity::Warning, &unsatisfiable), None);
+
+        // A failed property fails the run regardless of the policy.
+        let failure = VerificationResult::mock_with_properties(vec![Property::mock(
+            "assertion",
+            CheckStatus::Failure,
+        )]);
+        let policy = SeverityPolicy::new(&["undetermined=allow".parse().unwrap()]);
+        assert_eq!(policy.judge(&failure).status, VerificationStatus::Failure);
+    }
+
+    #[test]
+    fn check_judge_undetermined() {
+        let mut timed_out = VerificationResult::mock_with_properties(vec![
+            Property::mock("assertion", CheckStatus::Success),
+            Property::mock("assertion", CheckStatus::Undetermined),
+        ]);
+        timed_out.status = VerificationStatus::Failure;
+        timed_out.timed_out = true;
+        assert_eq!(
+            SeverityPolicy::default().judge(&timed_out),
+            Judgement {
+                status: VerificationStatus::Failure,
+                outcomes: vec![(Outcome::Undetermined, Severity::Error)]
+            }
+        );
+
+        let policy = SeverityPolicy::new(&["undetermined=warning".parse().unwrap()]);
+        let judgement = policy.judge(&timed_out);
+        assert_eq!(judgement.status, VerificationStatus::Success);
+        assert_eq!(judgement.describe(Severity::Warning, &timed_out).as_deref(), Some("timed out"));
+
+        // A failure found before the timeout still fails the run.
+        timed_out.results.as_mut().unwrap()[0].status = CheckStatus::Failure;
+        assert_eq!(policy.judge(&timed_out).status, VerificationStatus::Failure);
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Support for splitting the verification of a project across machines.
+//!
+//! With `--shard <I>/<N>`, Kani only verifies the harnesses assigned to the `I`-th of `N`
+//! shards. Every harness is assigned to exactly one shard, and the assignment only depends on the
+//! harnesses of the project and on the `--shard-timings`, so CI workers that run the same command
+//! with a different shard index verify each harness once. The timings are the JSON results of a
+//! previous run, which are used to balance the verification time of the shards. Otherwise, the
+//! shards get the same number of harnesses.
+//!
+//! `kani merge-results` combines the JSON results of the shards into the results of the whole
+//! project.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{self, Display};
+use std::path::Path;
+use std::str::FromStr;
+
+use kani_metadata::HarnessMetadata;
+
+use crate::args::merge_results_args::MergeResultsArgs;
+use crate::json_report::{JsonReport, SCHEMA_VERSION};
+
+/// The shard selected by `--shard <INDEX>/<COUNT>`, where the index starts at 1.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected `<INDEX>/<COUNT>`, e.g. `1/4`, but found `{value}`");
+        let (index, count) = value.split_once('/').ok_or_else(invalid)?;
+        let index: usize = index.parse().map_err(|_| invalid())?;
+        let count: usize = count.parse().map_err(|_| invalid())?;
+        if count == 0 || index == 0 || index > count {
+            return Err(format!(
+                "the shard index must be between 1 and the number of shards, but found `{value}`"
+            ));
+        }
+        Ok(Shard { index, count })
+    }
+}
+
+impl Display for Shard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+/// The verification time of each harness in a previous run, indexed by crate and harness name.
+pub type HarnessTimings = HashMap<(String, String), f64>;
+
+/// Read the verification time of each harness from the JSON results of a previous run.
+pub fn load_timings(path: &Path) -> Result<HarnessTimings> {
+    let report = JsonReport::load(path)?;
+    Ok(report
+        .harnesses
+        .iter()
+        .map(|harness| {
+            let secs = harness.profile.total_secs.max(harness.runtime_secs);
+            ((harness.crate_name.clone(), harness.name.clone()), secs)
+        })
+        .collect())
+}
+
+/// The harnesses assigned to `shard`.
+///
+/// The harnesses are assigned from the slowest to the fastest to the shard with the lowest
+/// total time so far. Harnesses without timings are estimated to take the average time of the
+/// others, so with no timings at all, the harnesses are distributed in a round-robin fashion.
+pub fn shard_harnesses<'a>(
+    harnesses: Vec<&'a HarnessMetadata>,
+    shard: Shard,
+    timings: &HarnessTimings,
+) -> Vec<&'a HarnessMetadata> {
+    let timing = |harness: &HarnessMetadata| {
+        timings.get(&(harness.crate_name.clone(), harness.pretty_name.clone())).copied()
+    };
+    let known: Vec<_> = harnesses.iter().filter_map(|harness| timing(harness)).collect();
+    let default_secs =
+        if known.is_empty() { 1.0 } else { known.iter().sum::<f64>() / known.len() as f64 };
+
+    let mut estimated: Vec<_> = harnesses
+        .into_iter()
+        .map(|harness| (timing(harness).unwrap_or(default_secs), harness))
+        .collect();
+    // Sort by name for ties, so the order doesn't depend on the order of the compiler artifacts.
+    estimated.sort_by(|(a_secs, a), (b_secs, b)| {
+        b_secs
+            .total_cmp(a_secs)
+            .then_with(|| a.crate_name.cmp(&b.crate_name))
+            .then_with(|| a.pretty_name.cmp(&b.pretty_name))
+    });
+    let mut loads = vec![0.0_f64; shard.count];
+    let mut selected = vec![];
+    for (secs, harness) in estimated {
+        let (lightest, _) = loads
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .expect("there is at least one shard");
+        loads[lightest] += secs;
+        if lightest + 1 == shard.index {
+            selected.push(harness);
+        }
+    }
+    selected
+}
+
+/// Combine the JSON results of the shards of a run, and print them or write them to
+/// `--output`.
+pub fn merge_results(args: MergeResultsArgs) -> Result<()> {
+    let reports = args
+        .files
+        .iter()
+        .map(|path| Ok((path.as_path(), JsonReport::load(path)?)))
+        .collect::<Result<Vec<_>>>()?;
+    let merged = merge_reports(reports)?;
+    let json = serde_json::to_string_pretty(&merged)?;
+    match &args.output {
+        Some(path) => std::fs::write(path, json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+fn merge_reports(reports: Vec<(&Path, JsonReport)>) -> Result<JsonReport> {
+    let mut origins: BTreeMap<(String, String), &Path> = BTreeMap::new();
+    let mut shards: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    let mut merged: Option<JsonReport> = None;
+    for (path, report) in reports {
+        if report.schema_version != SCHEMA_VERSION {
+            bail!(
+                "the results in `{}` use version {} of the schema, but this version of Kani only \
+                supports version {SCHEMA_VERSION}",
+                path.display(),
+                report.schema_version
+            );
+        }
+        for harness in &report.harnesses {
+            let key = (harness.crate_name.clone(), harness.name.clone());
+            if let Some(other) = origins.insert(key, path) {
+                bail!(
+                    "the harness `{}` of crate `{}` appears in both `{}` and `{}`",
+                    harness.name,
+                    harness.crate_name,
+                    other.display(),
+                    path.display()
+                );
+            }
+        }
+        if let Some(shard) = report.shard {
+            shards.entry(shard.count).or_default().insert(shard.index);
+        }
+        match &mut merged {
+            None => merged = Some(report),
+            Some(merged) => {
+                if merged.coverage_export.is_none() {
+                    merged.coverage_export = report.coverage_export;
+                }
+                merged.harnesses.extend(report.harnesses);
+            }
+        }
+    }
+    if shards.len() > 1 {
+        bail!("the results come from runs with different numbers of shards");
+    }
+    if let Some((count, indices)) = shards.first_key_value() {
+        let missing: Vec<_> = (1..=*count)
+            .filter(|index| !indices.contains(index))
+            .map(|index| Shard { index, count: *count }.to_string())
+            .collect();
+        if !missing.is_empty() {
+            // The standard output may be the merged results.
+            let warning = console::style("warning:").bold().yellow();
+            eprintln!("{warning} the results of shards {} are missing", missing.join(", "));
+        }
+    }
+    let Some(mut merged) = merged else { bail!("no results to merge") };
+    merged.shard = None;
+    merged.harnesses.sort_by(|a, b| a.name.cmp(&b.name));
+    merged.summary = merged.harnesses.as_slice().into();
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::call_cbmc::VerificationStatus;
+    use crate::json_report::{HarnessReport, ReportSummary, ToolVersions};
+    use crate::metadata::mock_proof_harness;
+
+    #[test]
+    fn check_parse_shard() {
+        assert_eq!("2/3".parse(), Ok(Shard { index: 2, count: 3 }));
+        assert_eq!(Shard { index: 2, count: 3 }.to_string(), "2/3");
+        assert!("0/3".parse::<Shard>().is_err());
+        assert!("4/3".parse::<Shard>().is_err());
+        assert!("1/0".parse::<Shard>().is_err());
+        assert!("1".parse::<Shard>().is_err());
+        assert!("a/3".parse::<Shard>().is_err());
+    }
+
+    fn names(harnesses: &[&HarnessMetadata]) -> Vec<String> {
+        harnesses.iter().map(|harness| harness.pretty_name.clone()).collect()
+    }
+
+    #[test]
+    fn check_shards_partition_harnesses() {
+        let harnesses: Vec<_> = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|name| mock_proof_harness(name, None, Some("my_crate"), None))
+            .collect();
+        let all: Vec<_> = harnesses.iter().collect();
+        let shard = |index, timings: &HarnessTimings| {
+            names(&shard_harnesses(all.clone(), Shard { index, count: 2 }, timings))
+        };
+
+        // Without timings, the harnesses are distributed by name.
+        let timings = HarnessTimings::new();
+        assert_eq!(shard(1, &timings), ["a", "c", "e"]);
+        assert_eq!(shard(2, &timings), ["b", "d"]);
+
+        // With timings, the slowest harness gets its own shard.
+        let timings: HarnessTimings = [("a", 10.0), ("b", 1.0), ("c", 2.0), ("d", 3.0)]
+            .into_iter()
+            .map(|(name, secs)| (("my_crate".to_string(), name.to_string()), secs))
+            .collect();
+        assert_eq!(shard(1, &timings), ["a"]);
+        assert_eq!(shard(2, &timings), ["e", "d", "c", "b"]);
+
+        // The order of the harnesses doesn't matter.
+        let reversed: Vec<_> = all.iter().rev().copied().collect();
+        let shard_one = shard_harnesses(reversed, Shard { index: 1, count: 2 }, &timings);
+        assert_eq!(names(&shard_one), ["a"]);
+    }
+
+    fn report(shard: Option<Shard>, harnesses: &[(&str, VerificationStatus)]) -> JsonReport {
+        let harnesses: Vec<_> = harnesses
+            .iter()
+            .map(|(name, status)| HarnessReport {
+                name: name.to_string(),
+                crate_name: "my_crate".to_string(),
+                file: "src/lib.rs".to_string(),
+                start_line: 1,
+                end_line: 2,
+                status: *status,
+                runtime_secs: 1.0,
+                cbmc_exit_status: None,
+                coverage_file: None,
+                profile: Default::default(),
+                properties: vec![],
+            })
+            .collect();
+        JsonReport {
+            schema_version: SCHEMA_VERSION,
+            tools: ToolVersions { kani: "0.55.0".to_string(), cbmc: None },
+            summary: harnesses.as_slice().into(),
+            coverage_export: None,
+            shard,
+            harnesses,
+        }
+    }
+
+    #[test]
+    fn check_merge_reports() {
+        let first =
+            report(Some(Shard { index: 1, count: 2 }), &[("b", VerificationStatus::Failure)]);
+        let second =
+            report(Some(Shard { index: 2, count: 2 }), &[("a", VerificationStatus::Success)]);
+        let merged =
+            merge_reports(vec![(Path::new("1.json"), first), (Path::new("2.json"), second)])
+                .unwrap();
+        assert_eq!(merged.shard, None);
+        assert_eq!(
+            merged.harnesses.iter().map(|harness| harness.name.as_str()).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(merged.summary, ReportSummary { total: 2, successful: 1, failed: 1 });
+
+        let first = report(None, &[("a", VerificationStatus::Success)]);
+        let second = report(None, &[("a", VerificationStatus::Success)]);
+        let error =
+            merge_reports(vec![(Path::new("1.json"), first), (Path::new("2.json"), second)])
+                .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the harness `a` of crate `my_crate` appears in both `1.json` and `2.json`"
+        );
+
+        let first = report(Some(Shard { index: 1, count: 2 }), &[]);
+        let second = report(Some(Shard { index: 1, count: 3 }), &[]);
+        assert!(
+            merge_reports(vec![(Path::new("1.json"), first), (Path::new("2.json"), second)])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,234 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Rendering of the struct, enum and array values of the traces, which CBMC only prints as their
+//! components, e.g., `Point { x: 1, y: 2 }` or `Some(3)`.
+//!
+//! The values are rendered like their `Debug` implementation would. CBMC doesn't print the type
+//! of the values, so the tag of the assigned struct is read from the expression assigned by the
+//! step (`--trace-json-extended`), and the names of the structs and enum variants from the
+//! layouts written by the compiler ([AdtMap]). The components whose value can't be recovered,
+//! e.g., the payload of a niche encoded enum, are rendered as `..`.
+
+use kani_metadata::artifact::convert_type;
+use kani_metadata::{AdtField, AdtLayout, AdtMap, ArtifactType};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use crate::cbmc_output_parser::{Property, TraceData, TraceMember, TraceValue};
+
+/// The rendering of a value that can't be recovered.
+const ELIDED: &str = "..";
+
+/// Read the layouts of the structs and enums of the goto model `binary`. The map is empty if it
+/// can't be read, in which case the values are rendered from their components alone.
+pub fn load_adt_map(binary: &Path) -> AdtMap {
+    let path = convert_type(binary, ArtifactType::Goto, ArtifactType::AdtMap);
+    File::open(path)
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+/// Set the `data` of the struct, union and array values assigned in the traces of `properties`.
+/// The values that can't be recovered are left without `data`, so they are skipped like before.
+pub fn render_trace_values(properties: &mut [Property], adt_map: &AdtMap) {
+    for step in properties.iter_mut().filter_map(|prop| prop.trace.as_mut()).flatten() {
+        let tag = step.lhs_tag().map(str::to_string);
+        if let Some(value) = &mut step.value
+            && value.data.is_none()
+            && (value.members.is_some() || value.member.is_some() || value.elements.is_some())
+        {
+            let rendered = render_value(value, tag.as_deref(), adt_map);
+            if rendered != ELIDED {
+                value.data = Some(TraceData::NonBool(rendered));
+            }
+        }
+    }
+}
+
+/// Render `value`, where `tag` is the tag of its type or of the type of its elements, if known.
+fn render_value(value: &TraceValue, tag: Option<&str>, adt_map: &AdtMap) -> String {
+    if let Some(data) = &value.data {
+        return data.to_string();
+    }
+    if let Some(elements) = &value.elements {
+        let elements: Vec<_> =
+            elements.iter().map(|element| render_value(&element.value, tag, adt_map)).collect();
+        return format!("[{}]", elements.join(", "));
+    }
+    // CBMC prints a single component of the unions, which may not be the one that was set.
+    let Some(members) = &value.members else { return ELIDED.to_string() };
+    match tag.and_then(|tag| adt_map.get(tag)) {
+        Some(AdtLayout::Struct { name, fields }) => render_fields(name, fields, members, adt_map),
+        Some(AdtLayout::Enum { variants }) => {
+            let case = find_member(members, "case")
+                .and_then(|case| case.binary.as_ref())
+                .and_then(|binary| u128::from_str_radix(binary, 2).ok());
+            let Some(variant) = variants.iter().find(|variant| Some(variant.discriminant) == case)
+            else {
+                return ELIDED.to_string();
+            };
+            // The fields of the variant are only known if CBMC picked the component of the
+            // variant among the components of the union of the variants.
+            let payload = find_member(members, "cases")
+                .and_then(|cases| cases.member.as_ref())
+                .filter(|payload| payload.name == variant.name)
+                .and_then(|payload| payload.value.members.as_deref())
+                .unwrap_or_default();
+            render_fields(&variant.name, &variant.fields, payload, adt_map)
+        }
+        Some(AdtLayout::Opaque) => ELIDED.to_string(),
+        // The structs without layout, e.g., tuples, are rendered from their components.
+        None => {
+            let fields: Vec<_> = members
+                .iter()
+                .filter(|member| !member.name.starts_with("$pad"))
+                .map(|member| AdtField { name: member.name.clone(), tag: None })
+                .collect();
+            render_fields("", &fields, members, adt_map)
+        }
+    }
+}
+
+fn find_member<'a>(members: &'a [TraceMember], name: &str) -> Option<&'a TraceValue> {
+    members.iter().find(|member| member.name == name).map(|member| &member.value)
+}
+
+/// Render the `fields` of a struct or enum variant named `name` from the `members` of its value,
+/// e.g., `Point { x: 1, y: 2 }`, `Some(3)` or `(1, 2)`. The missing fields are elided.
+fn render_fields(
+    name: &str,
+    fields: &[AdtField],
+    members: &[TraceMember],
+    adt_map: &AdtMap,
+) -> String {
+    let values: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            find_member(members, &field.name).map_or(ELIDED.to_string(), |value| {
+                render_value(value, field.tag.as_deref(), adt_map)
+            })
+        })
+        .collect();
+    if fields.is_empty() {
+        if name.is_empty() { "()".to_string() } else { name.to_string() }
+    } else if fields.iter().all(|field| field.name.parse::<usize>().is_ok()) {
+        let trailing_comma = if name.is_empty() && values.len() == 1 { "," } else { "" };
+        format!("{name}({}{trailing_comma})", values.join(", "))
+    } else {
+        let values: Vec<_> = fields
+            .iter()
+            .zip(values)
+            .map(|(field, value)| format!("{}: {value}", field.name))
+            .collect();
+        format!("{name} {{ {} }}", values.join(", ")).trim_start().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cbmc_output_parser::TraceItem;
+    use serde_json::json;
+
+    fn int(value: i64, width: usize) -> serde_json::Value {
+        let binary = format!("{:0width$b}", value & ((1 << width) - 1));
+        json!({ "name": "integer", "binary": binary, "data": value.to_string(), "width": width })
+    }
+
+    fn adt_map() -> AdtMap {
+        serde_json::from_value(json!({
+            "tag-Point": { "Struct": { "name": "Point", "fields": [
+                { "name": "x", "tag": null },
+                { "name": "y", "tag": null },
+            ] } },
+            "tag-Shape": { "Enum": { "variants": [
+                { "name": "Empty", "discriminant": 0, "fields": [] },
+                { "name": "Dot", "discriminant": 1, "fields": [{ "name": "0", "tag": "tag-Point" }] },
+                { "name": "Pos", "discriminant": 255, "fields": [{ "name": "0", "tag": null }] },
+            ] } },
+            "tag-Option<&u8>": "Opaque",
+        }))
+        .unwrap()
+    }
+
+    fn render(value: serde_json::Value, tag: Option<&str>) -> String {
+        let value: TraceValue = serde_json::from_value(value).unwrap();
+        render_value(&value, tag, &adt_map())
+    }
+
+    #[test]
+    fn check_render_struct() {
+        let point = json!({ "name": "struct", "members": [
+            { "name": "y", "value": int(-2, 16) },
+            { "name": "x", "value": int(1, 8) },
+            { "name": "$pad2", "value": int(0, 8) },
+        ] });
+        assert_eq!(render(point.clone(), Some("tag-Point")), "Point { x: 1, y: -2 }");
+        assert_eq!(render(point.clone(), None), "{ y: -2, x: 1 }");
+        let tuple = json!({ "name": "struct", "members": [
+            { "name": "0", "value": int(1, 8) },
+            { "name": "1", "value": point },
+        ] });
+        assert_eq!(render(tuple, None), "(1, { y: -2, x: 1 })");
+        let array = json!({ "name": "array", "elements": [
+            { "index": 0, "value": { "name": "struct", "members": [] } },
+            { "index": 1, "value": { "name": "struct", "members": [
+                { "name": "0", "value": int(3, 8) },
+            ] } },
+        ] });
+        assert_eq!(render(array, None), "[(), (3,)]");
+    }
+
+    #[test]
+    fn check_render_enum() {
+        let shape = |case: i64, payload: Option<serde_json::Value>| {
+            let mut members = vec![json!({ "name": "case", "value": int(case, 8) })];
+            if let Some(payload) = payload {
+                members.push(
+                    json!({ "name": "cases", "value": { "name": "union", "member": payload } }),
+                );
+            }
+            json!({ "name": "struct", "members": members })
+        };
+        let point = json!({ "name": "struct", "members": [
+            { "name": "x", "value": int(1, 8) },
+            { "name": "y", "value": int(2, 16) },
+        ] });
+        let dot = json!({ "name": "Dot", "value": { "name": "struct", "members": [
+            { "name": "0", "value": point },
+        ] } });
+        assert_eq!(render(shape(0, None), Some("tag-Shape")), "Empty");
+        assert_eq!(
+            render(shape(1, Some(dot.clone())), Some("tag-Shape")),
+            "Dot(Point { x: 1, y: 2 })"
+        );
+        // The discriminants are compared as unsigned values of the size of the `case`.
+        assert_eq!(render(shape(-1, Some(dot)), Some("tag-Shape")), "Pos(..)");
+        assert_eq!(render(shape(7, None), Some("tag-Shape")), "..");
+        assert_eq!(
+            render(json!({ "name": "struct", "members": [] }), Some("tag-Option<&u8>")),
+            ".."
+        );
+    }
+
+    #[test]
+    fn check_lhs_tag() {
+        let step = |typ: serde_json::Value| -> TraceItem {
+            serde_json::from_value(json!({
+                "stepType": "assignment",
+                "lhs": "p",
+                "rawLhs": { "id": "symbol", "namedSub": { "type": typ } },
+            }))
+            .unwrap()
+        };
+        let point =
+            json!({ "id": "struct_tag", "namedSub": { "identifier": { "id": "tag-Point" } } });
+        assert_eq!(step(point.clone()).lhs_tag(), Some("tag-Point"));
+        let array = json!({ "id": "array", "sub": [point] });
+        assert_eq!(step(array).lhs_tag(), Some("tag-Point"));
+        assert_eq!(step(json!({ "id": "unsignedbv" })).lhs_tag(), None);
+    }
+}
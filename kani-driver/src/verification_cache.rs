@@ -0,0 +1,188 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! A cache of verification results, so unchanged harnesses aren't verified again.
+//!
+//! Results are keyed by a SHA-256 hash of the instrumented goto program of the harness together
+//! with the options passed to CBMC, and the Kani and CBMC versions. Thus, any change to the code
+//! reachable from the harness, to its attributes, or to the verification options invalidates the
+//! result.
+//!
+//! Only the results of plain verification runs are cached. Options that do more than verifying
+//! the harness (e.g., `--concrete-playback`) or that don't produce a complete result (e.g., a
+//! timeout) bypass the cache.
+
+use anyhow::Result;
+use kani_metadata::HarnessMetadata;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::args::{OutputFormat, ProofMode};
+use crate::call_cbmc::{cbmc_version, VerificationResult};
+use crate::cbmc_output_parser::{CheckStatus, Property};
+use crate::session::KaniSession;
+use crate::version::KANI_VERSION;
+
+/// The name of the cache directory inside the project output directory.
+const CACHE_DIR: &str = "verification-cache";
+
+/// A result stored in the cache.
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResult {
+    kani_version: String,
+    harness: String,
+    /// How long the verification took when the result was computed.
+    runtime_secs: f64,
+    /// The postprocessed properties of the harness.
+    properties: Vec<Property>,
+}
+
+/// A stable hash of the inputs that determine a cached result.
+#[derive(Default)]
+pub struct CacheKey(Sha256);
+
+impl CacheKey {
+    /// Add an input to the key. The length of the input is hashed too, so the boundaries between
+    /// the inputs are part of the key.
+    pub fn add(&mut self, input: impl AsRef<[u8]>) {
+        let input = input.as_ref();
+        self.0.update((input.len() as u64).to_le_bytes());
+        self.0.update(input);
+    }
+
+    /// The hexadecimal digest of the inputs.
+    fn finish(self) -> String {
+        format!("{:x}", self.0.finalize())
+    }
+}
+
+/// The location of a harness result in the cache.
+pub struct CacheEntry {
+    path: PathBuf,
+}
+
+impl KaniSession {
+    /// The cache entry for the result of verifying `harness` with the goto program
+    /// `goto_file`, or `None` if the result of this session shouldn't be cached.
+    pub fn cache_entry(
+        &self,
+        outdir: &Path,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+    ) -> Result<Option<CacheEntry>> {
+        if !self.is_cacheable() {
+            return Ok(None);
+        }
+        Ok(Some(self.entry_with_key(outdir, goto_file, harness, CacheKey::default())?))
+    }
+
+    /// The cache entry for the result of verifying `harness` with the goto program `goto_file`,
+    /// where `key` already contains the inputs that are specific to the caller.
+    pub fn entry_with_key(
+        &self,
+        outdir: &Path,
+        goto_file: &Path,
+        harness: &HarnessMetadata,
+        mut key: CacheKey,
+    ) -> Result<CacheEntry> {
+        key.add(KANI_VERSION);
+        key.add(cbmc_version()?);
+        key.add(fs::read(goto_file)?);
+        // The last argument is the goto file, whose content is already hashed.
+        let mut cbmc_args = self.cbmc_flags(goto_file, harness)?;
+        cbmc_args.pop();
+        for arg in &cbmc_args {
+            key.add(arg.as_encoded_bytes());
+        }
+        key.add([self.args.extra_pointer_checks as u8, harness.attributes.should_panic as u8]);
+        let key = key.finish();
+        let harness_filename = harness.pretty_name.replace("::", "-");
+        let path = outdir.join(CACHE_DIR).join(format!("{harness_filename}-{key}.json"));
+        Ok(CacheEntry { path })
+    }
+
+    /// Whether results should be cached in this session.
+    fn is_cacheable(&self) -> bool {
+        !self.args.no_cache && self.reuses_results()
+    }
+
+    /// Whether the result of verifying a harness only depends on the goto program and the
+    /// verification options, so it can be reused by a later run.
+    pub fn reuses_results(&self) -> bool {
+        !self.args.visualize
+            && !self.args.only_vcgen
+            && self.args.concrete_playback.is_none()
+            && !self.args.suggest_assumptions
+            && !self.args.heap_graph
+            && self.args.hardest_properties.is_none()
+            && self.args.dump_formula.is_none()
+            && self.args.proof_mode == ProofMode::Bounded
+            && self.args.output_format != OutputFormat::Old
+    }
+
+    /// Get the cached result of `harness`, if there is one.
+    pub fn cached_result(
+        &self,
+        entry: &CacheEntry,
+        harness: &HarnessMetadata,
+    ) -> Option<VerificationResult> {
+        let reader = BufReader::new(File::open(&entry.path).ok()?);
+        let cached: CachedResult = serde_json::from_reader(reader).ok()?;
+        if cached.kani_version != KANI_VERSION || cached.harness != harness.pretty_name {
+            return None;
+        }
+        Some(VerificationResult::from_properties(
+            cached.properties,
+            harness.attributes.should_panic,
+            Duration::from_secs_f64(cached.runtime_secs),
+        ))
+    }
+
+    /// Store the result of `harness` in the cache, unless it's incomplete.
+    pub fn cache_result(
+        &self,
+        entry: &CacheEntry,
+        harness: &HarnessMetadata,
+        result: &VerificationResult,
+    ) -> Result<()> {
+        let Ok(properties) = &result.results else { return Ok(()) };
+        if properties.iter().any(|prop| prop.status == CheckStatus::Undetermined) {
+            // E.g., the harness timed out.
+            return Ok(());
+        }
+        let cached = CachedResult {
+            kani_version: KANI_VERSION.to_string(),
+            harness: harness.pretty_name.clone(),
+            runtime_secs: result.runtime.as_secs_f64(),
+            properties: properties.clone(),
+        };
+        fs::create_dir_all(entry.path.parent().unwrap())?;
+        serde_json::to_writer(File::create(&entry.path)?, &cached)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(inputs: &[&str]) -> String {
+        let mut key = CacheKey::default();
+        inputs.iter().for_each(|input| key.add(input));
+        key.finish()
+    }
+
+    #[test]
+    fn check_cache_key() {
+        // The key must not change across Rust releases, since the cache outlives the build.
+        assert_eq!(
+            key(&["kani"]),
+            "5ff66f6112d6137a1b0b5f046ad49ea008b478d110d36a63deb2fd375d010a81"
+        );
+        assert_ne!(key(&["ab", "c"]), key(&["a", "bc"]));
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Watch mode: `cargo kani --watch` verifies the project again whenever its source files change.
+//!
+//! Watch mode implies `--incremental`, so only the harnesses affected by a change are verified
+//! again. After each run, we print how the results changed since the previous one.
+//!
+//! We don't depend on the file notification APIs of each platform. Instead, we poll the
+//! modification time of the source files and manifests of the local packages of the workspace.
+
+use anyhow::Result;
+use cargo_metadata::MetadataCommand;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::call_cbmc::VerificationStatus;
+use crate::harness_runner::HarnessRunner;
+use crate::project;
+use crate::session::KaniSession;
+use crate::util::error;
+
+/// How long to wait between two checks for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The status of each harness verified by a run, indexed by the harness name.
+type RunResults = BTreeMap<String, VerificationStatus>;
+
+/// Verify the project every time one of its source files changes. This only returns if the files
+/// to watch can't be determined.
+pub fn watch(session: KaniSession) -> Result<()> {
+    let (roots, ignored) = watched_dirs(&session)?;
+    let mut previous: Option<RunResults> = None;
+    loop {
+        // Take the snapshot before the build, so changes made while verifying trigger a new run.
+        let snapshot = snapshot(&roots, &ignored);
+        match verify_once(&session) {
+            Ok(results) => {
+                if let Some(previous) = &previous {
+                    print_changes(previous, &results);
+                }
+                previous = Some(results);
+            }
+            Err(err) => error(&format!("{err:#}")),
+        }
+        println!("Watching for changes... (press Ctrl+C to stop)");
+        while snapshot == self::snapshot(&roots, &ignored) {
+            thread::sleep(POLL_INTERVAL);
+        }
+        println!("\nChanges detected, verifying again.");
+    }
+}
+
+/// Build the project, verify its harnesses and print the summary.
+fn verify_once(session: &KaniSession) -> Result<RunResults> {
+    let project = project::cargo_project(session, false)?;
+    if session.args.only_codegen {
+        return Ok(RunResults::new());
+    }
+    let harnesses = session.determine_targets(&project.get_all_harnesses())?;
+    let runner = HarnessRunner { sess: session, project: &project };
+    let results = runner.check_all_harnesses(&harnesses)?;
+    session.print_summary(&results)?;
+    Ok(results
+        .iter()
+        .map(|result| (result.harness.pretty_name.clone(), result.result.status))
+        .collect())
+}
+
+/// Print how the results of the harnesses changed since the previous run.
+fn print_changes(previous: &RunResults, current: &RunResults) {
+    let changes = result_changes(previous, current);
+    if changes.is_empty() {
+        println!("No changes to the verification results since the last run.");
+    } else {
+        println!("Changes since the last run:");
+        for change in changes {
+            println!(" - {change}");
+        }
+    }
+}
+
+/// Describe the harnesses whose status changed, that were added, or that were removed.
+fn result_changes(previous: &RunResults, current: &RunResults) -> Vec<String> {
+    let status = |status: &VerificationStatus| match status {
+        VerificationStatus::Success => "SUCCESSFUL",
+        VerificationStatus::Failure => "FAILED",
+    };
+    let mut changes = vec![];
+    for (harness, new) in current {
+        match previous.get(harness) {
+            Some(old) if old == new => {}
+            Some(old) => changes.push(format!("{harness}: {} -> {}", status(old), status(new))),
+            None => changes.push(format!("{harness}: new, {}", status(new))),
+        }
+    }
+    for harness in previous.keys().filter(|harness| !current.contains_key(*harness)) {
+        changes.push(format!("{harness}: removed"));
+    }
+    changes
+}
+
+/// The directories to watch, i.e., the directories of the local packages such as the workspace
+/// members and path dependencies, and the build directories inside them to ignore.
+fn watched_dirs(session: &KaniSession) -> Result<(Vec<PathBuf>, Vec<PathBuf>)> {
+    let mut cmd = MetadataCommand::new();
+    if let Some(path) = &session.args.cargo.manifest_path {
+        cmd.manifest_path(path);
+    }
+    let metadata = cmd.exec()?;
+    let roots = metadata
+        .packages
+        .iter()
+        .filter(|package| package.source.is_none())
+        .filter_map(|package| package.manifest_path.parent())
+        .map(|dir| dir.as_std_path().to_path_buf())
+        .collect();
+    let mut ignored = vec![metadata.target_directory.into_std_path_buf()];
+    if let Some(target_dir) = &session.args.target_dir {
+        ignored.push(target_dir.canonicalize().unwrap_or_else(|_| target_dir.clone()));
+    }
+    Ok((roots, ignored))
+}
+
+/// The modification time of each Rust source file and manifest in the watched directories.
+fn snapshot(roots: &[PathBuf], ignored: &[PathBuf]) -> BTreeMap<PathBuf, SystemTime> {
+    let mut files = BTreeMap::new();
+    for root in roots {
+        collect_files(root, ignored, &mut files);
+    }
+    files
+}
+
+fn collect_files(dir: &Path, ignored: &[PathBuf], files: &mut BTreeMap<PathBuf, SystemTime>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+        let Ok(metadata) = entry.metadata() else { continue };
+        if metadata.is_dir() {
+            if !is_hidden && !ignored.iter().any(|ignored| path.starts_with(ignored)) {
+                collect_files(&path, ignored, files);
+            }
+        } else if matches!(path.extension().and_then(|ext| ext.to_str()), Some("rs" | "toml")) {
+            if let Ok(modified) = metadata.modified() {
+                files.insert(path, modified);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(statuses: &[(&str, VerificationStatus)]) -> RunResults {
+        statuses.iter().map(|(name, status)| (name.to_string(), *status)).collect()
+    }
+
+    #[test]
+    fn check_result_changes() {
+        let previous = results(&[
+            ("fixed", VerificationStatus::Failure),
+            ("broken", VerificationStatus::Success),
+            ("same", VerificationStatus::Success),
+            ("deleted", VerificationStatus::Success),
+        ]);
+        let current = results(&[
+            ("fixed", VerificationStatus::Success),
+            ("broken", VerificationStatus::Failure),
+            ("same", VerificationStatus::Success),
+            ("added", VerificationStatus::Failure),
+        ]);
+        assert_eq!(
+            result_changes(&previous, &current),
+            vec![
+                "added: new, FAILED",
+                "broken: SUCCESSFUL -> FAILED",
+                "fixed: FAILED -> SUCCESSFUL",
+                "deleted: removed",
+            ]
+        );
+        assert!(result_changes(&current, &current).is_empty());
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Verification of the packages of a workspace, each one with its own configuration.
+//!
+//! When `cargo kani` selects more than one package, e.g., at the root of a virtual workspace or
+//! with `--workspace`, each package is built and verified by its own session. The arguments of a
+//! session combine the `[workspace.metadata.kani]` configuration of the workspace, the
+//! `[package.metadata.kani]` configuration of the package, and the command line arguments, in
+//! increasing order of precedence. The results of all packages are summarized at the end.
+
+use anyhow::{Context, Result};
+use cargo_metadata::{Metadata, MetadataCommand, Package};
+use clap::Parser;
+use std::ffi::OsString;
+
+use crate::args::{self, CargoKaniArgs};
+use crate::args_toml::join_member_args;
+use crate::baseline::run_failed;
+use crate::call_cargo::packages_to_verify;
+use crate::call_cbmc::VerificationStatus;
+use crate::harness_runner::HarnessRunner;
+use crate::json_report::{export_reports, exports_reports, save_last_run, HarnessReport};
+use crate::project;
+use crate::session::KaniSession;
+use crate::severity::SeverityPolicy;
+
+/// The verification results of a package.
+struct PackageSummary {
+    name: String,
+    succeeding: usize,
+    failing: usize,
+    /// The status of each harness of the package.
+    statuses: Vec<(String, VerificationStatus)>,
+    /// The results of the harnesses.
+    reports: Vec<HarnessReport>,
+}
+
+/// The packages selected by the arguments of `session` and the metadata of their workspace, if
+/// more than one package should be verified.
+///
+/// We keep a single session with `--exact`, since each harness only exists in one of the
+/// packages.
+pub fn selected_packages(session: &KaniSession) -> Result<Option<(Vec<Package>, Metadata)>> {
+    if session.args.exact {
+        return Ok(None);
+    }
+    let mut cmd = MetadataCommand::new();
+    if let Some(path) = &session.args.cargo.manifest_path {
+        cmd.manifest_path(path);
+    }
+    let metadata = cmd.no_deps().exec()?;
+    let packages = packages_to_verify(&session.args, &metadata)?;
+    if packages.len() < 2 {
+        return Ok(None);
+    }
+    let packages = packages.into_iter().cloned().collect();
+    Ok(Some((packages, metadata)))
+}
+
+/// Verify each package with the arguments given in the command line, `cmd_args`, and the
+/// configuration of the package. Returns an error if a package can't be built. Like the
+/// verification of a single package, this exits with an error code if any harness failed, or if
+/// the failures don't match the `--baseline`.
+pub fn verify_packages(
+    session: KaniSession,
+    cmd_args: &[OsString],
+    packages: &[Package],
+    metadata: &Metadata,
+) -> Result<()> {
+    let workspace_toml = metadata.workspace_root.join("Cargo.toml").into_std_path_buf();
+    let mut summaries = vec![];
+    for package in packages {
+        let member_args = join_member_args(
+            cmd_args.to_vec(),
+            &workspace_toml,
+            package.manifest_path.as_std_path(),
+        )?;
+        let mut member_args = CargoKaniArgs::parse_from(&member_args);
+        args::check_is_valid(&member_args);
+        // The package selection already happened, so only build the package.
+        member_args.verify_opts.cargo.package = vec![package.name.clone()];
+        member_args.verify_opts.cargo.workspace = false;
+        member_args.verify_opts.cargo.exclude = vec![];
+        if !session.args.common_args.quiet {
+            println!("Verifying package `{}`", package.name);
+        }
+        let summary = verify_package(member_args, &package.name)
+            .with_context(|| format!("failed to verify package `{}`", package.name))?;
+        summaries.extend(summary);
+    }
+
+    let succeeding = summaries.iter().map(|summary| summary.succeeding).sum::<usize>();
+    let failing = summaries.iter().map(|summary| summary.failing).sum::<usize>();
+    let total = succeeding + failing;
+    if session.args.only_codegen {
+        return Ok(());
+    }
+    let reports: Vec<_> =
+        summaries.iter_mut().flat_map(|summary| std::mem::take(&mut summary.reports)).collect();
+    if exports_reports(&session.args) {
+        export_reports(&session.args, reports.clone())?;
+    }
+    save_last_run(&session.args, Some(metadata), reports)?;
+    if total == 0 {
+        // Report the missing harnesses like for a single package.
+        return session.print_summary(&[]);
+    }
+    if !session.args.common_args.quiet {
+        println!("Summary per package:");
+        for PackageSummary { name, succeeding, failing, .. } in &summaries {
+            println!(
+                " - {name}: {succeeding} successfully verified harnesses, {failing} failures, {} total.",
+                succeeding + failing
+            );
+        }
+        println!(
+            "Complete - {succeeding} successfully verified harnesses, {failing} failures, {total} total."
+        );
+    }
+    let statuses = summaries
+        .iter()
+        .flat_map(|summary| summary.statuses.iter())
+        .map(|(name, status)| (name.as_str(), *status));
+    if run_failed(session.args.baseline.as_deref(), session.args.common_args.quiet, statuses)? {
+        // Failure exit code without additional error message
+        drop(session);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Build and verify a single package. Returns `None` if the package wasn't verified, e.g., because
+/// it has no harnesses.
+fn verify_package(args: CargoKaniArgs, name: &str) -> Result<Option<PackageSummary>> {
+    let session = KaniSession::new(args.verify_opts)?;
+    let project = project::cargo_project(&session, false)?;
+    if session.args.only_codegen {
+        return Ok(None);
+    }
+    let harnesses = session.determine_targets(&project.get_all_harnesses())?;
+    if harnesses.is_empty() {
+        return Ok(None);
+    }
+    let runner = HarnessRunner { sess: &session, project: &project };
+    let results = runner.check_all_harnesses(&harnesses)?;
+    session.print_summary(&results)?;
+    let reports = HarnessReport::from_results(&session.args, &project.outdir, &results);
+    let policy = SeverityPolicy::new(&session.args.severity);
+    let statuses: Vec<_> = results
+        .iter()
+        .map(|result| (result.harness.pretty_name.clone(), policy.judge(&result.result).status))
+        .collect();
+    let failing =
+        statuses.iter().filter(|(_, status)| *status == VerificationStatus::Failure).count();
+    Ok(Some(PackageSummary {
+        name: name.to_string(),
+        succeeding: results.len() - failing,
+        failing,
+        statuses,
+        reports,
+    }))
+}
@@ -0,0 +1,44 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Data structures to represent the layout of the structs and enums of a goto model, which are
+//! used to render their values in the traces with the names of their fields and variants.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The layout of the structs and enums of a goto model, indexed by the tag of their goto type,
+/// e.g., `tag-Point`.
+pub type AdtMap = BTreeMap<String, AdtLayout>;
+
+/// The layout of a struct or an enum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AdtLayout {
+    /// A struct, or an enum with a single variant, which is represented like a struct.
+    Struct { name: String, fields: Vec<AdtField> },
+    /// An enum whose variant is identified by the `case` component of its goto type, and whose
+    /// fields are in the component of the `cases` union named after the variant.
+    Enum { variants: Vec<AdtVariant> },
+    /// An enum whose variant can't be read from a component of its value, e.g., a niche encoded
+    /// enum like `Option<&T>`.
+    Opaque,
+}
+
+/// A field of a struct or an enum variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdtField {
+    /// The name of the component of the goto type, e.g., `x`, or `0` for tuple fields.
+    pub name: String,
+    /// The tag of the struct or enum stored in the field, or in the elements of the field if
+    /// it's an array.
+    pub tag: Option<String>,
+}
+
+/// A variant of an enum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdtVariant {
+    pub name: String,
+    /// The value of the `case` component for this variant, truncated to its size.
+    pub discriminant: u128,
+    pub fields: Vec<AdtField>,
+}
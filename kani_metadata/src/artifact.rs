@@ -25,6 +25,9 @@ pub enum ArtifactType {
     /// A `json` file that stores the name to prettyName mapping for symbols
     /// (used to demangle names from the C dump).
     PrettyNameMap,
+    /// A `json` file that has the layout of the structs and enums of the goto model, indexed by
+    /// the tag of their goto type (used to render their values in the traces).
+    AdtMap,
 }
 
 impl ArtifactType {
@@ -37,6 +40,7 @@ const fn extension(&self) -> &'static str {
             ArtifactType::TypeMap => "type_map.json",
             ArtifactType::VTableRestriction => "restrictions.json",
             ArtifactType::PrettyNameMap => "pretty_name_map.json",
+            ArtifactType::AdtMap => "adt_map.json",
         }
     }
 }
@@ -64,7 +68,8 @@ pub fn convert_type(path: &Path, from: ArtifactType, to: ArtifactType) -> PathBu
         | ArtifactType::SymTabGoto
         | ArtifactType::TypeMap
         | ArtifactType::VTableRestriction
-        | ArtifactType::PrettyNameMap => {
+        | ArtifactType::PrettyNameMap
+        | ArtifactType::AdtMap => {
             result.set_extension("");
             result.set_extension(to);
         }
@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use serde::{Deserialize, Serialize};
+use strum_macros::{AsRefStr, EnumString, VariantNames};
+
+/// The CBMC slicing options that can be selected for a harness. The driver checks that the
+/// selected option is compatible with the other options before passing it to CBMC.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    AsRefStr,
+    EnumString,
+    VariantNames,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum CbmcSlicing {
+    /// Don't slice the program nor the formula.
+    None,
+    /// Remove the assignments that don't affect any property from the formula
+    /// (CBMC's `--slice-formula`). This is the default.
+    Formula,
+    /// Also remove the instructions that can't reach a property
+    /// (CBMC's `--reachability-slice`).
+    Reachability,
+    /// Also remove the instructions that don't affect any property
+    /// (CBMC's `--full-slice`). This option removes assignments needed to build
+    /// counterexample traces.
+    Full,
+}
@@ -6,7 +6,7 @@
 
 /// An enum for CBMC solver options. All variants are handled by Kani, except for
 /// the `Binary` one, which it passes as is to CBMC's `--external-sat-solver`
-/// option.
+/// option, and the `Portfolio` one, which runs one CBMC process per solver.
 #[derive(Debug, Clone, AsRefStr, EnumString, VariantNames, PartialEq, Eq, Serialize, Deserialize)]
 #[strum(serialize_all = "snake_case")]
 pub enum CbmcSolver {
@@ -23,4 +23,9 @@ pub enum CbmcSolver {
     /// `--external-sat-solver`. The specified binary must exist in path.
     #[strum(disabled, serialize = "bin=<SAT_SOLVER_BINARY>")]
     Binary(String),
+
+    /// Run CBMC with each of these solvers in parallel and use the result of the
+    /// first one to finish. This can only be selected with the `--solver` option.
+    #[strum(disabled, serialize = "portfolio:<SOLVER>,<SOLVER>,...")]
+    Portfolio(Vec<CbmcSolver>),
 }
@@ -0,0 +1,63 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The format of the per-harness coverage files emitted by `--coverage`.
+//!
+//! These files are meant to be consumed by external tools, so any change to these structures
+//! that isn't backwards compatible must bump [`COVERAGE_SCHEMA_VERSION`].
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// The version of the schema used by [`HarnessCoverage`].
+pub const COVERAGE_SCHEMA_VERSION: u32 = 1;
+
+/// The coverage results of a single harness.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HarnessCoverage {
+    /// The version of this schema, i.e. [`COVERAGE_SCHEMA_VERSION`].
+    pub schema_version: u32,
+    /// The fully qualified name of the harness.
+    pub harness: String,
+    /// The source files with coverage regions. Other structures refer to them by their index.
+    pub files: Vec<PathBuf>,
+    /// The functions reachable from the harness with their coverage regions.
+    pub functions: Vec<FunctionCoverage>,
+}
+
+/// The coverage regions of a function. Regions of closures and async blocks are reported as
+/// part of the function they are defined in.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FunctionCoverage {
+    /// The name of the function.
+    pub name: String,
+    /// The index of the file where the function is defined in [`HarnessCoverage::files`].
+    pub file: usize,
+    /// The coverage regions of this function, ordered by their location.
+    pub regions: Vec<CoverageRegion>,
+}
+
+/// A location in the source code instrumented with a coverage check.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CoverageRegion {
+    /// The line where the region starts (1-based).
+    pub line: usize,
+    /// The column where the region starts (1-based).
+    pub column: usize,
+    /// How many times the region is executed. Kani checks whether a region can be reached
+    /// instead of counting the executions, so this is either `0` or `1`.
+    pub count: u64,
+    /// The kind of region.
+    pub kind: CoverageRegionKind,
+}
+
+/// The kind of a coverage region.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageRegionKind {
+    /// A region with executable code.
+    Code,
+    /// A region in a line without executable code, such as a closing brace. These regions
+    /// shouldn't be counted as uncovered code.
+    Gap,
+}
@@ -1,7 +1,7 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
 
-use crate::CbmcSolver;
+use crate::{CbmcSlicing, CbmcSolver};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -35,6 +35,9 @@ pub struct HarnessMetadata {
     pub attributes: HarnessAttributes,
     /// A CBMC-level assigns contract that should be enforced when running this harness.
     pub contract: Option<AssignsContract>,
+    /// The time spent generating the goto model of this harness, in seconds.
+    #[serde(default)]
+    pub codegen_time_secs: Option<f64>,
 }
 
 /// The attributes added by the user to control how a harness is executed.
@@ -44,12 +47,34 @@ pub struct HarnessAttributes {
     pub proof: bool,
     /// Whether the harness is expected to panic or not.
     pub should_panic: bool,
+    /// Whether panics inside `catch_unwind` are recovered.
+    pub catch_unwind: bool,
     /// Optional data to store solver.
     pub solver: Option<CbmcSolver>,
+    /// Optional data to store the slicing options.
+    pub slicing: Option<CbmcSlicing>,
     /// Optional data to store unwind value.
     pub unwind_value: Option<u32>,
     /// The stubs used in this harness.
     pub stubs: Vec<Stub>,
+    /// The function whose contract is checked by a `#[kani::proof_for_contract]` harness.
+    #[serde(default)]
+    pub proof_for_contract: Option<String>,
+    /// The functions replaced by their contract with `#[kani::stub_verified]`.
+    #[serde(default)]
+    pub verified_stubs: Vec<String>,
+    /// The clauses of the contract checked by a `#[kani::proof_for_contract]` harness.
+    #[serde(default)]
+    pub contract_clauses: ContractClauses,
+}
+
+/// The clauses of a function contract, as written in the source, e.g., `divisor != 0` for
+/// `#[kani::requires(divisor != 0)]`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ContractClauses {
+    pub requires: Vec<String>,
+    pub ensures: Vec<String>,
+    pub modifies: Vec<String>,
 }
 
 /// The stubbing type.
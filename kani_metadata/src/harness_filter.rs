@@ -0,0 +1,65 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+use crate::HarnessMetadata;
+use std::fmt::{self, Display};
+
+/// A `--harness` or `--exclude-harness` filter.
+///
+/// The filters are shared by the driver, which selects the harnesses to verify, and the compiler,
+/// which only generates the code of the selected harnesses.
+pub enum HarnessFilter<'a> {
+    /// A harness name, which matches any harness whose name contains it, or only the harness with
+    /// this fully qualified name with `--exact`.
+    Name(&'a str),
+    /// A glob pattern, e.g., `parser::*`, which must match the whole fully qualified name or the
+    /// unqualified name of a harness. It's used if the filter contains `*`, `?` or `[`.
+    Glob(glob::Pattern),
+    /// A regular expression, given with the `regex:` prefix, which must match some part of the
+    /// fully qualified name of a harness.
+    Regex(regex::Regex),
+}
+
+/// The error of a filter that isn't a valid glob pattern or regular expression.
+#[derive(Debug)]
+pub struct HarnessFilterError(String);
+
+impl Display for HarnessFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for HarnessFilterError {}
+
+impl<'a> HarnessFilter<'a> {
+    pub fn parse(filter: &'a str) -> Result<Self, HarnessFilterError> {
+        let error = |err: &dyn Display| HarnessFilterError(err.to_string());
+        if let Some(regex) = filter.strip_prefix("regex:") {
+            Ok(HarnessFilter::Regex(regex::Regex::new(regex).map_err(|err| error(&err))?))
+        } else if filter.contains(['*', '?', '[']) {
+            Ok(HarnessFilter::Glob(glob::Pattern::new(filter).map_err(|err| error(&err))?))
+        } else {
+            Ok(HarnessFilter::Name(filter))
+        }
+    }
+
+    pub fn matches(&self, md: &HarnessMetadata, exact_filter: bool) -> bool {
+        match self {
+            // Check for exact match only
+            HarnessFilter::Name(name) if exact_filter => md.pretty_name == *name,
+            // Either an exact match, or a substring match. We check the exact first since it's
+            // cheaper.
+            HarnessFilter::Name(name) => {
+                md.pretty_name == *name
+                    || md.get_harness_name_unqualified() == *name
+                    || md.pretty_name.contains(name)
+            }
+            HarnessFilter::Glob(pattern) => {
+                pattern.matches(&md.pretty_name)
+                    || (!exact_filter && pattern.matches(md.get_harness_name_unqualified()))
+            }
+            HarnessFilter::Regex(regex) => regex.is_match(&md.pretty_name),
+        }
+    }
+}
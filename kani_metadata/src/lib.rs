@@ -7,14 +7,22 @@
 
 use serde::{Deserialize, Serialize};
 
+pub use adt::*;
 pub use artifact::ArtifactType;
+pub use cbmc_slicing::CbmcSlicing;
 pub use cbmc_solver::CbmcSolver;
+pub use coverage::*;
 pub use harness::*;
+pub use harness_filter::{HarnessFilter, HarnessFilterError};
 pub use vtable::*;
 
+mod adt;
 pub mod artifact;
+mod cbmc_slicing;
 mod cbmc_solver;
+mod coverage;
 mod harness;
+mod harness_filter;
 pub mod unstable;
 mod vtable;
 
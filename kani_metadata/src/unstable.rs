@@ -87,6 +87,31 @@ pub enum UnstableFeature {
     /// Automatically check that no invalid value is produced which is considered UB in Rust.
     /// Note that this does not include checking uninitialized value.
     ValidValueChecks,
+    /// Automatically check that references aren't used after a conflicting access, following
+    /// the Tree Borrows aliasing model.
+    AliasingChecks,
+    /// Automatically check that pointer arithmetic stays within the allocation and that
+    /// integer-to-pointer casts don't lose the provenance of the pointer.
+    ProvenanceChecks,
+    /// Automatically check that memory is initialized before it's read.
+    UninitChecks,
+    /// Automatically check that raw pointers are aligned when they are dereferenced.
+    AlignmentChecks,
+    /// Model the ordering of atomic operations with a weak memory model.
+    WeakMemory,
+    /// Allow recovering from panics with `catch_unwind`.
+    CatchUnwind,
+    /// Model inline assembly with nondeterministic outputs instead of failing verification.
+    InlineAsm,
+    /// Allow verifying harnesses with const generic parameters for a range of values.
+    ConstGenericHarness,
+    /// Narrow the possible targets of calls through function pointers with a MIR analysis.
+    RestrictFnPtrs,
+    /// Replace the code that can only lead to a panic of the standard library, such as the
+    /// formatting of its message, by a panic with a placeholder message.
+    AbstractPanicFmt,
+    /// Allow loading instrumentation passes from dynamic libraries with `--instrumentation-pass`.
+    InstrumentationPasses,
 }
 
 impl UnstableFeature {
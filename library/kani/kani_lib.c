@@ -18,6 +18,9 @@ typedef __CPROVER_bool bool;
 struct Unit;
 extern struct Unit VoidUnit;
 
+/// The never type `!` is declared by the Kani compiler as an empty struct.
+struct Never;
+
 // `assert` then `assume`
 #define __KANI_assert(cond, msg)            \
     do {                                    \
@@ -29,6 +32,17 @@ extern struct Unit VoidUnit;
 // Check that the input is either a power of 2, or 0. Algorithm from Hackers Delight.
 bool __KANI_is_nonzero_power_of_two(size_t i) { return (i != 0) && (i & (i - 1)) == 0; }
 
+// With `--minimize-counterexamples`, this function is called with the bytes of every symbolic value
+// created by `kani::any()`. This definition does nothing, and it's replaced by a definition that
+// constrains the values to search for a smaller counterexample.
+__attribute__((weak)) struct Unit __KANI_nondet_created(const uint8_t *value, size_t size)
+{
+    return VoidUnit;
+}
+
+// The definitions of the Rust allocation functions below are weak, so that the ones generated for a
+// `#[global_allocator]` replace them when the crate under verification defines one.
+
 // This is a C implementation of the __rust_alloc function.
 // https://stdrs.dev/nightly/x86_64-unknown-linux-gnu/alloc/alloc/fn.__rust_alloc.html
 // It has the following Rust signature:
@@ -39,7 +53,7 @@ bool __KANI_is_nonzero_power_of_two(size_t i) { return (i != 0) && (i & (i - 1))
 // definition.
 // For safety, refer to the documentation of GlobalAlloc::alloc:
 // https://doc.rust-lang.org/std/alloc/trait.GlobalAlloc.html#tymethod.alloc
-uint8_t *__rust_alloc(size_t size, size_t align)
+__attribute__((weak)) uint8_t *__rust_alloc(size_t size, size_t align)
 {
     __KANI_assert(size > 0, "__rust_alloc must be called with a size greater than 0");
     // TODO: Ensure we are doing the right thing with align
@@ -58,7 +72,7 @@ uint8_t *__rust_alloc(size_t size, size_t align)
 // definition.
 // For safety, refer to the documentation of GlobalAlloc::alloc_zeroed:
 // hhttps://doc.rust-lang.org/std/alloc/fn.alloc_zeroed.html
-uint8_t *__rust_alloc_zeroed(size_t size, size_t align)
+__attribute__((weak)) uint8_t *__rust_alloc_zeroed(size_t size, size_t align)
 {
     __KANI_assert(size > 0, "__rust_alloc_zeroed must be called with a size greater than 0");
     // TODO: Ensure we are doing the right thing with align
@@ -77,7 +91,7 @@ uint8_t *__rust_alloc_zeroed(size_t size, size_t align)
 // definition.
 // For safety, refer to the documentation of GlobalAlloc::dealloc:
 // https://doc.rust-lang.org/std/alloc/trait.GlobalAlloc.html#tymethod.dealloc
-struct Unit __rust_dealloc(uint8_t *ptr, size_t size, size_t align)
+__attribute__((weak)) struct Unit __rust_dealloc(uint8_t *ptr, size_t size, size_t align)
 {
     // TODO: Ensure we are doing the right thing with align
     // https://github.com/model-checking/kani/issues/1168
@@ -97,7 +111,7 @@ struct Unit __rust_dealloc(uint8_t *ptr, size_t size, size_t align)
 // definition.
 // For safety, refer to the documentation of GlobalAlloc::realloc:
 // https://doc.rust-lang.org/std/alloc/trait.GlobalAlloc.html#method.realloc
-uint8_t *__rust_realloc(uint8_t *ptr, size_t old_size, size_t align, size_t new_size)
+__attribute__((weak)) uint8_t *__rust_realloc(uint8_t *ptr, size_t old_size, size_t align, size_t new_size)
 {
     // Passing a NULL pointer is undefined behavior
     __KANI_assert(ptr != 0, "rust_realloc must be called with a non-null pointer");
@@ -118,3 +132,16 @@ uint8_t *__rust_realloc(uint8_t *ptr, size_t old_size, size_t align, size_t new_
 
     return result;
 }
+
+// This is a C implementation of the __rust_alloc_error_handler function that has the following
+// signature:
+//     fn __rust_alloc_error_handler(size: usize, align: usize) -> !;
+// This low-level function is called by std::alloc::handle_alloc_error, and its implementation is
+// generated by the compiler backend together with the global allocator.
+// The default handler aborts the program. Allocations may only fail when Kani runs with
+// `--allocator-may-fail`, otherwise this function is unreachable.
+struct Never __rust_alloc_error_handler(size_t size, size_t align)
+{
+    __CPROVER_assert(0, "memory allocation failed");
+    __CPROVER_assume(0);
+}
@@ -35,6 +35,9 @@ fn any() -> Self {
                 // This size_of call does not use generic_const_exprs feature. It's inside a macro, and Self isn't generic.
                 unsafe { crate::any_raw_internal::<Self, { std::mem::size_of::<Self>() }>() }
             }
+            // The trace reports the value of each element of a nondet array, so the arrays are
+            // played back element by element, with the default implementation.
+            #[cfg(not(feature = "concrete_playback"))]
             fn any_array<const MAX_ARRAY_LENGTH: usize>() -> [Self; MAX_ARRAY_LENGTH]
             where
                 // `generic_const_exprs` requires all potential errors to be reflected in the signature/header.
@@ -3,15 +3,24 @@
 
 //! Helper code for concrete playback.
 
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::panic::{self, AssertUnwindSafe};
 
 thread_local! {
     /// thread_local! gives us a separate CONCRETE_VALS instance for each thread.
     /// This allows us to run concrete playback unit tests in parallel.
     /// RefCell is necessary for mut statics.
     static CONCRETE_VALS: RefCell<Vec<Vec<u8>>> = RefCell::new(Vec::new());
+
+    /// Whether the harness runs with trivial values instead of concrete values, see
+    /// [concrete_playback_test_run].
+    static TRIVIAL_VALS: Cell<bool> = Cell::new(false);
 }
 
+/// The payload used to stop a harness that runs with trivial values when one of its assumptions
+/// doesn't hold.
+struct AssumptionViolated;
+
 /// This function sets concrete values and plays back the user's proof harness.
 pub fn concrete_playback_run<F: Fn()>(mut local_concrete_vals: Vec<Vec<u8>>, proof_harness: F) {
     // Det vals in the user test case should be in the same order as the order of kani::any() calls.
@@ -40,6 +49,36 @@ pub fn concrete_playback_run<F: Fn()>(mut local_concrete_vals: Vec<Vec<u8>>, pro
     });
 }
 
+/// This function plays back the user's proof harness with trivial values, which is how
+/// `cargo kani test` runs a harness as a unit test: every `kani::any()` returns the value whose
+/// bytes are all zeros. If an assumption doesn't hold for these values, the rest of the harness
+/// is skipped and the test succeeds, since the harness doesn't apply to them.
+pub fn concrete_playback_test_run<F: Fn()>(proof_harness: F) {
+    CONCRETE_VALS.with(|glob_concrete_vals| glob_concrete_vals.borrow_mut().clear());
+    TRIVIAL_VALS.with(|trivial_vals| trivial_vals.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(proof_harness));
+    TRIVIAL_VALS.with(|trivial_vals| trivial_vals.set(false));
+    if let Err(payload) = result {
+        if payload.is::<AssumptionViolated>() {
+            println!(
+                "An assumption doesn't hold for the trivial values. Skipping the rest of the harness."
+            );
+        } else {
+            panic::resume_unwind(payload);
+        }
+    }
+}
+
+/// Concrete playback implementation of kani::assume. When the harness runs with trivial values,
+/// a violated assumption stops the harness without failing the test.
+pub(crate) fn assume(cond: bool) {
+    if !cond && TRIVIAL_VALS.with(|trivial_vals| trivial_vals.get()) {
+        // Unlike `panic!`, this doesn't print a panic message.
+        panic::resume_unwind(Box::new(AssumptionViolated));
+    }
+    assert!(cond, "`kani::assume` should always hold");
+}
+
 /// Concrete playback implementation of
 /// kani::any_raw_internal. Because CBMC does not bother putting in
 /// Zero-Sized Types, those are defaulted to an empty vector.
@@ -51,10 +90,12 @@ pub(crate) unsafe fn any_raw_internal<T, const SIZE_T: usize>() -> T {
     let mut next_concrete_val: Vec<u8> = Vec::new();
     CONCRETE_VALS.with(|glob_concrete_vals| {
         let mut_ref_glob_concrete_vals = &mut *glob_concrete_vals.borrow_mut();
-        next_concrete_val = if SIZE_T > 0 {
-            mut_ref_glob_concrete_vals.pop().expect("Not enough det vals found")
-        } else {
+        next_concrete_val = if SIZE_T == 0 {
             vec![]
+        } else if TRIVIAL_VALS.with(|trivial_vals| trivial_vals.get()) {
+            vec![0; SIZE_T]
+        } else {
+            mut_ref_glob_concrete_vals.pop().expect("Not enough det vals found")
         };
     });
     let next_concrete_val_len = next_concrete_val.len();
@@ -6,32 +6,68 @@
 use std::{
     future::Future,
     pin::Pin,
+    sync::atomic::{AtomicBool, Ordering},
     task::{Context, RawWaker, RawWakerVTable, Waker},
 };
 
-/// A very simple executor: it polls the future in a busy loop until completion
+/// A simple deterministic executor: it polls the future until completion
 ///
 /// This is intended as a drop-in replacement for `futures::block_on`, which Kani cannot handle.
 /// Whereas a clever executor like `block_on` in `futures` or `tokio` would interact with the OS scheduler
 /// to be woken up when a resource becomes available, this is not supported by Kani.
-/// As a consequence, this function completely ignores the waker infrastructure and just polls the given future in a busy loop.
+/// The only events that can make a pending future progress are the ones triggered by the future itself.
+/// As a consequence, this function polls the future again right away whenever it returns `Poll::Pending`,
+/// after checking that the future woke its waker.
+/// A future that is pending without having woken its waker would never complete with a real executor,
+/// so this is reported as a failed check instead of polling the future forever.
+///
+/// The loop that polls the future needs to be unwound once per poll, see `#[kani::unwind]`.
 ///
 /// Note that [`spawn`] is not supported with this function. Use [`block_on_with_spawn`] if you need it.
-#[crate::unstable(feature = "async-lib", issue = 2559, reason = "experimental async support")]
 pub fn block_on<T>(mut fut: impl Future<Output = T>) -> T {
-    let waker = unsafe { Waker::from_raw(NOOP_RAW_WAKER) };
+    let waker = unsafe { Waker::from_raw(FLAG_RAW_WAKER) };
     let cx = &mut Context::from_waker(&waker);
     // SAFETY: we shadow the original binding, so it cannot be accessed again for the rest of the scope.
     // This is the same as what the pin_mut! macro in the futures crate does.
     let mut fut = unsafe { Pin::new_unchecked(&mut fut) };
+    // The future may itself call `block_on`, so the flag of the caller is restored once we're done.
+    let caller_woken = WOKEN.load(Ordering::Relaxed);
     loop {
+        WOKEN.store(false, Ordering::Relaxed);
         match fut.as_mut().poll(cx) {
-            std::task::Poll::Ready(res) => return res,
-            std::task::Poll::Pending => continue,
+            std::task::Poll::Ready(res) => {
+                WOKEN.store(caller_woken, Ordering::Relaxed);
+                return res;
+            }
+            std::task::Poll::Pending => crate::assert(
+                WOKEN.load(Ordering::Relaxed),
+                "the future is pending but it didn't wake its waker, so it can never complete",
+            ),
         }
     }
 }
 
+/// Whether the waker of [`block_on`] was woken since the last poll.
+static WOKEN: AtomicBool = AtomicBool::new(false);
+
+/// The waker used by [`block_on`], which sets [`WOKEN`] when it's woken.
+const FLAG_RAW_WAKER: RawWaker = {
+    #[inline]
+    unsafe fn clone_waker(_: *const ()) -> RawWaker {
+        FLAG_RAW_WAKER
+    }
+
+    #[inline]
+    unsafe fn wake(_: *const ()) {
+        WOKEN.store(true, Ordering::Relaxed)
+    }
+
+    #[inline]
+    unsafe fn noop(_: *const ()) {}
+
+    RawWaker::new(std::ptr::null(), &RawWakerVTable::new(clone_waker, wake, wake, noop))
+};
+
 /// A dummy waker, which is needed to call [`Future::poll`]
 const NOOP_RAW_WAKER: RawWaker = {
     #[inline]
@@ -210,7 +246,7 @@ pub fn block_on_with_spawn<F: Future<Output = ()> + Sync + 'static>(
 /// Suspends execution of the current future, to allow the scheduler to poll another future
 ///
 /// Specifically, it returns a future that isn't ready until the second time it is polled.
-#[crate::unstable(feature = "async-lib", issue = 2559, reason = "experimental async support")]
+/// The first poll wakes the waker, so the future can be used with [`block_on`].
 pub fn yield_now() -> impl Future<Output = ()> {
     struct YieldNow {
         yielded: bool,
@@ -221,10 +257,11 @@ impl Future for YieldNow {
 
         fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> std::task::Poll<Self::Output> {
             if self.yielded {
-                cx.waker().wake_by_ref(); // For completeness. But Kani currently ignores wakers.
                 std::task::Poll::Ready(())
             } else {
                 self.yielded = true;
+                // Ask to be polled again, which is needed by `block_on`.
+                cx.waker().wake_by_ref();
                 std::task::Poll::Pending
             }
         }
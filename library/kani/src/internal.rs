@@ -76,3 +76,129 @@ unsafe fn assignable(self) -> &'a mut Self::Inner {
 pub fn untracked_deref<T>(_: &T) -> T {
     todo!()
 }
+
+/// Kani's model of `std::panic::catch_unwind`, which is used by the `std` overrides.
+///
+/// Panics are only recovered in harnesses annotated with `#[kani::catch_unwind]`. In that case,
+/// a panic inside `f` returns from every function until it reaches this function, which returns
+/// an error. Since the panic message isn't tracked, the error payload is a placeholder string.
+#[inline(never)]
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniCatchUnwind"]
+pub fn catch_unwind<F: FnOnce() -> R, R>(f: F) -> Result<R, Box<dyn std::any::Any + Send>> {
+    catch_unwind_enter();
+    // The result is invalid if `f` panicked, so it must not be dropped.
+    let result = std::mem::ManuallyDrop::new(f());
+    if catch_unwind_exit() {
+        Err(Box::new("panic caught by Kani's `catch_unwind` model"))
+    } else {
+        Ok(std::mem::ManuallyDrop::into_inner(result))
+    }
+}
+
+#[inline(never)]
+#[rustc_diagnostic_item = "KaniCatchUnwindEnter"]
+fn catch_unwind_enter() {}
+
+/// Returns whether a panic was caught.
+#[inline(never)]
+#[rustc_diagnostic_item = "KaniCatchUnwindExit"]
+fn catch_unwind_exit() -> bool {
+    false
+}
+
+/// Check that a value read from a union field is valid for the type of the field. This is added
+/// by `-Z valid-value-checks`, and failures are reported with the `invalid_union_read` property
+/// class instead of the class of assertions.
+#[cfg(not(feature = "concrete_playback"))]
+#[inline(never)]
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniUnionReadCheck"]
+pub fn union_read_check(cond: bool, msg: &'static str) {
+    let _ = cond;
+    let _ = msg;
+}
+
+#[cfg(feature = "concrete_playback")]
+#[inline(never)]
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniUnionReadCheck"]
+pub fn union_read_check(cond: bool, msg: &'static str) {
+    assert!(cond, "{}", msg);
+}
+
+// The permissions of the references tracked by `-Z aliasing-checks`, following Tree Borrows.
+// See <https://perso.crans.org/vanille/treebor/> for more details.
+
+/// The reference hasn't been created in the current execution.
+const ALIASING_UNTRACKED: u8 = 0;
+/// A mutable reference that hasn't been written to.
+const ALIASING_RESERVED: u8 = 1;
+/// A mutable reference that has been written to.
+const ALIASING_ACTIVE: u8 = 2;
+/// A reference that can only be read, i.e., a shared reference or an active mutable reference
+/// after a foreign read.
+const ALIASING_FROZEN: u8 = 3;
+/// A reference that can no longer be used.
+const ALIASING_DISABLED: u8 = 4;
+
+/// The permission of a new reference. `is_mut` indicates whether the reference is mutable.
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniAliasingRetag"]
+pub fn aliasing_retag(is_mut: bool) -> u8 {
+    if is_mut { ALIASING_RESERVED } else { ALIASING_FROZEN }
+}
+
+/// Update the permission `state` of a reference after an access through the reference or
+/// through a pointer derived from it.
+///
+/// Returns whether the access is allowed.
+///
+/// # Safety
+///
+/// `state` must point to the permission of a tracked reference. Note that these functions cannot
+/// create references, since they would be instrumented as well.
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniAliasingChildAccess"]
+pub unsafe fn aliasing_child_access(state: *mut u8, is_write: bool) -> bool {
+    match *state {
+        ALIASING_DISABLED => false,
+        ALIASING_FROZEN => !is_write,
+        ALIASING_RESERVED | ALIASING_ACTIVE if is_write => {
+            *state = ALIASING_ACTIVE;
+            true
+        }
+        _ => true,
+    }
+}
+
+/// Update the permission `state` of the reference to the `tracked_size` bytes at `tracked` after
+/// an access to `size` bytes at `ptr` that doesn't go through the reference.
+///
+/// # Safety
+///
+/// `state` must point to the permission of a tracked reference.
+#[doc(hidden)]
+#[rustc_diagnostic_item = "KaniAliasingForeignAccess"]
+pub unsafe fn aliasing_foreign_access(
+    state: *mut u8,
+    tracked: *const u8,
+    tracked_size: usize,
+    ptr: *const u8,
+    size: usize,
+    is_write: bool,
+) {
+    if *state == ALIASING_UNTRACKED || *state == ALIASING_DISABLED {
+        return;
+    }
+    let tracked = tracked as usize;
+    let ptr = ptr as usize;
+    if ptr >= tracked.wrapping_add(tracked_size) || tracked >= ptr.wrapping_add(size) {
+        return;
+    }
+    if is_write {
+        *state = ALIASING_DISABLED;
+    } else if *state == ALIASING_ACTIVE {
+        *state = ALIASING_FROZEN;
+    }
+}
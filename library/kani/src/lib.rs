@@ -25,6 +25,7 @@
 pub mod futures;
 pub mod mem;
 pub mod slice;
+pub mod thread;
 pub mod tuple;
 pub mod vec;
 
@@ -35,13 +36,19 @@
 
 pub use arbitrary::Arbitrary;
 #[cfg(feature = "concrete_playback")]
-pub use concrete_playback::concrete_playback_run;
+pub use concrete_playback::{concrete_playback_run, concrete_playback_test_run};
 
 #[cfg(not(feature = "concrete_playback"))]
 /// NOP `concrete_playback` for type checking during verification mode.
 pub fn concrete_playback_run<F: Fn()>(_: Vec<Vec<u8>>, _: F) {
     unreachable!("Concrete playback does not work during verification")
 }
+
+#[cfg(not(feature = "concrete_playback"))]
+/// NOP `concrete_playback_test_run` for type checking during verification mode.
+pub fn concrete_playback_test_run<F: Fn()>(_: F) {
+    unreachable!("Concrete playback does not work during verification")
+}
 pub use futures::{block_on, block_on_with_spawn, spawn, yield_now, RoundRobin};
 
 /// Creates an assumption that will be valid after this statement run. Note that the assumption
@@ -78,7 +85,7 @@ pub fn assume(cond: bool) {
 #[rustc_diagnostic_item = "KaniAssume"]
 #[cfg(feature = "concrete_playback")]
 pub fn assume(cond: bool) {
-    assert!(cond, "`kani::assume` should always hold");
+    concrete_playback::assume(cond)
 }
 
 /// `implies!(premise => conclusion)` means that if the `premise` is true, so
@@ -0,0 +1,103 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module contains functions to spawn threads in Kani harnesses.
+//!
+//! Threads spawned with [`spawn`] run concurrently with the harness, and Kani checks the
+//! properties of the harness for all the interleavings of the threads. Kani's model of
+//! concurrency doesn't support an unbounded number of threads: a thread spawned in a loop
+//! creates as many threads as the unwinding bound of the loop allows. `std::thread::spawn` and
+//! `std::thread::Builder::spawn` are replaced by this model in the harnesses.
+//!
+//! Atomic operations are sequentially consistent unless a weak memory model is selected with
+//! `--memory-model`. Other operations that synchronize threads, like locking a `Mutex`, aren't
+//! modeled yet.
+//!
+//! ```no_run
+//! use std::sync::atomic::{AtomicU32, Ordering};
+//! use std::sync::Arc;
+//!
+//! #[kani::proof]
+//! fn check_counter() {
+//!     let counter = Arc::new(AtomicU32::new(0));
+//!     let other = counter.clone();
+//!     let handle = kani::thread::spawn(move || other.fetch_add(1, Ordering::SeqCst));
+//!     counter.fetch_add(1, Ordering::SeqCst);
+//!     handle.join();
+//!     assert_eq!(counter.load(Ordering::SeqCst), 2);
+//! }
+//! ```
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The state shared between a thread and its [`JoinHandle`].
+struct Packet<F, T> {
+    f: Option<F>,
+    result: Option<T>,
+    finished: AtomicBool,
+}
+
+/// A handle to wait for the termination of a thread spawned with [`spawn`].
+pub struct JoinHandle<T> {
+    packet: *mut Packet<Box<dyn FnOnce() -> T + Send>, T>,
+}
+
+// SAFETY: The packet is only accessed by the thread until it finishes, and then by the handle.
+unsafe impl<T: Send> Send for JoinHandle<T> {}
+// SAFETY: A shared handle only reads the packet with an atomic load in `is_finished`.
+unsafe impl<T> Sync for JoinHandle<T> {}
+
+impl<T> JoinHandle<T> {
+    /// Wait for the thread to finish and return the value returned by its closure.
+    ///
+    /// Interleavings where the thread never finishes are discarded.
+    pub fn join(self) -> T {
+        // SAFETY: The packet was allocated by `spawn`, and it's only freed here.
+        let packet = unsafe { Box::from_raw(self.packet) };
+        crate::assume(packet.finished.load(Ordering::SeqCst));
+        packet.result.unwrap()
+    }
+
+    /// Check if the thread has finished running its closure.
+    pub fn is_finished(&self) -> bool {
+        // SAFETY: The packet is valid until the handle is joined.
+        unsafe { (*self.packet).finished.load(Ordering::SeqCst) }
+    }
+}
+
+/// Spawn a new thread that runs `f` concurrently with the current thread.
+///
+/// A thread that isn't joined is still checked, even if the harness finishes first.
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let f: Box<dyn FnOnce() -> T + Send> = Box::new(f);
+    let packet = Box::into_raw(Box::new(Packet {
+        f: Some(f),
+        result: None,
+        finished: AtomicBool::new(false),
+    }));
+    start_thread(run::<T>, packet);
+    JoinHandle { packet }
+}
+
+/// The body of a thread spawned with [`spawn`].
+fn run<T>(packet: *mut Packet<Box<dyn FnOnce() -> T + Send>, T>) {
+    // SAFETY: The handle doesn't access the packet until the thread finishes.
+    let packet = unsafe { &mut *packet };
+    let f = packet.f.take().unwrap();
+    packet.result = Some(f());
+    packet.finished.store(true, Ordering::SeqCst);
+}
+
+/// Start a thread that runs `f(arg)`.
+///
+/// Kani replaces the call by a new thread. Outside of Kani, e.g., during concrete playback, the
+/// function is executed in the current thread, which is one of the possible interleavings.
+#[inline(never)]
+#[rustc_diagnostic_item = "KaniStartThread"]
+fn start_thread<P>(f: fn(*mut P), arg: *mut P) {
+    f(arg)
+}
@@ -52,6 +52,18 @@ pub fn should_panic(attr: TokenStream, item: TokenStream) -> TokenStream {
     attr_impl::should_panic(attr, item)
 }
 
+/// Specifies that panics inside `std::panic::catch_unwind` are recovered in this harness.**
+///
+/// By default, every panic is reported as a verification failure. With this attribute, a panic
+/// inside a closure given to `catch_unwind` returns from the closure, and `catch_unwind` returns
+/// an error, as it would at runtime. Destructors aren't executed while the stack unwinds.
+///
+/// This is an unstable feature and requires `-Z catch-unwind`.
+#[proc_macro_attribute]
+pub fn catch_unwind(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attr_impl::catch_unwind(attr, item)
+}
+
 /// Specifies that a function contains recursion for contract instrumentation.**
 ///
 /// This attribute is only used for function-contract instrumentation. Kani uses
@@ -70,6 +82,24 @@ pub fn unwind(attr: TokenStream, item: TokenStream) -> TokenStream {
     attr_impl::unwind(attr, item)
 }
 
+/// Verify a proof harness for every value of a const generic parameter in a range.
+///
+/// The attribute `#[kani::const_generic(N, 0..=8)]` can only be used alongside `#[kani::proof]`.
+/// Kani verifies one instance of the harness for each value of `N` in the range, and reports
+/// their results separately, e.g., `harness::<0>`, `harness::<1>`, etc. If the harness has several
+/// const generic parameters, every combination of their values is verified.
+///
+/// # Arguments
+/// * `N` - The name of an integer const generic parameter of the harness.
+/// * `range` - The values of the parameter, as an exclusive (`0..8`) or inclusive (`0..=8`) range
+///   of integer literals.
+///
+/// This is an unstable feature and requires `-Z const-generic-harness`.
+#[proc_macro_attribute]
+pub fn const_generic(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attr_impl::const_generic(attr, item)
+}
+
 /// Specify a function/method stub pair to use for proof harness
 ///
 /// The attribute `#[kani::stub(original, replacement)]` can only be used alongside `#[kani::proof]`.
@@ -92,6 +122,29 @@ pub fn solver(attr: TokenStream, item: TokenStream) -> TokenStream {
     attr_impl::solver(attr, item)
 }
 
+/// Select the CBMC slicing options to use for this harness
+///
+/// The attribute `#[kani::slicing(arg)]` can only be used alongside `#[kani::proof]`.
+///
+/// arg - Either `none`, `formula`, `reachability` or `full`.
+#[proc_macro_attribute]
+pub fn slicing(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attr_impl::slicing(attr, item)
+}
+
+/// Turn coverage instrumentation on or off for an item and all the items nested inside it.
+///
+/// The attribute `#[kani::coverage(off)]` can be applied to functions, `impl` blocks, traits and
+/// modules to exclude their code from the coverage results reported by `--coverage`.
+/// The closest attribute to a function takes precedence, so `#[kani::coverage(on)]` can be used to
+/// re-enable coverage for an item inside an excluded one.
+///
+/// arg - Either `on` or `off`.
+#[proc_macro_attribute]
+pub fn coverage(attr: TokenStream, item: TokenStream) -> TokenStream {
+    attr_impl::coverage(attr, item)
+}
+
 /// Mark an API as unstable. This should only be used inside the Kani sysroot.
 /// See https://model-checking.github.io/kani/rfc/rfcs/0006-unstable-api.html for more details.
 #[doc(hidden)]
@@ -277,6 +330,7 @@ pub fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
             #[allow(dead_code)]
             #[kanitool::proof]
         );
+        let unit_test = playback_unit_test(&attrs, &sig);
 
         if sig.asyncness.is_none() {
             if proof_options.schedule.is_some() {
@@ -290,6 +344,8 @@ pub fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
                 #kani_attributes
                 #(#attrs)*
                 #vis #sig #body
+
+                #unit_test
             )
             .into()
         } else {
@@ -335,17 +391,79 @@ pub fn proof(attr: TokenStream, item: TokenStream) -> TokenStream {
                     #sig #body
                     #block_on_call
                 }
+
+                #unit_test
             )
             .into()
         }
     }
 
+    /// When building Kani's playback library, a proof harness also expands to a unit test that
+    /// runs it with trivial values under `cfg(kani_playback)`, which is how `cargo kani test`
+    /// checks that the harnesses still compile and run.
+    #[cfg(feature = "concrete_playback")]
+    fn playback_unit_test(
+        attrs: &[syn::Attribute],
+        sig: &syn::Signature,
+    ) -> proc_macro2::TokenStream {
+        if !sig.inputs.is_empty() || !sig.generics.params.is_empty() {
+            // The compiler reports the invalid harness.
+            return quote!();
+        }
+        let harness = &sig.ident;
+        let test_name = format_ident!("kani_test_{}", harness);
+        // The trivial values may not trigger the expected panic.
+        let should_panic = attrs.iter().any(|attr| {
+            attr.path().segments.last().is_some_and(|segment| segment.ident == "should_panic")
+        });
+        let ignore = should_panic
+            .then(|| quote!(#[ignore = "the harness is expected to panic for some values"]));
+        quote!(
+            #[cfg(kani_playback)]
+            #[test]
+            #ignore
+            fn #test_name() {
+                kani::concrete_playback_test_run(#harness);
+            }
+        )
+    }
+
+    #[cfg(not(feature = "concrete_playback"))]
+    fn playback_unit_test(
+        _attrs: &[syn::Attribute],
+        _sig: &syn::Signature,
+    ) -> proc_macro2::TokenStream {
+        quote!()
+    }
+
     kani_attribute!(should_panic, no_args);
+    kani_attribute!(catch_unwind, no_args);
+    kani_attribute!(const_generic);
     kani_attribute!(recursion, no_args);
+    kani_attribute!(slicing);
     kani_attribute!(solver);
     kani_attribute!(stub);
     kani_attribute!(unstable);
     kani_attribute!(unwind);
+
+    pub fn coverage(attr: TokenStream, item: TokenStream) -> TokenStream {
+        let args = proc_macro2::TokenStream::from(attr);
+        let item = parse_macro_input!(item as syn::Item);
+        if !matches!(
+            item,
+            syn::Item::Fn(_) | syn::Item::Impl(_) | syn::Item::Mod(_) | syn::Item::Trait(_)
+        ) {
+            abort!(
+                item,
+                "`#[kani::coverage]` can only be applied to functions, `impl` blocks, traits and modules"
+            );
+        }
+        quote!(
+            #[kanitool::coverage(#args)]
+            #item
+        )
+        .into()
+    }
 }
 
 /// This module provides dummy implementations of Kani attributes which cannot be interpreted by
@@ -374,11 +492,15 @@ pub fn proof(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 
     no_op!(should_panic);
+    no_op!(catch_unwind);
+    no_op!(const_generic);
     no_op!(recursion);
+    no_op!(slicing);
     no_op!(solver);
     no_op!(stub);
     no_op!(unstable);
     no_op!(unwind);
+    no_op!(coverage);
     no_op!(requires);
     no_op!(ensures);
     no_op!(modifies);
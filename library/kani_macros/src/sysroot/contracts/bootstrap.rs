@@ -8,7 +8,10 @@
 use quote::quote;
 use syn::ItemFn;
 
-use super::{helpers::*, shared::identifier_for_generated_function, ContractConditionsHandler};
+use super::{
+    contract_clause_attr, helpers::*, shared::identifier_for_generated_function,
+    ContractConditionsHandler,
+};
 
 impl<'a> ContractConditionsHandler<'a> {
     /// The complex case. We are the first time a contract is handled on this function, so
@@ -58,9 +61,11 @@ pub fn handle_untouched(&mut self) {
         // The same care is taken when we emit check and replace functions.
         // emit the check function.
         let is_impl_fn = is_probably_impl_fn(&self.annotated_fn);
+        let clause_attr = contract_clause_attr(self.condition_kind, &self.attr_copy);
         let ItemFn { attrs, vis, sig, block } = &self.annotated_fn;
         self.output.extend(quote!(
             #(#attrs)*
+            #clause_attr
             #[kanitool::checked_with = #recursion_wrapper_name_str]
             #[kanitool::replaced_with = #replace_fn_name_str]
             #[kanitool::inner_check = #wrapper_fn_name_str]
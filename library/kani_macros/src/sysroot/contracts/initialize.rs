@@ -88,7 +88,15 @@ pub fn new(
             }
         };
 
-        Ok(Self { function_state, condition_type, annotated_fn, attr_copy, output, hash })
+        Ok(Self {
+            function_state,
+            condition_kind: is_requires,
+            condition_type,
+            annotated_fn,
+            attr_copy,
+            output,
+            hash,
+        })
     }
 }
 impl ContractConditionsData {
@@ -316,6 +316,8 @@ enum ContractFunctionState {
 /// functions that integrate the conditions from this contract attribute.
 struct ContractConditionsHandler<'a> {
     function_state: ContractFunctionState,
+    /// The type of contract attribute we're expanding.
+    condition_kind: ContractConditionsType,
     /// Information specific to the type of contract attribute we're expanding.
     condition_type: ContractConditionsData,
     /// Body of the function this attribute was found on.
@@ -337,6 +339,25 @@ enum ContractConditionsType {
     Modifies,
 }
 
+impl ContractConditionsType {
+    /// The name of the attribute, e.g., `requires`.
+    fn name(self) -> &'static str {
+        match self {
+            ContractConditionsType::Requires => "requires",
+            ContractConditionsType::Ensures => "ensures",
+            ContractConditionsType::Modifies => "modifies",
+        }
+    }
+}
+
+/// The `#[kanitool::contract_clause = "requires(...)"]` attribute that records a clause of the
+/// contract on the original function, as written in the source, so Kani can list the contract
+/// without generating its code.
+fn contract_clause_attr(kind: ContractConditionsType, attr: &TokenStream2) -> syn::Attribute {
+    let open = format!("{}(", kind.name());
+    syn::parse_quote!(#[kanitool::contract_clause = concat!(#open, stringify!(#attr), ")")])
+}
+
 /// Clause-specific information mostly generated by parsing the attribute.
 ///
 /// [`ContractConditionsType`] is the corresponding pre-parse version.
@@ -409,6 +430,10 @@ fn contract_main(
         //
         // Since this is the only function state case that doesn't need a
         // handler to be constructed, we do this match early, separately.
+        //
+        // The clause is appended to the attributes, so the clauses are recorded in the order
+        // they are written.
+        item_fn.attrs.push(contract_clause_attr(is_requires, &attr_copy));
         return item_fn.into_token_stream().into();
     }
 
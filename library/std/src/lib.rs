@@ -16,6 +16,14 @@
 // Override process calls with stubs.
 pub mod process;
 
+#[cfg(not(feature = "concrete_playback"))]
+// Override catch_unwind with a model that can recover from panics.
+pub mod panic;
+
+#[cfg(not(feature = "concrete_playback"))]
+// Override thread spawning with Kani's model of threads.
+pub mod thread;
+
 /// This assert macro calls kani's assert function passing it down the condition
 /// as well as a message that will be used when reporting the assertion result.
 ///
@@ -0,0 +1,13 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module overrides `catch_unwind` with Kani's model, which can recover from panics in
+//! harnesses annotated with `#[kani::catch_unwind]`.
+
+// Export everything else from std::panic.
+pub use std::panic::*;
+
+#[inline(always)]
+pub fn catch_unwind<F: FnOnce() -> R + UnwindSafe, R>(f: F) -> std::thread::Result<R> {
+    kani::internal::catch_unwind(f)
+}
@@ -0,0 +1,134 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module overrides `spawn` and `Builder` with Kani's model of threads, so threads spawned
+//! with the `std::thread` API run concurrently with the harness. `scope` isn't modeled yet.
+//!
+//! Each modeled thread has its own [`Thread`] handle, which doesn't keep the name given with
+//! [`Builder::name`].
+
+// Export everything else from std::thread.
+pub use std::thread::*;
+
+use std::cell::UnsafeCell;
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The handle of a modeled thread, which the thread stores when it starts.
+struct ThreadSlot {
+    thread: UnsafeCell<Option<Thread>>,
+    is_set: AtomicBool,
+}
+
+// SAFETY: The handle is only written by the thread before `is_set` is set, and only read after.
+unsafe impl Sync for ThreadSlot {}
+
+/// A handle to wait for the termination of a thread spawned with [`spawn`] or
+/// [`Builder::spawn`].
+pub struct JoinHandle<T> {
+    inner: kani::thread::JoinHandle<T>,
+    thread: Arc<ThreadSlot>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Wait for the thread to finish. A panic in the thread fails the harness, so the result is
+    /// never an error.
+    #[inline(always)]
+    pub fn join(self) -> Result<T> {
+        Ok(self.inner.join())
+    }
+
+    /// Get the handle of the thread.
+    ///
+    /// The thread creates its handle when it starts, so interleavings where it hasn't started
+    /// yet are discarded. Starting the thread isn't visible to the rest of the program, so this
+    /// doesn't discard any other behavior.
+    #[inline(always)]
+    pub fn thread(&self) -> &Thread {
+        kani::assume(self.thread.is_set.load(Ordering::SeqCst));
+        // SAFETY: The thread doesn't write its handle after setting `is_set`.
+        unsafe { (*self.thread.thread.get()).as_ref().unwrap() }
+    }
+
+    #[inline(always)]
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
+}
+
+impl<T> fmt::Debug for JoinHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinHandle").finish_non_exhaustive()
+    }
+}
+
+#[inline(always)]
+pub fn spawn<F, T>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let thread =
+        Arc::new(ThreadSlot { thread: UnsafeCell::new(None), is_set: AtomicBool::new(false) });
+    let slot = thread.clone();
+    let inner = kani::thread::spawn(move || {
+        // SAFETY: The handle isn't read before `is_set` is set.
+        unsafe { *slot.thread.get() = Some(current()) };
+        slot.is_set.store(true, Ordering::SeqCst);
+        f()
+    });
+    JoinHandle { inner, thread }
+}
+
+/// A thread factory whose threads are spawned with Kani's model of threads. The name and stack
+/// size are only used by [`Builder::spawn_scoped`], which isn't modeled.
+pub struct Builder(std::thread::Builder);
+
+impl Builder {
+    #[allow(clippy::new_without_default)]
+    #[inline(always)]
+    pub fn new() -> Builder {
+        Builder(std::thread::Builder::new())
+    }
+
+    #[inline(always)]
+    pub fn name(self, name: String) -> Builder {
+        Builder(self.0.name(name))
+    }
+
+    #[inline(always)]
+    pub fn stack_size(self, size: usize) -> Builder {
+        Builder(self.0.stack_size(size))
+    }
+
+    /// Spawn a thread with Kani's model of threads, which never fails.
+    #[inline(always)]
+    pub fn spawn<F, T>(self, f: F) -> io::Result<JoinHandle<T>>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        Ok(spawn(f))
+    }
+
+    #[inline(always)]
+    pub fn spawn_scoped<'scope, 'env, F, T>(
+        self,
+        scope: &'scope Scope<'scope, 'env>,
+        f: F,
+    ) -> io::Result<ScopedJoinHandle<'scope, T>>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        self.0.spawn_scoped(scope, f)
+    }
+}
+
+impl fmt::Debug for Builder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
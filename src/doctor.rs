@@ -0,0 +1,426 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This module implements `cargo kani doctor`, which checks the Kani installation and the tools
+//! it depends on, and explains how to fix the problems it finds.
+//!
+//! With `--fix`, the problems that `cargo kani setup` can solve are fixed by running the
+//! corresponding setup steps again, e.g., by downloading the release bundle if one of the
+//! bundled tools is missing or has an unexpected version.
+
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{bail, Result};
+
+use crate::os_hacks;
+use crate::setup::{self, get_rust_toolchain_version, get_rustc_version_from_build, kani_dir};
+
+/// The tools that the release bundle ships in its `bin` directory.
+const BUNDLED_TOOLS: [&str; 8] = [
+    "kani-driver",
+    "kani-compiler",
+    "cbmc",
+    "goto-cc",
+    "goto-instrument",
+    "goto-analyzer",
+    "symtab2gb",
+    "kissat",
+];
+
+/// How bad the result of a check is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Level {
+    Ok,
+    /// Some features of Kani won't work, e.g., `--visualize`.
+    Warning,
+    /// Kani won't work.
+    Error,
+}
+
+/// A setup step that fixes a problem.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Fix {
+    /// Run the whole setup, e.g., when Kani was never set up.
+    Setup,
+    /// Download and unpack the release bundle again.
+    Bundle,
+    /// Install the Rust toolchain of the release bundle again.
+    Toolchain,
+    /// Install the Python dependencies again.
+    PythonDeps,
+}
+
+/// The result of a check.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Check {
+    name: &'static str,
+    level: Level,
+    detail: String,
+    fix: Option<Fix>,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Check { name, level: Level::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn problem(
+        name: &'static str,
+        level: Level,
+        detail: impl Into<String>,
+        fix: Option<Fix>,
+    ) -> Self {
+        Check { name, level, detail: detail.into(), fix }
+    }
+
+    fn print(&self) {
+        let tag = match self.level {
+            Level::Ok => "[ok]     ",
+            Level::Warning => "[warning]",
+            Level::Error => "[error]  ",
+        };
+        println!("{tag} {}: {}", self.name, self.detail);
+        if let Some(fix) = self.fix {
+            println!("          Fix: {}", fix.describe());
+        }
+    }
+}
+
+impl Fix {
+    fn describe(&self) -> &'static str {
+        match self {
+            Fix::Setup => "run `cargo kani setup` (or `cargo kani doctor --fix`)",
+            Fix::Bundle => "reinstall the Kani release bundle with `cargo kani doctor --fix`",
+            Fix::Toolchain => "reinstall the Rust toolchain of Kani with `cargo kani doctor --fix`",
+            Fix::PythonDeps => {
+                "reinstall the Python dependencies of Kani with `cargo kani doctor --fix`"
+            }
+        }
+    }
+
+    fn apply(&self, kani_dir: &Path) -> Result<()> {
+        match self {
+            Fix::Setup => setup::setup(None, None),
+            Fix::Bundle => {
+                setup::setup_kani_bundle(kani_dir, None)?;
+                os_hacks::setup_os_hacks(kani_dir, &os_info::get())
+            }
+            Fix::Toolchain => setup::setup_rust_toolchain(kani_dir, None).map(|_| ()),
+            Fix::PythonDeps => setup::setup_python_deps(kani_dir),
+        }
+    }
+}
+
+/// Check the installation, and fix the problems that can be fixed if `fix` is set. Returns an
+/// error if Kani won't work.
+pub fn doctor(fix: bool) -> Result<()> {
+    let kani_dir = kani_dir()?;
+    let mut checks = run_checks(&kani_dir);
+    if fix {
+        let mut fixes: Vec<_> = checks.iter().filter_map(|check| check.fix).collect();
+        fixes.sort();
+        fixes.dedup();
+        if fixes.contains(&Fix::Setup) {
+            // The whole setup includes the other fixes.
+            fixes = vec![Fix::Setup];
+        }
+        for fix in &fixes {
+            println!("Applying fix: {}", fix.describe());
+            fix.apply(&kani_dir)?;
+        }
+        if !fixes.is_empty() {
+            println!();
+            checks = run_checks(&kani_dir);
+        }
+    }
+
+    for check in &checks {
+        check.print();
+    }
+    let errors = checks.iter().filter(|check| check.level == Level::Error).count();
+    let warnings = checks.iter().filter(|check| check.level == Level::Warning).count();
+    println!("\n{errors} errors, {warnings} warnings.");
+    if errors > 0 {
+        bail!("Kani isn't set up correctly. See the fixes above.");
+    }
+    Ok(())
+}
+
+fn run_checks(kani_dir: &Path) -> Vec<Check> {
+    let mut checks = vec![check_platform()];
+    let bundle = check_bundle(kani_dir);
+    let has_bundle = bundle.level == Level::Ok;
+    checks.push(bundle);
+    if has_bundle {
+        let versions = std::fs::read_to_string(kani_dir.join("kani-dependencies"))
+            .map(|content| parse_dependencies(&content))
+            .unwrap_or_default();
+        checks.push(check_tool_version(
+            kani_dir,
+            "CBMC",
+            "cbmc",
+            versions.cbmc.as_deref(),
+            cbmc_version,
+        ));
+        checks.push(check_tool_version(
+            kani_dir,
+            "Kissat",
+            "kissat",
+            versions.kissat.as_deref(),
+            |output| output.lines().next().map(|line| line.trim().to_string()),
+        ));
+        checks.push(check_toolchain(kani_dir));
+        checks.push(check_python_deps(kani_dir));
+    }
+    checks.push(check_rustup());
+    checks.push(check_linker());
+    checks
+}
+
+fn check_platform() -> Check {
+    const NAME: &str = "Platform";
+    let target = env!("TARGET");
+    if let Err(err) = setup::fail_if_unsupported_target() {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            format!("{err}. Kani must be built from source on this platform"),
+            None,
+        );
+    }
+    let os = os_info::get();
+    if os.os_type() == os_info::Type::NixOS && !Path::new("/lib64/ld-linux-x86-64.so.2").exists() {
+        return Check::ok(
+            NAME,
+            format!("{target} on NixOS, where the bundled binaries are patched with `patchelf`"),
+        );
+    }
+    Check::ok(NAME, format!("{target} ({os})"))
+}
+
+fn check_bundle(kani_dir: &Path) -> Check {
+    const NAME: &str = "Kani release bundle";
+    if !kani_dir.exists() {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            format!("Kani isn't set up: `{}` doesn't exist", kani_dir.display()),
+            Some(Fix::Setup),
+        );
+    }
+    if let Some(bundle) = setup::appears_incomplete() {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            format!("the setup was interrupted, `{}` wasn't unpacked", bundle.display()),
+            Some(Fix::Bundle),
+        );
+    }
+    let bin = kani_dir.join("bin");
+    let missing: Vec<_> =
+        BUNDLED_TOOLS.iter().filter(|tool| !bin.join(tool).is_file()).copied().collect();
+    if !missing.is_empty() {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            format!("missing `{}` in `{}`", missing.join("`, `"), bin.display()),
+            Some(Fix::Bundle),
+        );
+    }
+    Check::ok(NAME, format!("installed in `{}`", kani_dir.display()))
+}
+
+/// Check that a bundled tool runs and has the version listed in the bundle.
+fn check_tool_version(
+    kani_dir: &Path,
+    name: &'static str,
+    binary: &str,
+    expected: Option<&str>,
+    parse_version: impl Fn(&str) -> Option<String>,
+) -> Check {
+    let path = kani_dir.join("bin").join(binary);
+    let output = match Command::new(&path).arg("--version").output() {
+        Ok(output) if output.status.success() => output,
+        // A binary that can't be executed is usually caused by a missing dynamic library or
+        // dynamic linker.
+        _ => {
+            return Check::problem(
+                name,
+                Level::Error,
+                format!("`{} --version` failed", path.display()),
+                Some(Fix::Bundle),
+            );
+        }
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let Some(version) = parse_version(&stdout) else {
+        return Check::problem(
+            name,
+            Level::Warning,
+            format!("couldn't parse the version printed by `{}`", path.display()),
+            None,
+        );
+    };
+    match expected {
+        Some(expected) if expected != version => Check::problem(
+            name,
+            Level::Error,
+            format!("found version {version}, but this version of Kani requires {expected}"),
+            Some(Fix::Bundle),
+        ),
+        _ => Check::ok(name, format!("version {version}")),
+    }
+}
+
+fn check_toolchain(kani_dir: &Path) -> Check {
+    const NAME: &str = "Rust toolchain";
+    let Ok(toolchain) = get_rust_toolchain_version(kani_dir) else {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            "the release bundle doesn't record its Rust toolchain",
+            Some(Fix::Bundle),
+        );
+    };
+    let toolchain = toolchain.trim();
+    let rustc = kani_dir.join("toolchain").join("bin").join("rustc");
+    let version = Command::new(&rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+    let Some(version) = version else {
+        return Check::problem(
+            NAME,
+            Level::Error,
+            format!("the toolchain `{toolchain}` isn't installed, `{}` failed", rustc.display()),
+            Some(Fix::Toolchain),
+        );
+    };
+    match get_rustc_version_from_build(kani_dir) {
+        Ok(expected) if expected.trim() != version => Check::problem(
+            NAME,
+            Level::Error,
+            format!("found `{version}`, but Kani was built with `{}`", expected.trim()),
+            Some(Fix::Toolchain),
+        ),
+        _ => Check::ok(NAME, format!("{toolchain} ({version})")),
+    }
+}
+
+fn check_python_deps(kani_dir: &Path) -> Check {
+    const NAME: &str = "Python dependencies";
+    if !command_succeeds("python3", &["--version"]) {
+        return Check::problem(
+            NAME,
+            Level::Warning,
+            "`python3` isn't available, which `--visualize` requires",
+            None,
+        );
+    }
+    let viewer = kani_dir.join("pyroot").join("bin").join("cbmc-viewer");
+    if !viewer.is_file() {
+        return Check::problem(
+            NAME,
+            Level::Warning,
+            format!("`{}` isn't installed, which `--visualize` requires", viewer.display()),
+            Some(Fix::PythonDeps),
+        );
+    }
+    Check::ok(NAME, "cbmc-viewer is installed")
+}
+
+fn check_rustup() -> Check {
+    const NAME: &str = "rustup";
+    if command_succeeds("rustup", &["--version"]) {
+        Check::ok(NAME, "available")
+    } else {
+        Check::problem(
+            NAME,
+            Level::Error,
+            "`rustup` isn't available. Install it from https://rustup.rs",
+            None,
+        )
+    }
+}
+
+fn check_linker() -> Check {
+    const NAME: &str = "Linker";
+    if command_succeeds("cc", &["--version"]) {
+        Check::ok(NAME, "`cc` is available")
+    } else {
+        let hint = if cfg!(target_os = "macos") {
+            "install the Xcode command line tools with `xcode-select --install`"
+        } else {
+            "install a C toolchain, e.g., the `build-essential` package on Debian and Ubuntu"
+        };
+        Check::problem(
+            NAME,
+            Level::Error,
+            format!("`cc` isn't available, which Rust uses to link binaries: {hint}"),
+            None,
+        )
+    }
+}
+
+fn command_succeeds(program: &str, args: &[&str]) -> bool {
+    Command::new(program).args(args).output().is_ok_and(|output| output.status.success())
+}
+
+/// The versions of the tools listed in the `kani-dependencies` file of the release bundle.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Dependencies {
+    cbmc: Option<String>,
+    kissat: Option<String>,
+}
+
+/// Parse the `KEY="VALUE"` lines of a `kani-dependencies` file.
+fn parse_dependencies(content: &str) -> Dependencies {
+    let mut dependencies = Dependencies::default();
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let value = Some(value.trim().trim_matches('"').to_string());
+        match key.trim() {
+            "CBMC_VERSION" => dependencies.cbmc = value,
+            "KISSAT_VERSION" => dependencies.kissat = value,
+            _ => {}
+        }
+    }
+    dependencies
+}
+
+/// Parse the output of `cbmc --version`, e.g., `5.95.1 (cbmc-5.95.1)`.
+fn cbmc_version(output: &str) -> Option<String> {
+    output.split_whitespace().next().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_parse_dependencies() {
+        let content =
+            "CBMC_MAJOR=\"5\"\nCBMC_VERSION=\"5.95.1\"\n\n# A comment\nKISSAT_VERSION=\"3.1.1\"\n";
+        assert_eq!(
+            parse_dependencies(content),
+            Dependencies { cbmc: Some("5.95.1".to_string()), kissat: Some("3.1.1".to_string()) }
+        );
+        assert_eq!(parse_dependencies(""), Dependencies::default());
+    }
+
+    #[test]
+    fn check_cbmc_version() {
+        assert_eq!(cbmc_version("5.95.1 (cbmc-5.95.1)\n").as_deref(), Some("5.95.1"));
+        assert_eq!(cbmc_version(""), None);
+    }
+
+    #[test]
+    fn check_missing_bundle() {
+        let check = check_bundle(Path::new("/nonexistent/kani-0.0.0"));
+        assert_eq!(check.level, Level::Error);
+        assert_eq!(check.fix, Some(Fix::Setup));
+    }
+}
@@ -10,9 +10,11 @@
 //!
 //! Upon first run, or upon running `cargo-kani setup`, these proxy
 //! binaries will download the appropriate Kani release bundle and invoke
-//! the "real" `kani` and `cargo-kani` binaries.
+//! the "real" `kani` and `cargo-kani` binaries. `cargo-kani doctor` checks
+//! and repairs this installation.
 
 mod cmd;
+mod doctor;
 mod os_hacks;
 mod setup;
 
@@ -31,6 +33,7 @@ pub fn proxy(bin: &str) -> Result<()> {
         ArgsResult::ExplicitSetup { use_local_bundle, use_local_toolchain } => {
             setup::setup(use_local_bundle, use_local_toolchain)
         }
+        ArgsResult::Doctor { fix } => doctor::doctor(fix),
         ArgsResult::Default => {
             fail_if_in_dev_environment()?;
             if !setup::appears_setup() {
@@ -54,6 +57,7 @@ pub fn proxy(bin: &str) -> Result<()> {
 #[derive(PartialEq, Eq, Debug)]
 enum ArgsResult {
     ExplicitSetup { use_local_bundle: Option<OsString>, use_local_toolchain: Option<OsString> },
+    Doctor { fix: bool },
     Default,
 }
 
@@ -106,6 +110,12 @@ fn parse_args(args: Vec<OsString>) -> ArgsResult {
         &[_, Some("setup")] | &[_, Some("kani"), Some("setup")] => {
             ArgsResult::ExplicitSetup { use_local_bundle: None, use_local_toolchain: None }
         }
+        &[_, Some("doctor")] | &[_, Some("kani"), Some("doctor")] => {
+            ArgsResult::Doctor { fix: false }
+        }
+        &[_, Some("doctor"), Some("--fix")] | &[_, Some("kani"), Some("doctor"), Some("--fix")] => {
+            ArgsResult::Doctor { fix: true }
+        }
         _ => ArgsResult::Default,
     }
 }
@@ -262,6 +272,15 @@ fn trial(args: &[&str]) -> ArgsResult {
             assert_eq!(e, trial(&["cargo", "kani", "setup"]));
             assert_eq!(e, trial(&["cargo-kani", "setup"]));
         }
+        {
+            let e = ArgsResult::Doctor { fix: false };
+            assert_eq!(e, trial(&["cargo-kani", "kani", "doctor"]));
+            assert_eq!(e, trial(&["cargo-kani", "doctor"]));
+            let e = ArgsResult::Doctor { fix: true };
+            assert_eq!(e, trial(&["cargo-kani", "kani", "doctor", "--fix"]));
+            assert_eq!(e, trial(&["kani", "doctor", "--fix"]));
+            assert_eq!(ArgsResult::Default, trial(&["cargo-kani", "kani", "doctor", "--fox"]));
+        }
         {
             let e = ArgsResult::ExplicitSetup {
                 use_local_bundle: Some(OsString::from("FILE")),
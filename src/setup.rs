@@ -96,7 +96,7 @@ pub fn setup(
 }
 
 /// Download and unpack the Kani release bundle
-fn setup_kani_bundle(kani_dir: &Path, use_local_bundle: Option<OsString>) -> Result<()> {
+pub(crate) fn setup_kani_bundle(kani_dir: &Path, use_local_bundle: Option<OsString>) -> Result<()> {
     // e.g. `~/.kani/`
     let base_dir = kani_dir.parent().expect("No base directory?");
 
@@ -147,7 +147,10 @@ pub(crate) fn get_rustc_version_from_build(kani_dir: &Path) -> Result<String> {
 }
 
 /// Install the Rust toolchain version we require
-fn setup_rust_toolchain(kani_dir: &Path, use_local_toolchain: Option<OsString>) -> Result<String> {
+pub(crate) fn setup_rust_toolchain(
+    kani_dir: &Path,
+    use_local_toolchain: Option<OsString>,
+) -> Result<String> {
     // Currently this means we require the bundle to have been unpacked first!
     let toolchain_version = get_rust_toolchain_version(kani_dir)?;
     let rustc_version = get_rustc_version_from_build(kani_dir)?.trim().to_string();
@@ -184,7 +187,7 @@ fn setup_rust_toolchain(kani_dir: &Path, use_local_toolchain: Option<OsString>)
 }
 
 /// Install into the pyroot the python dependencies we need
-fn setup_python_deps(kani_dir: &Path) -> Result<()> {
+pub(crate) fn setup_python_deps(kani_dir: &Path) -> Result<()> {
     println!("[4/5] Installing Kani python dependencies...");
     let pyroot = kani_dir.join("pyroot");
 
@@ -239,7 +242,7 @@ fn download_url() -> String {
 
 /// Give users a better error message than "404" if we're on an unsupported platform.
 /// This is called just before we try to download the release bundle.
-fn fail_if_unsupported_target() -> Result<()> {
+pub(crate) fn fail_if_unsupported_target() -> Result<()> {
     // This is basically going to be reduced to a compile-time constant
     match TARGET {
         "x86_64-unknown-linux-gnu"
@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+fn magic_number() -> u32 {
+    1
+}
+
+fn mock_magic_number() -> u32 {
+    42
+}
+
+/// Only succeeds with the stub of `kani.toml`.
+#[kani::proof]
+fn check_stubbed() {
+    assert_eq!(magic_number(), 42);
+}
+
+/// Only succeeds with the unwinding bound of `kani.toml`.
+#[kani::proof]
+fn check_unwind() {
+    let mut count = 0;
+    for _ in 0..3 {
+        count += 1;
+    }
+    assert_eq!(count, 3);
+}
+
+/// Excluded by `kani.toml`.
+#[kani::proof]
+fn check_excluded() {
+    assert!(false);
+}
@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::proof]
+fn check_unsatisfiable_cover() {
+    let x: u8 = kani::any();
+    kani::cover!(x.checked_add(1) == Some(0));
+}
+
+#[kani::proof]
+fn check_satisfiable_cover() {
+    let x: u8 = kani::any();
+    kani::cover!(x == 0);
+}
@@ -0,0 +1,13 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This harness needs the unwinding bound of the package, not the one of the workspace.
+
+#[kani::proof]
+fn check_sum() {
+    let mut sum = 0;
+    for i in 0..3 {
+        sum += i;
+    }
+    assert_eq!(sum, 3);
+}
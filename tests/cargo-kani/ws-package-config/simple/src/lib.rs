@@ -0,0 +1,11 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This harness uses the configuration of the workspace.
+
+#[kani::proof]
+fn check_add() {
+    let x: u8 = kani::any();
+    kani::assume(x < 10);
+    assert!(x + 1 <= 10);
+}
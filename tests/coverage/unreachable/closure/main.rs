@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that the coverage of closure bodies is reported.
+
+#[kani::proof]
+fn main() {
+    let x: u8 = kani::any();
+    let clamp = |y: u8| {
+        if y > 10 {
+            10
+        } else {
+            y
+        }
+    };
+    assert!(clamp(x) <= 10);
+    let unused = |y: u8| y + 1;
+    if x > 10 && x < 5 {
+        unused(x);
+    }
+}
@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that items marked with `#[kani::coverage(off)]` are excluded from the coverage results.
+
+#[kani::coverage(off)]
+fn excluded(x: u32) -> u32 {
+    if x > 100 { 0 } else { x + 1 }
+}
+
+fn included(x: u32) -> u32 {
+    x * 2
+}
+
+#[kani::proof]
+fn main() {
+    let a = excluded(5);
+    let b = included(a);
+    assert!(b == 12);
+}
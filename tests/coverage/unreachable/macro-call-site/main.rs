@@ -0,0 +1,17 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that the coverage of code generated by a macro is reported at the macro call site.
+
+macro_rules! is_positive {
+    ($x:expr) => {
+        if $x > 0 { true } else { false }
+    };
+}
+
+#[kani::proof]
+fn main() {
+    let x: u8 = kani::any();
+    kani::assume(x > 0);
+    assert!(is_positive!(x));
+}
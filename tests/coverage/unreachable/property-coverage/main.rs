@@ -0,0 +1,15 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that Kani combines the coverage of each line with the results of its properties.
+
+#[kani::proof]
+fn main() {
+    let x: u8 = kani::any();
+    if x > 10 {
+        assert!(x > 5);
+    }
+    if x > 20 && x < 10 {
+        assert!(x == 15);
+    }
+}
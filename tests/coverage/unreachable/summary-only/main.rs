@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --summary-only
+
+//! Check that `--summary-only` prints the coverage of each file with its uncovered lines.
+
+#[kani::proof]
+fn main() {
+    let x: u8 = kani::any();
+    if x > 5 && x < 2 {
+        let y = x + 1;
+        assert!(y > 3);
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z abstract-panic-fmt
+
+//! Check that the code on the path to an abstracted panic is kept, so an assumption before the
+//! panic still makes it unreachable.
+
+#[derive(Debug)]
+struct Error {
+    code: u32,
+}
+
+#[kani::proof]
+fn check_assume_before_panic() {
+    let code: u32 = kani::any();
+    if code > 10 {
+        let err = Error { code };
+        kani::assume(err.code < 10);
+        panic!("unexpected error {:?}", err);
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z abstract-panic-fmt
+
+//! Check that the panics of the standard library that format their message are replaced by a
+//! panic with a placeholder message, while the panics with a constant message are kept.
+
+#[derive(Debug)]
+struct Error {
+    code: u32,
+}
+
+fn parse(byte: u8) -> Result<u8, Error> {
+    if byte < 10 { Ok(byte) } else { Err(Error { code: byte.into() }) }
+}
+
+#[kani::proof]
+fn check_formatted_panic() {
+    let byte = kani::any();
+    assert_eq!(parse(byte).unwrap(), byte);
+}
+
+#[kani::proof]
+fn check_constant_panic() {
+    let byte: u8 = kani::any();
+    let digit = (byte < 10).then_some(byte);
+    assert!(digit.unwrap() < 10);
+}
@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z alignment-checks
+//
+//! Check that a misaligned pointer is reported when it is itself read through another pointer,
+//! and when it's read to compute the address of a place.
+
+#[repr(C, packed)]
+struct Header {
+    tag: u8,
+    len: u32,
+}
+
+struct Cursor {
+    len: *const u32,
+}
+
+#[kani::proof]
+fn check_nested_read() {
+    let header = Header { tag: 0, len: 10 };
+    let cursor = Cursor { len: std::ptr::addr_of!(header.len) };
+    let cursor_ptr = &cursor as *const Cursor;
+    let _len = unsafe { *(*cursor_ptr).len };
+}
+
+#[kani::proof]
+fn check_nested_address() {
+    let headers = [0u64; 2];
+    let misaligned = unsafe { (headers.as_ptr() as *const u8).add(1) } as *const *const Header;
+    let _len = unsafe { std::ptr::addr_of!((**misaligned).len) };
+}
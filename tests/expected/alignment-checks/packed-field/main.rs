@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z alignment-checks
+//
+//! Check that dereferencing a pointer to a field of a packed struct is reported, since the field
+//! may not be aligned.
+
+#[repr(C, packed)]
+struct Header {
+    tag: u8,
+    len: u32,
+}
+
+#[kani::proof]
+fn check_misaligned_read() {
+    let header = Header { tag: 0, len: 10 };
+    let ptr = std::ptr::addr_of!(header.len);
+    let _len = unsafe { *ptr };
+}
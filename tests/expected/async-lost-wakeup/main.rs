@@ -0,0 +1,42 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// compile-flags: --edition 2021
+
+//! Check that `kani::block_on` reports a future that is pending without waking its waker, since
+//! such a future would never complete with a real executor.
+
+use std::future::poll_fn;
+use std::task::Poll;
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn lost_wakeup() {
+    let mut ready = false;
+    let n = kani::block_on(poll_fn(|_| {
+        if ready {
+            Poll::Ready(1)
+        } else {
+            // Bug: the waker isn't woken.
+            ready = true;
+            Poll::Pending
+        }
+    }));
+    assert_eq!(n, 1);
+}
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn woken() {
+    let mut ready = false;
+    let n = kani::block_on(poll_fn(|cx| {
+        if ready {
+            Poll::Ready(1)
+        } else {
+            ready = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }));
+    assert_eq!(n, 1);
+}
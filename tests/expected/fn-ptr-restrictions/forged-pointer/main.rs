@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z restrict-fn-ptrs
+
+//! Check that a call through a function pointer that was transmuted from a pointer of another
+//! type fails, instead of calling one of the functions of the pointer type.
+
+fn narrow(x: u8) -> u8 {
+    x
+}
+
+fn wide(x: u16) -> u16 {
+    x
+}
+
+#[kani::proof]
+fn check_forged_pointer() {
+    let f: fn(u8) -> u8 = narrow;
+    let forged: fn(u16) -> u16 = unsafe { std::mem::transmute(f) };
+    let g: fn(u16) -> u16 = wide;
+    assert_eq!(g(1), 1);
+    let _ = forged(1);
+}
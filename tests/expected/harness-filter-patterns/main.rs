@@ -0,0 +1,29 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --harness parser::* --harness regex:^lexer::check_(a|b)$ --exclude-harness *_slow
+
+//! Check that harness filters can be glob patterns or regular expressions.
+
+mod parser {
+    #[kani::proof]
+    fn check_parse() {
+        assert!(kani::any::<u8>() <= u8::MAX);
+    }
+
+    #[kani::proof]
+    fn check_parse_slow() {
+        assert!(kani::any::<u8>() <= u8::MAX);
+    }
+}
+
+mod lexer {
+    #[kani::proof]
+    fn check_a() {
+        assert!(kani::any::<u8>() <= u8::MAX);
+    }
+
+    #[kani::proof]
+    fn check_c() {
+        assert!(kani::any::<u8>() <= u8::MAX);
+    }
+}
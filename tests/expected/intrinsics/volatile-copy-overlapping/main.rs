@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `volatile_copy_nonoverlapping_memory` fails if the `src`/`dst`
+// regions overlap, with the same check as `copy_nonoverlapping`.
+#![feature(core_intrinsics)]
+
+#[kani::proof]
+fn test_volatile_copy_nonoverlapping_with_overlap() {
+    let mut arr: [i32; 3] = [0, 1, 0];
+    let ptr = arr.as_mut_ptr();
+    // The regions overlap in `arr[1]`.
+    unsafe { std::intrinsics::volatile_copy_nonoverlapping_memory(ptr.add(1), ptr, 2) };
+}
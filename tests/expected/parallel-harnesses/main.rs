@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -j 2
+
+//! Check that harnesses can be verified in parallel with the default output format, and that the
+//! summary lists the verification time of each harness.
+
+#[kani::proof]
+fn check_add() {
+    let x: u8 = kani::any();
+    assert_eq!(x.wrapping_add(1).wrapping_sub(1), x);
+}
+
+#[kani::proof]
+fn check_fail() {
+    let x: u8 = kani::any();
+    assert!(x < 100, "x is small");
+}
+
+#[kani::proof]
+fn check_mul() {
+    let x: u8 = kani::any_where(|x| *x < 16);
+    assert!(x * x <= 225);
+}
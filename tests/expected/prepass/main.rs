@@ -0,0 +1,34 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that the branches that are statically dead after monomorphization are pruned without
+//! changing the result of the verification.
+
+use std::mem::size_of;
+
+fn first<T: Copy + Default>(items: &[T]) -> T {
+    if size_of::<T>() == 0 {
+        panic!("zero-sized items aren't supported: {}", items.len());
+    }
+    if items.is_empty() { T::default() } else { items[0] }
+}
+
+#[kani::proof]
+fn check_sized() {
+    let items: [u32; 2] = kani::any();
+    assert_eq!(first(&items), items[0]);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_zero_sized() {
+    let items = [(); 3];
+    first(&items);
+}
+
+#[kani::proof]
+fn check_empty() {
+    let items: [u8; 0] = [];
+    let len: usize = kani::any();
+    assert_eq!(first(&items[..len.min(0)]), 0);
+}
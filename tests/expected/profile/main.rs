@@ -0,0 +1,17 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --profile=solver
+
+//! Check that `--profile` prints the resources used by each harness.
+
+#[kani::proof]
+fn check_add() {
+    let x: u8 = kani::any();
+    assert!(x.wrapping_add(1) != x);
+}
+
+#[kani::proof]
+fn check_mul() {
+    let x: u8 = kani::any_where(|x| *x < 16);
+    assert!(x * 16 >= x);
+}
@@ -0,0 +1,15 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --severity unsatisfiable-cover=warning --severity unreachable-cover=warning
+
+//! Check that `--severity` reports the cover properties that can't be satisfied as warnings,
+//! without failing the run.
+
+#[kani::proof]
+fn check_covers() {
+    let x: u8 = kani::any();
+    kani::cover!(x.checked_add(1) == Some(0));
+    if x > 10 && x < 5 {
+        kani::cover!(true);
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// kani-flags: --output-format terse
+//! Check that the trace of a failed check prints the structs and enums with the names of their
+//! fields and variants, and collapses the assignments in the standard library.
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: u8,
+    y: i16,
+}
+
+enum Shape {
+    Empty,
+    Dot(Point),
+}
+
+#[kani::proof]
+fn check_shape() {
+    let x: u8 = kani::any();
+    kani::assume(x < 10);
+    let point = Point { x, y: -2 };
+    let shape = if x > 5 { Shape::Dot(point) } else { Shape::Empty };
+    let largest = core::cmp::max(x, 7);
+    assert!(!matches!(shape, Shape::Dot(Point { x: 9, .. })), "the dot is at 9");
+    assert!(largest < 10);
+}
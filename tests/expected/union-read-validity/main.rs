@@ -0,0 +1,44 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z valid-value-checks
+
+//! Check that reading a union field whose bytes aren't a valid value of the field type is
+//! reported with the `invalid_union_read` property class.
+
+use std::mem::MaybeUninit;
+use std::num::NonZeroI64;
+
+#[repr(C)]
+union ByteOrBool {
+    byte: u8,
+    flag: bool,
+}
+
+#[kani::proof]
+fn read_invalid_bool() {
+    let value = ByteOrBool { byte: kani::any() };
+    let _flag = unsafe { value.flag };
+}
+
+#[kani::proof]
+fn read_valid_bool() {
+    let byte: u8 = kani::any();
+    kani::assume(byte <= 1);
+    let value = ByteOrBool { byte };
+    let flag = unsafe { value.flag };
+    assert_eq!(flag, byte == 1);
+}
+
+#[kani::proof]
+fn read_written_field() {
+    let flag: bool = kani::any();
+    let value = ByteOrBool { flag };
+    assert_eq!(unsafe { value.flag }, flag);
+}
+
+/// `MaybeUninit` is a union, so `assume_init` reads one of its fields.
+#[kani::proof]
+fn assume_init_invalid_value() {
+    let maybe: MaybeUninit<NonZeroI64> = unsafe { std::mem::transmute(MaybeUninit::new(0i64)) };
+    let _val = unsafe { maybe.assume_init() };
+}
@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that calls to C variadic functions are linked to the models in the CBMC C library, even
+//! if C-FFI support isn't enabled. The model of `printf` may succeed or fail, and its result is
+//! returned to the caller.
+
+use std::ffi::{c_char, c_int};
+
+extern "C" {
+    fn printf(format: *const c_char, ...) -> c_int;
+}
+
+fn log_value(value: u32) -> c_int {
+    unsafe { printf(b"value: %u, half: %f\n\0".as_ptr() as *const c_char, value, value as f64 / 2.0) }
+}
+
+#[kani::proof]
+fn check_printf() {
+    let value: u32 = kani::any();
+    let written = log_value(value);
+    kani::cover!(written >= 0, "printf may succeed");
+    kani::cover!(written < 0, "printf may fail");
+}
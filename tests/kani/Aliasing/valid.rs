@@ -0,0 +1,49 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z aliasing-checks
+
+//! Check that aliasing checks don't report accesses that follow the aliasing model.
+
+#[kani::proof]
+fn check_access_through_derived_pointer() {
+    let mut value: u32 = kani::any();
+    let ptr = &mut value as *mut u32;
+    let reference = unsafe { &mut *ptr };
+    let derived = reference as *mut u32;
+    unsafe { *derived = 1 };
+    *reference += 1;
+    assert_eq!(value, 2);
+}
+
+#[kani::proof]
+fn check_reserved_after_foreign_read() {
+    let mut value: u32 = kani::any();
+    let ptr = &mut value as *mut u32;
+    let reference = unsafe { &mut *ptr };
+    // A mutable reference that wasn't written to can still be written after a foreign read.
+    let read = unsafe { *ptr };
+    *reference = read.wrapping_add(1);
+}
+
+#[kani::proof]
+fn check_disjoint_accesses() {
+    let mut pair: (u32, u32) = kani::any();
+    let ptr = &mut pair as *mut (u32, u32);
+    let first = unsafe { &mut (*ptr).0 };
+    unsafe { (*ptr).1 = 10 };
+    *first = 5;
+    assert_eq!(pair, (5, 10));
+}
+
+#[kani::proof]
+fn check_reborrow_after_write() {
+    let mut value: u32 = kani::any();
+    let ptr = &mut value as *mut u32;
+    for i in 0..2 {
+        unsafe { *ptr = i };
+        // Creating a new reference restores the permission.
+        let reference = unsafe { &mut *ptr };
+        *reference += 1;
+    }
+    assert_eq!(value, 2);
+}
@@ -0,0 +1,109 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that collections can be parameterized by a custom allocator that implements the
+//! `Allocator` trait, including allocators that manage their own memory.
+#![feature(allocator_api)]
+
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::{Cell, UnsafeCell};
+use std::collections::VecDeque;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+/// An allocator that counts the blocks that are currently allocated.
+struct Counting<'a> {
+    live: &'a Cell<usize>,
+}
+
+unsafe impl Allocator for Counting<'_> {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
+
+/// A bump allocator over a fixed buffer that never frees memory.
+struct Bump {
+    buffer: UnsafeCell<[u64; 8]>,
+    next: Cell<usize>,
+}
+
+impl Bump {
+    fn new() -> Self {
+        Bump { buffer: UnsafeCell::new([0; 8]), next: Cell::new(0) }
+    }
+}
+
+unsafe impl Allocator for Bump {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        let start = self.next.get().next_multiple_of(layout.align());
+        let end = start.checked_add(layout.size()).ok_or(AllocError)?;
+        if end > std::mem::size_of::<[u64; 8]>() {
+            return Err(AllocError);
+        }
+        self.next.set(end);
+        let ptr = unsafe { NonNull::new_unchecked(self.buffer.get().cast::<u8>().add(start)) };
+        Ok(NonNull::slice_from_raw_parts(ptr, layout.size()))
+    }
+
+    unsafe fn deallocate(&self, _ptr: NonNull<u8>, _layout: Layout) {}
+}
+
+trait Answer {
+    fn answer(&self) -> u32;
+}
+
+impl Answer for u32 {
+    fn answer(&self) -> u32 {
+        *self
+    }
+}
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn check_counting_vec() {
+    let live = Cell::new(0);
+    {
+        let mut vec = Vec::new_in(Counting { live: &live });
+        vec.push(kani::any::<u8>());
+        assert_eq!(vec.len(), 1);
+        assert_eq!(live.get(), 1);
+    }
+    assert_eq!(live.get(), 0);
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+fn check_bump_collections() {
+    let bump = Bump::new();
+    let mut vec = Vec::with_capacity_in(2, &bump);
+    vec.push(1u32);
+    vec.push(2);
+    vec.push(kani::any());
+    assert_eq!(vec[..2], [1, 2]);
+
+    let mut deque = VecDeque::new_in(&bump);
+    let value: u8 = kani::any();
+    deque.push_front(value);
+    assert_eq!(deque.pop_back(), Some(value));
+
+    let mut too_big: Vec<u8, &Bump> = Vec::new_in(&bump);
+    assert!(too_big.try_reserve(100).is_err());
+}
+
+#[kani::proof]
+fn check_box_in() {
+    let bump = Bump::new();
+    let value: u32 = kani::any();
+    let boxed: Box<dyn Answer, &Bump> = Box::new_in(value, &bump);
+    assert_eq!(boxed.answer(), value);
+
+    let rc = Rc::new_in(value, Global);
+    assert_eq!(*rc.clone(), value);
+}
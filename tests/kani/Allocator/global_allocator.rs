@@ -0,0 +1,42 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that heap allocations go through the `#[global_allocator]` of the crate.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the blocks that are currently allocated.
+struct Counting;
+
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE.fetch_sub(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn check_global_allocator() {
+    let live = || LIVE.load(Ordering::Relaxed);
+    let initial = live();
+    let boxed = Box::new(kani::any::<u8>());
+    assert_eq!(live(), initial + 1);
+    let mut vec = vec![*boxed];
+    vec.push(1);
+    assert_eq!(live(), initial + 2);
+    drop(boxed);
+    drop(vec);
+    assert_eq!(live(), initial);
+}
@@ -0,0 +1,72 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// Check that the `checked_*`, `overflowing_*`, `saturating_*`, `carrying_*` and `widening_mul`
+// integer methods agree with each other and with the mathematical result computed in a wider
+// type.
+
+#![feature(bigint_helper_methods)]
+
+macro_rules! check_overflow_family {
+    ($name:ident, $ty:ty, $wide:ty) => {
+        #[kani::proof]
+        fn $name() {
+            let a: $ty = kani::any();
+            let b: $ty = kani::any();
+            let min = <$ty>::MIN as $wide;
+            let max = <$ty>::MAX as $wide;
+
+            let sum = a as $wide + b as $wide;
+            let in_range = min <= sum && sum <= max;
+            assert_eq!(a.checked_add(b), in_range.then(|| sum as $ty));
+            assert_eq!(a.overflowing_add(b), (sum as $ty, !in_range));
+            assert_eq!(a.saturating_add(b), sum.max(min).min(max) as $ty);
+
+            let diff = a as $wide - b as $wide;
+            let in_range = min <= diff && diff <= max;
+            assert_eq!(a.checked_sub(b), in_range.then(|| diff as $ty));
+            assert_eq!(a.overflowing_sub(b), (diff as $ty, !in_range));
+            assert_eq!(a.saturating_sub(b), diff.max(min).min(max) as $ty);
+
+            let product = a as $wide * b as $wide;
+            let in_range = min <= product && product <= max;
+            assert_eq!(a.checked_mul(b), in_range.then(|| product as $ty));
+            assert_eq!(a.overflowing_mul(b), (product as $ty, !in_range));
+            assert_eq!(a.saturating_mul(b), product.max(min).min(max) as $ty);
+        }
+    };
+}
+
+check_overflow_family!(check_i8, i8, i32);
+check_overflow_family!(check_u8, u8, i32);
+check_overflow_family!(check_i16, i16, i64);
+check_overflow_family!(check_u32, u32, i128);
+
+#[kani::proof]
+fn check_carrying_add() {
+    let a: u32 = kani::any();
+    let b: u32 = kani::any();
+    let carry: bool = kani::any();
+    let sum = a as u64 + b as u64 + carry as u64;
+    assert_eq!(a.carrying_add(b, carry), (sum as u32, sum > u32::MAX as u64));
+}
+
+#[kani::proof]
+fn check_borrowing_sub() {
+    let a: u32 = kani::any();
+    let b: u32 = kani::any();
+    let borrow: bool = kani::any();
+    let diff = a as i64 - b as i64 - borrow as i64;
+    assert_eq!(a.borrowing_sub(b, borrow), (diff as u32, diff < 0));
+}
+
+#[kani::proof]
+fn check_widening_mul() {
+    let a: u16 = kani::any();
+    let b: u16 = kani::any();
+    let product = a as u32 * b as u32;
+    assert_eq!(a.widening_mul(b), (product as u16, (product >> 16) as u16));
+    let carry: u16 = kani::any();
+    let product = product + carry as u32;
+    assert_eq!(a.carrying_mul(b, carry), (product as u16, (product >> 16) as u16));
+}
@@ -0,0 +1,65 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// Check the semantics of the 128-bit integer operations that are common in hashing and
+// cryptographic code, which are encoded with the native 128-bit operators of CBMC.
+// The operands are bounded, but they still span both 64-bit halves. The fully symbolic
+// versions of these checks are in `tests/perf/misc/wide_int`.
+
+/// A value whose symbolic bits are in both halves of a 128-bit integer.
+fn any_wide() -> u128 {
+    let low: u8 = kani::any();
+    let high: u8 = kani::any();
+    ((high as u128) << 120) | ((low as u128) << 60) | 0xFF
+}
+
+#[kani::proof]
+fn check_u128_widening_mul() {
+    let a: u64 = kani::any();
+    let b: u64 = kani::any();
+    let wide = (a as u128) * (b as u128);
+    assert_eq!((wide as u64), a.wrapping_mul(b));
+    assert_eq!((wide >> 64) == 0, a.checked_mul(b).is_some());
+}
+
+#[kani::proof]
+fn check_u128_checked_mul() {
+    let a = any_wide();
+    let b: u128 = kani::any::<u16>() as u128;
+    let (wrapped, overflow) = a.overflowing_mul(b);
+    assert_eq!(wrapped, a.wrapping_mul(b));
+    match a.checked_mul(b) {
+        Some(product) => {
+            assert!(!overflow);
+            assert!(product / a == b);
+        }
+        None => {
+            assert!(overflow);
+            assert_eq!(a.saturating_mul(b), u128::MAX);
+        }
+    }
+}
+
+#[kani::proof]
+fn check_u128_div_rem() {
+    let a = any_wide();
+    let b: u128 = kani::any::<u8>() as u128;
+    kani::assume(b != 0);
+    let q = a / b;
+    let r = a % b;
+    assert!(r < b);
+    assert_eq!(q * b + r, a);
+    assert_eq!(a.checked_div(b), Some(q));
+}
+
+#[kani::proof]
+fn check_i128_checked_ops() {
+    let a: i128 = if kani::any() { i128::MIN } else { any_wide() as i128 };
+    let b: i128 = kani::any::<i8>() as i128;
+    assert_eq!(a.checked_div(b).is_none(), b == 0 || (a == i128::MIN && b == -1));
+    assert_eq!(a.checked_rem(b).is_none(), b == 0 || (a == i128::MIN && b == -1));
+    assert_eq!(a.checked_neg().is_none(), a == i128::MIN);
+    if let Some(sum) = a.checked_add(b) {
+        assert_eq!(sum.wrapping_sub(b), a);
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z inline-asm
+
+//! Check that `-Z inline-asm` models inline assembly by assigning nondeterministic values to its
+//! outputs and to the memory it may write, instead of failing verification.
+
+use std::arch::asm;
+
+/// A function whose slow path uses assembly.
+fn checked_div(x: u32, y: u32) -> u32 {
+    if y == 0 {
+        // Trap on a division by zero.
+        unsafe { asm!("ud2", options(noreturn)) }
+    }
+    x / y
+}
+
+#[kani::proof]
+fn asm_in_cold_path() {
+    let x: u32 = kani::any();
+    let y: u32 = kani::any();
+    kani::assume(y != 0);
+    assert!(checked_div(x, y) <= x);
+}
+
+#[kani::proof]
+fn noreturn_asm() {
+    let y: u32 = kani::any();
+    let _ = checked_div(10, y);
+    assert!(y != 0);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn output_is_nondet() {
+    let value: u64;
+    unsafe { asm!("mov {}, 0", out(reg) value, options(pure, nomem, nostack)) };
+    assert_eq!(value, 0);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn memory_may_be_written() {
+    let mut value = 5u32;
+    let ptr = &mut value as *mut u32;
+    unsafe { asm!("/* {} */", in(reg) ptr, options(nostack)) };
+    assert_eq!(value, 5);
+}
+
+#[kani::proof]
+fn nomem_asm_preserves_memory() {
+    let mut value = 5u32;
+    let ptr = &mut value as *mut u32;
+    unsafe { asm!("/* {} */", in(reg) ptr, options(nomem, nostack)) };
+    assert_eq!(value, 5);
+}
@@ -0,0 +1,92 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// compile-flags: --edition 2021
+
+//! Check that `kani::block_on` polls common kinds of futures to completion, without enabling
+//! the unstable async library.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+trait Service {
+    async fn call(&self, x: u32) -> u32;
+}
+
+struct Doubler;
+
+impl Service for Doubler {
+    async fn call(&self, x: u32) -> u32 {
+        kani::yield_now().await;
+        x * 2
+    }
+}
+
+fn fib(n: u32) -> Pin<Box<dyn Future<Output = u32>>> {
+    Box::pin(async move { if n < 2 { n } else { fib(n - 1).await + fib(n - 2).await } })
+}
+
+/// A future that is pending until it was polled a given number of times.
+struct Countdown(u8);
+
+impl Future for Countdown {
+    type Output = u8;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if self.0 == 0 {
+            Poll::Ready(42)
+        } else {
+            self.0 -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+async fn push_twice(v: &mut Vec<u32>, x: u32) -> usize {
+    v.push(x);
+    kani::yield_now().await;
+    v.push(x);
+    v.len()
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+fn check_async_trait_fn() {
+    let x: u16 = kani::any();
+    assert_eq!(kani::block_on(Doubler.call(x.into())), u32::from(x) * 2);
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+fn check_recursive_future() {
+    assert_eq!(kani::block_on(fib(3)), 2);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn check_manual_future() {
+    let polls: u8 = kani::any_where(|polls| *polls < 4);
+    assert_eq!(kani::block_on(Countdown(polls)), 42);
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+async fn check_borrowing_future() {
+    let mut v = vec![];
+    assert_eq!(push_twice(&mut v, 1).await, 2);
+    assert_eq!(v, [1, 1]);
+}
+
+#[kani::proof]
+#[kani::unwind(4)]
+async fn check_dropped_future() {
+    let fut = async {
+        let s = String::from("never polled");
+        kani::yield_now().await;
+        s.len()
+    };
+    drop(fut);
+    assert_eq!(std::future::ready(5).await, 5);
+}
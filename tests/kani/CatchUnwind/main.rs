@@ -0,0 +1,78 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z catch-unwind
+
+//! Check that panics inside `catch_unwind` are recovered in harnesses annotated with
+//! `#[kani::catch_unwind]`.
+
+use std::panic::{self, AssertUnwindSafe};
+
+fn checked_increment(x: u8) -> u8 {
+    if x > 10 {
+        panic!("value is too big");
+    }
+    x + 1
+}
+
+fn double_increment(x: u8) -> u8 {
+    let y = checked_increment(x);
+    // Not reached if the increment panics.
+    y * 2
+}
+
+#[kani::proof]
+#[kani::catch_unwind]
+fn check_nested_panic() {
+    let x: u8 = kani::any();
+    let result = panic::catch_unwind(|| double_increment(x));
+    if x > 10 {
+        assert!(result.is_err());
+    } else {
+        assert_eq!(result.unwrap(), (x + 1) * 2);
+    }
+}
+
+#[kani::proof]
+#[kani::catch_unwind]
+fn check_side_effects_are_kept() {
+    let mut counter = 0;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        counter += 1;
+        panic!("stop");
+    }));
+    assert!(result.is_err());
+    assert_eq!(counter, 1);
+}
+
+#[kani::proof]
+#[kani::catch_unwind]
+fn check_nested_catch_unwind() {
+    let result = panic::catch_unwind(|| {
+        let inner = panic::catch_unwind(|| checked_increment(20));
+        assert!(inner.is_err());
+        checked_increment(1)
+    });
+    assert_eq!(result.unwrap(), 2);
+}
+
+#[kani::proof]
+#[kani::catch_unwind]
+fn check_failed_assert() {
+    let x: u8 = kani::any();
+    let result = panic::catch_unwind(|| {
+        assert!(x < 10);
+        x + 1
+    });
+    assert_eq!(result.is_err(), x >= 10);
+}
+
+#[kani::proof]
+#[kani::catch_unwind]
+fn check_failed_kani_assert() {
+    let x: u8 = kani::any();
+    let result = panic::catch_unwind(|| {
+        kani::assert(x != 0, "x is not zero");
+        100 / x
+    });
+    assert_eq!(result.is_ok(), x != 0);
+}
@@ -0,0 +1,31 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that threads spawned with `kani::thread::spawn` can be joined, and that atomic
+//! read-modify-write operations aren't interleaved.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+#[kani::proof]
+fn check_join_result() {
+    let input: u8 = kani::any();
+    let handle = kani::thread::spawn(move || input as u32 + 1);
+    assert_eq!(handle.join(), input as u32 + 1);
+}
+
+#[kani::proof]
+fn check_atomic_counter() {
+    let counter = Arc::new(AtomicU32::new(0));
+    let handles: [_; 2] = std::array::from_fn(|_| {
+        let counter = counter.clone();
+        kani::thread::spawn(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+    });
+    counter.fetch_add(1, Ordering::SeqCst);
+    for handle in handles {
+        handle.join();
+    }
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
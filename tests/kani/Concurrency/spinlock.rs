@@ -0,0 +1,48 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check mutual exclusion of a spinlock implemented with an atomic flag.
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct SpinLock {
+    locked: AtomicBool,
+}
+
+impl SpinLock {
+    fn try_lock(&self) -> bool {
+        self.locked.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok()
+    }
+
+    fn unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    lock: SpinLock,
+    /// The number of threads in the critical section.
+    inside: AtomicU8,
+}
+
+fn critical_section(shared: &Shared) {
+    if shared.lock.try_lock() {
+        let others = shared.inside.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(others, 0, "mutual exclusion");
+        shared.inside.fetch_sub(1, Ordering::SeqCst);
+        shared.lock.unlock();
+    }
+}
+
+#[kani::proof]
+fn check_mutual_exclusion() {
+    let shared = Arc::new(Shared::default());
+    let other = shared.clone();
+    let handle = kani::thread::spawn(move || critical_section(&other));
+    critical_section(&shared);
+    handle.join();
+    assert!(!shared.lock.locked.load(Ordering::SeqCst));
+}
@@ -0,0 +1,46 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that threads spawned with `std::thread::spawn` are modeled like the ones spawned with
+//! `kani::thread::spawn`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+#[kani::proof]
+fn check_join_result() {
+    let input: u8 = kani::any();
+    let handle = thread::spawn(move || input as u32 + 1);
+    assert_eq!(handle.join().unwrap(), input as u32 + 1);
+}
+
+#[kani::proof]
+fn check_atomic_counter() {
+    let counter = Arc::new(AtomicU32::new(0));
+    let handles: [_; 2] = std::array::from_fn(|_| {
+        let counter = counter.clone();
+        thread::spawn(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+    });
+    counter.fetch_add(1, Ordering::SeqCst);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(counter.load(Ordering::SeqCst), 3);
+}
+
+#[derive(Debug)]
+struct Worker {
+    handle: thread::JoinHandle<thread::ThreadId>,
+}
+
+#[kani::proof]
+fn check_builder_handle() {
+    let handle: thread::JoinHandle<_> =
+        thread::Builder::new().name("worker".to_string()).spawn(|| thread::current().id()).unwrap();
+    let worker = Worker { handle };
+    let id = worker.handle.thread().id();
+    assert_eq!(worker.handle.join().unwrap(), id);
+}
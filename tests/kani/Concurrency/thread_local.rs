@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that each thread spawned with `kani::thread::spawn` has its own copy of the thread
+//! locals, which starts with their initial value.
+//!
+//! Note that these harnesses fail outside of Kani, where the spawned closure runs in the
+//! current thread.
+
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static COUNTER: Cell<u32> = const { Cell::new(10) };
+    static LAZY: RefCell<u32> = RefCell::new(20);
+}
+
+#[kani::proof]
+fn check_const_thread_local() {
+    COUNTER.set(1);
+    let handle = kani::thread::spawn(|| {
+        let initial = COUNTER.get();
+        COUNTER.set(2);
+        initial
+    });
+    assert_eq!(COUNTER.get(), 1);
+    assert_eq!(handle.join(), 10);
+    assert_eq!(COUNTER.get(), 1);
+}
+
+#[kani::proof]
+fn check_lazy_thread_local() {
+    LAZY.with_borrow_mut(|lazy| *lazy += 1);
+    let handle = kani::thread::spawn(|| LAZY.with_borrow_mut(|lazy| std::mem::replace(lazy, 0)));
+    assert_eq!(handle.join(), 20);
+    assert_eq!(LAZY.with_borrow(|lazy| *lazy), 21);
+}
@@ -0,0 +1,35 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z const-generic-harness
+
+//! Check that harnesses with const generic parameters are verified for every value of the
+//! ranges given with `#[kani::const_generic]`.
+
+fn sum<const N: usize>(values: [u8; N]) -> u32 {
+    values.iter().map(|v| u32::from(*v)).sum()
+}
+
+#[kani::proof]
+#[kani::const_generic(N, 0..=4)]
+#[kani::unwind(6)]
+fn check_sum<const N: usize>() {
+    let values: [u8; N] = std::array::from_fn(|_| kani::any());
+    assert!(sum(values) <= 255 * N as u32);
+}
+
+#[kani::proof]
+#[kani::const_generic(SHIFT, -2..2)]
+#[kani::const_generic(WIDTH, 1..=2)]
+fn check_product<const SHIFT: i8, const WIDTH: u8>() {
+    let value: i8 = kani::any();
+    kani::assume(value.unsigned_abs() < 16);
+    let shifted = i16::from(value) + i16::from(SHIFT);
+    assert!(shifted.abs() <= 17 * i16::from(WIDTH));
+}
+
+#[kani::proof]
+#[kani::const_generic(LEN, 1..3)]
+fn check_with_lifetime<'a, const LEN: usize>() {
+    let len: &'a usize = &LEN;
+    assert!(*len > 0 && *len < 3);
+}
@@ -0,0 +1,78 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the state machine of coroutines: values passed when resuming, trait objects, the drop
+//! of a suspended coroutine, and resuming a coroutine that has completed.
+
+#![feature(coroutines, coroutine_trait, iter_from_coroutine, stmt_expr_attributes)]
+
+use std::ops::{Coroutine, CoroutineState};
+use std::pin::Pin;
+
+#[kani::proof]
+fn check_dyn_coroutine() {
+    let mut coroutine: Pin<Box<dyn Coroutine<u8, Yield = u8, Return = bool>>> =
+        Box::pin(#[coroutine]
+        |first: u8| {
+            let second = yield first.wrapping_add(1);
+            second > first
+        });
+    let first = kani::any();
+    let second = kani::any();
+    assert_eq!(coroutine.as_mut().resume(first), CoroutineState::Yielded(first.wrapping_add(1)));
+    assert_eq!(coroutine.as_mut().resume(second), CoroutineState::Complete(second > first));
+}
+
+struct Counter<'a>(&'a mut u8);
+
+impl Drop for Counter<'_> {
+    fn drop(&mut self) {
+        *self.0 += 1;
+    }
+}
+
+#[kani::proof]
+fn check_drop_suspended() {
+    let mut drops = 0;
+    let suspend_twice: bool = kani::any();
+    {
+        let counter = Counter(&mut drops);
+        let mut coroutine = #[coroutine]
+        move || {
+            let _counter = counter;
+            yield;
+            if suspend_twice {
+                yield;
+            }
+        };
+        let _ = Pin::new(&mut coroutine).resume(());
+        let state = Pin::new(&mut coroutine).resume(());
+        assert_eq!(state == CoroutineState::Complete(()), !suspend_twice);
+    }
+    assert_eq!(drops, 1);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn check_from_coroutine() {
+    let len: u8 = kani::any_where(|len| *len < 4);
+    let iter = std::iter::from_coroutine(#[coroutine]
+    move || {
+        for i in 0..len {
+            yield i;
+        }
+    });
+    assert_eq!(iter.map(usize::from).sum::<usize>(), (0..len as usize).sum());
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_resume_after_completion() {
+    let mut coroutine = #[coroutine]
+    || {
+        yield 1;
+    };
+    let _ = Pin::new(&mut coroutine).resume(());
+    let _ = Pin::new(&mut coroutine).resume(());
+    let _ = Pin::new(&mut coroutine).resume(());
+}
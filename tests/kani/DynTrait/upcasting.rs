@@ -0,0 +1,136 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that trait objects can be upcast to any of their supertraits, including the ones whose
+//! methods aren't a prefix of the vtable of the subtrait.
+#![feature(trait_upcasting)]
+
+use std::rc::Rc;
+
+trait Named {
+    fn name(&self) -> char;
+}
+
+trait Sized2D {
+    fn width(&self) -> u32;
+    fn height(&self) -> u32;
+}
+
+trait Shape: Named + Sized2D {
+    fn area(&self) -> u32 {
+        self.width() * self.height()
+    }
+}
+
+trait Solid: Shape {
+    fn depth(&self) -> u32;
+}
+
+struct Rectangle {
+    w: u32,
+    h: u32,
+}
+
+impl Named for Rectangle {
+    fn name(&self) -> char {
+        'r'
+    }
+}
+
+impl Sized2D for Rectangle {
+    fn width(&self) -> u32 {
+        self.w
+    }
+
+    fn height(&self) -> u32 {
+        self.h
+    }
+}
+
+impl Shape for Rectangle {}
+
+struct Cube {
+    side: u32,
+}
+
+impl Named for Cube {
+    fn name(&self) -> char {
+        'c'
+    }
+}
+
+impl Sized2D for Cube {
+    fn width(&self) -> u32 {
+        self.side
+    }
+
+    fn height(&self) -> u32 {
+        self.side
+    }
+}
+
+impl Shape for Cube {}
+
+impl Solid for Cube {
+    fn depth(&self) -> u32 {
+        self.side
+    }
+}
+
+#[kani::proof]
+fn check_upcast_ref() {
+    let w: u8 = kani::any();
+    let h: u8 = kani::any();
+    let rect = Rectangle { w: w.into(), h: h.into() };
+    let shape: &dyn Shape = &rect;
+    let named: &dyn Named = shape;
+    let sized: &dyn Sized2D = shape;
+    assert_eq!(named.name(), 'r');
+    assert_eq!(sized.width(), w.into());
+    assert_eq!(sized.height(), h.into());
+    assert_eq!(shape.area(), sized.width() * sized.height());
+}
+
+#[kani::proof]
+fn check_upcast_chain() {
+    let side: u8 = kani::any();
+    let solid: Box<dyn Solid + Send> = Box::new(Cube { side: side.into() });
+    assert_eq!(solid.depth(), side.into());
+    let shape: Box<dyn Shape> = solid;
+    assert_eq!(shape.area(), u32::from(side) * u32::from(side));
+    let sized: Box<dyn Sized2D> = shape;
+    assert_eq!(sized.height(), side.into());
+}
+
+#[kani::proof]
+fn check_upcast_rc() {
+    let shapes: [Rc<dyn Shape>; 2] =
+        [Rc::new(Rectangle { w: 2, h: 3 }), Rc::new(Cube { side: 4 })];
+    let idx: usize = kani::any_where(|idx| *idx < shapes.len());
+    let sized: Rc<dyn Sized2D> = shapes[idx].clone();
+    let named: Rc<dyn Named> = shapes[idx].clone();
+    match named.name() {
+        'r' => assert_eq!(sized.width(), 2),
+        'c' => assert_eq!(sized.width(), 4),
+        _ => unreachable!(),
+    }
+}
+
+trait Countdown: Named + Iterator<Item = u32> {}
+
+impl Named for std::ops::Range<u32> {
+    fn name(&self) -> char {
+        'i'
+    }
+}
+
+impl Countdown for std::ops::Range<u32> {}
+
+#[kani::proof]
+fn check_upcast_assoc_type() {
+    let end: u8 = kani::any();
+    let mut countdown: Box<dyn Countdown> = Box::new(0..end.into());
+    assert_eq!(countdown.name(), 'i');
+    let iter: &mut dyn Iterator<Item = u32> = &mut *countdown;
+    assert_eq!(iter.next(), if end > 0 { Some(0) } else { None });
+}
@@ -0,0 +1,39 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that the vtable restrictions of a call through a trait object include the methods of the
+// vtables created for the same trait with auto traits, since coercing a trait object to a type
+// with fewer auto traits keeps its vtable.
+
+// kani-flags: --enable-unstable --restrict-vtable
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+struct Square(u32);
+struct Rect(u32, u32);
+
+impl Shape for Square {
+    fn area(&self) -> u32 {
+        self.0 * self.0
+    }
+}
+
+impl Shape for Rect {
+    fn area(&self) -> u32 {
+        self.0 * self.1
+    }
+}
+
+fn area(shape: &dyn Shape) -> u32 {
+    shape.area()
+}
+
+#[kani::proof]
+fn check_area_send() {
+    let shape: Box<dyn Shape + Send + Sync> =
+        if kani::any() { Box::new(Square(2)) } else { Box::new(Rect(1, 3)) };
+    let area = area(&*shape);
+    assert!(area == 4 || area == 3);
+}
@@ -0,0 +1,71 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! Check that the encoding of enums with niche matches the one from rustc, including niche values
+//! that wrap around the range of the tag and niches nested inside other enums.
+#![feature(rustc_attrs)]
+
+use std::cmp::Ordering;
+use std::mem::transmute;
+
+#[rustc_layout_scalar_valid_range_start(1)]
+#[rustc_layout_scalar_valid_range_end(254)]
+#[derive(Clone, Copy)]
+struct Inner(u8);
+
+/// The niche values of `B` and `C` are 255 and 0.
+#[derive(Clone, Copy)]
+enum Wrapping {
+    A(Inner),
+    B,
+    C,
+}
+
+#[kani::proof]
+fn check_wrapping_niche() {
+    let value = if kani::any() { Wrapping::B } else { Wrapping::C };
+    let raw: u8 = unsafe { transmute(value) };
+    match value {
+        Wrapping::A(_) => unreachable!(),
+        Wrapping::B => assert_eq!(raw, 255),
+        Wrapping::C => assert_eq!(raw, 0),
+    }
+}
+
+#[kani::proof]
+fn check_wrapping_niche_read() {
+    let raw: u8 = kani::any();
+    let value: Wrapping = unsafe { transmute(raw) };
+    match value {
+        Wrapping::A(inner) => assert!(inner.0 == raw && raw != 0 && raw != 255),
+        Wrapping::B => assert_eq!(raw, 255),
+        Wrapping::C => assert_eq!(raw, 0),
+    }
+}
+
+#[kani::proof]
+fn check_nested_niche() {
+    let raw: u8 = kani::any_where(|raw| *raw <= 3);
+    let value: Option<Option<bool>> = unsafe { transmute(raw) };
+    match value {
+        Some(Some(val)) => assert_eq!(val as u8, raw),
+        Some(None) => assert_eq!(raw, 2),
+        None => assert_eq!(raw, 3),
+    }
+    let back: u8 = unsafe { transmute(value) };
+    assert_eq!(back, raw);
+}
+
+#[kani::proof]
+fn check_signed_niche() {
+    let ordering: Option<Ordering> = if kani::any() { None } else { Some(Ordering::Less) };
+    let raw: i8 = unsafe { transmute(ordering) };
+    assert_eq!(raw, if ordering.is_none() { 2 } else { -1 });
+    let nested: Option<Option<Ordering>> = if kani::any() { None } else { Some(ordering) };
+    let raw: i8 = unsafe { transmute(nested) };
+    match nested {
+        None => assert_eq!(raw, 3),
+        Some(None) => assert_eq!(raw, 2),
+        Some(Some(_)) => assert_eq!(raw, -1),
+    }
+}
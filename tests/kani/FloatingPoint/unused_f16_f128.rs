@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that functions that use `f16` and `f128` don't prevent the verification of the
+//! harnesses that don't reach them.
+#![feature(f16, f128)]
+
+pub fn half_to_bits(half: f16) -> u16 {
+    half.to_bits()
+}
+
+pub fn quad_identity(quad: f128) -> f128 {
+    quad
+}
+
+fn identity<T>(val: T) -> T {
+    val
+}
+
+#[kani::proof]
+fn check_without_f16_f128() {
+    let val: u32 = kani::any();
+    assert_eq!(identity(val), val);
+}
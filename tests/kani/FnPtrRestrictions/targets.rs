@@ -0,0 +1,75 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z restrict-fn-ptrs
+
+//! Check that calls through function pointers still reach all of their possible targets when
+//! their targets are restricted to the functions converted to a pointer of the same type.
+
+fn inc(x: u8) -> u8 {
+    x.wrapping_add(1)
+}
+
+fn dec(x: u8) -> u8 {
+    x.wrapping_sub(1)
+}
+
+fn double(x: u8) -> u8 {
+    x.wrapping_mul(2)
+}
+
+fn identity(x: u16) -> u16 {
+    x
+}
+
+static OPERATIONS: [fn(u8) -> u8; 2] = [inc, dec];
+
+fn apply(f: fn(u8) -> u8, x: u8) -> u8 {
+    f(x)
+}
+
+unsafe fn apply_unsafe(f: unsafe fn(u8) -> u8, x: u8) -> u8 {
+    f(x)
+}
+
+struct Callbacks {
+    on_value: fn(u8) -> u8,
+    on_wide: fn(u16) -> u16,
+}
+
+#[kani::proof]
+fn check_local_fn_ptr() {
+    let f: fn(u8) -> u8 = if kani::any() { inc } else { double };
+    let x: u8 = kani::any();
+    let result = f(x);
+    assert!(result == x.wrapping_add(1) || result == x.wrapping_mul(2));
+}
+
+#[kani::proof]
+fn check_fn_ptr_argument() {
+    let idx: usize = kani::any_where(|idx| *idx < OPERATIONS.len());
+    let x: u8 = kani::any();
+    let result = apply(OPERATIONS[idx], x);
+    if idx == 0 {
+        assert_eq!(result, x.wrapping_add(1));
+    } else {
+        assert_eq!(result, x.wrapping_sub(1));
+    }
+    assert_eq!(unsafe { apply_unsafe(double, x) }, x.wrapping_mul(2));
+}
+
+#[kani::proof]
+fn check_fn_ptr_field() {
+    let callbacks = Callbacks { on_value: double, on_wide: identity };
+    let x: u8 = kani::any();
+    assert_eq!((callbacks.on_value)(x), x.wrapping_mul(2));
+    assert_eq!((callbacks.on_wide)(x.into()), x.into());
+}
+
+#[kani::proof]
+fn check_closure_fn_ptr() {
+    let offset: fn(u8) -> u8 = |x| x / 2;
+    let x: u8 = kani::any();
+    assert_eq!(apply(offset, x), x / 2);
+    let overwritten = kani::any::<bool>().then_some(offset).unwrap_or(inc);
+    assert!(overwritten(x) == x / 2 || overwritten(x) == x.wrapping_add(1));
+}
@@ -1,7 +1,9 @@
 // Copyright Kani Contributors
 // SPDX-License-Identifier: Apache-2.0 OR MIT
-//! To run this test, do
-//! kani fixme_varadic.rs -- lib.c
+//! Check that calls to C variadic functions pass their extra arguments to the C definitions
+//! provided with `--c-lib`.
+
+// kani-flags: -Z c-ffi --c-lib tests/kani/ForeignItems/lib.c
 
 use std::os::raw::c_int;
 
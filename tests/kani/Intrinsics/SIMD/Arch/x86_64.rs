@@ -0,0 +1,38 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that the `core::arch` intrinsics that are implemented with LLVM intrinsics, such as
+//! byte shuffles and CRC-32C, are supported and return the expected results.
+#![cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[kani::proof]
+fn check_shuffle_epi8() {
+    unsafe {
+        let table = _mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+        let indexes = _mm_setr_epi8(15, 0, -128, 3, 1, 1, 1, 1, 0x12, 0, 0, 0, 0, 0, 0, -1);
+        let result: [u8; 16] = std::mem::transmute(_mm_shuffle_epi8(table, indexes));
+        assert_eq!(result, [15, 0, 0, 3, 1, 1, 1, 1, 2, 0, 0, 0, 0, 0, 0, 0]);
+    }
+}
+
+#[kani::proof]
+fn check_shuffle_epi8_symbolic() {
+    let index: u8 = kani::any();
+    kani::assume(index < 16);
+    unsafe {
+        let table = _mm_setr_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+        let result: [u8; 16] =
+            std::mem::transmute(_mm_shuffle_epi8(table, _mm_set1_epi8(index as i8)));
+        assert_eq!(result[7], index);
+    }
+}
+
+#[kani::proof]
+fn check_crc32() {
+    // The CRC-32C of "123456789" is 0xE3069283.
+    let crc = b"123456789".iter().fold(!0u32, |crc, byte| unsafe { _mm_crc32_u8(crc, *byte) });
+    assert_eq!(!crc, 0xE306_9283);
+    let crc64 = unsafe { _mm_crc32_u64(0, 0) };
+    assert_eq!(crc64 >> 32, 0);
+}
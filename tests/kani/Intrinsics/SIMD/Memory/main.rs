@@ -0,0 +1,56 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that the SIMD memory intrinsics `simd_gather`, `simd_scatter`, `simd_masked_load` and
+//! `simd_masked_store` are supported, and that masked out elements aren't accessed.
+#![feature(repr_simd, core_intrinsics)]
+use std::intrinsics::simd::{simd_gather, simd_masked_load, simd_masked_store, simd_scatter};
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct ptrx4<T>(*const T, *const T, *const T, *const T);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct mutptrx4<T>(*mut T, *mut T, *mut T, *mut T);
+
+#[kani::proof]
+fn check_gather() {
+    let data = [10, 20, 30];
+    let dangling = std::ptr::null::<i32>();
+    let pointers = ptrx4(&data[2], &data[0], dangling, &data[1]);
+    let mask = i32x4(-1, -1, 0, -1);
+    let defaults = i32x4(-1, -2, -3, -4);
+    let result = unsafe { simd_gather(defaults, pointers, mask) };
+    assert_eq!(result, i32x4(30, 10, -3, 20));
+}
+
+#[kani::proof]
+fn check_scatter() {
+    let mut data = [0; 3];
+    let base = data.as_mut_ptr();
+    let pointers = unsafe { mutptrx4(base.add(1), base, std::ptr::null_mut(), base.add(2)) };
+    let mask = i32x4(-1, -1, 0, -1);
+    unsafe { simd_scatter(i32x4(1, 2, 3, 4), pointers, mask) };
+    assert_eq!(data, [2, 1, 4]);
+}
+
+#[kani::proof]
+fn check_masked_load_store() {
+    // Only the first three elements are in bounds.
+    let mut data = [1, 2, 3];
+    let mask = i32x4(-1, -1, -1, 0);
+    let loaded = unsafe { simd_masked_load(mask, data.as_ptr(), i32x4(0, 0, 0, 42)) };
+    assert_eq!(loaded, i32x4(1, 2, 3, 42));
+
+    let mask = i32x4(0, -1, -1, 0);
+    unsafe { simd_masked_store(mask, data.as_mut_ptr(), i32x4(7, 8, 9, 10)) };
+    assert_eq!(data, [1, 8, 9]);
+}
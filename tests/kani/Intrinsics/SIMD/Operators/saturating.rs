@@ -0,0 +1,58 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that the SIMD intrinsics `simd_saturating_add`, `simd_saturating_sub` and `simd_neg`
+//! are supported and return the same results as the scalar operations on each lane.
+#![feature(repr_simd, core_intrinsics)]
+use std::intrinsics::simd::{simd_neg, simd_saturating_add, simd_saturating_sub};
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct i8x2(i8, i8);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct u32x2(u32, u32);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct f32x2(f32, f32);
+
+#[kani::proof]
+fn check_saturating_signed() {
+    let a = i8x2(kani::any(), kani::any());
+    let b = i8x2(kani::any(), kani::any());
+    let sum: i8x2 = unsafe { simd_saturating_add(a, b) };
+    assert!(sum == i8x2(a.0.saturating_add(b.0), a.1.saturating_add(b.1)));
+    let diff: i8x2 = unsafe { simd_saturating_sub(a, b) };
+    assert!(diff == i8x2(a.0.saturating_sub(b.0), a.1.saturating_sub(b.1)));
+}
+
+#[kani::proof]
+fn check_saturating_unsigned() {
+    let a = u32x2(kani::any(), kani::any());
+    let b = u32x2(kani::any(), kani::any());
+    let sum: u32x2 = unsafe { simd_saturating_add(a, b) };
+    assert!(sum == u32x2(a.0.saturating_add(b.0), a.1.saturating_add(b.1)));
+    let diff: u32x2 = unsafe { simd_saturating_sub(a, b) };
+    assert!(diff == u32x2(a.0.saturating_sub(b.0), a.1.saturating_sub(b.1)));
+}
+
+#[kani::proof]
+fn check_neg_wraps() {
+    let a = i8x2(kani::any(), i8::MIN);
+    let neg: i8x2 = unsafe { simd_neg(a) };
+    assert!(neg == i8x2(a.0.wrapping_neg(), i8::MIN));
+}
+
+#[kani::proof]
+fn check_neg_float() {
+    let a = f32x2(kani::any(), 1.5);
+    kani::assume(a.0.is_finite());
+    let neg: f32x2 = unsafe { simd_neg(a) };
+    assert!(neg.0 == -a.0);
+    assert!(neg.1 == -1.5);
+}
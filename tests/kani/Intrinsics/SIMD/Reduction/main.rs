@@ -0,0 +1,58 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that the `simd_reduce_*` intrinsics are supported and return the expected results.
+#![feature(repr_simd, core_intrinsics)]
+use std::intrinsics::simd::*;
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct i8x4(i8, i8, i8, i8);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy)]
+pub struct u32x4(u32, u32, u32, u32);
+
+#[kani::proof]
+fn check_arith_reductions() {
+    let v = u32x4(1, 2, 3, 4);
+    unsafe {
+        assert_eq!(simd_reduce_add_unordered::<_, u32>(v), 10);
+        assert_eq!(simd_reduce_add_ordered::<_, u32>(v, 5), 15);
+        assert_eq!(simd_reduce_mul_unordered::<_, u32>(v), 24);
+        assert_eq!(simd_reduce_mul_ordered::<_, u32>(v, 2), 48);
+    }
+}
+
+#[kani::proof]
+fn check_integer_reductions_wrap() {
+    let v = i8x4(100, 100, -1, 1);
+    unsafe {
+        assert_eq!(simd_reduce_add_unordered::<_, i8>(v), -56);
+        assert_eq!(simd_reduce_max::<_, i8>(v), 100);
+        assert_eq!(simd_reduce_min::<_, i8>(v), -1);
+    }
+}
+
+#[kani::proof]
+fn check_bitwise_reductions() {
+    let v = u32x4(0b0111, 0b0110, 0b1110, 0b0100);
+    unsafe {
+        assert_eq!(simd_reduce_and::<_, u32>(v), 0b0100);
+        assert_eq!(simd_reduce_or::<_, u32>(v), 0b1111);
+        assert_eq!(simd_reduce_xor::<_, u32>(v), 0b1011);
+    }
+}
+
+#[kani::proof]
+fn check_mask_reductions() {
+    let lane: bool = kani::any();
+    let mask = i8x4(-1, -1, if lane { -1 } else { 0 }, -1);
+    unsafe {
+        assert_eq!(simd_reduce_all(mask), lane);
+        assert!(simd_reduce_any(mask));
+        assert!(!simd_reduce_any(i8x4(0, 0, 0, 0)));
+    }
+}
@@ -0,0 +1,47 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that `simd_select` and `simd_select_bitmask` are supported and return the expected
+//! results.
+#![feature(repr_simd, core_intrinsics)]
+use std::intrinsics::simd::{simd_select, simd_select_bitmask};
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct i32x4(i32, i32, i32, i32);
+
+#[repr(simd)]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct u64x4(u64, u64, u64, u64);
+
+#[kani::proof]
+fn check_select() {
+    let mask = i32x4(0, -1, -1, 0);
+    let a = u64x4(1, 2, 3, 4);
+    let b = u64x4(5, 6, 7, 8);
+    let c: u64x4 = unsafe { simd_select(mask, a, b) };
+    assert_eq!(c, u64x4(5, 2, 3, 8));
+}
+
+#[kani::proof]
+fn check_select_symbolic() {
+    let first: bool = kani::any();
+    let mask = i32x4(if first { -1 } else { 0 }, 0, 0, 0);
+    let a = i32x4(1, 2, 3, 4);
+    let b = i32x4(5, 6, 7, 8);
+    let c = unsafe { simd_select(mask, a, b) };
+    assert_eq!(c.0 == 1, first);
+    assert_eq!(c.3, 8);
+}
+
+#[kani::proof]
+fn check_select_bitmask() {
+    let a = i32x4(1, 2, 3, 4);
+    let b = i32x4(5, 6, 7, 8);
+    let c = unsafe { simd_select_bitmask(0b0101u8, a, b) };
+    assert_eq!(c, i32x4(1, 6, 3, 8));
+    let d = unsafe { simd_select_bitmask([0b1100u8], a, b) };
+    assert_eq!(d, i32x4(5, 6, 3, 4));
+}
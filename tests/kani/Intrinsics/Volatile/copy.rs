@@ -0,0 +1,35 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Check that `volatile_copy_memory` and `volatile_copy_nonoverlapping_memory`
+// copy `count` elements to their first argument from their second argument,
+// and that `volatile_copy_memory` supports overlapping regions.
+#![feature(core_intrinsics)]
+
+use std::intrinsics::{volatile_copy_memory, volatile_copy_nonoverlapping_memory};
+
+#[kani::proof]
+fn check_volatile_copy_nonoverlapping() {
+    let src: [u8; 3] = kani::any();
+    let mut dst = [0u8; 3];
+    unsafe { volatile_copy_nonoverlapping_memory(dst.as_mut_ptr(), src.as_ptr(), 3) };
+    assert_eq!(dst, src);
+}
+
+#[kani::proof]
+fn check_volatile_copy_overlapping() {
+    let mut arr: [u16; 4] = [1, 2, 3, 4];
+    let ptr = arr.as_mut_ptr();
+    unsafe { volatile_copy_memory(ptr.add(1), ptr, 3) };
+    assert_eq!(arr, [1, 1, 2, 3]);
+    unsafe { volatile_copy_memory(ptr, ptr.add(2), 2) };
+    assert_eq!(arr, [2, 3, 2, 3]);
+}
+
+#[kani::proof]
+fn check_volatile_copy_empty() {
+    let mut arr: [u32; 2] = [1, 2];
+    let ptr = arr.as_mut_ptr();
+    unsafe { volatile_copy_nonoverlapping_memory(ptr, ptr, 0) };
+    assert_eq!(arr, [1, 2]);
+}
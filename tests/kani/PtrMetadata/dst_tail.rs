@@ -0,0 +1,78 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check the address of the unsized tail of a struct. The offset of a trait object tail
+//! depends on the alignment of its concrete type, which is only known from the vtable.
+#![feature(offset_of_nested)]
+
+use std::mem::{align_of_val, offset_of, size_of_val};
+
+trait Value {
+    fn value(&self) -> u64;
+}
+
+impl Value for u8 {
+    fn value(&self) -> u64 {
+        *self as u64
+    }
+}
+
+impl Value for u64 {
+    fn value(&self) -> u64 {
+        *self
+    }
+}
+
+struct Wrapper<T: ?Sized> {
+    tag: u8,
+    tail: T,
+}
+
+#[repr(C)]
+struct Header {
+    len: u16,
+    flag: u8,
+    items: [u32; 2],
+}
+
+fn tail_offset<T: ?Sized>(wrapper: &Wrapper<T>) -> usize {
+    let tail = &wrapper.tail as *const T as *const u8 as usize;
+    tail - wrapper as *const Wrapper<T> as *const u8 as usize
+}
+
+#[kani::proof]
+fn check_dyn_tail_offset() {
+    let small: &Wrapper<dyn Value> = &Wrapper { tag: 1, tail: 2u8 };
+    let large: &Wrapper<dyn Value> = &Wrapper { tag: 3, tail: u64::MAX };
+    let wrapper = if kani::any() { small } else { large };
+    assert_eq!(tail_offset(wrapper), align_of_val(&wrapper.tail));
+    assert_eq!(size_of_val(wrapper), 2 * align_of_val(wrapper));
+    assert!(wrapper.tail.value() == 2 || wrapper.tail.value() == u64::MAX);
+    assert!(wrapper.tag == 1 || wrapper.tag == 3);
+}
+
+#[kani::proof]
+fn check_nested_dyn_tail() {
+    let nested: &Wrapper<Wrapper<dyn Value>> =
+        &Wrapper { tag: 1, tail: Wrapper { tag: 2, tail: 3u64 } };
+    assert_eq!(tail_offset(nested), 8);
+    assert_eq!(tail_offset(&nested.tail), 8);
+    assert_eq!(nested.tail.tail.value(), 3);
+    assert_eq!(nested.tail.tag, 2);
+}
+
+#[kani::proof]
+fn check_slice_tail_offset() {
+    let wrapper: &Wrapper<[u32]> = &Wrapper { tag: 1, tail: [2, 3] };
+    assert_eq!(tail_offset(wrapper), 4);
+    assert_eq!(size_of_val(wrapper), 12);
+    assert_eq!(wrapper.tail[1], 3);
+}
+
+#[kani::proof]
+fn check_offset_of() {
+    assert_eq!(offset_of!(Header, flag), 2);
+    assert_eq!(offset_of!(Header, items), 4);
+    assert_eq!(offset_of!(Wrapper<[u32; 2]>, tail), 4);
+    assert_eq!(offset_of!(Wrapper<Header>, tail.items), 8);
+}
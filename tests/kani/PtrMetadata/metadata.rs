@@ -0,0 +1,84 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that `ptr::metadata` and `ptr::from_raw_parts` work with every kind of pointer
+//! metadata, including structs with an unsized tail.
+#![feature(ptr_metadata)]
+
+use std::ptr;
+
+trait Shape {
+    fn area(&self) -> u32;
+}
+
+impl Shape for u32 {
+    fn area(&self) -> u32 {
+        *self
+    }
+}
+
+struct Wrapper<T: ?Sized> {
+    tag: u8,
+    tail: T,
+}
+
+#[kani::proof]
+fn check_sized() {
+    let x = 5u64;
+    ptr::metadata(&x);
+    let p: *const u64 = ptr::from_raw_parts(&x as *const u64 as *const (), ());
+    assert_eq!(unsafe { *p }, 5);
+}
+
+#[kani::proof]
+fn check_slice() {
+    let arr = [1u8, 2, 3, 4];
+    let slice: &[u8] = &arr;
+    assert_eq!(ptr::metadata(slice), 4);
+    let p: *const [u8] = ptr::from_raw_parts(slice.as_ptr() as *const (), 2);
+    assert_eq!(unsafe { &*p }, &[1, 2]);
+}
+
+#[kani::proof]
+fn check_str() {
+    let s = "hello";
+    assert_eq!(ptr::metadata(s), 5);
+    let p: *const str = ptr::from_raw_parts(s.as_ptr() as *const (), 4);
+    assert_eq!(unsafe { &*p }, "hell");
+}
+
+#[kani::proof]
+fn check_dyn() {
+    let x = 10u32;
+    let shape: &dyn Shape = &x;
+    let meta = ptr::metadata(shape);
+    assert_eq!(meta.size_of(), 4);
+    assert_eq!(meta.align_of(), 4);
+    let p: *const dyn Shape = ptr::from_raw_parts(shape as *const dyn Shape as *const (), meta);
+    assert_eq!(unsafe { &*p }.area(), 10);
+}
+
+#[kani::proof]
+fn check_slice_tail() {
+    let wrapper: Wrapper<[u16; 3]> = Wrapper { tag: 7, tail: [1, 2, 3] };
+    let unsized_wrapper: &Wrapper<[u16]> = &wrapper;
+    assert_eq!(ptr::metadata(unsized_wrapper), 3);
+    let p: *mut Wrapper<[u16]> = ptr::from_raw_parts_mut(unsized_wrapper as *const _ as *mut (), 2);
+    let back = unsafe { &*p };
+    assert_eq!(back.tag, 7);
+    assert_eq!(back.tail.len(), 2);
+    assert_eq!(back.tail[1], 2);
+}
+
+#[kani::proof]
+fn check_dyn_tail() {
+    let wrapper: Wrapper<u32> = Wrapper { tag: 7, tail: 42 };
+    let unsized_wrapper: &Wrapper<dyn Shape> = &wrapper;
+    let meta = ptr::metadata(unsized_wrapper);
+    assert_eq!(meta.size_of(), 4);
+    let p: *const Wrapper<dyn Shape> =
+        ptr::from_raw_parts(unsized_wrapper as *const _ as *const (), meta);
+    let back = unsafe { &*p };
+    assert_eq!(back.tag, 7);
+    assert_eq!(back.tail.area(), 42);
+}
@@ -0,0 +1,73 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z alignment-checks
+//
+//! Check the layout of `#[repr(packed)]` structs and that their fields can be accessed through
+//! pointers that don't require alignment.
+
+use std::mem::{align_of, size_of};
+use std::ptr::{addr_of, addr_of_mut};
+
+#[repr(C, packed)]
+#[derive(Clone, Copy)]
+struct Header {
+    tag: u8,
+    len: u32,
+    crc: u16,
+}
+
+#[repr(C, packed(2))]
+#[derive(Clone, Copy)]
+struct Pair {
+    first: u8,
+    second: u64,
+}
+
+#[kani::proof]
+fn check_layout() {
+    assert_eq!(size_of::<Header>(), 7);
+    assert_eq!(align_of::<Header>(), 1);
+    assert_eq!(size_of::<Pair>(), 10);
+    assert_eq!(align_of::<Pair>(), 2);
+    assert_eq!(size_of::<[Header; 3]>(), 21);
+}
+
+#[kani::proof]
+fn check_field_access() {
+    let len: u32 = kani::any();
+    let mut header = Header { tag: 1, len, crc: 0 };
+    header.crc = 0xffff;
+    let copy = header;
+    assert_eq!({ copy.len }, len);
+    assert_eq!({ copy.crc }, 0xffff);
+    let bytes: [u8; 7] = unsafe { std::mem::transmute(header) };
+    assert_eq!(bytes[0], 1);
+    assert_eq!(u32::from_ne_bytes([bytes[1], bytes[2], bytes[3], bytes[4]]), len);
+}
+
+#[kani::proof]
+fn check_unaligned_pointers() {
+    let second: u64 = kani::any();
+    let mut pair = Pair { first: 0, second: 0 };
+    let ptr = addr_of_mut!(pair.second);
+    unsafe { ptr.write_unaligned(second) };
+    assert_eq!(unsafe { addr_of!(pair.second).read_unaligned() }, second);
+
+    let headers = [Header { tag: 0, len: 10, crc: 1 }; 3];
+    let idx: usize = kani::any_where(|idx| *idx < 3);
+    let len = unsafe { addr_of!(headers[idx].len).read_unaligned() };
+    assert_eq!(len, 10);
+}
+
+#[kani::proof]
+fn check_aligned_dereference() {
+    let header = Header { tag: 0, len: 10, crc: 1 };
+    let ptr = addr_of!(header);
+    // The struct itself has alignment 1, so any pointer to it is aligned.
+    let copy = unsafe { *ptr };
+    assert_eq!({ copy.len }, 10);
+    let mut values = [0u32; 2];
+    let ptr = values.as_mut_ptr();
+    unsafe { *ptr.add(1) = 5 };
+    assert_eq!(values[1], 5);
+}
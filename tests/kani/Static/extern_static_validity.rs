@@ -0,0 +1,55 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Zfunction-contracts
+
+//! Check that the statics declared in `extern` blocks have a nondeterministic value that is valid
+//! for their type, and that their value can be restricted by the precondition of a contract.
+
+#[derive(Clone, Copy, PartialEq)]
+#[repr(u8)]
+enum Mode {
+    Off = 1,
+    On = 4,
+}
+
+#[allow(improper_ctypes)]
+extern "C" {
+    static FLAG: bool;
+    static MODE: Mode;
+    static LIMITS: [char; 2];
+    static mut COUNTER: u32;
+}
+
+#[kani::proof]
+fn check_valid_values() {
+    let flag = unsafe { FLAG };
+    assert!(flag as u8 <= 1);
+    let mode = unsafe { MODE };
+    assert!(mode == Mode::Off || mode == Mode::On);
+    let limits = unsafe { LIMITS };
+    assert!(limits[0] <= char::MAX);
+    assert!(limits[1] as u32 <= 0x10FFFF);
+}
+
+#[kani::proof]
+fn check_nondet_values() {
+    let flag = unsafe { FLAG };
+    kani::cover!(flag);
+    kani::cover!(!flag);
+    kani::cover!(unsafe { MODE } == Mode::On);
+}
+
+#[kani::requires(COUNTER < u32::MAX)]
+#[kani::ensures(result == COUNTER)]
+#[kani::modifies(&mut COUNTER)]
+unsafe fn next_id() -> u32 {
+    COUNTER += 1;
+    COUNTER
+}
+
+#[kani::proof_for_contract(next_id)]
+fn check_next_id() {
+    unsafe {
+        next_id();
+    }
+}
@@ -0,0 +1,59 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that statics are initialized with the value computed by the compiler's constant
+//! evaluation, including statics that refer to other statics and the nested statics that rustc
+//! creates for mutable references in the initializer of a `static mut`.
+
+static mut FIRST: &mut [u8] = &mut [1, 2, 3];
+static mut SECOND: &mut [u8] = &mut [1, 2, 3];
+
+const fn multiples_of_three() -> [u32; 4] {
+    let mut table = [0; 4];
+    let mut i = 0;
+    while i < table.len() {
+        table[i] = i as u32 * 3;
+        i += 1;
+    }
+    table
+}
+
+static TABLE: [u32; 4] = multiples_of_three();
+static ENTRIES: [&u32; 2] = [&TABLE[1], &TABLE[3]];
+
+struct Node {
+    next: Option<&'static Node>,
+    val: u8,
+}
+
+// Declared before the static it depends on.
+static HEAD: Node = Node { next: Some(&TAIL), val: 1 };
+static TAIL: Node = Node { next: None, val: 2 };
+
+#[kani::proof]
+fn check_nested_static_mut() {
+    let idx: usize = kani::any_where(|idx| *idx < 3);
+    let val: u8 = kani::any();
+    unsafe {
+        FIRST[idx] = val;
+        assert_eq!(FIRST[idx], val);
+        assert_eq!(SECOND[idx], idx as u8 + 1);
+        assert_eq!(SECOND.len(), 3);
+    }
+}
+
+#[kani::proof]
+fn check_const_fn_initializer() {
+    assert_eq!(TABLE, [0, 3, 6, 9]);
+    assert_eq!(*ENTRIES[0], 3);
+    assert_eq!(*ENTRIES[1], 9);
+    assert!(std::ptr::eq(ENTRIES[1], &TABLE[3]));
+}
+
+#[kani::proof]
+fn check_static_references() {
+    let next = HEAD.next.unwrap();
+    assert!(std::ptr::eq(next, &TAIL));
+    assert_eq!(HEAD.val + next.val, 3);
+    assert!(next.next.is_none());
+}
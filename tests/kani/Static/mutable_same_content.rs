@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that mutable statics with the same initial content don't share their memory, while
+//! constant allocations with the same content do.
+#![allow(static_mut_refs)]
+
+static mut FIRST: [u8; 2] = [1, 2];
+static mut SECOND: [u8; 2] = [1, 2];
+
+static mut FIRST_SLICE: &mut [u8] = &mut [1, 2];
+static mut SECOND_SLICE: &mut [u8] = &mut [1, 2];
+
+#[kani::proof]
+fn check_mutable_statics() {
+    unsafe {
+        FIRST[0] = 10;
+        assert_eq!(SECOND, [1, 2]);
+        FIRST_SLICE[1] = 20;
+        assert_eq!(SECOND_SLICE, &[1, 2]);
+        assert_eq!(FIRST_SLICE, &[1, 20]);
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+// Checks that thread locals whose values need to be dropped can be used. Their destructors are
+// registered when they are first accessed, and they are never run by Kani.
+
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static LIST: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+    static NAME: RefCell<String> = RefCell::new(String::from("kani"));
+    static FLAG: Cell<bool> = const { Cell::new(true) };
+}
+
+#[kani::proof]
+#[kani::unwind(3)]
+fn check_vec() {
+    let value: u8 = kani::any();
+    LIST.with_borrow_mut(|list| list.push(value));
+    LIST.with_borrow_mut(|list| list.push(value));
+    assert_eq!(LIST.with_borrow(|list| list.len()), 2);
+    assert_eq!(LIST.with_borrow(|list| list[1]), value);
+}
+
+#[kani::proof]
+#[kani::unwind(5)]
+fn check_string_and_flag() {
+    assert!(FLAG.get());
+    FLAG.set(false);
+    assert_eq!(NAME.with_borrow(|name| name.len()), 4);
+    assert!(!FLAG.get());
+}
@@ -0,0 +1,54 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z valid-value-checks
+//! Check that Kani can identify invalid values of closures and coroutines, whose captured
+//! variables and state must be valid.
+
+#![feature(coroutines, coroutine_trait, stmt_expr_attributes)]
+
+use std::ops::{Coroutine, CoroutineState};
+use std::pin::Pin;
+
+/// Read a value with the same type as `_value` from the given byte.
+unsafe fn read_as<T>(_value: &T, byte: &u8) -> T {
+    std::ptr::read(byte as *const u8 as *const T)
+}
+
+#[kani::proof]
+fn check_closure_valid() {
+    let flag: bool = kani::any();
+    let closure = move || flag;
+    let byte = kani::any_where(|byte: &u8| *byte <= 1);
+    let read = unsafe { read_as(&closure, &byte) };
+    assert_eq!(read(), byte == 1);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_closure_invalid() {
+    let flag: bool = kani::any();
+    let closure = move || flag;
+    let _read = unsafe { read_as(&closure, &2) };
+}
+
+#[kani::proof]
+fn check_coroutine_valid() {
+    let mut coroutine = #[coroutine]
+    || {
+        yield 1u8;
+    };
+    assert_eq!(std::mem::size_of_val(&coroutine), 1);
+    let mut copy = unsafe { read_as(&coroutine, &0) };
+    assert_eq!(Pin::new(&mut copy).resume(()), CoroutineState::Yielded(1));
+    assert_eq!(Pin::new(&mut coroutine).resume(()), CoroutineState::Yielded(1));
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_coroutine_invalid_state() {
+    let coroutine = #[coroutine]
+    || {
+        yield 1u8;
+    };
+    let _read = unsafe { read_as(&coroutine, &kani::any()) };
+}
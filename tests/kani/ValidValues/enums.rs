@@ -0,0 +1,87 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z valid-value-checks
+//! Check that Kani can identify invalid values of enums, including enums with niche and enums
+//! whose variants carry data.
+
+use std::mem::transmute;
+
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum Sparse {
+    First = 1,
+    Second = 5,
+}
+
+#[derive(Clone, Copy)]
+#[repr(u8)]
+enum WithData {
+    Flag(bool),
+    Byte(u8),
+}
+
+#[kani::proof]
+fn check_valid_sparse() {
+    let raw: u8 = kani::any_where(|raw| *raw == 1 || *raw == 5);
+    let value: Sparse = unsafe { transmute(raw) };
+    assert_eq!(value as u8, raw);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_invalid_sparse() {
+    let raw: u8 = kani::any_where(|raw| *raw > 1 && *raw < 5);
+    let _value: Sparse = unsafe { transmute(raw) };
+}
+
+#[kani::proof]
+fn check_valid_data() {
+    let raw: [u8; 2] = kani::any_where(|raw: &[u8; 2]| raw[0] == 1 || raw[1] <= 1);
+    let value: WithData = unsafe { transmute(raw) };
+    match value {
+        WithData::Flag(flag) => assert_eq!(flag as u8, raw[1]),
+        WithData::Byte(byte) => assert_eq!(byte, raw[1]),
+    }
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_invalid_data() {
+    let raw: [u8; 2] = kani::any_where(|raw: &[u8; 2]| raw[0] == 0 && raw[1] > 1);
+    let _value: WithData = unsafe { transmute(raw) };
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_invalid_tag() {
+    let raw: [u8; 2] = kani::any_where(|raw: &[u8; 2]| raw[0] > 1);
+    let _value: WithData = unsafe { transmute(raw) };
+}
+
+#[kani::proof]
+fn check_valid_niche() {
+    let raw: u8 = kani::any_where(|raw| *raw <= 3);
+    let value: Option<Option<bool>> = unsafe { transmute(raw) };
+    assert_eq!(value.is_none(), raw == 3);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_invalid_niche() {
+    let raw: u8 = kani::any_where(|raw| *raw > 3);
+    let _value: Option<Option<bool>> = unsafe { transmute(raw) };
+}
+
+#[kani::proof]
+fn check_valid_niche_deref() {
+    let raw: u8 = kani::any_where(|raw| *raw <= 2);
+    let value = unsafe { *(&raw as *const u8 as *const Option<bool>) };
+    assert_eq!(value.is_none(), raw == 2);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_invalid_niche_deref() {
+    let raw: u8 = kani::any_where(|raw| *raw > 2);
+    let _value = unsafe { *(&raw as *const u8 as *const Option<bool>) };
+}
@@ -0,0 +1,50 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! This test checks the performance of the 128-bit multiplication and division, which are
+//! encoded with the native 128-bit operators of CBMC, on fully symbolic operands.
+//! The same properties are checked with bounded operands in `tests/kani/ArithOperators`.
+
+#[kani::proof]
+fn check_u128_checked_mul() {
+    let a: u128 = kani::any();
+    let b: u128 = kani::any();
+    let (wrapped, overflow) = a.overflowing_mul(b);
+    assert_eq!(wrapped, a.wrapping_mul(b));
+    match a.checked_mul(b) {
+        Some(product) => {
+            assert!(!overflow);
+            assert!(a == 0 || product / a == b);
+        }
+        None => {
+            assert!(overflow);
+            assert_eq!(a.saturating_mul(b), u128::MAX);
+        }
+    }
+}
+
+#[kani::proof]
+fn check_u128_div_rem() {
+    let a: u128 = kani::any();
+    let b: u128 = kani::any();
+    kani::assume(b != 0);
+    let q = a / b;
+    let r = a % b;
+    assert!(r < b);
+    assert_eq!(q * b + r, a);
+    assert_eq!(a.checked_div(b), Some(q));
+}
+
+#[kani::proof]
+fn check_i128_checked_ops() {
+    let a: i128 = kani::any();
+    let b: i128 = kani::any();
+    assert_eq!(a.checked_div(b).is_none(), b == 0 || (a == i128::MIN && b == -1));
+    assert_eq!(a.checked_rem(b).is_none(), b == 0 || (a == i128::MIN && b == -1));
+    assert_eq!(a.checked_neg().is_none(), a == i128::MIN);
+    if let Some(sum) = a.checked_add(b) {
+        assert_eq!(sum.wrapping_sub(b), a);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `cargo kani assess` assesses all the members of the workspace, and with
+# `--path-dependencies`, the path dependencies that aren't members, in one report.
+
+set +e
+
+TMP_DIR="/tmp/assess-workspace"
+
+# Print the number of analyzed packages, and the tests and readiness of each package.
+readiness() {
+    awk -F '|' '/^Analyzed/ { print } $1 ~ /^ (app|member|vendored) / { print $1 $4 $NF }' \
+        | tr -s ' ' | sed 's/ *$//'
+}
+
+rm -rf ${TMP_DIR}
+cp -r . ${TMP_DIR}
+pushd ${TMP_DIR}/ws > /dev/null
+
+echo "[TEST] Assess the workspace"
+cargo kani --enable-unstable assess 2> /dev/null | readiness
+
+echo "[TEST] Assess the workspace and its path dependencies"
+cargo kani --enable-unstable assess --path-dependencies 2> /dev/null | readiness
+
+popd > /dev/null
+rm -rf ${TMP_DIR}
@@ -0,0 +1,11 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+pub fn double(x: u8) -> u8 {
+    x * 2
+}
+
+#[test]
+fn test_double() {
+    assert_eq!(double(2), 4);
+}
@@ -0,0 +1,8 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[test]
+fn test_member() {
+    let v = vec![1, 2, 3];
+    assert_eq!(v.len(), 3);
+}
@@ -0,0 +1,16 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+pub fn add(a: u8, b: u8) -> u8 {
+    a.wrapping_add(b)
+}
+
+#[test]
+fn test_add() {
+    assert_eq!(add(1, 2), 3);
+}
+
+#[test]
+fn test_vendored() {
+    assert_eq!(vendored::double(100), 200);
+}
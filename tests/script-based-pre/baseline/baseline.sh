@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--baseline` only fails the verification if the failing harnesses don't match the
+# expected failures.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=baseline_out
+rm -rf ${OUT_DIR}
+mkdir ${OUT_DIR}
+
+# Expects two arguments: "test name" "baseline content"
+function check_baseline {
+    echo "[TEST] $1"
+    echo "$2" > ${OUT_DIR}/baseline.toml
+    kani known_failures.rs --baseline ${OUT_DIR}/baseline.toml | grep -E "^( - |Baseline)"
+    echo "Kani exit status: ${PIPESTATUS[0]}"
+}
+
+check_baseline "Only the expected failure" \
+    $'[expected-failures]\n"check_known_issue" = "x can be large"'
+check_baseline "A new failure" \
+    $'[expected-failures]'
+check_baseline "An unexpected success" \
+    $'[expected-failures]\n"check_known_issue" = "x can be large"\n"check_pass" = "fixed"'
+
+rm -rf ${OUT_DIR}
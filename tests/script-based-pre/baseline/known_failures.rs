@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::proof]
+fn check_pass() {
+    let x: u8 = kani::any();
+    assert!(x / 2 <= x);
+}
+
+#[kani::proof]
+fn check_known_issue() {
+    let x: u8 = kani::any();
+    assert!(x < 100, "x is small");
+}
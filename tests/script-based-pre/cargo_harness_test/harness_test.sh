@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+set +e
+
+pushd sample_crate > /dev/null
+cargo clean
+
+echo "[TEST] Unstable..."
+cargo kani test
+
+echo "[TEST] Run all..."
+cargo kani test -Z concrete-playback >& output.log
+echo "exit code: $?"
+# Sort output so we can rely on the order.
+grep "^test verify::" output.log | sort
+grep "assertion failed" output.log
+rm output.log
+
+echo "[TEST] Assumption..."
+cargo kani test -Z concrete-playback -- check_assume --nocapture 2>&1 | grep "An assumption"
+
+echo "[TEST] Filter..."
+cargo kani test -Z concrete-playback -- check_div 2>&1 | grep "^test verify::" | sort
+
+cargo clean
+popd > /dev/null
@@ -0,0 +1,52 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that `cargo kani test` runs the harnesses with trivial values, along with the concrete
+//! playback tests.
+
+pub fn checked_div(a: u32, b: u32) -> Option<u32> {
+    if b == 0 { None } else { Some(a / b) }
+}
+
+#[cfg(kani)]
+mod verify {
+    use super::*;
+
+    #[kani::proof]
+    fn check_div() {
+        let a: u32 = kani::any();
+        let b: u32 = kani::any();
+        assert_eq!(checked_div(a, b).is_none(), b == 0);
+    }
+
+    #[kani::proof]
+    fn check_assume() {
+        let b: u32 = kani::any();
+        kani::assume(b > 0);
+        assert!(checked_div(1, b).is_some());
+    }
+
+    #[kani::proof]
+    #[kani::should_panic]
+    fn check_should_panic() {
+        let b: u32 = kani::any();
+        assert!(b > 10);
+    }
+
+    #[kani::proof]
+    async fn check_async() {
+        let b: u8 = kani::any();
+        assert!(checked_div(1, b.into()).is_some());
+    }
+
+    #[test]
+    fn kani_concrete_playback_check_div_1234() {
+        let concrete_vals: Vec<Vec<u8>> = vec![
+            // 6
+            vec![6, 0, 0, 0],
+            // 3
+            vec![3, 0, 0, 0],
+        ];
+        kani::concrete_playback_run(concrete_vals, check_div);
+    }
+}
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that the constant allocations with the same content, e.g., the panic locations and
+# messages, are only generated once.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=target
+
+rm -rf ${OUT_DIR}
+mkdir -p ${OUT_DIR}
+# The pre-pass would prune the panics of `first`.
+kani interning.rs --target-dir ${OUT_DIR} --only-codegen --keep-temps --write-json-symtab \
+    --no-prepass > /dev/null 2>&1
+
+# Compare the initializers of the allocations, which refer to the allocations they point to. The
+# empty allocations are left out, since they only differ by their alignment.
+python3 -c '
+import collections, glob, json, re
+symtab = json.load(open(glob.glob("'${OUT_DIR}'/*.symtab.json")[0]))["symbolTable"]
+contents = collections.Counter()
+for name, symbol in symtab.items():
+    if re.search(r"AllocId\(\d+\)_init$", name) and symbol["value"].get("id") == "code":
+        content = json.dumps(symbol["value"]).replace(name[:-len("_init")], "self")
+        if "AllocId" in content:
+            contents[re.sub(r"AllocId\(\d+\)", "AllocId", content)] += 1
+print("Found allocations:", len(contents) > 0)
+print("Duplicated allocations:", sum(count - 1 for count in contents.values()))
+'
+
+# Try to leave a clean output folder at the end
+rm -rf ${OUT_DIR}
@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//
+//! The panics of `first` use constants that rustc stores in different allocations with the same
+//! content.
+
+use std::mem::size_of;
+
+fn first<T: Copy + Default>(items: &[T]) -> T {
+    if size_of::<T>() == 0 {
+        panic!("zero-sized items aren't supported: {}", items.len());
+    }
+    if items.is_empty() { T::default() } else { items[0] }
+}
+
+#[kani::proof]
+fn check_first() {
+    let items: [u32; 2] = kani::any();
+    assert_eq!(first(&items), items[0]);
+}
@@ -0,0 +1,14 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--fail-fast` stops the verification after the first failed harness, and that
+# `--max-failures` stops it after the given number of failed harnesses. The harnesses are verified
+# one at a time, so the ones that are stopped are known.
+# Note: This should run in the folder where the script is.
+
+echo "[TEST] Fail fast"
+kani harnesses.rs -j 1 --fail-fast 2>&1 | grep -E "^(Checking harness|Stopped after|Complete)"
+
+echo "[TEST] Max failures"
+kani harnesses.rs -j 1 --max-failures 2 2>&1 | grep -E "^(Checking harness|Stopped after|Complete)"
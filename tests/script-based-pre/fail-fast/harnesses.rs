@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::proof]
+fn check_a() {
+    let x: u8 = kani::any();
+    assert!(x.wrapping_add(0) == x);
+}
+
+#[kani::proof]
+fn check_b() {
+    let x: u8 = kani::any();
+    assert!(x < 100);
+}
+
+#[kani::proof]
+fn check_c() {
+    let x: u16 = kani::any();
+    assert!(x / 2 <= x);
+}
@@ -0,0 +1,28 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that only the code of the harnesses selected by `--harness` and `--exclude-harness` is
+# generated.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=target
+
+function check_kani {
+    rm -rf ${OUT_DIR}
+    mkdir -p ${OUT_DIR}
+    kani harnesses.rs --target-dir ${OUT_DIR} --keep-temps "$@" 2>&1 | grep -E "^Complete"
+    echo "Generated $(find ${OUT_DIR} -name '*.symtab.out' | wc -l) harnesses"
+}
+
+echo "[TEST] One harness"
+check_kani --harness check_add
+
+echo "[TEST] Excluded harness"
+check_kani --harness 'check_*' --exclude-harness check_div
+
+echo "[TEST] All harnesses"
+check_kani
+
+# Try to leave a clean output folder at the end
+rm -rf ${OUT_DIR}
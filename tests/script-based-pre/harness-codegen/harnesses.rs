@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::proof]
+fn check_add() {
+    let x: u8 = kani::any();
+    assert!(x.wrapping_add(0) == x);
+}
+
+#[kani::proof]
+fn check_sub() {
+    let x: u8 = kani::any();
+    assert!(x.wrapping_sub(0) == x);
+}
+
+#[kani::proof]
+fn check_div() {
+    let x: u16 = kani::any();
+    assert!(x / 2 <= x);
+}
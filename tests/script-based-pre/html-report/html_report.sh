@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `cargo kani report --html` renders the results of the last verification.
+
+set +e
+
+TMP_DIR="/tmp/html-report"
+
+rm -rf ${TMP_DIR}
+cp -r . ${TMP_DIR}
+pushd ${TMP_DIR} > /dev/null
+
+echo "[TEST] Report without results"
+cargo kani report --html out 2>&1 | grep -o "no results found"
+
+echo "[TEST] Run verification"
+cargo kani > /dev/null
+echo "Kani exit status: $?"
+
+echo "[TEST] Render the dashboard"
+cargo kani report --html out | grep -o "Wrote the dashboard"
+grep -o "<p>2 harnesses: 1 successfully verified, 1 failed.</p>" out/index.html
+grep -o '<a href="#harness-0">check_fail</a>' out/index.html
+grep -o "<h4>x is too large</h4>" out/index.html
+grep -o '<span class="line current"><span class="number">13</span>.*x &lt; 100' out/index.html
+
+popd > /dev/null
+rm -rf ${TMP_DIR}
@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that Kani reads the results CBMC prints after each unwinding with `--incremental-unwind`.
+# The loop id of the harness is listed by `cbmc --show-loops` on its goto model.
+# Note: This should run in the folder where the script is.
+
+kani main.rs --only-codegen --keep-temps > /dev/null 2>&1
+
+for harness in check_fail check_pass; do
+    echo "[TEST] ${harness}"
+    goto=$(ls *${harness}.out)
+    loop_id=$(cbmc --show-loops "${goto}" | grep -B1 "file main.rs" | grep -m1 "^Loop " \
+        | sed 's/^Loop \(.*\):$/\1/')
+    kani main.rs --enable-unstable --harness "${harness}" --incremental-unwind "${loop_id}" 2>&1 \
+        | grep -E "^(VERIFICATION|Failed Checks)"
+done
+
+rm -f main__* main.kani-metadata.json libmain.rlib
@@ -0,0 +1,26 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The harnesses of the `--incremental-unwind` test, whose loop ids are read from the goto
+//! model.
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn check_fail() {
+    let mut sum: u32 = 0;
+    for i in 0..5 {
+        assert!(i < 3, "the loop reached its fourth iteration");
+        sum += i;
+    }
+    assert!(sum > 0);
+}
+
+#[kani::proof]
+#[kani::unwind(6)]
+fn check_pass() {
+    let mut sum: u32 = 0;
+    for i in 0..5 {
+        sum += i;
+    }
+    assert!(sum < 11, "the sum is too large");
+}
@@ -0,0 +1,49 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--incremental` only verifies the harnesses that changed since their last successful
+# verification.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=target
+MANIFEST=${OUT_DIR}/target_lib/Cargo.toml
+LIB_SRC=${OUT_DIR}/target_lib/src/lib.rs
+
+# Expects two arguments: "kani arguments" "output_file"
+function check_kani {
+    local args=$1
+    local log_file="${OUT_DIR}/$2"
+    cargo kani --manifest-path "${MANIFEST}" --target-dir "${OUT_DIR}" --incremental ${args} \
+        2>&1 | tee "${log_file}"
+
+    # Check how many harnesses were skipped
+    local skipped=$(grep -c "Skipped: the harness didn't change" ${log_file})
+    echo "${log_file}:Skipped ${skipped} harnesses"
+
+    # Check the verification summary
+    grep "successfully verified harnesses" -H ${log_file} || true
+}
+
+# Ensure output folder is clean
+rm -rf ${OUT_DIR}
+mkdir -p ${OUT_DIR}
+
+# Copy the project so we don't make changes to the source code
+cp -r target_lib ${OUT_DIR}
+
+echo "Initial verification"
+check_kani "" initial.log
+
+echo "Run without changes"
+check_kani "" unchanged.log
+
+echo "Run after a change to the code reached by one harness"
+sed -i 's|x / 2|x / 3|' ${LIB_SRC}
+check_kani "" changed_src.log
+
+echo "Run with a new verification option"
+check_kani "--no-assertion-reach-checks" new_option.log
+
+# Try to leave a clean output folder at the end
+rm -rf ${OUT_DIR}
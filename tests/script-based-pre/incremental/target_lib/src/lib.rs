@@ -0,0 +1,24 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Two harnesses that don't share any code, so changing one of them doesn't affect the other.
+
+fn double(x: u8) -> u16 {
+    x as u16 * 2
+}
+
+fn halve(x: u8) -> u8 {
+    x / 2
+}
+
+#[kani::proof]
+fn check_double() {
+    let x: u8 = kani::any();
+    assert!(double(x) >= x as u16);
+}
+
+#[kani::proof]
+fn check_halve() {
+    let x: u8 = kani::any();
+    assert!(halve(x) <= x);
+}
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Build an instrumentation pass as a dynamic library and check that Kani runs it over the harness
+# before verifying it, and that it rejects the passes that implement another version of the
+# interface, or that are built by another compiler.
+
+set -eu
+
+OUT_DIR=$(mktemp -d)
+rustc --edition 2021 --crate-type dylib -C prefer-dynamic --out-dir ${OUT_DIR} redirect_sink.rs
+rustc --edition 2021 --crate-type cdylib --out-dir ${OUT_DIR} old_interface.rs
+rustc --edition 2021 --crate-type cdylib --out-dir ${OUT_DIR} other_rustc.rs
+
+set +e
+kani sink.rs -Z instrumentation-passes \
+    --instrumentation-pass ${OUT_DIR}/libredirect_sink.so
+echo "Kani exit status: $?"
+
+kani sink.rs -Z instrumentation-passes \
+    --instrumentation-pass ${OUT_DIR}/libold_interface.so
+echo "Kani exit status: $?"
+
+kani sink.rs -Z instrumentation-passes \
+    --instrumentation-pass ${OUT_DIR}/libother_rustc.so
+echo "Kani exit status: $?"
+set -e
+
+rm -rf ${OUT_DIR}
@@ -0,0 +1,10 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An instrumentation pass that implements another version of the interface, which Kani must
+//! reject before calling it.
+
+#[no_mangle]
+pub extern "C" fn kani_instrument_abi_version() -> u32 {
+    0
+}
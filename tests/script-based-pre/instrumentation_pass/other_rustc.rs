@@ -0,0 +1,16 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An instrumentation pass that claims to be built by another compiler, which Kani must reject
+//! before calling it.
+use std::ffi::c_char;
+
+#[no_mangle]
+pub extern "C" fn kani_instrument_abi_version() -> u32 {
+    1
+}
+
+#[no_mangle]
+pub extern "C" fn kani_instrument_rustc_version() -> *const c_char {
+    c"0.0.0".as_ptr()
+}
@@ -0,0 +1,66 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! An instrumentation pass that replaces the calls to a function named `sink` by calls to the
+//! function named `checked_sink` of the crate being verified.
+#![feature(rustc_private)]
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate stable_mir;
+
+use stable_mir::mir::mono::Instance;
+use stable_mir::mir::{Body, Constant, Operand, TerminatorKind};
+use stable_mir::ty::{Const, GenericArgs, RigidTy, TyKind};
+use stable_mir::{CrateDef, ItemKind};
+use std::ffi::{c_char, CString};
+
+#[no_mangle]
+pub extern "C" fn kani_instrument_abi_version() -> u32 {
+    1
+}
+
+#[no_mangle]
+pub extern "C" fn kani_instrument_rustc_version() -> *const c_char {
+    let version = rustc_interface::util::rustc_version_str().unwrap_or_default();
+    CString::new(version).unwrap().into_raw()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn kani_instrument(
+    _instance: *const Instance,
+    body: *const Body,
+    new_body: *mut Body,
+) -> bool {
+    match redirect_sink(unsafe { &*body }) {
+        Some(body) => {
+            unsafe { new_body.write(body) };
+            true
+        }
+        None => false,
+    }
+}
+
+fn redirect_sink(body: &Body) -> Option<Body> {
+    let checked = stable_mir::all_local_items()
+        .into_iter()
+        .find(|item| item.kind() == ItemKind::Fn && item.name() == "checked_sink")?;
+    let TyKind::RigidTy(RigidTy::FnDef(checked_def, _)) = checked.ty().kind() else { return None };
+    let checked = Instance::resolve(checked_def, &GenericArgs(vec![])).ok()?;
+    let mut new_body = body.clone();
+    let mut modified = false;
+    for block in new_body.blocks.iter_mut() {
+        if let TerminatorKind::Call { func, .. } = &mut block.terminator.kind {
+            let Operand::Constant(constant) = func else { continue };
+            let TyKind::RigidTy(RigidTy::FnDef(def, _)) = constant.ty().kind() else { continue };
+            if def.name() == "sink" {
+                *func = Operand::Constant(Constant {
+                    span: constant.span,
+                    user_ty: None,
+                    literal: Const::try_new_zero_sized(checked.ty()).unwrap(),
+                });
+                modified = true;
+            }
+        }
+    }
+    modified.then_some(new_body)
+}
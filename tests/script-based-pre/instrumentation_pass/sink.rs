@@ -0,0 +1,19 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! The `redirect_sink` pass replaces the calls to `sink` by calls to `checked_sink`.
+
+const SECRET: u32 = 42;
+
+fn sink(_value: u32) {}
+
+#[allow(dead_code)]
+fn checked_sink(value: u32) {
+    assert!(value != SECRET, "secret value reaches sink");
+}
+
+#[kani::proof]
+fn check_sink() {
+    let value: u32 = kani::any();
+    sink(value);
+}
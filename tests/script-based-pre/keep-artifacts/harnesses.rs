@@ -0,0 +1,16 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::proof]
+fn check_pass() {
+    let x: u8 = kani::any();
+    assert!(x.wrapping_add(0) == x);
+}
+
+mod checks {
+    #[kani::proof]
+    fn check_fail() {
+        let x: u8 = kani::any();
+        assert!(x < 100, "x is too large");
+    }
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--keep-artifacts` writes the artifacts of the selected harnesses to one directory
+# per harness in `--artifacts-dir`, and that each run replaces the artifacts of the previous one.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=artifacts_out
+rm -rf ${OUT_DIR}
+
+echo "[TEST] Keep the artifacts of all harnesses"
+kani harnesses.rs --artifacts-dir ${OUT_DIR} | grep -o "Harness artifacts written to ${OUT_DIR}"
+(cd ${OUT_DIR} && find . -type f | sort)
+grep -o '"description": "x is too large"' ${OUT_DIR}/harnesses/checks-check_fail/properties.json
+grep -o '"status": "FAILURE"' ${OUT_DIR}/harnesses/checks-check_fail/properties.json | head -1
+head -1 ${OUT_DIR}/harnesses/checks-check_fail/log.txt | grep -o '^$ cbmc .* harness.goto$' | wc -l
+grep -o "VERIFICATION:- FAILED" ${OUT_DIR}/harnesses/checks-check_fail/log.txt
+
+echo "[TEST] Keep the artifacts of failed harnesses"
+kani harnesses.rs --artifacts-dir ${OUT_DIR} --keep-artifacts failed > /dev/null
+(cd ${OUT_DIR} && find . -type f | sort)
+
+rm -rf ${OUT_DIR}
@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `cargo kani list` lists the harnesses with their contracts and stubs, without
+# generating their code.
+
+set +e
+
+TMP_DIR="/tmp/list"
+
+rm -rf ${TMP_DIR}
+cp -r . ${TMP_DIR}
+pushd ${TMP_DIR} > /dev/null
+
+echo "[TEST] Pretty list"
+cargo kani list 2> /dev/null
+
+echo "[TEST] Goto models: $(find target -name '*.symtab.out' | wc -l)"
+
+echo "[TEST] JSON list"
+cargo kani list --format json 2> /dev/null > list.json
+python3 -c '
+import json
+harnesses = json.load(open("list.json"))["harnesses"]
+for harness in harnesses:
+    print(harness["name"], harness["kind"], harness["checked_contract"], harness["requires"],
+          harness["ensures"], harness["modifies"], harness["verified_stubs"],
+          [stub["original"] for stub in harness["stubs"]], harness["unwind"])
+'
+
+popd > /dev/null
+rm -rf ${TMP_DIR}
@@ -0,0 +1,51 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+#[kani::requires(divisor != 0)]
+#[kani::ensures(result <= dividend)]
+fn div(dividend: u32, divisor: u32) -> u32 {
+    dividend / divisor
+}
+
+fn half(x: u32) -> u32 {
+    div(x, 2)
+}
+
+fn random() -> u32 {
+    unimplemented!()
+}
+
+fn mock_random() -> u32 {
+    42
+}
+
+#[kani::modifies(value)]
+#[kani::requires(*value < 100)]
+fn increment(value: &mut u32) {
+    *value += 1;
+}
+
+#[kani::proof_for_contract(increment)]
+fn check_increment() {
+    let mut value = kani::any();
+    increment(&mut value);
+}
+
+#[kani::proof_for_contract(div)]
+fn check_div() {
+    div(kani::any(), kani::any());
+}
+
+#[kani::proof]
+#[kani::stub_verified(div)]
+#[kani::stub(random, mock_random)]
+#[kani::unwind(3)]
+fn check_half() {
+    assert!(half(random()) == 21);
+}
+
+#[kani::proof]
+#[kani::should_panic]
+fn check_panic() {
+    random();
+}
@@ -25,7 +25,7 @@ fn try_nz_u8() {
         }
     }
     #[test]
-    fn kani_concrete_playback_try_nz_u8_17663051139329126359() {
+    fn kani_concrete_playback_try_nz_u8_11661742897267559519() {
         let concrete_vals: Vec<Vec<u8>> = vec![
             // 0
             vec![0],
@@ -0,0 +1,41 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Check that the concrete playback tests of harnesses with nondet arrays, vectors and derived
+//! `Arbitrary` types reproduce the failure. The only counterexample of each harness is the value
+//! of its assertion, so the tests must panic with the assertion message.
+extern crate kani;
+
+#[cfg(kani)]
+mod verify {
+    #[derive(kani::Arbitrary, PartialEq)]
+    struct Packet {
+        kind: u8,
+        payload: [u16; 2],
+    }
+
+    #[derive(kani::Arbitrary, PartialEq)]
+    enum Command {
+        Reset,
+        Send(Packet),
+    }
+
+    #[kani::proof]
+    fn check_array() {
+        let bytes: [u8; 3] = kani::any();
+        assert!(bytes != [1, 2, 3], "array played back");
+    }
+
+    #[kani::proof]
+    #[kani::unwind(4)]
+    fn check_vec() {
+        let values = kani::vec::any_vec::<u32, 3>();
+        assert!(values != [7, 8], "vector played back");
+    }
+
+    #[kani::proof]
+    fn check_derived() {
+        let command: Command = kani::any();
+        let sent = Command::Send(Packet { kind: 4, payload: [5, 600] });
+        assert!(command != sent, "derived value played back");
+    }
+}
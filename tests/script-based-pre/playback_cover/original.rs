@@ -0,0 +1,28 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+//! Check that `--playback-cover` only adds the tests of the selected cover statements, and warns
+//! about the selected cover statements that aren't satisfiable.
+extern crate kani;
+
+#[cfg(kani)]
+mod verify {
+    use kani::cover;
+    use std::convert::TryFrom;
+    use std::num::NonZeroU8;
+
+    #[kani::proof]
+    fn try_nz_u8() {
+        let val: u8 = kani::any();
+        let result = NonZeroU8::try_from(val);
+        match result {
+            Ok(nz_val) => {
+                cover!(nz_val.get() == 42, "The answer");
+                assert_eq!(nz_val.get(), val);
+            }
+            Err(_) => {
+                cover!(val != 0, "Never");
+                assert_eq!(val, 1, "Zero isn't one");
+            }
+        }
+    }
+}
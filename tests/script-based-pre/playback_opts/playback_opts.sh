@@ -22,3 +22,4 @@ kani playback -Z concrete-playback ${RS_FILE} --only-codegen --message-format=js
 
 # Cleanup
 rm ${RS_FILE}
+rm -rf kani_playback
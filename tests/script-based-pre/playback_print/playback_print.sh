@@ -10,6 +10,7 @@ function error() {
     echo $@
     # Cleanup
     rm ${RS_FILE}
+    rm -rf kani_playback
     rm output.log
     exit 1
 }
@@ -37,4 +38,5 @@ echo
 
 # Cleanup
 rm ${RS_FILE}
+rm -rf kani_playback
 rm output.log
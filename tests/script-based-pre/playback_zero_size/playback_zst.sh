@@ -16,3 +16,4 @@ kani playback -Z concrete-playback ${RS_FILE} -- kani_concrete_playback
 
 # Cleanup
 rm ${RS_FILE}
+rm -rf kani_playback
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--sarif` writes the failed checks with their location.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=sarif_out
+rm -rf ${OUT_DIR}
+mkdir ${OUT_DIR}
+
+kani sarif.rs --sarif ${OUT_DIR}/results.sarif > ${OUT_DIR}/output.log 2>&1
+echo "Kani exit status: $?"
+
+python3 - ${OUT_DIR}/results.sarif <<'PYTHON'
+import json, sys
+log = json.load(open(sys.argv[1]))
+print("version:", log["version"])
+for result in log["runs"][0]["results"]:
+    location = result["locations"][0]["physicalLocation"]
+    print("result:", result["ruleId"], result["message"]["text"],
+          location["artifactLocation"]["uri"], location["region"]["startLine"])
+PYTHON
+
+rm -rf ${OUT_DIR}
@@ -0,0 +1,31 @@
+#!/usr/bin/env bash
+# Copyright Kani Contributors
+# SPDX-License-Identifier: Apache-2.0 OR MIT
+
+# Checks that `--shard` verifies each harness in exactly one shard, and that `kani merge-results`
+# combines the results of the shards.
+# Note: This should run in the folder where the script is.
+
+OUT_DIR=shard_out
+rm -rf ${OUT_DIR}
+mkdir ${OUT_DIR}
+
+for shard in 1 2; do
+    echo "[TEST] Shard ${shard}/2"
+    kani harnesses.rs --shard ${shard}/2 --output-format json > ${OUT_DIR}/shard${shard}.json
+    echo "Kani exit status: $?"
+    grep -o '"name": "check_[a-c]"' ${OUT_DIR}/shard${shard}.json
+done
+
+echo "[TEST] Merge the shards"
+kani merge-results ${OUT_DIR}/shard1.json ${OUT_DIR}/shard2.json -o ${OUT_DIR}/merged.json
+grep -o -E '"(total|successful|failed)": [0-9]+' ${OUT_DIR}/merged.json
+grep -c '"shard"' ${OUT_DIR}/merged.json
+
+echo "[TEST] Merge a missing shard"
+kani merge-results ${OUT_DIR}/shard1.json 2>&1 >/dev/null | grep -o "the results of shards 2/2 are missing"
+
+echo "[TEST] Merge the same shard twice"
+kani merge-results ${OUT_DIR}/shard1.json ${OUT_DIR}/shard1.json 2>&1 | grep -o "appears in both"
+
+rm -rf ${OUT_DIR}
@@ -0,0 +1,36 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z aliasing-checks
+
+//! Check that Kani reports the use of a reference after a conflicting access as UB.
+
+#[kani::proof]
+fn write_after_foreign_write() {
+    let mut value: u32 = kani::any();
+    let ptr = &mut value as *mut u32;
+    let reference = unsafe { &mut *ptr };
+    unsafe { *ptr = 1 };
+    *reference = 2;
+}
+
+#[kani::proof]
+fn read_after_foreign_write() {
+    let mut value: [u8; 4] = kani::any();
+    let ptr = &mut value as *mut [u8; 4];
+    let first = unsafe { &(*ptr)[0] };
+    let idx: usize = kani::any();
+    kani::assume(idx < 4);
+    // Only the writes that overlap with the reference invalidate it, i.e., when `idx == 0`.
+    value[idx] = 0;
+    let _read = *first;
+}
+
+#[kani::proof]
+fn write_after_foreign_read() {
+    let mut value: u32 = kani::any();
+    let ptr = &mut value as *mut u32;
+    let reference = unsafe { &mut *ptr };
+    *reference = 1;
+    let _read = unsafe { *ptr };
+    *reference = 2;
+}
@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --allocator-may-fail
+
+//! Checks that allocations may fail with `--allocator-may-fail`, both for fallible allocations
+//! and for the ones that abort the program on failure.
+
+#[kani::proof]
+fn check_try_reserve() {
+    let mut v: Vec<u8> = Vec::new();
+    let result = v.try_reserve(4);
+    kani::cover!(result.is_err(), "allocation failed");
+    kani::cover!(result.is_ok(), "allocation succeeded");
+}
+
+#[kani::proof]
+fn check_box_new() {
+    let b = Box::new(10u8);
+    assert_eq!(*b, 10);
+}
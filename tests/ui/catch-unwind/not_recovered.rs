@@ -0,0 +1,12 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z catch-unwind
+
+//! Check that panics inside `catch_unwind` are still reported as failures in harnesses that
+//! don't have the `#[kani::catch_unwind]` attribute.
+
+#[kani::proof]
+fn check_not_recovered() {
+    let result = std::panic::catch_unwind(|| panic!("caught at runtime"));
+    assert!(result.is_err());
+}
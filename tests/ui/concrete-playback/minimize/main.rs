@@ -0,0 +1,14 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// kani-flags: --enable-unstable --concrete-playback=print --minimize-counterexamples
+
+//! Check that the values of the counterexample are minimized before they're played back: the
+//! solver may pick any `x >= 3` and any non-zero `y`.
+
+#[kani::proof]
+pub fn harness() {
+    let x: u8 = kani::any();
+    let y: u8 = kani::any();
+    assert!(x < 3 || y == 0);
+}
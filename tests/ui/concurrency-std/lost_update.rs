@@ -0,0 +1,23 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that Kani finds the interleaving where an increment made of a separate load and store
+//! is lost when the thread is spawned with `std::thread::spawn`.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+fn increment(counter: &AtomicU32) {
+    let value = counter.load(Ordering::SeqCst);
+    counter.store(value + 1, Ordering::SeqCst);
+}
+
+#[kani::proof]
+fn check_lost_update() {
+    let counter = Arc::new(AtomicU32::new(0));
+    let other = counter.clone();
+    let handle = std::thread::spawn(move || increment(&other));
+    increment(&counter);
+    handle.join().unwrap();
+    kani::assert(counter.load(Ordering::SeqCst) == 2, "no update is lost");
+}
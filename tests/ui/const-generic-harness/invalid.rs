@@ -0,0 +1,42 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z const-generic-harness
+
+//! Check that invalid uses of `#[kani::const_generic]` are reported.
+
+#[kani::proof]
+fn missing_range<const N: usize>() {}
+
+#[kani::proof]
+#[kani::const_generic(M, 0..2)]
+fn unknown_param<const N: usize>() {}
+
+#[kani::proof]
+#[kani::const_generic(T, 0..2)]
+fn type_param<T>() {}
+
+#[kani::proof]
+#[kani::const_generic(B, 0..2)]
+fn not_an_integer<const B: bool>() {}
+
+#[kani::proof]
+#[kani::const_generic(N, 250..=256)]
+fn out_of_range<const N: u8>() {}
+
+#[kani::proof]
+#[kani::const_generic(N, 3..3)]
+fn empty_range<const N: u8>() {}
+
+#[kani::proof]
+#[kani::const_generic(N, 0..1000)]
+fn too_many_values<const N: u16>() {}
+
+#[kani::proof]
+#[kani::const_generic(N, 0..2)]
+#[kani::const_generic(N, 0..3)]
+fn duplicate<const N: u8>() {}
+
+#[kani::proof]
+#[kani::const_generic(N, 0..64)]
+#[kani::const_generic(M, 0..32)]
+fn too_many_instances<const N: u8, const M: u8>() {}
@@ -0,0 +1,25 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --check data-races
+
+//! Check that `--check data-races` reports unsynchronized accesses to a static, but not the
+//! accesses made with atomic operations.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+static mut COUNTER: u32 = 0;
+static ATOMIC_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+#[kani::proof]
+fn check_racy_counter() {
+    let handle = kani::thread::spawn(|| unsafe { COUNTER += 1 });
+    unsafe { COUNTER += 1 };
+    handle.join();
+}
+
+#[kani::proof]
+fn check_atomic_counter() {
+    let handle = kani::thread::spawn(|| ATOMIC_COUNTER.fetch_add(1, Ordering::SeqCst));
+    ATOMIC_COUNTER.fetch_add(1, Ordering::SeqCst);
+    handle.join();
+}
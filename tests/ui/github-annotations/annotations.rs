@@ -0,0 +1,17 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// kani-flags: --output-format github
+//! Check that `--output-format github` prints a workflow command for each failed check.
+
+#[kani::proof]
+fn check_annotation() {
+    let x: u8 = kani::any();
+    assert!(x < 100, "x is too large");
+}
+
+#[kani::proof]
+fn check_success() {
+    let x: u8 = kani::any();
+    assert!(x <= u8::MAX);
+}
@@ -0,0 +1,20 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --proof-mode k-induction
+
+//! Checks that `--proof-mode k-induction` doesn't report a harness as successful if the step
+//! case fails, even though the base case succeeds.
+
+#[kani::proof]
+fn check_non_inductive_loop() {
+    let mut x: u32 = 0;
+    let mut y: u32 = 0;
+    while kani::any() {
+        if x < 10 {
+            x += 1;
+            y += 1;
+        }
+        // Holds since `y == x`, which the step case doesn't know.
+        assert!(y <= 10);
+    }
+}
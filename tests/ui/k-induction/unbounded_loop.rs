@@ -0,0 +1,15 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --proof-mode k-induction
+
+//! Checks that `--proof-mode k-induction` reports the outcome of the induction for a harness
+//! with a loop that has no bound.
+
+#[kani::proof]
+fn check_unbounded_loop() {
+    let mut x: u32 = 0;
+    while kani::any() {
+        x = if x < 10 { x + 1 } else { 0 };
+        assert!(x <= 10);
+    }
+}
@@ -0,0 +1,55 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --check leaks
+
+//! Check that `--check leaks` reports the allocations that aren't freed when the harness returns,
+//! unless they are still reachable from a static.
+
+use std::mem::ManuallyDrop;
+
+static mut CACHE: Option<&'static u32> = None;
+
+/// A container whose destructor doesn't free its buffer.
+struct LeakyBuffer {
+    data: ManuallyDrop<Vec<u8>>,
+}
+
+impl LeakyBuffer {
+    fn new(len: usize) -> Self {
+        LeakyBuffer { data: ManuallyDrop::new(vec![0; len]) }
+    }
+}
+
+/// Same as `LeakyBuffer`, but its destructor frees the buffer.
+struct Buffer {
+    data: ManuallyDrop<Vec<u8>>,
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.data) }
+    }
+}
+
+#[kani::proof]
+fn leak_forgotten_box() {
+    let value = Box::new(kani::any::<u32>());
+    std::mem::forget(value);
+}
+
+#[kani::proof]
+fn leak_in_destructor() {
+    let buffer = LeakyBuffer::new(4);
+    assert_eq!(buffer.data.len(), 4);
+}
+
+#[kani::proof]
+fn free_in_destructor() {
+    let buffer = Buffer { data: ManuallyDrop::new(vec![0; 4]) };
+    assert_eq!(buffer.data.len(), 4);
+}
+
+#[kani::proof]
+fn reachable_from_static() {
+    unsafe { CACHE = Some(Box::leak(Box::new(kani::any()))) };
+}
@@ -0,0 +1,45 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z provenance-checks
+
+//! Check that Kani reports pointer arithmetic outside of the allocation and integer-to-pointer
+//! round trips that lose the provenance of the pointer.
+#![feature(strict_provenance)]
+
+#[kani::proof]
+fn offset_out_of_bounds() {
+    let array = [0u8; 4];
+    let ptr = array.as_ptr();
+    let offset: usize = kani::any();
+    kani::assume(offset <= 5);
+    // Pointing one byte past the end is allowed, but the pointer isn't dereferenced.
+    let _end = unsafe { ptr.add(offset) };
+}
+
+#[kani::proof]
+fn cast_without_exposing() {
+    let value = 10u32;
+    let addr = (&value as *const u32).addr();
+    let _ptr = addr as *const u32;
+}
+
+#[kani::proof]
+fn cast_after_exposing() {
+    let value = 10u32;
+    let addr = &value as *const u32 as usize;
+    let ptr = addr as *const u32;
+    assert_eq!(unsafe { *ptr }, 10);
+}
+
+#[kani::proof]
+fn transmute_address() {
+    let value = 10u32;
+    let addr = &value as *const u32 as usize;
+    let _ptr = std::ptr::without_provenance::<u32>(addr);
+}
+
+#[kani::proof]
+fn dangling_is_allowed() {
+    let ptr = std::ptr::NonNull::<u64>::dangling();
+    let _ptr = unsafe { ptr.as_ptr().add(0) };
+}
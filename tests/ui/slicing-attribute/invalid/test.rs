@@ -0,0 +1,8 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that `kani::slicing` rejects an unknown argument
+
+#[kani::proof]
+#[kani::slicing(everything)]
+fn check() {}
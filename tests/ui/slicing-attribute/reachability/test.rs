@@ -0,0 +1,11 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Checks that `reachability` is a valid argument to `kani::slicing`
+
+#[kani::proof]
+#[kani::slicing(reachability)]
+fn check() {
+    let v: Vec<u8> = vec![kani::any(); 4];
+    assert_eq!(v.len(), 4);
+}
@@ -0,0 +1,26 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: --max-call-depth 5
+
+//! Check that `--max-call-depth` reports the harnesses whose call stack can have more frames than
+//! the given depth.
+
+fn count_down(n: u32) -> u32 {
+    if n == 0 { 0 } else { 1 + count_down(n - 1) }
+}
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn deep_recursion() {
+    let n: u32 = kani::any();
+    kani::assume(n < 8);
+    assert!(count_down(n) == n);
+}
+
+#[kani::proof]
+#[kani::unwind(10)]
+fn shallow_recursion() {
+    let n: u32 = kani::any();
+    kani::assume(n < 3);
+    assert!(count_down(n) == n);
+}
@@ -0,0 +1,12 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// kani-flags: --output-format terse --trace-length 1
+//! Check that the terse output format prints the last steps of the trace of a failed check.
+
+#[kani::proof]
+fn check_trace() {
+    let x: u8 = kani::any();
+    let y = x % 10;
+    assert!(y < 9, "y is too large");
+}
@@ -0,0 +1,62 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z uninit-checks
+
+//! Check that Kani reports reads of uninitialized memory, and that initialized memory is
+//! accepted.
+use std::alloc::{alloc, dealloc, Layout};
+use std::mem::MaybeUninit;
+
+#[repr(C)]
+struct WithPadding {
+    first: u8,
+    second: u16,
+}
+
+#[kani::proof]
+fn read_maybe_uninit() {
+    let value: MaybeUninit<u32> = MaybeUninit::uninit();
+    let _value = unsafe { value.assume_init() };
+}
+
+#[kani::proof]
+fn read_uninit_allocation() {
+    let layout = Layout::new::<u32>();
+    unsafe {
+        let ptr = alloc(layout) as *mut u32;
+        let _value = *ptr;
+        dealloc(ptr as *mut u8, layout);
+    }
+}
+
+#[kani::proof]
+fn read_padding() {
+    let value = WithPadding { first: 1, second: 2 };
+    let ptr = &value as *const WithPadding as *const u8;
+    let _byte = unsafe { *ptr.add(1) };
+}
+
+#[kani::proof]
+fn read_initialized_maybe_uninit() {
+    let value = MaybeUninit::new(10u32);
+    assert_eq!(unsafe { value.assume_init() }, 10);
+}
+
+#[kani::proof]
+fn read_initialized_allocation() {
+    let layout = Layout::new::<u32>();
+    unsafe {
+        let ptr = alloc(layout) as *mut u32;
+        ptr.write(5);
+        assert_eq!(*ptr, 5);
+        dealloc(ptr as *mut u8, layout);
+    }
+}
+
+#[kani::proof]
+fn read_fields_with_padding() {
+    let value = WithPadding { first: 1, second: 2 };
+    let ptr = &value as *const WithPadding;
+    let (first, second) = unsafe { ((*ptr).first, (*ptr).second) };
+    assert_eq!(first as u16 + second, 3);
+}
@@ -0,0 +1,18 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+//! Check that Kani reports an error for the functions that use `f16` and `f128`, which it
+//! doesn't support yet, instead of crashing.
+#![feature(f16, f128)]
+
+#[kani::proof]
+fn check_f16() {
+    let half = f16::from_bits(0x3C00);
+    assert!(half as f32 == 1.0);
+}
+
+#[kani::proof]
+fn check_f128() {
+    let quad = [1.0f128; 2];
+    assert!(quad[1] as f64 == 1.0);
+}
@@ -0,0 +1,21 @@
+// Copyright Kani Contributors
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+// kani-flags: -Z weak-memory --memory-model tso
+
+//! Checks that atomic operations with every ordering can be verified under a weak memory model.
+//! Without threads, the fences generated for the orderings don't change the results.
+
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+
+#[kani::proof]
+fn check_orderings() {
+    let counter = AtomicUsize::new(0);
+    counter.store(1, Ordering::Release);
+    assert_eq!(counter.load(Ordering::Acquire), 1);
+    assert_eq!(counter.fetch_add(1, Ordering::AcqRel), 1);
+    assert_eq!(counter.fetch_add(1, Ordering::Relaxed), 2);
+    fence(Ordering::SeqCst);
+    let result = counter.compare_exchange(3, 4, Ordering::SeqCst, Ordering::Relaxed);
+    assert_eq!(result, Ok(3));
+    assert_eq!(counter.swap(0, Ordering::SeqCst), 4);
+}
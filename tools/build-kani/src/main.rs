@@ -100,6 +100,8 @@ fn bundle_kani(dir: &Path) -> Result<()> {
     // 5. Record the exact toolchain and rustc version we use
     std::fs::write(dir.join("rust-toolchain-version"), env!("RUSTUP_TOOLCHAIN"))?;
     std::fs::write(dir.join("rustc-version"), get_rustc_version()?)?;
+    // The versions of the tools we bundle, which `cargo kani doctor` checks
+    cp(Path::new("kani-dependencies"), dir)?;
 
     // 6. Include a licensing note
     cp(Path::new("tools/build-kani/license-notes.txt"), dir)?;